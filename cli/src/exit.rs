@@ -0,0 +1,166 @@
+//! Process exit codes for `skd`.
+//!
+//! Failures the CLI detects itself (bad arguments, a config directory `Sys`
+//! cannot open) exit with their `ErrorCode` directly. `Response::Error`
+//! carries only a message, so command errors are classified first by the
+//! command's family: commands that drive tmux, SSH, or rsync, or whose only
+//! work is writing state files, fail in the backend whatever the cause says.
+//! Other commands fall back to the fixed phrasing the command layer uses
+//! for argument, lookup, and conflict errors ("Usage:", "not found", ...).
+//! Scripts can then tell a missing agent apart from a daemon that is down.
+
+use skill_docket_core::command::Command;
+
+/// An error that matched no known category.
+pub const EXIT_UNCLASSIFIED: i32 = 1;
+
+
+/// Category of a failed command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCode {
+    /// Bad arguments, unknown keys, or unparseable values.
+    InvalidArgument,
+    /// The named agent, task, project, snapshot, or key does not exist.
+    NotFound,
+    /// The target already exists or is in a state that forbids the change.
+    Conflict,
+    /// tmux, SSH, or the filesystem failed underneath the command.
+    Backend,
+    /// The daemon or a required subsystem could not be reached.
+    Unavailable,
+}
+
+impl ErrorCode {
+    /// The category every failure of `cmd` belongs to, when its family
+    /// decides it. `None` for commands whose failures vary.
+    pub fn for_command(cmd: &Command) -> Option<ErrorCode> {
+        match cmd {
+            Command::AgentExec { .. }
+            | Command::Interrupt { .. }
+            | Command::LayoutRow { .. }
+            | Command::LayoutColumn { .. }
+            | Command::LayoutMerge { .. }
+            | Command::LayoutPlace { .. }
+            | Command::LayoutCapture { .. }
+            | Command::LayoutSession { .. }
+            | Command::ClientNext
+            | Command::ClientPrev
+            | Command::RigInit { .. }
+            | Command::RigPush { .. }
+            | Command::RigPull { .. }
+            | Command::RigStatus { .. }
+            | Command::RigHealth { .. }
+            | Command::RigStop { .. }
+            | Command::ConfigSave { .. }
+            | Command::HistorySnapshot { .. } => Some(ErrorCode::Backend),
+            _ => None,
+        }
+    }
+
+    /// Classify an error message from a command whose family gave
+    /// `family`. Argument errors are reported before the command does any
+    /// work, so usage phrasing wins over the family. Returns `None` for
+    /// unrecognized text.
+    pub fn classify(family: Option<ErrorCode>, message: &str) -> Option<ErrorCode> {
+        let m = message.to_ascii_lowercase();
+        if m.starts_with("usage:") || m.starts_with("unknown") || m.starts_with("invalid") {
+            return Some(ErrorCode::InvalidArgument);
+        }
+        if family.is_some() {
+            return family;
+        }
+        if m.contains("not found") || m.starts_with("no such") || m.starts_with("nothing found") {
+            Some(ErrorCode::NotFound)
+        } else if m.contains("already") || m.starts_with("duplicate") {
+            Some(ErrorCode::Conflict)
+        } else if m.contains("must be") || m.contains("needs a") {
+            Some(ErrorCode::InvalidArgument)
+        } else {
+            None
+        }
+    }
+
+    /// The process exit code for this category.
+    pub fn exit_code(self) -> i32 {
+        match self {
+            ErrorCode::InvalidArgument => 2,
+            ErrorCode::NotFound => 3,
+            ErrorCode::Conflict => 4,
+            ErrorCode::Backend => 5,
+            ErrorCode::Unavailable => 6,
+        }
+    }
+}
+
+
+/// Exit code for an error message from a command whose family gave
+/// `family`: its category's code, or 1 if unclassified.
+pub fn exit_code_for(family: Option<ErrorCode>, message: &str) -> i32 {
+    ErrorCode::classify(family, message).map_or(EXIT_UNCLASSIFIED, ErrorCode::exit_code)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn each_code_maps_to_distinct_exit() {
+        assert_eq!(ErrorCode::InvalidArgument.exit_code(), 2);
+        assert_eq!(ErrorCode::NotFound.exit_code(), 3);
+        assert_eq!(ErrorCode::Conflict.exit_code(), 4);
+        assert_eq!(ErrorCode::Backend.exit_code(), 5);
+        assert_eq!(ErrorCode::Unavailable.exit_code(), 6);
+    }
+
+    #[test]
+    fn invalid_argument_messages() {
+        assert_eq!(exit_code_for(None, "Usage: cmx agent kill <name>"), 2);
+        assert_eq!(exit_code_for(None, "Unknown config key: colour"), 2);
+        assert_eq!(exit_code_for(None, "Invalid u32 for max_retries: lots"), 2);
+        assert_eq!(exit_code_for(Some(ErrorCode::Backend), "Usage: skd rig push <folder>"), 2);
+    }
+
+    #[test]
+    fn not_found_messages() {
+        assert_eq!(exit_code_for(None, "Agent 'w9' not found"), 3);
+        assert_eq!(exit_code_for(None, "Nothing found named 'x'"), 3);
+    }
+
+    #[test]
+    fn conflict_messages() {
+        assert_eq!(exit_code_for(None, "agent already exists: w1"), 4);
+        assert_eq!(exit_code_for(None, "Duplicate message for 'w1' already pending; not queued"), 4);
+    }
+
+    #[test]
+    fn backend_family_decides_over_message_text() {
+        let push = Command::RigPush {
+            folder: ".".into(),
+            remote: None,
+            label: None,
+            all: false,
+            bwlimit: None,
+            exclude: Vec::new(),
+        };
+        let family = ErrorCode::for_command(&push);
+        assert_eq!(family, Some(ErrorCode::Backend));
+        assert_eq!(exit_code_for(family, "Push to gpu1 failed: connection refused"), 5);
+        assert_eq!(exit_code_for(family, "rsync: No such file or directory"), 5);
+
+        let save = ErrorCode::for_command(&Command::ConfigSave { path: None });
+        assert_eq!(exit_code_for(save, "Failed to write settings.yaml: permission denied"), 5);
+    }
+
+    #[test]
+    fn other_families_ignore_backend_sounding_text() {
+        let family = ErrorCode::for_command(&Command::Status { format: None });
+        assert_eq!(family, None);
+        assert_eq!(exit_code_for(family, "connection refused"), EXIT_UNCLASSIFIED);
+    }
+
+    #[test]
+    fn unclassified_is_one() {
+        assert_eq!(exit_code_for(None, "something odd happened"), EXIT_UNCLASSIFIED);
+    }
+}
@@ -0,0 +1,215 @@
+//! Follow mode for `skd watch` — re-issue `Watch` until interrupted.
+//!
+//! A single `Watch` returns after one state change or its timeout. In
+//! follow mode the CLI loops, advancing `since` to the newest event
+//! timestamp it has printed so no change is reported twice. If the daemon
+//! goes away, the next iteration re-registers once it is reachable again.
+
+use std::path::Path;
+use std::time::Duration;
+
+use skill_docket_core::command::Command;
+use cmx_utils::response::Response;
+
+/// Default per-registration timeout, matching the service layer default.
+const DEFAULT_WATCH_TIMEOUT_MS: u64 = 30_000;
+
+/// Extra time the socket read waits beyond the watch timeout.
+const READ_GRACE_MS: u64 = 5_000;
+
+/// Delay before re-registering after the daemon could not be reached.
+const RECONNECT_DELAY_MS: u64 = 1_000;
+
+
+/// Remove a `--follow` flag from `watch` arguments.
+///
+/// Returns true if the flag was present. Arguments for other commands
+/// are left untouched so `parse_args` still rejects unknown flags.
+pub fn take_follow_flag(args: &mut Vec<&str>) -> bool {
+    if args.first() != Some(&"watch") {
+        return false;
+    }
+    let before = args.len();
+    args.retain(|a| *a != "--follow");
+    args.len() != before
+}
+
+
+/// Tracks the `since` cursor across successive watch registrations.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FollowState {
+    since: Option<u64>,
+    timeout: Option<String>,
+}
+
+impl FollowState {
+    pub fn new(since: Option<u64>, timeout: Option<String>) -> Self {
+        FollowState { since, timeout }
+    }
+
+    #[cfg(test)]
+    pub fn since(&self) -> Option<u64> {
+        self.since
+    }
+
+    /// The `Watch` command for the next registration.
+    pub fn command(&self) -> Command {
+        Command::Watch {
+            since: self.since.map(|s| s.to_string()),
+            timeout: self.timeout.clone(),
+        }
+    }
+
+    /// Socket read timeout for one registration.
+    pub fn read_timeout_ms(&self) -> u64 {
+        self.timeout
+            .as_deref()
+            .and_then(|t| t.parse::<u64>().ok())
+            .unwrap_or(DEFAULT_WATCH_TIMEOUT_MS)
+            + READ_GRACE_MS
+    }
+
+    /// Absorb one watch response body.
+    ///
+    /// Advances `since` to the newest event timestamp in the body and
+    /// returns the text to print. Returns `None` for timeouts, empty
+    /// bodies, and events at or before the current cursor.
+    pub fn absorb(&mut self, output: &str) -> Option<String> {
+        let mut printed = Vec::new();
+        for line in output.lines() {
+            let line = line.trim();
+            if line.is_empty() || is_timeout(line) {
+                continue;
+            }
+            if let Some(ts) = event_timestamp(line) {
+                if self.since.is_some_and(|s| ts <= s) {
+                    continue;
+                }
+                self.since = Some(ts);
+            }
+            printed.push(line);
+        }
+        if printed.is_empty() {
+            None
+        } else {
+            Some(printed.join("\n"))
+        }
+    }
+}
+
+
+/// Extract the epoch-ms timestamp from a JSON watch event.
+pub fn event_timestamp(line: &str) -> Option<u64> {
+    let value: serde_json::Value = serde_json::from_str(line).ok()?;
+    ["timestamp_ms", "timestamp", "ts"]
+        .iter()
+        .find_map(|k| value.get(*k).and_then(|v| v.as_u64()))
+}
+
+/// True if the line is a watch timeout rather than a state change.
+fn is_timeout(line: &str) -> bool {
+    let value: serde_json::Value = match serde_json::from_str(line) {
+        Ok(v) => v,
+        Err(_) => return line == "timeout",
+    };
+    ["event", "type"]
+        .iter()
+        .any(|k| value.get(*k).and_then(|v| v.as_str()) == Some("timeout"))
+}
+
+
+/// Run the follow loop until the process is interrupted.
+pub fn run(config_dir: &Path, state: &mut FollowState) -> ! {
+    let mut reported_down = false;
+    loop {
+        let cmd = state.command();
+        match skill_docket_core::client::execute_remote(config_dir, &cmd, state.read_timeout_ms()) {
+            Ok(Response::Ok { output }) => {
+                reported_down = false;
+                if let Some(text) = state.absorb(&output) {
+                    println!("{}", text);
+                }
+            }
+            Ok(Response::Error { message }) => {
+                eprintln!("skd watch: {}", message);
+                std::thread::sleep(Duration::from_millis(RECONNECT_DELAY_MS));
+            }
+            Err(e) => {
+                if !reported_down {
+                    eprintln!("skd watch: daemon unavailable ({}), retrying", e);
+                    reported_down = true;
+                }
+                std::thread::sleep(Duration::from_millis(RECONNECT_DELAY_MS));
+            }
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(ts: u64) -> String {
+        format!(r#"{{"event":"state_changed","summary":"AgentNew","timestamp":{}}}"#, ts)
+    }
+
+    #[test]
+    fn take_follow_flag_only_for_watch() {
+        let mut args = vec!["watch", "--follow", "--timeout", "500"];
+        assert!(take_follow_flag(&mut args));
+        assert_eq!(args, vec!["watch", "--timeout", "500"]);
+
+        let mut other = vec!["status", "--follow"];
+        assert!(!take_follow_flag(&mut other));
+        assert_eq!(other.len(), 2);
+    }
+
+    #[test]
+    fn since_advances_through_sequence() {
+        let mut state = FollowState::new(None, None);
+        assert_eq!(state.command(), Command::Watch { since: None, timeout: None });
+
+        assert!(state.absorb(&event(100)).is_some());
+        assert_eq!(state.since(), Some(100));
+        assert!(state.absorb(&event(250)).is_some());
+        assert_eq!(state.since(), Some(250));
+        assert_eq!(state.command(), Command::Watch {
+            since: Some("250".into()),
+            timeout: None,
+        });
+    }
+
+    #[test]
+    fn stale_events_are_not_reprinted() {
+        let mut state = FollowState::new(Some(200), None);
+        assert!(state.absorb(&event(150)).is_none());
+        assert!(state.absorb(&event(200)).is_none());
+        assert_eq!(state.since(), Some(200));
+    }
+
+    #[test]
+    fn timeout_keeps_cursor() {
+        let mut state = FollowState::new(Some(300), None);
+        assert!(state.absorb(r#"{"event":"timeout"}"#).is_none());
+        assert!(state.absorb("").is_none());
+        assert_eq!(state.since(), Some(300));
+    }
+
+    #[test]
+    fn multi_line_body_takes_newest() {
+        let mut state = FollowState::new(Some(100), None);
+        let body = format!("{}\n{}\n{}", event(90), event(120), event(180));
+        let printed = state.absorb(&body).unwrap();
+        assert_eq!(printed.lines().count(), 2);
+        assert_eq!(state.since(), Some(180));
+    }
+
+    #[test]
+    fn read_timeout_exceeds_watch_timeout() {
+        let state = FollowState::new(None, Some("1000".into()));
+        assert_eq!(state.read_timeout_ms(), 6_000);
+        let default = FollowState::new(None, None);
+        assert_eq!(default.read_timeout_ms(), 35_000);
+    }
+}
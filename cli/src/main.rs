@@ -8,9 +8,15 @@
 //! skd task list
 //! skd daemon run
 //! skd daemon stop
+//! skd watch --follow
 //! ```
+//!
+//! Exit codes: 0 success, 1 unclassified error, 2 invalid argument,
+//! 3 not found, 4 conflict, 5 backend failure, 6 unavailable.
 
 mod client;
+mod exit;
+mod follow;
 
 use std::path::{Path, PathBuf};
 use std::process;
@@ -20,16 +26,19 @@ use skill_docket_core::command::Command;
 use skill_docket_core::sys::Sys;
 use cmx_utils::response::Response;
 
+use exit::ErrorCode;
+
 
 fn main() {
     let args: Vec<String> = std::env::args().collect();
-    let arg_refs: Vec<&str> = args[1..].iter().map(|s| s.as_str()).collect();
+    let mut arg_refs: Vec<&str> = args[1..].iter().map(|s| s.as_str()).collect();
+    let follow = follow::take_follow_flag(&mut arg_refs);
 
     let cmd = match parse_args(&arg_refs) {
         Ok(c) => c,
         Err(e) => {
             eprintln!("skd: {}", e);
-            process::exit(1);
+            process::exit(ErrorCode::InvalidArgument.exit_code());
         }
     };
 
@@ -53,6 +62,22 @@ fn main() {
         return;
     }
 
+    // Watch --follow loops in the client, re-registering after each event.
+    if follow {
+        if let Command::Watch { since, timeout } = cmd {
+            let since = match since.map(|s| s.parse::<u64>()) {
+                Some(Ok(ms)) => Some(ms),
+                Some(Err(_)) => {
+                    eprintln!("skd: --since must be an epoch-ms timestamp");
+                    process::exit(ErrorCode::InvalidArgument.exit_code());
+                }
+                None => None,
+            };
+            let mut state = follow::FollowState::new(since, timeout);
+            follow::run(&config_dir, &mut state);
+        }
+    }
+
     // DaemonRun is handled directly — run the daemon in this process.
     if matches!(cmd, Command::DaemonRun) {
         let pid_path = config_dir.join("skd.pid");
@@ -77,11 +102,18 @@ fn main() {
     }
 
     // All other commands: use execute_remote (handles daemon lifecycle).
+    let family = ErrorCode::for_command(&cmd);
     let response = match skill_docket_core::client::execute_remote(&config_dir, &cmd, 10_000) {
         Ok(resp) => resp,
         Err(e) => {
             eprintln!("skd: daemon unavailable ({}), using local mode", e);
-            execute_local(&config_dir, cmd)
+            match execute_local(&config_dir, cmd) {
+                Ok(resp) => resp,
+                Err(e) => {
+                    eprintln!("skd error: failed to initialize: {}", e);
+                    process::exit(ErrorCode::Unavailable.exit_code());
+                }
+            }
         }
     };
 
@@ -93,7 +125,7 @@ fn main() {
         }
         Response::Error { message } => {
             eprintln!("skd error: {}", message);
-            process::exit(1);
+            process::exit(exit::exit_code_for(family, &message));
         }
     }
 }
@@ -108,13 +140,10 @@ fn resolve_config_dir() -> PathBuf {
 }
 
 
-fn execute_local(config_dir: &Path, cmd: Command) -> Response {
-    match Sys::new(config_dir) {
-        Ok(mut sys) => sys.execute(cmd),
-        Err(e) => Response::Error {
-            message: format!("Failed to initialize: {}", e),
-        },
-    }
+/// Run `cmd` in this process. Fails when `Sys` cannot start, which the
+/// caller reports as `ErrorCode::Unavailable` rather than by message text.
+fn execute_local(config_dir: &Path, cmd: Command) -> Result<Response, String> {
+    Sys::new(config_dir).map(|mut sys| sys.execute(cmd))
 }
 
 
@@ -146,11 +175,21 @@ mod tests {
         let dir = std::env::temp_dir().join("skd-cli-test-local");
         let _ = std::fs::create_dir_all(&dir);
         let cmd = Command::Status { format: None };
-        let resp = execute_local(&dir, cmd);
+        let resp = execute_local(&dir, cmd).unwrap();
         match resp {
             Response::Ok { output } => assert!(output.contains("agents: 0")),
             Response::Error { message } => panic!("Unexpected error: {}", message),
         }
         let _ = std::fs::remove_dir_all(&dir);
     }
+
+    #[test]
+    fn execute_local_fails_when_config_dir_is_unusable() {
+        let file = std::env::temp_dir().join("skd-cli-test-local-file");
+        let _ = std::fs::remove_dir_all(&file);
+        std::fs::write(&file, "not a directory").unwrap();
+        let resp = execute_local(&file, Command::Status { format: None });
+        assert!(resp.is_err());
+        let _ = std::fs::remove_file(&file);
+    }
 }
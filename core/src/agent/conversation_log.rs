@@ -16,6 +16,19 @@ pub struct LogConfig {
     pub retention_days: u32,
     /// Capture interval in seconds. How often to poll tmux panes. Default: 5.
     pub capture_interval_secs: u32,
+    /// Size limit for one log file in bytes. An append that would push the
+    /// file past it first rotates the file to `<name>.1.md`. 0 disables
+    /// rotation. Default: 0.
+    #[serde(default)]
+    pub max_bytes: u64,
+    /// Rotated files kept per agent and day (`.1.md` newest to `.N.md`
+    /// oldest); older content is deleted. Default: 5.
+    #[serde(default = "default_max_files")]
+    pub max_files: usize,
+}
+
+fn default_max_files() -> usize {
+    5
 }
 
 impl Default for LogConfig {
@@ -25,6 +38,8 @@ impl Default for LogConfig {
             capture_responses: true,
             retention_days: 7,
             capture_interval_secs: 5,
+            max_bytes: 0,
+            max_files: default_max_files(),
         }
     }
 }
@@ -75,6 +90,9 @@ pub struct AgentLogTracker {
     pub current_log_path: PathBuf,
     /// The date of the current log file (YYYY-MM-DD).
     pub current_date: String,
+    /// Bytes of today's log deleted by rotation. `read_since` offsets count
+    /// from the start of the day's log, so they stay valid across rotations.
+    pub discarded_bytes: usize,
 }
 
 /// Manages conversation logging for all agents.
@@ -108,6 +126,7 @@ impl ConversationLogger {
             last_offset: 0,
             current_log_path: self.log_file_path(agent_name, "0000-00-00"),
             current_date: String::new(),
+            discarded_bytes: 0,
         };
         self.trackers.insert(agent_name.to_string(), tracker);
         Ok(())
@@ -143,6 +162,8 @@ impl ConversationLogger {
 
         // Compute the new path before borrowing tracker mutably.
         let new_path = self.log_file_path(agent_name, date);
+        let max_bytes = self.config.max_bytes;
+        let max_files = self.config.max_files;
 
         let tracker = self.trackers.get_mut(agent_name).unwrap();
 
@@ -150,6 +171,7 @@ impl ConversationLogger {
         if tracker.current_date != date {
             tracker.current_date = date.to_string();
             tracker.current_log_path = new_path;
+            tracker.discarded_bytes = 0;
         }
 
         // Determine new content based on byte offset.
@@ -163,6 +185,14 @@ impl ConversationLogger {
             return Ok(0);
         }
 
+        // Rotate first if this append would push the file past the limit.
+        if max_bytes > 0 {
+            let current = fs::metadata(&tracker.current_log_path).map_or(0, |m| m.len());
+            if current > 0 && current + new_content.len() as u64 > max_bytes {
+                tracker.discarded_bytes += rotate(&tracker.current_log_path, max_files)? as usize;
+            }
+        }
+
         // Append new content to the log file.
         use std::io::Write;
         let mut file = fs::OpenOptions::new()
@@ -184,14 +214,18 @@ impl ConversationLogger {
             .map(|t| t.current_log_path.as_path())
     }
 
-    /// Read the full log for an agent on a given date (YYYY-MM-DD).
+    /// Read the full log for an agent on a given date (YYYY-MM-DD),
+    /// including rotated files, oldest first.
     pub fn read_log(&self, agent_name: &str, date: &str) -> Result<String, LogError> {
         if !is_valid_date(date) {
             return Err(LogError::InvalidDate(date.to_string()));
         }
         let path = self.log_file_path(agent_name, date);
-        let content = fs::read_to_string(&path)?;
-        Ok(content)
+        if log_files(&path).is_empty() {
+            // Surface the usual not-found error.
+            fs::read_to_string(&path)?;
+        }
+        read_spanning(&path)
     }
 
     /// List all available log dates for an agent.
@@ -243,7 +277,7 @@ impl ConversationLogger {
                 let date_part = &name_str[..10];
                 if is_valid_date(date_part) {
                     // After "YYYY-MM-DD-" (11 chars) and before ".md" (3 chars).
-                    let agent_part = &name_str[11..name_str.len() - 3];
+                    let agent_part = strip_rotation_suffix(&name_str[11..name_str.len() - 3]);
                     if !agent_part.is_empty() {
                         agents.insert(agent_part.to_string());
                     }
@@ -315,18 +349,20 @@ impl ConversationLogger {
             .ok_or_else(|| LogError::AgentNotRegistered(agent_name.to_string()))?;
 
         let path = &tracker.current_log_path;
-        if !path.exists() {
+        if log_files(path).is_empty() {
             return Ok((String::new(), offset));
         }
 
-        let content = fs::read_to_string(path)?;
-        let content_len = content.len();
+        // The retained files hold the log from `discarded_bytes` onward.
+        let content = read_spanning(path)?;
+        let base = tracker.discarded_bytes;
+        let content_len = base + content.len();
 
         if offset >= content_len {
             return Ok((String::new(), content_len));
         }
 
-        let new_content = content[offset..].to_string();
+        let new_content = content[offset.saturating_sub(base)..].to_string();
         Ok((new_content, content_len))
     }
 
@@ -336,6 +372,71 @@ impl ConversationLogger {
     }
 }
 
+/// Path of rotated file `n` for the log at `path`: `<name>.<n>.md`.
+fn rotated_path(path: &Path, n: usize) -> PathBuf {
+    path.with_extension(format!("{}.md", n))
+}
+
+/// Rotate the log at `path`: drop the oldest rotated file if `max_files`
+/// are already kept, shift the rest up by one, and move `path` to `.1.md`.
+/// With `max_files == 0` the current file is deleted instead.
+/// Returns the number of bytes deleted.
+fn rotate(path: &Path, max_files: usize) -> Result<u64, LogError> {
+    let oldest = if max_files == 0 {
+        path.to_path_buf()
+    } else {
+        rotated_path(path, max_files)
+    };
+    let mut discarded = 0;
+    if let Ok(meta) = fs::metadata(&oldest) {
+        discarded = meta.len();
+        fs::remove_file(&oldest)?;
+    }
+    if max_files == 0 {
+        return Ok(discarded);
+    }
+    for n in (1..max_files).rev() {
+        let from = rotated_path(path, n);
+        if from.exists() {
+            fs::rename(&from, rotated_path(path, n + 1))?;
+        }
+    }
+    fs::rename(path, rotated_path(path, 1))?;
+    Ok(discarded)
+}
+
+/// Existing files of the log at `path`, oldest first: rotated files from
+/// the highest number down, then `path` itself.
+fn log_files(path: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = (1..)
+        .map(|n| rotated_path(path, n))
+        .take_while(|p| p.exists())
+        .collect();
+    files.reverse();
+    if path.exists() {
+        files.push(path.to_path_buf());
+    }
+    files
+}
+
+/// The log at `path` with its rotated files, concatenated in order.
+fn read_spanning(path: &Path) -> Result<String, LogError> {
+    let mut content = String::new();
+    for file in log_files(path) {
+        content.push_str(&fs::read_to_string(&file)?);
+    }
+    Ok(content)
+}
+
+/// Strip a rotation number from the agent part of a log file name
+/// (`pilot.2` -> `pilot`).
+fn strip_rotation_suffix(name: &str) -> &str {
+    match name.rsplit_once('.') {
+        Some((stem, n)) if !stem.is_empty() && !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()) => stem,
+        _ => name,
+    }
+}
+
 /// Validate that a date string has YYYY-MM-DD format with plausible values.
 fn is_valid_date(date: &str) -> bool {
     if date.len() != 10 {
@@ -762,6 +863,8 @@ mod tests {
         assert!(config.capture_responses);
         assert_eq!(config.retention_days, 7);
         assert_eq!(config.capture_interval_secs, 5);
+        assert_eq!(config.max_bytes, 0);
+        assert_eq!(config.max_files, 5);
     }
 
     #[test]
@@ -778,4 +881,74 @@ mod tests {
         let date_err = LogError::InvalidDate("bad".into());
         assert!(format!("{}", date_err).contains("bad"));
     }
+
+    fn rotating_logger(dir: &Path, max_bytes: u64, max_files: usize) -> ConversationLogger {
+        let config = LogConfig {
+            max_bytes,
+            max_files,
+            ..LogConfig::default()
+        };
+        let mut logger = ConversationLogger::new(dir, config).unwrap();
+        logger.register_agent("pilot").unwrap();
+        logger
+    }
+
+    #[test]
+    fn rotation_keeps_max_files_and_read_spans_them_in_order() {
+        let dir = temp_dir();
+        let mut logger = rotating_logger(&dir, 10, 2);
+
+        let mut pane = String::new();
+        for chunk in ["aaaaaaaaaa", "bbbbbbbbbb", "cccccccccc", "dddddddddd"] {
+            pane.push_str(chunk);
+            logger.process_capture("pilot", &pane, "2026-02-17").unwrap();
+        }
+
+        let log_dir = dir.join(".pilot-log");
+        assert_eq!(fs::read_to_string(log_dir.join("2026-02-17-pilot.md")).unwrap(), "dddddddddd");
+        assert_eq!(fs::read_to_string(log_dir.join("2026-02-17-pilot.1.md")).unwrap(), "cccccccccc");
+        assert_eq!(fs::read_to_string(log_dir.join("2026-02-17-pilot.2.md")).unwrap(), "bbbbbbbbbb");
+        assert!(!log_dir.join("2026-02-17-pilot.3.md").exists());
+
+        // The oldest chunk was dropped; the rest reads back in write order.
+        let log = logger.read_log("pilot", "2026-02-17").unwrap();
+        assert_eq!(log, "bbbbbbbbbbccccccccccdddddddddd");
+
+        // Rotated files still belong to the same agent and date.
+        assert_eq!(logger.list_agents().unwrap(), vec!["pilot"]);
+        assert_eq!(logger.list_dates("pilot").unwrap(), vec!["2026-02-17"]);
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn read_since_offsets_survive_rotation() {
+        let dir = temp_dir();
+        let mut logger = rotating_logger(&dir, 8, 1);
+
+        logger.process_capture("pilot", "12345678", "2026-02-17").unwrap();
+        let (first, offset) = logger.read_since("pilot", 0).unwrap();
+        assert_eq!((first.as_str(), offset), ("12345678", 8));
+
+        // Two more rotations: "12345678" is deleted, "abcdefgh" is kept as .1.
+        logger.process_capture("pilot", "12345678abcdefgh", "2026-02-17").unwrap();
+        logger.process_capture("pilot", "12345678abcdefghXY", "2026-02-17").unwrap();
+
+        let (new, offset) = logger.read_since("pilot", offset).unwrap();
+        assert_eq!(new, "abcdefghXY");
+        assert_eq!(offset, 18);
+        let (none, _) = logger.read_since("pilot", offset).unwrap();
+        assert!(none.is_empty());
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn no_rotation_when_max_bytes_is_zero() {
+        let dir = temp_dir();
+        let mut logger = rotating_logger(&dir, 0, 2);
+        logger.process_capture("pilot", &"x".repeat(100), "2026-02-17").unwrap();
+        logger.process_capture("pilot", &"x".repeat(200), "2026-02-17").unwrap();
+        assert!(!dir.join(".pilot-log/2026-02-17-pilot.1.md").exists());
+        assert_eq!(logger.read_log("pilot", "2026-02-17").unwrap().len(), 200);
+        fs::remove_dir_all(&dir).ok();
+    }
 }
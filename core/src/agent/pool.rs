@@ -12,6 +12,10 @@ pub struct PoolConfig {
     pub target_size: u32,
     pub auto_expand: bool,
     pub max_size: u32,
+    /// Idle agents to keep in reserve, spawning ahead of demand (up to
+    /// `max_size`) so urgent tasks don't wait for a spawn.
+    #[serde(default)]
+    pub min_idle: u32,
     pub path: String,
 }
 
@@ -28,9 +32,28 @@ pub struct PoolState {
 }
 
 
+/// How long a surplus worker must sit idle before `autoscale` retires it.
+pub const DEFAULT_RETIRE_AFTER_MS: u64 = 10 * 60 * 1000;
+
+
+/// A scaling recommendation from `PoolManager::autoscale`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PoolAction {
+    /// Spawn one worker for the role.
+    Spawn { role: String },
+    /// Retire this idle worker.
+    Retire { agent: String },
+}
+
+
 /// Manages worker pools across all configured roles.
 pub struct PoolManager {
     configs: HashMap<String, PoolConfig>,
+    /// When each currently idle agent was first seen idle by `observe_idle`.
+    idle_since: HashMap<String, u64>,
+    /// Time of the last `observe_idle` call.
+    observed_ms: u64,
+    retire_after_ms: u64,
 }
 
 
@@ -39,9 +62,17 @@ impl PoolManager {
     pub fn new() -> Self {
         Self {
             configs: HashMap::new(),
+            idle_since: HashMap::new(),
+            observed_ms: 0,
+            retire_after_ms: DEFAULT_RETIRE_AFTER_MS,
         }
     }
 
+    /// Set how long a surplus worker must be idle before it is retired.
+    pub fn set_retire_after(&mut self, ms: u64) {
+        self.retire_after_ms = ms;
+    }
+
     /// Configure a pool for a role.
     pub fn set_pool(&mut self, role: &str, config: PoolConfig) {
         self.configs.insert(role.to_string(), config);
@@ -82,14 +113,21 @@ impl PoolManager {
         })
     }
 
-    /// Determine how many agents need to be spawned to reach target for a role.
-    /// Returns 0 if pool is already at or above target.
+    /// Determine how many agents need to be spawned for a role: enough to
+    /// reach the target size, or to restore the `min_idle` reserve if that
+    /// is larger. Agents still spawning count toward the reserve, and the
+    /// reserve never grows the pool beyond `max_size`.
+    /// Returns 0 if both are already satisfied.
     pub fn deficit(&self, role: &str, registry: &AgentRegistry) -> u32 {
         let state = match self.pool_state(role, registry) {
             Some(s) => s,
             None => return 0,
         };
-        state.config.target_size.saturating_sub(state.total)
+        let to_target = state.config.target_size.saturating_sub(state.total);
+        let available = state.idle_count + state.spawning_count;
+        let headroom = state.config.max_size.saturating_sub(state.total);
+        let to_reserve = state.config.min_idle.saturating_sub(available).min(headroom);
+        to_target.max(to_reserve)
     }
 
     /// Determine deficits across all configured pools.
@@ -133,6 +171,75 @@ impl PoolManager {
             .map(|a| a.name.clone())
     }
 
+    /// Record which agents are idle at `now_ms`, so `autoscale` can tell
+    /// how long each has been idle. Call this on every monitoring pass.
+    pub fn observe_idle(&mut self, registry: &AgentRegistry, now_ms: u64) {
+        let mut idle_since = HashMap::new();
+        for agent in registry.list() {
+            if agent.status == AgentStatus::Idle && agent.task.is_none() {
+                let since = self.idle_since.get(&agent.name).copied().unwrap_or(now_ms);
+                idle_since.insert(agent.name.clone(), since);
+            }
+        }
+        self.idle_since = idle_since;
+        self.observed_ms = now_ms;
+    }
+
+    /// How long `agent` had been idle as of the last `observe_idle`.
+    pub fn idle_for_ms(&self, agent: &str) -> Option<u64> {
+        self.idle_since
+            .get(agent)
+            .map(|since| self.observed_ms.saturating_sub(*since))
+    }
+
+    /// Recommend scaling actions for a role's pool given the tasks waiting
+    /// for a worker.
+    ///
+    /// - Under load (`pending_tasks > 0` and `should_auto_expand`), the
+    ///   pool spawns one worker per pending task not already covered by a
+    ///   spawning agent, and at least its `deficit`, never past `max_size`.
+    /// - With nothing pending, idle workers beyond `target_size` are
+    ///   retired once idle for the retire threshold, longest idle first,
+    ///   keeping the `min_idle` reserve.
+    pub fn autoscale(&self, role: &str, pending_tasks: usize, agents: &AgentRegistry) -> Vec<PoolAction> {
+        let state = match self.pool_state(role, agents) {
+            Some(s) => s,
+            None => return Vec::new(),
+        };
+        let config = &state.config;
+
+        if pending_tasks > 0 {
+            if !self.should_auto_expand(role, agents) {
+                return Vec::new();
+            }
+            let wanted = (pending_tasks as u32).saturating_sub(state.spawning_count);
+            let headroom = config.max_size.saturating_sub(state.total);
+            let count = wanted.max(self.deficit(role, agents)).min(headroom);
+            return (0..count)
+                .map(|_| PoolAction::Spawn { role: role.to_string() })
+                .collect();
+        }
+
+        let surplus = state.total.saturating_sub(config.target_size);
+        let spare = state.idle_count.saturating_sub(config.min_idle);
+        let mut candidates: Vec<(&str, u64)> = agents
+            .find_by_role(role)
+            .into_iter()
+            .filter(|a| a.status == AgentStatus::Idle && a.task.is_none())
+            .filter_map(|a| {
+                self.idle_for_ms(&a.name)
+                    .filter(|ms| *ms >= self.retire_after_ms)
+                    .map(|ms| (a.name.as_str(), ms))
+            })
+            .collect();
+        candidates.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        candidates
+            .into_iter()
+            .take(surplus.min(spare) as usize)
+            .map(|(name, _)| PoolAction::Retire { agent: name.to_string() })
+            .collect()
+    }
+
     /// Check if auto-expand should create a new worker.
     /// Returns true if the role's pool is configured for auto-expand,
     /// all members are busy, and total < max_size.
@@ -163,6 +270,7 @@ mod tests {
             target_size: target,
             auto_expand: false,
             max_size: target * 2,
+            min_idle: 0,
             path: path.to_string(),
         }
     }
@@ -179,6 +287,8 @@ mod tests {
             health: HealthState::Unknown,
             last_heartbeat_ms: None,
             session: None,
+            created_at_ms: None,
+            assigned_at_ms: None,
         }
     }
 
@@ -194,6 +304,8 @@ mod tests {
             health: HealthState::Unknown,
             last_heartbeat_ms: None,
             session: None,
+            created_at_ms: None,
+            assigned_at_ms: None,
         }
     }
 
@@ -388,6 +500,7 @@ mod tests {
                 target_size: 2,
                 auto_expand: true,
                 max_size: 4,
+                min_idle: 0,
                 path: "/tmp".into(),
             },
         );
@@ -407,6 +520,7 @@ mod tests {
                 target_size: 2,
                 auto_expand: true,
                 max_size: 4,
+                min_idle: 0,
                 path: "/tmp".into(),
             },
         );
@@ -426,6 +540,7 @@ mod tests {
                 target_size: 2,
                 auto_expand: true,
                 max_size: 2,
+                min_idle: 0,
                 path: "/tmp".into(),
             },
         );
@@ -469,6 +584,7 @@ mod tests {
                 target_size: 2,
                 auto_expand: false,
                 max_size: 4,
+                min_idle: 0,
                 path: "/tmp".into(),
             },
         );
@@ -505,6 +621,47 @@ mod tests {
         assert_eq!(cfg.path, "/tmp/new");
     }
 
+    // 25a. Min idle: all busy at target -> spawn one ahead of demand
+    #[test]
+    fn min_idle_spawns_when_all_busy() {
+        let mut pm = PoolManager::new();
+        let mut cfg = make_pool_config(2, "/tmp");
+        cfg.min_idle = 1;
+        pm.set_pool("worker", cfg);
+        let mut reg = AgentRegistry::new();
+        reg.add(make_busy_agent("worker1", "worker", "T1")).unwrap();
+        reg.add(make_busy_agent("worker2", "worker", "T2")).unwrap();
+        assert_eq!(pm.deficit("worker", &reg), 1);
+        assert_eq!(pm.replenishment_names(&reg).len(), 1);
+    }
+
+    // 25b. Min idle: reserve already satisfied -> no spawn
+    #[test]
+    fn min_idle_satisfied_spawns_none() {
+        let mut pm = PoolManager::new();
+        let mut cfg = make_pool_config(2, "/tmp");
+        cfg.min_idle = 1;
+        pm.set_pool("worker", cfg);
+        let mut reg = AgentRegistry::new();
+        reg.add(make_agent("worker1", "worker")).unwrap();
+        reg.add(make_busy_agent("worker2", "worker", "T1")).unwrap();
+        assert_eq!(pm.deficit("worker", &reg), 0);
+    }
+
+    // 25c. Min idle never grows the pool past max_size
+    #[test]
+    fn min_idle_capped_at_max_size() {
+        let mut pm = PoolManager::new();
+        let mut cfg = make_pool_config(2, "/tmp");
+        cfg.min_idle = 3;
+        cfg.max_size = 3;
+        pm.set_pool("worker", cfg);
+        let mut reg = AgentRegistry::new();
+        reg.add(make_busy_agent("worker1", "worker", "T1")).unwrap();
+        reg.add(make_busy_agent("worker2", "worker", "T2")).unwrap();
+        assert_eq!(pm.deficit("worker", &reg), 1);
+    }
+
     // 25. All deficits empty when all pools satisfied
     #[test]
     fn all_deficits_empty_when_satisfied() {
@@ -516,4 +673,101 @@ mod tests {
         let deficits = pm.all_deficits(&reg);
         assert!(deficits.is_empty());
     }
+
+    fn expanding_config(target: u32, max: u32) -> PoolConfig {
+        PoolConfig {
+            auto_expand: true,
+            max_size: max,
+            ..make_pool_config(target, "/tmp")
+        }
+    }
+
+    #[test]
+    fn autoscale_spawns_under_load_up_to_max_size() {
+        let mut pm = PoolManager::new();
+        pm.set_pool("worker", expanding_config(2, 3));
+        let mut reg = AgentRegistry::new();
+        reg.add(make_busy_agent("worker1", "worker", "T1")).unwrap();
+        reg.add(make_busy_agent("worker2", "worker", "T2")).unwrap();
+
+        // Five tasks waiting, but only one slot below max_size.
+        let actions = pm.autoscale("worker", 5, &reg);
+        assert_eq!(actions, vec![PoolAction::Spawn { role: "worker".into() }]);
+
+        // With an idle worker available, nothing is spawned.
+        reg.add(make_agent("worker3", "worker")).unwrap();
+        assert!(pm.autoscale("worker", 5, &reg).is_empty());
+    }
+
+    #[test]
+    fn autoscale_caps_deficit_at_max_size() {
+        let mut pm = PoolManager::new();
+        pm.set_pool("worker", expanding_config(5, 3));
+        let mut reg = AgentRegistry::new();
+        reg.add(make_busy_agent("worker1", "worker", "T1")).unwrap();
+
+        // The target asks for four more, but max_size leaves room for two.
+        assert_eq!(pm.deficit("worker", &reg), 4);
+        assert_eq!(pm.autoscale("worker", 1, &reg).len(), 2);
+    }
+
+    #[test]
+    fn autoscale_does_not_spawn_without_auto_expand_or_pending() {
+        let mut pm = PoolManager::new();
+        pm.set_pool("worker", make_pool_config(1, "/tmp"));
+        let mut reg = AgentRegistry::new();
+        reg.add(make_busy_agent("worker1", "worker", "T1")).unwrap();
+        assert!(pm.autoscale("worker", 3, &reg).is_empty());
+
+        pm.set_pool("worker", expanding_config(1, 4));
+        assert!(pm.autoscale("worker", 0, &reg).is_empty());
+        assert_eq!(pm.autoscale("worker", 2, &reg).len(), 2);
+        assert!(pm.autoscale("ghost", 2, &reg).is_empty());
+    }
+
+    #[test]
+    fn autoscale_retires_overprovisioned_idle_workers() {
+        let mut pm = PoolManager::new();
+        pm.set_pool("worker", expanding_config(1, 4));
+        pm.set_retire_after(1_000);
+        let mut reg = AgentRegistry::new();
+        reg.add(make_agent("worker1", "worker")).unwrap();
+        pm.observe_idle(&reg, 0);
+        reg.add(make_agent("worker2", "worker")).unwrap();
+        reg.add(make_busy_agent("worker3", "worker", "T1")).unwrap();
+        pm.observe_idle(&reg, 500);
+
+        // Not idle long enough yet.
+        assert!(pm.autoscale("worker", 0, &reg).is_empty());
+
+        pm.observe_idle(&reg, 1_500);
+        // Surplus is 2 (3 workers, target 1), but only the two idle workers
+        // past the threshold can go, longest idle first.
+        assert_eq!(
+            pm.autoscale("worker", 0, &reg),
+            vec![
+                PoolAction::Retire { agent: "worker1".into() },
+                PoolAction::Retire { agent: "worker2".into() },
+            ]
+        );
+
+        // Pending work keeps idle workers around.
+        assert!(pm.autoscale("worker", 1, &reg).is_empty());
+    }
+
+    #[test]
+    fn autoscale_retire_keeps_min_idle_reserve() {
+        let mut pm = PoolManager::new();
+        let mut cfg = expanding_config(1, 4);
+        cfg.min_idle = 1;
+        pm.set_pool("worker", cfg);
+        pm.set_retire_after(0);
+        let mut reg = AgentRegistry::new();
+        reg.add(make_agent("worker1", "worker")).unwrap();
+        reg.add(make_agent("worker2", "worker")).unwrap();
+        reg.add(make_agent("worker3", "worker")).unwrap();
+        pm.observe_idle(&reg, 100);
+        assert_eq!(pm.autoscale("worker", 0, &reg).len(), 2);
+        assert_eq!(pm.idle_for_ms("worker1"), Some(0));
+    }
 }
@@ -32,6 +32,7 @@ pub fn parse_args(args: &[&str]) -> Result<Command, String> {
         "diagnosis" => parse_diagnosis(args),
         "history" => parse_history(args),
         "learnings" => parse_learnings(args),
+        "skill" => parse_skill(args),
         "daemon" => parse_daemon(args),
         "watch" => parse_watch(args),
         "tui" => Ok(Command::Tui),
@@ -54,20 +55,29 @@ fn parse_view(args: &[&str]) -> Result<Command, String> {
     })
 }
 
-/// `cmx help [topic]`
+/// `cmx help [topic]` or `cmx help --search <keywords...>`
 fn parse_help(args: &[&str]) -> Result<Command, String> {
+    if args.get(1) == Some(&"--search") {
+        if args.len() < 3 {
+            return Err("Usage: cmx help --search <keywords>".into());
+        }
+        return Ok(Command::Help {
+            topic: None,
+            search: Some(args[2..].join(" ")),
+        });
+    }
     let topic = if args.len() > 1 {
         Some(args[1..].join(" "))
     } else {
         None
     };
-    Ok(Command::Help { topic })
+    Ok(Command::Help { topic, search: None })
 }
 
 /// `cmx agent <subcommand> ...`
 fn parse_agent(args: &[&str]) -> Result<Command, String> {
     if args.len() < 2 {
-        return Err("Usage: cmx agent <new|kill|restart|assign|unassign|status|list>".into());
+        return Err("Usage: cmx agent <new|kill|restart|assign|unassign|status|list|recheck|exec|state>".into());
     }
     match args[1] {
         "new" => parse_agent_new(args),
@@ -77,6 +87,9 @@ fn parse_agent(args: &[&str]) -> Result<Command, String> {
         "unassign" => parse_agent_unassign(args),
         "status" => parse_agent_status(args),
         "list" => parse_agent_list(args),
+        "recheck" => parse_agent_recheck(args),
+        "exec" => parse_agent_exec(args),
+        "state" => parse_agent_state(args),
         _ => Err(format!("Unknown agent subcommand: '{}'", args[1])),
     }
 }
@@ -114,13 +127,74 @@ fn parse_agent_new(args: &[&str]) -> Result<Command, String> {
     Ok(Command::AgentNew { role, name, path, agent_type })
 }
 
-/// `cmx agent kill <name>`
+/// `cmx agent kill <name> [--graceful]` or
+/// `cmx agent kill [--role <r>] [--status <s>] [--label <l>]`
 fn parse_agent_kill(args: &[&str]) -> Result<Command, String> {
     if args.len() < 3 {
-        return Err("Usage: cmx agent kill <name>".into());
+        return Err("Usage: cmx agent kill <name> [--graceful] | --role <r> | --status <s> | --label <l>".into());
+    }
+    if args[2].starts_with("--") {
+        return parse_agent_kill_where(args);
+    }
+    let mut graceful = false;
+    for flag in &args[3..] {
+        match *flag {
+            "--graceful" => graceful = true,
+            other => return Err(format!("Unknown flag for agent kill: '{}'", other)),
+        }
     }
     Ok(Command::AgentKill {
         name: args[2].into(),
+        graceful,
+    })
+}
+
+/// `cmx agent kill [--role <r>] [--status <s>] [--label <l>]`
+fn parse_agent_kill_where(args: &[&str]) -> Result<Command, String> {
+    let mut role = None;
+    let mut status = None;
+    let mut label = None;
+    let rest = &args[2..];
+    let mut i = 0;
+    while i < rest.len() {
+        match rest[i] {
+            "--role" => {
+                i += 1;
+                role = Some(take_arg(rest, i, "--role")?);
+            }
+            "--status" => {
+                i += 1;
+                status = Some(take_arg(rest, i, "--status")?);
+            }
+            "--label" => {
+                i += 1;
+                label = Some(take_arg(rest, i, "--label")?);
+            }
+            other => return Err(format!("Unknown flag for agent kill: '{}'", other)),
+        }
+        i += 1;
+    }
+    Ok(Command::AgentKillWhere { role, status, label })
+}
+
+/// `cmx agent recheck <name>`
+fn parse_agent_recheck(args: &[&str]) -> Result<Command, String> {
+    if args.len() < 3 {
+        return Err("Usage: cmx agent recheck <name>".into());
+    }
+    Ok(Command::AgentRecheck {
+        name: args[2].into(),
+    })
+}
+
+/// `cmx agent exec <agent> <cmd...>`
+fn parse_agent_exec(args: &[&str]) -> Result<Command, String> {
+    if args.len() < 4 {
+        return Err("Usage: cmx agent exec <agent> <cmd...>".into());
+    }
+    Ok(Command::AgentExec {
+        agent: args[2].into(),
+        cmd: args[3..].join(" "),
     })
 }
 
@@ -169,20 +243,66 @@ fn parse_agent_status(args: &[&str]) -> Result<Command, String> {
     Ok(Command::AgentStatus { name, notes })
 }
 
-/// `cmx agent list [--json]`
+/// `cmx agent list [--sort <key>] [--columns <list>] [--json]`
 fn parse_agent_list(args: &[&str]) -> Result<Command, String> {
-    let format = if args.contains(&"--json") {
-        Some("json".into())
-    } else {
-        None
-    };
-    Ok(Command::AgentList { format })
+    let mut format = None;
+    let mut sort_by = None;
+    let mut columns = None;
+    let rest = &args[2..];
+    let mut i = 0;
+    while i < rest.len() {
+        match rest[i] {
+            "--sort" => {
+                i += 1;
+                sort_by = Some(take_arg(rest, i, "--sort")?);
+            }
+            "--columns" => {
+                i += 1;
+                columns = Some(take_arg(rest, i, "--columns")?);
+            }
+            "--json" => {
+                format = Some("json".into());
+            }
+            other => return Err(format!("Unknown flag for agent list: '{}'", other)),
+        }
+        i += 1;
+    }
+    Ok(Command::AgentList { format, sort_by, columns })
+}
+
+/// `cmx agent state <set|get> <agent> <key> [<value...>]`
+fn parse_agent_state(args: &[&str]) -> Result<Command, String> {
+    if args.len() < 3 {
+        return Err("Usage: cmx agent state <set|get> <agent> <key> [<value>]".into());
+    }
+    match args[2] {
+        "set" => {
+            if args.len() < 6 {
+                return Err("Usage: cmx agent state set <agent> <key> <value>".into());
+            }
+            Ok(Command::AgentStateSet {
+                agent: args[3].into(),
+                key: args[4].into(),
+                value: args[5..].join(" "),
+            })
+        }
+        "get" => {
+            if args.len() < 5 {
+                return Err("Usage: cmx agent state get <agent> <key>".into());
+            }
+            Ok(Command::AgentStateGet {
+                agent: args[3].into(),
+                key: args[4].into(),
+            })
+        }
+        _ => Err(format!("Unknown agent state subcommand: '{}'", args[2])),
+    }
 }
 
 /// `cmx task <subcommand> ...`
 fn parse_task(args: &[&str]) -> Result<Command, String> {
     if args.len() < 2 {
-        return Err("Usage: cmx task <list|get|set|check|uncheck>".into());
+        return Err("Usage: cmx task <list|get|set|check|uncheck|note|move>".into());
     }
     match args[1] {
         "list" => parse_task_list(args),
@@ -190,6 +310,8 @@ fn parse_task(args: &[&str]) -> Result<Command, String> {
         "set" => parse_task_set(args),
         "check" => parse_task_check(args),
         "uncheck" => parse_task_uncheck(args),
+        "note" => parse_task_note(args),
+        "move" => parse_task_move(args),
         _ => Err(format!("Unknown task subcommand: '{}'", args[1])),
     }
 }
@@ -198,12 +320,30 @@ fn parse_task(args: &[&str]) -> Result<Command, String> {
 fn parse_task_list(args: &[&str]) -> Result<Command, String> {
     let mut format = None;
     let mut project = None;
+    let mut max_depth = None;
+    let mut status = None;
+    let mut agent = None;
     let mut i = 2;
     while i < args.len() {
         match args[i] {
             "--json" => {
                 format = Some("json".into());
             }
+            "--depth" => {
+                i += 1;
+                let value = take_arg(args, i, "--depth")?;
+                max_depth = Some(value.parse::<usize>().map_err(|_| {
+                    format!("--depth must be a non-negative integer, got '{}'", value)
+                })?);
+            }
+            "--status" => {
+                i += 1;
+                status = Some(take_arg(args, i, "--status")?);
+            }
+            "--agent" => {
+                i += 1;
+                agent = Some(take_arg(args, i, "--agent")?);
+            }
             other if !other.starts_with("--") => {
                 project = Some(other.into());
             }
@@ -211,7 +351,13 @@ fn parse_task_list(args: &[&str]) -> Result<Command, String> {
         }
         i += 1;
     }
-    Ok(Command::TaskList { format, project })
+    Ok(Command::TaskList {
+        format,
+        project,
+        max_depth,
+        status,
+        agent,
+    })
 }
 
 /// `cmx task get <id>`
@@ -234,9 +380,13 @@ fn parse_task_set(args: &[&str]) -> Result<Command, String> {
     let mut title = None;
     let mut result = None;
     let mut agent = None;
+    let mut due = None;
+    let mut cascade = false;
 
     for kv in &args[3..] {
-        if let Some(eq_pos) = kv.find('=') {
+        if *kv == "--cascade" {
+            cascade = true;
+        } else if let Some(eq_pos) = kv.find('=') {
             let key = &kv[..eq_pos];
             let value = kv[eq_pos + 1..].to_string();
             match key {
@@ -244,22 +394,31 @@ fn parse_task_set(args: &[&str]) -> Result<Command, String> {
                 "title" => title = Some(value),
                 "result" => result = Some(value),
                 "agent" => agent = Some(value),
+                "due" => due = Some(value),
                 _ => return Err(format!("Unknown task field: '{}'", key)),
             }
         } else {
             return Err(format!("Expected key=value, got: '{}'", kv));
         }
     }
-    Ok(Command::TaskSet { id, status, title, result, agent })
+    Ok(Command::TaskSet { id, status, title, result, agent, due, cascade })
 }
 
-/// `cmx task check <id>`
+/// `cmx task check <id> [--cascade]`
 fn parse_task_check(args: &[&str]) -> Result<Command, String> {
     if args.len() < 3 {
-        return Err("Usage: cmx task check <id>".into());
+        return Err("Usage: cmx task check <id> [--cascade]".into());
+    }
+    let mut cascade = false;
+    for arg in &args[3..] {
+        match *arg {
+            "--cascade" => cascade = true,
+            other => return Err(format!("Unknown flag for task check: '{}'", other)),
+        }
     }
     Ok(Command::TaskCheck {
         id: args[2].into(),
+        cascade,
     })
 }
 
@@ -273,10 +432,32 @@ fn parse_task_uncheck(args: &[&str]) -> Result<Command, String> {
     })
 }
 
+/// `cmx task note <id> <text...>`
+fn parse_task_note(args: &[&str]) -> Result<Command, String> {
+    if args.len() < 4 {
+        return Err("Usage: cmx task note <id> <text...>".into());
+    }
+    Ok(Command::TaskNote {
+        id: args[2].into(),
+        text: args[3..].join(" "),
+    })
+}
+
+/// `cmx task move <id> <new-parent>`
+fn parse_task_move(args: &[&str]) -> Result<Command, String> {
+    if args.len() < 4 {
+        return Err("Usage: cmx task move <id> <new-parent>".into());
+    }
+    Ok(Command::TaskMove {
+        id: args[2].into(),
+        parent: args[3].into(),
+    })
+}
+
 /// `cmx config <load|save|add|list>`
 fn parse_config(args: &[&str]) -> Result<Command, String> {
     if args.len() < 2 {
-        return Err("Usage: cmx config <load|save|add|list>".into());
+        return Err("Usage: cmx config <load|save|add|unset|list>".into());
     }
     match args[1] {
         "load" => {
@@ -304,6 +485,14 @@ fn parse_config(args: &[&str]) -> Result<Command, String> {
                 value: args[3..].join(" "),
             })
         }
+        "unset" => {
+            if args.len() < 3 {
+                return Err("Usage: cmx config unset <key>".into());
+            }
+            Ok(Command::ConfigUnset {
+                key: args[2].into(),
+            })
+        }
         "list" => Ok(Command::ConfigList),
         _ => Err(format!("Unknown config subcommand: '{}'", args[1])),
     }
@@ -387,23 +576,33 @@ fn parse_pool(args: &[&str]) -> Result<Command, String> {
         }
         "set" => {
             if args.len() < 4 {
-                return Err("Usage: cmx pool set <role> <size> [--path <path>]".into());
+                return Err(
+                    "Usage: cmx pool set <role> <size> [--path <path>] [--min-idle <n>]".into(),
+                );
             }
             let role = args[2].to_string();
             let size: u32 = args[3]
                 .parse()
                 .map_err(|_| format!("Invalid pool size: '{}'", args[3]))?;
             let mut path = None;
+            let mut min_idle = None;
             let rest = &args[4..];
             let mut i = 0;
             while i < rest.len() {
                 if rest[i] == "--path" {
                     i += 1;
                     path = Some(take_arg(rest, i, "--path")?);
+                } else if rest[i] == "--min-idle" {
+                    i += 1;
+                    let val = take_arg(rest, i, "--min-idle")?;
+                    min_idle = Some(
+                        val.parse::<u32>()
+                            .map_err(|_| format!("Invalid --min-idle value: '{}'", val))?,
+                    );
                 }
                 i += 1;
             }
-            Ok(Command::PoolSet { role, size, path })
+            Ok(Command::PoolSet { role, size, path, min_idle })
         }
         "remove" => {
             if args.len() < 3 {
@@ -417,14 +616,20 @@ fn parse_pool(args: &[&str]) -> Result<Command, String> {
     }
 }
 
-/// `cmx tell <agent> <text...>`
+/// `cmx tell [--dedup] <agent> <text...>`
+///
+/// `--dedup` is only recognized before the agent name so message text
+/// may contain it verbatim.
 fn parse_tell(args: &[&str]) -> Result<Command, String> {
-    if args.len() < 3 {
-        return Err("Usage: cmx tell <agent> <text...>".into());
+    let dedup = args.get(1) == Some(&"--dedup");
+    let rest = if dedup { &args[2..] } else { &args[1..] };
+    if rest.len() < 2 {
+        return Err("Usage: cmx tell [--dedup] <agent> <text...>".into());
     }
     Ok(Command::Tell {
-        agent: args[1].into(),
-        text: args[2..].join(" "),
+        agent: rest[0].into(),
+        text: rest[1..].join(" "),
+        dedup,
     })
 }
 
@@ -601,42 +806,84 @@ fn parse_rig_init(args: &[&str]) -> Result<Command, String> {
     Ok(Command::RigInit { host, name })
 }
 
-/// `cmx rig push <folder> [--remote <name>]`
+/// `cmx rig push <folder> [--remote <name> | --label <label> | --all] [--bwlimit <kbps>] [--exclude <pat>]...`
 fn parse_rig_push(args: &[&str]) -> Result<Command, String> {
+    const USAGE: &str = "Usage: cmx rig push <folder> [--remote <name> | --label <label> | --all] [--bwlimit <kbps>] [--exclude <pat>]...";
     if args.len() < 3 {
-        return Err("Usage: cmx rig push <folder> [--remote <name>]".into());
+        return Err(USAGE.into());
     }
     let folder = args[2].to_string();
     let mut remote = None;
+    let mut label = None;
+    let mut all = false;
+    let mut bwlimit = None;
+    let mut exclude = Vec::new();
     let rest = &args[3..];
     let mut i = 0;
     while i < rest.len() {
         if rest[i] == "--remote" {
             i += 1;
             remote = Some(take_arg(rest, i, "--remote")?);
+        } else if rest[i] == "--label" {
+            i += 1;
+            label = Some(take_arg(rest, i, "--label")?);
+        } else if rest[i] == "--all" {
+            all = true;
+        } else if rest[i] == "--bwlimit" {
+            i += 1;
+            bwlimit = Some(parse_bwlimit(&take_arg(rest, i, "--bwlimit")?)?);
+        } else if rest[i] == "--exclude" {
+            i += 1;
+            exclude.push(take_arg(rest, i, "--exclude")?);
         }
         i += 1;
     }
-    Ok(Command::RigPush { folder, remote })
+    let targets = remote.is_some() as u8 + label.is_some() as u8 + all as u8;
+    if targets > 1 {
+        return Err(USAGE.into());
+    }
+    Ok(Command::RigPush { folder, remote, label, all, bwlimit, exclude })
+}
+
+/// Parse a `--bwlimit` value in KiB/s.
+fn parse_bwlimit(val: &str) -> Result<u32, String> {
+    val.parse::<u32>()
+        .map_err(|_| format!("Invalid --bwlimit value: '{}'", val))
 }
 
-/// `cmx rig pull <folder> [--remote <name>]`
+/// `cmx rig pull <folder> [--remote <name> | --label <label>] [--bwlimit <kbps>] [--exclude <pat>]...`
 fn parse_rig_pull(args: &[&str]) -> Result<Command, String> {
+    const USAGE: &str = "Usage: cmx rig pull <folder> [--remote <name> | --label <label>] [--bwlimit <kbps>] [--exclude <pat>]...";
     if args.len() < 3 {
-        return Err("Usage: cmx rig pull <folder> [--remote <name>]".into());
+        return Err(USAGE.into());
     }
     let folder = args[2].to_string();
     let mut remote = None;
+    let mut label = None;
+    let mut bwlimit = None;
+    let mut exclude = Vec::new();
     let rest = &args[3..];
     let mut i = 0;
     while i < rest.len() {
         if rest[i] == "--remote" {
             i += 1;
             remote = Some(take_arg(rest, i, "--remote")?);
+        } else if rest[i] == "--label" {
+            i += 1;
+            label = Some(take_arg(rest, i, "--label")?);
+        } else if rest[i] == "--bwlimit" {
+            i += 1;
+            bwlimit = Some(parse_bwlimit(&take_arg(rest, i, "--bwlimit")?)?);
+        } else if rest[i] == "--exclude" {
+            i += 1;
+            exclude.push(take_arg(rest, i, "--exclude")?);
         }
         i += 1;
     }
-    Ok(Command::RigPull { folder, remote })
+    if remote.is_some() && label.is_some() {
+        return Err(USAGE.into());
+    }
+    Ok(Command::RigPull { folder, remote, label, bwlimit, exclude })
 }
 
 /// `cmx rig status [--remote <name>]`
@@ -684,24 +931,57 @@ fn parse_rig_stop(args: &[&str]) -> Result<Command, String> {
     Ok(Command::RigStop { remote })
 }
 
+/// `cmx diagnosis report [--since <t>] [--until <t>] [--out <file>]`
+fn parse_diagnosis_report(args: &[&str]) -> Result<Command, String> {
+    let mut since = None;
+    let mut until = None;
+    let mut dest = None;
+    let rest = &args[2..];
+    let mut i = 0;
+    while i < rest.len() {
+        match rest[i] {
+            "--since" => {
+                i += 1;
+                since = Some(take_arg(rest, i, "--since")?);
+            }
+            "--until" => {
+                i += 1;
+                until = Some(take_arg(rest, i, "--until")?);
+            }
+            "--out" => {
+                i += 1;
+                dest = Some(take_arg(rest, i, "--out")?);
+            }
+            other => return Err(format!("Unknown flag for diagnosis report: '{}'", other)),
+        }
+        i += 1;
+    }
+    Ok(Command::DiagnosisReport { since, until, dest })
+}
+
 /// `cmx diagnosis <subcommand>`
 fn parse_diagnosis(args: &[&str]) -> Result<Command, String> {
     if args.len() < 2 {
-        return Err("Usage: cmx diagnosis <report|reliability|effectiveness|thresholds|events>".into());
+        return Err("Usage: cmx diagnosis <report|reliability|effectiveness|thresholds|events|compare|annotate>".into());
     }
     match args[1] {
-        "report" => Ok(Command::DiagnosisReport),
+        "report" => parse_diagnosis_report(args),
         "reliability" => parse_diagnosis_reliability(args),
         "effectiveness" => parse_diagnosis_effectiveness(args),
         "thresholds" => parse_diagnosis_thresholds(args),
         "events" => parse_diagnosis_events(args),
+        "compare" => parse_diagnosis_compare(args),
+        "annotate" => parse_diagnosis_annotate(args),
         _ => Err(format!("Unknown diagnosis subcommand: '{}'", args[1])),
     }
 }
 
-/// `cmx diagnosis reliability [--signal <name>] [--json]`
+/// `cmx diagnosis reliability [--signal <name>] [--agent <name>]
+/// [--since <time> | --window-hours <n>] [--json]`
 fn parse_diagnosis_reliability(args: &[&str]) -> Result<Command, String> {
     let mut signal = None;
+    let mut agent = None;
+    let mut since = None;
     let mut format = None;
     let rest = &args[2..];
     let mut i = 0;
@@ -711,6 +991,22 @@ fn parse_diagnosis_reliability(args: &[&str]) -> Result<Command, String> {
                 i += 1;
                 signal = Some(take_arg(rest, i, "--signal")?);
             }
+            "--agent" => {
+                i += 1;
+                agent = Some(take_arg(rest, i, "--agent")?);
+            }
+            "--since" => {
+                i += 1;
+                since = Some(take_arg(rest, i, "--since")?);
+            }
+            "--window-hours" => {
+                i += 1;
+                let hours = take_arg(rest, i, "--window-hours")?;
+                hours
+                    .parse::<u64>()
+                    .map_err(|_| format!("Invalid --window-hours value: '{}'", hours))?;
+                since = Some(format!("{}h", hours));
+            }
             "--json" => {
                 format = Some("json".into());
             }
@@ -718,12 +1014,13 @@ fn parse_diagnosis_reliability(args: &[&str]) -> Result<Command, String> {
         }
         i += 1;
     }
-    Ok(Command::DiagnosisReliability { signal, format })
+    Ok(Command::DiagnosisReliability { signal, agent, since, format })
 }
 
-/// `cmx diagnosis effectiveness [--signal <name>] [--json]`
+/// `cmx diagnosis effectiveness [--signal <name>] [--min-attempts <n>] [--json]`
 fn parse_diagnosis_effectiveness(args: &[&str]) -> Result<Command, String> {
     let mut signal = None;
+    let mut min_attempts = None;
     let mut format = None;
     let rest = &args[2..];
     let mut i = 0;
@@ -733,6 +1030,10 @@ fn parse_diagnosis_effectiveness(args: &[&str]) -> Result<Command, String> {
                 i += 1;
                 signal = Some(take_arg(rest, i, "--signal")?);
             }
+            "--min-attempts" => {
+                i += 1;
+                min_attempts = Some(take_arg(rest, i, "--min-attempts")?);
+            }
             "--json" => {
                 format = Some("json".into());
             }
@@ -740,7 +1041,7 @@ fn parse_diagnosis_effectiveness(args: &[&str]) -> Result<Command, String> {
         }
         i += 1;
     }
-    Ok(Command::DiagnosisEffectiveness { signal, format })
+    Ok(Command::DiagnosisEffectiveness { signal, min_attempts, format })
 }
 
 /// `cmx diagnosis thresholds [--json]`
@@ -768,6 +1069,9 @@ fn parse_diagnosis_events(args: &[&str]) -> Result<Command, String> {
             "--json" => {
                 format = Some("json".into());
             }
+            "--csv" => {
+                format = Some("csv".into());
+            }
             other => return Err(format!("Unknown flag for diagnosis events: '{}'", other)),
         }
         i += 1;
@@ -775,22 +1079,84 @@ fn parse_diagnosis_events(args: &[&str]) -> Result<Command, String> {
     Ok(Command::DiagnosisEvents { limit, format })
 }
 
+/// `cmx diagnosis compare <agent-a> <agent-b> [--json]`
+fn parse_diagnosis_compare(args: &[&str]) -> Result<Command, String> {
+    let positional: Vec<&str> = args[2..].iter().copied().filter(|a| *a != "--json").collect();
+    if positional.len() != 2 {
+        return Err("Usage: cmx diagnosis compare <agent-a> <agent-b> [--json]".into());
+    }
+    let format = if args.contains(&"--json") {
+        Some("json".into())
+    } else {
+        None
+    };
+    Ok(Command::DiagnosisCompare {
+        a: positional[0].to_string(),
+        b: positional[1].to_string(),
+        format,
+    })
+}
+
+/// `cmx diagnosis annotate <event-id> <note...>`
+fn parse_diagnosis_annotate(args: &[&str]) -> Result<Command, String> {
+    if args.len() < 4 {
+        return Err("Usage: cmx diagnosis annotate <event-id> <note>".into());
+    }
+    Ok(Command::DiagnosisAnnotate {
+        id: args[2].to_string(),
+        note: args[3..].join(" "),
+    })
+}
+
 /// `cmx history <subcommand>`
 fn parse_history(args: &[&str]) -> Result<Command, String> {
     if args.len() < 2 {
-        return Err("Usage: cmx history <list|show|diff|restore|snapshot|prune>".into());
+        return Err("Usage: cmx history <list|show|diff|restore|snapshot|prune|pin|unpin|search>".into());
     }
     match args[1] {
         "list" => parse_history_list(args),
         "show" => parse_history_show(args),
         "diff" => parse_history_diff(args),
         "restore" => parse_history_restore(args),
-        "snapshot" => Ok(Command::HistorySnapshot),
-        "prune" => Ok(Command::HistoryPrune),
+        "snapshot" => parse_history_snapshot(args),
+        "prune" => parse_history_prune(args),
+        "pin" => parse_history_pin(args),
+        "unpin" => parse_history_unpin(args),
+        "search" => parse_history_search(args),
         _ => Err(format!("Unknown history subcommand: '{}'", args[1])),
     }
 }
 
+/// `cmx history snapshot [--label <name>]`
+fn parse_history_snapshot(args: &[&str]) -> Result<Command, String> {
+    let mut label = None;
+    let rest = &args[2..];
+    let mut i = 0;
+    while i < rest.len() {
+        match rest[i] {
+            "--label" => {
+                i += 1;
+                label = Some(take_arg(rest, i, "--label")?);
+            }
+            other => return Err(format!("Unknown flag for history snapshot: '{}'", other)),
+        }
+        i += 1;
+    }
+    Ok(Command::HistorySnapshot { label })
+}
+
+/// `cmx history prune [--dry-run]`
+fn parse_history_prune(args: &[&str]) -> Result<Command, String> {
+    let mut dry_run = false;
+    for arg in &args[2..] {
+        match *arg {
+            "--dry-run" => dry_run = true,
+            other => return Err(format!("Unknown flag for history prune: '{}'", other)),
+        }
+    }
+    Ok(Command::HistoryPrune { dry_run })
+}
+
 /// `cmx history list [--limit <n>] [--json]`
 fn parse_history_list(args: &[&str]) -> Result<Command, String> {
     let mut limit = None;
@@ -823,24 +1189,78 @@ fn parse_history_show(args: &[&str]) -> Result<Command, String> {
 
 /// `cmx history diff <entry> [<entry2>]`
 fn parse_history_diff(args: &[&str]) -> Result<Command, String> {
-    if args.len() < 3 {
-        return Err("Usage: cmx history diff <entry> [<entry2>]".into());
+    let usage = "Usage: cmx history diff <entry> [<entry2>] [--unified]";
+    let mut unified = false;
+    let mut entries = Vec::new();
+    for arg in &args[2..] {
+        match *arg {
+            "--unified" => unified = true,
+            other if other.starts_with("--") => {
+                return Err(format!("Unknown flag for history diff: '{}'", other));
+            }
+            other => entries.push(other.to_string()),
+        }
     }
-    let from = args[2].to_string();
-    let to = if args.len() > 3 {
-        Some(args[3].into())
-    } else {
-        None
-    };
-    Ok(Command::HistoryDiff { from, to })
+    let mut entries = entries.into_iter();
+    let from = entries.next().ok_or_else(|| usage.to_string())?;
+    let to = entries.next();
+    if entries.next().is_some() {
+        return Err(usage.into());
+    }
+    Ok(Command::HistoryDiff { from, to, unified })
 }
 
-/// `cmx history restore <entry>`
+/// `cmx history restore <entry> [--dry-run]`
 fn parse_history_restore(args: &[&str]) -> Result<Command, String> {
+    let mut id = None;
+    let mut dry_run = false;
+    for arg in &args[2..] {
+        match *arg {
+            "--dry-run" => dry_run = true,
+            other if other.starts_with("--") => {
+                return Err(format!("Unknown flag for history restore: '{}'", other));
+            }
+            other if id.is_none() => id = Some(other.to_string()),
+            _ => return Err("Usage: cmx history restore <entry> [--dry-run]".into()),
+        }
+    }
+    let id = id.ok_or("Usage: cmx history restore <entry> [--dry-run]")?;
+    Ok(Command::HistoryRestore { id, dry_run })
+}
+
+/// `cmx history search <query...> [-i|--ignore-case]`
+fn parse_history_search(args: &[&str]) -> Result<Command, String> {
+    let mut ignore_case = false;
+    let mut words = Vec::new();
+    for arg in &args[2..] {
+        match *arg {
+            "-i" | "--ignore-case" => ignore_case = true,
+            flag if flag.starts_with('-') && flag.len() > 1 => {
+                return Err(format!("Unknown flag for history search: {}", flag));
+            }
+            other => words.push(other),
+        }
+    }
+    if words.is_empty() {
+        return Err("Usage: cmx history search <query> [--ignore-case]".into());
+    }
+    Ok(Command::HistorySearch { query: words.join(" "), ignore_case })
+}
+
+/// `cmx history pin <entry>`
+fn parse_history_pin(args: &[&str]) -> Result<Command, String> {
     if args.len() < 3 {
-        return Err("Usage: cmx history restore <entry>".into());
+        return Err("Usage: cmx history pin <entry>".into());
     }
-    Ok(Command::HistoryRestore { id: args[2].into() })
+    Ok(Command::HistoryPin { id: args[2].into() })
+}
+
+/// `cmx history unpin <entry>`
+fn parse_history_unpin(args: &[&str]) -> Result<Command, String> {
+    if args.len() < 3 {
+        return Err("Usage: cmx history unpin <entry>".into());
+    }
+    Ok(Command::HistoryUnpin { id: args[2].into() })
 }
 
 /// `cmx watch [--since <ms>] [--timeout <ms>]`
@@ -893,6 +1313,17 @@ fn parse_learnings(args: &[&str]) -> Result<Command, String> {
     }
 }
 
+/// `cmx skill <subcommand>`
+fn parse_skill(args: &[&str]) -> Result<Command, String> {
+    if args.len() < 2 {
+        return Err("Usage: cmx skill <validate>".into());
+    }
+    match args[1] {
+        "validate" => Ok(Command::SkillValidate),
+        _ => Err(format!("Unknown skill subcommand: '{}'", args[1])),
+    }
+}
+
 /// `cmx learnings list [--project <name>] [--tag <tag>]`
 fn parse_learnings_list(args: &[&str]) -> Result<Command, String> {
     let mut project = None;
@@ -1032,7 +1463,32 @@ mod tests {
     #[test]
     fn agent_kill() {
         let cmd = parse_args(&["agent", "kill", "w1"]).unwrap();
-        assert_eq!(cmd, Command::AgentKill { name: "w1".into() });
+        assert_eq!(cmd, Command::AgentKill { name: "w1".into(), graceful: false });
+    }
+
+    #[test]
+    fn agent_kill_graceful() {
+        let cmd = parse_args(&["agent", "kill", "w1", "--graceful"]).unwrap();
+        assert_eq!(cmd, Command::AgentKill { name: "w1".into(), graceful: true });
+        assert!(parse_args(&["agent", "kill", "w1", "--soft"]).is_err());
+    }
+
+    #[test]
+    fn agent_kill_by_predicate() {
+        let cmd = parse_args(&["agent", "kill", "--role", "worker", "--status", "dead"]).unwrap();
+        assert_eq!(cmd, Command::AgentKillWhere {
+            role: Some("worker".into()),
+            status: Some("dead".into()),
+            label: None,
+        });
+        let cmd = parse_args(&["agent", "kill", "--label", "ci"]).unwrap();
+        assert_eq!(cmd, Command::AgentKillWhere {
+            role: None,
+            status: None,
+            label: Some("ci".into()),
+        });
+        assert!(parse_args(&["agent", "kill", "--role"]).is_err());
+        assert!(parse_args(&["agent", "kill", "--name", "w1"]).is_err());
     }
 
     #[test]
@@ -1068,19 +1524,86 @@ mod tests {
     #[test]
     fn agent_list_plain() {
         let cmd = parse_args(&["agent", "list"]).unwrap();
-        assert_eq!(cmd, Command::AgentList { format: None });
+        assert_eq!(cmd, Command::AgentList { format: None, sort_by: None, columns: None });
     }
 
     #[test]
     fn agent_list_json() {
         let cmd = parse_args(&["agent", "list", "--json"]).unwrap();
-        assert_eq!(cmd, Command::AgentList { format: Some("json".into()) });
+        assert_eq!(cmd, Command::AgentList {
+            format: Some("json".into()),
+            sort_by: None,
+            columns: None,
+        });
+    }
+
+    #[test]
+    fn agent_list_sort_and_columns() {
+        let cmd = parse_args(&["agent", "list", "--sort", "health", "--columns", "name,health"])
+            .unwrap();
+        assert_eq!(cmd, Command::AgentList {
+            format: None,
+            sort_by: Some("health".into()),
+            columns: Some("name,health".into()),
+        });
+    }
+
+    #[test]
+    fn agent_list_sort_missing_value() {
+        assert!(parse_args(&["agent", "list", "--sort"]).is_err());
+    }
+
+    #[test]
+    fn agent_state_set() {
+        let cmd = parse_args(&["agent", "state", "set", "w1", "attempts", "3"]).unwrap();
+        assert_eq!(cmd, Command::AgentStateSet {
+            agent: "w1".into(),
+            key: "attempts".into(),
+            value: "3".into(),
+        });
+    }
+
+    #[test]
+    fn agent_state_get() {
+        let cmd = parse_args(&["agent", "state", "get", "w1", "attempts"]).unwrap();
+        assert_eq!(cmd, Command::AgentStateGet {
+            agent: "w1".into(),
+            key: "attempts".into(),
+        });
+    }
+
+    #[test]
+    fn agent_state_missing_value() {
+        assert!(parse_args(&["agent", "state", "set", "w1", "attempts"]).is_err());
+    }
+
+    #[test]
+    fn agent_recheck() {
+        let cmd = parse_args(&["agent", "recheck", "w1"]).unwrap();
+        assert_eq!(cmd, Command::AgentRecheck { name: "w1".into() });
+        assert!(parse_args(&["agent", "recheck"]).is_err());
+    }
+
+    #[test]
+    fn agent_exec_joins_command_words() {
+        let cmd = parse_args(&["agent", "exec", "w1", "git", "status", "-s"]).unwrap();
+        assert_eq!(cmd, Command::AgentExec {
+            agent: "w1".into(),
+            cmd: "git status -s".into(),
+        });
+        assert!(parse_args(&["agent", "exec", "w1"]).is_err());
     }
 
     #[test]
     fn task_list_plain() {
         let cmd = parse_args(&["task", "list"]).unwrap();
-        assert_eq!(cmd, Command::TaskList { format: None, project: None });
+        assert_eq!(cmd, Command::TaskList {
+            format: None,
+            project: None,
+            max_depth: None,
+            status: None,
+            agent: None,
+        });
     }
 
     #[test]
@@ -1089,6 +1612,9 @@ mod tests {
         assert_eq!(cmd, Command::TaskList {
             format: None,
             project: Some("CMX".into()),
+            max_depth: None,
+            status: None,
+            agent: None,
         });
     }
 
@@ -1098,7 +1624,37 @@ mod tests {
         assert_eq!(cmd, Command::TaskList {
             format: Some("json".into()),
             project: None,
+            max_depth: None,
+            status: None,
+            agent: None,
+        });
+    }
+
+    #[test]
+    fn task_list_depth_and_status() {
+        let cmd = parse_args(&["task", "list", "CMX", "--depth", "1", "--status", "failed"]).unwrap();
+        assert_eq!(cmd, Command::TaskList {
+            format: None,
+            project: Some("CMX".into()),
+            max_depth: Some(1),
+            status: Some("failed".into()),
+            agent: None,
+        });
+        assert!(parse_args(&["task", "list", "--depth", "deep"]).is_err());
+        assert!(parse_args(&["task", "list", "--status"]).is_err());
+    }
+
+    #[test]
+    fn task_list_agent() {
+        let cmd = parse_args(&["task", "list", "--status", "in_progress", "--agent", "w1"]).unwrap();
+        assert_eq!(cmd, Command::TaskList {
+            format: None,
+            project: None,
+            max_depth: None,
+            status: Some("in_progress".into()),
+            agent: Some("w1".into()),
         });
+        assert!(parse_args(&["task", "list", "--agent"]).is_err());
     }
 
     #[test]
@@ -1116,9 +1672,37 @@ mod tests {
             title: Some("Done".into()),
             result: None,
             agent: None,
+            due: None,
+            cascade: false,
         });
     }
 
+    #[test]
+    fn task_set_due() {
+        let cmd = parse_args(&["task", "set", "CMX1", "due=2h"]).unwrap();
+        match cmd {
+            Command::TaskSet { due, status, .. } => {
+                assert_eq!(due.as_deref(), Some("2h"));
+                assert!(status.is_none());
+            }
+            other => panic!("expected TaskSet, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn task_set_and_check_cascade() {
+        match parse_args(&["task", "set", "M1", "status=failed", "--cascade"]).unwrap() {
+            Command::TaskSet { status, cascade, .. } => {
+                assert_eq!(status.as_deref(), Some("failed"));
+                assert!(cascade);
+            }
+            other => panic!("expected TaskSet, got {:?}", other),
+        }
+        let cmd = parse_args(&["task", "check", "M1", "--cascade"]).unwrap();
+        assert_eq!(cmd, Command::TaskCheck { id: "M1".into(), cascade: true });
+        assert!(parse_args(&["task", "check", "M1", "--bogus"]).is_err());
+    }
+
     #[test]
     fn task_set_bad_kv() {
         assert!(parse_args(&["task", "set", "CMX1", "noequalssign"]).is_err());
@@ -1132,7 +1716,7 @@ mod tests {
     #[test]
     fn task_check() {
         let cmd = parse_args(&["task", "check", "T1"]).unwrap();
-        assert_eq!(cmd, Command::TaskCheck { id: "T1".into() });
+        assert_eq!(cmd, Command::TaskCheck { id: "T1".into(), cascade: false });
     }
 
     #[test]
@@ -1141,15 +1725,47 @@ mod tests {
         assert_eq!(cmd, Command::TaskUncheck { id: "T1".into() });
     }
 
+    #[test]
+    fn task_note() {
+        let cmd = parse_args(&["task", "note", "T1", "waiting", "on", "review"]).unwrap();
+        assert_eq!(cmd, Command::TaskNote {
+            id: "T1".into(),
+            text: "waiting on review".into(),
+        });
+        assert!(parse_args(&["task", "note", "T1"]).is_err());
+    }
+
+    #[test]
+    fn task_move() {
+        let cmd = parse_args(&["task", "move", "T1.2", "T3"]).unwrap();
+        assert_eq!(cmd, Command::TaskMove {
+            id: "T1.2".into(),
+            parent: "T3".into(),
+        });
+        assert!(parse_args(&["task", "move", "T1.2"]).is_err());
+    }
+
     #[test]
     fn tell() {
         let cmd = parse_args(&["tell", "w1", "start", "task", "CMX1"]).unwrap();
         assert_eq!(cmd, Command::Tell {
             agent: "w1".into(),
             text: "start task CMX1".into(),
+            dedup: false,
         });
     }
 
+    #[test]
+    fn tell_dedup() {
+        let cmd = parse_args(&["tell", "--dedup", "w1", "start", "--dedup"]).unwrap();
+        assert_eq!(cmd, Command::Tell {
+            agent: "w1".into(),
+            text: "start --dedup".into(),
+            dedup: true,
+        });
+        assert!(parse_args(&["tell", "--dedup", "w1"]).is_err());
+    }
+
     #[test]
     fn interrupt_no_text() {
         let cmd = parse_args(&["interrupt", "w1"]).unwrap();
@@ -1183,6 +1799,15 @@ mod tests {
         });
     }
 
+    #[test]
+    fn config_unset() {
+        let cmd = parse_args(&["config", "unset", "max_retries"]).unwrap();
+        assert_eq!(cmd, Command::ConfigUnset {
+            key: "max_retries".into(),
+        });
+        assert!(parse_args(&["config", "unset"]).is_err());
+    }
+
     #[test]
     fn config_load_with_path() {
         let cmd = parse_args(&["config", "load", "/etc/cmx.yaml"]).unwrap();
@@ -1258,6 +1883,16 @@ mod tests {
         assert_eq!(cmd, Command::PoolStatus { role: "worker".into() });
     }
 
+    #[test]
+    fn help_search_joins_keywords() {
+        let cmd = parse_args(&["help", "--search", "take", "snapshot"]).unwrap();
+        assert_eq!(cmd, Command::Help {
+            topic: None,
+            search: Some("take snapshot".into()),
+        });
+        assert!(parse_args(&["help", "--search"]).is_err());
+    }
+
     #[test]
     fn pool_set_minimal() {
         let cmd = parse_args(&["pool", "set", "worker", "3"]).unwrap();
@@ -1265,6 +1900,7 @@ mod tests {
             role: "worker".into(),
             size: 3,
             path: None,
+            min_idle: None,
         });
     }
 
@@ -1275,7 +1911,20 @@ mod tests {
             role: "worker".into(),
             size: 3,
             path: Some("/tmp/work".into()),
+            min_idle: None,
+        });
+    }
+
+    #[test]
+    fn pool_set_with_min_idle() {
+        let cmd = parse_args(&["pool", "set", "worker", "3", "--min-idle", "1"]).unwrap();
+        assert_eq!(cmd, Command::PoolSet {
+            role: "worker".into(),
+            size: 3,
+            path: None,
+            min_idle: Some(1),
         });
+        assert!(parse_args(&["pool", "set", "worker", "3", "--min-idle", "x"]).is_err());
     }
 
     #[test]
@@ -1420,6 +2069,10 @@ mod tests {
         assert_eq!(cmd, Command::RigPush {
             folder: "/local/folder".into(),
             remote: None,
+            label: None,
+            all: false,
+            bwlimit: None,
+            exclude: Vec::new(),
         });
     }
 
@@ -1429,7 +2082,82 @@ mod tests {
         assert_eq!(cmd, Command::RigPush {
             folder: "/local/folder".into(),
             remote: Some("gpu1".into()),
+            label: None,
+            all: false,
+            bwlimit: None,
+            exclude: Vec::new(),
+        });
+    }
+
+    #[test]
+    fn rig_push_with_label() {
+        let cmd = parse_args(&["rig", "push", "/local/folder", "--label", "gpu"]).unwrap();
+        assert_eq!(cmd, Command::RigPush {
+            folder: "/local/folder".into(),
+            remote: None,
+            label: Some("gpu".into()),
+            all: false,
+            bwlimit: None,
+            exclude: Vec::new(),
+        });
+    }
+
+    #[test]
+    fn rig_push_all() {
+        let cmd = parse_args(&["rig", "push", "/local/folder", "--all"]).unwrap();
+        assert_eq!(cmd, Command::RigPush {
+            folder: "/local/folder".into(),
+            remote: None,
+            label: None,
+            all: true,
+            bwlimit: None,
+            exclude: Vec::new(),
+        });
+        assert!(parse_args(&["rig", "push", "/local/folder", "--all", "--label", "gpu"]).is_err());
+    }
+
+    #[test]
+    fn rig_pull_with_label() {
+        let cmd = parse_args(&["rig", "pull", "/local/folder", "--label", "gpu"]).unwrap();
+        assert_eq!(cmd, Command::RigPull {
+            folder: "/local/folder".into(),
+            remote: None,
+            label: Some("gpu".into()),
+            bwlimit: None,
+            exclude: Vec::new(),
+        });
+    }
+
+    #[test]
+    fn rig_push_and_pull_sync_options() {
+        let cmd = parse_args(&[
+            "rig", "push", "/f", "--bwlimit", "800", "--exclude", "data/", "--exclude", "*.ckpt",
+        ])
+        .unwrap();
+        assert_eq!(cmd, Command::RigPush {
+            folder: "/f".into(),
+            remote: None,
+            label: None,
+            all: false,
+            bwlimit: Some(800),
+            exclude: vec!["data/".into(), "*.ckpt".into()],
+        });
+
+        let cmd = parse_args(&["rig", "pull", "/f", "--remote", "g1", "--bwlimit", "64"]).unwrap();
+        assert_eq!(cmd, Command::RigPull {
+            folder: "/f".into(),
+            remote: Some("g1".into()),
+            label: None,
+            bwlimit: Some(64),
+            exclude: Vec::new(),
         });
+
+        assert!(parse_args(&["rig", "push", "/f", "--bwlimit", "fast"]).is_err());
+    }
+
+    #[test]
+    fn rig_push_remote_and_label_conflict() {
+        assert!(parse_args(&["rig", "push", "/f", "--remote", "g1", "--label", "gpu"]).is_err());
     }
 
     #[test]
@@ -1443,6 +2171,9 @@ mod tests {
         assert_eq!(cmd, Command::RigPull {
             folder: "/local/folder".into(),
             remote: None,
+            label: None,
+            bwlimit: None,
+            exclude: Vec::new(),
         });
     }
 
@@ -1452,6 +2183,9 @@ mod tests {
         assert_eq!(cmd, Command::RigPull {
             folder: "/local/folder".into(),
             remote: Some("gpu1".into()),
+            label: None,
+            bwlimit: None,
+            exclude: Vec::new(),
         });
     }
 
@@ -1511,6 +2245,38 @@ mod tests {
 
     // --- diagnosis CLI tests ---
 
+    #[test]
+    fn diagnosis_compare_two_agents() {
+        let cmd = parse_args(&["diagnosis", "compare", "w1", "w2"]).unwrap();
+        assert_eq!(cmd, Command::DiagnosisCompare {
+            a: "w1".into(),
+            b: "w2".into(),
+            format: None,
+        });
+        let cmd = parse_args(&["diagnosis", "compare", "w1", "w2", "--json"]).unwrap();
+        assert_eq!(cmd, Command::DiagnosisCompare {
+            a: "w1".into(),
+            b: "w2".into(),
+            format: Some("json".into()),
+        });
+    }
+
+    #[test]
+    fn diagnosis_annotate_joins_note() {
+        let cmd = parse_args(&["diagnosis", "annotate", "12", "long", "compile"]).unwrap();
+        assert_eq!(cmd, Command::DiagnosisAnnotate {
+            id: "12".into(),
+            note: "long compile".into(),
+        });
+        assert!(parse_args(&["diagnosis", "annotate", "12"]).is_err());
+    }
+
+    #[test]
+    fn diagnosis_compare_requires_two_agents() {
+        assert!(parse_args(&["diagnosis", "compare", "w1"]).is_err());
+        assert!(parse_args(&["diagnosis", "compare", "w1", "w2", "w3"]).is_err());
+    }
+
     #[test]
     fn diagnosis_missing_subcommand() {
         assert!(parse_args(&["diagnosis"]).is_err());
@@ -1524,13 +2290,28 @@ mod tests {
     #[test]
     fn diagnosis_report() {
         let cmd = parse_args(&["diagnosis", "report"]).unwrap();
-        assert_eq!(cmd, Command::DiagnosisReport);
+        assert_eq!(cmd, Command::DiagnosisReport { since: None, until: None, dest: None });
+    }
+
+    #[test]
+    fn diagnosis_report_window_and_out() {
+        let cmd = parse_args(&[
+            "diagnosis", "report", "--since", "24h", "--until", "1700000000000", "--out", "diag.md",
+        ])
+        .unwrap();
+        assert_eq!(cmd, Command::DiagnosisReport {
+            since: Some("24h".into()),
+            until: Some("1700000000000".into()),
+            dest: Some("diag.md".into()),
+        });
+        assert!(parse_args(&["diagnosis", "report", "--since"]).is_err());
+        assert!(parse_args(&["diagnosis", "report", "--json"]).is_err());
     }
 
     #[test]
     fn diagnosis_reliability_no_args() {
         let cmd = parse_args(&["diagnosis", "reliability"]).unwrap();
-        assert_eq!(cmd, Command::DiagnosisReliability { signal: None, format: None });
+        assert_eq!(cmd, Command::DiagnosisReliability { signal: None, agent: None, since: None, format: None });
     }
 
     #[test]
@@ -1538,6 +2319,8 @@ mod tests {
         let cmd = parse_args(&["diagnosis", "reliability", "--signal", "heartbeat_stale"]).unwrap();
         assert_eq!(cmd, Command::DiagnosisReliability {
             signal: Some("heartbeat_stale".into()),
+            agent: None,
+            since: None,
             format: None,
         });
     }
@@ -1547,15 +2330,44 @@ mod tests {
         let cmd = parse_args(&["diagnosis", "reliability", "--json"]).unwrap();
         assert_eq!(cmd, Command::DiagnosisReliability {
             signal: None,
+            agent: None,
+            since: None,
             format: Some("json".into()),
         });
     }
 
+    #[test]
+    fn diagnosis_reliability_agent() {
+        let cmd = parse_args(&["diagnosis", "reliability", "--agent", "w2"]).unwrap();
+        assert_eq!(cmd, Command::DiagnosisReliability {
+            signal: None,
+            agent: Some("w2".into()),
+            since: None,
+            format: None,
+        });
+    }
+
+    #[test]
+    fn diagnosis_reliability_window() {
+        let cmd = parse_args(&["diagnosis", "reliability", "--window-hours", "48"]).unwrap();
+        assert_eq!(cmd, Command::DiagnosisReliability {
+            signal: None,
+            agent: None,
+            since: Some("48h".into()),
+            format: None,
+        });
+        let cmd = parse_args(&["diagnosis", "reliability", "--since", "1700000000000"]).unwrap();
+        assert!(matches!(cmd, Command::DiagnosisReliability { since: Some(s), .. } if s == "1700000000000"));
+        assert!(parse_args(&["diagnosis", "reliability", "--window-hours", "x"]).is_err());
+    }
+
     #[test]
     fn diagnosis_reliability_all_flags() {
         let cmd = parse_args(&["diagnosis", "reliability", "--signal", "error_pattern", "--json"]).unwrap();
         assert_eq!(cmd, Command::DiagnosisReliability {
             signal: Some("error_pattern".into()),
+            agent: None,
+            since: None,
             format: Some("json".into()),
         });
     }
@@ -1563,7 +2375,11 @@ mod tests {
     #[test]
     fn diagnosis_effectiveness_no_args() {
         let cmd = parse_args(&["diagnosis", "effectiveness"]).unwrap();
-        assert_eq!(cmd, Command::DiagnosisEffectiveness { signal: None, format: None });
+        assert_eq!(cmd, Command::DiagnosisEffectiveness {
+            signal: None,
+            min_attempts: None,
+            format: None,
+        });
     }
 
     #[test]
@@ -1571,6 +2387,17 @@ mod tests {
         let cmd = parse_args(&["diagnosis", "effectiveness", "--signal", "error_pattern"]).unwrap();
         assert_eq!(cmd, Command::DiagnosisEffectiveness {
             signal: Some("error_pattern".into()),
+            min_attempts: None,
+            format: None,
+        });
+    }
+
+    #[test]
+    fn diagnosis_effectiveness_min_attempts() {
+        let cmd = parse_args(&["diagnosis", "effectiveness", "--min-attempts", "3"]).unwrap();
+        assert_eq!(cmd, Command::DiagnosisEffectiveness {
+            signal: None,
+            min_attempts: Some("3".into()),
             format: None,
         });
     }
@@ -1611,6 +2438,15 @@ mod tests {
         });
     }
 
+    #[test]
+    fn diagnosis_events_csv() {
+        let cmd = parse_args(&["diagnosis", "events", "--csv"]).unwrap();
+        assert_eq!(cmd, Command::DiagnosisEvents {
+            limit: None,
+            format: Some("csv".into()),
+        });
+    }
+
     #[test]
     fn diagnosis_events_all_flags() {
         let cmd = parse_args(&["diagnosis", "events", "--limit", "10", "--json"]).unwrap();
@@ -1685,7 +2521,19 @@ mod tests {
     #[test]
     fn history_diff_one_entry() {
         let cmd = parse_args(&["history", "diff", "0"]).unwrap();
-        assert_eq!(cmd, Command::HistoryDiff { from: "0".into(), to: None });
+        assert_eq!(cmd, Command::HistoryDiff { from: "0".into(), to: None, unified: false });
+    }
+
+    #[test]
+    fn history_diff_unified() {
+        let cmd = parse_args(&["history", "diff", "1", "--unified", "0"]).unwrap();
+        assert_eq!(cmd, Command::HistoryDiff {
+            from: "1".into(),
+            to: Some("0".into()),
+            unified: true,
+        });
+        assert!(parse_args(&["history", "diff", "--unified"]).is_err());
+        assert!(parse_args(&["history", "diff", "0", "1", "2"]).is_err());
     }
 
     #[test]
@@ -1694,6 +2542,7 @@ mod tests {
         assert_eq!(cmd, Command::HistoryDiff {
             from: "0".into(),
             to: Some("1".into()),
+            unified: false,
         });
     }
 
@@ -1705,7 +2554,14 @@ mod tests {
     #[test]
     fn history_restore() {
         let cmd = parse_args(&["history", "restore", "0"]).unwrap();
-        assert_eq!(cmd, Command::HistoryRestore { id: "0".into() });
+        assert_eq!(cmd, Command::HistoryRestore { id: "0".into(), dry_run: false });
+    }
+
+    #[test]
+    fn history_restore_dry_run() {
+        let cmd = parse_args(&["history", "restore", "--dry-run", "2"]).unwrap();
+        assert_eq!(cmd, Command::HistoryRestore { id: "2".into(), dry_run: true });
+        assert!(parse_args(&["history", "restore", "--dry-run"]).is_err());
     }
 
     #[test]
@@ -1716,13 +2572,50 @@ mod tests {
     #[test]
     fn history_snapshot() {
         let cmd = parse_args(&["history", "snapshot"]).unwrap();
-        assert_eq!(cmd, Command::HistorySnapshot);
+        assert_eq!(cmd, Command::HistorySnapshot { label: None });
+    }
+
+    #[test]
+    fn history_snapshot_with_label() {
+        let cmd = parse_args(&["history", "snapshot", "--label", "pre-upgrade"]).unwrap();
+        assert_eq!(cmd, Command::HistorySnapshot { label: Some("pre-upgrade".into()) });
+        assert!(parse_args(&["history", "snapshot", "--label"]).is_err());
+        assert!(parse_args(&["history", "snapshot", "--bogus"]).is_err());
     }
 
     #[test]
     fn history_prune() {
         let cmd = parse_args(&["history", "prune"]).unwrap();
-        assert_eq!(cmd, Command::HistoryPrune);
+        assert_eq!(cmd, Command::HistoryPrune { dry_run: false });
+        let cmd = parse_args(&["history", "prune", "--dry-run"]).unwrap();
+        assert_eq!(cmd, Command::HistoryPrune { dry_run: true });
+        assert!(parse_args(&["history", "prune", "--force"]).is_err());
+    }
+
+    #[test]
+    fn history_pin_and_unpin() {
+        let cmd = parse_args(&["history", "pin", "0"]).unwrap();
+        assert_eq!(cmd, Command::HistoryPin { id: "0".into() });
+        let cmd = parse_args(&["history", "unpin", "0"]).unwrap();
+        assert_eq!(cmd, Command::HistoryUnpin { id: "0".into() });
+        assert!(parse_args(&["history", "pin"]).is_err());
+    }
+
+    #[test]
+    fn skill_validate() {
+        assert_eq!(parse_args(&["skill", "validate"]).unwrap(), Command::SkillValidate);
+        assert!(parse_args(&["skill"]).is_err());
+        assert!(parse_args(&["skill", "lint"]).is_err());
+    }
+
+    #[test]
+    fn history_search() {
+        let cmd = parse_args(&["history", "search", "agent:", "w3", "-i"]).unwrap();
+        assert_eq!(cmd, Command::HistorySearch { query: "agent: w3".into(), ignore_case: true });
+        let cmd = parse_args(&["history", "search", "pilot"]).unwrap();
+        assert_eq!(cmd, Command::HistorySearch { query: "pilot".into(), ignore_case: false });
+        assert!(parse_args(&["history", "search", "--ignore-case"]).is_err());
+        assert!(parse_args(&["history", "search", "pilot", "--bogus"]).is_err());
     }
 
     // --- watch CLI tests ---
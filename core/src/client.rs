@@ -6,13 +6,17 @@
 //!
 //! The command send itself serves as the liveness check — there is no
 //! separate ping or health-check protocol.
+//!
+//! Each request carries a correlation id (see `frame`), and a request
+//! whose matching response does not arrive within `timeout_ms` fails with
+//! a timeout error.
 
-use std::io::{Read, Write};
 use std::os::unix::net::UnixStream;
 use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
 
 use crate::command::Command;
+use crate::frame::Correlator;
 use cmx_utils::response::Response;
 
 
@@ -84,7 +88,8 @@ pub fn execute_remote(
 }
 
 
-/// Send a command to the daemon socket with a read timeout.
+/// Send a command to the daemon socket and wait up to `timeout_ms` for
+/// the response carrying its correlation id.
 fn send_command(
     config_dir: &Path,
     cmd: &Command,
@@ -95,62 +100,7 @@ fn send_command(
     let stream = UnixStream::connect(&sock_path)
         .map_err(|e| format!("Cannot connect to {}: {}", sock_path.display(), e))?;
 
-    stream
-        .set_read_timeout(Some(Duration::from_millis(timeout_ms)))
-        .map_err(|e| format!("Cannot set timeout: {}", e))?;
-
-    // Write length-prefixed JSON command
-    let json = serde_json::to_vec(cmd)
-        .map_err(|e| format!("Failed to serialize command: {}", e))?;
-    write_frame(&stream, &json)?;
-
-    // Read length-prefixed JSON response (subject to timeout)
-    let payload = read_frame(&stream)?;
-
-    serde_json::from_slice(&payload)
-        .map_err(|e| format!("Failed to parse response: {}", e))
-}
-
-
-/// Write a length-prefixed frame to a stream.
-fn write_frame(stream: &UnixStream, payload: &[u8]) -> Result<(), String> {
-    let mut stream = stream;
-    let len = payload.len() as u32;
-    stream
-        .write_all(&len.to_be_bytes())
-        .map_err(|e| format!("Failed to write frame length: {}", e))?;
-    stream
-        .write_all(payload)
-        .map_err(|e| format!("Failed to write frame payload: {}", e))?;
-    stream
-        .flush()
-        .map_err(|e| format!("Failed to flush: {}", e))?;
-    Ok(())
-}
-
-
-/// Read a length-prefixed frame from a stream.
-fn read_frame(stream: &UnixStream) -> Result<Vec<u8>, String> {
-    let mut stream = stream;
-    let mut len_buf = [0u8; 4];
-    stream
-        .read_exact(&mut len_buf)
-        .map_err(|e| format!("Failed to read response length: {}", e))?;
-    let len = u32::from_be_bytes(len_buf) as usize;
-
-    if len == 0 {
-        return Err("Empty response frame".into());
-    }
-    if len > 16 * 1024 * 1024 {
-        return Err(format!("Response frame too large: {} bytes", len));
-    }
-
-    let mut payload = vec![0u8; len];
-    stream
-        .read_exact(&mut payload)
-        .map_err(|e| format!("Failed to read response payload: {}", e))?;
-
-    Ok(payload)
+    Correlator::new(stream).call(cmd, Duration::from_millis(timeout_ms))
 }
 
 
@@ -21,18 +21,19 @@
 //! | Group | Commands |
 //! |-------|----------|
 //! | Top-level | `status`, `view` |
-//! | Agent | `agent.new`, `agent.kill`, `agent.restart`, `agent.assign`, `agent.unassign`, `agent.status`, `agent.list` |
-//! | Task | `task.list`, `task.get`, `task.set`, `task.check`, `task.uncheck` |
-//! | Config | `config.load`, `config.save`, `config.add`, `config.list` |
+//! | Agent | `agent.new`, `agent.kill`, `agent.kill.where`, `agent.restart`, `agent.assign`, `agent.unassign`, `agent.status`, `agent.list`, `agent.recheck`, `agent.exec`, `agent.state.set`, `agent.state.get` |
+//! | Task | `task.list`, `task.get`, `task.set`, `task.check`, `task.uncheck`, `task.note`, `task.move` |
+//! | Config | `config.load`, `config.save`, `config.add`, `config.unset`, `config.list` |
 //! | Project | `project.add`, `project.remove`, `project.list`, `project.scan` |
 //! | Pool | `pool.list`, `pool.status`, `pool.set`, `pool.remove` |
 //! | Messaging | `tell`, `interrupt` |
 //! | Layout | `layout.row`, `layout.column`, `layout.merge`, `layout.place`, `layout.capture`, `layout.session` |
 //! | Client | `client.next`, `client.prev` |
 //! | Rig | `rig.init`, `rig.push`, `rig.pull`, `rig.status`, `rig.health`, `rig.stop`, `rig.list`, `rig.default` |
-//! | Diagnosis | `diagnosis.report`, `diagnosis.reliability`, `diagnosis.effectiveness`, `diagnosis.thresholds`, `diagnosis.events` |
-//! | History | `history.list`, `history.show`, `history.diff`, `history.restore`, `history.snapshot`, `history.prune` |
+//! | Diagnosis | `diagnosis.report`, `diagnosis.reliability`, `diagnosis.effectiveness`, `diagnosis.thresholds`, `diagnosis.events`, `diagnosis.compare`, `diagnosis.annotate` |
+//! | History | `history.list`, `history.show`, `history.diff`, `history.restore`, `history.snapshot`, `history.prune`, `history.pin`, `history.unpin`, `history.search` |
 //! | Learnings | `learnings.list`, `learnings.add`, `learnings.search` |
+//! | Skill | `skill.validate` |
 //! | Watch | `watch` |
 //! | Daemon | `daemon.run`, `daemon.stop` |
 
@@ -92,6 +93,25 @@ pub enum Command {
     AgentKill {
         /// Name of the agent to kill.
         name: String,
+        /// Send the configured shutdown keys before killing, so the agent
+        /// can save and exit. Default is an immediate hard kill.
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        graceful: bool,
+    },
+
+    /// Kill every agent matching all of the given predicates. At least one
+    /// predicate is required.
+    #[serde(rename = "agent.kill.where")]
+    AgentKillWhere {
+        /// Match agents with this role (case-insensitive).
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        role: Option<String>,
+        /// Match agents in this status: idle, busy, stalled, error, or dead.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        status: Option<String>,
+        /// Match agents whose `labels` state value is or contains this label.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        label: Option<String>,
     },
 
     /// Restart an agent (kill + re-create with same config).
@@ -127,12 +147,59 @@ pub enum Command {
         notes: Option<String>,
     },
 
-    /// List all agents. Supports optional JSON output.
+    /// List all agents. Supports optional JSON output, sorting, and
+    /// column selection.
     #[serde(rename = "agent.list")]
     AgentList {
         /// Output format: "json" for JSON, omit for tabular.
         #[serde(default, skip_serializing_if = "Option::is_none")]
         format: Option<String>,
+        /// Sort key: name, role, status, health, or age. Omit for registry order.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        sort_by: Option<String>,
+        /// Comma-separated column names for tabular output. Omit for all columns.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        columns: Option<String>,
+    },
+
+    /// Capture an agent's pane and reassess its health now instead of
+    /// waiting for the next monitor cycle. Needs the session backend, so
+    /// the daemon handles it rather than `Sys::execute`.
+    #[serde(rename = "agent.recheck")]
+    AgentRecheck {
+        /// Name of the agent.
+        name: String,
+    },
+
+    /// Type a one-off command into an agent's pane and return what it
+    /// printed. `Sys::execute` queues the SendKeys; the daemon waits for
+    /// the pane to settle and replies with the output delta.
+    #[serde(rename = "agent.exec")]
+    AgentExec {
+        /// Name of the agent.
+        agent: String,
+        /// Command line to type (Enter is appended).
+        cmd: String,
+    },
+
+    /// Store a per-agent scratch value under `agent.<agent>.<key>`.
+    #[serde(rename = "agent.state.set")]
+    AgentStateSet {
+        /// Name of the agent.
+        agent: String,
+        /// State key (dotted segments allowed, no wildcards).
+        key: String,
+        /// JSON value. Text that is not valid JSON is stored as a string.
+        value: String,
+    },
+
+    /// Read a per-agent scratch value stored with `agent.state.set`.
+    #[serde(rename = "agent.state.get")]
+    AgentStateGet {
+        /// Name of the agent.
+        agent: String,
+        /// State key.
+        key: String,
     },
 
     // -----------------------------------------------------------------
@@ -148,6 +215,15 @@ pub enum Command {
         /// Filter to tasks under this project.
         #[serde(default, skip_serializing_if = "Option::is_none")]
         project: Option<String>,
+        /// Omit tasks nested deeper than this (0 = roots only).
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max_depth: Option<usize>,
+        /// Show only tasks with this status, plus their ancestors.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        status: Option<String>,
+        /// Show only tasks assigned to this agent.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        agent: Option<String>,
     },
 
     /// Get detailed information about a single task.
@@ -157,7 +233,7 @@ pub enum Command {
         id: String,
     },
 
-    /// Update fields on a task (status, title, result, agent).
+    /// Update fields on a task (status, title, result, agent, due).
     #[serde(rename = "task.set")]
     TaskSet {
         /// Task ID.
@@ -174,6 +250,13 @@ pub enum Command {
         /// Agent name to assign, or "-" to clear.
         #[serde(default, skip_serializing_if = "Option::is_none")]
         agent: Option<String>,
+        /// Due date: epoch ms, a duration from now (e.g. "2h"), or "-" to clear.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        due: Option<String>,
+        /// Apply the new status to every subtask too (cancelled ones are
+        /// left alone). Requires `status`.
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        cascade: bool,
     },
 
     /// Mark a task as completed.
@@ -181,6 +264,9 @@ pub enum Command {
     TaskCheck {
         /// Task ID.
         id: String,
+        /// Also complete every subtask that is not cancelled.
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        cascade: bool,
     },
 
     /// Mark a task as pending (undo check).
@@ -190,6 +276,24 @@ pub enum Command {
         id: String,
     },
 
+    /// Append a timestamped freeform note to a task.
+    #[serde(rename = "task.note")]
+    TaskNote {
+        /// Task ID.
+        id: String,
+        /// Note text.
+        text: String,
+    },
+
+    /// Move a task, with its subtasks, under a different parent task.
+    #[serde(rename = "task.move")]
+    TaskMove {
+        /// Task ID to move.
+        id: String,
+        /// ID of the new parent task.
+        parent: String,
+    },
+
     // -----------------------------------------------------------------
     // Config commands
     // -----------------------------------------------------------------
@@ -219,6 +323,13 @@ pub enum Command {
         value: String,
     },
 
+    /// Reset a configuration key to its built-in default.
+    #[serde(rename = "config.unset")]
+    ConfigUnset {
+        /// Configuration key, as accepted by `config.add`.
+        key: String,
+    },
+
     /// List all current configuration values.
     #[serde(rename = "config.list")]
     ConfigList,
@@ -290,6 +401,9 @@ pub enum Command {
         /// Working directory for spawned workers. Defaults to project_root.
         #[serde(default, skip_serializing_if = "Option::is_none")]
         path: Option<String>,
+        /// Idle agents to keep in reserve. Keeps the current value if omitted.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        min_idle: Option<u32>,
     },
 
     /// Remove a worker pool configuration for a role.
@@ -310,6 +424,9 @@ pub enum Command {
         agent: String,
         /// Message text.
         text: String,
+        /// Skip the message if an identical one is already pending for the agent.
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        dedup: bool,
     },
 
     /// Interrupt an agent (sends Ctrl-C, optionally followed by text).
@@ -413,6 +530,20 @@ pub enum Command {
         /// Optional remote name.
         #[serde(default, skip_serializing_if = "Option::is_none")]
         remote: Option<String>,
+        /// Optional label; fans out to every remote carrying it.
+        /// Mutually exclusive with `remote`.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        label: Option<String>,
+        /// Push to every registered remote. Mutually exclusive with
+        /// `remote` and `label`.
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        all: bool,
+        /// Cap the transfer rate, in KiB/s.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        bwlimit: Option<u32>,
+        /// Extra rsync exclude patterns, on top of the defaults.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        exclude: Vec<String>,
     },
 
     /// Pull results from a remote via rsync.
@@ -423,6 +554,16 @@ pub enum Command {
         /// Optional remote name.
         #[serde(default, skip_serializing_if = "Option::is_none")]
         remote: Option<String>,
+        /// Optional label; fans out to every remote carrying it.
+        /// Mutually exclusive with `remote`.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        label: Option<String>,
+        /// Cap the transfer rate, in KiB/s.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        bwlimit: Option<u32>,
+        /// Extra rsync exclude patterns, on top of the defaults.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        exclude: Vec<String>,
     },
 
     /// Show status for a remote.
@@ -467,7 +608,18 @@ pub enum Command {
 
     /// Generate a self-diagnosis report (markdown).
     #[serde(rename = "diagnosis.report")]
-    DiagnosisReport,
+    DiagnosisReport {
+        /// Only include events at or after this time: epoch ms, or a
+        /// duration ago such as "24h". Omit for no lower bound.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        since: Option<String>,
+        /// Only include events before this time, same forms as `since`.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        until: Option<String>,
+        /// Write the markdown to this file instead of returning it.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        dest: Option<String>,
+    },
 
     /// Show signal reliability statistics.
     #[serde(rename = "diagnosis.reliability")]
@@ -475,6 +627,13 @@ pub enum Command {
         /// Optional signal type to filter (e.g. "heartbeat_stale"). Shows all if omitted.
         #[serde(default, skip_serializing_if = "Option::is_none")]
         signal: Option<String>,
+        /// Optional agent name: compute reliability from that agent's events only.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        agent: Option<String>,
+        /// Only count events at or after this time: epoch ms, or a duration
+        /// ago such as "24h". Omit to use all retained events.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        since: Option<String>,
         /// Output format: "json" for JSON, omit for tabular.
         #[serde(default, skip_serializing_if = "Option::is_none")]
         format: Option<String>,
@@ -486,6 +645,9 @@ pub enum Command {
         /// Optional signal type to filter.
         #[serde(default, skip_serializing_if = "Option::is_none")]
         signal: Option<String>,
+        /// Pairs with fewer attempts are flagged as low confidence. Default: 5.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        min_attempts: Option<String>,
         /// Output format: "json" for JSON, omit for tabular.
         #[serde(default, skip_serializing_if = "Option::is_none")]
         format: Option<String>,
@@ -505,11 +667,32 @@ pub enum Command {
         /// Number of recent events to show. Default: 20.
         #[serde(default, skip_serializing_if = "Option::is_none")]
         limit: Option<String>,
+        /// Output format: "json" for JSON, "csv" for CSV, omit for tabular.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        format: Option<String>,
+    },
+
+    /// Compare per-signal reliability between two agents side by side.
+    #[serde(rename = "diagnosis.compare")]
+    DiagnosisCompare {
+        /// First agent name.
+        a: String,
+        /// Second agent name.
+        b: String,
         /// Output format: "json" for JSON, omit for tabular.
         #[serde(default, skip_serializing_if = "Option::is_none")]
         format: Option<String>,
     },
 
+    /// Add a timestamped note to a recorded intervention event.
+    #[serde(rename = "diagnosis.annotate")]
+    DiagnosisAnnotate {
+        /// Event id, as listed by `diagnosis.events`.
+        id: String,
+        /// Note text.
+        note: String,
+    },
+
     // -----------------------------------------------------------------
     // History commands
     // -----------------------------------------------------------------
@@ -540,6 +723,9 @@ pub enum Command {
         /// To snapshot (filename or index). Default: current config.
         #[serde(default, skip_serializing_if = "Option::is_none")]
         to: Option<String>,
+        /// Emit a standard unified diff instead of the summary.
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        unified: bool,
     },
 
     /// Restore a history snapshot as the current configuration.
@@ -547,15 +733,49 @@ pub enum Command {
     HistoryRestore {
         /// Snapshot filename or index to restore.
         id: String,
+        /// Show what would change without writing anything.
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        dry_run: bool,
     },
 
-    /// Take a snapshot of the current configuration now.
+    /// Take a snapshot of the current configuration now, optionally named.
     #[serde(rename = "history.snapshot")]
-    HistorySnapshot,
+    HistorySnapshot {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        label: Option<String>,
+    },
 
     /// Prune old history snapshots per retention policy.
     #[serde(rename = "history.prune")]
-    HistoryPrune,
+    HistoryPrune {
+        /// Report what would be deleted without deleting anything.
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        dry_run: bool,
+    },
+
+    /// Pin a history snapshot so pruning never removes it.
+    #[serde(rename = "history.pin")]
+    HistoryPin {
+        /// Snapshot filename or index to pin.
+        id: String,
+    },
+
+    /// Unpin a history snapshot, returning it to normal retention.
+    #[serde(rename = "history.unpin")]
+    HistoryUnpin {
+        /// Snapshot filename or index to unpin.
+        id: String,
+    },
+
+    /// Find lines containing a string across all history snapshots.
+    #[serde(rename = "history.search")]
+    HistorySearch {
+        /// Text to look for.
+        query: String,
+        /// Match regardless of case.
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        ignore_case: bool,
+    },
 
     // -----------------------------------------------------------------
     // Watch commands
@@ -625,17 +845,30 @@ pub enum Command {
         query: String,
     },
 
+    // -----------------------------------------------------------------
+    // Skill commands
+    // -----------------------------------------------------------------
+
+    /// Parse every resolved skill and report the ones that fail.
+    #[serde(rename = "skill.validate")]
+    SkillValidate,
+
     // -----------------------------------------------------------------
     // Help
     // -----------------------------------------------------------------
 
     /// Show help text. With no topic, shows the command overview.
     /// With a topic, shows detailed help for that command or group.
+    /// With `search`, lists the commands whose help mentions the keywords.
     #[serde(rename = "help")]
     Help {
         /// Optional topic: a command name (e.g. "agent.new") or group (e.g. "agent").
         #[serde(default, skip_serializing_if = "Option::is_none")]
         topic: Option<String>,
+        /// Keywords to search for across all command help. Takes
+        /// precedence over `topic`.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        search: Option<String>,
     },
 }
 
@@ -721,11 +954,34 @@ mod tests {
         );
     }
 
+    #[test]
+    fn agent_kill_where_round_trip() {
+        let cmd = Command::AgentKillWhere {
+            role: Some("worker".into()),
+            status: None,
+            label: Some("ci".into()),
+        };
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert_eq!(json, r#"{"command":"agent.kill.where","role":"worker","label":"ci"}"#);
+        let back: Command = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, cmd);
+    }
+
     #[test]
     fn agent_kill_round_trip() {
-        let cmd = Command::AgentKill { name: "w1".into() };
+        let cmd = Command::AgentKill { name: "w1".into(), graceful: false };
         let json = serde_json::to_string(&cmd).unwrap();
         assert!(json.contains("\"command\":\"agent.kill\""));
+        assert!(!json.contains("graceful"));
+        let back: Command = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, cmd);
+    }
+
+    #[test]
+    fn agent_kill_graceful_round_trip() {
+        let cmd = Command::AgentKill { name: "w1".into(), graceful: true };
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert!(json.contains("\"graceful\":true"));
         let back: Command = serde_json::from_str(&json).unwrap();
         assert_eq!(back, cmd);
     }
@@ -786,17 +1042,40 @@ mod tests {
         );
     }
 
+    #[test]
+    fn agent_recheck_round_trip() {
+        let cmd = Command::AgentRecheck { name: "w1".into() };
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert_eq!(json, r#"{"command":"agent.recheck","name":"w1"}"#);
+        let back: Command = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, cmd);
+    }
+
+    #[test]
+    fn agent_exec_round_trip() {
+        let cmd = Command::AgentExec {
+            agent: "w1".into(),
+            cmd: "git status".into(),
+        };
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert_eq!(json, r#"{"command":"agent.exec","agent":"w1","cmd":"git status"}"#);
+        let back: Command = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, cmd);
+    }
+
     #[test]
     fn agent_list_plain() {
         let json = r#"{"command":"agent.list"}"#;
         let cmd: Command = serde_json::from_str(json).unwrap();
-        assert_eq!(cmd, Command::AgentList { format: None });
+        assert_eq!(cmd, Command::AgentList { format: None, sort_by: None, columns: None });
     }
 
     #[test]
     fn agent_list_json_format() {
         let cmd = Command::AgentList {
             format: Some("json".into()),
+            sort_by: None,
+            columns: None,
         };
         let json = serde_json::to_string(&cmd).unwrap();
         assert!(json.contains("\"format\":\"json\""));
@@ -804,11 +1083,53 @@ mod tests {
         assert_eq!(back, cmd);
     }
 
+    #[test]
+    fn agent_list_sort_and_columns_round_trip() {
+        let cmd = Command::AgentList {
+            format: None,
+            sort_by: Some("health".into()),
+            columns: Some("name,health".into()),
+        };
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert!(json.contains("\"sort_by\":\"health\""));
+        let back: Command = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, cmd);
+    }
+
+    #[test]
+    fn agent_state_set_round_trip() {
+        let cmd = Command::AgentStateSet {
+            agent: "w1".into(),
+            key: "attempts".into(),
+            value: "3".into(),
+        };
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert!(json.contains("\"command\":\"agent.state.set\""));
+        assert!(json.contains("\"key\":\"attempts\""));
+        let back: Command = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, cmd);
+    }
+
+    #[test]
+    fn agent_state_get_round_trip() {
+        let cmd = Command::AgentStateGet {
+            agent: "w1".into(),
+            key: "attempts".into(),
+        };
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert!(json.contains("\"command\":\"agent.state.get\""));
+        let back: Command = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, cmd);
+    }
+
     #[test]
     fn task_list_round_trip() {
         let cmd = Command::TaskList {
             format: Some("json".into()),
             project: Some("CMX".into()),
+            max_depth: Some(1),
+            status: Some("failed".into()),
+            agent: Some("w1".into()),
         };
         let json = serde_json::to_string(&cmd).unwrap();
         assert!(json.contains("\"command\":\"task.list\""));
@@ -833,10 +1154,13 @@ mod tests {
             title: Some("New Title".into()),
             result: None,
             agent: None,
+            due: Some("1700000000000".into()),
+            cascade: false,
         };
         let json = serde_json::to_string(&cmd).unwrap();
         assert!(json.contains("\"command\":\"task.set\""));
         assert!(json.contains("\"status\":\"in_progress\""));
+        assert!(json.contains("\"due\":\"1700000000000\""));
         assert!(!json.contains("\"result\""));
         let back: Command = serde_json::from_str(&json).unwrap();
         assert_eq!(back, cmd);
@@ -854,15 +1178,27 @@ mod tests {
                 title: None,
                 result: None,
                 agent: None,
+                due: None,
+                cascade: false,
             }
         );
     }
 
     #[test]
     fn task_check_round_trip() {
-        let cmd = Command::TaskCheck { id: "T1".into() };
+        let cmd = Command::TaskCheck { id: "T1".into(), cascade: false };
         let json = serde_json::to_string(&cmd).unwrap();
         assert!(json.contains("\"command\":\"task.check\""));
+        assert!(!json.contains("cascade"));
+        let back: Command = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, cmd);
+    }
+
+    #[test]
+    fn task_check_cascade_round_trip() {
+        let cmd = Command::TaskCheck { id: "M1".into(), cascade: true };
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert!(json.contains("\"cascade\":true"));
         let back: Command = serde_json::from_str(&json).unwrap();
         assert_eq!(back, cmd);
     }
@@ -876,6 +1212,52 @@ mod tests {
         assert_eq!(back, cmd);
     }
 
+    #[test]
+    fn task_note_round_trip() {
+        let cmd = Command::TaskNote {
+            id: "T1".into(),
+            text: "waiting on API keys".into(),
+        };
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert!(json.contains("\"command\":\"task.note\""));
+        let back: Command = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, cmd);
+    }
+
+    #[test]
+    fn task_move_round_trip() {
+        let cmd = Command::TaskMove {
+            id: "T1.2".into(),
+            parent: "T3".into(),
+        };
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert!(json.contains("\"command\":\"task.move\""));
+        let back: Command = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, cmd);
+    }
+
+    #[test]
+    fn rig_push_all_round_trip() {
+        let cmd = Command::RigPush {
+            folder: "/src".into(),
+            remote: None,
+            label: None,
+            all: true,
+            bwlimit: Some(500),
+            exclude: vec!["data/".into()],
+        };
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert!(json.contains("\"all\":true"));
+        let back: Command = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, cmd);
+
+        let json = r#"{"command":"rig.push","folder":"/src"}"#;
+        match serde_json::from_str::<Command>(json).unwrap() {
+            Command::RigPush { all, .. } => assert!(!all),
+            other => panic!("expected RigPush, got {:?}", other),
+        }
+    }
+
     #[test]
     fn config_load_with_path() {
         let cmd = Command::ConfigLoad {
@@ -915,6 +1297,17 @@ mod tests {
         assert_eq!(back, cmd);
     }
 
+    #[test]
+    fn config_unset_round_trip() {
+        let cmd = Command::ConfigUnset {
+            key: "heartbeat_marker.worker".into(),
+        };
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert!(json.contains("\"command\":\"config.unset\""));
+        let back: Command = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, cmd);
+    }
+
     #[test]
     fn config_list_round_trip() {
         let cmd = Command::ConfigList;
@@ -972,9 +1365,24 @@ mod tests {
         let cmd = Command::Tell {
             agent: "w1".into(),
             text: "start task".into(),
+            dedup: false,
         };
         let json = serde_json::to_string(&cmd).unwrap();
         assert!(json.contains("\"command\":\"tell\""));
+        assert!(!json.contains("dedup"));
+        let back: Command = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, cmd);
+    }
+
+    #[test]
+    fn tell_dedup_round_trip() {
+        let cmd = Command::Tell {
+            agent: "w1".into(),
+            text: "start task".into(),
+            dedup: true,
+        };
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert!(json.contains("\"dedup\":true"));
         let back: Command = serde_json::from_str(&json).unwrap();
         assert_eq!(back, cmd);
     }
@@ -1132,6 +1540,7 @@ mod tests {
             role: "worker".into(),
             size: 3,
             path: Some("/tmp/work".into()),
+            min_idle: None,
         };
         let json = serde_json::to_string(&cmd).unwrap();
         assert!(json.contains("\"command\":\"pool.set\""));
@@ -1148,6 +1557,7 @@ mod tests {
             role: "worker".into(),
             size: 2,
             path: None,
+            min_idle: None,
         });
     }
 
@@ -1217,13 +1627,14 @@ mod tests {
     fn help_no_topic() {
         let json = r#"{"command":"help"}"#;
         let cmd: Command = serde_json::from_str(json).unwrap();
-        assert_eq!(cmd, Command::Help { topic: None });
+        assert_eq!(cmd, Command::Help { topic: None, search: None });
     }
 
     #[test]
     fn help_with_topic() {
         let cmd = Command::Help {
             topic: Some("agent".into()),
+            search: None,
         };
         let json = serde_json::to_string(&cmd).unwrap();
         assert!(json.contains("\"command\":\"help\""));
@@ -1232,13 +1643,37 @@ mod tests {
         assert_eq!(back, cmd);
     }
 
+    #[test]
+    fn help_search_round_trip() {
+        let cmd = Command::Help {
+            topic: None,
+            search: Some("snapshot".into()),
+        };
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert!(json.contains("\"search\":\"snapshot\""));
+        assert!(!json.contains("topic"));
+        let back: Command = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, cmd);
+    }
+
     // --- Diagnosis command round-trips ---
 
     #[test]
     fn diagnosis_report_round_trip() {
-        let cmd = Command::DiagnosisReport;
+        let cmd = Command::DiagnosisReport { since: None, until: None, dest: None };
         let json = serde_json::to_string(&cmd).unwrap();
-        assert!(json.contains(r#""command":"diagnosis.report""#));
+        assert_eq!(json, r#"{"command":"diagnosis.report"}"#);
+        let back: Command = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, cmd);
+
+        let cmd = Command::DiagnosisReport {
+            since: Some("24h".into()),
+            until: None,
+            dest: Some("/tmp/report.md".into()),
+        };
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert!(json.contains(r#""since":"24h""#));
+        assert!(json.contains(r#""dest":"/tmp/report.md""#));
         let back: Command = serde_json::from_str(&json).unwrap();
         assert_eq!(back, cmd);
     }
@@ -1247,6 +1682,8 @@ mod tests {
     fn diagnosis_reliability_round_trip() {
         let cmd = Command::DiagnosisReliability {
             signal: Some("heartbeat_stale".into()),
+            agent: Some("w1".into()),
+            since: None,
             format: Some("json".into()),
         };
         let json = serde_json::to_string(&cmd).unwrap();
@@ -1260,13 +1697,14 @@ mod tests {
     fn diagnosis_reliability_no_args() {
         let json = r#"{"command":"diagnosis.reliability"}"#;
         let cmd: Command = serde_json::from_str(json).unwrap();
-        assert_eq!(cmd, Command::DiagnosisReliability { signal: None, format: None });
+        assert_eq!(cmd, Command::DiagnosisReliability { signal: None, agent: None, since: None, format: None });
     }
 
     #[test]
     fn diagnosis_effectiveness_round_trip() {
         let cmd = Command::DiagnosisEffectiveness {
             signal: Some("error_pattern".into()),
+            min_attempts: Some("3".into()),
             format: None,
         };
         let json = serde_json::to_string(&cmd).unwrap();
@@ -1287,6 +1725,32 @@ mod tests {
         assert_eq!(back, cmd);
     }
 
+    #[test]
+    fn diagnosis_compare_round_trip() {
+        let cmd = Command::DiagnosisCompare {
+            a: "w1".into(),
+            b: "w2".into(),
+            format: None,
+        };
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert!(json.contains(r#""command":"diagnosis.compare""#));
+        assert!(!json.contains("format"));
+        let back: Command = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, cmd);
+    }
+
+    #[test]
+    fn diagnosis_annotate_round_trip() {
+        let cmd = Command::DiagnosisAnnotate {
+            id: "7".into(),
+            note: "stall was a long test run".into(),
+        };
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert!(json.contains(r#""command":"diagnosis.annotate""#));
+        let back: Command = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, cmd);
+    }
+
     #[test]
     fn diagnosis_events_round_trip() {
         let cmd = Command::DiagnosisEvents {
@@ -1344,6 +1808,7 @@ mod tests {
         let cmd = Command::HistoryDiff {
             from: "0".into(),
             to: Some("1".into()),
+            unified: true,
         };
         let json = serde_json::to_string(&cmd).unwrap();
         assert!(json.contains(r#""command":"history.diff""#));
@@ -1356,32 +1821,84 @@ mod tests {
     fn history_diff_no_to() {
         let json = r#"{"command":"history.diff","from":"0"}"#;
         let cmd: Command = serde_json::from_str(json).unwrap();
-        assert_eq!(cmd, Command::HistoryDiff { from: "0".into(), to: None });
+        assert_eq!(cmd, Command::HistoryDiff { from: "0".into(), to: None, unified: false });
     }
 
     #[test]
     fn history_restore_round_trip() {
-        let cmd = Command::HistoryRestore { id: "0".into() };
+        let cmd = Command::HistoryRestore { id: "0".into(), dry_run: false };
         let json = serde_json::to_string(&cmd).unwrap();
         assert!(json.contains(r#""command":"history.restore""#));
+        assert!(!json.contains("dry_run"));
+        let back: Command = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, cmd);
+
+        let cmd = Command::HistoryRestore { id: "0".into(), dry_run: true };
+        let json = serde_json::to_string(&cmd).unwrap();
         let back: Command = serde_json::from_str(&json).unwrap();
         assert_eq!(back, cmd);
     }
 
     #[test]
     fn history_snapshot_round_trip() {
-        let cmd = Command::HistorySnapshot;
+        let cmd = Command::HistorySnapshot { label: None };
         let json = serde_json::to_string(&cmd).unwrap();
         assert!(json.contains(r#""command":"history.snapshot""#));
+        assert!(!json.contains("label"));
+        let back: Command = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, cmd);
+
+        let cmd = Command::HistorySnapshot { label: Some("baseline".into()) };
+        let json = serde_json::to_string(&cmd).unwrap();
         let back: Command = serde_json::from_str(&json).unwrap();
         assert_eq!(back, cmd);
     }
 
     #[test]
     fn history_prune_round_trip() {
-        let cmd = Command::HistoryPrune;
+        let cmd = Command::HistoryPrune { dry_run: false };
         let json = serde_json::to_string(&cmd).unwrap();
         assert!(json.contains(r#""command":"history.prune""#));
+        assert!(!json.contains("dry_run"));
+        let back: Command = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, cmd);
+
+        let dry = Command::HistoryPrune { dry_run: true };
+        let json = serde_json::to_string(&dry).unwrap();
+        assert!(json.contains(r#""dry_run":true"#));
+        let back: Command = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, dry);
+    }
+
+    #[test]
+    fn history_pin_round_trip() {
+        let cmd = Command::HistoryPin { id: "2026-02-22T10-00-00.md".into() };
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert!(json.contains(r#""command":"history.pin""#));
+        let back: Command = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, cmd);
+
+        let cmd = Command::HistoryUnpin { id: "0".into() };
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert!(json.contains(r#""command":"history.unpin""#));
+        let back: Command = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, cmd);
+    }
+
+    #[test]
+    fn skill_validate_round_trip() {
+        let cmd = Command::SkillValidate;
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert_eq!(json, r#"{"command":"skill.validate"}"#);
+        let back: Command = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, cmd);
+    }
+
+    #[test]
+    fn history_search_round_trip() {
+        let cmd = Command::HistorySearch { query: "agent w3".into(), ignore_case: true };
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert!(json.contains(r#""command":"history.search""#));
         let back: Command = serde_json::from_str(&json).unwrap();
         assert_eq!(back, cmd);
     }
@@ -1394,19 +1911,25 @@ mod tests {
             r#"{"command":"view","name":"x"}"#,
             r#"{"command":"agent.new","role":"worker"}"#,
             r#"{"command":"agent.kill","name":"x"}"#,
+            r#"{"command":"agent.kill.where","status":"dead"}"#,
             r#"{"command":"agent.restart","name":"x"}"#,
             r#"{"command":"agent.assign","name":"x","task":"t"}"#,
             r#"{"command":"agent.unassign","name":"x"}"#,
             r#"{"command":"agent.status","name":"x"}"#,
             r#"{"command":"agent.list"}"#,
+            r#"{"command":"agent.recheck","name":"w1"}"#,
+            r#"{"command":"agent.exec","agent":"w1","cmd":"ls"}"#,
             r#"{"command":"task.list"}"#,
             r#"{"command":"task.get","id":"x"}"#,
             r#"{"command":"task.set","id":"x"}"#,
             r#"{"command":"task.check","id":"x"}"#,
             r#"{"command":"task.uncheck","id":"x"}"#,
+            r#"{"command":"task.note","id":"x","text":"t"}"#,
+            r#"{"command":"task.move","id":"x","parent":"y"}"#,
             r#"{"command":"config.load"}"#,
             r#"{"command":"config.save"}"#,
             r#"{"command":"config.add","key":"k","value":"v"}"#,
+            r#"{"command":"config.unset","key":"k"}"#,
             r#"{"command":"config.list"}"#,
             r#"{"command":"project.add","name":"p","path":"/x"}"#,
             r#"{"command":"project.remove","name":"p"}"#,
@@ -1429,6 +1952,9 @@ mod tests {
             r#"{"command":"rig.init","host":"user@host:22"}"#,
             r#"{"command":"rig.push","folder":"/local"}"#,
             r#"{"command":"rig.pull","folder":"/local"}"#,
+            r#"{"command":"rig.push","folder":"/local","label":"gpu"}"#,
+            r#"{"command":"rig.push","folder":"/local","all":true}"#,
+            r#"{"command":"rig.pull","folder":"/local","bwlimit":500,"exclude":["data/"]}"#,
             r#"{"command":"rig.status"}"#,
             r#"{"command":"rig.health"}"#,
             r#"{"command":"rig.stop"}"#,
@@ -1439,13 +1965,18 @@ mod tests {
             r#"{"command":"diagnosis.effectiveness"}"#,
             r#"{"command":"diagnosis.thresholds"}"#,
             r#"{"command":"diagnosis.events"}"#,
+            r#"{"command":"diagnosis.annotate","id":"1","note":"n"}"#,
             r#"{"command":"history.list"}"#,
             r#"{"command":"history.show","id":"0"}"#,
             r#"{"command":"history.diff","from":"0"}"#,
             r#"{"command":"history.restore","id":"0"}"#,
             r#"{"command":"history.snapshot"}"#,
             r#"{"command":"history.prune"}"#,
+            r#"{"command":"history.pin","id":"0"}"#,
+            r#"{"command":"history.unpin","id":"0"}"#,
+            r#"{"command":"history.search","query":"w1"}"#,
             r#"{"command":"learnings.list"}"#,
+            r#"{"command":"skill.validate"}"#,
             r#"{"command":"learnings.add","project":"p","title":"t","body":"b"}"#,
             r#"{"command":"learnings.search","query":"q"}"#,
             r#"{"command":"watch"}"#,
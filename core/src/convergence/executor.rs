@@ -1,7 +1,10 @@
 //! Convergence executor — runs planned actions through a backend with retry.
 
+use std::collections::VecDeque;
+
+use crate::agent::bridge;
 use crate::convergence::retry::{ActionRetryTracker, RetryPolicy};
-use crate::infrastructure::SessionBackend;
+use crate::infrastructure::{BatchError, SessionBackend};
 use cmx_utils::response::Action;
 
 /// Outcome of a convergence execution pass.
@@ -9,11 +12,14 @@ use cmx_utils::response::Action;
 pub struct ConvergenceResult {
     pub succeeded: Vec<Action>,
     pub failed: Vec<(Action, String)>,
+    /// Actions whose effect already held; reported as no-ops, not re-applied.
+    pub skipped: Vec<Action>,
     pub retries_used: u32,
 }
 
 /// Executes a batch of actions through a `SessionBackend`, retrying failures
-/// according to the configured policy.
+/// according to the configured policy. Consecutive actions go to the backend
+/// as one `execute_batch` call, split only before creations.
 pub struct ConvergenceExecutor {
     retry_tracker: ActionRetryTracker,
 }
@@ -32,28 +38,50 @@ impl ConvergenceExecutor {
     ) -> ConvergenceResult {
         let mut succeeded = Vec::new();
         let mut failed = Vec::new();
+        let mut skipped = Vec::new();
         let mut retries_used: u32 = 0;
         let mut pending = actions;
 
         loop {
             let mut still_failing = Vec::new();
             let mut last_errors: Vec<(Action, String)> = Vec::new();
+            let mut queue: VecDeque<Action> = pending.into();
 
-            for action in pending {
-                let key = action_key(&action);
-                match backend.execute_action(&action) {
-                    Ok(()) => {
-                        self.retry_tracker.record_success(&key);
-                        succeeded.push(action);
-                    }
-                    Err(e) => {
-                        self.retry_tracker.record_failure(&key);
-                        if self.retry_tracker.can_retry(&key) {
-                            retries_used += 1;
-                            still_failing.push(action);
-                        } else {
-                            last_errors.push((action, e));
+            while let Some(first) = queue.pop_front() {
+                if already_satisfied(&first, backend) {
+                    skipped.push(first);
+                    continue;
+                }
+                // A batch runs up to the next creation, so that creation's
+                // existence check sees the batch's effects.
+                let mut batch = vec![first];
+                while queue.front().is_some_and(|a| !needs_existence_check(a)) {
+                    batch.extend(queue.pop_front());
+                }
+                let (done, failure) = match backend.execute_batch(&batch) {
+                    Ok(()) => (batch, None),
+                    Err(BatchError { index, message }) => {
+                        let mut rest = batch.split_off(index);
+                        let action = rest.remove(0);
+                        // Actions after the failure have not run yet.
+                        for later in rest.into_iter().rev() {
+                            queue.push_front(later);
                         }
+                        (batch, Some((action, message)))
+                    }
+                };
+                for action in done {
+                    self.retry_tracker.record_success(&action_key(&action));
+                    succeeded.push(action);
+                }
+                if let Some((action, e)) = failure {
+                    let key = action_key(&action);
+                    self.retry_tracker.record_failure(&key);
+                    if self.retry_tracker.can_retry(&key) {
+                        retries_used += 1;
+                        still_failing.push(action);
+                    } else {
+                        last_errors.push((action, e));
                     }
                 }
             }
@@ -69,11 +97,28 @@ impl ConvergenceExecutor {
         ConvergenceResult {
             succeeded,
             failed,
+            skipped,
             retries_used,
         }
     }
 }
 
+/// Actions `already_satisfied` inspects before running.
+fn needs_existence_check(action: &Action) -> bool {
+    matches!(action, Action::CreateSession { .. } | Action::CreateAgent { .. })
+}
+
+/// Whether an action's effect already holds in the backend. Only creations
+/// are checked: a session (or the session backing an agent) that already
+/// exists needs no second create. Everything else is always applied.
+fn already_satisfied(action: &Action, backend: &dyn SessionBackend) -> bool {
+    match action {
+        Action::CreateSession { name, .. } => backend.session_exists(name),
+        Action::CreateAgent { name, .. } => backend.session_exists(&bridge::session_name(name)),
+        _ => false,
+    }
+}
+
 fn action_key(action: &Action) -> String {
     match action {
         Action::CreateSession { name, .. } => format!("create_session:{}", name),
@@ -217,6 +262,82 @@ mod tests {
         assert_eq!(result.failed.len(), 1);
     }
 
+    #[test]
+    fn executor_sends_consecutive_actions_as_one_batch() {
+        let policy = RetryPolicy::new(3, BackoffStrategy::Fixed, 100);
+        let mut executor = ConvergenceExecutor::new(policy);
+        let mut backend = MockBackend::new();
+        let keys = |k: &str| Action::SendKeys { target: "cmx-w1".into(), keys: k.into() };
+        let actions = vec![
+            Action::CreateSession { name: "cmx-w1".into(), cwd: "/tmp".into() },
+            keys("claude Enter"),
+            Action::UpdateAssignment { agent: "w1".into(), task: Some("T1".into()) },
+            keys("brief Enter"),
+            Action::CreateSession { name: "cmx-w2".into(), cwd: "/tmp".into() },
+            keys("claude Enter"),
+        ];
+        let result = executor.execute(actions.clone(), &mut backend);
+        assert_eq!(result.succeeded, actions);
+        assert_eq!(backend.batches, vec![4, 2]);
+    }
+
+    #[test]
+    fn executor_maps_mid_batch_failure_to_its_action() {
+        let policy = RetryPolicy::new(0, BackoffStrategy::Fixed, 100);
+        let mut executor = ConvergenceExecutor::new(policy);
+
+        struct KillFails(Vec<Action>);
+        impl SessionBackend for KillFails {
+            fn execute_action(&mut self, action: &Action) -> Result<(), String> {
+                if let Action::KillAgent { name } = action {
+                    return Err(format!("cannot kill {}", name));
+                }
+                self.0.push(action.clone());
+                Ok(())
+            }
+            fn session_exists(&self, _name: &str) -> bool { false }
+            fn list_sessions(&self) -> Vec<String> { Vec::new() }
+            fn capture_pane(&self, _target: &str) -> Result<String, String> { Err("nope".into()) }
+        }
+
+        let before = Action::SendKeys { target: "w1".into(), keys: "a".into() };
+        let bad = Action::KillAgent { name: "w9".into() };
+        let after = Action::SendKeys { target: "w1".into(), keys: "b".into() };
+        let mut backend = KillFails(Vec::new());
+        let result = executor.execute(vec![before.clone(), bad.clone(), after.clone()], &mut backend);
+        assert_eq!(result.succeeded, vec![before.clone(), after.clone()]);
+        assert_eq!(result.failed, vec![(bad, "cannot kill w9".to_string())]);
+        assert_eq!(backend.0, vec![before, after]);
+    }
+
+    #[test]
+    fn executor_skips_already_satisfied_create_agent() {
+        let policy = RetryPolicy::new(3, BackoffStrategy::Fixed, 100);
+        let mut executor = ConvergenceExecutor::new(policy);
+        let mut backend = MockBackend::with_sessions(vec!["cmx-w1".into()]);
+        let existing = Action::CreateAgent { name: "w1".into(), role: "worker".into(), path: "/tmp".into() };
+        let missing = Action::CreateAgent { name: "w2".into(), role: "worker".into(), path: "/tmp".into() };
+        let result = executor.execute(vec![existing.clone(), missing.clone()], &mut backend);
+        assert_eq!(result.skipped, vec![existing]);
+        assert_eq!(result.succeeded, vec![missing.clone()]);
+        assert!(result.failed.is_empty());
+        assert_eq!(backend.actions, vec![missing]);
+    }
+
+    #[test]
+    fn executor_skips_existing_session_on_rerun() {
+        let policy = RetryPolicy::new(3, BackoffStrategy::Fixed, 100);
+        let mut executor = ConvergenceExecutor::new(policy);
+        let mut backend = MockBackend::new();
+        let create = Action::CreateSession { name: "s1".into(), cwd: "/tmp".into() };
+        let first = executor.execute(vec![create.clone()], &mut backend);
+        assert_eq!(first.succeeded.len(), 1);
+        let second = executor.execute(vec![create.clone()], &mut backend);
+        assert!(second.succeeded.is_empty());
+        assert_eq!(second.skipped, vec![create]);
+        assert_eq!(backend.actions.len(), 1);
+    }
+
     #[test]
     fn executor_action_key_uniqueness() {
         let a1 = Action::CreateSession { name: "s1".into(), cwd: "/tmp".into() };
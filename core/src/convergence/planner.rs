@@ -153,6 +153,8 @@ mod tests {
             health: HealthState::Healthy,
             last_heartbeat_ms: None,
             session: None,
+            created_at_ms: None,
+            assigned_at_ms: None,
         }
     }
 
@@ -17,14 +17,16 @@ use crate::agent::bridge;
 use crate::command::Command;
 use crate::convergence::executor::ConvergenceExecutor;
 use crate::convergence::retry::RetryPolicy;
-use crate::infrastructure::SessionBackend;
+use crate::infrastructure::{backend_for, SessionBackend};
 use crate::infrastructure::mock::MockBackend;
-use crate::monitor::cycle::MonitorCycle;
-use crate::monitor::heartbeat;
-use crate::service::ServiceSocket;
+use crate::monitor::cycle::{capture_heartbeat, MonitorCycle};
+use crate::monitor::exec::{self, ExecPoll, PendingExec};
+use crate::monitor::health;
+use crate::monitor::heartbeat::{self, HeartbeatPatterns};
+use crate::service::{PendingReply, ServiceSocket};
 use crate::sys::Sys;
-use crate::types::config::BackoffStrategy;
-use cmx_utils::response::Action;
+use crate::types::config::{BackoffStrategy, Settings};
+use cmx_utils::response::{Action, Response};
 use cmx_utils::watch::WatchRegistry;
 
 
@@ -97,6 +99,9 @@ impl DaemonHandle {
 }
 
 
+/// Zellij session that holds agent tabs when `session_backend` is zellij.
+pub const ZELLIJ_SESSION: &str = "cmx";
+
 /// The CMX daemon — owns the event loop, Sys, service socket, and watch registry.
 pub struct Daemon {
     sys: Sys,
@@ -113,13 +118,20 @@ pub struct Daemon {
     monitor: MonitorCycle,
     /// Timestamp of last monitor cycle run (ms).
     last_monitor_ms: u64,
+    /// agent.exec requests waiting for their pane to settle.
+    running_execs: Vec<(PendingReply, PendingExec)>,
+    /// Settings the monitor's derived state was last built from.
+    monitor_settings: Option<Settings>,
 }
 
 
 impl Daemon {
     /// Initialize the daemon: create Sys, bind socket, set up channel.
+    /// The session backend is the one named by the `session_backend` setting.
     pub fn new(config_dir: &Path) -> Result<Daemon, String> {
-        Self::with_config(config_dir, DaemonConfig::default())
+        let sys = Sys::new(config_dir)?;
+        let backend = backend_for(sys.settings().session_backend, ZELLIJ_SESSION);
+        Self::from_sys(sys, config_dir, DaemonConfig::default(), backend)
     }
 
     /// Initialize with custom config (uses MockBackend by default).
@@ -134,19 +146,39 @@ impl Daemon {
         backend: Box<dyn SessionBackend + Send>,
     ) -> Result<Daemon, String> {
         let sys = Sys::new(config_dir)?;
+        Self::from_sys(sys, config_dir, config, backend)
+    }
+
+    fn from_sys(
+        sys: Sys,
+        config_dir: &Path,
+        config: DaemonConfig,
+        backend: Box<dyn SessionBackend + Send>,
+    ) -> Result<Daemon, String> {
         let service = ServiceSocket::start(config_dir)?;
         let registry = WatchRegistry::new();
         let (sender, receiver) = mpsc::channel();
         let handle = DaemonHandle { sender };
         let policy = RetryPolicy::new(3, BackoffStrategy::Fixed, 100);
         let executor = ConvergenceExecutor::new(policy);
-        let monitor = MonitorCycle::new(
+        let mut monitor = MonitorCycle::new(
             sys.settings().message_timeout as u64,
             sys.settings().heartbeat_timeout as u64 / 1000,
             sys.settings().ready_prompt_pattern.clone(),
         );
+        let (rules, warnings) = health::load_health_rules(config_dir);
+        for warning in warnings {
+            eprintln!("cmx daemon: {}", warning);
+        }
+        monitor.health_rules = rules;
+        for skipped in sys.library().skipped_entries() {
+            eprintln!("cmx daemon: {}", skipped);
+        }
+        for duplicate in sys.library().duplicates() {
+            eprintln!("cmx daemon: {}", duplicate);
+        }
 
-        Ok(Daemon {
+        let mut daemon = Daemon {
             sys,
             service,
             registry,
@@ -158,7 +190,11 @@ impl Daemon {
             spawning_agents: Vec::new(),
             monitor,
             last_monitor_ms: now_ms(),
-        })
+            running_execs: Vec::new(),
+            monitor_settings: None,
+        };
+        daemon.refresh_monitor_settings();
+        Ok(daemon)
     }
 
     /// Get a handle for sending events to this daemon.
@@ -174,6 +210,9 @@ impl Daemon {
             }
         }
 
+        if self.sys.settings_dirty() {
+            eprintln!("cmx daemon: settings modified, unsaved (run `skd config save` to keep them)");
+        }
         self.service.shutdown_ref();
         Ok(())
     }
@@ -199,6 +238,15 @@ impl Daemon {
             }
         }
 
+        // Answer agent.recheck requests, which need the backend
+        self.run_pending_rechecks();
+
+        // Capture panes for new agent.exec requests before their keys go out
+        self.start_pending_execs();
+
+        // Kill agents whose graceful-shutdown grace period has passed
+        self.sys.finish_pending_kills(now_ms());
+
         // Execute any actions accumulated from socket commands
         self.execute_pending_actions();
 
@@ -207,12 +255,18 @@ impl Daemon {
             return true;
         }
 
+        // Pick up config changes before the monitor next reads them
+        self.refresh_monitor_settings();
+
         // 3. Check spawning agents for ready-state detection
         self.check_spawning_agents();
 
         // 4. Run monitor cycle (health, messages, triggers) at configured interval
         self.run_monitor_cycle();
 
+        // Reply to agent.exec requests whose output has settled
+        self.poll_running_execs();
+
         // 5. Expire stale watchers
         self.registry.expire_stale();
 
@@ -277,6 +331,78 @@ impl Daemon {
         }
     }
 
+    /// Reassess agents whose recheck was requested over the socket and
+    /// reply on each parked connection.
+    fn run_pending_rechecks(&mut self) {
+        for (stream, name) in self.service.take_rechecks() {
+            let response =
+                self.sys.recheck_agent(&name, &mut self.monitor, self.backend.as_ref(), now_ms());
+            if let Err(e) = ServiceSocket::respond(stream, &response) {
+                eprintln!("cmx daemon: recheck reply failed: {}", e);
+            }
+            self.registry.notify_all(&format!("agent.recheck {}", name), now_ms());
+        }
+    }
+
+    /// Take newly accepted agent.exec requests and record each pane's
+    /// current contents. Must run before `execute_pending_actions` sends
+    /// the queued keystrokes, so the capture predates the command.
+    fn start_pending_execs(&mut self) {
+        for parked in self.service.take_execs() {
+            match self.backend.capture_pane_lines(&parked.agent, exec::EXEC_CAPTURE_LINES) {
+                Ok(before) => {
+                    let pattern = self.sys.data().agents().get(&parked.agent).and_then(|a| {
+                        self.monitor.heartbeat_patterns.get(&a.agent_type).cloned()
+                    });
+                    let pending = PendingExec::new(&parked.agent, &parked.cmd, &before, now_ms())
+                        .with_ready_pattern(&self.monitor.prompt_pattern, pattern);
+                    self.running_execs.push((parked.stream, pending));
+                }
+                Err(e) => {
+                    let response = Response::Error {
+                        message: format!(
+                            "Sent to '{}' but its pane could not be captured: {}",
+                            parked.agent, e
+                        ),
+                    };
+                    if let Err(e) = ServiceSocket::respond(parked.stream, &response) {
+                        eprintln!("cmx daemon: exec reply failed: {}", e);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Re-capture panes of running agent.exec requests and reply to those
+    /// that settled or timed out.
+    fn poll_running_execs(&mut self) {
+        let now = now_ms();
+        for (stream, mut pending) in std::mem::take(&mut self.running_execs) {
+            let response = match self.backend.capture_pane_lines(&pending.agent, exec::EXEC_CAPTURE_LINES) {
+                Ok(capture) => match pending.observe(&capture, now) {
+                    ExecPoll::Waiting => {
+                        self.running_execs.push((stream, pending));
+                        continue;
+                    }
+                    ExecPoll::Settled(output) => Response::Ok { output },
+                    ExecPoll::TimedOut(output) => Response::Ok {
+                        output: format!(
+                            "{}\n(timed out after {}s; output may be incomplete)",
+                            output,
+                            exec::EXEC_TIMEOUT_MS / 1000
+                        ),
+                    },
+                },
+                Err(e) => Response::Error {
+                    message: format!("Lost pane for '{}' during exec: {}", pending.agent, e),
+                },
+            };
+            if let Err(e) = ServiceSocket::respond(stream, &response) {
+                eprintln!("cmx daemon: exec reply failed: {}", e);
+            }
+        }
+    }
+
     /// Poll spawning agents' panes to detect when they reach the ready prompt.
     /// When detected, update agent health to Healthy and remove from spawning list.
     fn check_spawning_agents(&mut self) {
@@ -288,16 +414,20 @@ impl Daemon {
         let mut newly_ready = Vec::new();
 
         for agent_name in &self.spawning_agents {
-            let session = match self.sys.data().agents().get(agent_name) {
+            let (session, role, agent_type) = match self.sys.data().agents().get(agent_name) {
                 Some(a) => match &a.session {
-                    Some(s) => s.clone(),
+                    Some(s) => (s.clone(), a.role.clone(), a.agent_type.clone()),
                     None => continue,
                 },
                 None => continue,
             };
+            let marker = self.monitor.marker_for(&role).to_string();
+            let pattern = self.monitor.heartbeat_patterns.get(&agent_type);
 
-            if let Ok(output) = self.backend.capture_pane(&session) {
-                let result = heartbeat::parse_capture(&output, &prompt_pattern);
+            let history_lines = heartbeat::HEARTBEAT_HISTORY_LINES;
+            if let Ok((_, result)) = capture_heartbeat(self.backend.as_ref(), &session, history_lines, |output| {
+                heartbeat::parse_capture_with_pattern(output, &prompt_pattern, &marker, pattern)
+            }) {
                 if result.state == heartbeat::AgentState::Ready {
                     newly_ready.push(agent_name.clone());
                 }
@@ -310,6 +440,26 @@ impl Daemon {
         }
     }
 
+    /// Rebuild the monitor state derived from settings if the settings have
+    /// changed since it was built (config add, unset, or load).
+    fn refresh_monitor_settings(&mut self) {
+        let settings = self.sys.settings();
+        let previous = self.monitor_settings.as_ref();
+        if previous == Some(settings) {
+            return;
+        }
+        if previous.is_none_or(|p| p.heartbeat_patterns != settings.heartbeat_patterns) {
+            let (patterns, warnings) = HeartbeatPatterns::from_settings(&settings.heartbeat_patterns);
+            for warning in warnings {
+                eprintln!("cmx daemon: {}", warning);
+            }
+            self.monitor.heartbeat_patterns = patterns;
+        }
+        self.monitor.heartbeat_marker = settings.heartbeat_marker.clone();
+        self.monitor.heartbeat_marker_overrides = settings.heartbeat_marker_overrides.clone();
+        self.monitor_settings = Some(settings.clone());
+    }
+
     /// Run one monitoring cycle if enough time has elapsed since the last one.
     ///
     /// The health check interval from settings controls the frequency.
@@ -593,6 +743,39 @@ mod tests {
         cleanup(&dir);
     }
 
+    #[test]
+    fn daemon_refreshes_monitor_after_config_change() {
+        use crate::types::agent::AgentType;
+        let dir = test_config_dir();
+        let mut daemon = Daemon::new(&dir).unwrap();
+        assert!(daemon.monitor.heartbeat_patterns.is_empty());
+
+        daemon.handle().send_command(
+            Command::ConfigAdd { key: "heartbeat_pattern.SSH".into(), value: r"^\[\w+\]\$".into() },
+            "test",
+        ).unwrap();
+        daemon.tick();
+        assert!(daemon.monitor.heartbeat_patterns.get(&AgentType::Ssh).is_some());
+
+        daemon.handle().send_command(
+            Command::ConfigUnset { key: "heartbeat_pattern.ssh".into() },
+            "test",
+        ).unwrap();
+        daemon.tick();
+        assert!(daemon.monitor.heartbeat_patterns.is_empty());
+
+        daemon.handle().send_command(
+            Command::ConfigAdd { key: "heartbeat_marker.worker".into(), value: "PULSE".into() },
+            "test",
+        ).unwrap();
+        daemon.tick();
+        assert_eq!(daemon.monitor.marker_for("worker"), "PULSE");
+        assert_eq!(daemon.monitor.marker_for("pilot"), daemon.sys().settings().heartbeat_marker);
+
+        daemon.service.shutdown_ref();
+        cleanup(&dir);
+    }
+
     #[test]
     fn daemon_config_default() {
         let config = DaemonConfig::default();
@@ -701,7 +884,7 @@ mod tests {
 
         // Now kill it
         handle
-            .send_command(Command::AgentKill { name: "k1".into() }, "test")
+            .send_command(Command::AgentKill { name: "k1".into(), graceful: false }, "test")
             .unwrap();
         daemon.tick();
 
@@ -101,6 +101,7 @@ impl AgentRegistry {
             .get_mut(agent_name)
             .ok_or_else(|| format!("agent not found: {}", agent_name))?;
         let old = agent.task.take();
+        agent.assigned_at_ms = None;
         agent.status = AgentStatus::Idle;
         Ok(old)
     }
@@ -153,6 +154,8 @@ mod tests {
             health: HealthState::Unknown,
             last_heartbeat_ms: None,
             session: None,
+            created_at_ms: None,
+            assigned_at_ms: None,
         }
     }
 
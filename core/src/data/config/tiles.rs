@@ -97,7 +97,7 @@ mod tests {
     use super::*;
     use crate::types::agent::{Agent, AgentStatus, AgentType, HealthState};
     fn make_agent(name: &str, role: &str) -> Agent {
-        Agent { name: name.into(), role: role.into(), agent_type: AgentType::Claude, task: None, path: "/tmp".into(), status: AgentStatus::Idle, status_notes: String::new(), health: HealthState::Unknown, last_heartbeat_ms: None, session: None }
+        Agent { name: name.into(), role: role.into(), agent_type: AgentType::Claude, task: None, path: "/tmp".into(), status: AgentStatus::Idle, status_notes: String::new(), health: HealthState::Unknown, last_heartbeat_ms: None, session: None, created_at_ms: None, assigned_at_ms: None }
     }
     #[test] fn parse_tile_with_layout() {
         let r = TileRegistry::parse("## two-workers\nkind: composition\nlayout: ROW(worker 50%, worker 50%)\n").unwrap();
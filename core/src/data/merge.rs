@@ -1,3 +1,6 @@
+use std::fmt::Debug;
+
+use crate::data::task_tree::TaskTree;
 use crate::types::task::{TaskNode, TaskSource};
 
 pub fn merge_task_trees(roadmap_tasks: &mut Vec<TaskNode>, filesystem_tasks: Vec<TaskNode>) {
@@ -12,12 +15,118 @@ pub fn merge_task_trees(roadmap_tasks: &mut Vec<TaskNode>, filesystem_tasks: Vec
     }
 }
 
+/// A field that both sides of a three-way merge changed differently from base.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conflict {
+    pub task_id: String,
+    /// Field name (`title`, `status`, `result`, `agent`, `spec_path`, `depends_on`, `due_at_ms`), or
+    /// `deleted` when one side removed a task the other side modified.
+    pub field: String,
+    pub base: Option<String>,
+    pub ours: String,
+    pub theirs: String,
+}
+
+/// Three-way merge of two roadmap copies that diverged from `base`.
+///
+/// A field takes whichever side changed it; a conflict is reported only when
+/// both sides changed the same field of the same task to different values.
+/// On conflict the `ours` value is kept. Tasks are matched by id per level;
+/// ordering follows `ours`, with tasks added only in `theirs` appended.
+pub fn merge_three_way(base: &TaskTree, ours: &TaskTree, theirs: &TaskTree) -> (TaskTree, Vec<Conflict>) {
+    let mut conflicts = Vec::new();
+    let merged = merge_levels(base.roots(), ours.roots(), theirs.roots(), &mut conflicts);
+    let mut tree = TaskTree::new();
+    for node in merged { tree.add_root(node); }
+    (tree, conflicts)
+}
+
+fn merge_levels(base: &[TaskNode], ours: &[TaskNode], theirs: &[TaskNode], conflicts: &mut Vec<Conflict>) -> Vec<TaskNode> {
+    let find = |list: &[TaskNode], id: &str| list.iter().position(|t| t.id == id);
+    let mut out = Vec::new();
+    for o in ours {
+        let b = find(base, &o.id).map(|i| &base[i]);
+        match (find(theirs, &o.id).map(|i| &theirs[i]), b) {
+            (Some(t), _) => out.push(merge_node(b, o, t, conflicts)),
+            // Theirs deleted it: drop unless ours modified it meanwhile.
+            (None, Some(b)) => if !same_task(b, o) {
+                conflicts.push(deleted_conflict(b, "modified", "deleted"));
+                out.push(o.clone());
+            },
+            (None, None) => out.push(o.clone()),
+        }
+    }
+    for t in theirs.iter().filter(|t| find(ours, &t.id).is_none()) {
+        match find(base, &t.id).map(|i| &base[i]) {
+            // Ours deleted it: drop unless theirs modified it meanwhile.
+            Some(b) => if !same_task(b, t) {
+                conflicts.push(deleted_conflict(b, "deleted", "modified"));
+                out.push(t.clone());
+            },
+            None => out.push(t.clone()),
+        }
+    }
+    out
+}
+
+fn merge_node(base: Option<&TaskNode>, ours: &TaskNode, theirs: &TaskNode, conflicts: &mut Vec<Conflict>) -> TaskNode {
+    let mut merged = ours.clone();
+    let id = &ours.id;
+    merged.title = merge_field(id, "title", base.map(|b| &b.title), &ours.title, &theirs.title, conflicts);
+    merged.status = merge_field(id, "status", base.map(|b| &b.status), &ours.status, &theirs.status, conflicts);
+    merged.result = merge_field(id, "result", base.map(|b| &b.result), &ours.result, &theirs.result, conflicts);
+    merged.agent = merge_field(id, "agent", base.map(|b| &b.agent), &ours.agent, &theirs.agent, conflicts);
+    merged.spec_path = merge_field(id, "spec_path", base.map(|b| &b.spec_path), &ours.spec_path, &theirs.spec_path, conflicts);
+    merged.depends_on = merge_field(id, "depends_on", base.map(|b| &b.depends_on), &ours.depends_on, &theirs.depends_on, conflicts);
+    merged.due_at_ms = merge_field(id, "due_at_ms", base.map(|b| &b.due_at_ms), &ours.due_at_ms, &theirs.due_at_ms, conflicts);
+    merged.notes = merge_notes(&ours.notes, &theirs.notes);
+    let base_children = base.map(|b| b.children.as_slice()).unwrap_or(&[]);
+    merged.children = merge_levels(base_children, &ours.children, &theirs.children, conflicts);
+    merged
+}
+
+fn merge_field<T: Clone + PartialEq + Debug>(id: &str, field: &str, base: Option<&T>, ours: &T, theirs: &T, conflicts: &mut Vec<Conflict>) -> T {
+    if ours == theirs || base == Some(theirs) { return ours.clone(); }
+    if base == Some(ours) { return theirs.clone(); }
+    conflicts.push(Conflict {
+        task_id: id.to_string(),
+        field: field.to_string(),
+        base: base.map(|b| format!("{:?}", b)),
+        ours: format!("{:?}", ours),
+        theirs: format!("{:?}", theirs),
+    });
+    ours.clone()
+}
+
+/// Notes are append-only, so both sides' notes are kept: the union, ordered
+/// by timestamp (ours first on ties), without duplicates.
+fn merge_notes(ours: &[(u64, String)], theirs: &[(u64, String)]) -> Vec<(u64, String)> {
+    let mut notes = ours.to_vec();
+    for note in theirs {
+        if !notes.contains(note) { notes.push(note.clone()); }
+    }
+    notes.sort_by_key(|(ts, _)| *ts);
+    notes
+}
+
+/// True if two nodes agree on every merged field and on all descendants.
+fn same_task(a: &TaskNode, b: &TaskNode) -> bool {
+    a.title == b.title && a.status == b.status && a.result == b.result && a.agent == b.agent
+        && a.spec_path == b.spec_path && a.depends_on == b.depends_on && a.due_at_ms == b.due_at_ms
+        && a.notes == b.notes && a.children.len() == b.children.len()
+        && a.children.iter().zip(&b.children).all(|(x, y)| x.id == y.id && same_task(x, y))
+}
+
+fn deleted_conflict(base: &TaskNode, ours: &str, theirs: &str) -> Conflict {
+    Conflict { task_id: base.id.clone(), field: "deleted".into(), base: None, ours: ours.into(), theirs: theirs.into() }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::types::task::TaskStatus;
     fn mt(id: &str, title: &str, source: TaskSource) -> TaskNode {
-        TaskNode { id: id.into(), title: title.into(), source, status: TaskStatus::Pending, result: None, agent: None, children: Vec::new(), spec_path: None }
+        TaskNode { id: id.into(), title: title.into(), source, status: TaskStatus::Pending, result: None, agent: None, children: Vec::new(), spec_path: None, depends_on: Vec::new(), notes: Vec::new(), due_at_ms: None }
     }
     #[test] fn merge_matching_sets_both() {
         let mut rm = vec![mt("1", "RM", TaskSource::Roadmap)];
@@ -60,4 +169,85 @@ mod tests {
         merge_task_trees(&mut rm, vec![fst]);
         assert_eq!(rm[0].spec_path.as_deref(), Some("/rm.md"));
     }
+    fn tree(nodes: Vec<TaskNode>) -> TaskTree {
+        let mut t = TaskTree::new(); for n in nodes { t.add_root(n); } t
+    }
+    #[test] fn three_way_independent_changes_merge_cleanly() {
+        let base = tree(vec![mt("1", "A", TaskSource::Roadmap), mt("2", "B", TaskSource::Roadmap)]);
+        let mut o1 = mt("1", "A", TaskSource::Roadmap); o1.status = TaskStatus::Completed;
+        let ours = tree(vec![o1, mt("2", "B", TaskSource::Roadmap)]);
+        let mut t2 = mt("2", "B renamed", TaskSource::Roadmap); t2.agent = Some("w1".into());
+        let theirs = tree(vec![mt("1", "A", TaskSource::Roadmap), t2, mt("3", "C", TaskSource::Roadmap)]);
+        let (merged, conflicts) = merge_three_way(&base, &ours, &theirs);
+        assert!(conflicts.is_empty());
+        assert_eq!(merged.roots().len(), 3);
+        assert_eq!(merged.get("1").unwrap().status, TaskStatus::Completed);
+        assert_eq!(merged.get("2").unwrap().title, "B renamed");
+        assert_eq!(merged.get("2").unwrap().agent.as_deref(), Some("w1"));
+        assert!(merged.get("3").is_some());
+    }
+    #[test] fn three_way_same_change_both_sides_is_not_conflict() {
+        let base = tree(vec![mt("1", "A", TaskSource::Roadmap)]);
+        let mut o = mt("1", "A", TaskSource::Roadmap); o.status = TaskStatus::Completed;
+        let t = o.clone();
+        let (merged, conflicts) = merge_three_way(&base, &tree(vec![o]), &tree(vec![t]));
+        assert!(conflicts.is_empty());
+        assert_eq!(merged.get("1").unwrap().status, TaskStatus::Completed);
+    }
+    #[test] fn three_way_true_conflict_reported() {
+        let base = tree(vec![mt("1", "A", TaskSource::Roadmap)]);
+        let ours = tree(vec![mt("1", "Ours", TaskSource::Roadmap)]);
+        let theirs = tree(vec![mt("1", "Theirs", TaskSource::Roadmap)]);
+        let (merged, conflicts) = merge_three_way(&base, &ours, &theirs);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].task_id, "1"); assert_eq!(conflicts[0].field, "title");
+        assert_eq!(conflicts[0].base.as_deref(), Some("\"A\""));
+        assert_eq!(merged.get("1").unwrap().title, "Ours");
+    }
+    #[test] fn three_way_recurses_into_children() {
+        let mut b = mt("1", "A", TaskSource::Roadmap); b.children.push(mt("1.1", "C", TaskSource::Roadmap));
+        let mut o = b.clone(); o.children[0].status = TaskStatus::Completed;
+        let mut t = b.clone(); t.children.push(mt("1.2", "D", TaskSource::Roadmap));
+        let (merged, conflicts) = merge_three_way(&tree(vec![b]), &tree(vec![o]), &tree(vec![t]));
+        assert!(conflicts.is_empty());
+        assert_eq!(merged.get("1.1").unwrap().status, TaskStatus::Completed);
+        assert!(merged.get("1.2").is_some());
+    }
+    #[test] fn three_way_unmodified_delete_is_applied() {
+        let base = tree(vec![mt("1", "A", TaskSource::Roadmap), mt("2", "B", TaskSource::Roadmap)]);
+        let ours = tree(vec![mt("1", "A", TaskSource::Roadmap)]);
+        let (merged, conflicts) = merge_three_way(&base, &ours, &base.clone());
+        assert!(conflicts.is_empty()); assert!(merged.get("2").is_none());
+    }
+    #[test] fn three_way_takes_deadline_set_only_in_theirs() {
+        let base = tree(vec![mt("1", "A", TaskSource::Roadmap)]);
+        let ours = tree(vec![mt("1", "A", TaskSource::Roadmap)]);
+        let mut t = mt("1", "A", TaskSource::Roadmap); t.due_at_ms = Some(1_700_000_000_000);
+        let (merged, conflicts) = merge_three_way(&base, &ours, &tree(vec![t.clone()]));
+        assert!(conflicts.is_empty());
+        assert_eq!(merged.get("1").unwrap().due_at_ms, Some(1_700_000_000_000));
+        // Ours deleting a task theirs gave a deadline is a modify/delete conflict.
+        let (_, conflicts) = merge_three_way(&base, &tree(vec![]), &tree(vec![t]));
+        assert_eq!(conflicts.len(), 1); assert_eq!(conflicts[0].field, "deleted");
+    }
+    #[test] fn three_way_keeps_notes_from_both_sides() {
+        let mut b = mt("1", "A", TaskSource::Roadmap); b.notes = vec![(100, "start".into())];
+        let mut o = b.clone(); o.notes.push((300, "ours later".into()));
+        let mut t = b.clone(); t.notes.push((200, "theirs earlier".into()));
+        let (merged, conflicts) = merge_three_way(&tree(vec![b.clone()]), &tree(vec![o]), &tree(vec![t.clone()]));
+        assert!(conflicts.is_empty());
+        let texts: Vec<&str> = merged.get("1").unwrap().notes.iter().map(|(_, n)| n.as_str()).collect();
+        assert_eq!(texts, vec!["start", "theirs earlier", "ours later"]);
+        // A note added on a side the other deleted counts as a modification.
+        let (_, conflicts) = merge_three_way(&tree(vec![b]), &tree(vec![]), &tree(vec![t]));
+        assert_eq!(conflicts.len(), 1);
+    }
+    #[test] fn three_way_delete_vs_modify_conflict() {
+        let base = tree(vec![mt("1", "A", TaskSource::Roadmap)]);
+        let ours = tree(vec![]);
+        let theirs = tree(vec![mt("1", "A2", TaskSource::Roadmap)]);
+        let (merged, conflicts) = merge_three_way(&base, &ours, &theirs);
+        assert_eq!(conflicts.len(), 1); assert_eq!(conflicts[0].field, "deleted");
+        assert!(merged.get("1").is_some());
+    }
 }
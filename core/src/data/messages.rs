@@ -1,10 +1,22 @@
+use std::collections::HashMap;
+
 use crate::types::message::Message;
 
 
 /// In-memory store for agent-to-agent messages with per-recipient FIFO delivery.
+///
+/// Delivery can be paced: with a minimum interval set, a recipient gets at
+/// most one message per interval, so a burst of queued messages is not
+/// typed into its pane all at once.
 #[derive(Debug, Clone)]
 pub struct MessageStore {
     messages: Vec<Message>,
+    /// Minimum delay between deliveries to one recipient (0 = unpaced).
+    min_interval_ms: u64,
+    /// Per-recipient overrides of `min_interval_ms`.
+    recipient_intervals: HashMap<String, u64>,
+    /// Time of the most recent delivery to each recipient.
+    last_delivery_ms: HashMap<String, u64>,
 }
 
 
@@ -13,14 +25,56 @@ impl MessageStore {
     pub fn new() -> Self {
         MessageStore {
             messages: Vec::new(),
+            min_interval_ms: 0,
+            recipient_intervals: HashMap::new(),
+            last_delivery_ms: HashMap::new(),
         }
     }
 
+    /// Set the default minimum delay between deliveries to one recipient.
+    pub fn set_min_interval(&mut self, interval_ms: u64) {
+        self.min_interval_ms = interval_ms;
+    }
+
+    /// Override the pacing interval for one recipient.
+    pub fn set_recipient_interval(&mut self, recipient: &str, interval_ms: u64) {
+        self.recipient_intervals
+            .insert(recipient.to_string(), interval_ms);
+    }
+
+    /// Pacing interval that applies to `recipient`.
+    pub fn interval_for(&self, recipient: &str) -> u64 {
+        self.recipient_intervals
+            .get(recipient)
+            .copied()
+            .unwrap_or(self.min_interval_ms)
+    }
+
     /// Enqueue a message. It should have `delivered_at_ms = None`.
     pub fn enqueue(&mut self, msg: Message) {
         self.messages.push(msg);
     }
 
+    /// Enqueue a message unless an identical one is already pending.
+    ///
+    /// A message is a duplicate when a pending message has the same
+    /// recipient and text. Returns true if the message was queued and
+    /// false if it was suppressed as a duplicate.
+    pub fn enqueue_dedup(&mut self, msg: Message) -> bool {
+        if self.has_pending_duplicate(&msg.recipient, &msg.text) {
+            return false;
+        }
+        self.messages.push(msg);
+        true
+    }
+
+    /// Whether a pending message with this recipient and text exists.
+    pub fn has_pending_duplicate(&self, recipient: &str, text: &str) -> bool {
+        self.messages
+            .iter()
+            .any(|m| m.delivered_at_ms.is_none() && m.recipient == recipient && m.text == text)
+    }
+
     /// Return references to all pending (undelivered) messages for a given agent.
     pub fn pending_for(&self, agent: &str) -> Vec<&Message> {
         self.messages
@@ -39,7 +93,35 @@ impl MessageStore {
             .position(|m| m.recipient == agent && m.delivered_at_ms.is_none())?;
         // Mark as delivered with a simple timestamp (milliseconds since we don't
         // have a clock dependency, we use a sentinel value; callers can overwrite).
-        self.messages[pos].delivered_at_ms = Some(now_ms());
+        let now = now_ms();
+        self.messages[pos].delivered_at_ms = Some(now);
+        self.last_delivery_ms.insert(agent.to_string(), now);
+        Some(self.messages[pos].clone())
+    }
+
+    /// The oldest pending message for `recipient`, if its pacing interval
+    /// has elapsed since the last delivery to it.
+    pub fn next_deliverable(&self, recipient: &str, now_ms: u64) -> Option<&Message> {
+        if let Some(&last) = self.last_delivery_ms.get(recipient) {
+            if now_ms.saturating_sub(last) < self.interval_for(recipient) {
+                return None;
+            }
+        }
+        self.messages
+            .iter()
+            .find(|m| m.recipient == recipient && m.delivered_at_ms.is_none())
+    }
+
+    /// Deliver the oldest pending message for `recipient` if pacing allows,
+    /// stamping it with `now_ms`.
+    pub fn deliver_paced(&mut self, recipient: &str, now_ms: u64) -> Option<Message> {
+        self.next_deliverable(recipient, now_ms)?;
+        let pos = self
+            .messages
+            .iter()
+            .position(|m| m.recipient == recipient && m.delivered_at_ms.is_none())?;
+        self.messages[pos].delivered_at_ms = Some(now_ms);
+        self.last_delivery_ms.insert(recipient.to_string(), now_ms);
         Some(self.messages[pos].clone())
     }
 
@@ -127,6 +209,28 @@ mod tests {
         assert!(store.deliver("w1").is_none());
     }
 
+    #[test]
+    fn enqueue_dedup_suppresses_identical_pending() {
+        let mut store = MessageStore::new();
+        assert!(store.enqueue_dedup(make_msg("pm", "w1", "run tests")));
+        assert!(!store.enqueue_dedup(make_msg("user", "w1", "run tests")));
+        assert_eq!(store.pending_for("w1").len(), 1);
+
+        // Different recipient or text is not a duplicate.
+        assert!(store.enqueue_dedup(make_msg("pm", "w2", "run tests")));
+        assert!(store.enqueue_dedup(make_msg("pm", "w1", "run lint")));
+        assert_eq!(store.len(), 3);
+    }
+
+    #[test]
+    fn enqueue_dedup_allows_repeat_after_delivery() {
+        let mut store = MessageStore::new();
+        assert!(store.enqueue_dedup(make_msg("pm", "w1", "status?")));
+        store.deliver("w1");
+        assert!(store.enqueue_dedup(make_msg("pm", "w1", "status?")));
+        assert_eq!(store.pending_for("w1").len(), 1);
+    }
+
     #[test]
     fn deliver_empty_returns_none() {
         let mut store = MessageStore::new();
@@ -179,4 +283,58 @@ mod tests {
         let msg = store.deliver("w1").unwrap();
         assert!(msg.delivered_at_ms.unwrap() > 0);
     }
+
+    #[test]
+    fn paced_delivery_holds_second_message_within_interval() {
+        let mut store = MessageStore::new();
+        store.set_min_interval(1000);
+        store.enqueue(make_msg("pm", "w1", "first"));
+        store.enqueue(make_msg("pm", "w1", "second"));
+
+        assert_eq!(store.next_deliverable("w1", 5000).unwrap().text, "first");
+        assert_eq!(store.deliver_paced("w1", 5000).unwrap().text, "first");
+
+        assert!(store.next_deliverable("w1", 5500).is_none());
+        assert!(store.deliver_paced("w1", 5999).is_none());
+        assert_eq!(store.pending_for("w1").len(), 1);
+    }
+
+    #[test]
+    fn paced_delivery_releases_after_interval() {
+        let mut store = MessageStore::new();
+        store.set_min_interval(1000);
+        store.enqueue(make_msg("pm", "w1", "first"));
+        store.enqueue(make_msg("pm", "w1", "second"));
+        store.deliver_paced("w1", 5000).unwrap();
+
+        let second = store.deliver_paced("w1", 6000).unwrap();
+        assert_eq!(second.text, "second");
+        assert_eq!(second.delivered_at_ms, Some(6000));
+    }
+
+    #[test]
+    fn pacing_is_per_recipient() {
+        let mut store = MessageStore::new();
+        store.set_min_interval(1000);
+        store.set_recipient_interval("w2", 0);
+        store.enqueue(make_msg("pm", "w1", "a"));
+        store.enqueue(make_msg("pm", "w2", "b"));
+        store.enqueue(make_msg("pm", "w2", "c"));
+
+        store.deliver_paced("w1", 5000).unwrap();
+        assert!(store.next_deliverable("w2", 5000).is_some());
+        store.deliver_paced("w2", 5000).unwrap();
+        assert_eq!(store.deliver_paced("w2", 5000).unwrap().text, "c");
+        assert_eq!(store.interval_for("w1"), 1000);
+        assert_eq!(store.interval_for("w2"), 0);
+    }
+
+    #[test]
+    fn unpaced_store_delivers_back_to_back() {
+        let mut store = MessageStore::new();
+        store.enqueue(make_msg("pm", "w1", "a"));
+        store.enqueue(make_msg("pm", "w1", "b"));
+        assert!(store.deliver_paced("w1", 100).is_some());
+        assert!(store.deliver_paced("w1", 100).is_some());
+    }
 }
@@ -27,7 +27,7 @@ pub use task_tree::TaskTree;
 // M2 re-exports
 pub use config::layout_expr::{parse_layout_expr, serialize_layout_expr};
 pub use config::tiles::TileRegistry;
-pub use merge::merge_task_trees;
+pub use merge::{merge_task_trees, merge_three_way, Conflict};
 pub use scanner::scan_tasks;
 
 
@@ -44,6 +44,8 @@ pub struct Data {
     layouts: HashMap<String, String>,
     /// Roadmap file paths loaded via `roadmap.load`, used for write-back.
     roadmap_paths: Vec<PathBuf>,
+    /// Problems `settings::validate` found in the settings file last loaded.
+    settings_warnings: Vec<String>,
 }
 
 
@@ -53,12 +55,19 @@ impl Data {
     /// directory structure and writes defaults before loading proceeds.
     /// Also loads folders from `config_dir/folders.yaml` if present.
     pub fn new(config_dir: &Path) -> Result<Data, String> {
+        let settings_path = config_dir.join("settings.yaml");
+        // Validate the file as the user wrote it: an upgrade below rewrites
+        // it and would silently drop unknown keys.
+        let user_settings = std::fs::read_to_string(&settings_path).ok();
+
         // Ensure CMX is installed (creates dirs, writes defaults if needed)
         crate::install::ensure_installed(config_dir)?;
 
         // Now proceed with loading — settings.yaml is guaranteed to exist
-        let settings_path = config_dir.join("settings.yaml");
         let settings = settings::load(&settings_path)?;
+        let settings_warnings = user_settings
+            .map(|content| settings::validate(&settings::raw_document(&content)))
+            .unwrap_or_default();
 
         let folders_path = config_dir.join("folders.yaml");
         let folders = if folders_path.exists() {
@@ -67,15 +76,19 @@ impl Data {
             FolderRegistry::new()
         };
 
+        let mut messages = MessageStore::new();
+        messages.set_min_interval(settings.message_interval);
+
         Ok(Data {
             settings,
             agents: AgentRegistry::new(),
             tasks: TaskTree::new(),
             folders,
-            messages: MessageStore::new(),
+            messages,
             config_dir: config_dir.to_path_buf(),
             layouts: HashMap::new(),
             roadmap_paths: Vec::new(),
+            settings_warnings,
         })
     }
 
@@ -83,6 +96,18 @@ impl Data {
         &self.settings
     }
 
+    /// Warnings from validating the settings file last loaded, either
+    /// settings.yaml when this Data was created or a later `config.load`.
+    /// Validation is advisory; the settings were loaded regardless.
+    pub fn settings_warnings(&self) -> &[String] {
+        &self.settings_warnings
+    }
+
+    /// Replace the settings warnings after settings are reloaded.
+    pub fn set_settings_warnings(&mut self, warnings: Vec<String>) {
+        self.settings_warnings = warnings;
+    }
+
     pub fn agents(&self) -> &AgentRegistry {
         &self.agents
     }
@@ -149,6 +174,63 @@ impl Data {
 }
 
 
+/// Replace `path` with `content` without ever leaving a partial file: the
+/// content goes to a synced temporary file in the same directory, which is
+/// then renamed over `path`, and the directory is synced so the rename
+/// survives a crash. On failure the temporary file is removed and `path` is
+/// untouched.
+pub fn write_atomic(path: &Path, content: &str) -> Result<(), String> {
+    let tmp_path = temp_sibling(path)?;
+    if let Err(e) = write_synced(&tmp_path, content) {
+        let _ = std::fs::remove_file(&tmp_path);
+        return Err(e);
+    }
+    std::fs::rename(&tmp_path, path).map_err(|e| {
+        let _ = std::fs::remove_file(&tmp_path);
+        format!("cannot rename {} to {}: {}", tmp_path.display(), path.display(), e)
+    })?;
+    sync_parent_dir(path)
+}
+
+/// A fresh temp sibling for staging a write to `path`:
+/// `<dir>/.<name>.<pid>.<n>.tmp`. The name is unique per process and call,
+/// so concurrent writers of the same file never share a temp file.
+pub fn temp_sibling(path: &Path) -> Result<PathBuf, String> {
+    static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| format!("invalid path: {}", path.display()))?;
+    let n = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    Ok(path.with_file_name(format!(
+        ".{}.{}.{}.tmp",
+        file_name.to_string_lossy(),
+        std::process::id(),
+        n
+    )))
+}
+
+/// Create or truncate `path`, write `content` and fsync it.
+pub fn write_synced(path: &Path, content: &str) -> Result<(), String> {
+    use std::io::Write;
+    let mut file = std::fs::File::create(path)
+        .map_err(|e| format!("cannot write {}: {}", path.display(), e))?;
+    file.write_all(content.as_bytes())
+        .and_then(|_| file.sync_all())
+        .map_err(|e| format!("cannot write {}: {}", path.display(), e))
+}
+
+/// Fsync the directory holding `path`, making a rename into it durable.
+pub fn sync_parent_dir(path: &Path) -> Result<(), String> {
+    let dir = match path.parent() {
+        Some(d) if !d.as_os_str().is_empty() => d,
+        _ => Path::new("."),
+    };
+    std::fs::File::open(dir)
+        .and_then(|d| d.sync_all())
+        .map_err(|e| format!("cannot sync {}: {}", dir.display(), e))
+}
+
+
 /// Convenience wrapper for parsing/serializing Roadmap.md files into TaskNode trees.
 pub struct RoadmapParser;
 
@@ -190,10 +272,57 @@ mod tests {
         let data = Data::new(&dir).unwrap();
         assert_eq!(data.settings().health_check_interval, 9999);
         assert_eq!(data.settings().max_retries, 11);
+        assert!(data.settings_warnings().is_empty());
 
         let _ = std::fs::remove_dir_all(&dir);
     }
 
+    #[test]
+    fn data_new_collects_settings_warnings() {
+        let dir = std::env::temp_dir().join("cmx_test_data_settings_warnings");
+        let _ = std::fs::remove_dir_all(&dir);
+        let _ = std::fs::create_dir_all(&dir);
+        std::fs::write(
+            dir.join("settings.yaml"),
+            "health_check_intervall: 9999\nmax_retries: 101\n",
+        )
+        .unwrap();
+
+        let data = Data::new(&dir).unwrap();
+        assert_eq!(data.settings().max_retries, 101);
+        let warnings = data.settings_warnings();
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings[0].contains("health_check_intervall"));
+        assert!(warnings[1].contains("max_retries"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn write_atomic_replaces_file_and_leaves_no_temp() {
+        let dir = std::env::temp_dir().join(format!("cmx_test_write_atomic_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("state.yaml");
+
+        write_atomic(&path, "one").unwrap();
+        write_atomic(&path, "two").unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "two");
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn temp_sibling_names_are_unique() {
+        let path = Path::new("/tmp/cmx/settings.yaml");
+        let a = temp_sibling(path).unwrap();
+        let b = temp_sibling(path).unwrap();
+        assert_ne!(a, b);
+        assert_eq!(a.parent(), path.parent());
+        assert!(a.file_name().unwrap().to_string_lossy().starts_with(".settings.yaml."));
+    }
+
     #[test]
     fn data_getters_work() {
         let data = Data::new(Path::new("/tmp/cmx_nonexistent_test_dir_67890")).unwrap();
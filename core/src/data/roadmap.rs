@@ -50,6 +50,9 @@ pub fn parse(content: &str) -> Result<Vec<TaskNode>, String> {
             agent: None,
             children: Vec::new(),
             spec_path: None,
+            depends_on: Vec::new(),
+            notes: Vec::new(),
+            due_at_ms: None,
         };
 
         items.push((depth, node));
@@ -21,7 +21,7 @@ fn scan_inner(project_path: &Path, anchor_name: Option<&str>) -> Result<Vec<Task
                 if anchor.exists() {
                     let mut task = TaskNode { id: number.to_string(), title, source: TaskSource::Filesystem,
                         status: TaskStatus::Pending, result: None, agent: None, children: Vec::new(),
-                        spec_path: Some(anchor.to_string_lossy().to_string()) };
+                        spec_path: Some(anchor.to_string_lossy().to_string()), depends_on: Vec::new(), notes: Vec::new(), due_at_ms: None };
                     if let Ok(sub) = scan_inner(&path, Some(&name)) { task.children = sub; }
                     tasks.push(task);
                 }
@@ -31,7 +31,7 @@ fn scan_inner(project_path: &Path, anchor_name: Option<&str>) -> Result<Vec<Task
                 if dir_names.contains(&stem.to_string()) { continue; }
                 tasks.push(TaskNode { id: number.to_string(), title, source: TaskSource::Filesystem,
                     status: TaskStatus::Pending, result: None, agent: None, children: Vec::new(),
-                    spec_path: Some(path.to_string_lossy().to_string()) });
+                    spec_path: Some(path.to_string_lossy().to_string()), depends_on: Vec::new(), notes: Vec::new(), due_at_ms: None });
             }
         }
     }
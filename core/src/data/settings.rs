@@ -1,7 +1,8 @@
 use std::collections::HashMap;
 use std::path::Path;
 
-use crate::types::config::{BackoffStrategy, PoolConfigYaml, Settings};
+use crate::library::ConflictStrategy;
+use crate::types::config::{BackoffStrategy, PoolConfigYaml, SessionBackendKind, Settings};
 
 
 /// Returns sensible defaults for all settings fields.
@@ -23,6 +24,17 @@ pub fn default_settings() -> Settings {
         pool_configs: HashMap::new(),
         pool_auto_expand: false,
         agent_launch_command: "claude".into(),
+        auto_snapshot_on_change: false,
+        heartbeat_marker: crate::monitor::heartbeat::DEFAULT_HEARTBEAT_MARKER.into(),
+        heartbeat_marker_overrides: HashMap::new(),
+        heartbeat_patterns: HashMap::new(),
+        stall_after_unhealthy: crate::types::config::default_stall_after_unhealthy(),
+        clear_after_healthy: crate::types::config::default_clear_after_healthy(),
+        message_interval: 0,
+        shutdown_keys: crate::types::config::default_shutdown_keys(),
+        shutdown_grace_ms: crate::types::config::default_shutdown_grace_ms(),
+        session_backend: SessionBackendKind::default(),
+        skill_conflict_strategy: ConflictStrategy::default(),
     }
 }
 
@@ -37,11 +49,25 @@ pub fn load(path: &Path) -> Result<Settings, String> {
 }
 
 
-/// Save `Settings` to a YAML-like key:value file.
+/// Save `Settings` to a YAML-like key:value file. The write is atomic (see
+/// `data::write_atomic`), and an existing file is first copied to
+/// `<path>.bak`.
 pub fn save(path: &Path, settings: &Settings) -> Result<(), String> {
     let content = serialize(settings);
-    std::fs::write(path, content)
-        .map_err(|e| format!("cannot write {}: {}", path.display(), e))
+    if path.is_file() {
+        let backup = backup_path(path);
+        std::fs::copy(path, &backup)
+            .map_err(|e| format!("cannot back up {} to {}: {}", path.display(), backup.display(), e))?;
+    }
+    crate::data::write_atomic(path, &content)
+}
+
+
+/// Where `save` keeps the previous copy of `path`.
+pub fn backup_path(path: &Path) -> std::path::PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".bak");
+    path.with_file_name(name)
 }
 
 
@@ -92,10 +118,28 @@ pub fn parse(content: &str) -> Result<Settings, String> {
                         "size" => entry.size = Some(parse_u32("pool size", &val)?),
                         "path" => entry.path = Some(unquote(&val)),
                         "max_size" => entry.max_size = Some(parse_u32("pool max_size", &val)?),
+                        "min_idle" => entry.min_idle = Some(parse_u32("pool min_idle", &val)?),
                         _ => {} // Ignore unknown pool fields
                     }
                 }
                 current_key = Some(key);
+            } else if let Some(role) = key.strip_prefix("heartbeat_marker.") {
+                // Per-role override: heartbeat_marker.<role>: "<marker>"
+                if !role.is_empty() {
+                    s.heartbeat_marker_overrides.insert(role.to_string(), unquote(&val));
+                }
+                current_key = Some(key);
+            } else if let Some(agent_type) = key.strip_prefix("heartbeat_pattern.") {
+                // Per-agent-type prompt regex: heartbeat_pattern.<type>: "<regex>".
+                // Known types are stored under their canonical name; others
+                // are kept as written so the monitor can warn about them.
+                if !agent_type.is_empty() {
+                    let name = crate::types::agent::AgentType::parse(agent_type)
+                        .map(|t| t.as_str().to_string())
+                        .unwrap_or_else(|_| agent_type.to_string());
+                    s.heartbeat_patterns.insert(name, unquote(&val));
+                }
+                current_key = Some(key);
             } else if val.is_empty() {
                 // This key introduces a list
                 current_key = Some(key);
@@ -121,6 +165,7 @@ pub fn parse(content: &str) -> Result<Settings, String> {
                 size,
                 path,
                 max_size: partial.max_size,
+                min_idle: partial.min_idle,
             });
         }
     }
@@ -129,11 +174,151 @@ pub fn parse(content: &str) -> Result<Settings, String> {
 }
 
 
+/// Top-level keys understood by `parse`, besides the `pool.<role>.<field>`
+/// `heartbeat_marker.<role>` and `heartbeat_pattern.<type>` families.
+/// Read off `serialize(&default_settings())`, which writes every plain
+/// key, so a new setting is known as soon as it is serialized.
+fn known_keys() -> Vec<String> {
+    serialize(&default_settings())
+        .lines()
+        .filter(|line| !line.starts_with(' '))
+        .filter_map(|line| line.split_once(':').map(|(key, _)| key))
+        .filter(|key| !key.contains('.'))
+        .map(String::from)
+        .collect()
+}
+
+/// Fields accepted after `pool.<role>.`.
+const POOL_FIELDS: &[&str] = &["size", "path", "max_size", "min_idle"];
+
+/// Upper bound for `max_retries` and `ssh_retries`.
+const MAX_RETRIES_LIMIT: u32 = 100;
+
+/// Lower bound for `health_check_interval`, in milliseconds.
+const MIN_HEALTH_CHECK_INTERVAL_MS: u64 = 100;
+
+
+/// Read settings text into a raw document for `validate`, using the same
+/// line rules as `parse`: each `key: value` becomes a string entry and a
+/// bare `key:` followed by `- item` lines becomes a sequence. The settings
+/// format is not strict YAML (e.g. `"\$"` in the default prompt pattern),
+/// so this never fails.
+pub fn raw_document(content: &str) -> serde_yaml::Value {
+    use serde_yaml::{Mapping, Value};
+    let mut map = Mapping::new();
+    let mut list_key: Option<String> = None;
+    for raw_line in content.lines() {
+        let line = raw_line.trim_end();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if line.starts_with("  - ") || line.starts_with("- ") {
+            let item = line.trim_start_matches("  - ").trim_start_matches("- ").trim();
+            if let Some(Value::Sequence(items)) =
+                list_key.as_ref().and_then(|k| map.get_mut(k.as_str()))
+            {
+                items.push(Value::String(unquote(item)));
+            }
+            continue;
+        }
+        if let Some((key, val)) = line.split_once(':') {
+            let key = key.trim().to_string();
+            let val = val.trim();
+            if val.is_empty() {
+                map.insert(Value::String(key.clone()), Value::Sequence(Vec::new()));
+                list_key = Some(key);
+            } else {
+                map.insert(Value::String(key), Value::String(unquote(val)));
+                list_key = None;
+            }
+        }
+    }
+    Value::Mapping(map)
+}
+
+
+/// Check a parsed settings document for keys `parse` would ignore and for
+/// values outside their sensible range. Returns one human-readable warning
+/// per problem; an empty list means the document looks fine. Never fails:
+/// `parse` still decides what is loaded.
+pub fn validate(raw: &serde_yaml::Value) -> Vec<String> {
+    let map = match raw {
+        serde_yaml::Value::Mapping(map) => map,
+        serde_yaml::Value::Null => return Vec::new(),
+        _ => return vec!["settings: expected a mapping of key: value pairs".into()],
+    };
+    let known = known_keys();
+    let mut warnings = Vec::new();
+    for (key, value) in map {
+        let key = match key.as_str() {
+            Some(k) => k,
+            None => {
+                warnings.push(format!("settings: ignoring non-string key {:?}", key));
+                continue;
+            }
+        };
+        if !is_known_key(&known, key) {
+            warnings.push(format!("settings: unknown key '{}' (ignored)", key));
+            continue;
+        }
+        let text = match value {
+            serde_yaml::Value::String(s) => s.clone(),
+            serde_yaml::Value::Number(n) => n.to_string(),
+            _ => continue,
+        };
+        match key {
+            "max_retries" | "ssh_retries" => {
+                if let Ok(n) = parse_u32(key, &text) {
+                    if n > MAX_RETRIES_LIMIT {
+                        warnings.push(format!(
+                            "settings: {} is {}, above the limit of {}",
+                            key, n, MAX_RETRIES_LIMIT
+                        ));
+                    }
+                }
+            }
+            "health_check_interval" => {
+                if let Ok(ms) = parse_duration_ms(key, &text) {
+                    if ms < MIN_HEALTH_CHECK_INTERVAL_MS {
+                        warnings.push(format!(
+                            "settings: {} is {}ms, below the minimum of {}ms",
+                            key, ms, MIN_HEALTH_CHECK_INTERVAL_MS
+                        ));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    warnings
+}
+
+fn is_known_key(known: &[String], key: &str) -> bool {
+    if known.iter().any(|k| k == key) {
+        return true;
+    }
+    if let Some(role) = key.strip_prefix("heartbeat_marker.") {
+        return !role.is_empty();
+    }
+    if let Some(agent_type) = key.strip_prefix("heartbeat_pattern.") {
+        return crate::types::agent::AgentType::parse(agent_type).is_ok();
+    }
+    if let Some(rest) = key.strip_prefix("pool.") {
+        return match rest.split_once('.') {
+            Some((role, field)) => !role.is_empty() && POOL_FIELDS.contains(&field),
+            None => false,
+        };
+    }
+    false
+}
+
+
 /// Helper struct for accumulating pool config during parsing.
 struct PartialPoolConfig {
     size: Option<u32>,
     path: Option<String>,
     max_size: Option<u32>,
+    min_idle: Option<u32>,
 }
 
 impl PartialPoolConfig {
@@ -142,6 +327,7 @@ impl PartialPoolConfig {
             size: None,
             path: None,
             max_size: None,
+            min_idle: None,
         }
     }
 }
@@ -153,16 +339,19 @@ fn apply_scalar(s: &mut Settings, key: &str, val: &str) -> Result<(), String> {
             s.version = unquote(val);
         }
         "health_check_interval" => {
-            s.health_check_interval = parse_u64(key, val)?;
+            s.health_check_interval = parse_duration_ms(key, val)?;
         }
         "heartbeat_timeout" => {
-            s.heartbeat_timeout = parse_u64(key, val)?;
+            s.heartbeat_timeout = parse_duration_ms(key, val)?;
         }
         "message_timeout" => {
-            s.message_timeout = parse_u64(key, val)?;
+            s.message_timeout = parse_duration_ms(key, val)?;
+        }
+        "message_interval" => {
+            s.message_interval = parse_duration_ms(key, val)?;
         }
         "snapshot_interval" => {
-            s.snapshot_interval = parse_u64(key, val)?;
+            s.snapshot_interval = parse_duration_ms(key, val)?;
         }
         "project_root" => {
             s.project_root = unquote(val);
@@ -170,6 +359,12 @@ fn apply_scalar(s: &mut Settings, key: &str, val: &str) -> Result<(), String> {
         "ready_prompt_pattern" => {
             s.ready_prompt_pattern = unquote(val);
         }
+        "shutdown_keys" => {
+            s.shutdown_keys = unquote(val);
+        }
+        "shutdown_grace_ms" => {
+            s.shutdown_grace_ms = parse_duration_ms(key, val)?;
+        }
         "max_retries" => {
             s.max_retries = parse_u32(key, val)?;
         }
@@ -185,7 +380,7 @@ fn apply_scalar(s: &mut Settings, key: &str, val: &str) -> Result<(), String> {
             s.ssh_retries = parse_u32(key, val)?;
         }
         "escalation_timeout" => {
-            s.escalation_timeout = parse_u64(key, val)?;
+            s.escalation_timeout = parse_duration_ms(key, val)?;
         }
         "pool_auto_expand" => {
             s.pool_auto_expand = match val.to_lowercase().as_str() {
@@ -194,9 +389,27 @@ fn apply_scalar(s: &mut Settings, key: &str, val: &str) -> Result<(), String> {
                 _ => return Err(format!("invalid bool for pool_auto_expand: {}", val)),
             };
         }
+        "auto_snapshot_on_change" => {
+            s.auto_snapshot_on_change = parse_bool(key, val)?;
+        }
         "agent_launch_command" => {
             s.agent_launch_command = unquote(val);
         }
+        "heartbeat_marker" => {
+            s.heartbeat_marker = unquote(val);
+        }
+        "stall_after_unhealthy" => {
+            s.stall_after_unhealthy = parse_u32(key, val)?;
+        }
+        "clear_after_healthy" => {
+            s.clear_after_healthy = parse_u32(key, val)?;
+        }
+        "session_backend" => {
+            s.session_backend = SessionBackendKind::parse(&unquote(val))?;
+        }
+        "skill_conflict_strategy" => {
+            s.skill_conflict_strategy = ConflictStrategy::parse(&unquote(val))?;
+        }
         _ => {
             // Unknown keys are silently ignored for forward-compatibility
         }
@@ -210,7 +423,7 @@ fn apply_list(s: &mut Settings, key: &str, items: &[String]) -> Result<(), Strin
         "ssh_backoff" => {
             s.ssh_backoff = items
                 .iter()
-                .map(|v| parse_u64("ssh_backoff item", v))
+                .map(|v| parse_duration_ms("ssh_backoff item", v))
                 .collect::<Result<Vec<u64>, String>>()?;
         }
         "alert_targets" => {
@@ -224,9 +437,40 @@ fn apply_list(s: &mut Settings, key: &str, items: &[String]) -> Result<(), Strin
 }
 
 
-fn parse_u64(key: &str, val: &str) -> Result<u64, String> {
-    val.parse::<u64>()
-        .map_err(|_| format!("invalid u64 for {}: {}", key, val))
+/// Parse a duration setting into milliseconds. Accepts a bare integer
+/// (already ms) or a number with an `ms`, `s`, `m`, or `h` suffix, e.g.
+/// `"30s"`, `"2m"`, `"1h"`. Quotes around the value are ignored.
+pub fn parse_duration_ms(key: &str, val: &str) -> Result<u64, String> {
+    let raw = unquote(val);
+    let err = || {
+        format!(
+            "invalid duration for {}: {} (expected ms or a number with ms/s/m/h suffix)",
+            key, val
+        )
+    };
+    let (digits, factor) = if let Some(n) = raw.strip_suffix("ms") {
+        (n, 1)
+    } else if let Some(n) = raw.strip_suffix('s') {
+        (n, 1_000)
+    } else if let Some(n) = raw.strip_suffix('m') {
+        (n, 60_000)
+    } else if let Some(n) = raw.strip_suffix('h') {
+        (n, 3_600_000)
+    } else {
+        (raw.as_str(), 1)
+    };
+    let n = digits.trim().parse::<u64>().map_err(|_| err())?;
+    n.checked_mul(factor).ok_or_else(err)
+}
+
+
+/// Parse a boolean setting (`true`/`yes`/`1` or `false`/`no`/`0`).
+pub fn parse_bool(key: &str, val: &str) -> Result<bool, String> {
+    match val.to_lowercase().as_str() {
+        "true" | "yes" | "1" => Ok(true),
+        "false" | "no" | "0" => Ok(false),
+        _ => Err(format!("invalid bool for {}: {}", key, val)),
+    }
 }
 
 
@@ -257,12 +501,15 @@ pub fn serialize(s: &Settings) -> String {
     out.push_str(&format!("health_check_interval: {}\n", s.health_check_interval));
     out.push_str(&format!("heartbeat_timeout: {}\n", s.heartbeat_timeout));
     out.push_str(&format!("message_timeout: {}\n", s.message_timeout));
+    out.push_str(&format!("message_interval: {}\n", s.message_interval));
     out.push_str(&format!("snapshot_interval: {}\n", s.snapshot_interval));
     out.push_str(&format!("project_root: \"{}\"\n", s.project_root));
     out.push_str(&format!(
         "ready_prompt_pattern: \"{}\"\n",
         s.ready_prompt_pattern
     ));
+    out.push_str(&format!("shutdown_keys: \"{}\"\n", s.shutdown_keys));
+    out.push_str(&format!("shutdown_grace_ms: {}\n", s.shutdown_grace_ms));
     out.push_str(&format!("max_retries: {}\n", s.max_retries));
     let bs = match s.backoff_strategy {
         BackoffStrategy::Exponential => "exponential",
@@ -282,6 +529,28 @@ pub fn serialize(s: &Settings) -> String {
     out.push_str(&format!("escalation_timeout: {}\n", s.escalation_timeout));
     out.push_str(&format!("pool_auto_expand: {}\n", s.pool_auto_expand));
     out.push_str(&format!("agent_launch_command: \"{}\"\n", s.agent_launch_command));
+    out.push_str(&format!("auto_snapshot_on_change: {}\n", s.auto_snapshot_on_change));
+    out.push_str(&format!("heartbeat_marker: \"{}\"\n", s.heartbeat_marker));
+    out.push_str(&format!("stall_after_unhealthy: {}\n", s.stall_after_unhealthy));
+    out.push_str(&format!("clear_after_healthy: {}\n", s.clear_after_healthy));
+    out.push_str(&format!("session_backend: {}\n", s.session_backend.as_str()));
+    out.push_str(&format!("skill_conflict_strategy: {}\n", s.skill_conflict_strategy));
+    let mut marker_roles: Vec<&String> = s.heartbeat_marker_overrides.keys().collect();
+    marker_roles.sort();
+    for role in marker_roles {
+        out.push_str(&format!(
+            "heartbeat_marker.{}: \"{}\"\n",
+            role, s.heartbeat_marker_overrides[role]
+        ));
+    }
+    let mut pattern_types: Vec<&String> = s.heartbeat_patterns.keys().collect();
+    pattern_types.sort();
+    for agent_type in pattern_types {
+        out.push_str(&format!(
+            "heartbeat_pattern.{}: \"{}\"\n",
+            agent_type, s.heartbeat_patterns[agent_type]
+        ));
+    }
     // Serialize pool configs as pool.<role>.<field> keys
     let mut roles: Vec<&String> = s.pool_configs.keys().collect();
     roles.sort();
@@ -292,6 +561,9 @@ pub fn serialize(s: &Settings) -> String {
         if let Some(max) = cfg.max_size {
             out.push_str(&format!("pool.{}.max_size: {}\n", role, max));
         }
+        if let Some(min_idle) = cfg.min_idle {
+            out.push_str(&format!("pool.{}.min_idle: {}\n", role, min_idle));
+        }
     }
     out
 }
@@ -385,7 +657,56 @@ max_retries: 2
         let text = "health_check_interval: not_a_number\n";
         let result = parse(text);
         assert!(result.is_err());
-        assert!(result.unwrap_err().contains("invalid u64"));
+        assert!(result.unwrap_err().contains("invalid duration for health_check_interval"));
+    }
+
+    #[test]
+    fn duration_bare_integer_is_ms() {
+        assert_eq!(parse_duration_ms("heartbeat_timeout", "30000").unwrap(), 30000);
+    }
+
+    #[test]
+    fn duration_suffixes() {
+        assert_eq!(parse_duration_ms("k", "250ms").unwrap(), 250);
+        assert_eq!(parse_duration_ms("k", "30s").unwrap(), 30_000);
+        assert_eq!(parse_duration_ms("k", "2m").unwrap(), 120_000);
+        assert_eq!(parse_duration_ms("k", "1h").unwrap(), 3_600_000);
+        assert_eq!(parse_duration_ms("k", "\"5m\"").unwrap(), 300_000);
+    }
+
+    #[test]
+    fn duration_invalid_names_field() {
+        let err = parse_duration_ms("heartbeat_timeout", "soon").unwrap_err();
+        assert!(err.contains("heartbeat_timeout"));
+        assert!(err.contains("soon"));
+        assert!(parse_duration_ms("k", "5d").is_err());
+        assert!(parse_duration_ms("k", "s").is_err());
+        assert!(parse_duration_ms("k", "-5s").is_err());
+    }
+
+    #[test]
+    fn parse_human_durations() {
+        let text = "\
+heartbeat_timeout: 30s
+message_timeout: \"2m\"
+escalation_timeout: 1h
+snapshot_interval: 90000
+ssh_backoff:
+  - 500ms
+  - 1s
+";
+        let s = parse(text).unwrap();
+        assert_eq!(s.heartbeat_timeout, 30_000);
+        assert_eq!(s.message_timeout, 120_000);
+        assert_eq!(s.escalation_timeout, 3_600_000);
+        assert_eq!(s.snapshot_interval, 90_000);
+        assert_eq!(s.ssh_backoff, vec![500, 1000]);
+    }
+
+    #[test]
+    fn parse_invalid_duration_rejected() {
+        let err = parse("heartbeat_timeout: 30 seconds\n").unwrap_err();
+        assert!(err.contains("invalid duration for heartbeat_timeout"));
     }
 
     #[test]
@@ -417,6 +738,92 @@ max_retries: 2
         assert_eq!(parsed, s);
     }
 
+    #[test]
+    fn parse_heartbeat_markers() {
+        let s = parse("heartbeat_marker: \"<<hb>>\"\nheartbeat_marker.pilot: \"PILOT-HB\"\n").unwrap();
+        assert_eq!(s.heartbeat_marker_for("worker"), "<<hb>>");
+        assert_eq!(s.heartbeat_marker_for("pilot"), "PILOT-HB");
+        assert_eq!(default_settings().heartbeat_marker_for("worker"), "HEARTBEAT");
+
+        let text = serialize(&s);
+        assert_eq!(parse(&text).unwrap(), s);
+    }
+
+    #[test]
+    fn heartbeat_patterns_round_trip() {
+        let mut s = default_settings();
+        s.heartbeat_patterns.insert("ssh".into(), r"^\[(?P<host>[^\]]+)\] \$\s*$".into());
+        let text = serialize(&s);
+        assert!(text.contains("heartbeat_pattern.ssh: "));
+        assert_eq!(parse(&text).unwrap(), s);
+        assert!(validate(&raw_document(&text)).is_empty());
+        assert!(!validate(&yaml("heartbeat_pattern.robot: x\n")).is_empty());
+        let upper = parse("heartbeat_pattern.SSH: x\n").unwrap();
+        assert_eq!(upper.heartbeat_patterns.get("ssh").map(String::as_str), Some("x"));
+    }
+
+    #[test]
+    fn parse_health_hysteresis() {
+        let s = parse("stall_after_unhealthy: 5\nclear_after_healthy: 4\n").unwrap();
+        assert_eq!(s.stall_after_unhealthy, 5);
+        assert_eq!(s.clear_after_healthy, 4);
+        assert_eq!(parse(&serialize(&s)).unwrap(), s);
+        assert!(parse("stall_after_unhealthy: often\n").is_err());
+    }
+
+    #[test]
+    fn parse_message_interval() {
+        let s = parse("message_interval: 1500ms\n").unwrap();
+        assert_eq!(s.message_interval, 1500);
+        assert_eq!(parse(&serialize(&s)).unwrap(), s);
+        assert_eq!(default_settings().message_interval, 0);
+    }
+
+    #[test]
+    fn parse_shutdown_keys() {
+        let s = parse("shutdown_keys: \"C-c\"\n").unwrap();
+        assert_eq!(s.shutdown_keys, "C-c");
+        assert_eq!(parse(&serialize(&s)).unwrap(), s);
+        assert_eq!(default_settings().shutdown_keys, "/exit");
+    }
+
+    #[test]
+    fn parse_shutdown_grace_ms() {
+        let s = parse("shutdown_grace_ms: 10s\n").unwrap();
+        assert_eq!(s.shutdown_grace_ms, 10_000);
+        assert_eq!(parse(&serialize(&s)).unwrap(), s);
+        assert_eq!(default_settings().shutdown_grace_ms, 5000);
+    }
+
+    #[test]
+    fn parse_session_backend() {
+        let s = parse("session_backend: Zellij\n").unwrap();
+        assert_eq!(s.session_backend, SessionBackendKind::Zellij);
+        assert_eq!(parse(&serialize(&s)).unwrap(), s);
+        assert_eq!(default_settings().session_backend, SessionBackendKind::Tmux);
+        assert!(parse("session_backend: screen\n").is_err());
+    }
+
+    #[test]
+    fn parse_skill_conflict_strategy() {
+        let s = parse("skill_conflict_strategy: newest-mtime\n").unwrap();
+        assert_eq!(s.skill_conflict_strategy, ConflictStrategy::NewestMtime);
+        let back = parse(&serialize(&s)).unwrap();
+        assert_eq!(back.skill_conflict_strategy, ConflictStrategy::NewestMtime);
+        assert_eq!(default_settings().skill_conflict_strategy, ConflictStrategy::HighestPriority);
+        assert!(parse("skill_conflict_strategy: random\n").is_err());
+    }
+
+    #[test]
+    fn parse_auto_snapshot_flag() {
+        let s = parse("auto_snapshot_on_change: yes\n").unwrap();
+        assert!(s.auto_snapshot_on_change);
+        let mut custom = default_settings();
+        custom.auto_snapshot_on_change = true;
+        assert_eq!(parse(&serialize(&custom)).unwrap(), custom);
+        assert!(parse("auto_snapshot_on_change: maybe\n").is_err());
+    }
+
     #[test]
     fn parse_empty_returns_defaults() {
         let s = parse("").unwrap();
@@ -485,14 +892,133 @@ pool.pilot.path: \"/tmp/pilot\"
             size: 3,
             path: "/tmp/work".into(),
             max_size: Some(6),
+            min_idle: Some(1),
         });
         s.pool_configs.insert("pilot".into(), PoolConfigYaml {
             size: 1,
             path: "/tmp/pilot".into(),
             max_size: None,
+            min_idle: None,
         });
         let text = serialize(&s);
         let parsed = parse(&text).unwrap();
         assert_eq!(parsed, s);
     }
+
+    #[test]
+    fn known_keys_follow_serialized_defaults() {
+        let known = known_keys();
+        for key in ["version", "ssh_backoff", "alert_targets", "skill_conflict_strategy"] {
+            assert!(known.iter().any(|k| k == key), "missing '{}'", key);
+        }
+        assert!(known.iter().all(|k| !k.contains('.')));
+    }
+
+    #[test]
+    fn every_serialized_key_is_known() {
+        let mut s = default_settings();
+        s.heartbeat_marker_overrides.insert("worker".into(), "PULSE".into());
+        s.heartbeat_patterns.insert("ssh".into(), "^\\$ $".into());
+        s.pool_configs.insert("worker".into(), PoolConfigYaml {
+            size: 2,
+            path: "/tmp/work".into(),
+            max_size: Some(4),
+            min_idle: Some(1),
+        });
+        let known = known_keys();
+        let text = serialize(&s);
+        for line in text.lines().filter(|l| !l.starts_with(' ')) {
+            let key = line.split(':').next().unwrap();
+            assert!(is_known_key(&known, key), "serialize writes '{}' but it is not known", key);
+        }
+    }
+
+    fn yaml(text: &str) -> serde_yaml::Value {
+        serde_yaml::from_str(text).unwrap()
+    }
+
+    #[test]
+    fn raw_document_follows_parse_line_rules() {
+        let raw = raw_document("# c\nmax_retries: 4\nalert_targets:\n  - pm\n  - \"ops\"\nprompt: \"\\$ \"\n");
+        assert_eq!(raw["max_retries"], serde_yaml::Value::String("4".into()));
+        assert_eq!(raw["alert_targets"], yaml("[pm, ops]"));
+        assert!(raw["prompt"].is_string());
+    }
+
+    #[test]
+    fn validate_accepts_serialized_defaults() {
+        let mut s = default_settings();
+        s.heartbeat_marker_overrides.insert("worker".into(), "PING".into());
+        s.heartbeat_patterns.insert("console".into(), r">>> $".into());
+        s.pool_configs.insert("worker".into(), PoolConfigYaml {
+            size: 2,
+            path: "/tmp/work".into(),
+            max_size: Some(4),
+            min_idle: Some(1),
+        });
+        assert!(validate(&raw_document(&serialize(&s))).is_empty());
+    }
+
+    #[test]
+    fn validate_flags_unknown_keys() {
+        let warnings = validate(&yaml("health_check_intervall: 5000\npool.worker.sise: 3\nmax_retries: 3\n"));
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings[0].contains("'health_check_intervall'"));
+        assert!(warnings[1].contains("'pool.worker.sise'"));
+    }
+
+    #[test]
+    fn validate_flags_out_of_range_values() {
+        let warnings = validate(&yaml("max_retries: 500\nhealth_check_interval: 50ms\nssh_retries: 5\n"));
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings[0].contains("max_retries is 500"));
+        assert!(warnings[1].contains("health_check_interval is 50ms"));
+        assert!(validate(&yaml("health_check_interval: 1s\n")).is_empty());
+    }
+
+    fn save_test_dir(tag: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("cmx_settings_save_{}_{}", tag, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn no_temp_files(dir: &std::path::Path) -> bool {
+        std::fs::read_dir(dir)
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .all(|e| !e.file_name().to_string_lossy().ends_with(".tmp"))
+    }
+
+    #[test]
+    fn save_keeps_backup_of_previous_file() {
+        let dir = save_test_dir("backup");
+        let path = dir.join("settings.yaml");
+        let mut s = default_settings();
+        s.max_retries = 7;
+        save(&path, &s).unwrap();
+        assert!(!backup_path(&path).exists());
+
+        s.max_retries = 9;
+        save(&path, &s).unwrap();
+        assert_eq!(load(&path).unwrap().max_retries, 9);
+        assert_eq!(load(&backup_path(&path)).unwrap().max_retries, 7);
+        assert!(no_temp_files(&dir));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn failed_save_leaves_target_intact() {
+        // Permission bits do not stop root, so a non-empty directory at the
+        // target path stands in for a target that cannot be replaced.
+        let dir = save_test_dir("blocked");
+        let path = dir.join("settings.yaml");
+        std::fs::create_dir_all(&path).unwrap();
+        std::fs::write(path.join("keep.txt"), "original").unwrap();
+
+        assert!(save(&path, &default_settings()).is_err());
+        assert_eq!(std::fs::read_to_string(path.join("keep.txt")).unwrap(), "original");
+        assert!(no_temp_files(&dir));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
 }
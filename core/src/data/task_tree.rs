@@ -1,3 +1,6 @@
+use std::fmt;
+use std::path::Path;
+
 use crate::types::task::{TaskNode, TaskStatus};
 
 
@@ -54,6 +57,61 @@ impl TaskTree {
         Ok(())
     }
 
+    /// Move a task, with its whole subtree, under a new parent. Status,
+    /// children, and every other field travel unchanged; the task is
+    /// appended after the new parent's existing children. Fails if either
+    /// id is missing or if the new parent is the task itself or one of its
+    /// descendants.
+    pub fn reparent(&mut self, task_id: &str, new_parent_id: &str) -> Result<(), String> {
+        let node = self
+            .get(task_id)
+            .ok_or_else(|| format!("task not found: {}", task_id))?;
+        if self.get(new_parent_id).is_none() {
+            return Err(format!("task not found: {}", new_parent_id));
+        }
+        if find_node(node, new_parent_id).is_some() {
+            return Err(format!(
+                "cannot move task {} under {}: {} is in its subtree",
+                task_id, new_parent_id, new_parent_id
+            ));
+        }
+        let node = detach_node(&mut self.roots, task_id)
+            .ok_or_else(|| format!("task not found: {}", task_id))?;
+        let parent = self
+            .get_mut(new_parent_id)
+            .ok_or_else(|| format!("task not found: {}", new_parent_id))?;
+        parent.children.push(node);
+        Ok(())
+    }
+
+    /// Set the status of a task and every descendant. Descendants already
+    /// `Cancelled` keep that status (their own children are still
+    /// updated). Returns how many tasks actually changed.
+    pub fn set_status_cascade(&mut self, id: &str, status: TaskStatus) -> Result<usize, String> {
+        self.cascade_status(id, status).map(|changed| changed.len())
+    }
+
+    /// `set_status_cascade`, returning the ids of the tasks that changed in
+    /// depth-first order.
+    pub(crate) fn cascade_status(
+        &mut self,
+        id: &str,
+        status: TaskStatus,
+    ) -> Result<Vec<String>, String> {
+        let node = self
+            .get_mut(id)
+            .ok_or_else(|| format!("task not found: {}", id))?;
+        let mut changed = Vec::new();
+        if node.status != status {
+            node.status = status.clone();
+            changed.push(node.id.clone());
+        }
+        for child in &mut node.children {
+            cascade_node(child, &status, &mut changed);
+        }
+        Ok(changed)
+    }
+
     /// Assign an agent to a task. Sets status to InProgress.
     pub fn assign(&mut self, task_id: &str, agent: &str) -> Result<(), String> {
         let node = self
@@ -82,18 +140,191 @@ impl TaskTree {
         }
     }
 
+    /// Ids in `task.depends_on` that are not Completed. Unknown ids count
+    /// as incomplete, so a dangling dependency keeps the task blocked.
+    pub fn blockers(&self, task: &TaskNode) -> Vec<String> {
+        task.depends_on
+            .iter()
+            .filter(|dep| {
+                self.get(dep).map(|d| d.status != TaskStatus::Completed).unwrap_or(true)
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Pending tasks whose dependencies have all completed, in tree order.
+    pub fn ready_tasks(&self) -> Vec<&TaskNode> {
+        self.flat_list()
+            .into_iter()
+            .map(|(t, _)| t)
+            .filter(|t| t.status == TaskStatus::Pending && self.blockers(t).is_empty())
+            .collect()
+    }
+
+    /// Ready tasks that belong under `dir`: the task or one of its
+    /// ancestors has a `spec_path` inside it. Tasks carry no role, so this
+    /// is how a pool working in `dir` finds its own queue.
+    pub fn ready_tasks_under(&self, dir: &Path) -> Vec<&TaskNode> {
+        let mut inside: Vec<bool> = Vec::new();
+        let mut result = Vec::new();
+        for (task, depth) in self.flat_list() {
+            inside.truncate(depth);
+            let here = inside.last().copied().unwrap_or(false)
+                || task.spec_path.as_deref().is_some_and(|p| Path::new(p).starts_with(dir));
+            inside.push(here);
+            if here && task.status == TaskStatus::Pending && self.blockers(task).is_empty() {
+                result.push(task);
+            }
+        }
+        result
+    }
+
+    /// Pending tasks with at least one incomplete dependency, each paired
+    /// with the ids blocking it, in tree order.
+    pub fn blocked_tasks(&self) -> Vec<(&TaskNode, Vec<String>)> {
+        self.flat_list()
+            .into_iter()
+            .map(|(t, _)| t)
+            .filter(|t| t.status == TaskStatus::Pending)
+            .filter_map(|t| {
+                let blockers = self.blockers(t);
+                if blockers.is_empty() { None } else { Some((t, blockers)) }
+            })
+            .collect()
+    }
+
+    /// Tasks past their due date at `now_ms` that are not yet completed,
+    /// in tree order.
+    pub fn overdue(&self, now_ms: u64) -> Vec<&TaskNode> {
+        self.flat_list()
+            .into_iter()
+            .map(|(t, _)| t)
+            .filter(|t| t.is_overdue(now_ms))
+            .collect()
+    }
+
+    /// The state shown in listings. Pending tasks are split into Ready and
+    /// Blocked; every other status is shown as stored.
+    pub fn display_state(&self, task: &TaskNode) -> TaskDisplayState {
+        if task.status != TaskStatus::Pending {
+            return TaskDisplayState::Status(task.status.clone());
+        }
+        let blockers = self.blockers(task);
+        if blockers.is_empty() {
+            TaskDisplayState::Ready
+        } else {
+            TaskDisplayState::Blocked(blockers)
+        }
+    }
+
     /// Depth-first flattened list of all tasks with their indent level.
-    /// Level 0 = root tasks.
+    /// Level 0 = root tasks. A node repeating an ancestor's id is left out
+    /// along with its subtree; see `cycles`.
     pub fn flat_list(&self) -> Vec<(&TaskNode, usize)> {
+        self.walk().0
+    }
+
+    /// Every place a task id reappears inside its own subtree.
+    pub fn cycles(&self) -> Vec<TaskCycle> {
+        self.walk().1
+    }
+
+    fn walk(&self) -> (Vec<(&TaskNode, usize)>, Vec<TaskCycle>) {
+        let mut result = Vec::new();
+        let mut cycles = Vec::new();
+        let mut path = Vec::new();
+        for root in &self.roots {
+            flatten_node(root, 0, &mut path, &mut result, &mut cycles);
+        }
+        (result, cycles)
+    }
+
+    /// Export the whole tree as `{"tasks": [<root>, ...]}`, each node with
+    /// its children nested in place. Every `TaskNode` field is included, so
+    /// `from_json` restores an identical tree.
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({ "tasks": self.roots })
+    }
+
+    /// Import a tree exported by `to_json`. Fails only if the value is not
+    /// of that shape: like the in-memory tree, the import keeps repeated
+    /// ids and cycles as they are (`cycles` reports them), so any exported
+    /// tree comes back unchanged.
+    pub fn from_json(value: serde_json::Value) -> Result<TaskTree, String> {
+        let tasks = match value {
+            serde_json::Value::Object(mut map) => map
+                .remove("tasks")
+                .ok_or_else(|| "task tree JSON missing 'tasks' array".to_string())?,
+            _ => return Err("task tree JSON must be an object".into()),
+        };
+        let roots: Vec<TaskNode> = serde_json::from_value(tasks)
+            .map_err(|e| format!("invalid task tree JSON: {}", e))?;
+
+        Ok(TaskTree { roots })
+    }
+
+    /// Like `flat_list`, but skips tasks deeper than `max_depth` and, when
+    /// `status` is given, keeps only matching tasks plus their ancestors so
+    /// every match still appears under its parent chain.
+    pub fn flat_list_filtered(
+        &self,
+        max_depth: Option<usize>,
+        status: Option<TaskStatus>,
+    ) -> Vec<(&TaskNode, usize)> {
         let mut result = Vec::new();
+        let mut path = Vec::new();
         for root in &self.roots {
-            flatten_node(root, 0, &mut result);
+            filter_node(root, 0, max_depth, status.as_ref(), &mut path, &mut result);
         }
         result
     }
 }
 
 
+/// A task id that reappears inside its own subtree.
+///
+/// The tree owns its children, so a bad move or merge cannot form a literal
+/// pointer loop; it leaves a copy of an ancestor nested below itself, and
+/// re-expanding that copy is what would recurse forever. Traversals treat
+/// the copy as the back edge of a cycle and do not descend into it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskCycle {
+    /// The repeated id.
+    pub id: String,
+    /// Ids from the first occurrence down to the repeat, inclusive.
+    pub path: Vec<String>,
+}
+
+impl fmt::Display for TaskCycle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.path.join(" > "))
+    }
+}
+
+
+/// Computed presentation state of a task. Never stored; the persisted
+/// `TaskStatus` stays `Pending` for both Ready and Blocked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TaskDisplayState {
+    /// Pending with every dependency completed.
+    Ready,
+    /// Pending with the listed dependencies still incomplete.
+    Blocked(Vec<String>),
+    /// Any non-pending status.
+    Status(TaskStatus),
+}
+
+impl fmt::Display for TaskDisplayState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TaskDisplayState::Ready => write!(f, "ready"),
+            TaskDisplayState::Blocked(_) => write!(f, "blocked"),
+            TaskDisplayState::Status(s) => write!(f, "{}", format!("{:?}", s).to_lowercase()),
+        }
+    }
+}
+
+
 impl Default for TaskTree {
     fn default() -> Self {
         Self::new()
@@ -127,10 +358,79 @@ fn find_node_mut<'a>(node: &'a mut TaskNode, id: &str) -> Option<&'a mut TaskNod
 }
 
 
-fn flatten_node<'a>(node: &'a TaskNode, depth: usize, out: &mut Vec<(&'a TaskNode, usize)>) {
+/// Remove the first node with `id` from `nodes` or any subtree below them.
+fn detach_node(nodes: &mut Vec<TaskNode>, id: &str) -> Option<TaskNode> {
+    if let Some(pos) = nodes.iter().position(|n| n.id == id) {
+        return Some(nodes.remove(pos));
+    }
+    nodes
+        .iter_mut()
+        .find_map(|n| detach_node(&mut n.children, id))
+}
+
+
+/// Depth-first walk for `flat_list`. `path` holds the ids of the current
+/// ancestors; a node repeating one of them is recorded in `cycles` instead
+/// of being visited.
+fn flatten_node<'a>(
+    node: &'a TaskNode,
+    depth: usize,
+    path: &mut Vec<&'a str>,
+    out: &mut Vec<(&'a TaskNode, usize)>,
+    cycles: &mut Vec<TaskCycle>,
+) {
+    if let Some(start) = path.iter().position(|id| *id == node.id) {
+        let mut ids: Vec<String> = path[start..].iter().map(|id| id.to_string()).collect();
+        ids.push(node.id.clone());
+        cycles.push(TaskCycle { id: node.id.clone(), path: ids });
+        return;
+    }
+    out.push((node, depth));
+    path.push(&node.id);
+    for child in &node.children {
+        flatten_node(child, depth + 1, path, out, cycles);
+    }
+    path.pop();
+}
+
+
+/// Depth-first walk for `flat_list_filtered`. Returns true if `node` was
+/// kept, either because it matches or because a descendant does.
+fn filter_node<'a>(
+    node: &'a TaskNode,
+    depth: usize,
+    max_depth: Option<usize>,
+    status: Option<&TaskStatus>,
+    path: &mut Vec<&'a str>,
+    out: &mut Vec<(&'a TaskNode, usize)>,
+) -> bool {
+    if max_depth.is_some_and(|max| depth > max) || path.contains(&node.id.as_str()) {
+        return false;
+    }
+    let mark = out.len();
     out.push((node, depth));
+    path.push(&node.id);
+    let mut kept_child = false;
     for child in &node.children {
-        flatten_node(child, depth + 1, out);
+        kept_child |= filter_node(child, depth + 1, max_depth, status, path, out);
+    }
+    path.pop();
+    let keep = kept_child || status.is_none_or(|s| node.status == *s);
+    if !keep {
+        out.truncate(mark);
+    }
+    keep
+}
+
+
+/// Apply `status` below a cascade root, leaving cancelled tasks alone.
+fn cascade_node(node: &mut TaskNode, status: &TaskStatus, changed: &mut Vec<String>) {
+    if node.status != TaskStatus::Cancelled && node.status != *status {
+        node.status = status.clone();
+        changed.push(node.id.clone());
+    }
+    for child in &mut node.children {
+        cascade_node(child, status, changed);
     }
 }
 
@@ -181,6 +481,9 @@ mod tests {
             agent: None,
             children: Vec::new(),
             spec_path: None,
+            depends_on: Vec::new(),
+            notes: Vec::new(),
+            due_at_ms: None,
         }
     }
 
@@ -353,6 +656,108 @@ mod tests {
         );
     }
 
+    fn filter_fixture() -> TaskTree {
+        let mut tree = TaskTree::new();
+        let mut root = make_task("M1", "Root");
+        let mut child = make_task("M1.1", "Child");
+        let mut grandchild = make_task("M1.1.1", "Grandchild");
+        grandchild.status = TaskStatus::Failed;
+        child.children.push(grandchild);
+        root.children.push(child);
+        root.children.push(make_task("M1.2", "Child 2"));
+        tree.add_root(root);
+        tree.add_root(make_task("M2", "Root 2"));
+        tree
+    }
+
+    #[test]
+    fn flat_list_filtered_depth_limit() {
+        let tree = filter_fixture();
+        let ids: Vec<&str> = tree
+            .flat_list_filtered(Some(1), None)
+            .iter()
+            .map(|(n, _)| n.id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["M1", "M1.1", "M1.2", "M2"]);
+
+        let roots: Vec<&str> = tree
+            .flat_list_filtered(Some(0), None)
+            .iter()
+            .map(|(n, _)| n.id.as_str())
+            .collect();
+        assert_eq!(roots, vec!["M1", "M2"]);
+        assert_eq!(tree.flat_list_filtered(None, None).len(), tree.flat_list().len());
+    }
+
+    #[test]
+    fn flat_list_filtered_status_keeps_ancestors() {
+        let tree = filter_fixture();
+        let entries: Vec<(&str, usize)> = tree
+            .flat_list_filtered(None, Some(TaskStatus::Failed))
+            .iter()
+            .map(|(n, d)| (n.id.as_str(), *d))
+            .collect();
+        assert_eq!(entries, vec![("M1", 0), ("M1.1", 1), ("M1.1.1", 2)]);
+    }
+
+    #[test]
+    fn flat_list_filtered_depth_hides_deep_matches() {
+        let tree = filter_fixture();
+        assert!(tree.flat_list_filtered(Some(1), Some(TaskStatus::Failed)).is_empty());
+    }
+
+    /// M1 > M1.1 > M1 (copy, with its own M1.1 below), plus a sibling M2.
+    fn cyclic_tree() -> TaskTree {
+        let mut copy = make_task("M1", "Milestone (copy)");
+        copy.children.push(make_task("M1.1", "Section (copy)"));
+        let mut section = make_task("M1.1", "Section");
+        section.status = TaskStatus::Failed;
+        section.children.push(copy);
+        let mut root = make_task("M1", "Milestone");
+        root.children.push(section);
+        let mut tree = TaskTree::new();
+        tree.add_root(root);
+        tree.add_root(make_task("M2", "Other"));
+        tree
+    }
+
+    #[test]
+    fn flat_list_stops_at_cycle() {
+        let tree = cyclic_tree();
+        let ids: Vec<(&str, usize)> = tree.flat_list().iter().map(|(t, d)| (t.id.as_str(), *d)).collect();
+        assert_eq!(ids, vec![("M1", 0), ("M1.1", 1), ("M2", 0)]);
+
+        let cycles = tree.cycles();
+        assert_eq!(cycles.len(), 1);
+        assert_eq!(cycles[0].id, "M1");
+        assert_eq!(cycles[0].path, vec!["M1", "M1.1", "M1"]);
+        assert_eq!(cycles[0].to_string(), "M1 > M1.1 > M1");
+    }
+
+    #[test]
+    fn filtered_list_stops_at_cycle() {
+        let tree = cyclic_tree();
+        let ids: Vec<&str> = tree
+            .flat_list_filtered(None, Some(TaskStatus::Failed))
+            .iter()
+            .map(|(t, _)| t.id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["M1", "M1.1"]);
+        assert_eq!(tree.ready_tasks().len(), 2);
+    }
+
+    #[test]
+    fn acyclic_tree_has_no_cycles() {
+        let mut tree = TaskTree::new();
+        let mut root = make_task("M1", "Milestone");
+        root.children.push(make_task("M1.1", "Section"));
+        tree.add_root(root);
+        // The same id in separate branches is not a cycle.
+        tree.add_root(make_task("M1.1", "Elsewhere"));
+        assert!(tree.cycles().is_empty());
+        assert_eq!(tree.flat_list().len(), 3);
+    }
+
     #[test]
     fn flat_list_empty() {
         let tree = TaskTree::new();
@@ -371,4 +776,209 @@ mod tests {
         // All children pending, parent stays pending
         assert_eq!(tree.get("M1").unwrap().status, TaskStatus::Pending);
     }
+
+    #[test]
+    fn dependency_blocks_until_completed() {
+        let mut tree = TaskTree::new();
+        tree.add_root(make_task("A", "Build"));
+        let mut b = make_task("B", "Deploy");
+        b.depends_on = vec!["A".into()];
+        tree.add_root(b);
+
+        let b = tree.get("B").unwrap();
+        assert_eq!(tree.display_state(b), TaskDisplayState::Blocked(vec!["A".into()]));
+        assert_eq!(tree.ready_tasks().iter().map(|t| t.id.as_str()).collect::<Vec<_>>(), vec!["A"]);
+        assert_eq!(tree.blocked_tasks().len(), 1);
+
+        tree.set_status("A", TaskStatus::Completed).unwrap();
+        let b = tree.get("B").unwrap();
+        assert_eq!(tree.display_state(b), TaskDisplayState::Ready);
+        assert!(tree.blocked_tasks().is_empty());
+        // Stored status is untouched.
+        assert_eq!(b.status, TaskStatus::Pending);
+    }
+
+    #[test]
+    fn unknown_dependency_blocks() {
+        let mut tree = TaskTree::new();
+        let mut t = make_task("T", "Orphan");
+        t.depends_on = vec!["MISSING".into()];
+        tree.add_root(t);
+        assert_eq!(tree.blockers(tree.get("T").unwrap()), vec!["MISSING".to_string()]);
+    }
+
+    fn due_tree() -> TaskTree {
+        let mut tree = TaskTree::new();
+        let mut root = make_task("P", "Project");
+        let mut late = make_task("LATE", "Past due");
+        late.due_at_ms = Some(1_000);
+        let mut done = make_task("DONE", "Finished late");
+        done.due_at_ms = Some(1_000);
+        done.status = TaskStatus::Completed;
+        let mut future = make_task("SOON", "Due later");
+        future.due_at_ms = Some(9_000);
+        root.children = vec![late, done, future, make_task("NODATE", "No deadline")];
+        tree.add_root(root);
+        tree
+    }
+
+    #[test]
+    fn json_round_trip_is_lossless() {
+        let mut tree = TaskTree::new();
+        let mut root = make_task("R", "Root");
+        root.status = TaskStatus::InProgress;
+        root.spec_path = Some("/tasks/R/R.md".into());
+        let mut mid = make_task("M", "Middle");
+        mid.agent = Some("worker1".into());
+        mid.depends_on = vec!["S".into()];
+        mid.notes = vec![(1_000, "blocked on review".into())];
+        let mut leaf = make_task("L", "Leaf");
+        leaf.status = TaskStatus::Completed;
+        leaf.result = Some("merged".into());
+        leaf.due_at_ms = Some(9_000);
+        leaf.source = TaskSource::Filesystem;
+        mid.children.push(leaf);
+        root.children.push(mid);
+        root.children.push(make_task("S", "Sibling"));
+        tree.add_root(root);
+        tree.add_root(make_task("R2", "Second root"));
+
+        let json = tree.to_json();
+        assert_eq!(json["tasks"].as_array().unwrap().len(), 2);
+        let back = TaskTree::from_json(json).unwrap();
+        assert_eq!(back.roots(), tree.roots());
+        let depths: Vec<(&str, usize)> =
+            back.flat_list().iter().map(|(t, d)| (t.id.as_str(), *d)).collect();
+        assert_eq!(depths, vec![("R", 0), ("M", 1), ("L", 2), ("S", 1), ("R2", 0)]);
+    }
+
+    #[test]
+    fn json_import_rejects_bad_input() {
+        assert!(TaskTree::from_json(serde_json::json!([])).is_err());
+        assert!(TaskTree::from_json(serde_json::json!({"nodes": []})).is_err());
+        assert!(TaskTree::from_json(serde_json::json!({"tasks": [{"id": 7}]})).is_err());
+    }
+
+    #[test]
+    fn json_round_trip_keeps_repeated_ids() {
+        let mut tree = TaskTree::new();
+        tree.add_root(make_task("A", "First"));
+        tree.add_root(make_task("A", "Again"));
+        let mut looped = make_task("L", "Loop");
+        looped.children.push(make_task("L", "Loop again"));
+        tree.add_root(looped);
+
+        let back = TaskTree::from_json(tree.to_json()).unwrap();
+        assert_eq!(back.roots(), tree.roots());
+        assert_eq!(back.cycles().len(), 1);
+    }
+
+    #[test]
+    fn ready_tasks_under_follows_project_spec_path() {
+        let mut tree = TaskTree::new();
+        let mut alpha = make_task("A", "Alpha");
+        alpha.spec_path = Some("/work/alpha".into());
+        alpha.status = TaskStatus::InProgress;
+        alpha.children.push(make_task("A1", "Alpha step"));
+        let mut beta = make_task("B", "Beta");
+        beta.spec_path = Some("/work/beta".into());
+        beta.children.push(make_task("B1", "Beta step"));
+        tree.add_root(alpha);
+        tree.add_root(beta);
+        tree.add_root(make_task("LOOSE", "No project"));
+
+        let ids = |dir: &str| -> Vec<String> {
+            tree.ready_tasks_under(Path::new(dir)).iter().map(|t| t.id.clone()).collect()
+        };
+        assert_eq!(ids("/work/alpha"), vec!["A1"]);
+        assert_eq!(ids("/work/beta"), vec!["B", "B1"]);
+        assert!(ids("/work/al").is_empty());
+    }
+
+    #[test]
+    fn overdue_pending_task_flagged() {
+        let tree = due_tree();
+        let ids: Vec<&str> = tree.overdue(5_000).iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, vec!["LATE"]);
+    }
+
+    #[test]
+    fn completed_past_due_not_overdue() {
+        let tree = due_tree();
+        assert!(!tree.get("DONE").unwrap().is_overdue(5_000));
+    }
+
+    #[test]
+    fn future_due_not_overdue() {
+        let tree = due_tree();
+        assert!(!tree.get("SOON").unwrap().is_overdue(5_000));
+        // Once its deadline passes it shows up as well.
+        assert_eq!(tree.overdue(10_000).len(), 2);
+    }
+
+    /// M1 > (M1.1 > M1.1.1, M1.2), plus a root M2.
+    fn move_fixture() -> TaskTree {
+        let mut section = make_task("M1.1", "Section");
+        section.status = TaskStatus::InProgress;
+        section.children.push(make_task("M1.1.1", "Leaf"));
+        let mut root = make_task("M1", "Milestone 1");
+        root.children.push(section);
+        root.children.push(make_task("M1.2", "Other section"));
+        let mut tree = TaskTree::new();
+        tree.add_root(root);
+        tree.add_root(make_task("M2", "Milestone 2"));
+        tree
+    }
+
+    #[test]
+    fn reparent_moves_subtree() {
+        let mut tree = move_fixture();
+        tree.reparent("M1.1", "M2").unwrap();
+
+        let ids: Vec<(&str, usize)> = tree.flat_list().iter().map(|(t, d)| (t.id.as_str(), *d)).collect();
+        assert_eq!(ids, vec![("M1", 0), ("M1.2", 1), ("M2", 0), ("M1.1", 1), ("M1.1.1", 2)]);
+        assert_eq!(tree.get("M1.1").unwrap().status, TaskStatus::InProgress);
+    }
+
+    #[test]
+    fn reparent_rejects_cycle() {
+        let mut tree = move_fixture();
+        assert!(tree.reparent("M1", "M1").is_err());
+        let err = tree.reparent("M1", "M1.1.1").unwrap_err();
+        assert!(err.contains("subtree"));
+        // Tree untouched.
+        assert_eq!(tree.flat_list().len(), 5);
+        assert_eq!(tree.roots()[0].id, "M1");
+    }
+
+    #[test]
+    fn reparent_rejects_missing_ids() {
+        let mut tree = move_fixture();
+        assert!(tree.reparent("M1.1", "NOPE").unwrap_err().contains("NOPE"));
+        assert!(tree.reparent("NOPE", "M2").unwrap_err().contains("NOPE"));
+        assert_eq!(tree.get("M1").unwrap().children.len(), 2);
+    }
+
+    #[test]
+    fn cascade_sets_whole_subtree() {
+        let mut tree = move_fixture();
+        tree.get_mut("M1.2").unwrap().status = TaskStatus::Completed;
+        let changed = tree.set_status_cascade("M1", TaskStatus::Completed).unwrap();
+        // M1, M1.1, M1.1.1 change; M1.2 was already completed.
+        assert_eq!(changed, 3);
+        for id in ["M1", "M1.1", "M1.1.1", "M1.2"] {
+            assert_eq!(tree.get(id).unwrap().status, TaskStatus::Completed, "{}", id);
+        }
+        assert_eq!(tree.get("M2").unwrap().status, TaskStatus::Pending);
+    }
+
+    #[test]
+    fn cascade_preserves_cancelled_descendants() {
+        let mut tree = move_fixture();
+        tree.get_mut("M1.1.1").unwrap().status = TaskStatus::Cancelled;
+        let changed = tree.cascade_status("M1", TaskStatus::Completed).unwrap();
+        assert_eq!(changed, vec!["M1", "M1.1", "M1.2"]);
+        assert_eq!(tree.get("M1.1.1").unwrap().status, TaskStatus::Cancelled);
+        assert!(tree.set_status_cascade("NOPE", TaskStatus::Completed).is_err());
+    }
 }
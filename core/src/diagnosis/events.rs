@@ -96,6 +96,19 @@ pub enum InterventionOutcome {
     Pending,
 }
 
+impl fmt::Display for InterventionOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InterventionOutcome::Resolved => write!(f, "resolved"),
+            InterventionOutcome::StillBroken => write!(f, "still_broken"),
+            InterventionOutcome::DifferentError => write!(f, "different_error"),
+            InterventionOutcome::SelfResolved => write!(f, "self_resolved"),
+            InterventionOutcome::Timeout => write!(f, "timeout"),
+            InterventionOutcome::Pending => write!(f, "pending"),
+        }
+    }
+}
+
 
 // ---------------------------------------------------------------------------
 // InterventionEvent
@@ -114,6 +127,10 @@ pub struct InterventionEvent {
     pub outcome_detail: String,
     pub duration_ms: u64,
     pub failure_mode: String,
+    /// Operator notes added after the fact as `(timestamp_ms, text)`,
+    /// oldest first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub annotations: Vec<(u64, String)>,
 }
 
 
@@ -128,6 +145,9 @@ pub enum DiagnosisError {
     ParseError(String),
     EventNotFound(u64),
     InvalidOutcome(String),
+    /// A record for the same (signal, agent) arrived before the minimum
+    /// interval elapsed; retry after this many milliseconds.
+    RateLimited { retry_after_ms: u64 },
 }
 
 impl fmt::Display for DiagnosisError {
@@ -137,6 +157,9 @@ impl fmt::Display for DiagnosisError {
             DiagnosisError::ParseError(msg) => write!(f, "parse error: {}", msg),
             DiagnosisError::EventNotFound(id) => write!(f, "event not found: {}", id),
             DiagnosisError::InvalidOutcome(msg) => write!(f, "invalid outcome: {}", msg),
+            DiagnosisError::RateLimited { retry_after_ms } => {
+                write!(f, "rate limited: retry after {}ms", retry_after_ms)
+            }
         }
     }
 }
@@ -224,6 +247,50 @@ pub fn save_all_events(
 }
 
 
+// ---------------------------------------------------------------------------
+// CSV export
+// ---------------------------------------------------------------------------
+
+/// Column names of the CSV export, in order.
+pub const CSV_HEADER: &str = "id,timestamp_ms,agent,signal,action,outcome,duration_ms,\
+failure_mode,signal_detail,outcome_detail";
+
+/// Render events as CSV: a header row, then one row per event. Signals,
+/// actions, and outcomes use their `Display` strings (e.g.
+/// `trigger_fired(name)`). Fields containing commas, quotes, or newlines
+/// are quoted.
+pub fn events_to_csv(events: &[InterventionEvent]) -> String {
+    let mut out = String::from(CSV_HEADER);
+    out.push('\n');
+    for e in events {
+        let fields = [
+            e.id.to_string(),
+            e.timestamp_ms.to_string(),
+            e.agent.clone(),
+            e.signal.to_string(),
+            e.action.to_string(),
+            e.outcome.to_string(),
+            e.duration_ms.to_string(),
+            e.failure_mode.clone(),
+            e.signal_detail.clone(),
+            e.outcome_detail.clone(),
+        ];
+        let row: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+        out.push_str(&row.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+
 // ---------------------------------------------------------------------------
 // Test helpers
 // ---------------------------------------------------------------------------
@@ -262,7 +329,52 @@ mod tests {
             outcome_detail: "agent resumed".to_string(),
             duration_ms: 5000,
             failure_mode: "infrastructure".to_string(),
+            annotations: Vec::new(),
+        }
+    }
+
+    /// Split CSV text into rows of fields, honoring quoted fields.
+    fn parse_csv(text: &str) -> Vec<Vec<String>> {
+        let mut rows = Vec::new();
+        let mut row = Vec::new();
+        let mut field = String::new();
+        let mut quoted = false;
+        let mut chars = text.chars().peekable();
+        while let Some(c) = chars.next() {
+            match (c, quoted) {
+                ('"', true) if chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                ('"', _) => quoted = !quoted,
+                (',', false) => row.push(std::mem::take(&mut field)),
+                ('\n', false) => {
+                    row.push(std::mem::take(&mut field));
+                    rows.push(std::mem::take(&mut row));
+                }
+                (c, _) => field.push(c),
+            }
         }
+        rows
+    }
+
+    #[test]
+    fn csv_export_parses_back() {
+        let mut events: Vec<InterventionEvent> = (1..=3).map(sample_event).collect();
+        events[1].signal = SignalType::TriggerFired("build_failed".into());
+        events[1].signal_detail = "exit 2, see \"make\" log".into();
+        events[2].outcome = InterventionOutcome::SelfResolved;
+        events[2].outcome_detail = "line one\nline two".into();
+
+        let csv = events_to_csv(&events);
+        let rows = parse_csv(&csv);
+        assert_eq!(rows.len(), events.len() + 1);
+        assert_eq!(rows[0].join(","), CSV_HEADER);
+        assert!(rows.iter().all(|r| r.len() == 10));
+        assert_eq!(rows[2][3], "trigger_fired(build_failed)");
+        assert_eq!(rows[2][8], "exit 2, see \"make\" log");
+        assert_eq!(rows[3][5], "self_resolved");
+        assert_eq!(rows[3][9], "line one\nline two");
     }
 
     #[test]
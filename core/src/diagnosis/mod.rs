@@ -19,6 +19,8 @@ pub use events::{
     SignalType,
 };
 pub use reliability::{ActionEffectiveness, SignalReliability};
+pub use events::{events_to_csv, CSV_HEADER};
+pub use report::ReportWindow;
 pub use thresholds::AdaptiveThreshold;
 
 
@@ -30,6 +32,10 @@ pub use thresholds::AdaptiveThreshold;
 ///
 /// Records intervention events, computes signal reliability and action
 /// effectiveness, adjusts thresholds, and generates diagnostic reports.
+///
+/// `events` is kept in chronological order (by `timestamp_ms`, ties broken
+/// by `id`) regardless of arrival order, so trimming drops the oldest
+/// events and `recent_events` returns the newest.
 pub struct DiagnosisEngine {
     events: Vec<InterventionEvent>,
     reliability: HashMap<SignalType, SignalReliability>,
@@ -38,12 +44,28 @@ pub struct DiagnosisEngine {
     events_path: PathBuf,
     next_id: u64,
     max_events: usize,
+    /// Event lines currently in the JSONL file (in-memory events plus any
+    /// not-yet-compacted overflow).
+    file_events: usize,
+    /// Number of full rewrites performed to compact the file.
+    compactions: u64,
+    /// Minimum spacing between records for one (signal, agent) pair.
+    /// `None` disables rate limiting.
+    min_record_interval_ms: Option<u64>,
+    /// Timestamp of the last accepted record per (signal, agent). Seeded
+    /// from the loaded events, so the window holds across engines opened
+    /// on the same file.
+    last_record_ms: HashMap<(SignalType, String), u64>,
 }
 
 impl DiagnosisEngine {
     /// Default maximum number of events to retain.
     const DEFAULT_MAX_EVENTS: usize = 10_000;
 
+    /// How far (percent of `max_events`) the file may grow past the cap
+    /// before it is compacted.
+    const COMPACTION_SLACK_PERCENT: usize = 20;
+
     /// Create a new engine, loading existing events from disk if available.
     ///
     /// `config_dir` is the CMX configuration directory (e.g. `~/.config/cmx/`).
@@ -62,11 +84,18 @@ impl DiagnosisEngine {
     }
 
     /// Load all events from the JSONL file and rebuild statistics.
+    ///
+    /// The file is append-only and may hold events out of timestamp order
+    /// (e.g. replayed records), so events are sorted before the cap is
+    /// applied.
     pub fn load(
         events_path: PathBuf,
         max_events: usize,
     ) -> Result<Self, DiagnosisError> {
         let mut loaded = events::load_events(&events_path)?;
+        let file_events = loaded.len();
+        loaded.sort_by_key(chronological_key);
+        let next_id = loaded.iter().map(|e| e.id + 1).max().unwrap_or(0);
 
         // Apply bounded history.
         if loaded.len() > max_events {
@@ -74,9 +103,12 @@ impl DiagnosisEngine {
             loaded.drain(0..excess);
         }
 
-        let next_id = loaded.last().map(|e| e.id + 1).unwrap_or(0);
         let reliability = reliability::compute_reliability(&loaded);
         let effectiveness = reliability::compute_effectiveness(&loaded);
+        let mut last_record_ms = HashMap::new();
+        for event in &loaded {
+            last_record_ms.insert((event.signal.clone(), event.agent.clone()), event.timestamp_ms);
+        }
 
         Ok(DiagnosisEngine {
             events: loaded,
@@ -86,9 +118,48 @@ impl DiagnosisEngine {
             events_path,
             next_id,
             max_events,
+            file_events,
+            compactions: 0,
+            min_record_interval_ms: None,
+            last_record_ms,
         })
     }
 
+    /// Reject records for the same (signal, agent) that arrive less than
+    /// `interval_ms` after the previous accepted one. Guards the JSONL file
+    /// against a detector stuck in a loop.
+    pub fn with_rate_limit(mut self, interval_ms: u64) -> Self {
+        self.min_record_interval_ms = Some(interval_ms);
+        self
+    }
+
+    /// Admit a record at `now_ms`, or report how long until one would be.
+    ///
+    /// The first record for a pair is always admitted; only accepted
+    /// records move the window.
+    fn check_rate(
+        &mut self,
+        signal: &SignalType,
+        agent: &str,
+        now_ms: u64,
+    ) -> Result<(), DiagnosisError> {
+        let interval = match self.min_record_interval_ms {
+            Some(ms) => ms,
+            None => return Ok(()),
+        };
+        let key = (signal.clone(), agent.to_string());
+        if let Some(&last) = self.last_record_ms.get(&key) {
+            let elapsed = now_ms.saturating_sub(last);
+            if elapsed < interval {
+                return Err(DiagnosisError::RateLimited {
+                    retry_after_ms: interval - elapsed,
+                });
+            }
+        }
+        self.last_record_ms.insert(key, now_ms);
+        Ok(())
+    }
+
     // -------------------------------------------------------------------
     // Event recording
     // -------------------------------------------------------------------
@@ -99,11 +170,13 @@ impl DiagnosisEngine {
         &mut self,
         mut event: InterventionEvent,
     ) -> Result<(), DiagnosisError> {
+        self.check_rate(&event.signal, &event.agent, event.timestamp_ms)?;
         event.id = self.next_id;
         self.next_id += 1;
 
         events::append_event(&self.events_path, &event)?;
-        self.events.push(event);
+        self.file_events += 1;
+        self.insert_chronological(event);
         self.enforce_bounds()?;
         self.recompute_stats();
         Ok(())
@@ -118,6 +191,7 @@ impl DiagnosisEngine {
         detail: &str,
         now_ms: u64,
     ) -> Result<u64, DiagnosisError> {
+        self.check_rate(&signal, agent, now_ms)?;
         let id = self.next_id;
         self.next_id += 1;
 
@@ -132,10 +206,12 @@ impl DiagnosisEngine {
             outcome_detail: String::new(),
             duration_ms: 0,
             failure_mode: "none".to_string(),
+            annotations: Vec::new(),
         };
 
         events::append_event(&self.events_path, &event)?;
-        self.events.push(event);
+        self.file_events += 1;
+        self.insert_chronological(event);
         self.enforce_bounds()?;
         // Don't recompute stats here — pending events are skipped.
         Ok(id)
@@ -180,10 +256,69 @@ impl DiagnosisEngine {
 
         // Full rewrite since we modified an existing event.
         self.save()?;
+        self.file_events = self.events.len();
+        self.recompute_stats();
+        Ok(())
+    }
+
+    /// Undo a recorded outcome so `record_outcome` can classify the event
+    /// again. The event returns to the state `record_signal` left it in;
+    /// its signal, timestamp and annotations are kept.
+    pub fn revert_outcome(&mut self, event_id: u64) -> Result<(), DiagnosisError> {
+        let event = self
+            .events
+            .iter_mut()
+            .find(|e| e.id == event_id)
+            .ok_or(DiagnosisError::EventNotFound(event_id))?;
+
+        if event.outcome == InterventionOutcome::Pending {
+            return Err(DiagnosisError::InvalidOutcome(format!(
+                "event {} has no outcome to revert",
+                event_id
+            )));
+        }
+
+        event.action = InterventionAction::Ignore;
+        event.outcome = InterventionOutcome::Pending;
+        event.outcome_detail = String::new();
+        event.duration_ms = 0;
+        event.failure_mode = "none".to_string();
+
+        self.save()?;
+        self.file_events = self.events.len();
         self.recompute_stats();
         Ok(())
     }
 
+    /// Append a timestamped operator note to an event and persist it.
+    /// Annotations do not affect statistics.
+    pub fn annotate(
+        &mut self,
+        event_id: u64,
+        note: &str,
+        now_ms: u64,
+    ) -> Result<(), DiagnosisError> {
+        let event = self
+            .events
+            .iter_mut()
+            .find(|e| e.id == event_id)
+            .ok_or(DiagnosisError::EventNotFound(event_id))?;
+        event.annotations.push((now_ms, note.to_string()));
+
+        // Full rewrite since we modified an existing event.
+        self.save()?;
+        self.file_events = self.events.len();
+        Ok(())
+    }
+
+    /// Insert after every event with an earlier or equal sort key. New
+    /// events carry the highest id, so in-order arrivals still append.
+    fn insert_chronological(&mut self, event: InterventionEvent) {
+        let key = chronological_key(&event);
+        let pos = self.events.partition_point(|e| chronological_key(e) <= key);
+        self.events.insert(pos, event);
+    }
+
     // -------------------------------------------------------------------
     // Persistence
     // -------------------------------------------------------------------
@@ -193,13 +328,30 @@ impl DiagnosisEngine {
         events::save_all_events(&self.events_path, &self.events)
     }
 
-    /// Enforce bounded history. If events exceed max_events, prune the
-    /// oldest and do a full rewrite.
+    /// Number of compaction rewrites performed by this engine.
+    pub fn compaction_count(&self) -> u64 {
+        self.compactions
+    }
+
+    /// File size (in events) that triggers compaction: the cap plus slack.
+    fn compaction_threshold(&self) -> usize {
+        let slack = (self.max_events * Self::COMPACTION_SLACK_PERCENT / 100).max(1);
+        self.max_events + slack
+    }
+
+    /// Enforce bounded history. The in-memory list is trimmed to
+    /// `max_events` immediately; the file stays append-only until it
+    /// exceeds the compaction threshold, then is rewritten once. `load`
+    /// drops the overflow, so readers never see more than the cap.
     fn enforce_bounds(&mut self) -> Result<(), DiagnosisError> {
         if self.events.len() > self.max_events {
             let excess = self.events.len() - self.max_events;
             self.events.drain(0..excess);
+        }
+        if self.file_events > self.compaction_threshold() {
             self.save()?;
+            self.file_events = self.events.len();
+            self.compactions += 1;
         }
         Ok(())
     }
@@ -253,6 +405,89 @@ impl DiagnosisEngine {
         reliability::best_action_for_signal(&self.effectiveness, signal, min_attempts)
     }
 
+    /// Reliability of `signal` computed from `agent`'s events only, or
+    /// `None` if that agent has no events for the signal.
+    pub fn agent_signal_reliability(
+        &self,
+        agent: &str,
+        signal: &SignalType,
+    ) -> Option<SignalReliability> {
+        self.agent_reliability(agent).remove(signal)
+    }
+
+    /// Per-signal reliability computed from `agent`'s events only.
+    pub fn agent_reliability(&self, agent: &str) -> HashMap<SignalType, SignalReliability> {
+        self.scoped_reliability(Some(agent), None)
+    }
+
+    /// Reliability of `signal` over events with `timestamp_ms >= cutoff_ms`
+    /// only, so old behavior stops weighing on the score. `None` if no
+    /// event for the signal falls in the window.
+    pub fn reliability_since(
+        &self,
+        signal: &SignalType,
+        cutoff_ms: u64,
+    ) -> Option<SignalReliability> {
+        self.scoped_reliability(None, Some(cutoff_ms)).remove(signal)
+    }
+
+    /// Per-signal reliability over the events matching both optional
+    /// filters: a single agent, and a `timestamp_ms >= since_ms` cutoff.
+    /// Signals with no matching events are absent.
+    pub fn scoped_reliability(
+        &self,
+        agent: Option<&str>,
+        since_ms: Option<u64>,
+    ) -> HashMap<SignalType, SignalReliability> {
+        let events: Vec<InterventionEvent> = self
+            .events
+            .iter()
+            .filter(|e| agent.is_none_or(|a| e.agent == a))
+            .filter(|e| since_ms.is_none_or(|s| e.timestamp_ms >= s))
+            .cloned()
+            .collect();
+        reliability::compute_reliability(&events)
+    }
+
+    /// Distinct agent names that appear in the event history, sorted.
+    pub fn agents_with_events(&self) -> Vec<&str> {
+        let mut agents: Vec<&str> = self.events.iter().map(|e| e.agent.as_str()).collect();
+        agents.sort();
+        agents.dedup();
+        agents
+    }
+
+    /// Compare per-signal reliability between two agents. Every signal seen
+    /// by either agent appears once; the side with no history for it gets
+    /// `SignalReliability::empty`. Rows are sorted by signal name.
+    pub fn compare_agents(
+        &self,
+        a: &str,
+        b: &str,
+    ) -> Vec<(SignalType, SignalReliability, SignalReliability)> {
+        let rel_a = self.agent_reliability(a);
+        let rel_b = self.agent_reliability(b);
+
+        let mut signals: Vec<&SignalType> = rel_a.keys().chain(rel_b.keys()).collect();
+        signals.sort_by_key(|s| s.to_string());
+        signals.dedup();
+
+        signals
+            .into_iter()
+            .map(|signal| {
+                let left = rel_a
+                    .get(signal)
+                    .cloned()
+                    .unwrap_or_else(|| SignalReliability::empty(signal.clone()));
+                let right = rel_b
+                    .get(signal)
+                    .cloned()
+                    .unwrap_or_else(|| SignalReliability::empty(signal.clone()));
+                (signal.clone(), left, right)
+            })
+            .collect()
+    }
+
     // -------------------------------------------------------------------
     // Thresholds
     // -------------------------------------------------------------------
@@ -266,6 +501,20 @@ impl DiagnosisEngine {
             thresholds::compute_thresholds(base_thresholds, &self.reliability);
     }
 
+    /// Recompute adjusted thresholds from recency-weighted reliability:
+    /// each event counts `0.5^(age / half_life_ms)` at `now_ms`, so an old
+    /// burst of false positives stops inflating a timeout once behavior
+    /// improves. The cached all-time reliability stats are not changed.
+    pub fn recompute_thresholds_decayed(
+        &mut self,
+        base_thresholds: &HashMap<SignalType, u64>,
+        now_ms: u64,
+        half_life_ms: u64,
+    ) {
+        let decayed = reliability::compute_decayed_reliability(&self.events, now_ms, half_life_ms);
+        self.thresholds = thresholds::compute_thresholds(base_thresholds, &decayed);
+    }
+
     /// Get the adjusted timeout for a signal type.
     pub fn adjusted_timeout(&self, signal: &SignalType) -> Option<u64> {
         self.thresholds
@@ -292,6 +541,31 @@ impl DiagnosisEngine {
         )
     }
 
+    /// Write the markdown report to `out` section by section.
+    pub fn write_report(&self, out: &mut impl std::io::Write) -> std::io::Result<()> {
+        report::generate_report_into(
+            out,
+            &self.events,
+            &self.reliability,
+            &self.effectiveness,
+            &self.thresholds,
+        )
+    }
+
+    /// Generate a report over only the events inside `window`.
+    pub fn generate_window_report(&self, window: &ReportWindow) -> String {
+        report::generate_window_report(&self.events, window, &self.thresholds)
+    }
+
+    /// Write a report over only the events inside `window` to `out`.
+    pub fn write_window_report(
+        &self,
+        out: &mut impl std::io::Write,
+        window: &ReportWindow,
+    ) -> std::io::Result<()> {
+        report::generate_window_report_into(out, &self.events, window, &self.thresholds)
+    }
+
     // -------------------------------------------------------------------
     // Accessors
     // -------------------------------------------------------------------
@@ -318,6 +592,12 @@ impl DiagnosisEngine {
 }
 
 
+/// Sort key for the chronological event order.
+fn chronological_key(event: &InterventionEvent) -> (u64, u64) {
+    (event.timestamp_ms, event.id)
+}
+
+
 // ---------------------------------------------------------------------------
 // Tests
 // ---------------------------------------------------------------------------
@@ -356,6 +636,7 @@ mod tests {
                     outcome_detail: "fixed".into(),
                     duration_ms: 500,
                     failure_mode: "none".into(),
+                    annotations: Vec::new(),
                 })
                 .unwrap();
         }
@@ -373,6 +654,7 @@ mod tests {
                     outcome_detail: "went away".into(),
                     duration_ms: 200,
                     failure_mode: "none".into(),
+                    annotations: Vec::new(),
                 })
                 .unwrap();
         }
@@ -403,6 +685,7 @@ mod tests {
                     outcome_detail: "still broken".into(),
                     duration_ms: 1000,
                     failure_mode: "agent".into(),
+                    annotations: Vec::new(),
                 })
                 .unwrap();
         }
@@ -419,6 +702,7 @@ mod tests {
                     outcome_detail: "ok".into(),
                     duration_ms: 500,
                     failure_mode: "none".into(),
+                    annotations: Vec::new(),
                 })
                 .unwrap();
         }
@@ -446,6 +730,7 @@ mod tests {
                     outcome_detail: "ok".into(),
                     duration_ms: 100,
                     failure_mode: "none".into(),
+                    annotations: Vec::new(),
                 })
                 .unwrap();
         }
@@ -478,6 +763,7 @@ mod tests {
                     outcome_detail: "test".into(),
                     duration_ms: 1000,
                     failure_mode: "none".into(),
+                    annotations: Vec::new(),
                 })
                 .unwrap();
         }
@@ -500,6 +786,7 @@ mod tests {
                     outcome_detail: "test".into(),
                     duration_ms: 1000,
                     failure_mode: "none".into(),
+                    annotations: Vec::new(),
                 })
                 .unwrap();
         }
@@ -530,6 +817,7 @@ mod tests {
                     outcome_detail: "ok".into(),
                     duration_ms: 500,
                     failure_mode: "none".into(),
+                    annotations: Vec::new(),
                 })
                 .unwrap();
         }
@@ -548,6 +836,7 @@ mod tests {
                     outcome_detail: "ok".into(),
                     duration_ms: 100,
                     failure_mode: "none".into(),
+                    annotations: Vec::new(),
                 })
                 .unwrap();
         }
@@ -566,6 +855,46 @@ mod tests {
         assert!(os > 60_000);
     }
 
+    #[test]
+    fn decayed_thresholds_favor_recent_events() {
+        const HOUR: u64 = 3_600_000;
+        let now = 100 * HOUR;
+        let event = |ts: u64, outcome: InterventionOutcome| InterventionEvent {
+            timestamp_ms: ts,
+            ..agent_event("w1", SignalType::OutputStall, outcome)
+        };
+        let mut base = HashMap::new();
+        base.insert(SignalType::OutputStall, 60_000_u64);
+
+        // Five false positives and five true positives in both engines;
+        // only which burst is recent differs.
+        let mut recent_fp = test_engine("decay_recent_fp");
+        let mut old_fp = test_engine("decay_old_fp");
+        for _ in 0..5 {
+            recent_fp.record(event(now - HOUR, InterventionOutcome::SelfResolved)).unwrap();
+            recent_fp.record(event(now - 50 * HOUR, InterventionOutcome::Resolved)).unwrap();
+            old_fp.record(event(now - 50 * HOUR, InterventionOutcome::SelfResolved)).unwrap();
+            old_fp.record(event(now - HOUR, InterventionOutcome::Resolved)).unwrap();
+        }
+
+        // Undecayed, both sit at 0.5 and keep the default timeout.
+        recent_fp.recompute_thresholds(&base);
+        old_fp.recompute_thresholds(&base);
+        assert_eq!(recent_fp.adjusted_timeout(&SignalType::OutputStall), Some(60_000));
+        assert_eq!(old_fp.adjusted_timeout(&SignalType::OutputStall), Some(60_000));
+
+        recent_fp.recompute_thresholds_decayed(&base, now, 10 * HOUR);
+        old_fp.recompute_thresholds_decayed(&base, now, 10 * HOUR);
+        let recent = recent_fp.adjusted_timeout(&SignalType::OutputStall).unwrap();
+        let old = old_fp.adjusted_timeout(&SignalType::OutputStall).unwrap();
+        assert!(recent > 60_000, "recent false positives lengthen the timeout: {}", recent);
+        assert!(old < 60_000, "old false positives have faded: {}", old);
+
+        // Cached all-time stats are untouched.
+        let rel = recent_fp.signal_reliability(&SignalType::OutputStall).unwrap();
+        assert!((rel.reliability_score - 0.5).abs() < 0.001);
+    }
+
     // --- Test 6: JSONL persistence round-trip ---
 
     #[test]
@@ -589,6 +918,7 @@ mod tests {
                         outcome_detail: "ok".into(),
                         duration_ms: 500,
                         failure_mode: "none".into(),
+                        annotations: Vec::new(),
                     })
                     .unwrap();
             }
@@ -624,6 +954,7 @@ mod tests {
                     outcome_detail: "ok".into(),
                     duration_ms: 500,
                     failure_mode: "none".into(),
+                    annotations: Vec::new(),
                 })
                 .unwrap();
         }
@@ -696,6 +1027,7 @@ mod tests {
                 outcome_detail: "ok".into(),
                 duration_ms: 500,
                 failure_mode: "none".into(),
+                annotations: Vec::new(),
             })
             .unwrap();
 
@@ -745,6 +1077,7 @@ mod tests {
                     outcome_detail: "ok".into(),
                     duration_ms: 100,
                     failure_mode: "none".into(),
+                    annotations: Vec::new(),
                 })
                 .unwrap();
         }
@@ -763,6 +1096,7 @@ mod tests {
                     outcome_detail: "ok".into(),
                     duration_ms: 500,
                     failure_mode: "none".into(),
+                    annotations: Vec::new(),
                 })
                 .unwrap();
         }
@@ -773,6 +1107,47 @@ mod tests {
         assert!((rel.reliability_score - 1.0).abs() < 0.001);
     }
 
+    // --- Test: append-then-compact amortizes rewrites ---
+
+    #[test]
+    fn compaction_is_amortized() {
+        let dir = events::test_dir("t12b_amortized");
+        let mut engine = DiagnosisEngine::with_capacity(dir.clone(), 50).unwrap();
+
+        for i in 0..500 {
+            engine
+                .record(InterventionEvent {
+                    id: 0,
+                    timestamp_ms: 1000 + i,
+                    agent: "w1".into(),
+                    signal: SignalType::OutputStall,
+                    signal_detail: "stall".into(),
+                    action: InterventionAction::Retry,
+                    outcome: InterventionOutcome::Resolved,
+                    outcome_detail: "ok".into(),
+                    duration_ms: 10,
+                    failure_mode: "none".into(),
+                    annotations: Vec::new(),
+                })
+                .unwrap();
+            assert!(engine.event_count() <= 50);
+        }
+
+        // One rewrite per 10 appends past the cap (20% of 50), not one per append.
+        let compactions = engine.compaction_count();
+        assert!(compactions > 0);
+        assert!(compactions <= 45, "too many rewrites: {}", compactions);
+
+        // The file carries at most the slack beyond the cap, and loading
+        // keeps only the newest 50.
+        let path = dir.join("logs").join("events.jsonl");
+        let lines = std::fs::read_to_string(&path).unwrap().lines().count();
+        assert!(lines <= 60);
+        let reloaded = DiagnosisEngine::with_capacity(dir, 50).unwrap();
+        assert_eq!(reloaded.event_count(), 50);
+        assert_eq!(reloaded.events().last().unwrap().timestamp_ms, 1499);
+    }
+
     // --- Test: record_outcome on already-completed event ---
 
     #[test]
@@ -823,6 +1198,7 @@ mod tests {
                     outcome_detail: "ok".into(),
                     duration_ms: 500,
                     failure_mode: "none".into(),
+                    annotations: Vec::new(),
                 })
                 .unwrap();
         }
@@ -835,4 +1211,313 @@ mod tests {
         let all = engine.recent_events(100);
         assert_eq!(all.len(), 10);
     }
+
+    fn event_at(id: u64, timestamp_ms: u64) -> InterventionEvent {
+        InterventionEvent {
+            id,
+            timestamp_ms,
+            agent: "w1".into(),
+            signal: SignalType::HeartbeatStale,
+            signal_detail: "stale".into(),
+            action: InterventionAction::Retry,
+            outcome: InterventionOutcome::Resolved,
+            outcome_detail: "ok".into(),
+            duration_ms: 500,
+            failure_mode: "none".into(),
+            annotations: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn load_sorts_out_of_order_events() {
+        let dir = events::test_dir("t15_load_order");
+        let path = dir.join("logs").join("events.jsonl");
+        let shuffled = vec![
+            event_at(0, 3000),
+            event_at(1, 1000),
+            event_at(4, 2000),
+            event_at(3, 2000),
+            event_at(2, 5000),
+        ];
+        events::save_all_events(&path, &shuffled).unwrap();
+
+        let engine = DiagnosisEngine::load(path, 100).unwrap();
+        let order: Vec<(u64, u64)> = engine
+            .recent_events(10)
+            .iter()
+            .map(|e| (e.timestamp_ms, e.id))
+            .collect();
+        assert_eq!(order, vec![(1000, 1), (2000, 3), (2000, 4), (3000, 0), (5000, 2)]);
+        assert_eq!(engine.recent_events(1)[0].timestamp_ms, 5000);
+    }
+
+    #[test]
+    fn load_cap_drops_oldest_by_timestamp() {
+        let dir = events::test_dir("t16_load_cap_order");
+        let path = dir.join("logs").join("events.jsonl");
+        let shuffled = vec![event_at(0, 900), event_at(1, 100), event_at(2, 500)];
+        events::save_all_events(&path, &shuffled).unwrap();
+
+        let mut engine = DiagnosisEngine::load(path, 2).unwrap();
+        let stamps: Vec<u64> = engine.events().iter().map(|e| e.timestamp_ms).collect();
+        assert_eq!(stamps, vec![500, 900]);
+
+        // Ids continue past the highest loaded id, not the last by time.
+        let id = engine.record_signal("w2", SignalType::ErrorPattern, "panic", 1000).unwrap();
+        assert_eq!(id, 3);
+    }
+
+    #[test]
+    fn record_keeps_chronological_order() {
+        let mut engine = test_engine("t17_record_order");
+        engine.record(event_at(0, 2000)).unwrap();
+        engine.record(event_at(0, 3000)).unwrap();
+        // A replayed event older than everything recorded so far.
+        engine.record(event_at(0, 1000)).unwrap();
+
+        let stamps: Vec<u64> = engine.recent_events(3).iter().map(|e| e.timestamp_ms).collect();
+        assert_eq!(stamps, vec![1000, 2000, 3000]);
+        assert_eq!(engine.recent_events(1)[0].timestamp_ms, 3000);
+    }
+
+    // --- compare_agents ---
+
+    fn agent_event(agent: &str, signal: SignalType, outcome: InterventionOutcome) -> InterventionEvent {
+        InterventionEvent {
+            id: 0,
+            timestamp_ms: 1000,
+            agent: agent.into(),
+            signal,
+            signal_detail: "detail".into(),
+            action: InterventionAction::Retry,
+            outcome,
+            outcome_detail: String::new(),
+            duration_ms: 100,
+            failure_mode: "none".into(),
+            annotations: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn agent_signal_reliability_diverges_per_agent() {
+        let mut engine = test_engine("agent_reliability");
+        // w1: 4 true positives. w2 (flaky): 1 true positive, 3 false alarms.
+        for _ in 0..4 {
+            engine.record(agent_event("w1", SignalType::HeartbeatStale, InterventionOutcome::Resolved)).unwrap();
+        }
+        engine.record(agent_event("w2", SignalType::HeartbeatStale, InterventionOutcome::Resolved)).unwrap();
+        for _ in 0..3 {
+            engine.record(agent_event("w2", SignalType::HeartbeatStale, InterventionOutcome::SelfResolved)).unwrap();
+        }
+
+        let w1 = engine.agent_signal_reliability("w1", &SignalType::HeartbeatStale).unwrap();
+        let w2 = engine.agent_signal_reliability("w2", &SignalType::HeartbeatStale).unwrap();
+        assert!((w1.reliability_score - 1.0).abs() < 0.001);
+        assert!((w2.reliability_score - 0.25).abs() < 0.001);
+        let overall = engine.signal_reliability(&SignalType::HeartbeatStale).unwrap();
+        assert!((overall.reliability_score - 0.625).abs() < 0.001);
+
+        assert!(engine.agent_signal_reliability("w1", &SignalType::OutputStall).is_none());
+        assert!(engine.agent_signal_reliability("w9", &SignalType::HeartbeatStale).is_none());
+        assert_eq!(engine.agents_with_events(), vec!["w1", "w2"]);
+    }
+
+    #[test]
+    fn reliability_since_includes_event_at_cutoff() {
+        let mut engine = test_engine("reliability_since");
+        let mut at = |ts: u64, outcome: InterventionOutcome| {
+            let mut e = agent_event("w1", SignalType::HeartbeatStale, outcome);
+            e.timestamp_ms = ts;
+            engine.record(e).unwrap();
+        };
+        // Old false alarms, then recent true positives.
+        at(1_000, InterventionOutcome::SelfResolved);
+        at(1_999, InterventionOutcome::SelfResolved);
+        at(2_000, InterventionOutcome::Resolved);
+        at(3_000, InterventionOutcome::Resolved);
+
+        let recent = engine.reliability_since(&SignalType::HeartbeatStale, 2_000).unwrap();
+        assert_eq!(recent.total_fires, 2);
+        assert!((recent.reliability_score - 1.0).abs() < 0.001);
+
+        let wider = engine.reliability_since(&SignalType::HeartbeatStale, 1_999).unwrap();
+        assert_eq!(wider.total_fires, 3);
+
+        assert!(engine.reliability_since(&SignalType::HeartbeatStale, 3_001).is_none());
+        assert!(engine.reliability_since(&SignalType::OutputStall, 0).is_none());
+    }
+
+    #[test]
+    fn compare_agents_overlapping_signals() {
+        let mut engine = test_engine("compare_overlap");
+        for _ in 0..3 {
+            engine.record(agent_event("w1", SignalType::HeartbeatStale, InterventionOutcome::Resolved)).unwrap();
+        }
+        engine.record(agent_event("w1", SignalType::HeartbeatStale, InterventionOutcome::SelfResolved)).unwrap();
+        engine.record(agent_event("w2", SignalType::HeartbeatStale, InterventionOutcome::SelfResolved)).unwrap();
+
+        let rows = engine.compare_agents("w1", "w2");
+        assert_eq!(rows.len(), 1);
+        let (signal, a, b) = &rows[0];
+        assert_eq!(*signal, SignalType::HeartbeatStale);
+        assert_eq!(a.total_fires, 4);
+        assert!((a.reliability_score - 0.75).abs() < 0.001);
+        assert_eq!(b.total_fires, 1);
+        assert!((b.reliability_score - 0.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn compare_agents_disjoint_signals_zero_fill() {
+        let mut engine = test_engine("compare_disjoint");
+        engine.record(agent_event("w1", SignalType::HeartbeatStale, InterventionOutcome::Resolved)).unwrap();
+        engine.record(agent_event("w2", SignalType::ErrorPattern, InterventionOutcome::Resolved)).unwrap();
+        engine.record(agent_event("w3", SignalType::OutputStall, InterventionOutcome::Resolved)).unwrap();
+
+        let rows = engine.compare_agents("w1", "w2");
+        let signals: Vec<SignalType> = rows.iter().map(|(s, _, _)| s.clone()).collect();
+        assert_eq!(signals, vec![SignalType::ErrorPattern, SignalType::HeartbeatStale]);
+
+        let (_, a, b) = &rows[0];
+        assert_eq!(a.total_fires, 0);
+        assert_eq!(a.reliability_score, 0.0);
+        assert_eq!(b.total_fires, 1);
+
+        let (_, a, b) = &rows[1];
+        assert_eq!(a.total_fires, 1);
+        assert_eq!(b.total_fires, 0);
+    }
+
+    #[test]
+    fn compare_agents_unknown_agents_empty() {
+        let engine = test_engine("compare_empty");
+        assert!(engine.compare_agents("nobody", "none").is_empty());
+    }
+
+    // --- Test: per-signal rate limiting ---
+
+    #[test]
+    fn rate_limit_accepts_first_record() {
+        let mut engine = test_engine("rate_first").with_rate_limit(1_000);
+        assert!(engine.record_signal("w1", SignalType::HeartbeatStale, "stale", 5_000).is_ok());
+        assert_eq!(engine.event_count(), 1);
+    }
+
+    #[test]
+    fn rate_limit_rejects_rapid_second() {
+        let mut engine = test_engine("rate_rapid").with_rate_limit(1_000);
+        engine.record_signal("w1", SignalType::HeartbeatStale, "stale", 5_000).unwrap();
+        match engine.record_signal("w1", SignalType::HeartbeatStale, "stale", 5_300) {
+            Err(DiagnosisError::RateLimited { retry_after_ms }) => assert_eq!(retry_after_ms, 700),
+            other => panic!("expected RateLimited, got {:?}", other),
+        }
+        assert_eq!(engine.event_count(), 1);
+
+        // Other agents and signals have their own windows.
+        assert!(engine.record_signal("w2", SignalType::HeartbeatStale, "stale", 5_300).is_ok());
+        assert!(engine.record_signal("w1", SignalType::ErrorPattern, "err", 5_300).is_ok());
+    }
+
+    #[test]
+    fn revert_outcome_returns_event_to_pending() {
+        let dir = events::test_dir("revert_outcome");
+        let mut engine = DiagnosisEngine::with_capacity(dir.clone(), 100).unwrap();
+        let id = engine.record_signal("w1", SignalType::HeartbeatStale, "stale", 1_000).unwrap();
+        engine
+            .record_outcome(id, InterventionAction::Restart, InterventionOutcome::SelfResolved, "oops", 4_000)
+            .unwrap();
+        assert_eq!(engine.signal_reliability(&SignalType::HeartbeatStale).unwrap().false_positives, 1);
+
+        engine.revert_outcome(id).unwrap();
+        let event = &engine.events()[0];
+        assert_eq!(event.outcome, InterventionOutcome::Pending);
+        assert_eq!(event.action, InterventionAction::Ignore);
+        assert!(event.outcome_detail.is_empty());
+        assert_eq!(event.duration_ms, 0);
+        assert_eq!(event.failure_mode, "none");
+        let rel = engine.signal_reliability(&SignalType::HeartbeatStale).unwrap();
+        assert_eq!(rel.total_fires, 0);
+        assert_eq!(rel.false_positives, 0);
+
+        // Reclassify correctly; the change is persisted.
+        engine
+            .record_outcome(id, InterventionAction::Restart, InterventionOutcome::Resolved, "fixed", 4_000)
+            .unwrap();
+        let reloaded = DiagnosisEngine::with_capacity(dir, 100).unwrap();
+        assert_eq!(reloaded.events()[0].outcome, InterventionOutcome::Resolved);
+        assert_eq!(reloaded.signal_reliability(&SignalType::HeartbeatStale).unwrap().true_positives, 1);
+    }
+
+    #[test]
+    fn revert_pending_outcome_errors() {
+        let mut engine = test_engine("revert_pending");
+        let id = engine.record_signal("w1", SignalType::OutputStall, "quiet", 1_000).unwrap();
+        assert!(matches!(engine.revert_outcome(id), Err(DiagnosisError::InvalidOutcome(_))));
+        assert!(matches!(engine.revert_outcome(99), Err(DiagnosisError::EventNotFound(99))));
+    }
+
+    #[test]
+    fn annotate_appends_timestamped_notes() {
+        let mut engine = test_engine("annotate_append");
+        let id = engine.record_signal("w1", SignalType::HeartbeatStale, "stale", 1_000).unwrap();
+        engine.annotate(id, "false alarm: long compile", 5_000).unwrap();
+        engine.annotate(id, "confirmed by build log", 6_000).unwrap();
+
+        let event = &engine.events()[0];
+        assert_eq!(
+            event.annotations,
+            vec![
+                (5_000, "false alarm: long compile".to_string()),
+                (6_000, "confirmed by build log".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn annotate_unknown_event_errors() {
+        let mut engine = test_engine("annotate_missing");
+        match engine.annotate(42, "note", 1_000) {
+            Err(DiagnosisError::EventNotFound(42)) => {}
+            other => panic!("expected EventNotFound, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn annotation_survives_reload() {
+        let dir = events::test_dir("annotate_reload");
+        {
+            let mut engine = DiagnosisEngine::with_capacity(dir.clone(), 100).unwrap();
+            let id = engine.record_signal("w1", SignalType::OutputStall, "quiet", 1_000).unwrap();
+            engine.record_signal("w2", SignalType::OutputStall, "quiet", 2_000).unwrap();
+            engine.annotate(id, "agent was waiting on review", 3_000).unwrap();
+        }
+        let engine = DiagnosisEngine::with_capacity(dir, 100).unwrap();
+        assert_eq!(engine.event_count(), 2);
+        let annotated = engine.events().iter().find(|e| e.agent == "w1").unwrap();
+        assert_eq!(annotated.annotations, vec![(3_000, "agent was waiting on review".to_string())]);
+        assert!(engine.events().iter().find(|e| e.agent == "w2").unwrap().annotations.is_empty());
+    }
+
+    #[test]
+    fn rate_limit_window_survives_reload() {
+        let dir = events::test_dir("rate_reload");
+        let mut engine = DiagnosisEngine::with_capacity(dir.clone(), 100).unwrap().with_rate_limit(1_000);
+        engine.record_signal("w1", SignalType::HeartbeatStale, "stale", 5_000).unwrap();
+
+        let mut reloaded = DiagnosisEngine::with_capacity(dir, 100).unwrap().with_rate_limit(1_000);
+        match reloaded.record_signal("w1", SignalType::HeartbeatStale, "stale", 5_400) {
+            Err(DiagnosisError::RateLimited { retry_after_ms }) => assert_eq!(retry_after_ms, 600),
+            other => panic!("expected RateLimited, got {:?}", other),
+        }
+        assert!(reloaded.record_signal("w1", SignalType::HeartbeatStale, "stale", 6_000).is_ok());
+    }
+
+    #[test]
+    fn rate_limit_accepts_after_interval() {
+        let mut engine = test_engine("rate_after").with_rate_limit(1_000);
+        engine.record(agent_event("w1", SignalType::OutputStall, InterventionOutcome::Resolved)).unwrap();
+        let mut later = agent_event("w1", SignalType::OutputStall, InterventionOutcome::Resolved);
+        later.timestamp_ms += 1_000;
+        assert!(engine.record(later).is_ok());
+        assert_eq!(engine.event_count(), 2);
+    }
 }
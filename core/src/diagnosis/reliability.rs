@@ -33,6 +33,11 @@ pub struct SignalReliability {
     pub reliability_score: f64,
     /// Average time to resolve when intervention succeeds.
     pub avg_resolution_ms: u64,
+    /// Lower bound of the 95% Wilson score interval for
+    /// true_positives / total_fires. A few fires give a wide interval.
+    pub confidence_low: f64,
+    /// Upper bound of the same interval. `[0.0, 1.0]` with no fires.
+    pub confidence_high: f64,
 }
 
 impl SignalReliability {
@@ -45,6 +50,17 @@ impl SignalReliability {
             unknown: 0,
             reliability_score: 0.5,
             avg_resolution_ms: 0,
+            confidence_low: 0.0,
+            confidence_high: 1.0,
+        }
+    }
+
+    /// All-zero stats for a signal with no history (used when comparing
+    /// agents where only one of them has seen the signal).
+    pub fn empty(signal: SignalType) -> Self {
+        SignalReliability {
+            reliability_score: 0.0,
+            ..SignalReliability::new(signal)
         }
     }
 }
@@ -90,8 +106,35 @@ impl ActionEffectiveness {
 /// Returns a map from SignalType to SignalReliability.
 pub fn compute_reliability(
     events: &[InterventionEvent],
+) -> HashMap<SignalType, SignalReliability> {
+    compute_weighted_reliability(events, |_| 1.0)
+}
+
+/// Like `compute_reliability`, but each event's contribution to
+/// `reliability_score` decays by half every `half_life_ms` of age at
+/// `now_ms`, so recent behavior dominates. Counts and the confidence
+/// interval stay unweighted. A zero half-life disables decay.
+pub fn compute_decayed_reliability(
+    events: &[InterventionEvent],
+    now_ms: u64,
+    half_life_ms: u64,
+) -> HashMap<SignalType, SignalReliability> {
+    if half_life_ms == 0 {
+        return compute_reliability(events);
+    }
+    compute_weighted_reliability(events, |e| {
+        let age = now_ms.saturating_sub(e.timestamp_ms) as f64;
+        0.5f64.powf(age / half_life_ms as f64)
+    })
+}
+
+fn compute_weighted_reliability(
+    events: &[InterventionEvent],
+    weight: impl Fn(&InterventionEvent) -> f64,
 ) -> HashMap<SignalType, SignalReliability> {
     let mut map: HashMap<SignalType, SignalReliability> = HashMap::new();
+    // Weighted (true positive, false positive) mass per signal.
+    let mut mass: HashMap<SignalType, (f64, f64)> = HashMap::new();
 
     for event in events {
         let entry = map
@@ -104,16 +147,21 @@ pub fn compute_reliability(
         }
 
         entry.total_fires += 1;
+        let w = weight(event);
+        let m = mass.entry(event.signal.clone()).or_insert((0.0, 0.0));
 
         match &event.outcome {
             InterventionOutcome::Resolved => {
                 entry.true_positives += 1;
+                m.0 += w;
             }
             InterventionOutcome::StillBroken => {
                 entry.true_positives += 1;
+                m.0 += w;
             }
             InterventionOutcome::SelfResolved => {
                 entry.false_positives += 1;
+                m.1 += w;
             }
             InterventionOutcome::Timeout | InterventionOutcome::DifferentError => {
                 entry.unknown += 1;
@@ -126,12 +174,15 @@ pub fn compute_reliability(
 
     // Compute reliability scores and average resolution time.
     for entry in map.values_mut() {
-        let denom = entry.true_positives + entry.false_positives;
-        entry.reliability_score = if denom > 0 {
-            entry.true_positives as f64 / denom as f64
+        let (tp, fp) = mass.get(&entry.signal).copied().unwrap_or((0.0, 0.0));
+        entry.reliability_score = if tp + fp > 0.0 {
+            tp / (tp + fp)
         } else {
             0.5
         };
+        let (low, high) = wilson_interval(entry.true_positives, entry.total_fires);
+        entry.confidence_low = low;
+        entry.confidence_high = high;
 
         // Average resolution time for successful interventions.
         let resolved_events: Vec<&InterventionEvent> = events
@@ -149,6 +200,25 @@ pub fn compute_reliability(
     map
 }
 
+/// z for a two-sided 95% interval.
+const Z_95: f64 = 1.96;
+
+/// 95% Wilson score interval for `successes` out of `trials`. Unlike the
+/// normal approximation it stays inside [0, 1] and is sensibly wide for
+/// small samples. Zero trials give `(0.0, 1.0)`.
+pub fn wilson_interval(successes: u64, trials: u64) -> (f64, f64) {
+    if trials == 0 {
+        return (0.0, 1.0);
+    }
+    let n = trials as f64;
+    let p = successes as f64 / n;
+    let z2 = Z_95 * Z_95;
+    let denom = 1.0 + z2 / n;
+    let center = (p + z2 / (2.0 * n)) / denom;
+    let margin = Z_95 * (p * (1.0 - p) / n + z2 / (4.0 * n * n)).sqrt() / denom;
+    ((center - margin).max(0.0), (center + margin).min(1.0))
+}
+
 /// Recompute all action effectiveness stats from a set of events.
 /// Returns a map from (SignalType, InterventionAction) to ActionEffectiveness.
 pub fn compute_effectiveness(
@@ -241,6 +311,7 @@ mod tests {
             outcome_detail: "test outcome".to_string(),
             duration_ms,
             failure_mode: "none".to_string(),
+            annotations: Vec::new(),
         }
     }
 
@@ -463,4 +534,34 @@ mod tests {
         assert!(rel.is_empty());
         assert!(eff.is_empty());
     }
+
+    #[test]
+    fn wilson_interval_narrows_with_sample_size() {
+        let few: Vec<InterventionEvent> = (0..2)
+            .map(|i| make_event(i, SignalType::HeartbeatStale, InterventionAction::Retry, InterventionOutcome::Resolved, 100))
+            .collect();
+        let many: Vec<InterventionEvent> = (0..200)
+            .map(|i| make_event(i, SignalType::HeartbeatStale, InterventionAction::Retry, InterventionOutcome::Resolved, 100))
+            .collect();
+
+        let small = compute_reliability(&few)[&SignalType::HeartbeatStale].clone();
+        let large = compute_reliability(&many)[&SignalType::HeartbeatStale].clone();
+        assert!((small.reliability_score - 1.0).abs() < 0.001);
+        assert!((large.reliability_score - 1.0).abs() < 0.001);
+
+        let small_width = small.confidence_high - small.confidence_low;
+        let large_width = large.confidence_high - large.confidence_low;
+        assert!(small_width > 0.5, "2 fires: {:?}", (small.confidence_low, small.confidence_high));
+        assert!(large_width < 0.05, "200 fires: {:?}", (large.confidence_low, large.confidence_high));
+        assert!(large.confidence_low > small.confidence_low);
+        assert!((large.confidence_high - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn wilson_interval_zero_trials_is_unit_interval() {
+        assert_eq!(wilson_interval(0, 0), (0.0, 1.0));
+        let (low, high) = wilson_interval(5, 10);
+        assert!(low < 0.5 && high > 0.5);
+        assert!((0.5 - low - (high - 0.5)).abs() < 1e-9);
+    }
 }
@@ -2,14 +2,52 @@
 //!
 //! Produces a human-readable summary of signal reliability, intervention
 //! effectiveness, threshold adjustments, and actionable recommendations.
+//!
+//! Reports are written section by section into any `io::Write`, so large
+//! histories can be streamed to a socket or file. The `-> String`
+//! functions are conveniences over the same writer.
 
 use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, Write};
 
 use super::events::{InterventionAction, InterventionEvent, SignalType};
-use super::reliability::{ActionEffectiveness, SignalReliability};
+use super::reliability::{self, ActionEffectiveness, SignalReliability};
 use super::thresholds::AdaptiveThreshold;
 
 
+/// A time window a report is scoped to. Either bound may be open; `since`
+/// is inclusive and `until` exclusive, both in ms since epoch.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReportWindow {
+    pub since_ms: Option<u64>,
+    pub until_ms: Option<u64>,
+}
+
+impl ReportWindow {
+    pub fn new(since_ms: Option<u64>, until_ms: Option<u64>) -> Self {
+        ReportWindow { since_ms, until_ms }
+    }
+
+    /// True if neither bound is set.
+    pub fn is_unbounded(&self) -> bool {
+        self.since_ms.is_none() && self.until_ms.is_none()
+    }
+
+    /// True if `ts_ms` falls inside the window.
+    pub fn contains(&self, ts_ms: u64) -> bool {
+        self.since_ms.is_none_or(|s| ts_ms >= s) && self.until_ms.is_none_or(|u| ts_ms < u)
+    }
+}
+
+impl fmt::Display for ReportWindow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bound = |b: Option<u64>| b.map_or("open".to_string(), |ms| format!("{} ms", ms));
+        write!(f, "{} to {}", bound(self.since_ms), bound(self.until_ms))
+    }
+}
+
+
 /// Generate a complete markdown diagnostic report.
 pub fn generate_report(
     events: &[InterventionEvent],
@@ -17,15 +55,82 @@ pub fn generate_report(
     effectiveness: &HashMap<(SignalType, InterventionAction), ActionEffectiveness>,
     thresholds: &HashMap<SignalType, AdaptiveThreshold>,
 ) -> String {
-    let mut out = String::new();
+    collect(|out| generate_report_into(out, events, reliability, effectiveness, thresholds))
+}
 
+/// Write a complete markdown diagnostic report to `out`.
+pub fn generate_report_into(
+    out: &mut impl Write,
+    events: &[InterventionEvent],
+    reliability: &HashMap<SignalType, SignalReliability>,
+    effectiveness: &HashMap<(SignalType, InterventionAction), ActionEffectiveness>,
+    thresholds: &HashMap<SignalType, AdaptiveThreshold>,
+) -> io::Result<()> {
+    render_report(out, events, None, reliability, effectiveness, thresholds)
+}
+
+/// Generate a report covering only the events inside `window`.
+///
+/// Reliability and effectiveness are recomputed from the windowed events,
+/// so the tables describe the window rather than all-time history.
+/// Thresholds are the engine's current ones and are shown unchanged.
+pub fn generate_window_report(
+    events: &[InterventionEvent],
+    window: &ReportWindow,
+    thresholds: &HashMap<SignalType, AdaptiveThreshold>,
+) -> String {
+    collect(|out| generate_window_report_into(out, events, window, thresholds))
+}
+
+/// Write a report covering only the events inside `window` to `out`.
+pub fn generate_window_report_into(
+    out: &mut impl Write,
+    events: &[InterventionEvent],
+    window: &ReportWindow,
+    thresholds: &HashMap<SignalType, AdaptiveThreshold>,
+) -> io::Result<()> {
+    let scoped: Vec<InterventionEvent> = events
+        .iter()
+        .filter(|e| window.contains(e.timestamp_ms))
+        .cloned()
+        .collect();
+    let rel = reliability::compute_reliability(&scoped);
+    let eff = reliability::compute_effectiveness(&scoped);
+    render_report(out, &scoped, Some(window), &rel, &eff, thresholds)
+}
+
+/// Run a report writer against an in-memory buffer.
+fn collect(write: impl FnOnce(&mut Vec<u8>) -> io::Result<()>) -> String {
+    let mut buf = Vec::new();
+    write(&mut buf).expect("writing to a Vec cannot fail");
+    String::from_utf8(buf).expect("report is valid UTF-8")
+}
+
+fn render_report(
+    out: &mut impl Write,
+    events: &[InterventionEvent],
+    window: Option<&ReportWindow>,
+    reliability: &HashMap<SignalType, SignalReliability>,
+    effectiveness: &HashMap<(SignalType, InterventionAction), ActionEffectiveness>,
+    thresholds: &HashMap<SignalType, AdaptiveThreshold>,
+) -> io::Result<()> {
     // --- Summary ---
-    out.push_str("# Diagnosis Report\n\n");
-    out.push_str("## Summary\n\n");
+    writeln!(out, "# Diagnosis Report\n")?;
+    writeln!(out, "## Summary\n")?;
+
+    let window = window.filter(|w| !w.is_unbounded());
+    if let Some(w) = window {
+        writeln!(out, "- **Window:** {}", w)?;
+    }
 
     if events.is_empty() {
-        out.push_str("No intervention events recorded.\n\n");
-        return out;
+        if window.is_some() {
+            writeln!(out, "- **Total events:** 0\n")?;
+            writeln!(out, "No events in window.\n")?;
+        } else {
+            writeln!(out, "No intervention events recorded.\n")?;
+        }
+        return Ok(());
     }
 
     let total = events.len();
@@ -44,19 +149,21 @@ pub fn generate_report(
         0.0
     };
 
-    out.push_str(&format!("- **Total events:** {}\n", total));
-    out.push_str(&format!(
-        "- **Time range:** {} ms to {} ms\n",
+    writeln!(out, "- **Total events:** {}", total)?;
+    writeln!(
+        out,
+        "- **Time range:** {} ms to {} ms",
         min_ts, max_ts
-    ));
-    out.push_str(&format!(
-        "- **Overall success rate:** {:.1}%\n",
+    )?;
+    writeln!(
+        out,
+        "- **Overall success rate:** {:.1}%",
         success_rate * 100.0
-    ));
-    out.push('\n');
+    )?;
+    writeln!(out)?;
 
     // --- Signal Reliability Table ---
-    out.push_str("## Signal Reliability\n\n");
+    writeln!(out, "## Signal Reliability\n")?;
 
     let mut rel_entries: Vec<&SignalReliability> = reliability.values().collect();
     rel_entries.sort_by(|a, b| {
@@ -66,17 +173,20 @@ pub fn generate_report(
     });
 
     if rel_entries.is_empty() {
-        out.push_str("No signal reliability data.\n\n");
+        writeln!(out, "No signal reliability data.\n")?;
     } else {
-        out.push_str(
-            "| Signal | Fires | True+ | False+ | Unknown | Reliability | Avg Resolution |\n",
-        );
-        out.push_str(
-            "|--------|-------|-------|--------|---------|-------------|----------------|\n",
-        );
+        writeln!(
+            out,
+            "| Signal | Fires | True+ | False+ | Unknown | Reliability | Avg Resolution |",
+        )?;
+        writeln!(
+            out,
+            "|--------|-------|-------|--------|---------|-------------|----------------|",
+        )?;
         for r in &rel_entries {
-            out.push_str(&format!(
-                "| {} | {} | {} | {} | {} | {:.2} | {}ms |\n",
+            writeln!(
+                out,
+                "| {} | {} | {} | {} | {} | {:.2} | {}ms |",
                 r.signal,
                 r.total_fires,
                 r.true_positives,
@@ -84,13 +194,13 @@ pub fn generate_report(
                 r.unknown,
                 r.reliability_score,
                 r.avg_resolution_ms,
-            ));
+            )?;
         }
-        out.push('\n');
+        writeln!(out)?;
     }
 
     // --- Intervention Effectiveness Table ---
-    out.push_str("## Intervention Effectiveness\n\n");
+    writeln!(out, "## Intervention Effectiveness\n")?;
 
     let mut eff_entries: Vec<&ActionEffectiveness> = effectiveness.values().collect();
     eff_entries.sort_by(|a, b| {
@@ -100,40 +210,45 @@ pub fn generate_report(
     });
 
     if eff_entries.is_empty() {
-        out.push_str("No intervention effectiveness data.\n\n");
+        writeln!(out, "No intervention effectiveness data.\n")?;
     } else {
-        out.push_str(
-            "| Signal | Action | Attempts | Successes | Failures | Success Rate |\n",
-        );
-        out.push_str(
-            "|--------|--------|----------|-----------|----------|-------------|\n",
-        );
+        writeln!(
+            out,
+            "| Signal | Action | Attempts | Successes | Failures | Success Rate |",
+        )?;
+        writeln!(
+            out,
+            "|--------|--------|----------|-----------|----------|-------------|",
+        )?;
         for e in &eff_entries {
-            out.push_str(&format!(
-                "| {} | {} | {} | {} | {} | {:.1}% |\n",
+            writeln!(
+                out,
+                "| {} | {} | {} | {} | {} | {:.1}% |",
                 e.signal,
                 e.action,
                 e.attempts,
                 e.successes,
                 e.failures,
                 e.success_rate * 100.0,
-            ));
+            )?;
         }
-        out.push('\n');
+        writeln!(out)?;
     }
 
     // --- Threshold Adjustments ---
-    out.push_str("## Threshold Adjustments\n\n");
+    writeln!(out, "## Threshold Adjustments\n")?;
 
     if thresholds.is_empty() {
-        out.push_str("No threshold adjustments configured.\n\n");
+        writeln!(out, "No threshold adjustments configured.\n")?;
     } else {
-        out.push_str(
-            "| Signal | Base | Adjusted | Reliability | Reason |\n",
-        );
-        out.push_str(
-            "|--------|------|----------|-------------|--------|\n",
-        );
+        writeln!(
+            out,
+            "| Signal | Base | Adjusted | Reliability | Reason |",
+        )?;
+        writeln!(
+            out,
+            "|--------|------|----------|-------------|--------|",
+        )?;
         let mut thresh_entries: Vec<&AdaptiveThreshold> = thresholds.values().collect();
         thresh_entries.sort_by(|a, b| {
             a.reliability_score
@@ -141,62 +256,141 @@ pub fn generate_report(
                 .unwrap_or(std::cmp::Ordering::Equal)
         });
         for t in &thresh_entries {
-            out.push_str(&format!(
-                "| {} | {}ms | {}ms | {:.2} | {} |\n",
+            writeln!(
+                out,
+                "| {} | {}ms | {}ms | {:.2} | {} |",
                 t.signal,
                 t.base_timeout_ms,
                 t.adjusted_timeout_ms,
                 t.reliability_score,
                 t.adjustment_reason,
-            ));
+            )?;
         }
-        out.push('\n');
+        writeln!(out)?;
     }
 
     // --- Recommendations ---
-    out.push_str("## Recommendations\n\n");
-
-    let mut has_recommendations = false;
+    writeln!(out, "## Recommendations\n")?;
 
-    // Signals with reliability < 0.2
-    for r in &rel_entries {
-        if r.reliability_score < 0.2 && r.total_fires > 0 {
-            out.push_str(&format!(
-                "- **{}** — reliability {:.2}, consider removing or increasing threshold\n",
-                r.signal, r.reliability_score,
-            ));
-            has_recommendations = true;
+    let recommendations = recommendations(&rel_entries, &eff_entries, thresholds);
+    if recommendations.is_empty() {
+        writeln!(out, "No actionable recommendations at this time.")?;
+    } else {
+        for rec in &recommendations {
+            writeln!(out, "- {}", rec)?;
         }
     }
 
-    // Actions with success rate < 0.1
-    for e in &eff_entries {
-        if e.success_rate < 0.1 && e.attempts > 0 {
-            out.push_str(&format!(
-                "- **{} + {}** — success rate {:.1}%, this intervention rarely works for this signal\n",
-                e.signal, e.action, e.success_rate * 100.0,
+    writeln!(out)?;
+    Ok(())
+}
+
+
+// ---------------------------------------------------------------------------
+// Recommendations
+// ---------------------------------------------------------------------------
+
+/// Signals with reliability below this are candidates for a longer timeout.
+pub const LOW_RELIABILITY_THRESHOLD: f64 = 0.2;
+
+/// Signals at or above this reliability (with enough fires) warrant a faster response.
+pub const HIGH_RELIABILITY_THRESHOLD: f64 = 0.8;
+
+/// Minimum fires/attempts before a signal or action is judged at all.
+pub const MIN_SAMPLES: u64 = 5;
+
+/// Success-rate lead the best action needs over the runner-up to be preferred.
+pub const DOMINANCE_MARGIN: f64 = 0.25;
+
+/// Actions with a success rate below this are flagged as rarely working.
+pub const LOW_SUCCESS_THRESHOLD: f64 = 0.1;
+
+/// Derive concrete recommendations from the statistics. Each entry cites
+/// the numbers that support it.
+fn recommendations(
+    rel_entries: &[&SignalReliability],
+    eff_entries: &[&ActionEffectiveness],
+    thresholds: &HashMap<SignalType, AdaptiveThreshold>,
+) -> Vec<String> {
+    let mut recs = Vec::new();
+
+    for r in rel_entries {
+        if r.total_fires == 0 {
+            continue;
+        }
+        if r.total_fires < MIN_SAMPLES {
+            recs.push(format!(
+                "**{}** — too few samples to judge ({} fires, need {})",
+                r.signal, r.total_fires, MIN_SAMPLES,
+            ));
+            continue;
+        }
+        if r.reliability_score < LOW_RELIABILITY_THRESHOLD {
+            let timeout = thresholds
+                .get(&r.signal)
+                .map(|t| format!(", current timeout {}ms", t.adjusted_timeout_ms))
+                .unwrap_or_default();
+            recs.push(format!(
+                "**{}** — reliability {:.2} below {:.2} ({} true+ / {} false+ over {} fires{}), \
+                 consider raising timeout or disabling this signal",
+                r.signal,
+                r.reliability_score,
+                LOW_RELIABILITY_THRESHOLD,
+                r.true_positives,
+                r.false_positives,
+                r.total_fires,
+                timeout,
+            ));
+        } else if r.reliability_score >= HIGH_RELIABILITY_THRESHOLD && r.total_fires >= 10 {
+            recs.push(format!(
+                "**{}** — reliable indicator ({:.2}, {} fires), consider faster response",
+                r.signal, r.reliability_score, r.total_fires,
             ));
-            has_recommendations = true;
         }
     }
 
-    // Signals with high fire count and high reliability
-    for r in &rel_entries {
-        if r.reliability_score >= 0.8 && r.total_fires >= 10 {
-            out.push_str(&format!(
-                "- **{}** — reliable indicator ({:.2}, {} fires), consider faster response\n",
-                r.signal, r.reliability_score, r.total_fires,
+    // Prefer the best action per signal when it clearly dominates the runner-up.
+    let mut signals: Vec<&SignalType> = eff_entries.iter().map(|e| &e.signal).collect();
+    signals.sort_by_key(|s| s.to_string());
+    signals.dedup();
+    for signal in signals {
+        let mut candidates: Vec<&&ActionEffectiveness> = eff_entries
+            .iter()
+            .filter(|e| &e.signal == signal && e.attempts >= MIN_SAMPLES)
+            .collect();
+        if candidates.len() < 2 {
+            continue;
+        }
+        candidates.sort_by(|a, b| {
+            b.success_rate
+                .partial_cmp(&a.success_rate)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        let (best, runner_up) = (candidates[0], candidates[1]);
+        if best.success_rate - runner_up.success_rate >= DOMINANCE_MARGIN {
+            recs.push(format!(
+                "**{}** — prefer {} ({:.1}% over {} attempts) to {} ({:.1}% over {} attempts)",
+                signal,
+                best.action,
+                best.success_rate * 100.0,
+                best.attempts,
+                runner_up.action,
+                runner_up.success_rate * 100.0,
+                runner_up.attempts,
             ));
-            has_recommendations = true;
         }
     }
 
-    if !has_recommendations {
-        out.push_str("No actionable recommendations at this time.\n");
+    for e in eff_entries {
+        if e.success_rate < LOW_SUCCESS_THRESHOLD && e.attempts >= MIN_SAMPLES {
+            recs.push(format!(
+                "**{} + {}** — success rate {:.1}% ({}/{} attempts), this intervention rarely works for this signal",
+                e.signal, e.action, e.success_rate * 100.0, e.successes, e.attempts,
+            ));
+        }
     }
 
-    out.push('\n');
-    out
+    recs
 }
 
 
@@ -228,6 +422,7 @@ mod tests {
             outcome_detail: "test".to_string(),
             duration_ms: 1000,
             failure_mode: "none".to_string(),
+            annotations: Vec::new(),
         }
     }
 
@@ -243,6 +438,82 @@ mod tests {
         assert!(report.contains("No intervention events recorded"));
     }
 
+    #[test]
+    fn streamed_report_matches_string_report() {
+        let events: Vec<InterventionEvent> = (0..6)
+            .map(|id| {
+                let outcome = if id % 2 == 0 {
+                    InterventionOutcome::Resolved
+                } else {
+                    InterventionOutcome::SelfResolved
+                };
+                make_event(id, SignalType::HeartbeatStale, InterventionAction::Retry, outcome)
+            })
+            .collect();
+        let rel = reliability::compute_reliability(&events);
+        let eff = reliability::compute_effectiveness(&events);
+        let thresholds = HashMap::new();
+
+        let mut streamed = Vec::new();
+        generate_report_into(&mut streamed, &events, &rel, &eff, &thresholds).unwrap();
+        assert_eq!(streamed, generate_report(&events, &rel, &eff, &thresholds).into_bytes());
+
+        let window = ReportWindow::new(Some(1200), None);
+        let mut streamed = Vec::new();
+        generate_window_report_into(&mut streamed, &events, &window, &thresholds).unwrap();
+        assert_eq!(
+            streamed,
+            generate_window_report(&events, &window, &thresholds).into_bytes()
+        );
+    }
+
+    #[test]
+    fn window_bounds_are_half_open() {
+        let w = ReportWindow::new(Some(100), Some(200));
+        assert!(!w.contains(99));
+        assert!(w.contains(100));
+        assert!(w.contains(199));
+        assert!(!w.contains(200));
+        assert!(ReportWindow::default().contains(0));
+        assert_eq!(w.to_string(), "100 ms to 200 ms");
+        assert_eq!(ReportWindow::new(Some(5), None).to_string(), "5 ms to open");
+    }
+
+    #[test]
+    fn window_report_recomputes_stats() {
+        // ids 0-1 resolve (ts 1000, 1100); ids 2-4 are false alarms (ts 1200+).
+        let mut events = Vec::new();
+        for id in 0..2 {
+            events.push(make_event(id, SignalType::HeartbeatStale, InterventionAction::Retry, InterventionOutcome::Resolved));
+        }
+        for id in 2..5 {
+            events.push(make_event(id, SignalType::HeartbeatStale, InterventionAction::Retry, InterventionOutcome::SelfResolved));
+        }
+        let all = generate_window_report(&events, &ReportWindow::default(), &HashMap::new());
+        assert!(all.contains("- **Total events:** 5"));
+        assert!(all.contains("40.0%"));
+        assert!(!all.contains("**Window:**"));
+
+        let early = generate_window_report(&events, &ReportWindow::new(None, Some(1200)), &HashMap::new());
+        assert!(early.contains("- **Window:** open to 1200 ms"));
+        assert!(early.contains("- **Total events:** 2"));
+        assert!(early.contains("100.0%"));
+        assert!(!early.contains("40.0%"));
+    }
+
+    #[test]
+    fn empty_window_report_is_well_formed() {
+        let events = vec![
+            make_event(0, SignalType::HeartbeatStale, InterventionAction::Retry, InterventionOutcome::Resolved),
+        ];
+        let report = generate_window_report(&events, &ReportWindow::new(Some(5000), None), &HashMap::new());
+        assert!(report.starts_with("# Diagnosis Report\n\n## Summary\n\n"));
+        assert!(report.contains("- **Window:** 5000 ms to open"));
+        assert!(report.contains("- **Total events:** 0"));
+        assert!(report.contains("No events in window."));
+        assert!(!report.contains("No intervention events recorded"));
+    }
+
     #[test]
     fn report_contains_signal_reliability_table() {
         let events = vec![
@@ -260,6 +531,8 @@ mod tests {
                 unknown: 0,
                 reliability_score: 1.0,
                 avg_resolution_ms: 1000,
+                confidence_low: 0.0,
+                confidence_high: 1.0,
             },
         );
 
@@ -311,12 +584,14 @@ mod tests {
                 unknown: 0,
                 reliability_score: 0.0,
                 avg_resolution_ms: 0,
+                confidence_low: 0.0,
+                confidence_high: 1.0,
             },
         );
 
         let report = generate_report(&events, &rel, &HashMap::new(), &HashMap::new());
         assert!(report.contains("## Recommendations"));
-        assert!(report.contains("consider removing or increasing threshold"));
+        assert!(report.contains("consider raising timeout"));
     }
 
     #[test]
@@ -336,6 +611,8 @@ mod tests {
                 unknown: 0,
                 reliability_score: 0.93,
                 avg_resolution_ms: 2000,
+                confidence_low: 0.0,
+                confidence_high: 1.0,
             },
         );
 
@@ -403,4 +680,139 @@ mod tests {
         let report = generate_report(&events, &HashMap::new(), &eff, &HashMap::new());
         assert!(report.contains("this intervention rarely works"));
     }
+
+    #[test]
+    fn low_success_action_needs_min_samples() {
+        let events = vec![
+            make_event(0, SignalType::HeartbeatStale, InterventionAction::Retry, InterventionOutcome::StillBroken),
+        ];
+
+        let mut eff = HashMap::new();
+        eff.insert(
+            (SignalType::HeartbeatStale, InterventionAction::Retry),
+            make_eff(SignalType::HeartbeatStale, InterventionAction::Retry, MIN_SAMPLES - 1, 0),
+        );
+
+        let report = generate_report(&events, &HashMap::new(), &eff, &HashMap::new());
+        assert!(!report.contains("this intervention rarely works"));
+    }
+
+    fn make_rel(signal: SignalType, fires: u64, tp: u64, fp: u64) -> SignalReliability {
+        let denom = tp + fp;
+        SignalReliability {
+            signal,
+            total_fires: fires,
+            true_positives: tp,
+            false_positives: fp,
+            unknown: fires - denom,
+            reliability_score: if denom > 0 { tp as f64 / denom as f64 } else { 0.5 },
+            avg_resolution_ms: 0,
+            confidence_low: 0.0,
+            confidence_high: 1.0,
+        }
+    }
+
+    fn make_eff(
+        signal: SignalType,
+        action: InterventionAction,
+        attempts: u64,
+        successes: u64,
+    ) -> ActionEffectiveness {
+        ActionEffectiveness {
+            signal,
+            action,
+            attempts,
+            successes,
+            failures: attempts - successes,
+            success_rate: successes as f64 / attempts as f64,
+        }
+    }
+
+    #[test]
+    fn low_reliability_recommends_raising_timeout_with_numbers() {
+        let events = vec![
+            make_event(0, SignalType::OutputStall, InterventionAction::Ignore, InterventionOutcome::SelfResolved),
+        ];
+
+        let mut rel = HashMap::new();
+        rel.insert(SignalType::OutputStall, make_rel(SignalType::OutputStall, 10, 1, 9));
+
+        let mut thresholds = HashMap::new();
+        thresholds.insert(
+            SignalType::OutputStall,
+            crate::diagnosis::thresholds::AdaptiveThreshold {
+                signal: SignalType::OutputStall,
+                base_timeout_ms: 30_000,
+                adjusted_timeout_ms: 90_000,
+                reliability_score: 0.1,
+                adjustment_reason: "very low".to_string(),
+            },
+        );
+
+        let report = generate_report(&events, &rel, &HashMap::new(), &thresholds);
+        let line = report
+            .lines()
+            .find(|l| l.contains("consider raising timeout"))
+            .expect("missing raise-timeout recommendation");
+        assert!(line.contains("output_stall"));
+        assert!(line.contains("0.10"));
+        assert!(line.contains("1 true+ / 9 false+ over 10 fires"));
+        assert!(line.contains("current timeout 90000ms"));
+    }
+
+    #[test]
+    fn few_samples_flagged_instead_of_judged() {
+        let events = vec![
+            make_event(0, SignalType::OutputStall, InterventionAction::Ignore, InterventionOutcome::SelfResolved),
+        ];
+
+        let mut rel = HashMap::new();
+        rel.insert(SignalType::OutputStall, make_rel(SignalType::OutputStall, 2, 0, 2));
+
+        let report = generate_report(&events, &rel, &HashMap::new(), &HashMap::new());
+        assert!(report.contains("too few samples to judge (2 fires, need 5)"));
+        assert!(!report.contains("consider raising timeout"));
+    }
+
+    #[test]
+    fn dominant_action_is_preferred() {
+        let events = vec![
+            make_event(0, SignalType::HeartbeatStale, InterventionAction::Restart, InterventionOutcome::Resolved),
+        ];
+
+        let mut eff = HashMap::new();
+        eff.insert(
+            (SignalType::HeartbeatStale, InterventionAction::Restart),
+            make_eff(SignalType::HeartbeatStale, InterventionAction::Restart, 10, 9),
+        );
+        eff.insert(
+            (SignalType::HeartbeatStale, InterventionAction::Retry),
+            make_eff(SignalType::HeartbeatStale, InterventionAction::Retry, 10, 4),
+        );
+
+        let report = generate_report(&events, &HashMap::new(), &eff, &HashMap::new());
+        assert!(report.contains(
+            "prefer restart (90.0% over 10 attempts) to retry (40.0% over 10 attempts)"
+        ));
+    }
+
+    #[test]
+    fn close_actions_are_not_preferred() {
+        let events = vec![
+            make_event(0, SignalType::HeartbeatStale, InterventionAction::Restart, InterventionOutcome::Resolved),
+        ];
+
+        let mut eff = HashMap::new();
+        eff.insert(
+            (SignalType::HeartbeatStale, InterventionAction::Restart),
+            make_eff(SignalType::HeartbeatStale, InterventionAction::Restart, 10, 6),
+        );
+        eff.insert(
+            (SignalType::HeartbeatStale, InterventionAction::Retry),
+            make_eff(SignalType::HeartbeatStale, InterventionAction::Retry, 10, 5),
+        );
+
+        let report = generate_report(&events, &HashMap::new(), &eff, &HashMap::new());
+        assert!(!report.contains("prefer "));
+    }
 }
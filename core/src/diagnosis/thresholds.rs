@@ -95,6 +95,8 @@ mod tests {
             unknown: 0,
             reliability_score: score,
             avg_resolution_ms: 1000,
+            confidence_low: 0.0,
+            confidence_high: 1.0,
         }
     }
 
@@ -8,6 +8,8 @@ use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
 
+use super::sandbox::ResourceLimits;
+
 // ---------------------------------------------------------------------------
 // ExecutionState
 // ---------------------------------------------------------------------------
@@ -82,6 +84,28 @@ pub struct Execution {
     pub timeout_ms: Option<u64>,
     pub created_ms: u64,
     pub priority: u32,
+    /// Resource caps the runner applies via `sandbox_command`. A
+    /// `max_wall_ms` here and `timeout_ms` are the same cap; the shorter
+    /// one applies (see `effective_limits`).
+    #[serde(default)]
+    pub limits: ResourceLimits,
+}
+
+impl Execution {
+    /// `limits` with `timeout_ms` folded in as the wall-clock cap, so the
+    /// shorter of the two wins both here and in `timeout_check`.
+    pub fn effective_limits(&self) -> ResourceLimits {
+        self.limits.tightest(&ResourceLimits {
+            max_wall_ms: self.timeout_ms,
+            ..Default::default()
+        })
+    }
+
+    /// The command wrapped with the `timeout`/`ulimit` prefix for
+    /// `effective_limits`.
+    pub fn sandbox_command(&self) -> Vec<String> {
+        self.effective_limits().wrap(&self.command)
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -232,7 +256,8 @@ impl TaskExecutor {
             .iter()
             .filter_map(|(id, exec)| {
                 if let ExecutionState::Running { started_ms, .. } = &exec.state {
-                    exec.timeout_ms.map(|timeout| (id.clone(), *started_ms, timeout))
+                    let timeout = exec.effective_limits().max_wall_ms;
+                    timeout.map(|timeout| (id.clone(), *started_ms, timeout))
                 } else {
                     None
                 }
@@ -426,6 +451,7 @@ fn make_execution(id: &str, task_id: &str, agent: &str, priority: u32) -> Execut
         timeout_ms: None,
         created_ms: 1000,
         priority,
+        limits: ResourceLimits::default(),
     }
 }
 
@@ -1016,4 +1042,33 @@ mod tests {
         let back: Execution = serde_json::from_str(&json).unwrap();
         assert_eq!(back.env.get("RUST_LOG").unwrap(), "debug");
     }
+
+    #[test]
+    fn execution_sandbox_command_applies_limits() {
+        let mut e = make_execution("e1", "T1", "w1", 1);
+        assert_eq!(e.sandbox_command(), vec!["cargo", "test"]);
+        e.limits.max_wall_ms = Some(120_000);
+        assert_eq!(e.sandbox_command(), vec!["timeout", "120s", "cargo", "test"]);
+
+        let json = serde_json::to_string(&e).unwrap();
+        let back: Execution = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.limits.max_wall_ms, Some(120_000));
+    }
+
+    #[test]
+    fn execution_wall_limit_is_shorter_of_timeout_and_limits() {
+        let mut e = make_execution("e1", "T1", "w1", 1);
+        e.timeout_ms = Some(30_000);
+        assert_eq!(e.sandbox_command(), vec!["timeout", "30s", "cargo", "test"]);
+        e.limits.max_wall_ms = Some(10_000);
+        assert_eq!(e.effective_limits().max_wall_ms, Some(10_000));
+        e.timeout_ms = None;
+        assert_eq!(e.effective_limits().max_wall_ms, Some(10_000));
+
+        let mut ex = TaskExecutor::new(4);
+        ex.submit(e).unwrap();
+        ex.start("e1", 1000).unwrap();
+        assert!(ex.timeout_check(5000).is_empty());
+        assert_eq!(ex.timeout_check(11_000), vec!["e1".to_string()]);
+    }
 }
@@ -1,7 +1,8 @@
 //! Output capture and buffering — ring buffers, pattern matching, aggregation.
 //!
 //! Provides `OutputBuffer` for per-execution output capture with configurable
-//! max capacity (ring buffer eviction), `PatternMatcher` for scanning output
+//! max capacity (ring buffer eviction) and an optional total byte cap
+//! (truncation, see `ResourceLimits::max_output_bytes`), `PatternMatcher` for scanning output
 //! lines against configurable patterns, and `OutputAggregator` for tracking
 //! multiple output buffers across executions.
 
@@ -9,6 +10,8 @@ use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
 
+use super::sandbox::ResourceLimits;
+
 // ---------------------------------------------------------------------------
 // OutputStream
 // ---------------------------------------------------------------------------
@@ -41,13 +44,18 @@ pub struct OutputLine {
 /// A ring buffer of output lines with configurable max capacity.
 ///
 /// When `max_lines` is reached, the oldest lines are evicted to make room
-/// for new ones.
+/// for new ones. When `max_bytes` is set, capture stops once that many
+/// bytes have been pushed: the line crossing the cap is cut short, later
+/// lines are dropped, and the buffer is flagged as truncated.
 #[derive(Debug)]
 pub struct OutputBuffer {
     lines: Vec<OutputLine>,
     max_lines: usize,
     total_pushed: usize,
     total_bytes: usize,
+    max_bytes: Option<usize>,
+    captured_bytes: usize,
+    truncated: bool,
 }
 
 impl OutputBuffer {
@@ -58,11 +66,35 @@ impl OutputBuffer {
             max_lines,
             total_pushed: 0,
             total_bytes: 0,
+            max_bytes: None,
+            captured_bytes: 0,
+            truncated: false,
+        }
+    }
+
+    /// Create a buffer that also stops capturing after `max_bytes` bytes.
+    pub fn with_max_bytes(max_lines: usize, max_bytes: Option<usize>) -> Self {
+        OutputBuffer {
+            max_bytes,
+            ..Self::new(max_lines)
         }
     }
 
     /// Push a new line into the buffer. If at capacity, the oldest line is evicted.
+    /// Past the byte cap, the line is cut short or dropped.
     pub fn push_line(&mut self, text: &str, stream: OutputStream, timestamp_ms: u64) {
+        let text = match self.max_bytes {
+            Some(max) if self.captured_bytes + text.len() > max => {
+                self.truncated = true;
+                let keep = floor_char_boundary(text, max.saturating_sub(self.captured_bytes));
+                if keep == 0 {
+                    return;
+                }
+                &text[..keep]
+            }
+            _ => text,
+        };
+        self.captured_bytes += text.len();
         self.total_pushed += 1;
         self.total_bytes += text.len();
 
@@ -126,6 +158,23 @@ impl OutputBuffer {
     pub fn total_lines_pushed(&self) -> usize {
         self.total_pushed
     }
+
+    /// Whether output was cut off at the byte cap.
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+}
+
+/// The largest index `<= max` that falls on a char boundary of `s`.
+fn floor_char_boundary(s: &str, max: usize) -> usize {
+    if max >= s.len() {
+        return s.len();
+    }
+    let mut i = max;
+    while !s.is_char_boundary(i) {
+        i -= 1;
+    }
+    i
 }
 
 // ---------------------------------------------------------------------------
@@ -237,6 +286,15 @@ impl OutputAggregator {
             .or_insert_with(|| OutputBuffer::new(max))
     }
 
+    /// Get or create the buffer for an execution, capping a new buffer at
+    /// `limits.max_output_bytes`.
+    pub fn buffer_for_limits(&mut self, execution_id: &str, limits: &ResourceLimits) -> &mut OutputBuffer {
+        let max = self.default_max_lines;
+        self.buffers
+            .entry(execution_id.to_string())
+            .or_insert_with(|| OutputBuffer::with_max_bytes(max, limits.max_output_bytes))
+    }
+
     /// Push a line to the buffer for the given execution ID.
     pub fn push_line(
         &mut self,
@@ -657,4 +715,49 @@ mod tests {
         assert_eq!(matches.len(), 1);
         assert_eq!(matches[0].pattern.action, PatternAction::Ignore);
     }
+
+    #[test]
+    fn max_bytes_truncates_crossing_line_and_drops_rest() {
+        let mut buf = OutputBuffer::with_max_bytes(100, Some(10));
+        buf.push_line("123456", OutputStream::Stdout, 1);
+        assert!(!buf.is_truncated());
+        buf.push_line("abcdef", OutputStream::Stdout, 2);
+        buf.push_line("dropped", OutputStream::Stderr, 3);
+
+        assert!(buf.is_truncated());
+        let texts: Vec<&str> = buf.lines().iter().map(|l| l.text.as_str()).collect();
+        assert_eq!(texts, vec!["123456", "abcd"]);
+        assert_eq!(buf.byte_count(), 10);
+        assert_eq!(buf.total_lines_pushed(), 2);
+    }
+
+    #[test]
+    fn max_bytes_cuts_on_char_boundary() {
+        let mut buf = OutputBuffer::with_max_bytes(10, Some(4));
+        // "é" is two bytes; a cut at byte 4 would split the second one.
+        buf.push_line("aéé", OutputStream::Stdout, 1);
+        assert!(buf.is_truncated());
+        assert_eq!(buf.lines()[0].text, "aé");
+    }
+
+    #[test]
+    fn unlimited_buffer_never_truncates() {
+        let mut buf = OutputBuffer::new(10);
+        for _ in 0..5 {
+            buf.push_line(&"x".repeat(1000), OutputStream::Stdout, 1);
+        }
+        assert!(!buf.is_truncated());
+        assert_eq!(buf.byte_count(), 5000);
+    }
+
+    #[test]
+    fn aggregator_buffer_for_limits_applies_output_cap() {
+        let mut agg = OutputAggregator::new(100);
+        let limits = ResourceLimits { max_output_bytes: Some(3), ..Default::default() };
+        agg.buffer_for_limits("e1", &limits);
+        agg.push_line("e1", "hello", OutputStream::Stdout, 1);
+        let buf = agg.get_buffer("e1").unwrap();
+        assert!(buf.is_truncated());
+        assert_eq!(buf.lines()[0].text, "hel");
+    }
 }
@@ -3,9 +3,24 @@
 //! A `Pipeline` is an ordered sequence of `PipelineStep` items, each with
 //! optional conditions and error-handling policy. The pipeline tracks per-step
 //! results and provides methods for advancing through steps.
+//!
+//! Consecutive steps may form a `StepGroup`: its members have no ordering
+//! between them and may run in parallel, and the step after the group (the
+//! join) only becomes current once every member has finished. A member
+//! failure is handled by the group's `GroupFailurePolicy`.
+
+use std::collections::HashMap;
 
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 
+/// Most bytes of a step's output kept for `WhenOutputMatches`; longer
+/// output keeps its tail.
+pub const MAX_CONDITION_OUTPUT_BYTES: usize = 64 * 1024;
+
+/// Compiled `WhenOutputMatches` patterns, keyed by pattern text.
+pub type PatternCache = HashMap<String, Regex>;
+
 // ---------------------------------------------------------------------------
 // StepCondition
 // ---------------------------------------------------------------------------
@@ -19,6 +34,11 @@ pub enum StepCondition {
     OnFailure,
     ExitCodeEquals { code: i32 },
     ExitCodeNonZero,
+    /// The previous step's captured output matches this regex. Output is
+    /// only known when the step was completed with
+    /// `Pipeline::complete_step_with_output`, and only its last
+    /// `MAX_CONDITION_OUTPUT_BYTES` are matched.
+    WhenOutputMatches { pattern: String },
 }
 
 impl StepCondition {
@@ -26,6 +46,17 @@ impl StepCondition {
     /// Returns true if the step should execute.
     /// `prev_exit_code` is None if this is the first step.
     pub fn evaluate(&self, prev_exit_code: Option<i32>) -> bool {
+        self.evaluate_with_output(prev_exit_code, None, &mut PatternCache::new())
+    }
+
+    /// Like `evaluate`, also given the previous step's captured output.
+    /// A `WhenOutputMatches` pattern is compiled once into `patterns`.
+    pub fn evaluate_with_output(
+        &self,
+        prev_exit_code: Option<i32>,
+        prev_output: Option<&str>,
+        patterns: &mut PatternCache,
+    ) -> bool {
         match self {
             StepCondition::Always => true,
             StepCondition::OnSuccess => prev_exit_code == Some(0),
@@ -36,8 +67,58 @@ impl StepCondition {
             StepCondition::ExitCodeNonZero => {
                 matches!(prev_exit_code, Some(c) if c != 0)
             }
+            StepCondition::WhenOutputMatches { pattern } => {
+                let Some(output) = prev_output else {
+                    return false;
+                };
+                if !patterns.contains_key(pattern) {
+                    match Regex::new(pattern) {
+                        Ok(re) => patterns.insert(pattern.clone(), re),
+                        Err(_) => return false,
+                    };
+                }
+                patterns[pattern].is_match(output)
+            }
         }
     }
+
+    /// Check that the condition is well formed (its regex compiles).
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            StepCondition::WhenOutputMatches { pattern } => Regex::new(pattern)
+                .map(|_| ())
+                .map_err(|e| format!("invalid output pattern '{}': {}", pattern, e)),
+            _ => Ok(()),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// StepGroup
+// ---------------------------------------------------------------------------
+
+/// How a step group reacts when one of its members fails.
+///
+/// Members with `continue_on_error` never count as failures.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum GroupFailurePolicy {
+    /// Fail the pipeline at the first member failure; pending members and
+    /// the join are skipped.
+    #[default]
+    FailFast,
+    /// Let every member finish, then fail the pipeline if any member failed.
+    WaitForAll,
+    /// Let every member finish and advance to the join regardless. The join
+    /// sees the failing exit code, so `OnFailure` conditions apply.
+    Continue,
+}
+
+/// A named set of consecutive steps that run without ordering between them.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StepGroup {
+    pub name: String,
+    pub policy: GroupFailurePolicy,
 }
 
 // ---------------------------------------------------------------------------
@@ -53,6 +134,9 @@ pub struct PipelineStep {
     pub timeout_ms: Option<u64>,
     pub continue_on_error: bool,
     pub condition: Option<StepCondition>,
+    /// Name of the `StepGroup` this step belongs to, set by `add_group`.
+    #[serde(default)]
+    pub group: Option<String>,
 }
 
 // ---------------------------------------------------------------------------
@@ -111,8 +195,25 @@ pub struct Pipeline {
     pub steps: Vec<PipelineStep>,
     pub results: Vec<StepResult>,
     pub status: PipelineStatus,
+    #[serde(default)]
+    pub groups: Vec<StepGroup>,
     current_index: usize,
     started_ms: Option<u64>,
+    /// Indices of members of the current group that have finished.
+    #[serde(default)]
+    group_done: Vec<usize>,
+    /// Whether a member of the current group has failed.
+    #[serde(default)]
+    group_failed: bool,
+    /// Exit code the join sees: the first non-zero member exit, else 0.
+    #[serde(default)]
+    group_exit: Option<i32>,
+    /// Tail of the last completed step's output, for `WhenOutputMatches`.
+    #[serde(default)]
+    last_output: Option<String>,
+    /// Compiled `WhenOutputMatches` patterns.
+    #[serde(skip)]
+    output_patterns: PatternCache,
 }
 
 impl Pipeline {
@@ -123,8 +224,14 @@ impl Pipeline {
             steps: Vec::new(),
             results: Vec::new(),
             status: PipelineStatus::Pending,
+            groups: Vec::new(),
             current_index: 0,
             started_ms: None,
+            group_done: Vec::new(),
+            group_failed: false,
+            group_exit: None,
+            last_output: None,
+            output_patterns: PatternCache::new(),
         }
     }
 
@@ -133,10 +240,49 @@ impl Pipeline {
         if self.status != PipelineStatus::Pending {
             return Err("cannot add steps to a running or finished pipeline".into());
         }
+        if step.group.is_some() {
+            return Err("grouped steps must be added with add_group".into());
+        }
+        if let Some(condition) = &step.condition {
+            condition.validate().map_err(|e| format!("step '{}': {}", step.name, e))?;
+        }
         self.steps.push(step);
         Ok(())
     }
 
+    /// Add a group of steps that may run in parallel. The next step added
+    /// becomes the join and waits for every member to finish.
+    pub fn add_group(
+        &mut self,
+        name: &str,
+        policy: GroupFailurePolicy,
+        members: Vec<PipelineStep>,
+    ) -> Result<(), String> {
+        if self.status != PipelineStatus::Pending {
+            return Err("cannot add steps to a running or finished pipeline".into());
+        }
+        if members.is_empty() {
+            return Err(format!("group '{}' has no steps", name));
+        }
+        if self.groups.iter().any(|g| g.name == name) {
+            return Err(format!("group '{}' already exists", name));
+        }
+        for step in &members {
+            if let Some(condition) = &step.condition {
+                condition.validate().map_err(|e| format!("step '{}': {}", step.name, e))?;
+            }
+        }
+        self.groups.push(StepGroup {
+            name: name.to_string(),
+            policy,
+        });
+        for mut step in members {
+            step.group = Some(name.to_string());
+            self.steps.push(step);
+        }
+        Ok(())
+    }
+
     /// Start the pipeline. Transitions from Pending to Running.
     pub fn start(&mut self, now_ms: u64) -> Result<(), String> {
         if self.status != PipelineStatus::Pending {
@@ -154,12 +300,36 @@ impl Pipeline {
     /// Complete the current step with the given exit code and timing.
     ///
     /// Automatically advances to the next step or finishes the pipeline.
+    /// Inside a group this completes the first pending member; use
+    /// `complete_group_step` when members finish out of order.
     pub fn complete_step(
         &mut self,
         exit_code: i32,
         duration_ms: u64,
         output_lines: usize,
-        now_ms: u64,
+        _now_ms: u64,
+    ) -> Result<(), String> {
+        self.complete_step_inner(exit_code, duration_ms, output_lines, None)
+    }
+
+    /// Complete the current step with its captured output, so the next
+    /// step's `WhenOutputMatches` condition can test it.
+    pub fn complete_step_with_output(
+        &mut self,
+        exit_code: i32,
+        duration_ms: u64,
+        output: &str,
+    ) -> Result<(), String> {
+        let lines = output.lines().count();
+        self.complete_step_inner(exit_code, duration_ms, lines, Some(output.to_string()))
+    }
+
+    fn complete_step_inner(
+        &mut self,
+        exit_code: i32,
+        duration_ms: u64,
+        output_lines: usize,
+        output: Option<String>,
     ) -> Result<(), String> {
         if self.status != PipelineStatus::Running {
             return Err("pipeline is not running".into());
@@ -167,6 +337,11 @@ impl Pipeline {
         if self.current_index >= self.steps.len() {
             return Err("no more steps to complete".into());
         }
+        self.last_output = output.map(output_tail);
+        if let Some(idx) = self.pending_member() {
+            self.finish_member(idx, exit_code, duration_ms, output_lines);
+            return Ok(());
+        }
 
         let step = &self.steps[self.current_index];
         let step_name = step.name.clone();
@@ -195,11 +370,36 @@ impl Pipeline {
             self.skip_remaining();
             return Ok(());
         }
-        self.advance_skipping_conditions(now_ms);
+        self.advance_skipping_conditions(Some(exit_code));
 
         Ok(())
     }
 
+    /// Complete a named member of the current group.
+    ///
+    /// The pipeline moves on to the join only once every member is done,
+    /// or fails early according to the group's policy.
+    pub fn complete_group_step(
+        &mut self,
+        name: &str,
+        exit_code: i32,
+        duration_ms: u64,
+        output_lines: usize,
+    ) -> Result<(), String> {
+        if self.status != PipelineStatus::Running {
+            return Err("pipeline is not running".into());
+        }
+        let range = self
+            .group_range()
+            .ok_or_else(|| "current step is not part of a group".to_string())?;
+        let idx = range
+            .filter(|i| !self.group_done.contains(i))
+            .find(|&i| self.steps[i].name == name)
+            .ok_or_else(|| format!("no pending step '{}' in the current group", name))?;
+        self.finish_member(idx, exit_code, duration_ms, output_lines);
+        Ok(())
+    }
+
     /// Skip the current step with a given reason (records as Skipped).
     pub fn skip_step(&mut self, reason: &str) -> Result<(), String> {
         if self.status != PipelineStatus::Running {
@@ -209,8 +409,15 @@ impl Pipeline {
             return Err("no more steps to skip".into());
         }
 
-        let step_name = self.steps[self.current_index].name.clone();
         let _ = reason; // Reason noted but not stored in StepResult currently.
+        if let Some(idx) = self.pending_member() {
+            self.record_skipped(idx);
+            self.group_done.push(idx);
+            self.settle_group();
+            return Ok(());
+        }
+
+        let step_name = self.steps[self.current_index].name.clone();
 
         self.results.push(StepResult {
             step_name,
@@ -229,12 +436,25 @@ impl Pipeline {
         Ok(())
     }
 
-    /// Get the current step, if any.
+    /// Get the current step, if any. Inside a group this is the first
+    /// member that has not finished.
     pub fn current_step(&self) -> Option<&PipelineStep> {
+        self.runnable_steps().into_iter().next()
+    }
+
+    /// Steps that may run now: every unfinished member of the current
+    /// group, or just the current step outside a group.
+    pub fn runnable_steps(&self) -> Vec<&PipelineStep> {
         if self.status != PipelineStatus::Running {
-            return None;
+            return Vec::new();
+        }
+        match self.group_range() {
+            Some(range) => range
+                .filter(|i| !self.group_done.contains(i))
+                .map(|i| &self.steps[i])
+                .collect(),
+            None => self.steps.get(self.current_index).into_iter().collect(),
         }
-        self.steps.get(self.current_index)
     }
 
     /// Whether the pipeline has finished (completed, failed, or cancelled).
@@ -297,29 +517,139 @@ impl Pipeline {
     /// Skip all remaining steps (mark as Skipped in results).
     fn skip_remaining(&mut self) {
         while self.current_index < self.steps.len() {
-            let step_name = self.steps[self.current_index].name.clone();
-            self.results.push(StepResult {
-                step_name,
-                exit_code: None,
-                duration_ms: 0,
-                output_lines: 0,
-                status: StepStatus::Skipped,
-            });
+            if !self.group_done.contains(&self.current_index) {
+                self.record_skipped(self.current_index);
+            }
             self.current_index += 1;
         }
+        self.reset_group();
     }
 
-    /// Advance past steps whose conditions are not met (auto-skip).
-    fn advance_skipping_conditions(&mut self, _now_ms: u64) {
-        let prev_exit = self
-            .results
-            .last()
-            .and_then(|r| r.exit_code);
+    /// Record step `idx` as Skipped.
+    fn record_skipped(&mut self, idx: usize) {
+        self.results.push(StepResult {
+            step_name: self.steps[idx].name.clone(),
+            exit_code: None,
+            duration_ms: 0,
+            output_lines: 0,
+            status: StepStatus::Skipped,
+        });
+    }
+
+    /// Index range of the group starting at the current step, if any.
+    fn group_range(&self) -> Option<std::ops::Range<usize>> {
+        let name = self.steps.get(self.current_index)?.group.as_ref()?;
+        let end = self.steps[self.current_index..]
+            .iter()
+            .position(|s| s.group.as_ref() != Some(name))
+            .map_or(self.steps.len(), |n| self.current_index + n);
+        Some(self.current_index..end)
+    }
+
+    /// First unfinished member of the current group.
+    fn pending_member(&self) -> Option<usize> {
+        self.group_range()?.find(|i| !self.group_done.contains(i))
+    }
 
+    fn group_policy(&self, idx: usize) -> GroupFailurePolicy {
+        let name = self.steps[idx].group.as_deref();
+        self.groups
+            .iter()
+            .find(|g| Some(g.name.as_str()) == name)
+            .map(|g| g.policy)
+            .unwrap_or_default()
+    }
+
+    fn reset_group(&mut self) {
+        self.group_done.clear();
+        self.group_failed = false;
+        self.group_exit = None;
+    }
+
+    /// Record a finished group member and settle the group.
+    fn finish_member(&mut self, idx: usize, exit_code: i32, duration_ms: u64, output_lines: usize) {
+        let failed = exit_code != 0;
+        self.results.push(StepResult {
+            step_name: self.steps[idx].name.clone(),
+            exit_code: Some(exit_code),
+            duration_ms,
+            output_lines,
+            status: if failed {
+                StepStatus::Failed
+            } else {
+                StepStatus::Succeeded
+            },
+        });
+        if failed {
+            if !self.steps[idx].continue_on_error {
+                self.group_failed = true;
+            }
+            if !matches!(self.group_exit, Some(c) if c != 0) {
+                self.group_exit = Some(exit_code);
+            }
+        } else if self.group_exit.is_none() {
+            self.group_exit = Some(0);
+        }
+        self.group_done.push(idx);
+        self.settle_group();
+    }
+
+    /// Apply the group's failure policy, and move past the group once
+    /// every member has finished.
+    fn settle_group(&mut self) {
+        let Some(range) = self.group_range() else {
+            return;
+        };
+        let policy = self.group_policy(range.start);
+        if self.group_failed && policy == GroupFailurePolicy::FailFast {
+            self.status = PipelineStatus::Failed;
+            self.skip_remaining();
+            return;
+        }
+        if range.clone().any(|i| !self.group_done.contains(&i)) {
+            return;
+        }
+        let failed = self.group_failed;
+        let exit = self.group_exit;
+        self.current_index = range.end;
+        self.reset_group();
+        if failed && policy != GroupFailurePolicy::Continue {
+            self.status = PipelineStatus::Failed;
+            self.skip_remaining();
+            return;
+        }
+        self.advance_skipping_conditions(exit);
+    }
+
+    /// Advance past steps whose conditions are not met (auto-skip).
+    /// Entering a group skips the members whose conditions are not met.
+    fn advance_skipping_conditions(&mut self, prev_exit: Option<i32>) {
+        let prev_output = self.last_output.clone();
+        let prev_output = prev_output.as_deref();
         while self.current_index < self.steps.len() {
+            if let Some(range) = self.group_range() {
+                for i in range.clone() {
+                    let unmet = self.steps[i]
+                        .condition
+                        .as_ref()
+                        .is_some_and(|c| {
+                            !c.evaluate_with_output(prev_exit, prev_output, &mut self.output_patterns)
+                        });
+                    if unmet {
+                        self.record_skipped(i);
+                        self.group_done.push(i);
+                    }
+                }
+                if range.clone().all(|i| self.group_done.contains(&i)) {
+                    self.current_index = range.end;
+                    self.reset_group();
+                    continue;
+                }
+                break;
+            }
             let step = &self.steps[self.current_index];
             if let Some(ref condition) = step.condition {
-                if !condition.evaluate(prev_exit) {
+                if !condition.evaluate_with_output(prev_exit, prev_output, &mut self.output_patterns) {
                     // Condition not met; auto-skip.
                     let step_name = step.name.clone();
                     self.results.push(StepResult {
@@ -344,6 +674,19 @@ impl Pipeline {
     }
 }
 
+/// The last `MAX_CONDITION_OUTPUT_BYTES` of `output`, cut on a char boundary.
+fn output_tail(mut output: String) -> String {
+    if output.len() <= MAX_CONDITION_OUTPUT_BYTES {
+        return output;
+    }
+    let mut start = output.len() - MAX_CONDITION_OUTPUT_BYTES;
+    while !output.is_char_boundary(start) {
+        start += 1;
+    }
+    output.drain(..start);
+    output
+}
+
 // ---------------------------------------------------------------------------
 // Tests
 // ---------------------------------------------------------------------------
@@ -360,6 +703,7 @@ mod tests {
             timeout_ms: None,
             continue_on_error: false,
             condition: None,
+            group: None,
         }
     }
 
@@ -371,6 +715,7 @@ mod tests {
             timeout_ms: None,
             continue_on_error: false,
             condition: Some(condition),
+            group: None,
         }
     }
 
@@ -382,6 +727,7 @@ mod tests {
             timeout_ms: None,
             continue_on_error: true,
             condition: None,
+            group: None,
         }
     }
 
@@ -766,6 +1112,7 @@ mod tests {
             timeout_ms: Some(60000),
             continue_on_error: false,
             condition: None,
+            group: None,
         };
 
         let json = serde_json::to_string(&step).unwrap();
@@ -798,4 +1145,223 @@ mod tests {
 
         assert!(!p.overall_success());
     }
+
+    fn fan_out_pipeline(policy: GroupFailurePolicy) -> Pipeline {
+        let mut p = Pipeline::new("ci");
+        p.add_step(make_step("build")).unwrap();
+        p.add_group(
+            "checks",
+            policy,
+            vec![make_step("lint"), make_step("test"), make_step("audit")],
+        )
+        .unwrap();
+        p.add_step(make_step("join")).unwrap();
+        p
+    }
+
+    #[test]
+    fn parallel_group_completes_before_join() {
+        let mut p = fan_out_pipeline(GroupFailurePolicy::FailFast);
+        p.start(1000).unwrap();
+        p.complete_step(0, 100, 1, 1100).unwrap();
+
+        let names: Vec<&str> = p.runnable_steps().into_iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["lint", "test", "audit"]);
+
+        // Members finish out of order; the join waits for all of them.
+        p.complete_group_step("audit", 0, 300, 1).unwrap();
+        p.complete_group_step("lint", 0, 100, 1).unwrap();
+        assert_eq!(p.runnable_steps().len(), 1);
+        assert_eq!(p.current_step().unwrap().name, "test");
+
+        p.complete_group_step("test", 0, 200, 1).unwrap();
+        assert_eq!(p.current_step().unwrap().name, "join");
+        assert_eq!(p.runnable_steps().len(), 1);
+
+        p.complete_step(0, 50, 1, 1500).unwrap();
+        assert!(p.overall_success());
+        assert_eq!(p.results.len(), 5);
+    }
+
+    #[test]
+    fn member_failure_aborts_join() {
+        let mut p = fan_out_pipeline(GroupFailurePolicy::FailFast);
+        p.start(1000).unwrap();
+        p.complete_step(0, 100, 1, 1100).unwrap();
+
+        p.complete_group_step("lint", 0, 100, 1).unwrap();
+        p.complete_group_step("test", 1, 200, 1).unwrap();
+
+        assert_eq!(p.status, PipelineStatus::Failed);
+        assert!(p.runnable_steps().is_empty());
+        let status_of = |name: &str| {
+            p.results.iter().find(|r| r.step_name == name).unwrap().status.clone()
+        };
+        assert_eq!(status_of("test"), StepStatus::Failed);
+        assert_eq!(status_of("audit"), StepStatus::Skipped);
+        assert_eq!(status_of("join"), StepStatus::Skipped);
+        assert_eq!(p.results.len(), p.steps.len());
+    }
+
+    #[test]
+    fn wait_for_all_lets_members_finish_before_failing() {
+        let mut p = fan_out_pipeline(GroupFailurePolicy::WaitForAll);
+        p.start(1000).unwrap();
+        p.complete_step(0, 100, 1, 1100).unwrap();
+
+        p.complete_group_step("lint", 2, 100, 1).unwrap();
+        assert_eq!(p.status, PipelineStatus::Running);
+        p.complete_group_step("test", 0, 100, 1).unwrap();
+        p.complete_group_step("audit", 0, 100, 1).unwrap();
+
+        assert_eq!(p.status, PipelineStatus::Failed);
+        assert_eq!(p.results.last().unwrap().step_name, "join");
+        assert_eq!(p.results.last().unwrap().status, StepStatus::Skipped);
+    }
+
+    #[test]
+    fn continue_policy_passes_failure_to_join() {
+        let mut p = Pipeline::new("ci");
+        p.add_group(
+            "checks",
+            GroupFailurePolicy::Continue,
+            vec![make_step("lint"), make_step("test")],
+        )
+        .unwrap();
+        p.add_step(make_step_with_condition("report", StepCondition::OnFailure))
+            .unwrap();
+        p.start(1000).unwrap();
+
+        p.complete_group_step("test", 3, 100, 1).unwrap();
+        p.complete_group_step("lint", 0, 100, 1).unwrap();
+        assert_eq!(p.current_step().unwrap().name, "report");
+    }
+
+    #[test]
+    fn add_group_rejects_empty_and_duplicate() {
+        let mut p = Pipeline::new("ci");
+        assert!(p.add_group("g", GroupFailurePolicy::FailFast, vec![]).is_err());
+        p.add_group("g", GroupFailurePolicy::FailFast, vec![make_step("a")])
+            .unwrap();
+        assert!(p
+            .add_group("g", GroupFailurePolicy::FailFast, vec![make_step("b")])
+            .is_err());
+
+        let mut grouped = make_step("c");
+        grouped.group = Some("g".into());
+        assert!(p.add_step(grouped).is_err());
+    }
+
+    #[test]
+    fn cancel_mid_group_skips_only_unfinished() {
+        let mut p = fan_out_pipeline(GroupFailurePolicy::FailFast);
+        p.start(1000).unwrap();
+        p.complete_step(0, 100, 1, 1100).unwrap();
+        p.complete_group_step("test", 0, 100, 1).unwrap();
+        p.cancel().unwrap();
+        assert_eq!(p.results.len(), p.steps.len());
+    }
+
+    fn two_step_on_success() -> Pipeline {
+        let mut p = Pipeline::new("build");
+        p.add_step(make_step_continue_on_error("compile")).unwrap();
+        p.add_step(make_step_with_condition("deploy", StepCondition::OnSuccess)).unwrap();
+        p
+    }
+
+    #[test]
+    fn on_success_step_skipped_after_failure() {
+        let mut p = two_step_on_success();
+        p.start(1000).unwrap();
+        p.complete_step(1, 100, 3, 1100).unwrap();
+
+        assert_eq!(p.status, PipelineStatus::Completed);
+        assert_eq!(p.results.len(), 2);
+        assert_eq!(p.results[0].status, StepStatus::Failed);
+        assert_eq!(p.results[1].step_name, "deploy");
+        assert_eq!(p.results[1].status, StepStatus::Skipped);
+    }
+
+    #[test]
+    fn on_success_step_runs_after_success() {
+        let mut p = two_step_on_success();
+        p.start(1000).unwrap();
+        p.complete_step(0, 100, 3, 1100).unwrap();
+
+        assert_eq!(p.current_step().unwrap().name, "deploy");
+        p.complete_step(0, 50, 1, 1200).unwrap();
+        assert_eq!(p.status, PipelineStatus::Completed);
+        assert_eq!(p.results[1].status, StepStatus::Succeeded);
+    }
+
+    #[test]
+    fn when_output_matches_gates_on_previous_output() {
+        let gated = || {
+            let mut p = Pipeline::new("bench");
+            p.add_step(make_step("measure")).unwrap();
+            p.add_step(make_step_with_condition(
+                "report",
+                StepCondition::WhenOutputMatches { pattern: r"regressed by \d+%".into() },
+            ))
+            .unwrap();
+            p.start(1000).unwrap();
+            p
+        };
+
+        let mut hit = gated();
+        hit.complete_step_with_output(0, 100, "bench a: ok\nbench b: regressed by 12%\n").unwrap();
+        assert_eq!(hit.results[0].output_lines, 2);
+        assert_eq!(hit.current_step().unwrap().name, "report");
+
+        let mut miss = gated();
+        miss.complete_step_with_output(0, 100, "all benches ok").unwrap();
+        assert_eq!(miss.results[1].status, StepStatus::Skipped);
+        assert_eq!(miss.status, PipelineStatus::Completed);
+
+        // Without captured output the pattern cannot match.
+        let mut unknown = gated();
+        unknown.complete_step(0, 100, 1, 1100).unwrap();
+        assert_eq!(unknown.results[1].status, StepStatus::Skipped);
+    }
+
+    #[test]
+    fn output_condition_sees_only_output_tail() {
+        let mut p = Pipeline::new("bench");
+        p.add_step(make_step("bench")).unwrap();
+        p.add_step(make_step_with_condition(
+            "report",
+            StepCondition::WhenOutputMatches { pattern: "^HEAD".into() },
+        ))
+        .unwrap();
+        p.start(1000).unwrap();
+        let output = format!("HEAD{}", "é".repeat(MAX_CONDITION_OUTPUT_BYTES));
+        p.complete_step_with_output(0, 100, &output).unwrap();
+        assert_eq!(p.last_output.as_ref().unwrap().len(), MAX_CONDITION_OUTPUT_BYTES);
+        assert_eq!(p.results[1].status, StepStatus::Skipped);
+        assert!(p.output_patterns.contains_key("^HEAD"));
+
+        let json = serde_json::to_string(&p).unwrap();
+        let back: Pipeline = serde_json::from_str(&json).unwrap();
+        assert!(back.output_patterns.is_empty());
+    }
+
+    #[test]
+    fn invalid_output_pattern_rejected_at_add() {
+        let mut p = Pipeline::new("bench");
+        let step = make_step_with_condition(
+            "report",
+            StepCondition::WhenOutputMatches { pattern: "(unclosed".into() },
+        );
+        let err = p.add_step(step).unwrap_err();
+        assert!(err.contains("step 'report'"));
+    }
+
+    #[test]
+    fn when_output_matches_serde() {
+        let c = StepCondition::WhenOutputMatches { pattern: "ok$".into() };
+        let json = serde_json::to_string(&c).unwrap();
+        assert!(json.contains("\"condition\":\"when_output_matches\""));
+        let back: StepCondition = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, c);
+    }
 }
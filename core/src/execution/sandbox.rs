@@ -3,11 +3,137 @@
 //! Provides `SandboxBuilder` for constructing execution environments with a
 //! fluent API, `EnvironmentResolver` for merging environment variables from
 //! multiple sources, and `EnvFile` for parsing KEY=VALUE env files.
+//!
+//! The sandbox only declares intent. Settings such as `NetworkPolicy` and
+//! `ResourceLimits` are turned into runner directives (a command prefix,
+//! environment variables) and enforcement is left to the runner that
+//! executes the command.
 
 use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
 
+// ---------------------------------------------------------------------------
+// NetworkPolicy
+// ---------------------------------------------------------------------------
+
+/// Network access a sandboxed command should have.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NetworkPolicy {
+    /// No restriction.
+    #[default]
+    Allow,
+    /// No network at all. The command is prefixed with `unshare -n`.
+    Deny,
+    /// Only the listed hosts. Passed to the runner through
+    /// `CMX_NETWORK_ALLOW`; there is no portable prefix for this.
+    Restricted { hosts: Vec<String> },
+}
+
+impl NetworkPolicy {
+    /// Arguments to put in front of the command, if any.
+    pub fn command_prefix(&self) -> &'static [&'static str] {
+        match self {
+            NetworkPolicy::Deny => &["unshare", "-n", "--"],
+            NetworkPolicy::Allow | NetworkPolicy::Restricted { .. } => &[],
+        }
+    }
+
+    /// Environment variables telling the runner what to enforce.
+    pub fn runner_env(&self) -> Vec<(String, String)> {
+        match self {
+            NetworkPolicy::Allow => Vec::new(),
+            NetworkPolicy::Deny => vec![("CMX_NETWORK_POLICY".into(), "deny".into())],
+            NetworkPolicy::Restricted { hosts } => vec![
+                ("CMX_NETWORK_POLICY".into(), "restricted".into()),
+                ("CMX_NETWORK_ALLOW".into(), hosts.join(",")),
+            ],
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// ResourceLimits
+// ---------------------------------------------------------------------------
+
+/// Resource caps for a sandboxed command. `None` leaves a resource unlimited.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct ResourceLimits {
+    /// Wall-clock limit, enforced with `timeout`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_wall_ms: Option<u64>,
+    /// Virtual memory limit, enforced with `ulimit -v`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_memory_mb: Option<u64>,
+    /// Captured output limit. Not part of the command prefix; applied by
+    /// `OutputBuffer` when capturing.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_output_bytes: Option<usize>,
+}
+
+impl ResourceLimits {
+    /// True if no limit is set.
+    pub fn is_unlimited(&self) -> bool {
+        self.max_wall_ms.is_none() && self.max_memory_mb.is_none() && self.max_output_bytes.is_none()
+    }
+
+    /// Arguments to put in front of the command.
+    ///
+    /// A wall limit becomes `timeout <secs>s`. A memory limit becomes
+    /// `sh -c 'ulimit -v <KiB> && exec "$@"' sh`, since `ulimit` is a shell
+    /// builtin; the command follows as the script's arguments.
+    pub fn command_prefix(&self) -> Vec<String> {
+        let mut prefix = Vec::new();
+        if let Some(ms) = self.max_wall_ms {
+            prefix.push("timeout".to_string());
+            prefix.push(format_timeout(ms));
+        }
+        if let Some(mb) = self.max_memory_mb {
+            prefix.push("sh".to_string());
+            prefix.push("-c".to_string());
+            prefix.push(format!("ulimit -v {} && exec \"$@\"", mb.saturating_mul(1024)));
+            prefix.push("sh".to_string());
+        }
+        prefix
+    }
+
+    /// `cmd` with the limit prefix applied.
+    pub fn wrap(&self, cmd: &[String]) -> Vec<String> {
+        let mut argv = self.command_prefix();
+        argv.extend(cmd.iter().cloned());
+        argv
+    }
+
+    /// Combine two sets of caps, keeping the tighter value of each. This is
+    /// how a `SandboxConfig`'s limits and an `Execution`'s limits meet:
+    /// neither overrides the other, the stricter cap wins.
+    pub fn tightest(&self, other: &ResourceLimits) -> ResourceLimits {
+        ResourceLimits {
+            max_wall_ms: min_set(self.max_wall_ms, other.max_wall_ms),
+            max_memory_mb: min_set(self.max_memory_mb, other.max_memory_mb),
+            max_output_bytes: min_set(self.max_output_bytes, other.max_output_bytes),
+        }
+    }
+}
+
+/// The smaller of two optional caps, where `None` means unlimited.
+fn min_set<T: Ord>(a: Option<T>, b: Option<T>) -> Option<T> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+/// A `timeout` duration: whole seconds when exact, else millisecond precision.
+fn format_timeout(ms: u64) -> String {
+    if ms.is_multiple_of(1000) {
+        format!("{}s", ms / 1000)
+    } else {
+        format!("{}.{:03}s", ms / 1000, ms % 1000)
+    }
+}
+
 // ---------------------------------------------------------------------------
 // SandboxConfig
 // ---------------------------------------------------------------------------
@@ -21,6 +147,13 @@ pub struct SandboxConfig {
     pub clear_env: bool,
     pub env_file: Option<String>,
     pub path_additions: Vec<String>,
+    /// Declared network access; enforced by the runner, not this crate.
+    #[serde(default)]
+    pub network: NetworkPolicy,
+    /// Declared resource caps; enforced by the runner and output capture.
+    /// Combined with an execution's own limits via `ResourceLimits::tightest`.
+    #[serde(default)]
+    pub limits: ResourceLimits,
 }
 
 impl SandboxConfig {
@@ -33,6 +166,8 @@ impl SandboxConfig {
             clear_env: false,
             env_file: None,
             path_additions: Vec::new(),
+            network: NetworkPolicy::Allow,
+            limits: ResourceLimits::default(),
         }
     }
 }
@@ -84,6 +219,18 @@ impl SandboxBuilder {
         self
     }
 
+    /// Declare the network access the command should have.
+    pub fn network(mut self, policy: NetworkPolicy) -> Self {
+        self.config.network = policy;
+        self
+    }
+
+    /// Declare resource limits for the command.
+    pub fn limits(mut self, limits: ResourceLimits) -> Self {
+        self.config.limits = limits;
+        self
+    }
+
     /// Consume the builder and return the config.
     pub fn build(self) -> SandboxConfig {
         self.config
@@ -207,15 +354,25 @@ impl EnvironmentResolver {
 
     /// Build the command environment tuple: (command_args, env_map).
     ///
-    /// Returns the original command and the resolved environment.
+    /// Returns the command, prefixed with any network policy directive and
+    /// then any resource limit wrappers, and the resolved environment
+    /// including the policy's runner variables.
     pub fn build_command_env(
         config: &SandboxConfig,
         base_cmd: &[String],
         base_env: &HashMap<String, String>,
         env_file_content: Option<&str>,
     ) -> (Vec<String>, HashMap<String, String>) {
-        let env = Self::resolve(config, base_env, env_file_content);
-        (base_cmd.to_vec(), env)
+        let mut env = Self::resolve(config, base_env, env_file_content);
+        env.extend(config.network.runner_env());
+        let cmd = config
+            .network
+            .command_prefix()
+            .iter()
+            .map(|a| a.to_string())
+            .chain(config.limits.wrap(base_cmd))
+            .collect();
+        (cmd, env)
     }
 }
 
@@ -523,6 +680,115 @@ mod tests {
         assert_eq!(result_env.get("RUST_LOG").unwrap(), "debug");
     }
 
+    #[test]
+    fn network_deny_prefixes_unshare() {
+        let config = SandboxBuilder::new("/work").network(NetworkPolicy::Deny).build();
+        let cmd = vec!["cargo".into(), "test".into()];
+        let (result_cmd, result_env) =
+            EnvironmentResolver::build_command_env(&config, &cmd, &HashMap::new(), None);
+        assert_eq!(result_cmd, vec!["unshare", "-n", "--", "cargo", "test"]);
+        assert_eq!(result_env.get("CMX_NETWORK_POLICY").unwrap(), "deny");
+    }
+
+    #[test]
+    fn network_allow_has_no_prefix() {
+        let config = SandboxBuilder::new("/work").build();
+        assert_eq!(config.network, NetworkPolicy::Allow);
+        let cmd = vec!["cargo".into(), "test".into()];
+        let (result_cmd, result_env) =
+            EnvironmentResolver::build_command_env(&config, &cmd, &HashMap::new(), None);
+        assert_eq!(result_cmd, vec!["cargo", "test"]);
+        assert!(!result_env.contains_key("CMX_NETWORK_POLICY"));
+    }
+
+    #[test]
+    fn network_restricted_passes_hosts_to_runner() {
+        let config = SandboxBuilder::new("/work")
+            .network(NetworkPolicy::Restricted {
+                hosts: vec!["crates.io".into(), "github.com".into()],
+            })
+            .build();
+        let cmd = vec!["cargo".into(), "fetch".into()];
+        let (result_cmd, result_env) =
+            EnvironmentResolver::build_command_env(&config, &cmd, &HashMap::new(), None);
+        assert_eq!(result_cmd, vec!["cargo", "fetch"]);
+        assert_eq!(result_env.get("CMX_NETWORK_ALLOW").unwrap(), "crates.io,github.com");
+    }
+
+    #[test]
+    fn resource_limits_wrap_timeout_and_ulimit() {
+        let limits = ResourceLimits {
+            max_wall_ms: Some(30_000),
+            max_memory_mb: Some(512),
+            max_output_bytes: Some(1_000),
+        };
+        let cmd = vec!["cargo".into(), "test".into()];
+        assert_eq!(
+            limits.wrap(&cmd),
+            vec![
+                "timeout",
+                "30s",
+                "sh",
+                "-c",
+                "ulimit -v 524288 && exec \"$@\"",
+                "sh",
+                "cargo",
+                "test",
+            ]
+        );
+    }
+
+    #[test]
+    fn resource_limits_partial_and_empty() {
+        let wall = ResourceLimits { max_wall_ms: Some(1_500), ..Default::default() };
+        assert_eq!(wall.command_prefix(), vec!["timeout", "1.500s"]);
+        // The output cap is applied at capture time, not in the prefix.
+        let output_only = ResourceLimits { max_output_bytes: Some(10), ..Default::default() };
+        assert!(output_only.command_prefix().is_empty());
+        assert!(!output_only.is_unlimited());
+        assert!(ResourceLimits::default().is_unlimited());
+    }
+
+    #[test]
+    fn resource_limits_tightest_keeps_stricter_cap() {
+        let sandbox = ResourceLimits {
+            max_wall_ms: Some(60_000),
+            max_memory_mb: Some(512),
+            max_output_bytes: None,
+        };
+        let exec = ResourceLimits {
+            max_wall_ms: Some(90_000),
+            max_memory_mb: Some(256),
+            max_output_bytes: Some(4096),
+        };
+        let both = sandbox.tightest(&exec);
+        assert_eq!(both.max_wall_ms, Some(60_000));
+        assert_eq!(both.max_memory_mb, Some(256));
+        assert_eq!(both.max_output_bytes, Some(4096));
+        assert_eq!(both, exec.tightest(&sandbox));
+        assert!(ResourceLimits::default().tightest(&ResourceLimits::default()).is_unlimited());
+    }
+
+    #[test]
+    fn build_command_env_applies_limits_after_network_prefix() {
+        let config = SandboxBuilder::new("/work")
+            .network(NetworkPolicy::Deny)
+            .limits(ResourceLimits { max_wall_ms: Some(60_000), ..Default::default() })
+            .build();
+        let cmd = vec!["make".into()];
+        let (result_cmd, _) =
+            EnvironmentResolver::build_command_env(&config, &cmd, &HashMap::new(), None);
+        assert_eq!(result_cmd, vec!["unshare", "-n", "--", "timeout", "60s", "make"]);
+    }
+
+    #[test]
+    fn network_policy_defaults_when_absent() {
+        let json = r#"{"working_dir":"/w","env_vars":{},"inherit_env":false,"clear_env":false,"env_file":null,"path_additions":[]}"#;
+        let config: SandboxConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(config.network, NetworkPolicy::Allow);
+        assert!(config.limits.is_unlimited());
+    }
+
     #[test]
     fn sandbox_config_serde() {
         let config = SandboxBuilder::new("/work")
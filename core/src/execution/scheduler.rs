@@ -2,7 +2,8 @@
 //!
 //! Provides a `Scheduler` that orders pending executions according to
 //! configurable policies (FIFO, priority, round-robin, affinity) and
-//! tracks scheduling metrics.
+//! tracks scheduling metrics, and a `PriorityScheduler` that serves the
+//! highest priority first while aging waiting entries so none starve.
 
 use serde::{Deserialize, Serialize};
 
@@ -303,6 +304,125 @@ impl Scheduler {
     }
 }
 
+// ---------------------------------------------------------------------------
+// PriorityScheduler
+// ---------------------------------------------------------------------------
+
+/// Cycles an entry waits before `PriorityScheduler` bumps its priority by one.
+pub const DEFAULT_AGING_CYCLES: u32 = 4;
+
+/// An entry dequeued by `PriorityScheduler`.
+#[derive(Debug, Clone)]
+pub struct ScheduledTask {
+    pub entry: ScheduleEntry,
+    /// Priority at dequeue time: `entry.priority` plus aging bumps.
+    pub effective_priority: u32,
+    /// Dequeues the entry waited through before it was picked.
+    pub waited_cycles: u32,
+}
+
+/// Highest-priority-first queue with aging.
+///
+/// Every dequeue is a cycle. Each entry left waiting gains one priority
+/// point per `aging_cycles` cycles waited, so a steady stream of urgent
+/// work cannot starve older, lower-priority entries. Equal effective
+/// priorities are served in insertion order.
+#[derive(Debug)]
+pub struct PriorityScheduler {
+    queue: Vec<QueuedEntry>,
+    /// Cycles per aging bump; 0 disables aging.
+    aging_cycles: u32,
+    next_seq: u64,
+}
+
+#[derive(Debug, Clone)]
+struct QueuedEntry {
+    entry: ScheduleEntry,
+    seq: u64,
+    waited_cycles: u32,
+}
+
+impl QueuedEntry {
+    fn effective_priority(&self, aging_cycles: u32) -> u32 {
+        if aging_cycles == 0 {
+            return self.entry.priority;
+        }
+        self.entry.priority.saturating_add(self.waited_cycles / aging_cycles)
+    }
+}
+
+impl PriorityScheduler {
+    /// Create a scheduler that bumps waiting entries every `aging_cycles`
+    /// dequeues (0 disables aging).
+    pub fn new(aging_cycles: u32) -> Self {
+        PriorityScheduler {
+            queue: Vec::new(),
+            aging_cycles,
+            next_seq: 0,
+        }
+    }
+
+    /// Queue `task` at `priority` (higher runs first), replacing
+    /// `task.priority`.
+    pub fn schedule(&mut self, mut task: ScheduleEntry, priority: u32) {
+        task.priority = priority;
+        self.queue.push(QueuedEntry {
+            entry: task,
+            seq: self.next_seq,
+            waited_cycles: 0,
+        });
+        self.next_seq += 1;
+    }
+
+    /// Remove an entry by execution ID.
+    pub fn remove(&mut self, id: &str) -> bool {
+        let len_before = self.queue.len();
+        self.queue.retain(|q| q.entry.execution_id != id);
+        self.queue.len() < len_before
+    }
+
+    /// Number of entries in the queue.
+    pub fn size(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Remove and return the entry with the highest effective priority,
+    /// then age the entries still waiting.
+    pub fn dequeue(&mut self) -> Option<ScheduledTask> {
+        let aging = self.aging_cycles;
+        let index = self
+            .queue
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| {
+                a.effective_priority(aging)
+                    .cmp(&b.effective_priority(aging))
+                    .then(b.seq.cmp(&a.seq))
+            })
+            .map(|(i, _)| i)?;
+        let picked = self.queue.remove(index);
+        for waiting in &mut self.queue {
+            waiting.waited_cycles = waiting.waited_cycles.saturating_add(1);
+        }
+        Some(ScheduledTask {
+            effective_priority: picked.effective_priority(aging),
+            waited_cycles: picked.waited_cycles,
+            entry: picked.entry,
+        })
+    }
+
+    /// Whether the queue is empty.
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+}
+
+impl Default for PriorityScheduler {
+    fn default() -> Self {
+        PriorityScheduler::new(DEFAULT_AGING_CYCLES)
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Tests
 // ---------------------------------------------------------------------------
@@ -686,4 +806,70 @@ mod tests {
         let entry = s.dequeue_for_agent("w2", 2000).unwrap();
         assert_eq!(entry.execution_id, "e1");
     }
+
+    // -- PriorityScheduler tests --
+
+    #[test]
+    fn priority_scheduler_high_priority_jumps_queue() {
+        let mut s = PriorityScheduler::default();
+        s.schedule(make_entry("low1", 0, 1000), 1);
+        s.schedule(make_entry("low2", 0, 1000), 1);
+        s.schedule(make_entry("urgent", 0, 3000), 9);
+
+        let first = s.dequeue().unwrap();
+        assert_eq!(first.entry.execution_id, "urgent");
+        assert_eq!(first.entry.priority, 9);
+        assert_eq!(s.dequeue().unwrap().entry.execution_id, "low1");
+        assert_eq!(s.dequeue().unwrap().entry.execution_id, "low2");
+        assert!(s.dequeue().is_none());
+    }
+
+    #[test]
+    fn priority_scheduler_ties_break_by_insertion_order() {
+        let mut s = PriorityScheduler::new(0);
+        // Later submission time, but scheduled first.
+        s.schedule(make_entry("a", 0, 5000), 3);
+        s.schedule(make_entry("b", 0, 1000), 3);
+        s.schedule(make_entry("c", 0, 2000), 3);
+        let mut order = Vec::new();
+        while let Some(task) = s.dequeue() {
+            order.push(task.entry.execution_id);
+        }
+        assert_eq!(order, vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn priority_scheduler_aging_prevents_starvation() {
+        let mut s = PriorityScheduler::new(2);
+        s.schedule(make_entry("starved", 0, 0), 0);
+
+        let mut ran_at = None;
+        for cycle in 0..100 {
+            // A fresh high-priority entry arrives every cycle.
+            s.schedule(make_entry(&format!("hot{}", cycle), 0, 0), 5);
+            let task = s.dequeue().unwrap();
+            if task.entry.execution_id == "starved" {
+                assert_eq!(task.effective_priority, 5);
+                assert_eq!(task.waited_cycles, cycle);
+                ran_at = Some(cycle);
+                break;
+            }
+        }
+        // At 2 cycles per bump, priority 0 reaches 5 after 10 waited cycles
+        // and wins the tie as the older entry.
+        assert_eq!(ran_at, Some(10));
+    }
+
+    #[test]
+    fn priority_scheduler_without_aging_serves_strictly_by_priority() {
+        let mut s = PriorityScheduler::new(0);
+        s.schedule(make_entry("low", 0, 0), 0);
+        for i in 0..10 {
+            s.schedule(make_entry(&format!("hot{}", i), 0, 0), 5);
+            assert_ne!(s.dequeue().unwrap().entry.execution_id, "low");
+        }
+        assert_eq!(s.size(), 1);
+        assert!(s.remove("low"));
+        assert!(s.is_empty());
+    }
 }
@@ -0,0 +1,372 @@
+//! Wire frames for the daemon socket — request/response correlation ids
+//! and a client-side correlator with a per-request timeout.
+//!
+//! Every frame is 4 bytes big-endian length followed by that many bytes of
+//! JSON. A request frame is the command's JSON with an optional
+//! `request_id` next to the `command` tag; the daemon echoes the id on the
+//! response frame. Frames without an id are the original protocol and are
+//! answered without one, so older clients keep working.
+//!
+//! `Correlator` sends commands over any `FrameTransport` and matches
+//! replies by id. Replies for other outstanding requests are held until
+//! asked for, replies for unknown or abandoned ids are dropped, and a
+//! request with no matching reply by its deadline fails with a timeout
+//! error instead of blocking.
+
+use std::collections::{HashMap, HashSet};
+use std::io::{Read, Write};
+use std::os::unix::net::UnixStream;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::command::Command;
+use cmx_utils::response::Response;
+
+
+/// Largest frame either side accepts.
+pub const MAX_FRAME_BYTES: usize = 16 * 1024 * 1024;
+
+
+/// A command as sent on the wire.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RequestFrame {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<u64>,
+    #[serde(flatten)]
+    pub command: Command,
+}
+
+/// A response as sent on the wire, carrying the id of its request.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResponseFrame {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<u64>,
+    #[serde(flatten)]
+    pub response: Response,
+}
+
+
+// ---------------------------------------------------------------------------
+// Transport
+// ---------------------------------------------------------------------------
+
+/// Moves whole frame payloads (without the length prefix) to and from
+/// the daemon.
+pub trait FrameTransport {
+    /// Send one frame payload.
+    fn send_frame(&mut self, payload: &[u8]) -> Result<(), String>;
+
+    /// Wait up to `timeout` for the next frame payload. Returns `Ok(None)`
+    /// if nothing arrived in time.
+    fn recv_frame(&mut self, timeout: Duration) -> Result<Option<Vec<u8>>, String>;
+}
+
+impl FrameTransport for UnixStream {
+    fn send_frame(&mut self, payload: &[u8]) -> Result<(), String> {
+        write_payload(self, payload)
+    }
+
+    fn recv_frame(&mut self, timeout: Duration) -> Result<Option<Vec<u8>>, String> {
+        // A zero read timeout means "block forever" to the OS.
+        self.set_read_timeout(Some(timeout.max(Duration::from_millis(1))))
+            .map_err(|e| format!("Cannot set timeout: {}", e))?;
+        read_payload(self)
+    }
+}
+
+/// Write one length-prefixed frame payload and flush.
+pub fn write_payload<W: Write>(writer: &mut W, payload: &[u8]) -> Result<(), String> {
+    let len = payload.len() as u32;
+    writer
+        .write_all(&len.to_be_bytes())
+        .map_err(|e| format!("Failed to write frame length: {}", e))?;
+    writer
+        .write_all(payload)
+        .map_err(|e| format!("Failed to write frame payload: {}", e))?;
+    writer.flush().map_err(|e| format!("Failed to flush: {}", e))
+}
+
+/// Read one length-prefixed frame payload. Returns `Ok(None)` if the
+/// reader's timeout expires before the length prefix arrives.
+pub fn read_payload<R: Read>(reader: &mut R) -> Result<Option<Vec<u8>>, String> {
+    let mut len_buf = [0u8; 4];
+    match reader.read_exact(&mut len_buf) {
+        Ok(()) => {}
+        Err(e) if is_timeout(&e) => return Ok(None),
+        Err(e) => return Err(format!("Failed to read frame length: {}", e)),
+    }
+    let len = u32::from_be_bytes(len_buf) as usize;
+    if len == 0 {
+        return Err("Empty frame".into());
+    }
+    if len > MAX_FRAME_BYTES {
+        return Err(format!("Frame too large: {} bytes", len));
+    }
+    let mut payload = vec![0u8; len];
+    reader
+        .read_exact(&mut payload)
+        .map_err(|e| format!("Failed to read frame payload: {}", e))?;
+    Ok(Some(payload))
+}
+
+fn is_timeout(e: &std::io::Error) -> bool {
+    matches!(e.kind(), std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut)
+}
+
+
+// ---------------------------------------------------------------------------
+// Correlator
+// ---------------------------------------------------------------------------
+
+/// Matches responses to requests by correlation id.
+pub struct Correlator<T: FrameTransport> {
+    transport: T,
+    next_id: u64,
+    /// Ids sent and not yet answered or abandoned.
+    outstanding: HashSet<u64>,
+    /// Replies that arrived while waiting for a different id.
+    arrived: HashMap<u64, Response>,
+}
+
+impl<T: FrameTransport> Correlator<T> {
+    pub fn new(transport: T) -> Self {
+        Correlator {
+            transport,
+            next_id: 1,
+            outstanding: HashSet::new(),
+            arrived: HashMap::new(),
+        }
+    }
+
+    /// Send `cmd` and wait up to `timeout` for its response.
+    pub fn call(&mut self, cmd: &Command, timeout: Duration) -> Result<Response, String> {
+        let id = self.send(cmd)?;
+        self.wait(id, timeout)
+    }
+
+    /// Send `cmd` without waiting. Returns the request's correlation id.
+    pub fn send(&mut self, cmd: &Command) -> Result<u64, String> {
+        let id = self.next_id;
+        self.next_id += 1;
+        let frame = RequestFrame {
+            request_id: Some(id),
+            command: cmd.clone(),
+        };
+        let json = serde_json::to_vec(&frame)
+            .map_err(|e| format!("Failed to serialize command: {}", e))?;
+        self.transport.send_frame(&json)?;
+        self.outstanding.insert(id);
+        Ok(id)
+    }
+
+    /// Wait up to `timeout` for the response to request `id`.
+    ///
+    /// Replies for other outstanding requests are kept for their own
+    /// `wait`. A reply without an id (from a daemon predating correlation,
+    /// or a watch notification) is taken as the answer when exactly one
+    /// request is outstanding. On timeout the request is abandoned and a
+    /// late reply to it is discarded.
+    pub fn wait(&mut self, id: u64, timeout: Duration) -> Result<Response, String> {
+        if let Some(resp) = self.arrived.remove(&id) {
+            self.outstanding.remove(&id);
+            return Ok(resp);
+        }
+        if !self.outstanding.contains(&id) {
+            return Err(format!("No outstanding request with id {}", id));
+        }
+
+        let deadline = Instant::now() + timeout;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                self.outstanding.remove(&id);
+                return Err(format!(
+                    "Timed out after {}ms waiting for response to request {}",
+                    timeout.as_millis(),
+                    id
+                ));
+            }
+            let payload = match self.transport.recv_frame(remaining)? {
+                Some(p) => p,
+                None => continue,
+            };
+            let frame: ResponseFrame = serde_json::from_slice(&payload)
+                .map_err(|e| format!("Failed to parse response: {}", e))?;
+            match frame.request_id {
+                Some(got) if got == id => {
+                    self.outstanding.remove(&id);
+                    return Ok(frame.response);
+                }
+                Some(got) if self.outstanding.contains(&got) => {
+                    self.arrived.insert(got, frame.response);
+                }
+                None if self.outstanding.len() == 1 => {
+                    self.outstanding.remove(&id);
+                    return Ok(frame.response);
+                }
+                // Unknown, abandoned, or ambiguous: drop it and keep waiting.
+                _ => {}
+            }
+        }
+    }
+
+    /// Number of requests sent and not yet answered or abandoned.
+    pub fn outstanding(&self) -> usize {
+        self.outstanding.len()
+    }
+
+    /// Borrow the underlying transport.
+    pub fn transport_mut(&mut self) -> &mut T {
+        &mut self.transport
+    }
+}
+
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+
+    /// Transport that records sent frames and replays queued replies.
+    #[derive(Default)]
+    struct MemoryTransport {
+        sent: Vec<RequestFrame>,
+        incoming: VecDeque<Vec<u8>>,
+    }
+
+    impl MemoryTransport {
+        fn reply(&mut self, request_id: Option<u64>, output: &str) {
+            let frame = ResponseFrame {
+                request_id,
+                response: Response::Ok { output: output.into() },
+            };
+            self.incoming.push_back(serde_json::to_vec(&frame).unwrap());
+        }
+    }
+
+    impl FrameTransport for MemoryTransport {
+        fn send_frame(&mut self, payload: &[u8]) -> Result<(), String> {
+            self.sent.push(serde_json::from_slice(payload).unwrap());
+            Ok(())
+        }
+
+        fn recv_frame(&mut self, timeout: Duration) -> Result<Option<Vec<u8>>, String> {
+            match self.incoming.pop_front() {
+                Some(frame) => Ok(Some(frame)),
+                None => {
+                    std::thread::sleep(timeout);
+                    Ok(None)
+                }
+            }
+        }
+    }
+
+    fn ok(text: &str) -> Response {
+        Response::Ok { output: text.into() }
+    }
+
+    const WAIT: Duration = Duration::from_millis(200);
+
+    #[test]
+    fn request_frame_flattens_command() {
+        let frame = RequestFrame {
+            request_id: Some(7),
+            command: Command::Status { format: None },
+        };
+        let json = serde_json::to_string(&frame).unwrap();
+        assert_eq!(json, r#"{"request_id":7,"command":"status"}"#);
+        let back: RequestFrame = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, frame);
+
+        // The original protocol: a bare command, no id.
+        let legacy: RequestFrame = serde_json::from_str(r#"{"command":"agent.kill","name":"w1"}"#).unwrap();
+        assert_eq!(legacy.request_id, None);
+        assert_eq!(legacy.command, Command::AgentKill { name: "w1".into(), graceful: false });
+    }
+
+    #[test]
+    fn response_frame_without_id_is_plain_response() {
+        let frame = ResponseFrame { request_id: None, response: ok("hi") };
+        let json = serde_json::to_string(&frame).unwrap();
+        let plain: Response = serde_json::from_str(&json).unwrap();
+        assert_eq!(plain, ok("hi"));
+    }
+
+    #[test]
+    fn ids_are_assigned_per_request() {
+        let mut c = Correlator::new(MemoryTransport::default());
+        let a = c.send(&Command::Status { format: None }).unwrap();
+        let b = c.send(&Command::DaemonStop).unwrap();
+        assert_ne!(a, b);
+        let sent = &c.transport_mut().sent;
+        assert_eq!(sent[0].request_id, Some(a));
+        assert_eq!(sent[1].request_id, Some(b));
+        assert_eq!(c.outstanding(), 2);
+    }
+
+    #[test]
+    fn out_of_order_replies_are_matched() {
+        let mut c = Correlator::new(MemoryTransport::default());
+        let a = c.send(&Command::Status { format: None }).unwrap();
+        let b = c.send(&Command::Status { format: None }).unwrap();
+        c.transport_mut().reply(Some(b), "second");
+        c.transport_mut().reply(Some(999), "stray");
+        c.transport_mut().reply(Some(a), "first");
+
+        assert_eq!(c.wait(a, WAIT).unwrap(), ok("first"));
+        // b's reply was read while waiting for a and kept.
+        assert_eq!(c.wait(b, WAIT).unwrap(), ok("second"));
+        assert_eq!(c.outstanding(), 0);
+        assert!(c.transport_mut().incoming.is_empty());
+    }
+
+    #[test]
+    fn timeout_is_an_error_not_a_hang() {
+        let mut c = Correlator::new(MemoryTransport::default());
+        c.transport_mut().reply(Some(42), "unrelated");
+        let started = Instant::now();
+        let err = c.call(&Command::Status { format: None }, Duration::from_millis(50)).unwrap_err();
+        assert!(err.contains("Timed out after 50ms"), "{}", err);
+        assert!(started.elapsed() < Duration::from_secs(2));
+        assert_eq!(c.outstanding(), 0);
+    }
+
+    #[test]
+    fn late_reply_after_timeout_is_dropped() {
+        let mut c = Correlator::new(MemoryTransport::default());
+        let a = c.send(&Command::Status { format: None }).unwrap();
+        assert!(c.wait(a, Duration::from_millis(20)).is_err());
+
+        let b = c.send(&Command::Status { format: None }).unwrap();
+        c.transport_mut().reply(Some(a), "late");
+        c.transport_mut().reply(Some(b), "current");
+        assert_eq!(c.wait(b, WAIT).unwrap(), ok("current"));
+        assert!(c.wait(a, WAIT).is_err());
+    }
+
+    #[test]
+    fn untagged_reply_answers_sole_request() {
+        let mut c = Correlator::new(MemoryTransport::default());
+        c.transport_mut().reply(None, "legacy");
+        assert_eq!(c.call(&Command::Status { format: None }, WAIT).unwrap(), ok("legacy"));
+
+        // With two outstanding, an untagged reply cannot be attributed.
+        let a = c.send(&Command::Status { format: None }).unwrap();
+        let _b = c.send(&Command::Status { format: None }).unwrap();
+        c.transport_mut().reply(None, "ambiguous");
+        assert!(c.wait(a, Duration::from_millis(20)).is_err());
+    }
+
+    #[test]
+    fn stream_transport_times_out() {
+        let (client, _server) = UnixStream::pair().unwrap();
+        let mut c = Correlator::new(client);
+        let err = c.call(&Command::Status { format: None }, Duration::from_millis(30)).unwrap_err();
+        assert!(err.contains("Timed out"));
+    }
+}
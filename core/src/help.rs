@@ -6,6 +6,8 @@
 //! 1. **Overview** (`skd help`) — lists all command groups with summaries
 //! 2. **Group help** (`skd help agent`) — lists commands within a group
 //! 3. **Command help** (`skd help agent.new`) — detailed usage for one command
+//!
+//! `skd help --search <words>` scans the command help texts for keywords.
 
 
 /// Generate help text for a given topic.
@@ -31,6 +33,80 @@ pub fn help_text(topic: Option<&str>) -> String {
 }
 
 
+/// Every command with a `command_help` entry, in overview order. This is
+/// the index `search` scans.
+const COMMANDS: &[&str] = &[
+    "status", "view", "help",
+    "agent.new", "agent.kill", "agent.kill.where", "agent.restart",
+    "agent.assign", "agent.unassign", "agent.status", "agent.list",
+    "agent.recheck", "agent.exec", "agent.state.set", "agent.state.get",
+    "task.list", "task.get", "task.set", "task.check", "task.uncheck",
+    "task.note", "task.move",
+    "config.load", "config.save", "config.add", "config.unset", "config.list",
+    "project.add", "project.remove", "project.list", "project.scan",
+    "roadmap.load",
+    "tell", "interrupt",
+    "layout.row", "layout.column", "layout.merge",
+    "layout.place", "layout.capture", "layout.session",
+    "client.next", "client.prev",
+    "rig.init", "rig.push", "rig.pull", "rig.status",
+    "rig.health", "rig.stop", "rig.list", "rig.default",
+    "diagnosis.report", "diagnosis.reliability", "diagnosis.effectiveness",
+    "diagnosis.thresholds", "diagnosis.events", "diagnosis.compare",
+    "diagnosis.annotate",
+    "history.list", "history.show", "history.diff",
+    "history.restore", "history.snapshot", "history.prune",
+    "history.pin", "history.unpin", "history.search",
+    "learnings.list", "learnings.add", "learnings.search",
+    "skill.validate",
+    "watch",
+    "daemon.run", "daemon.stop", "tui",
+    "pool.list", "pool.status", "pool.set", "pool.remove",
+];
+
+
+/// Find commands whose name or help text contains every word of `query`
+/// (case-insensitive). Returns one line per match: the command name and
+/// its one-line summary.
+pub fn search(query: &str) -> String {
+    let terms: Vec<String> = query.split_whitespace().map(|w| w.to_lowercase()).collect();
+    if terms.is_empty() {
+        return "Usage: skd help --search <keywords>".into();
+    }
+
+    let mut matches = Vec::new();
+    for &name in COMMANDS {
+        let text = match command_help(name) {
+            Some(t) => t,
+            None => continue,
+        };
+        let haystack = format!("{}\n{}", name, text).to_lowercase();
+        if terms.iter().all(|t| haystack.contains(t.as_str())) {
+            matches.push(format!("  {:<24} {}", name, summary(&text)));
+        }
+    }
+
+    if matches.is_empty() {
+        return format!(
+            "No commands match '{}'. Run 'skd help' for a list of commands.",
+            query.trim()
+        );
+    }
+    format!("Commands matching '{}':\n{}", query.trim(), matches.join("\n"))
+}
+
+
+/// The one-line summary from a command help text: its first line after
+/// the "skd <command> — " prefix.
+fn summary(text: &str) -> &str {
+    let first = text.lines().next().unwrap_or("");
+    match first.split_once(" — ") {
+        Some((_, rest)) => rest,
+        None => first,
+    }
+}
+
+
 /// Top-level overview of all commands.
 fn overview() -> String {
     "\
@@ -40,29 +116,38 @@ Usage: skd <command> [args...]
 
 Commands:
   status [--json]             Show system summary (agents, tasks, projects)
-  view <name>                Look up an agent, task, or project by name
+  view <name>                Look up an agent, task, project, or skill
   help [topic]               Show help (this message, or help on a topic)
+  help --search <words>      Find commands by keyword
 
 Agent commands:
   agent new <role> [flags]   Create a new agent
   agent kill <name>          Remove an agent
+  agent kill --role <r> ...  Remove every agent matching --role/--status/--label
   agent restart <name>       Restart an agent (kill + re-create)
   agent assign <name> <task> Assign an agent to a task
   agent unassign <name>      Remove task assignment from an agent
   agent status <name> [note] Update an agent's status notes
-  agent list [--json]        List all agents
+  agent list [--sort <key>] [--json]  List all agents
+  agent recheck <name>       Reassess an agent's health now
+  agent exec <name> <cmd>    Run a command in an agent's pane, print output
+  agent state set <a> <k> <v>  Store a per-agent scratch value (JSON)
+  agent state get <a> <k>      Read a per-agent scratch value
 
 Task commands:
-  task list [project] [--json]  List tasks, optionally filtered by project
+  task list [project] [flags]   List tasks (--depth <n>, --status <s>, --agent <a>, --json)
   task get <id>                 Show detailed task information
   task set <id> key=value ...   Update task fields (status, title, result, agent)
   task check <id>               Mark a task as completed
   task uncheck <id>             Mark a task as pending
+  task note <id> <text...>      Append a timestamped note to a task
+  task move <id> <parent>       Move a task and its subtasks under another task
 
 Config commands:
   config load [path]         Load settings from YAML file
   config save [path]         Save settings to YAML file
   config add <key> <value>   Set a configuration value
+  config unset <key>         Reset a configuration value to its default
   config list                Show all configuration values
 
 Project commands:
@@ -74,8 +159,11 @@ Project commands:
 Roadmap commands:
   roadmap load <path>        Load tasks from a Roadmap.md file
 
+Skill commands:
+  skill validate             Check every skill file for errors
+
 Messaging commands:
-  tell <agent> <text...>     Send a message to an agent
+  tell [--dedup] <agent> <text...>  Send a message to an agent
   interrupt <agent> [text]   Send Ctrl-C to an agent, optionally followed by text
 
 Layout commands:
@@ -92,8 +180,8 @@ Client commands:
 
 Rig commands (remote workers):
   rig init <host> [--name <n>]     Initialize a remote host
-  rig push <folder> [-r <remote>]  Push code to remote
-  rig pull <folder> [-r <remote>]  Pull results from remote
+  rig push <folder> [-r <remote>]  Push code to remote (--label <l> for tagged, --all)
+  rig pull <folder> [-r <remote>]  Pull results from remote (--label <l> for all tagged)
   rig status [-r <remote>]         Show remote status
   rig health [-r <remote>]         Health check remote SSH
   rig stop [-r <remote>]           Stop remote operations
@@ -106,6 +194,8 @@ Diagnosis commands:
   diagnosis effectiveness [signal] Intervention effectiveness
   diagnosis thresholds             Show adaptive thresholds
   diagnosis events [--limit <n>]   List recent intervention events
+  diagnosis compare <a> <b>        Compare two agents' signal reliability
+  diagnosis annotate <id> <note>   Add a note to an intervention event
 
 History commands:
   history list [--limit <n>]       List configuration snapshots
@@ -113,7 +203,10 @@ History commands:
   history diff <from> [<to>]       Diff two snapshots
   history restore <id>             Restore a snapshot
   history snapshot                 Take a snapshot now
-  history prune                    Prune old snapshots
+  history prune [--dry-run]        Prune old snapshots
+  history pin <id>                 Protect a snapshot from pruning
+  history unpin <id>               Remove a snapshot's pin
+  history search <query> [-i]      Find text across all snapshots
 
 Learnings commands:
   learnings list [flags]           List learning entries
@@ -134,6 +227,12 @@ Pool commands:
   pool set <role> <size> [--path]  Create or update a pool
   pool remove <role>               Remove a pool
 
+Exit codes:
+  0  success                  4  conflict (already exists, duplicate)
+  1  unclassified error       5  backend failure (tmux, ssh, filesystem)
+  2  invalid argument         6  unavailable (config dir unusable, rig not set up)
+  3  not found
+
 Run 'skd help <command>' for detailed help on a specific command.
 Run 'skd help <group>' for help on a command group (agent, task, config, etc.)."
         .into()
@@ -155,6 +254,10 @@ Agent commands — manage AI agent lifecycle
   agent kill <name>
     Remove an agent. Emits a KillAgent action for infrastructure cleanup.
 
+  agent kill [--role <r>] [--status <s>] [--label <l>]
+    Remove every agent matching all given predicates. At least one
+    predicate is required; matching nothing kills nothing.
+
   agent restart <name>
     Kill and re-create an agent with the same configuration.
     Resets status to idle and health to unknown.
@@ -170,15 +273,31 @@ Agent commands — manage AI agent lifecycle
   agent status <name> [notes...]
     Update the agent's free-text status notes (e.g. 'compiling', 'running tests').
 
-  agent list [--json]
-    List all agents in tabular format. Use --json for JSON output.",
+  agent list [--sort <key>] [--columns <list>] [--json]
+    List all agents in tabular format. Sort by name, role, status, health,
+    or age; pick columns with a comma-separated list. Use --json for JSON output.
+
+  agent recheck <name>
+    Capture the agent's pane and reassess its health immediately instead
+    of waiting for the next monitor cycle. Requires the daemon.
+
+  agent exec <name> <cmd...>
+    Type a command into the agent's pane, wait for the output to settle,
+    and print what the command wrote. Requires the daemon.
+
+  agent state set <agent> <key> <value>
+    Store a small per-agent value under agent.<agent>.<key>. The value is
+    parsed as JSON; anything else is stored as a string.
+
+  agent state get <agent> <key>
+    Read back a value stored with agent state set.",
 
         "task" => "\
 Task commands — manage the task tree
 
-  task list [<project>] [--json]
-    List all tasks. Optionally filter by project name prefix.
-    Use --json for JSON array output.
+  task list [<project>] [--depth <n>] [--status <s>] [--agent <a>] [--json]
+    List all tasks. Optionally filter by project name prefix, nesting
+    depth, status, or assigned agent. Use --json for JSON array output.
 
   task get <id>
     Show detailed JSON for a single task, including status, agent,
@@ -190,12 +309,22 @@ Task commands — manage the task tree
       title    — task title text
       result   — result/output text
       agent    — agent name, or '-' to clear
+    With --cascade, the new status is also applied to every subtask.
 
-  task check <id>
+  task check <id> [--cascade]
     Mark a task as completed (shorthand for task set <id> status=completed).
+    --cascade completes every subtask too; cancelled subtasks are kept.
 
   task uncheck <id>
-    Mark a task as pending (shorthand for task set <id> status=pending).",
+    Mark a task as pending (shorthand for task set <id> status=pending).
+
+  task note <id> <text...>
+    Append a timestamped freeform note to a task without changing its
+    status or result. Notes appear in task get.
+
+  task move <id> <parent>
+    Move a task, with its subtasks and status, under another task. Moving
+    a task under itself or one of its own subtasks is rejected.",
 
         "config" => "\
 Config commands — manage runtime settings
@@ -210,10 +339,37 @@ Config commands — manage runtime settings
     Set a configuration value. Supported keys:
       project_root          — default working directory for new agents
       max_retries           — maximum retry count (u32)
-      health_check_interval — health check interval in ms (u64)
-      heartbeat_timeout     — heartbeat timeout in ms (u64)
-      message_timeout       — message delivery timeout in ms (u64)
-      escalation_timeout    — escalation timeout in ms (u64)
+      health_check_interval — health check interval (duration)
+      heartbeat_timeout     — heartbeat timeout (duration)
+      message_timeout       — message delivery timeout (duration)
+      message_interval      — minimum delay between deliveries to one
+                              agent; 0 disables pacing (duration)
+      escalation_timeout    — escalation timeout (duration)
+      auto_snapshot_on_change — snapshot settings into history/settings/
+                              after config add/load/save (bool)
+      heartbeat_marker      — substring marking heartbeat lines in output
+      heartbeat_marker.<role> — per-role heartbeat marker override
+      heartbeat_pattern.<type> — prompt regex for claude, console or ssh
+                              agents; groups state and context set fields
+      stall_after_unhealthy — consecutive unhealthy checks before an
+                              agent is marked stalled (u32, >= 1)
+      clear_after_healthy   — consecutive healthy checks before a stalled
+                              agent is cleared (u32, >= 1)
+      shutdown_keys         — keys sent by agent kill --graceful before
+                              killing (default /exit; blank skips)
+      shutdown_grace_ms     — wait between the shutdown keys and the
+                              kill (duration, default 5s)
+      session_backend       — multiplexer for agent sessions: tmux
+                              (default) or zellij
+      skill_conflict_strategy — how a skill defined by several sources
+                              is resolved: highest-priority (default),
+                              newest-mtime or first-registered
+    Durations are ms (e.g. 30000) or a number with ms/s/m/h suffix (e.g. 30s).
+
+  config unset <key>
+    Reset a configuration value to its built-in default. Accepts the same
+    keys as config add; heartbeat_marker.<role> removes the role override
+    and heartbeat_pattern.<type> restores built-in prompt detection.
 
   config list
     Display all current configuration values in YAML format.",
@@ -243,6 +399,17 @@ Roadmap commands — load and manage roadmap task trees
     path for write-back so status changes (task.check, task.set)
     are reflected on disk.",
 
+        "skill" => "\
+Skill commands — inspect the skill library
+
+  skill validate
+    Parse every resolved skill and list the ones that fail, with
+    their source and the error. Catches malformed frontmatter,
+    repeated fields, and a frontmatter name that disagrees with
+    the file name. A SKILL.md file is named after its directory, so
+    skills/deploy/SKILL.md defines deploy; a name defined by two
+    files in one source is listed as a warning.",
+
         "layout" => "\
 Layout commands — manage tmux sessions and pane layout
 
@@ -276,10 +443,11 @@ Client commands — navigate between client views
         "tell" | "messaging" => "\
 Messaging commands — communicate with agents
 
-  tell <agent> <text...>
+  tell [--dedup] <agent> <text...>
     Send a text message to an agent. The message is queued in the
     message store and a SendKeys action is emitted to deliver it
-    via tmux.
+    via tmux. With --dedup, a message identical to one already
+    pending for the agent is skipped.
 
   interrupt <agent> [text...]
     Send Ctrl-C to an agent. If text is provided, it is sent after
@@ -295,18 +463,23 @@ Rig commands — manage remote worker hosts
     connectivity and sets up the remote environment. If --name is omitted,
     a name is derived from the host.
 
-  rig push <folder> [-r <remote>]
+  rig push <folder> [-r <remote> | --label <label> | --all]
     Push a local folder to the remote via rsync. Uses the default remote
-    unless -r is specified.
+    unless -r is specified. --label pushes to every remote with that label;
+    --all pushes to every registered remote. --bwlimit <kbps> caps the
+    transfer rate and each --exclude <pat> skips matching files.
 
-  rig pull <folder> [-r <remote>]
+  rig pull <folder> [-r <remote> | --label <label>]
     Pull results from the remote folder back to local via rsync.
+    --label pulls from every remote with that label. Accepts the same
+    --bwlimit and --exclude options as rig push.
 
   rig status [-r <remote>]
     Show the current status of the remote (running tasks, load, etc.).
 
   rig health [-r <remote>]
-    Perform an SSH health check on the remote.
+    Perform an SSH health check on the remote. Also counts its GPUs
+    with nvidia-smi and records the result.
 
   rig stop [-r <remote>]
     Stop all running operations on the remote.
@@ -320,25 +493,37 @@ Rig commands — manage remote worker hosts
         "diagnosis" => "\
 Diagnosis commands — self-diagnosis and monitoring analytics
 
-  diagnosis report
+  diagnosis report [--since <t>] [--until <t>] [--out <file>]
     Generate a comprehensive self-diagnosis report covering signal
     reliability, intervention effectiveness, and threshold health.
+    --since/--until scope it to a time window; --out writes it to a file.
 
-  diagnosis reliability [<signal>]
+  diagnosis reliability [<signal>] [--agent <name>]
     Show reliability statistics for heartbeat signals. Optionally
-    filter by a specific signal name.
+    filter by a specific signal name, or compute them from a single
+    agent's events with --agent. --since <time> or --window-hours <n>
+    limits them to recent events.
 
-  diagnosis effectiveness [<signal>]
+  diagnosis effectiveness [<signal>] [--min-attempts <n>]
     Show intervention effectiveness metrics. Optionally filter by
-    a specific signal or intervention type.
+    a specific signal or intervention type. Pairs with fewer than
+    <n> attempts (default 5) are marked low confidence.
 
   diagnosis thresholds
     Display current adaptive threshold values and their adjustment
     history.
 
-  diagnosis events [--limit <n>]
+  diagnosis events [--limit <n>] [--json|--csv]
     List recent intervention events. Defaults to the last 20 events.
-    Use --limit to control how many are shown.",
+    Use --limit to control how many are shown, and --csv for a
+    spreadsheet-friendly export.
+
+  diagnosis compare <agent-a> <agent-b> [--json]
+    Show per-signal reliability for two agents side by side. Signals
+    seen by only one agent show zeros for the other.
+
+  diagnosis annotate <event-id> <note>
+    Append a timestamped note to an event for post-hoc analysis.",
 
         "history" => "\
 History commands — configuration snapshot management
@@ -350,26 +535,44 @@ History commands — configuration snapshot management
   history show <id>
     Display the contents of a specific snapshot.
 
-  history diff <from> [<to>]
+  history diff <from> [<to>] [--unified]
     Show the differences between two snapshots. If <to> is omitted,
-    diffs against the current configuration.
+    diffs against the current configuration. --unified prints a
+    standard unified diff.
 
-  history restore <id>
-    Restore configuration from a previous snapshot.
+  history restore <id> [--dry-run]
+    Restore configuration from a previous snapshot. --dry-run
+    shows what would change without writing anything.
 
-  history snapshot
+  history snapshot [--label <name>]
     Take a snapshot of the current configuration immediately.
+    --label names it; other history commands accept the name
+    in place of an index or filename.
+
+  history prune [--dry-run]
+    Remove old snapshots according to the retention policy, then
+    gzip the kept snapshots older than the hourly window.
+    Pinned and labeled snapshots are never removed. --dry-run lists what
+    would be removed without deleting anything.
+
+  history pin <id>
+    Pin a snapshot so pruning always keeps it.
+
+  history unpin <id>
+    Remove a pin, returning the snapshot to normal retention.
 
-  history prune
-    Remove old snapshots according to the retention policy.",
+  history search <query> [--ignore-case]
+    Print every snapshot line containing <query>, newest snapshot
+    first, as <filename>:<line>: <text>.",
 
         "watch" => "\
 Watch command — stream state changes
 
-  watch [--since <ms>] [--timeout <ms>]
+  watch [--since <ms>] [--timeout <ms>] [--follow]
     Stream state change events to stdout as they occur. Use --since
     to replay events from a given epoch-ms timestamp. Use --timeout
-    to limit how long the stream stays open (default: indefinite).",
+    to limit how long the stream stays open (default: indefinite).
+    Use --follow to keep printing changes until interrupted.",
 
         "daemon" => "\
 Daemon commands — manage the CMX daemon process
@@ -406,10 +609,10 @@ Pool commands — manage worker agent pools
     Show detailed status for the pool with the given role, including
     current agent count, assigned tasks, and health.
 
-  pool set <role> <size> [--path <p>]
+  pool set <role> <size> [--path <p>] [--min-idle <n>]
     Create or update a worker pool. Sets the target size (number of
     agents). Use --path to specify the working directory for agents
-    in the pool.
+    in the pool, and --min-idle to keep idle agents in reserve.
 
   pool remove <role>
     Remove a worker pool. Kills all agents in the pool.",
@@ -431,6 +634,10 @@ Usage: skd status [--json]
 Displays a one-line summary of the system state:
   agents: N, tasks: N, projects: N, pending messages: N
 
+A second line, \"settings modified, unsaved\", appears when config add or
+config unset changed settings that config save has not yet written to
+settings.yaml.
+
 Use --json for machine-readable JSON output.
 No other arguments required.",
 
@@ -439,8 +646,9 @@ skd view — look up an entity by name
 
 Usage: skd view <name>
 
-Searches for the given name across agents, tasks, and projects
-(in that order). Returns the first match as pretty-printed JSON.
+Searches for the given name across agents, tasks, projects, and
+skills (in that order). Returns the first match as pretty-printed
+JSON. If nothing matches, suggests a skill with a similar name.
 
 Examples:
   skd view worker1     # show agent details
@@ -451,6 +659,7 @@ Examples:
 skd help — show help information
 
 Usage: skd help [topic]
+       skd help --search <keywords>
 
 With no topic, shows an overview of all available commands.
 With a topic, shows detailed help:
@@ -459,7 +668,12 @@ With a topic, shows detailed help:
   skd help agent        # all agent commands
   skd help agent.new    # detailed help for agent.new
   skd help task         # all task commands
-  skd help config       # all config commands",
+  skd help config       # all config commands
+
+With --search, lists the commands whose help mentions every keyword,
+each with its one-line summary:
+
+  skd help --search snapshot",
 
         "agent.new" => "\
 skd agent new — create a new agent
@@ -482,13 +696,34 @@ Examples:
 Side effects:
   Emits a CreateAgent action for infrastructure to spawn the agent.",
 
-        "agent.kill" => "\
+        "agent.kill" | "agent.kill.where" => "\
 skd agent kill — remove an agent
 
-Usage: skd agent kill <name>
+Usage: skd agent kill <name> [--graceful]
 
 Removes the named agent from the registry and emits a KillAgent action.
-Fails if the agent does not exist.",
+Fails if the agent does not exist. This hard kill is the default and
+works on unresponsive agents.
+
+With --graceful, the agent is first sent the shutdown_keys setting
+(default /exit) so it can save and exit. It stays registered until
+shutdown_grace_ms (default 5s) has passed, then the daemon kills it and
+removes it from the registry.
+
+Bulk form:
+  skd agent kill [--role <r>] [--status <s>] [--label <l>]
+
+Kills every agent matching all given predicates, emitting one KillAgent
+action each, and prints the count and names. At least one predicate is
+required. Matching nothing is not an error.
+
+Flags:
+  --graceful     Send shutdown_keys before killing (single agent only)
+  --role <r>     Agent role (case-insensitive)
+  --status <s>   idle, busy, stalled, error, or dead
+  --label <l>    Label listed in the agent's `labels` state value
+                 (set with: skd agent state set <a> labels '[\"ci\"]')",
+
 
         "agent.restart" => "\
 skd agent restart — restart an agent
@@ -528,24 +763,96 @@ Examples:
         "agent.list" => "\
 skd agent list — list all agents
 
-Usage: skd agent list [--json]
+Usage: skd agent list [--sort <key>] [--columns <list>] [--json]
 
 Displays agents in a table with columns:
-  NAME  ROLE  STATUS  HEALTH  TASK
+  NAME  ROLE  STATUS  HEALTH  AGE  ON_TASK  TASK
+
+Flags:
+  --sort <key>       Sort by name, role, status, health, or age.
+                     Health sorts unhealthy agents first; age sorts
+                     oldest first. Default: registry order.
+  --columns <list>   Comma-separated columns to show, e.g. name,health,task
+  --json             JSON output (honors --sort)
+
+Examples:
+  skd agent list --sort health
+  skd agent list --columns name,status,task",
+
+        "agent.recheck" => "\
+skd agent recheck — reassess an agent's health now
+
+Usage: skd agent recheck <name>
+
+Captures the agent's pane through the session backend, runs the health
+assessment, and applies the result. Use it when an agent flagged as
+stalled has recovered and you don't want to wait for the next monitor
+cycle. A recheck is conclusive: it ignores the stall_after_unhealthy /
+clear_after_healthy hysteresis, so a healthy result clears a stall at once.
+
+Prints the fresh assessment as JSON. Needs a running daemon, since only
+the daemon holds the session backend.
+
+Examples:
+  skd agent recheck worker1",
+
+        "agent.exec" => "\
+skd agent exec — run a one-off command in an agent's pane
+
+Usage: skd agent exec <name> <cmd...>
 
-Use --json for JSON array output.",
+Types the command (followed by Enter) into the agent's session, waits
+until the pane stops changing for 500ms, and prints only the lines the
+command produced — not the echoed command or the returning prompt.
+Gives up after 10s and prints what appeared so far, noting the timeout.
+
+Unlike tell, nothing is queued: the keys are sent at once, whatever the
+agent is doing. Needs a running daemon, since only the daemon can
+capture panes.
+
+Examples:
+  skd agent exec worker1 git status --short",
+
+        "agent.state.set" => "\
+skd agent state set — store a per-agent scratch value
+
+Usage: skd agent state set <agent> <key> <value>
+
+Stores <value> under agent.<agent>.<key> in the parameter store and
+persists it to the agent's state.json. The value is parsed as JSON
+(e.g. 3, true, {\"a\": 1}); text that is not valid JSON is stored as a
+string. Returns the stored value.",
+
+        "agent.state.get" => "\
+skd agent state get — read a per-agent scratch value
+
+Usage: skd agent state get <agent> <key>
+
+Returns the JSON value stored under agent.<agent>.<key>, or an error
+if no value has been set.",
 
         "task.list" => "\
 skd task list — list all tasks
 
-Usage: skd task list [<project>] [--json]
+Usage: skd task list [<project>] [--depth <n>] [--status <s>] [--agent <a>] [--json]
 
 Lists all tasks in the task tree with indentation for depth.
 Optionally filter by project name prefix.
 
+Flags:
+  --depth <n>     Omit tasks nested deeper than n (0 = roots only)
+  --status <s>    Show only tasks with this status (pending, in_progress,
+                  completed, failed, paused, cancelled). Ancestors of a
+                  match are kept for context and marked \"(context)\".
+  --agent <a>     Show only tasks assigned to agent a. Combined with
+                  --status, a task must match both; no context rows.
+  --json          JSON array output
+
 Columns: ID  TITLE  STATUS  AGENT
 
-Use --json for JSON array output.",
+Pending tasks show as \"ready\" when every dependency has completed,
+or \"blocked\" followed by the incomplete dependency ids. The stored
+status (in --json output) stays \"pending\" for both.",
 
         "task.get" => "\
 skd task get — show task details
@@ -553,7 +860,8 @@ skd task get — show task details
 Usage: skd task get <id>
 
 Returns the task as pretty-printed JSON, including all fields:
-id, title, source, status, result, agent, children, spec_path.",
+id, title, source, status, result, agent, children, spec_path,
+and depends_on (omitted when empty).",
 
         "task.set" => "\
 skd task set — update task fields
@@ -567,17 +875,30 @@ Supported fields:
   title    — task title text
   result   — result/output text
   agent    — agent name, or '-' to clear
+  due      — deadline as epoch ms or a duration from now (30m, 2h),
+             or '-' to clear. task list marks incomplete tasks past
+             their deadline as (overdue).
+
+Flags:
+  --cascade  Also apply the new status to every subtask. Subtasks that
+             are cancelled keep their status. Requires status=.
 
 Examples:
   skd task set T1 status=in_progress
-  skd task set T1 status=completed title=Done result='all tests passed'",
+  skd task set T1 due=4h
+  skd task set T1 status=completed title=Done result='all tests passed'
+  skd task set M1 status=paused --cascade",
 
         "task.check" => "\
 skd task check — mark task completed
 
-Usage: skd task check <id>
+Usage: skd task check <id> [--cascade]
+
+Shorthand for: skd task set <id> status=completed
 
-Shorthand for: skd task set <id> status=completed",
+Flags:
+  --cascade  Complete every subtask as well, except cancelled ones. Each
+             changed task is written back to its roadmap file.",
 
         "task.uncheck" => "\
 skd task uncheck — mark task pending
@@ -586,6 +907,31 @@ Usage: skd task uncheck <id>
 
 Shorthand for: skd task set <id> status=pending",
 
+        "task.note" => "\
+skd task note — append a note to a task
+
+Usage: skd task note <id> <text...>
+
+Appends a timestamped note. Status and result are untouched. Notes are
+shown by task get and kept with task state; they are never written to
+the roadmap file.
+
+Examples:
+  skd task note CMX1 waiting on API credentials",
+
+        "task.move" => "\
+skd task move — move a task under a different parent
+
+Usage: skd task move <id> <parent>
+
+Detaches the task together with its subtasks and appends it to the
+children of <parent>. Status, notes, and assignments are kept. Fails if
+either id is unknown or if <parent> is the task itself or one of its
+subtasks. The roadmap file is not rewritten.
+
+Examples:
+  skd task move CMX1.3 CMX2",
+
         "config.load" => "\
 skd config load — load settings from file
 
@@ -599,7 +945,9 @@ skd config save — save settings to file
 
 Usage: skd config save [<path>]
 
-Saves current runtime settings to a YAML file.",
+Saves current runtime settings to a YAML file, by default
+<config_dir>/settings.yaml. Saving a copy to another path leaves the
+settings marked unsaved.",
 
         "config.add" => "\
 skd config add — set a configuration value
@@ -607,9 +955,33 @@ skd config add — set a configuration value
 Usage: skd config add <key> <value>
 
 Supported keys: project_root, max_retries, health_check_interval,
-heartbeat_timeout, message_timeout, escalation_timeout.
+heartbeat_timeout, message_timeout, message_interval, escalation_timeout,
+auto_snapshot_on_change, heartbeat_marker, heartbeat_marker.<role>,
+heartbeat_pattern.<type>, stall_after_unhealthy, clear_after_healthy,
+shutdown_keys, shutdown_grace_ms, session_backend, skill_conflict_strategy.
 
-Numeric keys are validated on parse.",
+A blank heartbeat marker falls back to the default (HEARTBEAT).
+
+heartbeat_pattern.<type> sets a prompt regex for one agent type (claude,
+console, ssh), matched against the last non-empty pane line. A match
+means ready; named groups override fields: state (ready, idle, busy,
+error) and context (percent). Invalid regexes are rejected.
+
+Numeric keys are validated on parse. Duration keys accept ms (30000)
+or a suffixed value (250ms, 30s, 2m, 1h).",
+
+        "config.unset" => "\
+skd config unset — reset a configuration value to its default
+
+Usage: skd config unset <key>
+
+Accepts the same keys as config add. For heartbeat_marker.<role>, the
+role override is removed so the global marker applies again. For
+heartbeat_pattern.<type>, the agent type returns to built-in detection.
+
+Examples:
+  skd config unset max_retries
+  skd config unset heartbeat_marker.worker",
 
         "config.list" => "\
 skd config list — show all settings
@@ -659,10 +1031,14 @@ status marker in the file is updated in-place.",
         "tell" => "\
 skd tell — send a message to an agent
 
-Usage: skd tell <agent> <text...>
+Usage: skd tell [--dedup] <agent> <text...>
 
 Queues a message for the agent and emits a SendKeys action to deliver it.
-The agent must exist.",
+The agent must exist.
+
+Flags:
+  --dedup   Skip the message if identical text is already pending for
+            the agent (useful when retrying). Must precede the agent name.",
 
         "interrupt" => "\
 skd interrupt — interrupt an agent
@@ -742,27 +1118,46 @@ Flags:
   --name <n>   Name for this remote. Derived from host if omitted.
 
 Verifies SSH connectivity and sets up the remote environment for
-use as a worker rig.",
+use as a worker rig. Registered remotes and the default are saved to
+<config_dir>/rig.yaml and reloaded when the daemon starts. Add
+`multiplex: true` to a remote's entry there to reuse one persistent
+SSH connection; control sockets live in <config_dir>/ssh/.",
 
         "rig.push" => "\
 skd rig push — push code to remote
 
-Usage: skd rig push <folder> [-r <remote>]
+Usage: skd rig push <folder> [-r <remote> | --label <label> | --all]
+                    [--bwlimit <kbps>] [--exclude <pat>]...
 
 Pushes a local folder to the remote host via rsync. Uses the default
-remote unless -r is specified.
+remote unless -r is specified. With --label, pushes to every remote
+carrying that label; with --all, to every registered remote, each into
+its own workspace_dir. Each remote's result is reported.
+
+Options:
+  --bwlimit <kbps>   Cap the transfer rate (rsync --bwlimit)
+  --exclude <pat>    Skip files matching <pat>; repeatable
 
 Examples:
   skd rig push ./src
-  skd rig push ./project -r gpu1",
+  skd rig push ./project -r gpu1
+  skd rig push ./project --label gpu
+  skd rig push ./project --all
+  skd rig push ./project -r gpu1 --bwlimit 2000 --exclude data/",
 
         "rig.pull" => "\
 skd rig pull — pull results from remote
 
-Usage: skd rig pull <folder> [-r <remote>]
+Usage: skd rig pull <folder> [-r <remote> | --label <label>]
+                    [--bwlimit <kbps>] [--exclude <pat>]...
 
 Pulls a remote folder back to local via rsync. Uses the default
-remote unless -r is specified.",
+remote unless -r is specified. With --label, pulls from every remote
+carrying that label.
+
+Options:
+  --bwlimit <kbps>   Cap the transfer rate (rsync --bwlimit)
+  --exclude <pat>    Skip files matching <pat>; repeatable",
 
         "rig.status" => "\
 skd rig status — show remote status
@@ -778,7 +1173,8 @@ skd rig health — health check remote
 Usage: skd rig health [-r <remote>]
 
 Performs an SSH connectivity check on the remote. Reports latency
-and connection status.",
+and connection status. The same call runs `nvidia-smi -L`; when it
+lists GPUs, the count is shown and saved as the remote's gpu_count.",
 
         "rig.stop" => "\
 skd rig stop — stop remote operations
@@ -809,28 +1205,51 @@ With a name, sets that remote as the default for -r flags.",
         "diagnosis.report" => "\
 skd diagnosis report — generate self-diagnosis report
 
-Usage: skd diagnosis report
+Usage: skd diagnosis report [--since <t>] [--until <t>] [--out <file>]
 
 Generates a comprehensive report covering signal reliability,
 intervention effectiveness, adaptive threshold health, and
-recent events.",
+recent events.
+
+With a window, statistics are computed from the events inside it only.
+A window with no events still yields a report saying so.
+
+Flags:
+  --since <t>    Include events at or after t
+  --until <t>    Include events before t
+  --out <file>   Write the markdown to file instead of printing it.
+                 Relative paths resolve against the daemon's directory.
+
+Times are epoch milliseconds, or a duration ago: 30m, 24h, 90s.",
 
         "diagnosis.reliability" => "\
 skd diagnosis reliability — signal reliability statistics
 
-Usage: skd diagnosis reliability [<signal>]
+Usage: skd diagnosis reliability [--signal <s>] [--agent <name>]
+                                 [--since <time> | --window-hours <n>] [--json]
 
 Shows reliability metrics for heartbeat signals: hit rate, miss rate,
-false-positive rate. Optionally filter to a single signal name.",
+false-positive rate. Optionally filter to a single signal name.
+
+--agent computes the metrics from one agent's events only, so a single
+flaky worker can be told apart from a badly tuned signal.
+
+--since (epoch ms, or a duration ago such as 24h) or --window-hours
+counts only recent events, so a signal that has since been fixed is
+not held back by old history.",
 
         "diagnosis.effectiveness" => "\
 skd diagnosis effectiveness — intervention effectiveness
 
-Usage: skd diagnosis effectiveness [<signal>]
+Usage: skd diagnosis effectiveness [<signal>] [--min-attempts <n>] [--json]
 
 Shows how effective past interventions have been: success rate,
 average recovery time, repeat failure rate. Optionally filter
-by signal or intervention type.",
+by signal or intervention type.
+
+Flags:
+  --min-attempts <n>   Mark pairs with fewer attempts as low confidence (default: 5).
+  --json               Output as JSON.",
 
         "diagnosis.thresholds" => "\
 skd diagnosis thresholds — show adaptive thresholds
@@ -843,10 +1262,31 @@ and recent adjustment history.",
         "diagnosis.events" => "\
 skd diagnosis events — list recent intervention events
 
-Usage: skd diagnosis events [--limit <n>]
+Usage: skd diagnosis events [--limit <n>] [--json|--csv]
 
 Lists recent intervention events with timestamps, signal names,
-actions taken, and outcomes. Defaults to the last 20 events.",
+actions taken, and outcomes. Defaults to the last 20 events.
+
+--csv emits a header row and one row per event, including the signal
+and outcome details, for import into a spreadsheet.",
+
+        "diagnosis.compare" => "\
+skd diagnosis compare — compare two agents' signal reliability
+
+Usage: skd diagnosis compare <agent-a> <agent-b> [--json]
+
+Computes reliability separately from each agent's events and shows
+fires and score per signal side by side. A signal seen by only one
+agent appears with zeros for the other.",
+
+        "diagnosis.annotate" => "\
+skd diagnosis annotate — add a note to an intervention event
+
+Usage: skd diagnosis annotate <event-id> <note>
+
+Appends a timestamped note to the event, e.g. why an intervention was
+a false positive. Notes are shown under the event in diagnosis events
+and do not affect reliability statistics.",
 
         // --- History commands ---
 
@@ -868,45 +1308,89 @@ Displays the full contents of the specified configuration snapshot.",
         "history.diff" => "\
 skd history diff — diff two snapshots
 
-Usage: skd history diff <from> [<to>]
+Usage: skd history diff <from> [<to>] [--unified]
 
 Shows differences between two snapshots. If <to> is omitted,
-diffs the snapshot against the current live configuration.",
+diffs the snapshot against the current live configuration.
+With --unified, prints a standard unified diff with three lines
+of context, which can be piped to patch or delta.",
 
         "history.restore" => "\
 skd history restore — restore a snapshot
 
-Usage: skd history restore <id>
+Usage: skd history restore <id> [--dry-run]
 
 Restores configuration from a previous snapshot. The current
-configuration is automatically snapshotted before restoration.",
+configuration is automatically snapshotted before restoration.
+With --dry-run, prints the diff from the current configuration to
+the snapshot and leaves every file untouched.",
 
         "history.snapshot" => "\
 skd history snapshot — take a snapshot now
 
-Usage: skd history snapshot
+Usage: skd history snapshot [--label <name>]
 
-Takes an immediate snapshot of the current configuration state.",
+Takes an immediate snapshot of the current configuration state.
+With --label, the snapshot is named so show, diff, restore, and pin
+can refer to it by that name. If the configuration is unchanged, the
+label is attached to the most recent snapshot.",
 
         "history.prune" => "\
 skd history prune — prune old snapshots
 
-Usage: skd history prune
+Usage: skd history prune [--dry-run]
+
+Removes old snapshots according to the configured retention policy.
+Pinned and labeled snapshots are always kept and count toward any
+total cap.
+Kept snapshots older than the hourly window are then compressed to
+.md.gz; they stay listed and readable under their .md names.
+
+Flags:
+  --dry-run    List the snapshots that would be removed; delete nothing",
+
+        "history.pin" => "\
+skd history pin — protect a snapshot from pruning
+
+Usage: skd history pin <id>
+
+Pins a snapshot (by filename or index) so retention pruning never
+removes it. Shown with a \"pinned\" marker in history list.",
+
+        "history.unpin" => "\
+skd history unpin — remove a snapshot's pin
+
+Usage: skd history unpin <id>
 
-Removes old snapshots according to the configured retention policy.",
+Unpins a snapshot so it is subject to normal retention again.",
+
+        "history.search" => "\
+skd history search — find text across all snapshots
+
+Usage: skd history search <query> [--ignore-case]
+
+Searches every snapshot, compressed ones included, for lines
+containing <query>. Matches print newest snapshot first, one per
+line as <filename>:<line>: <text>. Useful for finding when an agent
+or setting first appeared.
+
+Options:
+  -i, --ignore-case    Match regardless of case",
 
         // --- Watch command ---
 
         "watch" => "\
 skd watch — stream state changes
 
-Usage: skd watch [--since <ms>] [--timeout <ms>]
+Usage: skd watch [--since <ms>] [--timeout <ms>] [--follow]
 
 Streams state change events to stdout as newline-delimited JSON.
 
 Flags:
   --since <ms>    Replay events from this epoch-ms timestamp
   --timeout <ms>  Close the stream after this many milliseconds
+  --follow        Re-register after each event and keep printing
+                  until interrupted; survives daemon restarts
 
 Without --since, only new events are streamed. Without --timeout,
 the stream stays open until interrupted.",
@@ -982,6 +1466,26 @@ Examples:
   skd learnings search \"rate limit\"
   skd learnings search sqlite",
 
+        // --- Skill commands ---
+
+        "skill.validate" => "\
+skd skill validate — check every skill file for errors
+
+Usage: skd skill validate
+
+Parses each resolved skill (the winning file for every name) and
+prints a table of skill, source, and error for those that fail.
+A skill fails if its frontmatter does not parse, repeats a field,
+or declares a name different from the file's. A missing name is
+allowed; the file name is used. Exits non-zero if any skill fails.
+
+A skill's name is its file name without .md, except that a SKILL.md
+file takes its directory's name: skills/deploy/SKILL.md defines
+deploy, not SKILL. A name defined by more than one file in the same
+source (e.g. deploy.md beside deploy/SKILL.md) is listed as a
+warning, as are directories the library scan skipped (symlink loops,
+depth limit).",
+
         // --- Pool commands ---
 
         "pool.list" => "\
@@ -1003,13 +1507,16 @@ individual agent states, assigned tasks, and health.",
         "pool.set" => "\
 skd pool set — create or update a pool
 
-Usage: skd pool set <role> <size> [--path <p>]
+Usage: skd pool set <role> <size> [--path <p>] [--min-idle <n>]
 
 Creates a new worker pool or updates an existing one. Sets the target
 number of agents for the given role.
 
 Flags:
-  --path <p>   Working directory for agents in the pool.
+  --path <p>       Working directory for agents in the pool.
+  --min-idle <n>   Keep at least n idle agents, spawning beyond the
+                   target (up to twice the target) when all are busy.
+                   Keeps the current value if omitted.
 
 Examples:
   skd pool set worker 4
@@ -1056,6 +1563,14 @@ mod tests {
         assert!(text.contains("Pool commands:"));
     }
 
+    #[test]
+    fn overview_documents_exit_codes() {
+        let text = help_text(None);
+        assert!(text.contains("Exit codes:"));
+        assert!(text.contains("3  not found"));
+        assert!(text.contains("6  unavailable"));
+    }
+
     #[test]
     fn overview_lists_status() {
         let text = help_text(None);
@@ -1083,6 +1598,7 @@ mod tests {
         assert!(text.contains("task set"));
         assert!(text.contains("task check"));
         assert!(text.contains("task uncheck"));
+        assert!(text.contains("task move"));
     }
 
     #[test]
@@ -1186,38 +1702,32 @@ mod tests {
 
     #[test]
     fn command_help_all_commands_covered() {
-        let commands = vec![
-            "status", "view", "help",
-            "agent.new", "agent.kill", "agent.restart",
-            "agent.assign", "agent.unassign", "agent.status", "agent.list",
-            "task.list", "task.get", "task.set", "task.check", "task.uncheck",
-            "config.load", "config.save", "config.add", "config.list",
-            "project.add", "project.remove", "project.list", "project.scan",
-            "roadmap.load",
-            "tell", "interrupt",
-            "layout.row", "layout.column", "layout.merge",
-            "layout.place", "layout.capture", "layout.session",
-            "client.next", "client.prev",
-            "rig.init", "rig.push", "rig.pull", "rig.status",
-            "rig.health", "rig.stop", "rig.list", "rig.default",
-            "diagnosis.report", "diagnosis.reliability", "diagnosis.effectiveness",
-            "diagnosis.thresholds", "diagnosis.events",
-            "history.list", "history.show", "history.diff",
-            "history.restore", "history.snapshot", "history.prune",
-            "learnings.list", "learnings.add", "learnings.search",
-            "watch",
-            "daemon.run", "daemon.stop", "tui",
-            "pool.list", "pool.status", "pool.set", "pool.remove",
-        ];
-        for cmd in commands {
-            assert!(
-                command_help(cmd).is_some(),
-                "Missing command help for: {}",
-                cmd
-            );
+        for cmd in COMMANDS {
+            assert!(command_help(cmd).is_some(), "Missing command help for: {}", cmd);
         }
     }
 
+    #[test]
+    fn search_snapshot_finds_history_snapshot() {
+        let text = search("snapshot");
+        assert!(text.contains("history.snapshot"));
+        assert!(text.contains("take a snapshot now"));
+    }
+
+    #[test]
+    fn search_reliability_finds_diagnosis() {
+        let text = search("Reliability");
+        assert!(text.contains("diagnosis.reliability"));
+        assert!(text.contains("signal reliability statistics"));
+        assert!(!text.contains("agent.new"));
+    }
+
+    #[test]
+    fn search_requires_all_words() {
+        assert!(search("snapshot pin").contains("history.pin"));
+        assert!(!search("snapshot pin").contains("history.list"));
+        assert!(search("xyzzy").starts_with("No commands match"));
+    }
 
     #[test]
     fn group_help_rig() {
@@ -1276,6 +1786,12 @@ mod tests {
         assert!(text.contains("learnings search"));
     }
 
+    #[test]
+    fn group_help_skill() {
+        let text = help_text(Some("skill"));
+        assert!(text.contains("skill validate"));
+    }
+
     #[test]
     fn group_help_pool() {
         let text = help_text(Some("pool"));
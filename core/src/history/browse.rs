@@ -52,6 +52,151 @@ pub fn line_diff(from_content: &str, to_content: &str) -> (Vec<String>, Vec<Stri
     (added, removed)
 }
 
+/// One step of an ordered line diff.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffOp {
+    /// Line present in both versions.
+    Same(String),
+    /// Line only in the old version.
+    Removed(String),
+    /// Line only in the new version.
+    Added(String),
+}
+
+/// Compute an ordered line diff from the longest common subsequence.
+///
+/// Unlike `line_diff`, which only reports which lines differ, this keeps
+/// positions so callers can align old and new text. Within a changed run,
+/// removals come before additions.
+pub fn ordered_diff(from_lines: &[&str], to_lines: &[&str]) -> Vec<DiffOp> {
+    let (n, m) = (from_lines.len(), to_lines.len());
+    // lcs[i][j] = LCS length of from_lines[i..] and to_lines[j..]
+    let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if from_lines[i] == to_lines[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut ops = Vec::with_capacity(n.max(m));
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if from_lines[i] == to_lines[j] {
+            ops.push(DiffOp::Same(from_lines[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            ops.push(DiffOp::Removed(from_lines[i].to_string()));
+            i += 1;
+        } else {
+            ops.push(DiffOp::Added(to_lines[j].to_string()));
+            j += 1;
+        }
+    }
+    ops.extend(from_lines[i..].iter().map(|l| DiffOp::Removed(l.to_string())));
+    ops.extend(to_lines[j..].iter().map(|l| DiffOp::Added(l.to_string())));
+    ops
+}
+
+/// Context lines shown around each change in a unified diff.
+pub const DEFAULT_CONTEXT_LINES: usize = 3;
+
+/// Render a standard unified diff (`--- a/..`, `+++ b/..`, `@@` hunks) of
+/// two snapshots, suitable for `patch` or `delta`.
+///
+/// Lines are compared with their terminators, so a lost or gained trailing
+/// newline shows up as a change marked `\ No newline at end of file`.
+/// Identical contents produce an empty string.
+pub fn compute_unified_diff(
+    from_name: &str,
+    to_name: &str,
+    from_content: &str,
+    to_content: &str,
+    context: usize,
+) -> String {
+    let from_lines: Vec<&str> = from_content.split_inclusive('\n').collect();
+    let to_lines: Vec<&str> = to_content.split_inclusive('\n').collect();
+    let ops = ordered_diff(&from_lines, &to_lines);
+
+    let changes: Vec<usize> = ops
+        .iter()
+        .enumerate()
+        .filter(|(_, op)| !matches!(op, DiffOp::Same(_)))
+        .map(|(i, _)| i)
+        .collect();
+    if changes.is_empty() {
+        return String::new();
+    }
+
+    // Line positions (0-based) in each file before every op.
+    let mut positions = Vec::with_capacity(ops.len() + 1);
+    let (mut old, mut new) = (0usize, 0usize);
+    for op in &ops {
+        positions.push((old, new));
+        match op {
+            DiffOp::Same(_) => {
+                old += 1;
+                new += 1;
+            }
+            DiffOp::Removed(_) => old += 1,
+            DiffOp::Added(_) => new += 1,
+        }
+    }
+
+    // Group changes whose separating context would overlap.
+    let mut groups: Vec<(usize, usize)> = Vec::new();
+    for &c in &changes {
+        match groups.last_mut() {
+            Some((_, last)) if c - *last <= 2 * context + 1 => *last = c,
+            _ => groups.push((c, c)),
+        }
+    }
+
+    let mut out = format!("--- a/{}\n+++ b/{}\n", from_name, to_name);
+    for (first, last) in groups {
+        let start = first.saturating_sub(context);
+        let end = (last + context + 1).min(ops.len());
+        let hunk = &ops[start..end];
+        let old_count = hunk.iter().filter(|op| !matches!(op, DiffOp::Added(_))).count();
+        let new_count = hunk.iter().filter(|op| !matches!(op, DiffOp::Removed(_))).count();
+        let (old_start, new_start) = positions[start];
+        out.push_str(&format!(
+            "@@ -{} +{} @@\n",
+            hunk_range(old_start, old_count),
+            hunk_range(new_start, new_count)
+        ));
+        for op in hunk {
+            let (prefix, line) = match op {
+                DiffOp::Same(l) => (' ', l),
+                DiffOp::Removed(l) => ('-', l),
+                DiffOp::Added(l) => ('+', l),
+            };
+            out.push(prefix);
+            out.push_str(line);
+            if !line.ends_with('\n') {
+                out.push_str("\n\\ No newline at end of file\n");
+            }
+        }
+    }
+    out
+}
+
+/// Format one side of a hunk header. An empty side names the line before
+/// the hunk, per the unified diff convention.
+fn hunk_range(start: usize, count: usize) -> String {
+    if count == 0 {
+        format!("{},0", start)
+    } else if count == 1 {
+        format!("{}", start + 1)
+    } else {
+        format!("{},{}", start + 1, count)
+    }
+}
+
 /// Generate a human-readable summary of configuration changes.
 ///
 /// Scans the added and removed lines for known configuration patterns
@@ -264,6 +409,8 @@ mod tests {
             filename,
             path: PathBuf::from("/tmp/test"),
             size_bytes: 0,
+            pinned: false,
+            label: None,
         }
     }
 
@@ -302,6 +449,70 @@ mod tests {
         assert!(removed.is_empty());
     }
 
+    #[test]
+    fn ordered_diff_keeps_positions() {
+        let ops = ordered_diff(&["a", "b", "c"], &["a", "x", "c", "d"]);
+        assert_eq!(
+            ops,
+            vec![
+                DiffOp::Same("a".into()),
+                DiffOp::Removed("b".into()),
+                DiffOp::Added("x".into()),
+                DiffOp::Same("c".into()),
+                DiffOp::Added("d".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn unified_diff_hunk_headers() {
+        let from: String = (1..=12).map(|i| format!("line {}\n", i)).collect();
+        let to = from
+            .replace("line 2\n", "line two\n")
+            .replace("line 11\n", "line 11\nline 11b\n");
+        let diff = compute_unified_diff("a.md", "b.md", &from, &to, 3);
+        let lines: Vec<&str> = diff.lines().collect();
+        assert_eq!(lines[0], "--- a/a.md");
+        assert_eq!(lines[1], "+++ b/b.md");
+        assert_eq!(lines[2], "@@ -1,5 +1,5 @@");
+        assert_eq!(lines[3], " line 1");
+        assert_eq!(lines[4], "-line 2");
+        assert_eq!(lines[5], "+line two");
+        let second = lines.iter().position(|l| l.starts_with("@@ -9")).unwrap();
+        assert_eq!(lines[second], "@@ -9,4 +9,5 @@");
+        assert_eq!(lines[second + 1], " line 9");
+        assert_eq!(lines[second + 4], "+line 11b");
+        assert_eq!(lines.len(), second + 6);
+    }
+
+    #[test]
+    fn unified_diff_merges_nearby_changes() {
+        let from = "a\nb\nc\nd\ne\n";
+        let to = "A\nb\nc\nd\nE\n";
+        let diff = compute_unified_diff("x", "y", from, to, 3);
+        assert_eq!(diff.matches("@@").count(), 2);
+        assert!(diff.contains("@@ -1,5 +1,5 @@"));
+    }
+
+    #[test]
+    fn unified_diff_identical_is_empty() {
+        assert_eq!(compute_unified_diff("x", "y", "a\nb\n", "a\nb\n", 3), "");
+        assert_eq!(compute_unified_diff("x", "y", "", "", 3), "");
+    }
+
+    #[test]
+    fn unified_diff_missing_trailing_newline() {
+        let diff = compute_unified_diff("x", "y", "a\nb", "a\nb\n", 3);
+        assert!(diff.contains("@@ -1,2 +1,2 @@"));
+        assert!(diff.contains("-b\n\\ No newline at end of file\n+b\n"));
+    }
+
+    #[test]
+    fn unified_diff_from_empty_file() {
+        let diff = compute_unified_diff("x", "y", "", "a\nb\n", 3);
+        assert!(diff.contains("@@ -0,0 +1,2 @@"));
+    }
+
     #[test]
     fn line_diff_empty_strings() {
         let (added, removed) = line_diff("", "");
@@ -3,7 +3,7 @@
 //! This module maintains a `history/` folder alongside the live configuration
 //! file, containing timestamped copies. A retention policy prunes old
 //! snapshots automatically: hourly resolution for the last 24 hours, daily
-//! for one week, weekly beyond that.
+//! for one week, weekly beyond that. Pinned snapshots are exempt.
 //!
 //! # Usage
 //!
@@ -18,7 +18,7 @@ pub mod browse;
 pub mod retention;
 pub mod snapshot;
 
-pub use browse::HistoryDiff;
+pub use browse::{DiffOp, HistoryDiff};
 pub use retention::RetentionPolicy;
 pub use snapshot::{HistoryEntry, HistoryError};
 
@@ -28,6 +28,18 @@ use std::path::PathBuf;
 // HistoryManager
 // ---------------------------------------------------------------------------
 
+/// One `search` hit: the entry and its matching `(line_number, text)` lines.
+pub type SearchHit = (HistoryEntry, Vec<(usize, String)>);
+
+/// Outcome of `HistoryManager::search`.
+#[derive(Debug, Clone)]
+pub struct SearchResults {
+    /// Entries with at least one matching line, newest first.
+    pub hits: Vec<SearchHit>,
+    /// Entries that could not be read, with the reason.
+    pub skipped: Vec<(HistoryEntry, String)>,
+}
+
 /// Main interface for configuration history operations.
 ///
 /// Manages timestamped copies of `Current Configuration.md` in a `history/`
@@ -64,6 +76,18 @@ impl HistoryManager {
         Self::new(config_dir, RetentionPolicy::default())
     }
 
+    /// Create a manager for runtime settings (`settings.yaml`), kept in
+    /// `history/settings/` so it never mixes with configuration snapshots.
+    pub fn for_settings(config_dir: PathBuf) -> Result<HistoryManager, HistoryError> {
+        let history_dir = config_dir.join("history").join("settings");
+        std::fs::create_dir_all(&history_dir)?;
+        Ok(HistoryManager {
+            history_dir,
+            config_path: config_dir.join("settings.yaml"),
+            policy: RetentionPolicy::default(),
+        })
+    }
+
     /// Create a history snapshot if the config has changed since the last one.
     ///
     /// Returns `Some(entry)` if a new snapshot was created, `None` if the
@@ -75,7 +99,48 @@ impl HistoryManager {
         }
 
         let current_content = std::fs::read_to_string(&self.config_path)?;
-        let current_hash = snapshot::content_hash(&current_content);
+        self.snapshot_content(&current_content, now_ms)
+    }
+
+    /// Like `maybe_snapshot`, but names the snapshot `label`. If the
+    /// configuration is unchanged, the label is attached to the most recent
+    /// snapshot instead and that entry is returned, so the moment can still
+    /// be found by name. The flag is true when a new snapshot was taken.
+    /// Returns `None` only if there is nothing to label.
+    pub fn maybe_snapshot_labeled(
+        &self,
+        now_ms: u64,
+        label: &str,
+    ) -> Result<Option<(HistoryEntry, bool)>, HistoryError> {
+        let label = label.trim();
+        if label.is_empty() || label.contains('\n') {
+            return Err(HistoryError::InvalidLabel(label.to_string()));
+        }
+        let (entry, created) = match self.maybe_snapshot(now_ms)? {
+            Some(entry) => (entry, true),
+            None => match snapshot::latest_entry(&self.history_dir)? {
+                Some(latest) if self.config_path.exists() => (latest, false),
+                _ => return Ok(None),
+            },
+        };
+        snapshot::write_label(&entry.path, label)?;
+        let entry = HistoryEntry {
+            label: Some(label.to_string()),
+            ..entry
+        };
+        Ok(Some((entry, created)))
+    }
+
+    /// Snapshot `content` unless it matches the most recent entry.
+    ///
+    /// Used when the tracked state lives in memory rather than in the
+    /// config file (e.g. settings changed by `config add`).
+    pub fn snapshot_content(
+        &self,
+        content: &str,
+        now_ms: u64,
+    ) -> Result<Option<HistoryEntry>, HistoryError> {
+        let current_hash = snapshot::content_hash(content);
 
         // Compare with the most recent history entry.
         if let Some(latest) = snapshot::latest_entry(&self.history_dir)? {
@@ -87,7 +152,7 @@ impl HistoryManager {
         }
 
         // Content changed — create a new snapshot.
-        let entry = snapshot::create_snapshot(&self.history_dir, &current_content, now_ms)?;
+        let entry = snapshot::create_snapshot(&self.history_dir, content, now_ms)?;
         Ok(Some(entry))
     }
 
@@ -99,6 +164,29 @@ impl HistoryManager {
         retention::prune_entries(&entries, now_ms, &self.policy)
     }
 
+    /// Gzip snapshots older than the policy's hourly window. They stay
+    /// listed and readable under their `.md` names.
+    ///
+    /// Returns the number of entries compressed.
+    pub fn compact(&self, now_ms: u64) -> Result<usize, HistoryError> {
+        let window_ms = self.policy.hourly_window_hours as u64 * 3_600_000;
+        let cutoff = now_ms.saturating_sub(window_ms);
+        let mut compacted = 0;
+        for entry in snapshot::list_entries(&self.history_dir)? {
+            if entry.timestamp_ms < cutoff && !snapshot::is_compressed(&entry.path) {
+                snapshot::compress_snapshot(&entry)?;
+                compacted += 1;
+            }
+        }
+        Ok(compacted)
+    }
+
+    /// The entries `prune` would delete at `now_ms`, without deleting them.
+    pub fn plan_prune(&self, now_ms: u64) -> Result<Vec<HistoryEntry>, HistoryError> {
+        let entries = snapshot::list_entries(&self.history_dir)?;
+        Ok(retention::plan_prune(&entries, now_ms, &self.policy))
+    }
+
     /// List all history entries, newest first.
     pub fn list(&self) -> Result<Vec<HistoryEntry>, HistoryError> {
         snapshot::list_entries(&self.history_dir)
@@ -114,6 +202,41 @@ impl HistoryManager {
         Ok(browse::filter_range(&entries, from_ms, to_ms))
     }
 
+    /// Find `query` in every snapshot, newest first. Each hit carries the
+    /// matching lines as `(line_number, text)`, numbered from 1. Entries
+    /// without a match are left out; entries that cannot be read (corrupt
+    /// gzip, not UTF-8) are skipped and listed in `skipped`.
+    pub fn search(&self, query: &str, case_insensitive: bool) -> Result<SearchResults, HistoryError> {
+        let needle = if case_insensitive { query.to_lowercase() } else { query.to_string() };
+        let mut hits = Vec::new();
+        let mut skipped = Vec::new();
+        for entry in snapshot::list_entries(&self.history_dir)? {
+            let content = match snapshot::read_snapshot(&entry) {
+                Ok(c) => c,
+                Err(e) => {
+                    skipped.push((entry, e.to_string()));
+                    continue;
+                }
+            };
+            let lines: Vec<(usize, String)> = content
+                .lines()
+                .enumerate()
+                .filter(|(_, line)| {
+                    if case_insensitive {
+                        line.to_lowercase().contains(&needle)
+                    } else {
+                        line.contains(&needle)
+                    }
+                })
+                .map(|(i, line)| (i + 1, line.to_string()))
+                .collect();
+            if !lines.is_empty() {
+                hits.push((entry, lines));
+            }
+        }
+        Ok(SearchResults { hits, skipped })
+    }
+
     /// Read the content of a history entry.
     pub fn read(&self, entry: &HistoryEntry) -> Result<String, HistoryError> {
         snapshot::read_snapshot(entry)
@@ -130,6 +253,59 @@ impl HistoryManager {
         Ok(browse::compute_diff(from, to, &from_content, &to_content))
     }
 
+    /// Render a unified diff between two entries with `context` lines
+    /// around each change. Empty if the snapshots are identical.
+    pub fn unified_diff(
+        &self,
+        from: &HistoryEntry,
+        to: &HistoryEntry,
+        context: usize,
+    ) -> Result<String, HistoryError> {
+        let from_content = snapshot::read_snapshot(from)?;
+        let to_content = snapshot::read_snapshot(to)?;
+        Ok(browse::compute_unified_diff(
+            &from.filename,
+            &to.filename,
+            &from_content,
+            &to_content,
+            context,
+        ))
+    }
+
+    /// Pin or unpin an entry so retention pruning keeps it.
+    ///
+    /// Returns `true` if the pin state changed.
+    pub fn set_pinned(&self, entry: &HistoryEntry, pinned: bool) -> Result<bool, HistoryError> {
+        snapshot::set_pinned(&self.history_dir, &entry.filename, pinned)
+    }
+
+    /// Preview what `restore` would change: the diff from the current
+    /// configuration to `entry`. Reads only; no file is written, not even
+    /// the pre-restore snapshot. A missing configuration diffs as empty, so
+    /// every historical line shows as added. Fails with `EntryNotFound` if
+    /// the snapshot file is gone.
+    pub fn restore_preview(&self, entry: &HistoryEntry) -> Result<HistoryDiff, HistoryError> {
+        let to_content = snapshot::read_snapshot(entry)?;
+        let current_content = if self.config_path.exists() {
+            std::fs::read_to_string(&self.config_path)?
+        } else {
+            String::new()
+        };
+        let current = HistoryEntry {
+            timestamp_ms: 0,
+            filename: self
+                .config_path
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            path: self.config_path.clone(),
+            size_bytes: current_content.len() as u64,
+            pinned: false,
+            label: None,
+        };
+        Ok(browse::compute_diff(&current, entry, &current_content, &to_content))
+    }
+
     /// Restore a history entry as the current configuration.
     ///
     /// Takes a snapshot of the current state first (so the pre-restore state
@@ -244,6 +420,26 @@ mod tests {
         let _ = std::fs::remove_dir_all(&dir);
     }
 
+    #[test]
+    fn settings_manager_dedups_content_in_own_dir() {
+        let dir = test_dir("settings_content");
+        let mgr = HistoryManager::for_settings(dir.clone()).unwrap();
+        let ts1 = compose_timestamp(2026, 2, 22, 10, 0, 0) * 1000;
+        let ts2 = compose_timestamp(2026, 2, 22, 10, 1, 0) * 1000;
+        let ts3 = compose_timestamp(2026, 2, 22, 10, 2, 0) * 1000;
+
+        assert!(mgr.snapshot_content("max_retries: 3\n", ts1).unwrap().is_some());
+        assert!(mgr.snapshot_content("max_retries: 3\n", ts2).unwrap().is_none());
+        assert!(mgr.snapshot_content("max_retries: 5\n", ts3).unwrap().is_some());
+        assert_eq!(mgr.list().unwrap().len(), 2);
+
+        // Configuration history is unaffected.
+        let config_mgr = HistoryManager::with_defaults(dir.clone()).unwrap();
+        assert!(config_mgr.list().unwrap().is_empty());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn list_returns_newest_first() {
         let dir = test_dir("list_order");
@@ -453,4 +649,181 @@ mod tests {
 
         let _ = std::fs::remove_dir_all(&dir);
     }
+
+    #[test]
+    fn labeled_snapshot_survives_reload() {
+        let dir = test_dir("labeled_snap");
+        let config = dir.join("Current Configuration.md");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(&config, "# Config v1\n").unwrap();
+
+        let mgr = HistoryManager::with_defaults(dir.clone()).unwrap();
+        let t1 = compose_timestamp(2026, 2, 22, 10, 0, 0) * 1000;
+        let (entry, created) = mgr.maybe_snapshot_labeled(t1, "before-upgrade").unwrap().unwrap();
+        assert!(created);
+        assert_eq!(entry.label.as_deref(), Some("before-upgrade"));
+
+        let listed = mgr.list().unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].label.as_deref(), Some("before-upgrade"));
+
+        // Unchanged config: the label moves onto the existing snapshot.
+        let t2 = t1 + 60_000;
+        let (again, created) = mgr.maybe_snapshot_labeled(t2, "stable").unwrap().unwrap();
+        assert!(!created);
+        assert_eq!(again.filename, entry.filename);
+        assert_eq!(mgr.list().unwrap()[0].label.as_deref(), Some("stable"));
+
+        assert!(mgr.maybe_snapshot_labeled(t2, "  ").is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn compact_compresses_only_old_entries() {
+        let dir = test_dir("compact");
+        let history_dir = dir.join("history");
+        std::fs::create_dir_all(&dir).unwrap();
+        let mgr = HistoryManager::with_defaults(dir.clone()).unwrap();
+
+        let now = compose_timestamp(2026, 2, 22, 12, 0, 0) * 1000;
+        let old = now - 48 * 3_600_000;
+        let recent = now - 3_600_000;
+        snapshot::create_snapshot(&history_dir, "# Old\nagent: pilot\n", old).unwrap();
+        snapshot::create_snapshot(&history_dir, "# Recent\n", recent).unwrap();
+
+        assert_eq!(mgr.compact(now).unwrap(), 1);
+        assert_eq!(mgr.compact(now).unwrap(), 0);
+
+        let entries = mgr.list().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert!(!snapshot::is_compressed(&entries[0].path));
+        assert!(snapshot::is_compressed(&entries[1].path));
+        assert_eq!(entries[1].size_bytes, "# Old\nagent: pilot\n".len() as u64);
+        assert_eq!(mgr.read(&entries[1]).unwrap(), "# Old\nagent: pilot\n");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    /// Names and contents of every file under `dir`, for no-write checks.
+    fn tree_snapshot(dir: &std::path::Path) -> Vec<(PathBuf, Vec<u8>)> {
+        let mut files = Vec::new();
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                files.extend(tree_snapshot(&path));
+            } else {
+                files.push((path.clone(), std::fs::read(&path).unwrap()));
+            }
+        }
+        files.sort();
+        files
+    }
+
+    #[test]
+    fn restore_preview_writes_nothing() {
+        let dir = test_dir("restore_preview");
+        let history_dir = dir.join("history");
+        let config = dir.join("Current Configuration.md");
+        std::fs::create_dir_all(&dir).unwrap();
+        let mgr = HistoryManager::with_defaults(dir.clone()).unwrap();
+
+        let ts = compose_timestamp(2026, 2, 22, 10, 0, 0) * 1000;
+        let entry = snapshot::create_snapshot(&history_dir, "agent: pilot\nagent: w1\n", ts).unwrap();
+        std::fs::write(&config, "agent: pilot\nagent: w2\n").unwrap();
+
+        let before = tree_snapshot(&dir);
+        let diff = mgr.restore_preview(&entry).unwrap();
+        assert_eq!(tree_snapshot(&dir), before);
+
+        assert_eq!(diff.from.filename, "Current Configuration.md");
+        assert_eq!(diff.added_lines, vec!["agent: w1".to_string()]);
+        assert_eq!(diff.removed_lines, vec!["agent: w2".to_string()]);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn restore_preview_missing_config_adds_everything() {
+        let dir = test_dir("restore_preview_missing");
+        let history_dir = dir.join("history");
+        std::fs::create_dir_all(&dir).unwrap();
+        let mgr = HistoryManager::with_defaults(dir.clone()).unwrap();
+
+        let ts = compose_timestamp(2026, 2, 22, 10, 0, 0) * 1000;
+        let entry = snapshot::create_snapshot(&history_dir, "line a\nline b\n", ts).unwrap();
+
+        let diff = mgr.restore_preview(&entry).unwrap();
+        assert_eq!(diff.added_lines, vec!["line a".to_string(), "line b".to_string()]);
+        assert!(diff.removed_lines.is_empty());
+        assert!(!dir.join("Current Configuration.md").exists());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn restore_preview_missing_snapshot_is_entry_not_found() {
+        let dir = test_dir("restore_preview_gone");
+        let history_dir = dir.join("history");
+        std::fs::create_dir_all(&dir).unwrap();
+        let mgr = HistoryManager::with_defaults(dir.clone()).unwrap();
+
+        let ts = compose_timestamp(2026, 2, 22, 10, 0, 0) * 1000;
+        let entry = snapshot::create_snapshot(&history_dir, "a\n", ts).unwrap();
+        std::fs::remove_file(&entry.path).unwrap();
+
+        assert!(matches!(mgr.restore_preview(&entry), Err(HistoryError::EntryNotFound(_))));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn search_matches_only_snapshots_with_term() {
+        let dir = test_dir("search");
+        let history_dir = dir.join("history");
+        std::fs::create_dir_all(&dir).unwrap();
+        let mgr = HistoryManager::with_defaults(dir.clone()).unwrap();
+
+        let t1 = compose_timestamp(2026, 2, 20, 10, 0, 0) * 1000;
+        let t2 = compose_timestamp(2026, 2, 21, 10, 0, 0) * 1000;
+        let t3 = compose_timestamp(2026, 2, 22, 10, 0, 0) * 1000;
+        snapshot::create_snapshot(&history_dir, "agent: pilot\n", t1).unwrap();
+        snapshot::create_snapshot(&history_dir, "agent: pilot\nagent: Worker7\n", t2).unwrap();
+        let e3 = snapshot::create_snapshot(&history_dir, "# Pool\nagent: worker7\n", t3).unwrap();
+        snapshot::compress_snapshot(&e3).unwrap();
+
+        let hits = mgr.search("worker7", false).unwrap().hits;
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].0.timestamp_ms, t3);
+        assert_eq!(hits[0].1, vec![(2, "agent: worker7".to_string())]);
+
+        let hits = mgr.search("WORKER7", true).unwrap().hits;
+        let stamps: Vec<u64> = hits.iter().map(|(e, _)| e.timestamp_ms).collect();
+        assert_eq!(stamps, vec![t3, t2]);
+
+        let none = mgr.search("nobody", true).unwrap();
+        assert!(none.hits.is_empty());
+        assert!(none.skipped.is_empty());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn search_skips_unreadable_entries() {
+        let dir = test_dir("search_skip");
+        let history_dir = dir.join("history");
+        std::fs::create_dir_all(&dir).unwrap();
+        let mgr = HistoryManager::with_defaults(dir.clone()).unwrap();
+
+        let t1 = compose_timestamp(2026, 2, 20, 10, 0, 0) * 1000;
+        let t2 = compose_timestamp(2026, 2, 21, 10, 0, 0) * 1000;
+        snapshot::create_snapshot(&history_dir, "agent: worker7\n", t1).unwrap();
+        let bad = snapshot::create_snapshot(&history_dir, "", t2).unwrap();
+        std::fs::write(&bad.path, [0xff, 0xfe, b'w', b'\n']).unwrap();
+
+        let found = mgr.search("worker7", false).unwrap();
+        assert_eq!(found.hits.len(), 1);
+        assert_eq!(found.hits[0].0.timestamp_ms, t1);
+        assert_eq!(found.skipped.len(), 1);
+        assert_eq!(found.skipped[0].0.filename, bad.filename);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
 }
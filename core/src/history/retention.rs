@@ -2,7 +2,8 @@
 //!
 //! Implements a tiered retention strategy: hourly resolution for recent
 //! snapshots, daily for the medium term, and weekly for long-term history.
-//! An optional hard cap limits total snapshot count.
+//! An optional hard cap limits total snapshot count. Pinned snapshots are
+//! always kept and count toward the cap.
 
 use serde::{Deserialize, Serialize};
 
@@ -76,8 +77,11 @@ fn weekly_slot(ms: u64) -> u64 {
 /// Determine which entries to keep and which to delete based on the policy.
 ///
 /// `entries` must be sorted newest-first. `now_ms` is the current timestamp.
-/// Returns the list of entries to delete.
-pub fn entries_to_prune(
+/// Returns the entries that would be deleted; nothing is removed, so this
+/// doubles as a dry run of `prune_entries`. Pinned and labeled entries are
+/// never returned and do not occupy time slots; they do count toward
+/// `max_total`, so the cap leaves room for that many fewer other entries.
+pub fn plan_prune(
     entries: &[HistoryEntry],
     now_ms: u64,
     policy: &RetentionPolicy,
@@ -95,6 +99,9 @@ pub fn entries_to_prune(
 
     // Walk newest to oldest (entries are already sorted newest-first).
     for (i, entry) in entries.iter().enumerate() {
+        if is_protected(entry) {
+            continue;
+        }
         let ts = entry.timestamp_ms;
 
         if ts >= hourly_cutoff {
@@ -122,21 +129,28 @@ pub fn entries_to_prune(
         // If !weekly_beyond, entries beyond the daily window are not kept.
     }
 
-    // Apply max_total cap: keep the newest `max_total` entries from keep_indices.
+    // Apply max_total cap: protected entries take their share first, then
+    // the newest other entries from keep_indices fill the rest.
     if let Some(max) = policy.max_total {
-        keep_indices.truncate(max);
+        let protected = entries.iter().filter(|e| is_protected(e)).count();
+        keep_indices.truncate(max.saturating_sub(protected));
     }
 
-    // Build the delete list: everything not in keep_indices.
+    // Build the delete list: everything unprotected not in keep_indices.
     let keep_set: std::collections::HashSet<usize> = keep_indices.into_iter().collect();
     entries
         .iter()
         .enumerate()
-        .filter(|(i, _)| !keep_set.contains(i))
+        .filter(|(i, e)| !is_protected(e) && !keep_set.contains(i))
         .map(|(_, e)| e.clone())
         .collect()
 }
 
+/// Pinned and labeled snapshots are never pruned.
+fn is_protected(entry: &HistoryEntry) -> bool {
+    entry.pinned || entry.label.is_some()
+}
+
 /// Execute pruning: delete files for entries that should be removed.
 ///
 /// Returns the number of entries deleted.
@@ -145,14 +159,25 @@ pub fn prune_entries(
     now_ms: u64,
     policy: &RetentionPolicy,
 ) -> Result<usize, HistoryError> {
-    let to_delete = entries_to_prune(entries, now_ms, policy);
+    apply_prune(&plan_prune(entries, now_ms, policy))
+}
+
+/// Delete the files of a prune plan from `plan_prune`.
+///
+/// Entries whose file is already gone are skipped. Returns the number of
+/// files deleted.
+pub fn apply_prune(plan: &[HistoryEntry]) -> Result<usize, HistoryError> {
     let mut deleted = 0;
 
-    for entry in &to_delete {
+    for entry in plan {
         if entry.path.exists() {
             fs::remove_file(&entry.path)?;
             deleted += 1;
         }
+        let meta = super::snapshot::meta_path(&entry.path);
+        if meta.exists() {
+            fs::remove_file(&meta)?;
+        }
     }
 
     Ok(deleted)
@@ -176,6 +201,8 @@ mod tests {
             filename: filename.clone(),
             path: PathBuf::from(format!("/tmp/history/{}", filename)),
             size_bytes: 100,
+            pinned: false,
+            label: None,
         }
     }
 
@@ -226,7 +253,7 @@ mod tests {
 
         let entries = vec![e1.clone(), e2.clone(), e3.clone()]; // newest first
         let policy = RetentionPolicy::default();
-        let to_delete = entries_to_prune(&entries, now, &policy);
+        let to_delete = plan_prune(&entries, now, &policy);
 
         // Should keep e1 (newest in 11:xx slot) and e3 (only in 10:xx slot).
         // Should delete e2.
@@ -244,7 +271,7 @@ mod tests {
 
         let entries = vec![e1.clone(), e2.clone()];
         let policy = RetentionPolicy::default();
-        let to_delete = entries_to_prune(&entries, now, &policy);
+        let to_delete = plan_prune(&entries, now, &policy);
 
         // Same day → keep only the newest.
         assert_eq!(to_delete.len(), 1);
@@ -261,7 +288,7 @@ mod tests {
 
         let entries = vec![e1.clone(), e2.clone()];
         let policy = RetentionPolicy::default();
-        let to_delete = entries_to_prune(&entries, now, &policy);
+        let to_delete = plan_prune(&entries, now, &policy);
 
         // Same week → keep only the newest.
         assert_eq!(to_delete.len(), 1);
@@ -279,7 +306,7 @@ mod tests {
             weekly_beyond: false,
             ..Default::default()
         };
-        let to_delete = entries_to_prune(&entries, now, &policy);
+        let to_delete = plan_prune(&entries, now, &policy);
 
         assert_eq!(to_delete.len(), 1);
     }
@@ -297,7 +324,7 @@ mod tests {
             max_total: Some(3),
             ..Default::default()
         };
-        let to_delete = entries_to_prune(&entries, now, &policy);
+        let to_delete = plan_prune(&entries, now, &policy);
 
         // Should keep 3 newest, delete 2 oldest.
         assert_eq!(to_delete.len(), 2);
@@ -308,7 +335,7 @@ mod tests {
         let now = compose_timestamp(2026, 2, 22, 12, 0, 0) * 1000;
         let entries: Vec<HistoryEntry> = vec![];
         let policy = RetentionPolicy::default();
-        let to_delete = entries_to_prune(&entries, now, &policy);
+        let to_delete = plan_prune(&entries, now, &policy);
         assert!(to_delete.is_empty());
     }
 
@@ -337,7 +364,7 @@ mod tests {
         entries.sort_by(|a, b| b.timestamp_ms.cmp(&a.timestamp_ms));
 
         let policy = RetentionPolicy::default();
-        let to_delete = entries_to_prune(&entries, now, &policy);
+        let to_delete = plan_prune(&entries, now, &policy);
 
         // All 50 entries minus the kept ones should be deleted.
         // Hourly window: up to 24 entries kept (one per distinct hour).
@@ -373,6 +400,67 @@ mod tests {
         let _ = std::fs::remove_dir_all(&dir);
     }
 
+    #[test]
+    fn plan_matches_prune() {
+        let dir = std::env::temp_dir().join("cmx_hist_test_plan_matches");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let now = compose_timestamp(2026, 2, 22, 12, 0, 0) * 1000;
+        // Several per hour today, several per day last week, a few old weeks.
+        let mut stamps = Vec::new();
+        for h in 0..6 {
+            stamps.push(now - h * MS_PER_HOUR - 60_000);
+            stamps.push(now - h * MS_PER_HOUR - 120_000);
+        }
+        for d in 2..6 {
+            stamps.push(now - d * MS_PER_DAY);
+            stamps.push(now - d * MS_PER_DAY - 2 * MS_PER_HOUR);
+        }
+        for w in 3..6 {
+            stamps.push(now - w * MS_PER_WEEK);
+            stamps.push(now - w * MS_PER_WEEK - MS_PER_DAY);
+        }
+        for ts in &stamps {
+            super::super::snapshot::create_snapshot(&dir, &format!("v{}", ts), *ts).unwrap();
+        }
+        let entries = super::super::snapshot::list_entries(&dir).unwrap();
+        let policy = RetentionPolicy {
+            max_total: Some(12),
+            ..RetentionPolicy::default()
+        };
+
+        let plan = plan_prune(&entries, now, &policy);
+        assert!(!plan.is_empty());
+        // Planning alone touches nothing.
+        assert!(entries.iter().all(|e| e.path.exists()));
+
+        let deleted = prune_entries(&entries, now, &policy).unwrap();
+        assert_eq!(deleted, plan.len());
+
+        let mut remaining: Vec<String> = super::super::snapshot::list_entries(&dir)
+            .unwrap()
+            .into_iter()
+            .map(|e| e.filename)
+            .collect();
+        let mut expected: Vec<String> = entries
+            .iter()
+            .filter(|e| !plan.contains(e))
+            .map(|e| e.filename.clone())
+            .collect();
+        remaining.sort();
+        expected.sort();
+        assert_eq!(remaining, expected);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn apply_prune_skips_missing_files() {
+        let missing = make_entry(compose_timestamp(2026, 2, 22, 11, 0, 0) * 1000);
+        assert_eq!(apply_prune(&[missing]).unwrap(), 0);
+    }
+
     #[test]
     fn max_total_with_many_entries() {
         let now = compose_timestamp(2026, 2, 22, 12, 0, 0) * 1000;
@@ -386,9 +474,70 @@ mod tests {
             max_total: Some(5),
             ..Default::default()
         };
-        let to_delete = entries_to_prune(&entries, now, &policy);
+        let to_delete = plan_prune(&entries, now, &policy);
 
         let kept = entries.len() - to_delete.len();
         assert_eq!(kept, 5);
     }
+
+    #[test]
+    fn pinned_old_snapshot_survives_prune() {
+        let now = compose_timestamp(2026, 3, 1, 12, 0, 0) * 1000;
+        let week_ago = now - 10 * MS_PER_DAY;
+        // Three entries in the same old week: only one would normally survive.
+        let mut pinned = make_entry(week_ago);
+        pinned.pinned = true;
+        let entries = vec![
+            make_entry(week_ago + 2 * MS_PER_HOUR),
+            make_entry(week_ago + MS_PER_HOUR),
+            pinned.clone(),
+        ];
+        let policy = RetentionPolicy {
+            weekly_beyond: false,
+            ..RetentionPolicy::default()
+        };
+        let to_delete = plan_prune(&entries, now, &policy);
+        assert_eq!(to_delete.len(), 2);
+        assert!(to_delete.iter().all(|e| !e.pinned));
+        assert!(!to_delete.contains(&pinned));
+    }
+
+    #[test]
+    fn labeled_old_snapshot_survives_prune() {
+        let now = compose_timestamp(2026, 3, 1, 12, 0, 0) * 1000;
+        let old = now - 40 * MS_PER_DAY;
+        let mut labeled = make_entry(old);
+        labeled.label = Some("before-migration".into());
+        let entries = vec![make_entry(now), make_entry(old + MS_PER_HOUR), labeled.clone()];
+        let policy = RetentionPolicy {
+            weekly_beyond: false,
+            max_total: Some(1),
+            ..RetentionPolicy::default()
+        };
+        let to_delete = plan_prune(&entries, now, &policy);
+        assert!(!to_delete.contains(&labeled));
+        // The label takes the only slot under the cap.
+        assert_eq!(to_delete.len(), 2);
+    }
+
+    #[test]
+    fn pinned_entries_count_toward_max_total() {
+        let now = compose_timestamp(2026, 3, 1, 12, 0, 0) * 1000;
+        let mut entries: Vec<HistoryEntry> = (0..4)
+            .map(|h| make_entry(now - h * MS_PER_HOUR))
+            .collect();
+        let mut old = make_entry(now - 30 * MS_PER_DAY);
+        old.pinned = true;
+        entries.push(old);
+
+        let policy = RetentionPolicy {
+            max_total: Some(3),
+            ..RetentionPolicy::default()
+        };
+        let to_delete = plan_prune(&entries, now, &policy);
+        // 5 entries, cap 3: the pinned one plus the two newest remain.
+        assert_eq!(to_delete.len(), 2);
+        assert_eq!(to_delete[0].timestamp_ms, now - 2 * MS_PER_HOUR);
+        assert_eq!(to_delete[1].timestamp_ms, now - 3 * MS_PER_HOUR);
+    }
 }
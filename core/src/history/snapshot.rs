@@ -4,9 +4,15 @@
 //! timestamp-based filename in the `history/` directory. Content hashing
 //! prevents duplicate snapshots when the configuration hasn't changed.
 
+use std::collections::HashSet;
 use std::fs;
+use std::io::{Read, Seek, SeekFrom, Write};
 use std::path::{Path, PathBuf};
 
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+
 use serde::{Deserialize, Serialize};
 
 // ---------------------------------------------------------------------------
@@ -20,6 +26,12 @@ pub struct HistoryEntry {
     pub filename: String,
     pub path: PathBuf,
     pub size_bytes: u64,
+    /// Pinned entries are never removed by retention pruning.
+    #[serde(default)]
+    pub pinned: bool,
+    /// Operator-chosen name, stored in a `.meta` sidecar file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
 }
 
 // ---------------------------------------------------------------------------
@@ -34,6 +46,7 @@ pub enum HistoryError {
     InvalidTimestamp(String),
     ConfigNotFound(PathBuf),
     RestoreFailed(String),
+    InvalidLabel(String),
 }
 
 impl std::fmt::Display for HistoryError {
@@ -46,6 +59,7 @@ impl std::fmt::Display for HistoryError {
                 write!(f, "config not found: {}", p.display())
             }
             HistoryError::RestoreFailed(s) => write!(f, "restore failed: {}", s),
+            HistoryError::InvalidLabel(s) => write!(f, "invalid label: '{}'", s),
         }
     }
 }
@@ -75,8 +89,15 @@ pub fn timestamp_to_filename(ms: u64) -> String {
 
 /// Parse a history filename back to a millisecond timestamp.
 ///
-/// Expected format: `YYYY-MM-DDTHH-MM-SS.md`.
+/// Expected format: `YYYY-MM-DDTHH-MM-SS.md`, or `YYYY-MM-DDTHH-MM-SS-N.md`
+/// for the Nth snapshot taken within the same second.
 pub fn filename_to_timestamp(filename: &str) -> Result<u64, HistoryError> {
+    parse_filename(filename).map(|(ms, _)| ms)
+}
+
+/// Parse a history filename into its timestamp and same-second sequence
+/// number (1 when the filename has no `-N` suffix).
+fn parse_filename(filename: &str) -> Result<(u64, u64), HistoryError> {
     let stem = filename.strip_suffix(".md").ok_or_else(|| {
         HistoryError::InvalidTimestamp(format!("missing .md extension: {}", filename))
     })?;
@@ -107,12 +128,13 @@ pub fn filename_to_timestamp(filename: &str) -> Result<u64, HistoryError> {
         })
         .collect::<Result<Vec<_>, _>>()?;
 
-    if date_parts.len() != 3 || time_parts.len() != 3 {
+    if date_parts.len() != 3 || !(3..=4).contains(&time_parts.len()) {
         return Err(HistoryError::InvalidTimestamp(format!(
             "wrong number of components: {}",
             filename
         )));
     }
+    let sequence = time_parts.get(3).copied().unwrap_or(1);
 
     let year = date_parts[0];
     let month = date_parts[1];
@@ -122,7 +144,7 @@ pub fn filename_to_timestamp(filename: &str) -> Result<u64, HistoryError> {
     let second = time_parts[2];
 
     let secs = compose_timestamp(year, month, day, hour, minute, second);
-    Ok(secs * 1000)
+    Ok((secs * 1000, sequence))
 }
 
 // ---------------------------------------------------------------------------
@@ -131,7 +153,9 @@ pub fn filename_to_timestamp(filename: &str) -> Result<u64, HistoryError> {
 
 /// Create a new history snapshot file.
 ///
-/// Writes the given content to `history_dir/YYYY-MM-DDTHH-MM-SS.md`.
+/// Writes the given content to `history_dir/YYYY-MM-DDTHH-MM-SS.md`. If a
+/// snapshot already holds that second, a `-2`, `-3`, ... suffix keeps the
+/// earlier one from being overwritten.
 /// Returns the resulting `HistoryEntry`.
 pub fn create_snapshot(
     history_dir: &Path,
@@ -140,7 +164,15 @@ pub fn create_snapshot(
 ) -> Result<HistoryEntry, HistoryError> {
     fs::create_dir_all(history_dir)?;
 
-    let filename = timestamp_to_filename(now_ms);
+    let base = timestamp_to_filename(now_ms);
+    let mut filename = base.clone();
+    let mut sequence = 1;
+    while history_dir.join(&filename).exists()
+        || history_dir.join(format!("{}{}", filename, GZ_SUFFIX)).exists()
+    {
+        sequence += 1;
+        filename = format!("{}-{}.md", base.trim_end_matches(".md"), sequence);
+    }
     let path = history_dir.join(&filename);
     fs::write(&path, content)?;
 
@@ -149,10 +181,12 @@ pub fn create_snapshot(
         filename,
         path,
         size_bytes: content.len() as u64,
+        pinned: false,
+        label: None,
     })
 }
 
-/// Read the content of a history entry.
+/// Read the content of a history entry, decompressing `.md.gz` files.
 pub fn read_snapshot(entry: &HistoryEntry) -> Result<String, HistoryError> {
     if !entry.path.exists() {
         return Err(HistoryError::EntryNotFound(format!(
@@ -160,45 +194,119 @@ pub fn read_snapshot(entry: &HistoryEntry) -> Result<String, HistoryError> {
             entry.path.display()
         )));
     }
+    if is_compressed(&entry.path) {
+        let mut content = String::new();
+        GzDecoder::new(fs::File::open(&entry.path)?).read_to_string(&mut content)?;
+        return Ok(content);
+    }
     Ok(fs::read_to_string(&entry.path)?)
 }
 
+// ---------------------------------------------------------------------------
+// Compression
+// ---------------------------------------------------------------------------
+
+/// Suffix appended to a compacted snapshot, e.g. `2026-02-22T10-00-00.md.gz`.
+pub const GZ_SUFFIX: &str = ".gz";
+
+/// True if the snapshot at `path` is stored gzip-compressed.
+pub fn is_compressed(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "gz")
+}
+
+/// Replace a plain snapshot file with a gzip-compressed `.md.gz` copy.
+///
+/// The entry keeps its `.md` filename, so pins and labels still refer to
+/// it. The plain file is removed only after the compressed one is written.
+/// Returns the updated entry; already-compressed entries are returned as is.
+pub fn compress_snapshot(entry: &HistoryEntry) -> Result<HistoryEntry, HistoryError> {
+    if is_compressed(&entry.path) {
+        return Ok(entry.clone());
+    }
+    let content = fs::read(&entry.path)?;
+    let mut name = entry.path.file_name().unwrap_or_default().to_os_string();
+    name.push(GZ_SUFFIX);
+    let gz_path = entry.path.with_file_name(name);
+
+    let mut encoder = GzEncoder::new(fs::File::create(&gz_path)?, Compression::default());
+    encoder.write_all(&content)?;
+    encoder.finish()?.sync_all()?;
+    fs::remove_file(&entry.path)?;
+
+    Ok(HistoryEntry {
+        path: gz_path,
+        size_bytes: content.len() as u64,
+        ..entry.clone()
+    })
+}
+
+/// Uncompressed size of a gzip file, from its trailer (size modulo 2^32).
+fn gzip_uncompressed_size(path: &Path) -> Result<u64, HistoryError> {
+    let mut file = fs::File::open(path)?;
+    if file.metadata()?.len() < 4 {
+        return Ok(0);
+    }
+    let mut tail = [0u8; 4];
+    file.seek(SeekFrom::End(-4))?;
+    file.read_exact(&mut tail)?;
+    Ok(u32::from_le_bytes(tail) as u64)
+}
+
 /// List all history entries in a directory, sorted newest first.
 pub fn list_entries(history_dir: &Path) -> Result<Vec<HistoryEntry>, HistoryError> {
     if !history_dir.exists() {
         return Ok(Vec::new());
     }
 
+    let pins = read_pins(history_dir)?;
     let mut entries = Vec::new();
 
     for dir_entry in fs::read_dir(history_dir)? {
         let dir_entry = dir_entry?;
-        let filename = match dir_entry.file_name().into_string() {
+        let mut filename = match dir_entry.file_name().into_string() {
             Ok(s) => s,
             Err(_) => continue,
         };
 
-        if !filename.ends_with(".md") {
+        let compressed = filename.ends_with(".md.gz");
+        if compressed {
+            // Report the logical name; a plain copy left by an interrupted
+            // compaction takes precedence.
+            let plain = filename.trim_end_matches(GZ_SUFFIX).to_string();
+            if history_dir.join(&plain).exists() {
+                continue;
+            }
+            filename = plain;
+        } else if !filename.ends_with(".md") {
             continue;
         }
 
-        let timestamp_ms = match filename_to_timestamp(&filename) {
-            Ok(ts) => ts,
+        let (timestamp_ms, sequence) = match parse_filename(&filename) {
+            Ok(parsed) => parsed,
             Err(_) => continue, // skip non-conforming files
         };
 
-        let metadata = dir_entry.metadata()?;
-        entries.push(HistoryEntry {
+        let path = dir_entry.path();
+        let size_bytes = if compressed {
+            gzip_uncompressed_size(&path)?
+        } else {
+            dir_entry.metadata()?.len()
+        };
+        let pinned = pins.contains(&filename);
+        let label = read_label(&path)?;
+        entries.push((sequence, HistoryEntry {
             timestamp_ms,
             filename,
-            path: dir_entry.path(),
-            size_bytes: metadata.len(),
-        });
+            path,
+            size_bytes,
+            pinned,
+            label,
+        }));
     }
 
-    // Sort newest first.
-    entries.sort_by(|a, b| b.timestamp_ms.cmp(&a.timestamp_ms));
-    Ok(entries)
+    // Sort newest first; same-second snapshots by sequence.
+    entries.sort_by(|(sa, a), (sb, b)| (b.timestamp_ms, sb).cmp(&(a.timestamp_ms, sa)));
+    Ok(entries.into_iter().map(|(_, entry)| entry).collect())
 }
 
 /// Read the most recent history entry, if any.
@@ -207,6 +315,89 @@ pub fn latest_entry(history_dir: &Path) -> Result<Option<HistoryEntry>, HistoryE
     Ok(entries.into_iter().next())
 }
 
+// ---------------------------------------------------------------------------
+// Pins
+// ---------------------------------------------------------------------------
+
+/// File in the history directory listing pinned snapshot filenames, one per line.
+pub const PINS_FILE: &str = ".pinned";
+
+/// Read the set of pinned snapshot filenames. Missing file means none.
+pub fn read_pins(history_dir: &Path) -> Result<HashSet<String>, HistoryError> {
+    let path = history_dir.join(PINS_FILE);
+    if !path.exists() {
+        return Ok(HashSet::new());
+    }
+    let content = fs::read_to_string(&path)?;
+    Ok(content
+        .lines()
+        .map(|l| l.trim())
+        .filter(|l| !l.is_empty())
+        .map(|l| l.to_string())
+        .collect())
+}
+
+/// Pin or unpin a snapshot by filename.
+///
+/// Returns `true` if the pin state changed.
+pub fn set_pinned(history_dir: &Path, filename: &str, pinned: bool) -> Result<bool, HistoryError> {
+    let mut pins = read_pins(history_dir)?;
+    let changed = if pinned {
+        pins.insert(filename.to_string())
+    } else {
+        pins.remove(filename)
+    };
+    if changed {
+        let mut names: Vec<&String> = pins.iter().collect();
+        names.sort();
+        let mut content = String::new();
+        for name in names {
+            content.push_str(name);
+            content.push('\n');
+        }
+        fs::create_dir_all(history_dir)?;
+        fs::write(history_dir.join(PINS_FILE), content)?;
+    }
+    Ok(changed)
+}
+
+// ---------------------------------------------------------------------------
+// Labels
+// ---------------------------------------------------------------------------
+
+/// Suffix of the sidecar file holding a snapshot's metadata, e.g.
+/// `2026-02-22T10-00-00.md.meta`. It does not end in `.md`, so listings
+/// never mistake it for a snapshot.
+pub const META_SUFFIX: &str = ".meta";
+
+/// The sidecar metadata path for a snapshot file. Compressed and plain
+/// copies of a snapshot share one sidecar, named after the `.md` file.
+pub fn meta_path(snapshot_path: &Path) -> PathBuf {
+    let name = snapshot_path.file_name().unwrap_or_default().to_string_lossy();
+    let name = name.strip_suffix(GZ_SUFFIX).unwrap_or(&name);
+    snapshot_path.with_file_name(format!("{}{}", name, META_SUFFIX))
+}
+
+/// Read a snapshot's label from its sidecar file. Missing file means none.
+pub fn read_label(snapshot_path: &Path) -> Result<Option<String>, HistoryError> {
+    let path = meta_path(snapshot_path);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(&path)?;
+    Ok(content
+        .lines()
+        .find_map(|l| l.strip_prefix("label:"))
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty()))
+}
+
+/// Write a snapshot's label to its sidecar file, replacing any previous one.
+pub fn write_label(snapshot_path: &Path, label: &str) -> Result<(), HistoryError> {
+    fs::write(meta_path(snapshot_path), format!("label: {}\n", label.trim()))?;
+    Ok(())
+}
+
 // ---------------------------------------------------------------------------
 // Content hashing
 // ---------------------------------------------------------------------------
@@ -394,6 +585,26 @@ mod tests {
         let _ = fs::remove_dir_all(&dir);
     }
 
+    #[test]
+    fn same_second_snapshots_get_distinct_files() {
+        let dir = std::env::temp_dir().join("cmx_hist_test_same_second");
+        let _ = fs::remove_dir_all(&dir);
+
+        let now_ms = compose_timestamp(2026, 2, 22, 10, 0, 0) * 1000;
+        let first = create_snapshot(&dir, "v1\n", now_ms).unwrap();
+        let second = create_snapshot(&dir, "v2\n", now_ms + 400).unwrap();
+        assert_eq!(first.filename, "2026-02-22T10-00-00.md");
+        assert_eq!(second.filename, "2026-02-22T10-00-00-2.md");
+        assert_eq!(filename_to_timestamp(&second.filename).unwrap(), now_ms);
+
+        let entries = list_entries(&dir).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(read_snapshot(&entries[0]).unwrap(), "v2\n");
+        assert_eq!(read_snapshot(&entries[1]).unwrap(), "v1\n");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn list_entries_empty_dir() {
         let dir = std::env::temp_dir().join("cmx_hist_test_list_empty");
@@ -456,6 +667,50 @@ mod tests {
         let _ = fs::remove_dir_all(&dir);
     }
 
+    #[test]
+    fn label_sidecar_round_trips() {
+        let dir = std::env::temp_dir().join("cmx_hist_test_label_sidecar");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        let ts = compose_timestamp(2026, 1, 1, 0, 0, 0) * 1000;
+        let entry = create_snapshot(&dir, "valid", ts).unwrap();
+        assert_eq!(read_label(&entry.path).unwrap(), None);
+
+        write_label(&entry.path, "baseline").unwrap();
+        assert!(meta_path(&entry.path).ends_with("2026-01-01T00-00-00.md.meta"));
+
+        let entries = list_entries(&dir).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].label.as_deref(), Some("baseline"));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn compressed_snapshot_lists_and_reads_like_plain() {
+        let dir = std::env::temp_dir().join("cmx_hist_test_compress");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        let content = "# Config\nagent: pilot\n".repeat(20);
+        let ts = compose_timestamp(2026, 1, 1, 0, 0, 0) * 1000;
+        let entry = create_snapshot(&dir, &content, ts).unwrap();
+        write_label(&entry.path, "old").unwrap();
+        let packed = compress_snapshot(&entry).unwrap();
+        assert!(packed.path.ends_with("2026-01-01T00-00-00.md.gz"));
+        assert!(!entry.path.exists());
+
+        let entries = list_entries(&dir).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].filename, "2026-01-01T00-00-00.md");
+        assert_eq!(entries[0].size_bytes, content.len() as u64);
+        assert_eq!(entries[0].label.as_deref(), Some("old"));
+        assert_eq!(read_snapshot(&entries[0]).unwrap(), content);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn latest_entry_returns_newest() {
         let dir = std::env::temp_dir().join("cmx_hist_test_latest");
@@ -492,6 +747,8 @@ mod tests {
             filename: "nonexistent.md".into(),
             path: PathBuf::from("/tmp/cmx_hist_does_not_exist/nonexistent.md"),
             size_bytes: 0,
+            pinned: false,
+            label: None,
         };
         let result = read_snapshot(&entry);
         assert!(result.is_err());
@@ -526,4 +783,28 @@ mod tests {
 
         let _ = fs::remove_dir_all(&dir);
     }
+
+    #[test]
+    fn pins_persist_and_mark_entries() {
+        let dir = std::env::temp_dir().join("cmx_hist_test_pins");
+        let _ = fs::remove_dir_all(&dir);
+
+        let ts1 = compose_timestamp(2026, 3, 1, 0, 0, 0) * 1000;
+        let ts2 = compose_timestamp(2026, 3, 2, 0, 0, 0) * 1000;
+        let old = create_snapshot(&dir, "v1", ts1).unwrap();
+        create_snapshot(&dir, "v2", ts2).unwrap();
+
+        assert!(set_pinned(&dir, &old.filename, true).unwrap());
+        assert!(!set_pinned(&dir, &old.filename, true).unwrap());
+
+        let entries = list_entries(&dir).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert!(!entries[0].pinned);
+        assert!(entries[1].pinned);
+
+        assert!(set_pinned(&dir, &old.filename, false).unwrap());
+        assert!(list_entries(&dir).unwrap().iter().all(|e| !e.pinned));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
 }
@@ -3,11 +3,12 @@
 //! Records all actions and provides controllable responses, making it easy
 //! to write deterministic tests for higher-level orchestration code.
 
+use std::cell::RefCell;
 use std::collections::HashMap;
 
 use cmx_utils::response::Action;
 
-use super::SessionBackend;
+use super::{execute_each, BatchError, SessionBackend};
 
 /// A test-double that records actions and serves pre-configured pane captures.
 pub struct MockBackend {
@@ -17,6 +18,14 @@ pub struct MockBackend {
     pub sessions: Vec<String>,
     /// Pre-configured pane capture responses, keyed by target string.
     pub pane_captures: HashMap<String, String>,
+    /// Size of each `execute_batch` call, in order. Batched actions are
+    /// also recorded in `actions`.
+    pub batches: Vec<usize>,
+    /// Pre-configured scrollback (lines above the visible capture), keyed
+    /// by target string.
+    pub scrollback: HashMap<String, String>,
+    /// `(target, lines)` of every `capture_pane_lines` call with history.
+    pub history_requests: RefCell<Vec<(String, u32)>>,
 }
 
 impl MockBackend {
@@ -25,6 +34,9 @@ impl MockBackend {
             actions: Vec::new(),
             sessions: Vec::new(),
             pane_captures: HashMap::new(),
+            batches: Vec::new(),
+            scrollback: HashMap::new(),
+            history_requests: RefCell::new(Vec::new()),
         }
     }
 
@@ -34,6 +46,9 @@ impl MockBackend {
             actions: Vec::new(),
             sessions,
             pane_captures: HashMap::new(),
+            batches: Vec::new(),
+            scrollback: HashMap::new(),
+            history_requests: RefCell::new(Vec::new()),
         }
     }
 
@@ -43,9 +58,17 @@ impl MockBackend {
             .insert(target.to_string(), content.to_string());
     }
 
-    /// Clear all recorded actions.
+    /// Pre-load scrollback history for a target. `capture_pane_lines`
+    /// returns its last `lines` lines followed by the visible capture.
+    pub fn set_scrollback(&mut self, target: &str, history: &str) {
+        self.scrollback
+            .insert(target.to_string(), history.to_string());
+    }
+
+    /// Clear all recorded actions and batches.
     pub fn clear_actions(&mut self) {
         self.actions.clear();
+        self.batches.clear();
     }
 }
 
@@ -73,6 +96,11 @@ impl SessionBackend for MockBackend {
         Ok(())
     }
 
+    fn execute_batch(&mut self, actions: &[Action]) -> Result<(), BatchError> {
+        self.batches.push(actions.len());
+        execute_each(self, actions)
+    }
+
     fn session_exists(&self, name: &str) -> bool {
         self.sessions.iter().any(|s| s == name)
     }
@@ -87,6 +115,27 @@ impl SessionBackend for MockBackend {
             .cloned()
             .ok_or_else(|| format!("mock: no capture for '{}'", target))
     }
+
+    fn capture_pane_lines(&self, target: &str, lines: u32) -> Result<String, String> {
+        if lines == 0 {
+            return self.capture_pane(target);
+        }
+        self.history_requests
+            .borrow_mut()
+            .push((target.to_string(), lines));
+        let history = match self.scrollback.get(target) {
+            Some(h) => h,
+            None => return self.capture_pane(target),
+        };
+        let history: Vec<&str> = history.lines().collect();
+        let start = history.len().saturating_sub(lines as usize);
+        let mut out = history[start..].join("\n");
+        if let Some(visible) = self.pane_captures.get(target) {
+            out.push('\n');
+            out.push_str(visible);
+        }
+        Ok(out)
+    }
 }
 
 #[cfg(test)]
@@ -132,6 +181,30 @@ mod tests {
         assert_eq!(mock.capture_pane("s1:0.0").unwrap(), "$ ready");
     }
 
+    #[test]
+    fn capture_pane_lines_falls_back_to_capture() {
+        let mut mock = MockBackend::new();
+        mock.set_capture("s1:0.0", "$ ready");
+        assert_eq!(mock.capture_pane_lines("s1:0.0", 200).unwrap(), "$ ready");
+    }
+
+    #[test]
+    fn capture_pane_lines_returns_scrollback_tail() {
+        let mut mock = MockBackend::new();
+        let history: Vec<String> = (1..=500).map(|i| format!("line {}", i)).collect();
+        mock.set_scrollback("s1:0.0", &history.join("\n"));
+        mock.set_capture("s1:0.0", "$ ready");
+
+        let out = mock.capture_pane_lines("s1:0.0", 100).unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines.len(), 101);
+        assert_eq!(lines[0], "line 401");
+        assert_eq!(lines[99], "line 500");
+        assert_eq!(lines[100], "$ ready");
+        assert_eq!(mock.capture_pane("s1:0.0").unwrap(), "$ ready");
+        assert_eq!(*mock.history_requests.borrow(), vec![("s1:0.0".to_string(), 100)]);
+    }
+
     #[test]
     fn capture_pane_missing_returns_error() {
         let mock = MockBackend::new();
@@ -146,6 +219,19 @@ mod tests {
         assert!(!mock.session_exists("c"));
     }
 
+    #[test]
+    fn batch_is_recorded() {
+        let mut mock = MockBackend::new();
+        mock.execute_batch(&[
+            Action::CreateSession { name: "s1".into(), cwd: "/tmp".into() },
+            Action::SendKeys { target: "s1:0.0".into(), keys: "ls".into() },
+        ])
+        .unwrap();
+        assert_eq!(mock.batches, vec![2]);
+        assert_eq!(mock.actions.len(), 2);
+        assert!(mock.session_exists("s1"));
+    }
+
     #[test]
     fn clear_actions() {
         let mut mock = MockBackend::new();
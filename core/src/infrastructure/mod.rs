@@ -1,21 +1,65 @@
 //! Infrastructure backends for session management.
 //!
-//! Provides the `SessionBackend` trait and implementations for tmux (production)
-//! and mock (testing). The tmux backend builds command strings without executing
-//! them, keeping this crate free of process-spawning side effects.
+//! Provides the `SessionBackend` trait and implementations for tmux and
+//! zellij (production) and mock (testing). The production backends build
+//! commands without executing them, keeping this crate free of
+//! process-spawning side effects. The `session_backend` setting picks one.
 
 pub mod mock;
 pub mod runner;
+pub mod shell;
 pub mod tmux;
+pub mod zellij;
 
 use cmx_utils::response::Action;
 
+pub use crate::types::config::SessionBackendKind;
+
+/// Create the backend selected by `kind`. `session` names the zellij
+/// session that holds agent tabs; tmux addresses targets directly.
+pub fn backend_for(kind: SessionBackendKind, session: &str) -> Box<dyn SessionBackend + Send> {
+    match kind {
+        SessionBackendKind::Tmux => Box::new(tmux::TmuxBackend::new()),
+        SessionBackendKind::Zellij => Box::new(zellij::ZellijBackend::new(session)),
+    }
+}
+
+/// Where a batch stopped: the failing action's index and its error.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchError {
+    pub index: usize,
+    pub message: String,
+}
+
+/// Run `actions` one at a time through `execute_action`, stopping at the
+/// first error. The default `execute_batch`.
+pub fn execute_each<B: SessionBackend + ?Sized>(
+    backend: &mut B,
+    actions: &[Action],
+) -> Result<(), BatchError> {
+    for (index, action) in actions.iter().enumerate() {
+        backend
+            .execute_action(action)
+            .map_err(|message| BatchError { index, message })?;
+    }
+    Ok(())
+}
+
 /// Trait for session management backends. Implementations translate abstract
 /// actions into backend-specific operations.
 pub trait SessionBackend {
     /// Execute a single action against the backend.
     fn execute_action(&mut self, action: &Action) -> Result<(), String>;
 
+    /// Execute several actions in order, stopping at the first error.
+    /// Backends that can combine work into fewer round-trips override this;
+    /// the end state must match executing the actions one by one. The error
+    /// names the failing action's index: actions before it took effect,
+    /// actions after it did not run.
+    fn execute_batch(&mut self, actions: &[Action]) -> Result<(), BatchError> {
+        execute_each(self, actions)
+    }
+
     /// Check whether a session with the given name exists.
     fn session_exists(&self, name: &str) -> bool;
 
@@ -25,6 +69,14 @@ pub trait SessionBackend {
     /// Capture the current content of a pane, identified by a target string
     /// (e.g. `"session:window.pane"`).
     fn capture_pane(&self, target: &str) -> Result<String, String>;
+
+    /// Capture a pane including up to `lines` lines of scrollback history.
+    /// `lines == 0` means visible content only. Backends without history
+    /// support fall back to `capture_pane`.
+    fn capture_pane_lines(&self, target: &str, lines: u32) -> Result<String, String> {
+        let _ = lines;
+        self.capture_pane(target)
+    }
 }
 
 #[cfg(test)]
@@ -32,6 +84,56 @@ mod tests {
     use super::*;
     use super::mock::MockBackend;
 
+    /// Backend relying on the default `execute_batch`.
+    struct Recorder(Vec<Action>);
+
+    impl SessionBackend for Recorder {
+        fn execute_action(&mut self, action: &Action) -> Result<(), String> {
+            if let Action::KillSession { name } = action {
+                return Err(format!("cannot kill {}", name));
+            }
+            self.0.push(action.clone());
+            Ok(())
+        }
+        fn session_exists(&self, _name: &str) -> bool {
+            false
+        }
+        fn list_sessions(&self) -> Vec<String> {
+            Vec::new()
+        }
+        fn capture_pane(&self, target: &str) -> Result<String, String> {
+            Err(target.to_string())
+        }
+    }
+
+    #[test]
+    fn default_batch_runs_in_order_and_stops_at_error() {
+        let keys = |k: &str| Action::SendKeys { target: "w1".into(), keys: k.into() };
+        let mut backend = Recorder(Vec::new());
+        let actions = vec![keys("a"), keys("b"), Action::KillSession { name: "s".into() }, keys("c")];
+        assert_eq!(backend.execute_batch(&actions).unwrap_err().index, 2);
+        let sent: Vec<&str> = backend
+            .0
+            .iter()
+            .filter_map(|a| match a {
+                Action::SendKeys { keys, .. } => Some(keys.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(sent, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn backend_for_selects_implementation() {
+        let mut zellij = backend_for(SessionBackendKind::Zellij, "cmx");
+        zellij
+            .execute_action(&Action::CreateSession { name: "cmx".into(), cwd: "/tmp".into() })
+            .unwrap();
+        assert!(zellij.session_exists("cmx"));
+        let tmux = backend_for(SessionBackendKind::default(), "cmx");
+        assert!(tmux.list_sessions().is_empty());
+    }
+
     #[test]
     fn mock_implements_session_backend() {
         let backend = MockBackend::new();
@@ -0,0 +1,74 @@
+//! Shell quoting shared by the session backends.
+//!
+//! Backends describe each operation as a command line for the runner. The
+//! tmux backend formats those strings directly; the zellij backend builds
+//! an argument vector and joins it with `join_argv`. Both quote arguments
+//! with `shell_escape` so `ShellRunner` (`sh -c`) sees the intended words.
+
+/// Escape a string for safe use in a shell command.
+///
+/// Wraps the value in single quotes and escapes any embedded single quotes
+/// using the `'\''` idiom.
+pub fn shell_escape(s: &str) -> String {
+    if s.is_empty() {
+        return "''".to_string();
+    }
+    // If the string contains no special characters, return it bare.
+    if s.chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' || c == '/' || c == '%' || c == ':')
+    {
+        return s.to_string();
+    }
+    // Otherwise, wrap in single quotes.
+    let escaped = s.replace('\'', "'\\''");
+    format!("'{}'", escaped)
+}
+
+/// Join an argument vector into one shell command line, escaping each
+/// argument.
+pub fn join_argv(argv: &[String]) -> String {
+    argv.iter()
+        .map(|arg| shell_escape(arg))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escape_simple() {
+        assert_eq!(shell_escape("hello"), "hello");
+    }
+
+    #[test]
+    fn escape_with_space() {
+        assert_eq!(shell_escape("hello world"), "'hello world'");
+    }
+
+    #[test]
+    fn escape_with_single_quote() {
+        assert_eq!(shell_escape("it's"), "'it'\\''s'");
+    }
+
+    #[test]
+    fn escape_empty() {
+        assert_eq!(shell_escape(""), "''");
+    }
+
+    #[test]
+    fn escape_path_no_quoting() {
+        assert_eq!(shell_escape("/tmp/proj-1/src"), "/tmp/proj-1/src");
+    }
+
+    #[test]
+    fn join_argv_quotes_each_argument() {
+        let argv: Vec<String> = ["zellij", "action", "write-chars", "echo it's"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(join_argv(&argv), "zellij action write-chars 'echo it'\\''s'");
+    }
+}
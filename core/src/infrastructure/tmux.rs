@@ -9,7 +9,8 @@ use std::collections::HashMap;
 use cmx_utils::response::{Action, Direction};
 use crate::types::session::{LayoutNode, TmuxPane, TmuxWindow};
 
-use super::SessionBackend;
+pub use super::shell::shell_escape;
+use super::{execute_each, BatchError, SessionBackend};
 
 // ---------------------------------------------------------------------------
 // Command builder
@@ -65,6 +66,20 @@ impl TmuxCommandBuilder {
         format!("tmux capture-pane -t {} -p", shell_escape(target))
     }
 
+    /// `tmux capture-pane -t <target> -p -S -<lines>`
+    ///
+    /// With `lines == 0` this is identical to `capture_pane` (visible only).
+    pub fn capture_pane_lines(&self, target: &str, lines: u32) -> String {
+        if lines == 0 {
+            return self.capture_pane(target);
+        }
+        format!(
+            "tmux capture-pane -t {} -p -S -{}",
+            shell_escape(target),
+            lines
+        )
+    }
+
     /// `tmux resize-pane -t <target> [-L|-R|-U|-D] <amount>`
     pub fn resize_pane(&self, target: &str, direction: &Direction, amount: u32) -> String {
         let flag = match direction {
@@ -135,6 +150,39 @@ impl Default for TmuxCommandBuilder {
     }
 }
 
+/// Separator between commands chained into one tmux invocation. The
+/// backslash keeps the shell from treating `;` as its own separator.
+pub const TMUX_COMMAND_SEPARATOR: &str = " \\; ";
+
+/// Merge consecutive `tmux ...` command strings into single invocations
+/// joined with `TMUX_COMMAND_SEPARATOR`, preserving order.
+///
+/// A command is only chained if it contains no `;` at all: tmux splits its
+/// arguments on a trailing semicolon, so keys like `make;` sent through
+/// send-keys would otherwise be cut short. Such commands, and anything not
+/// starting with `tmux `, stay standalone and end the current chain.
+pub fn chain_commands(commands: Vec<String>) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut chain: Option<String> = None;
+    for cmd in commands {
+        match cmd.strip_prefix("tmux ") {
+            Some(args) if !args.contains(';') => match chain.as_mut() {
+                Some(current) => {
+                    current.push_str(TMUX_COMMAND_SEPARATOR);
+                    current.push_str(args);
+                }
+                None => chain = Some(cmd),
+            },
+            _ => {
+                out.extend(chain.take());
+                out.push(cmd);
+            }
+        }
+    }
+    out.extend(chain);
+    out
+}
+
 // ---------------------------------------------------------------------------
 // Output parsers
 // ---------------------------------------------------------------------------
@@ -270,29 +318,6 @@ fn realize_node(
     }
 }
 
-// ---------------------------------------------------------------------------
-// Shell escaping
-// ---------------------------------------------------------------------------
-
-/// Escape a string for safe use in a shell command.
-///
-/// Wraps the value in single quotes and escapes any embedded single quotes
-/// using the `'\''` idiom.
-pub fn shell_escape(s: &str) -> String {
-    if s.is_empty() {
-        return "''".to_string();
-    }
-    // If the string contains no special characters, return it bare.
-    if s.chars()
-        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' || c == '/' || c == '%' || c == ':')
-    {
-        return s.to_string();
-    }
-    // Otherwise, wrap in single quotes.
-    let escaped = s.replace('\'', "'\\''");
-    format!("'{}'", escaped)
-}
-
 // ---------------------------------------------------------------------------
 // TmuxBackend (SessionBackend implementation)
 // ---------------------------------------------------------------------------
@@ -388,6 +413,17 @@ impl SessionBackend for TmuxBackend {
         Ok(())
     }
 
+    /// Generates the same commands as executing each action in turn, then
+    /// chains them with `chain_commands` so the batch costs one tmux
+    /// invocation wherever that is safe.
+    fn execute_batch(&mut self, actions: &[Action]) -> Result<(), BatchError> {
+        let start = self.commands.len();
+        let result = execute_each(self, actions);
+        let generated = self.commands.split_off(start);
+        self.commands.extend(chain_commands(generated));
+        result
+    }
+
     fn session_exists(&self, name: &str) -> bool {
         self.sessions.iter().any(|s| s == name)
     }
@@ -464,6 +500,19 @@ mod tests {
         assert_eq!(cmd, "tmux capture-pane -t work:0.1 -p");
     }
 
+    #[test]
+    fn cmd_capture_pane_lines_default_is_visible_only() {
+        let b = TmuxCommandBuilder::new();
+        assert_eq!(b.capture_pane_lines("work:0.1", 0), b.capture_pane("work:0.1"));
+    }
+
+    #[test]
+    fn cmd_capture_pane_lines_with_history() {
+        let b = TmuxCommandBuilder::new();
+        let cmd = b.capture_pane_lines("work:0.1", 500);
+        assert_eq!(cmd, "tmux capture-pane -t work:0.1 -p -S -500");
+    }
+
     #[test]
     fn cmd_resize_pane() {
         let b = TmuxCommandBuilder::new();
@@ -590,33 +639,6 @@ mod tests {
         assert!(parse_list_sessions("").is_empty());
     }
 
-    // -- Shell escape tests --
-
-    #[test]
-    fn escape_simple() {
-        assert_eq!(shell_escape("hello"), "hello");
-    }
-
-    #[test]
-    fn escape_with_space() {
-        assert_eq!(shell_escape("hello world"), "'hello world'");
-    }
-
-    #[test]
-    fn escape_with_single_quote() {
-        assert_eq!(shell_escape("it's"), "'it'\\''s'");
-    }
-
-    #[test]
-    fn escape_empty() {
-        assert_eq!(shell_escape(""), "''");
-    }
-
-    #[test]
-    fn escape_path_no_quoting() {
-        assert_eq!(shell_escape("/tmp/proj-1/src"), "/tmp/proj-1/src");
-    }
-
     // -- Layout realization tests --
 
     #[test]
@@ -788,4 +810,61 @@ mod tests {
         backend.execute_action(&action).unwrap();
         assert_eq!(backend.list_sessions().len(), 1);
     }
+
+    // -- Batch tests --
+
+    fn assignment_batch() -> Vec<Action> {
+        vec![
+            Action::CreateSession { name: "work".into(), cwd: "/tmp".into() },
+            Action::UpdateAssignment { agent: "w1".into(), task: Some("T1".into()) },
+            Action::SendKeys { target: "work:0.1".into(), keys: "read the brief".into() },
+            Action::SendKeys { target: "work:0.1".into(), keys: "start T1".into() },
+        ]
+    }
+
+    #[test]
+    fn batch_uses_single_invocation() {
+        let mut backend = TmuxBackend::new();
+        backend.execute_batch(&assignment_batch()).unwrap();
+        assert_eq!(backend.commands.len(), 1);
+        assert!(backend.commands[0].starts_with("tmux new-session"));
+        assert_eq!(backend.commands[0].matches("tmux ").count(), 1);
+        assert_eq!(backend.commands[0].matches(TMUX_COMMAND_SEPARATOR).count(), 2);
+    }
+
+    #[test]
+    fn batch_matches_sequential_end_state() {
+        let mut sequential = TmuxBackend::new();
+        for action in assignment_batch() {
+            sequential.execute_action(&action).unwrap();
+        }
+        let mut batched = TmuxBackend::new();
+        batched.execute_batch(&assignment_batch()).unwrap();
+
+        assert_eq!(batched.list_sessions(), sequential.list_sessions());
+        let expected: Vec<String> = sequential
+            .commands
+            .iter()
+            .map(|c| c.trim_start_matches("tmux ").to_string())
+            .collect();
+        let chained: Vec<String> = batched.commands[0]
+            .trim_start_matches("tmux ")
+            .split(TMUX_COMMAND_SEPARATOR)
+            .map(String::from)
+            .collect();
+        assert_eq!(chained, expected);
+    }
+
+    #[test]
+    fn batch_keeps_semicolon_commands_standalone() {
+        let mut backend = TmuxBackend::new();
+        let keys = |k: &str| Action::SendKeys { target: "w1".into(), keys: k.into() };
+        backend
+            .execute_batch(&[keys("cd src"), keys("make;"), keys("ls"), keys("pwd")])
+            .unwrap();
+        assert_eq!(backend.commands.len(), 3);
+        assert_eq!(backend.commands[0], "tmux send-keys -t w1 'cd src' Enter");
+        assert_eq!(backend.commands[1], "tmux send-keys -t w1 'make;' Enter");
+        assert_eq!(backend.commands[2], "tmux send-keys -t w1 ls Enter \\; send-keys -t w1 pwd Enter");
+    }
 }
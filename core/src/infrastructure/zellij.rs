@@ -0,0 +1,367 @@
+//! Zellij command builder.
+//!
+//! `ZellijBackend` implements `SessionBackend` by building zellij CLI
+//! argument vectors. Like the tmux backend it never spawns processes; the
+//! caller runs each argv (joined with `shell::join_argv` for a
+//! `CommandRunner`).
+//!
+//! Zellij cannot address a pane by name from the CLI, so every agent gets
+//! its own tab named after the agent. Actions aimed at an agent first switch
+//! to that tab, then act on the focused pane.
+
+use std::collections::HashMap;
+
+use cmx_utils::response::{Action, Direction};
+
+use super::shell::join_argv;
+use super::SessionBackend;
+
+/// Byte written for Enter after `write-chars`.
+const ENTER_BYTE: u8 = 13;
+
+/// Byte written for the tmux-style `C-c` key.
+const CTRL_C_BYTE: u8 = 3;
+
+// ---------------------------------------------------------------------------
+// Command builder
+// ---------------------------------------------------------------------------
+
+/// Builds zellij argument vectors for one session without executing them.
+pub struct ZellijCommandBuilder {
+    session: String,
+}
+
+impl ZellijCommandBuilder {
+    pub fn new(session: &str) -> Self {
+        ZellijCommandBuilder {
+            session: session.to_string(),
+        }
+    }
+
+    /// `zellij --session <session> action <args...>`
+    fn action(&self, args: &[&str]) -> Vec<String> {
+        let mut argv = argv(&["zellij", "--session", &self.session, "action"]);
+        argv.extend(args.iter().map(|a| a.to_string()));
+        argv
+    }
+
+    /// `zellij attach --create-background <name> options --default-cwd <cwd>`
+    pub fn new_session(&self, name: &str, cwd: &str) -> Vec<String> {
+        argv(&["zellij", "attach", "--create-background", name, "options", "--default-cwd", cwd])
+    }
+
+    /// `zellij kill-session <name>`
+    pub fn kill_session(&self, name: &str) -> Vec<String> {
+        argv(&["zellij", "kill-session", name])
+    }
+
+    /// `zellij list-sessions --short`
+    pub fn list_sessions(&self) -> Vec<String> {
+        argv(&["zellij", "list-sessions", "--short"])
+    }
+
+    /// `... action new-tab --name <name> --cwd <cwd>`
+    pub fn new_tab(&self, name: &str, cwd: &str) -> Vec<String> {
+        self.action(&["new-tab", "--name", name, "--cwd", cwd])
+    }
+
+    /// `... action go-to-tab-name <name>`
+    pub fn go_to_tab(&self, name: &str) -> Vec<String> {
+        self.action(&["go-to-tab-name", name])
+    }
+
+    /// `... action close-tab` (closes the focused tab)
+    pub fn close_tab(&self) -> Vec<String> {
+        self.action(&["close-tab"])
+    }
+
+    /// `... action new-pane --direction <right|down>`
+    pub fn split_pane(&self, direction: &Direction) -> Vec<String> {
+        let dir = match direction {
+            Direction::Horizontal => "right",
+            Direction::Vertical => "down",
+        };
+        self.action(&["new-pane", "--direction", dir])
+    }
+
+    /// Type `keys` into the tab named `target` and press Enter. The tmux
+    /// key name `C-c` is sent as the interrupt byte instead.
+    pub fn send_keys(&self, target: &str, keys: &str) -> Vec<Vec<String>> {
+        let mut commands = vec![self.go_to_tab(target)];
+        if keys == "C-c" {
+            commands.push(self.action(&["write", &CTRL_C_BYTE.to_string()]));
+        } else {
+            commands.push(self.action(&["write-chars", keys]));
+            commands.push(self.action(&["write", &ENTER_BYTE.to_string()]));
+        }
+        commands
+    }
+
+    /// Dump the screen of the tab named `target` to stdout. `lines > 0`
+    /// requests the full scrollback; zellij cannot limit it to a count.
+    pub fn capture_pane_lines(&self, target: &str, lines: u32) -> Vec<Vec<String>> {
+        let dump = if lines == 0 {
+            self.action(&["dump-screen", "/dev/stdout"])
+        } else {
+            self.action(&["dump-screen", "--full", "/dev/stdout"])
+        };
+        vec![self.go_to_tab(target), dump]
+    }
+}
+
+fn argv(args: &[&str]) -> Vec<String> {
+    args.iter().map(|a| a.to_string()).collect()
+}
+
+/// Parse the output of `zellij list-sessions --short` into session names.
+pub fn parse_list_sessions(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty())
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// ZellijBackend (SessionBackend implementation)
+// ---------------------------------------------------------------------------
+
+/// A `SessionBackend` that records the zellij argvs it would execute and
+/// tracks logical session state, mirroring `TmuxBackend`.
+pub struct ZellijBackend {
+    builder: ZellijCommandBuilder,
+    /// Argument vectors generated by `execute_action`, in order.
+    pub commands: Vec<Vec<String>>,
+    /// Logical set of sessions (names) tracked by the backend.
+    sessions: Vec<String>,
+    /// Simulated screen dumps, keyed by tab (agent) name.
+    pane_captures: HashMap<String, String>,
+}
+
+impl ZellijBackend {
+    /// A backend whose agent tabs live in `session`.
+    pub fn new(session: &str) -> Self {
+        ZellijBackend {
+            builder: ZellijCommandBuilder::new(session),
+            commands: Vec::new(),
+            sessions: Vec::new(),
+            pane_captures: HashMap::new(),
+        }
+    }
+
+    /// Pre-populate known sessions (e.g. after parsing `list-sessions` output).
+    pub fn set_sessions(&mut self, sessions: Vec<String>) {
+        self.sessions = sessions;
+    }
+
+    /// Pre-populate a screen dump (e.g. after running `dump-screen`).
+    pub fn set_pane_capture(&mut self, target: &str, content: &str) {
+        self.pane_captures.insert(target.to_string(), content.to_string());
+    }
+
+    /// Return all generated argvs and clear the buffer.
+    pub fn drain_commands(&mut self) -> Vec<Vec<String>> {
+        std::mem::take(&mut self.commands)
+    }
+
+    /// Generated commands as shell command lines for a `CommandRunner`.
+    pub fn command_lines(&self) -> Vec<String> {
+        self.commands.iter().map(|argv| join_argv(argv)).collect()
+    }
+}
+
+impl SessionBackend for ZellijBackend {
+    fn execute_action(&mut self, action: &Action) -> Result<(), String> {
+        match action {
+            Action::CreateSession { name, cwd } => {
+                self.commands.push(self.builder.new_session(name, cwd));
+                if !self.sessions.contains(name) {
+                    self.sessions.push(name.clone());
+                }
+            }
+            Action::KillSession { name } => {
+                self.commands.push(self.builder.kill_session(name));
+                self.sessions.retain(|s| s != name);
+            }
+            Action::SplitPane {
+                session,
+                direction,
+                percent,
+            } => {
+                // Zellij splits in half; the percentage has no CLI equivalent.
+                let _ = percent;
+                let builder = ZellijCommandBuilder::new(session);
+                self.commands.push(builder.split_pane(direction));
+            }
+            Action::SendKeys { target, keys } => {
+                self.commands.extend(self.builder.send_keys(target, keys));
+            }
+            Action::CreateAgent { name, role, path } => {
+                let _ = role;
+                self.commands.push(self.builder.new_tab(name, path));
+            }
+            Action::KillAgent { name } => {
+                self.commands.push(self.builder.go_to_tab(name));
+                self.commands.push(self.builder.close_tab());
+            }
+            Action::PlaceAgent { pane_id, agent } => {
+                let _ = (pane_id, agent);
+            }
+            Action::ConnectSsh { agent, host, port } => {
+                let ssh_cmd = format!("ssh -p {} {}", port, host);
+                self.commands.extend(self.builder.send_keys(agent, &ssh_cmd));
+            }
+            Action::UpdateAssignment { agent, task } => {
+                let _ = (agent, task);
+            }
+        }
+        Ok(())
+    }
+
+    fn session_exists(&self, name: &str) -> bool {
+        self.sessions.iter().any(|s| s == name)
+    }
+
+    fn list_sessions(&self) -> Vec<String> {
+        self.sessions.clone()
+    }
+
+    fn capture_pane(&self, target: &str) -> Result<String, String> {
+        self.pane_captures
+            .get(target)
+            .cloned()
+            .ok_or_else(|| format!("no capture for target '{}'", target))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(line: &str) -> Vec<String> {
+        line.split(' ').map(String::from).collect()
+    }
+
+    #[test]
+    fn create_session_argv() {
+        let mut backend = ZellijBackend::new("cmx");
+        backend
+            .execute_action(&Action::CreateSession { name: "cmx".into(), cwd: "/tmp/proj".into() })
+            .unwrap();
+        assert_eq!(
+            backend.commands,
+            vec![words("zellij attach --create-background cmx options --default-cwd /tmp/proj")]
+        );
+        assert!(backend.session_exists("cmx"));
+    }
+
+    #[test]
+    fn kill_session_argv() {
+        let mut backend = ZellijBackend::new("cmx");
+        backend.set_sessions(vec!["cmx".into()]);
+        backend.execute_action(&Action::KillSession { name: "cmx".into() }).unwrap();
+        assert_eq!(backend.commands, vec![words("zellij kill-session cmx")]);
+        assert!(backend.list_sessions().is_empty());
+    }
+
+    #[test]
+    fn create_agent_opens_named_tab() {
+        let mut backend = ZellijBackend::new("cmx");
+        backend
+            .execute_action(&Action::CreateAgent {
+                name: "w1".into(),
+                role: "worker".into(),
+                path: "/work/w1".into(),
+            })
+            .unwrap();
+        assert_eq!(
+            backend.commands,
+            vec![words("zellij --session cmx action new-tab --name w1 --cwd /work/w1")]
+        );
+    }
+
+    #[test]
+    fn send_keys_focuses_tab_then_types() {
+        let mut backend = ZellijBackend::new("cmx");
+        backend
+            .execute_action(&Action::SendKeys { target: "w1".into(), keys: "cargo test".into() })
+            .unwrap();
+        assert_eq!(backend.commands.len(), 3);
+        assert_eq!(backend.commands[0], words("zellij --session cmx action go-to-tab-name w1"));
+        assert_eq!(
+            backend.commands[1],
+            vec!["zellij", "--session", "cmx", "action", "write-chars", "cargo test"]
+        );
+        assert_eq!(backend.commands[2], words("zellij --session cmx action write 13"));
+        assert_eq!(
+            backend.command_lines()[1],
+            "zellij --session cmx action write-chars 'cargo test'"
+        );
+    }
+
+    #[test]
+    fn send_ctrl_c_writes_interrupt_byte() {
+        let b = ZellijCommandBuilder::new("cmx");
+        let cmds = b.send_keys("w1", "C-c");
+        assert_eq!(cmds.len(), 2);
+        assert_eq!(cmds[1], words("zellij --session cmx action write 3"));
+    }
+
+    #[test]
+    fn kill_agent_closes_its_tab() {
+        let mut backend = ZellijBackend::new("cmx");
+        backend.execute_action(&Action::KillAgent { name: "w1".into() }).unwrap();
+        assert_eq!(
+            backend.commands,
+            vec![
+                words("zellij --session cmx action go-to-tab-name w1"),
+                words("zellij --session cmx action close-tab"),
+            ]
+        );
+    }
+
+    #[test]
+    fn split_pane_argv() {
+        let mut backend = ZellijBackend::new("cmx");
+        backend
+            .execute_action(&Action::SplitPane {
+                session: "other".into(),
+                direction: Direction::Vertical,
+                percent: 30,
+            })
+            .unwrap();
+        assert_eq!(
+            backend.commands,
+            vec![words("zellij --session other action new-pane --direction down")]
+        );
+    }
+
+    #[test]
+    fn capture_pane_argv() {
+        let b = ZellijCommandBuilder::new("cmx");
+        let visible = b.capture_pane_lines("w1", 0);
+        assert_eq!(visible[0], words("zellij --session cmx action go-to-tab-name w1"));
+        assert_eq!(visible[1], words("zellij --session cmx action dump-screen /dev/stdout"));
+        let full = b.capture_pane_lines("w1", 500);
+        assert_eq!(full[1], words("zellij --session cmx action dump-screen --full /dev/stdout"));
+    }
+
+    #[test]
+    fn capture_pane_serves_preset_dump() {
+        let mut backend = ZellijBackend::new("cmx");
+        backend.set_pane_capture("w1", "$ ready");
+        assert_eq!(backend.capture_pane("w1").unwrap(), "$ ready");
+        assert!(backend.capture_pane("w2").is_err());
+    }
+
+    #[test]
+    fn list_sessions_argv_and_parse() {
+        let b = ZellijCommandBuilder::new("cmx");
+        assert_eq!(b.list_sessions(), words("zellij list-sessions --short"));
+        assert_eq!(parse_list_sessions("cmx\n  other \n\n"), vec!["cmx", "other"]);
+    }
+}
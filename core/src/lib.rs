@@ -9,6 +9,7 @@ pub mod data;
 pub mod infrastructure;
 pub mod cli;
 pub mod client;
+pub mod frame;
 pub mod agent;
 pub mod monitor;
 pub mod rules;
@@ -22,6 +22,14 @@ pub enum LibraryError {
     IoError(std::io::Error),
     /// Configuration/settings error.
     SettingsError(String),
+    /// Orchestrations reference each other in a loop. Holds the path,
+    /// starting and ending at the same skill.
+    DependencyCycle(Vec<String>),
+    /// A skill's frontmatter `name` differs from the name its file gives it.
+    NameMismatch {
+        skill: String,
+        declared: String,
+    },
 }
 
 impl fmt::Display for LibraryError {
@@ -40,6 +48,12 @@ impl fmt::Display for LibraryError {
             LibraryError::SettingsError(msg) => {
                 write!(f, "settings error: {}", msg)
             }
+            LibraryError::DependencyCycle(path) => {
+                write!(f, "skill dependency cycle: {}", path.join(" -> "))
+            }
+            LibraryError::NameMismatch { skill, declared } => {
+                write!(f, "frontmatter name '{}' does not match skill name '{}'", declared, skill)
+            }
         }
     }
 }
@@ -0,0 +1,181 @@
+//! Git-backed library sources.
+//!
+//! A git source is a local checkout of a shared skills repository. The
+//! library never runs git itself: `plan_sync` describes the clone or update
+//! as argument vectors, the caller executes them, and a `Library::reload`
+//! then scans the refreshed checkout like any other folder.
+
+use std::path::{Path, PathBuf};
+
+use super::source::{LibrarySource, SourceKind};
+
+/// Where a git source comes from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitRemote {
+    pub url: String,
+    /// Branch or tag to track.
+    pub git_ref: String,
+}
+
+impl GitRemote {
+    /// Reject a url or ref that is empty or starts with `-`, which git
+    /// would read as an option (e.g. `--upload-pack=...`).
+    pub fn validate(&self) -> Result<(), String> {
+        for (what, value) in [("url", &self.url), ("ref", &self.git_ref)] {
+            if value.trim().is_empty() {
+                return Err(format!("git {} is empty", what));
+            }
+            if value.starts_with('-') {
+                return Err(format!("git {} '{}' must not start with '-'", what, value));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// What a sync step does to its checkout.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GitSyncAction {
+    /// No checkout yet: clone the repository at the ref.
+    Clone,
+    /// Checkout exists: fetch the ref and move onto it.
+    Update,
+}
+
+/// The git invocations that bring one source's checkout up to date.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitSyncStep {
+    pub url: String,
+    pub git_ref: String,
+    pub checkout: PathBuf,
+    pub action: GitSyncAction,
+    /// Each entry is a full argv, starting with `git`.
+    pub commands: Vec<Vec<String>>,
+}
+
+/// Sync steps for every git source in a library, in source order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GitSyncPlan {
+    pub steps: Vec<GitSyncStep>,
+}
+
+impl GitSyncPlan {
+    /// True if the library has no git sources.
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// All commands across steps, in the order they must run.
+    pub fn commands(&self) -> impl Iterator<Item = &Vec<String>> {
+        self.steps.iter().flat_map(|s| s.commands.iter())
+    }
+}
+
+/// Plan the sync for `source`, or `None` if it is not a git source.
+/// Whether to clone or update depends on whether the checkout already has
+/// a `.git` directory.
+pub fn plan_sync(source: &LibrarySource) -> Option<GitSyncStep> {
+    let SourceKind::Git { url, git_ref } = &source.kind else {
+        return None;
+    };
+    let checkout = source.path.clone();
+    let (action, commands) = if checkout.join(".git").exists() {
+        (GitSyncAction::Update, update_commands(&checkout, git_ref))
+    } else {
+        (GitSyncAction::Clone, vec![clone_command(url, git_ref, &checkout)])
+    };
+    Some(GitSyncStep {
+        url: url.clone(),
+        git_ref: git_ref.clone(),
+        checkout,
+        action,
+        commands,
+    })
+}
+
+fn clone_command(url: &str, git_ref: &str, checkout: &Path) -> Vec<String> {
+    argv(&["git", "clone", "--branch", git_ref, "--", url, &checkout.to_string_lossy()])
+}
+
+/// Fetch the ref and check it out detached, so a moved branch or tag is
+/// followed without merging and local edits are never overwritten.
+fn update_commands(checkout: &Path, git_ref: &str) -> Vec<Vec<String>> {
+    let dir = checkout.to_string_lossy();
+    vec![
+        argv(&["git", "-C", &dir, "fetch", "origin", "--", git_ref]),
+        argv(&["git", "-C", &dir, "checkout", "--detach", "FETCH_HEAD"]),
+    ]
+}
+
+fn argv(parts: &[&str]) -> Vec<String> {
+    parts.iter().map(|p| p.to_string()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::library::source::LibraryType;
+    use std::fs;
+
+    fn git_source(path: PathBuf, git_ref: &str) -> LibrarySource {
+        LibrarySource {
+            kind: SourceKind::Git {
+                url: "https://example.com/team/skills.git".into(),
+                git_ref: git_ref.into(),
+            },
+            library_type: LibraryType::Git,
+            path,
+            priority: 30,
+        }
+    }
+
+    #[test]
+    fn plan_clones_missing_checkout() {
+        let dir = std::env::temp_dir().join(format!("cmx_git_plan_clone_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+
+        let step = plan_sync(&git_source(dir.clone(), "main")).unwrap();
+        assert_eq!(step.action, GitSyncAction::Clone);
+        assert_eq!(step.commands, vec![vec![
+            "git".to_string(), "clone".into(), "--branch".into(), "main".into(), "--".into(),
+            "https://example.com/team/skills.git".into(), dir.to_string_lossy().into_owned(),
+        ]]);
+    }
+
+    #[test]
+    fn plan_updates_existing_checkout_to_ref() {
+        let dir = std::env::temp_dir().join(format!("cmx_git_plan_update_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join(".git")).unwrap();
+
+        let step = plan_sync(&git_source(dir.clone(), "v2.1")).unwrap();
+        assert_eq!(step.action, GitSyncAction::Update);
+        let d = dir.to_string_lossy().into_owned();
+        assert_eq!(step.commands, vec![
+            argv(&["git", "-C", &d, "fetch", "origin", "--", "v2.1"]),
+            argv(&["git", "-C", &d, "checkout", "--detach", "FETCH_HEAD"]),
+        ]);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn remote_rejects_option_like_values() {
+        let remote = |url: &str, git_ref: &str| GitRemote { url: url.into(), git_ref: git_ref.into() };
+        assert!(remote("https://example.com/skills.git", "main").validate().is_ok());
+        assert!(remote("--upload-pack=touch /tmp/x", "main").validate().is_err());
+        assert!(remote("https://example.com/skills.git", "-b").validate().is_err());
+        assert!(remote("https://example.com/skills.git", "").validate().is_err());
+    }
+
+    #[test]
+    fn non_git_sources_have_no_plan() {
+        let source = LibrarySource {
+            kind: SourceKind::User,
+            library_type: LibraryType::Full,
+            path: PathBuf::from("/tmp/none"),
+            priority: 20,
+        };
+        assert!(plan_sync(&source).is_none());
+    }
+}
@@ -1,14 +1,21 @@
 pub mod errors;
+pub mod git;
 pub mod query;
 pub mod registry;
 pub mod source;
 
 pub use errors::LibraryError;
-pub use registry::ConflictWarning;
-pub use source::{LibrarySource, LibraryType, SkillEntry, SourceKind};
-
+pub use git::{GitRemote, GitSyncAction, GitSyncPlan, GitSyncStep};
+pub use query::SkillSummary;
+pub use registry::{ConflictStrategy, ConflictWarning};
+pub use source::{
+    DuplicateSkill, LibrarySource, LibraryType, SkillEntry, SkipReason, SkippedEntry, SourceKind,
+};
+
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::time::SystemTime;
 
 use crate::skill::types::{SkillDocument, SkillKind};
 
@@ -26,8 +33,11 @@ pub struct LibraryConfig {
     pub project_dir: Option<PathBuf>,
     /// Additional registered sources from settings.
     pub extra_sources: Vec<ExtraSource>,
-    /// Manual overrides: skill name -> source kind display string.
+    /// Manual overrides: skill name -> source display string
+    /// (`registered:low`) or source folder path.
     pub overrides: HashMap<String, String>,
+    /// How to choose among sources defining the same skill.
+    pub conflict_strategy: ConflictStrategy,
 }
 
 /// An extra source to register (from settings.yaml).
@@ -38,6 +48,9 @@ pub struct ExtraSource {
     pub priority: u32,
     /// A label for this source.
     pub name: String,
+    /// Set for a git source: `path` is then the local checkout of this
+    /// remote, and `library_type` is treated as `LibraryType::Git`.
+    pub git: Option<GitRemote>,
 }
 
 // ---------------------------------------------------------------------------
@@ -48,9 +61,23 @@ pub struct ExtraSource {
 ///
 /// Skills are discovered eagerly (file paths) but parsed lazily (file
 /// contents are only read when `get_parsed()` or `list_by_kind()` is called).
+/// Parsed documents are cached per skill and reused while the file's
+/// modification time is unchanged.
 #[derive(Debug)]
 pub struct Library {
     registry: Registry,
+    parse_cache: RefCell<HashMap<String, CachedDocument>>,
+    /// Files actually read and parsed, so tests can observe cache hits.
+    #[cfg(test)]
+    parses: std::cell::Cell<usize>,
+}
+
+/// A parsed skill and the file state it was parsed from.
+#[derive(Debug, Clone)]
+struct CachedDocument {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    doc: SkillDocument,
 }
 
 impl Library {
@@ -72,27 +99,47 @@ impl Library {
 
         // Add extra registered sources
         for extra in &config.extra_sources {
+            let (kind, library_type) = match &extra.git {
+                Some(remote) => {
+                    remote.validate().map_err(|e| {
+                        LibraryError::SettingsError(format!("source '{}': {}", extra.name, e))
+                    })?;
+                    (
+                        SourceKind::Git { url: remote.url.clone(), git_ref: remote.git_ref.clone() },
+                        LibraryType::Git,
+                    )
+                }
+                None => (SourceKind::Registered(extra.name.clone()), extra.library_type.clone()),
+            };
             let source = LibrarySource {
-                kind: SourceKind::Registered(extra.name.clone()),
-                library_type: extra.library_type.clone(),
+                kind,
+                library_type,
                 path: extra.path.clone(),
                 priority: extra.priority,
             };
             registry.add_source(source);
         }
 
-        // Set overrides and resolve
+        // Set overrides and strategy, then resolve
         registry.set_overrides(config.overrides.clone());
+        registry.set_strategy(config.conflict_strategy);
         let _warnings = registry.resolve();
 
-        Ok(Library { registry })
+        Ok(Library::from_registry(registry))
     }
 
     /// Create an empty library (no default sources).
     /// Useful for testing.
     pub fn empty() -> Library {
+        Library::from_registry(Registry::new())
+    }
+
+    fn from_registry(registry: Registry) -> Library {
         Library {
-            registry: Registry::new(),
+            registry,
+            parse_cache: RefCell::new(HashMap::new()),
+            #[cfg(test)]
+            parses: std::cell::Cell::new(0),
         }
     }
 
@@ -101,9 +148,85 @@ impl Library {
         query::get(&self.registry, name)
     }
 
-    /// Get and parse a skill by name. Parses the file on demand.
+    /// Get and parse a skill by name. Parses the file on demand, then
+    /// serves the cached document until the file's mtime changes.
     pub fn get_parsed(&self, name: &str) -> Result<SkillDocument, LibraryError> {
-        query::get_parsed(&self.registry, name)
+        let entry = self
+            .registry
+            .get(name)
+            .ok_or_else(|| LibraryError::SkillNotFound(name.to_string()))?;
+        let modified = std::fs::metadata(&entry.path).and_then(|m| m.modified()).ok();
+
+        if let Some(cached) = self.parse_cache.borrow().get(name) {
+            if cached.path == entry.path && modified.is_some() && cached.modified == modified {
+                return Ok(cached.doc.clone());
+            }
+        }
+
+        let doc = query::get_parsed(&self.registry, name)?;
+        #[cfg(test)]
+        self.parses.set(self.parses.get() + 1);
+        self.parse_cache.borrow_mut().insert(
+            name.to_string(),
+            CachedDocument { path: entry.path.clone(), modified, doc: doc.clone() },
+        );
+        Ok(doc)
+    }
+
+    /// Every skill `name` depends on, directly or through other
+    /// orchestrations, ordered so each skill comes after the ones it
+    /// references. `name` itself is not included. Fails with
+    /// `SkillNotFound` for a reference that does not resolve and with
+    /// `DependencyCycle` if the references loop.
+    pub fn dependency_closure(&self, name: &str) -> Result<Vec<String>, LibraryError> {
+        let mut order = Vec::new();
+        let mut path = Vec::new();
+        self.visit_dependencies(name, &mut path, &mut order)?;
+        order.retain(|n| n != name);
+        Ok(order)
+    }
+
+    /// Depth-first walk for `dependency_closure`. `path` is the chain of
+    /// skills being expanded; meeting one of them again is a cycle.
+    fn visit_dependencies(
+        &self,
+        name: &str,
+        path: &mut Vec<String>,
+        order: &mut Vec<String>,
+    ) -> Result<(), LibraryError> {
+        if let Some(start) = path.iter().position(|n| n == name) {
+            let mut cycle = path[start..].to_vec();
+            cycle.push(name.to_string());
+            return Err(LibraryError::DependencyCycle(cycle));
+        }
+        if order.iter().any(|n| n == name) {
+            return Ok(());
+        }
+        let doc = self.get_parsed(name)?;
+        path.push(name.to_string());
+        for dep in doc.referenced_skills() {
+            self.visit_dependencies(&dep, path, order)?;
+        }
+        path.pop();
+        order.push(name.to_string());
+        Ok(())
+    }
+
+    /// Drop every cached parse. For callers that edit skill files out of
+    /// band and cannot rely on mtimes (e.g. coarse filesystem clocks).
+    pub fn clear_cache(&self) {
+        self.parse_cache.borrow_mut().clear();
+    }
+
+    /// Resolved skill names within `max_distance` edits of `name`, closest
+    /// first (at most `query::FUZZY_MATCH_LIMIT`).
+    pub fn find_fuzzy(&self, name: &str, max_distance: usize) -> Vec<(&str, usize)> {
+        query::find_fuzzy(&self.registry, name, max_distance)
+    }
+
+    /// A likely intended skill name when `name` does not resolve.
+    pub fn did_you_mean(&self, name: &str) -> Option<&str> {
+        query::did_you_mean(&self.registry, name)
     }
 
     /// List all skill names in the resolved library (sorted).
@@ -111,13 +234,39 @@ impl Library {
         query::list(&self.registry)
     }
 
+    /// Name, winning source, and priority for every resolved skill.
+    /// Cheap: reads no files, so `kind` is always `None`.
+    pub fn catalog(&self) -> Vec<SkillSummary> {
+        query::catalog(&self.registry)
+    }
+
+    /// Like `catalog`, with `kind` filled in by parsing each resolved
+    /// skill once. Skills that fail to parse keep `kind: None`.
+    pub fn catalog_with_kinds(&self) -> Vec<SkillSummary> {
+        query::catalog_with_kinds(&self.registry)
+    }
+
+    /// Check every resolved skill and return the ones that fail, by name.
+    /// Reads each file fresh, bypassing the parse cache.
+    pub fn validate(&self) -> Vec<(String, LibraryError)> {
+        query::validate(&self.registry)
+    }
+
     /// List skills filtered by kind (requires parsing each skill).
-    /// Use sparingly — this parses every resolved skill file.
+    /// Parses go through `get_parsed`, so unchanged files hit the cache.
     pub fn list_by_kind(
         &self,
         kind: SkillKind,
     ) -> Result<Vec<(String, SkillDocument)>, LibraryError> {
-        query::list_by_kind(&self.registry, kind)
+        query::list_by_kind_with(&self.registry, kind, |name| self.get_parsed(name))
+    }
+
+    /// The git commands that would clone or update every git source's
+    /// checkout. The caller runs them, then calls `reload`.
+    pub fn plan_git_sync(&self) -> GitSyncPlan {
+        GitSyncPlan {
+            steps: self.registry.sources().iter().filter_map(git::plan_sync).collect(),
+        }
     }
 
     /// List all sources in registration order.
@@ -125,15 +274,40 @@ impl Library {
         self.registry.sources()
     }
 
+    /// Directories skipped while scanning sources (symlink loops, depth
+    /// limit), for reporting as warnings.
+    pub fn skipped_entries(&self) -> &[SkippedEntry] {
+        self.registry.skipped()
+    }
+
+    /// Skill names defined by more than one file within a single source,
+    /// for reporting as warnings.
+    pub fn duplicates(&self) -> &[DuplicateSkill] {
+        self.registry.duplicates()
+    }
+
+    /// How conflicts between sources are resolved.
+    pub fn strategy(&self) -> ConflictStrategy {
+        self.registry.strategy
+    }
+
     /// List conflicts — skill names appearing in multiple sources.
     pub fn conflicts(&self) -> Vec<(&str, Vec<&SkillEntry>)> {
         self.registry.conflicts()
     }
 
-    /// Add a new source. Re-resolves conflicts after adding.
+    /// Add a new source. Re-resolves conflicts after adding. A git source
+    /// whose url or ref fails `GitRemote::validate` is rejected.
     pub fn add_source(&mut self, source: LibrarySource) -> Result<Vec<ConflictWarning>, LibraryError> {
+        if let SourceKind::Git { url, git_ref } = &source.kind {
+            let remote = GitRemote { url: url.clone(), git_ref: git_ref.clone() };
+            remote.validate().map_err(|e| {
+                LibraryError::SettingsError(format!("source {}: {}", source.path.display(), e))
+            })?;
+        }
         self.registry.add_source(source);
         let warnings = self.registry.resolve();
+        self.clear_cache();
         Ok(warnings)
     }
 
@@ -141,6 +315,7 @@ impl Library {
     pub fn reload(&mut self) -> Result<Vec<ConflictWarning>, LibraryError> {
         self.registry.rescan();
         let warnings = self.registry.resolve();
+        self.clear_cache();
         Ok(warnings)
     }
 }
@@ -230,15 +405,18 @@ Orchestrate the thing.
                     library_type: LibraryType::SkillsOnly,
                     priority: 5,
                     name: "low".into(),
+                    git: None,
                 },
                 ExtraSource {
                     path: high.clone(),
                     library_type: LibraryType::SkillsOnly,
                     priority: 50,
                     name: "high".into(),
+                    git: None,
                 },
             ],
             overrides: HashMap::new(),
+            conflict_strategy: ConflictStrategy::default(),
         };
 
         let lib = Library::new(&config).unwrap();
@@ -270,15 +448,18 @@ Orchestrate the thing.
                     library_type: LibraryType::SkillsOnly,
                     priority: 5,
                     name: "low".into(),
+                    git: None,
                 },
                 ExtraSource {
                     path: high.clone(),
                     library_type: LibraryType::SkillsOnly,
                     priority: 50,
                     name: "high".into(),
+                    git: None,
                 },
             ],
             overrides,
+            conflict_strategy: ConflictStrategy::default(),
         };
 
         let lib = Library::new(&config).unwrap();
@@ -367,6 +548,65 @@ Orchestrate the thing.
         let _ = fs::remove_dir_all(&dir);
     }
 
+    #[test]
+    fn list_by_kind_reuses_cached_parses() {
+        let dir = make_temp_dir("parse_cache");
+        fs::write(dir.join("simple.md"), simple_skill("simple")).unwrap();
+        fs::write(dir.join("orch.md"), orchestration_skill("orch")).unwrap();
+
+        let mut lib = Library::empty();
+        lib.add_source(LibrarySource {
+            kind: SourceKind::User,
+            library_type: LibraryType::SkillsOnly,
+            path: dir.clone(),
+            priority: 10,
+        }).unwrap();
+
+        assert_eq!(lib.list_by_kind(SkillKind::Simple).unwrap().len(), 1);
+        assert_eq!(lib.parses.get(), 2);
+        assert_eq!(lib.list_by_kind(SkillKind::Orchestration).unwrap().len(), 1);
+        assert_eq!(lib.parses.get(), 2, "second listing should hit the cache");
+
+        lib.clear_cache();
+        lib.list_by_kind(SkillKind::Simple).unwrap();
+        assert_eq!(lib.parses.get(), 4);
+
+        lib.reload().unwrap();
+        lib.get_parsed("simple").unwrap();
+        assert_eq!(lib.parses.get(), 5);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn parse_cache_notices_changed_mtime() {
+        let dir = make_temp_dir("parse_cache_mtime");
+        let path = dir.join("deploy.md");
+        fs::write(&path, simple_skill("deploy-v1")).unwrap();
+
+        let mut lib = Library::empty();
+        lib.add_source(LibrarySource {
+            kind: SourceKind::User,
+            library_type: LibraryType::SkillsOnly,
+            path: dir.clone(),
+            priority: 10,
+        }).unwrap();
+
+        let doc = lib.get_parsed("deploy").unwrap();
+        assert_eq!(doc.frontmatter.name.as_deref(), Some("deploy-v1"));
+
+        // Rewrite without reload, with a clearly later mtime.
+        fs::write(&path, simple_skill("deploy-v2")).unwrap();
+        let later = SystemTime::now() + std::time::Duration::from_secs(5);
+        fs::File::options().write(true).open(&path).unwrap().set_modified(later).unwrap();
+
+        let doc = lib.get_parsed("deploy").unwrap();
+        assert_eq!(doc.frontmatter.name.as_deref(), Some("deploy-v2"));
+        assert_eq!(lib.parses.get(), 2);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn add_source_dynamically() {
         let dir1 = make_temp_dir("dyn_add1");
@@ -432,8 +672,10 @@ Orchestrate the thing.
                 library_type: LibraryType::SkillsOnly,
                 priority: 99,
                 name: "missing".into(),
+                git: None,
             }],
             overrides: HashMap::new(),
+            conflict_strategy: ConflictStrategy::default(),
         };
 
         let lib = Library::new(&config).unwrap();
@@ -442,6 +684,136 @@ Orchestrate the thing.
         assert!(lib.get("anything").is_none());
     }
 
+    #[test]
+    fn git_source_scans_checkout_and_plans_sync() {
+        let checkout = make_temp_dir("git_checkout");
+        fs::create_dir_all(checkout.join(".git")).unwrap();
+        fs::write(checkout.join(".git").join("notes.md"), "not a skill").unwrap();
+        fs::create_dir_all(checkout.join("skills")).unwrap();
+        fs::write(checkout.join("skills").join("deploy.md"), simple_skill("deploy")).unwrap();
+        fs::write(checkout.join("README.md"), "# Team skills").unwrap();
+
+        let config = LibraryConfig {
+            project_dir: None,
+            extra_sources: vec![ExtraSource {
+                path: checkout.clone(),
+                library_type: LibraryType::SkillsOnly,
+                priority: 30,
+                name: "team".into(),
+                git: Some(GitRemote {
+                    url: "git@example.com:team/skills.git".into(),
+                    git_ref: "main".into(),
+                }),
+            }],
+            overrides: HashMap::new(),
+            conflict_strategy: ConflictStrategy::default(),
+        };
+        let lib = Library::new(&config).unwrap();
+
+        let entry = lib.get("deploy").unwrap();
+        assert_eq!(entry.source.to_string(), "git:git@example.com:team/skills.git@main");
+        assert!(lib.get("README").is_none());
+        assert!(lib.get("notes").is_none());
+
+        let plan = lib.plan_git_sync();
+        assert_eq!(plan.steps.len(), 1);
+        assert_eq!(plan.steps[0].action, GitSyncAction::Update);
+        assert_eq!(plan.commands().count(), 2);
+
+        let _ = fs::remove_dir_all(&checkout);
+    }
+
+    #[test]
+    fn add_source_rejects_option_like_git_url() {
+        let checkout = make_temp_dir("git_add_reject");
+        let mut lib = Library::empty();
+        let result = lib.add_source(LibrarySource {
+            kind: SourceKind::Git {
+                url: "--upload-pack=touch /tmp/x".into(),
+                git_ref: "main".into(),
+            },
+            library_type: LibraryType::Git,
+            path: checkout.clone(),
+            priority: 30,
+        });
+        assert!(matches!(result, Err(LibraryError::SettingsError(_))));
+        assert!(lib.plan_git_sync().is_empty());
+        let _ = fs::remove_dir_all(&checkout);
+    }
+
+    /// An orchestration whose nodes run the given skills.
+    fn flow_skill(name: &str, roles: &[&str]) -> String {
+        let rows: String = roles
+            .iter()
+            .enumerate()
+            .map(|(i, role)| format!("| step{} | {} | Step |\n", i, role))
+            .collect();
+        format!(
+            "---\nname: {}\ndescription: A flow\n---\n\n| Nodes | Role | Description |\n|-------|------|-------------|\n{}\nRun it.\n",
+            name, rows
+        )
+    }
+
+    fn library_from(dir: &std::path::Path) -> Library {
+        let mut lib = Library::empty();
+        lib.add_source(LibrarySource {
+            kind: SourceKind::User,
+            library_type: LibraryType::SkillsOnly,
+            path: dir.to_path_buf(),
+            priority: 10,
+        }).unwrap();
+        lib
+    }
+
+    #[test]
+    fn dependency_closure_linear_chain() {
+        let dir = make_temp_dir("deps_chain");
+        fs::write(dir.join("release.md"), flow_skill("release", &["flow:build"])).unwrap();
+        fs::write(dir.join("build.md"), flow_skill("build", &["compile"])).unwrap();
+        fs::write(dir.join("compile.md"), simple_skill("compile")).unwrap();
+
+        let lib = library_from(&dir);
+        assert_eq!(lib.dependency_closure("release").unwrap(), vec!["compile", "build"]);
+        assert!(lib.dependency_closure("compile").unwrap().is_empty());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn dependency_closure_diamond_lists_shared_skill_once() {
+        let dir = make_temp_dir("deps_diamond");
+        fs::write(dir.join("top.md"), flow_skill("top", &["left", "right"])).unwrap();
+        fs::write(dir.join("left.md"), flow_skill("left", &["base"])).unwrap();
+        fs::write(dir.join("right.md"), flow_skill("right", &["base"])).unwrap();
+        fs::write(dir.join("base.md"), simple_skill("base")).unwrap();
+
+        let lib = library_from(&dir);
+        assert_eq!(lib.dependency_closure("top").unwrap(), vec!["base", "left", "right"]);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn dependency_closure_reports_cycle_and_missing() {
+        let dir = make_temp_dir("deps_cycle");
+        fs::write(dir.join("a.md"), flow_skill("a", &["b"])).unwrap();
+        fs::write(dir.join("b.md"), flow_skill("b", &["flow:c"])).unwrap();
+        fs::write(dir.join("c.md"), flow_skill("c", &["a"])).unwrap();
+        fs::write(dir.join("lonely.md"), flow_skill("lonely", &["ghost"])).unwrap();
+
+        let lib = library_from(&dir);
+        match lib.dependency_closure("a") {
+            Err(LibraryError::DependencyCycle(path)) => assert_eq!(path, vec!["a", "b", "c", "a"]),
+            other => panic!("expected cycle, got {:?}", other),
+        }
+        assert!(matches!(
+            lib.dependency_closure("lonely"),
+            Err(LibraryError::SkillNotFound(name)) if name == "ghost"
+        ));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn get_parsed_lazy() {
         let dir = make_temp_dir("lazy_parse");
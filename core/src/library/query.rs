@@ -1,11 +1,25 @@
 use std::fs;
+use std::path::PathBuf;
 
 use crate::skill::parse::parse_skill;
-use crate::skill::types::{SkillDocument, SkillKind};
+use crate::skill::types::{SkillDocument, SkillKind, SkillParseError};
 
 use super::errors::LibraryError;
 use super::registry::Registry;
-use super::source::SkillEntry;
+use super::source::{SkillEntry, SourceKind};
+
+/// One row of the library catalog: a resolved skill and where it came from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkillSummary {
+    pub name: String,
+    /// Source of the winning entry after stacking.
+    pub source: SourceKind,
+    pub priority: u32,
+    pub path: PathBuf,
+    /// Parsed skill kind. `None` unless the catalog was enriched, or if
+    /// the file could not be read or parsed.
+    pub kind: Option<SkillKind>,
+}
 
 // ---------------------------------------------------------------------------
 // Query operations on the registry
@@ -29,21 +43,136 @@ pub fn get_parsed(registry: &Registry, name: &str) -> Result<SkillDocument, Libr
     })
 }
 
+/// Check one skill file: it must parse, must not repeat a frontmatter
+/// field, and any frontmatter `name` must match the name the file resolves
+/// under. A missing `name` is fine; the file name stands in for it.
+pub fn validate_entry(entry: &SkillEntry) -> Result<(), LibraryError> {
+    let content = fs::read_to_string(&entry.path).map_err(LibraryError::IoError)?;
+    if let Some(key) = duplicate_frontmatter_key(&content) {
+        return Err(LibraryError::ParseError {
+            skill: entry.name.clone(),
+            error: SkillParseError::InvalidFrontmatter(format!("duplicate field '{}'", key)),
+        });
+    }
+    let doc = parse_skill(&content).map_err(|e| LibraryError::ParseError {
+        skill: entry.name.clone(),
+        error: e,
+    })?;
+    match doc.frontmatter.name {
+        Some(declared) if declared != entry.name => Err(LibraryError::NameMismatch {
+            skill: entry.name.clone(),
+            declared,
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// Validate every resolved skill, collecting failures by name (sorted)
+/// instead of stopping at the first.
+pub fn validate(registry: &Registry) -> Vec<(String, LibraryError)> {
+    registry
+        .list_names()
+        .into_iter()
+        .filter_map(|name| registry.get(name))
+        .filter_map(|entry| validate_entry(entry).err().map(|e| (entry.name.clone(), e)))
+        .collect()
+}
+
+/// The first top-level key that appears twice in the frontmatter block.
+fn duplicate_frontmatter_key(content: &str) -> Option<String> {
+    let mut lines = content.trim_start().lines();
+    let delimiter = lines.next().map(str::trim).filter(|l| *l == "---" || *l == "~~~")?;
+    let mut seen = std::collections::HashSet::new();
+    for line in lines {
+        if line.trim() == delimiter {
+            break;
+        }
+        if line.starts_with(|c: char| c.is_whitespace() || c == '-' || c == '#') {
+            continue;
+        }
+        if let Some((key, _)) = line.split_once(':') {
+            let key = key.trim();
+            if !key.is_empty() && !seen.insert(key.to_string()) {
+                return Some(key.to_string());
+            }
+        }
+    }
+    None
+}
+
 /// List all resolved skill names (sorted).
 pub fn list(registry: &Registry) -> Vec<&str> {
     registry.list_names()
 }
 
+/// Most near-miss names `find_fuzzy` returns.
+pub const FUZZY_MATCH_LIMIT: usize = 5;
+
+/// Edit distance used by `did_you_mean`.
+pub const SUGGESTION_DISTANCE: usize = 2;
+
+/// Resolved skill names within `max_distance` edits of `name`, closest
+/// first and then by name, at most `FUZZY_MATCH_LIMIT` of them.
+pub fn find_fuzzy<'a>(registry: &'a Registry, name: &str, max_distance: usize) -> Vec<(&'a str, usize)> {
+    let mut matches: Vec<(&str, usize)> = registry
+        .list_names()
+        .into_iter()
+        .map(|candidate| (candidate, levenshtein(name, candidate)))
+        .filter(|(_, distance)| *distance <= max_distance)
+        .collect();
+    matches.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(b.0)));
+    matches.truncate(FUZZY_MATCH_LIMIT);
+    matches
+}
+
+/// The closest resolved skill name to a failed lookup, if any is near
+/// enough to be a likely typo. Never returns `name` itself.
+pub fn did_you_mean<'a>(registry: &'a Registry, name: &str) -> Option<&'a str> {
+    find_fuzzy(registry, name, SUGGESTION_DISTANCE)
+        .into_iter()
+        .map(|(candidate, _)| candidate)
+        .find(|candidate| *candidate != name)
+}
+
+/// Levenshtein distance between two strings, counted in characters.
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut row = vec![0; b.len() + 1];
+    for (i, ca) in a.chars().enumerate() {
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitute = prev[j] + usize::from(ca != *cb);
+            row[j + 1] = substitute.min(prev[j + 1] + 1).min(row[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut row);
+    }
+    prev[b.len()]
+}
+
 /// List skills filtered by kind. Parses every resolved skill to classify.
 /// Use sparingly.
 pub fn list_by_kind(
     registry: &Registry,
     kind: SkillKind,
 ) -> Result<Vec<(String, SkillDocument)>, LibraryError> {
+    list_by_kind_with(registry, kind, |name| get_parsed(registry, name))
+}
+
+/// Like `list_by_kind`, obtaining each skill's document from `parse` so
+/// callers can serve it from a cache.
+pub fn list_by_kind_with<F>(
+    registry: &Registry,
+    kind: SkillKind,
+    mut parse: F,
+) -> Result<Vec<(String, SkillDocument)>, LibraryError>
+where
+    F: FnMut(&str) -> Result<SkillDocument, LibraryError>,
+{
     let mut results = Vec::new();
 
     for name in registry.list_names() {
-        let doc = get_parsed(registry, name)?;
+        let doc = parse(name)?;
         if doc.kind() == kind {
             results.push((name.to_string(), doc));
         }
@@ -52,6 +181,36 @@ pub fn list_by_kind(
     Ok(results)
 }
 
+/// Summaries of every resolved skill (sorted by name), without touching disk.
+pub fn catalog(registry: &Registry) -> Vec<SkillSummary> {
+    registry
+        .list_names()
+        .into_iter()
+        .filter_map(|name| registry.get(name))
+        .map(|entry| SkillSummary {
+            name: entry.name.clone(),
+            source: entry.source.clone(),
+            priority: entry.priority,
+            path: entry.path.clone(),
+            kind: None,
+        })
+        .collect()
+}
+
+/// Like `catalog`, but reads and parses each resolved file once to fill in
+/// `kind`. Unreadable or malformed skills keep `kind: None` rather than
+/// failing the whole listing.
+pub fn catalog_with_kinds(registry: &Registry) -> Vec<SkillSummary> {
+    let mut summaries = catalog(registry);
+    for summary in &mut summaries {
+        summary.kind = fs::read_to_string(&summary.path)
+            .ok()
+            .and_then(|content| parse_skill(&content).ok())
+            .map(|doc| doc.kind());
+    }
+    summaries
+}
+
 // ---------------------------------------------------------------------------
 // Tests
 // ---------------------------------------------------------------------------
@@ -59,8 +218,7 @@ pub fn list_by_kind(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::library::source::{LibrarySource, LibraryType, SourceKind};
-    use std::path::PathBuf;
+    use crate::library::source::{LibrarySource, LibraryType};
 
     fn make_temp_dir(suffix: &str) -> PathBuf {
         let dir = std::env::temp_dir().join(format!(
@@ -185,4 +343,146 @@ Orchestrate the thing.
 
         let _ = fs::remove_dir_all(&dir);
     }
+
+    #[test]
+    fn validate_reports_each_bad_skill() {
+        let dir = make_temp_dir("validate");
+        fs::write(dir.join("test-skill.md"), simple_skill_content()).unwrap();
+        fs::write(
+            dir.join("deploy.md"),
+            "---\nname: deploy-prod\ndescription: Ship\n---\n\nGo.\n",
+        ).unwrap();
+        fs::write(
+            dir.join("review.md"),
+            "---\nname: review\ndescription: One\ndescription: Two\n---\n\nLook.\n",
+        ).unwrap();
+
+        let mut reg = Registry::new();
+        reg.add_source(LibrarySource {
+            kind: SourceKind::User,
+            library_type: LibraryType::SkillsOnly,
+            path: dir.clone(),
+            priority: 10,
+        });
+        reg.resolve();
+
+        let errors = validate(&reg);
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].0, "deploy");
+        assert!(matches!(
+            &errors[0].1,
+            LibraryError::NameMismatch { declared, .. } if declared == "deploy-prod"
+        ));
+        assert_eq!(errors[1].0, "review");
+        assert!(errors[1].1.to_string().contains("duplicate field 'description'"));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn levenshtein_counts_edits() {
+        assert_eq!(levenshtein("deploy", "deploy"), 0);
+        assert_eq!(levenshtein("deplyo", "deploy"), 2);
+        assert_eq!(levenshtein("deplo", "deploy"), 1);
+        assert_eq!(levenshtein("", "abc"), 3);
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn find_fuzzy_resolves_single_typo() {
+        let dir = make_temp_dir("fuzzy");
+        for name in ["deploy", "deploy-docs", "review", "repay"] {
+            fs::write(dir.join(format!("{}.md", name)), simple_skill_content()).unwrap();
+        }
+        let mut reg = Registry::new();
+        reg.add_source(LibrarySource {
+            kind: SourceKind::User,
+            library_type: LibraryType::SkillsOnly,
+            path: dir.clone(),
+            priority: 10,
+        });
+        reg.resolve();
+
+        assert_eq!(find_fuzzy(&reg, "deplay", 2), vec![("deploy", 1), ("repay", 2)]);
+        assert_eq!(did_you_mean(&reg, "deplyo"), Some("deploy"));
+        assert!(find_fuzzy(&reg, "quantum-flux", 2).is_empty());
+        assert_eq!(did_you_mean(&reg, "quantum-flux"), None);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn find_fuzzy_empty_registry() {
+        let reg = Registry::new();
+        assert!(find_fuzzy(&reg, "deploy", 3).is_empty());
+    }
+
+    #[test]
+    fn catalog_reflects_stacking_without_parsing() {
+        let low = make_temp_dir("catalog_low");
+        let high = make_temp_dir("catalog_high");
+        fs::write(low.join("deploy.md"), simple_skill_content()).unwrap();
+        fs::write(high.join("deploy.md"), structured_skill_content()).unwrap();
+        fs::write(low.join("notes.md"), "no frontmatter at all").unwrap();
+
+        let mut reg = Registry::new();
+        reg.add_source(LibrarySource {
+            kind: SourceKind::User,
+            library_type: LibraryType::SkillsOnly,
+            path: low.clone(),
+            priority: 10,
+        });
+        reg.add_source(LibrarySource {
+            kind: SourceKind::Registered("team".into()),
+            library_type: LibraryType::SkillsOnly,
+            path: high.clone(),
+            priority: 40,
+        });
+        reg.resolve();
+
+        // Remove the files: the plain catalog must not need them.
+        let _ = fs::remove_dir_all(&low);
+        let _ = fs::remove_dir_all(&high);
+
+        let rows = catalog(&reg);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].name, "deploy");
+        assert_eq!(rows[0].source, SourceKind::Registered("team".into()));
+        assert_eq!(rows[0].priority, 40);
+        assert_eq!(rows[0].kind, None);
+        assert_eq!(rows[1].name, "notes");
+    }
+
+    #[test]
+    fn catalog_with_kinds_parses_winner_only() {
+        let low = make_temp_dir("catalog_kind_low");
+        let high = make_temp_dir("catalog_kind_high");
+        fs::write(low.join("deploy.md"), simple_skill_content()).unwrap();
+        fs::write(high.join("deploy.md"), structured_skill_content()).unwrap();
+        fs::write(high.join("orch.md"), orchestration_skill_content()).unwrap();
+
+        let mut reg = Registry::new();
+        reg.add_source(LibrarySource {
+            kind: SourceKind::User,
+            library_type: LibraryType::SkillsOnly,
+            path: low.clone(),
+            priority: 10,
+        });
+        reg.add_source(LibrarySource {
+            kind: SourceKind::Registered("team".into()),
+            library_type: LibraryType::SkillsOnly,
+            path: high.clone(),
+            priority: 40,
+        });
+        reg.resolve();
+
+        let rows = catalog_with_kinds(&reg);
+        assert_eq!(rows[0].name, "deploy");
+        assert_eq!(rows[0].kind, Some(SkillKind::Structured));
+        assert_eq!(rows[1].name, "orch");
+        assert_eq!(rows[1].kind, Some(SkillKind::Orchestration));
+
+        let _ = fs::remove_dir_all(&low);
+        let _ = fs::remove_dir_all(&high);
+    }
 }
@@ -1,6 +1,10 @@
+use std::cmp::Ordering;
 use std::collections::HashMap;
+use std::path::Path;
 
-use super::source::{LibrarySource, SkillEntry};
+use serde::{Deserialize, Serialize};
+
+use super::source::{DuplicateSkill, LibrarySource, SkillEntry, SkippedEntry};
 
 // ---------------------------------------------------------------------------
 // Registry — ordered source management and conflict resolution
@@ -15,8 +19,58 @@ pub struct Registry {
     pub(crate) skills: HashMap<String, Vec<SkillEntry>>,
     /// After resolution: skill name -> winning entry.
     pub(crate) resolved: HashMap<String, SkillEntry>,
-    /// Manual overrides: skill name -> source kind string to prefer.
+    /// Manual overrides: skill name -> source to prefer, given as a
+    /// `kind:name` display string or a source folder path.
     pub(crate) overrides: HashMap<String, String>,
+    /// Directories skipped by the most recent scan of each source.
+    pub(crate) skipped: Vec<SkippedEntry>,
+    /// Skill names defined by several files of one source, from the most
+    /// recent scan of each source.
+    pub(crate) duplicates: Vec<DuplicateSkill>,
+    /// How to pick a winner when no override applies.
+    pub(crate) strategy: ConflictStrategy,
+}
+
+/// How `resolve` picks the winning entry among sources that define the
+/// same skill name, when no override applies.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ConflictStrategy {
+    /// Highest source priority, with rank and path tie-breaks.
+    #[default]
+    HighestPriority,
+    /// Most recently modified file; priority order breaks ties and
+    /// handles files whose mtime cannot be read.
+    NewestMtime,
+    /// The entry from the earliest-registered source.
+    FirstRegistered,
+}
+
+impl ConflictStrategy {
+    /// Parse a setting value (`highest-priority`, `newest-mtime`, or
+    /// `first-registered`, case-insensitive).
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.trim().to_lowercase().as_str() {
+            "highest-priority" => Ok(ConflictStrategy::HighestPriority),
+            "newest-mtime" => Ok(ConflictStrategy::NewestMtime),
+            "first-registered" => Ok(ConflictStrategy::FirstRegistered),
+            _ => Err(format!(
+                "unknown skill_conflict_strategy: {} (expected highest-priority, newest-mtime or first-registered)",
+                value
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for ConflictStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            ConflictStrategy::HighestPriority => "highest-priority",
+            ConflictStrategy::NewestMtime => "newest-mtime",
+            ConflictStrategy::FirstRegistered => "first-registered",
+        };
+        f.write_str(s)
+    }
 }
 
 impl Registry {
@@ -27,13 +81,18 @@ impl Registry {
             skills: HashMap::new(),
             resolved: HashMap::new(),
             overrides: HashMap::new(),
+            skipped: Vec::new(),
+            duplicates: Vec::new(),
+            strategy: ConflictStrategy::default(),
         }
     }
 
     /// Add a source and scan it for skills.
     pub fn add_source(&mut self, source: LibrarySource) {
-        let entries = source.scan();
-        for entry in entries {
+        let report = source.scan_report();
+        self.skipped.extend(report.skipped);
+        self.duplicates.extend(report.duplicates);
+        for entry in report.entries {
             self.skills
                 .entry(entry.name.clone())
                 .or_default()
@@ -47,13 +106,24 @@ impl Registry {
         self.overrides = overrides;
     }
 
+    /// Set the conflict strategy used by later `resolve` calls.
+    pub fn set_strategy(&mut self, strategy: ConflictStrategy) {
+        self.strategy = strategy;
+    }
+
     /// Resolve conflicts. Must be called after all sources are added.
     ///
     /// Resolution rules:
     /// 1. If an override exists for a skill name, pick the entry from the
-    ///    matching source kind (by display string).
-    /// 2. Otherwise, highest priority wins.
-    /// 3. Returns a list of conflict warnings for skills found in multiple sources.
+    ///    matching source — by display string (`system`, `registered:low`)
+    ///    or by source folder path. An override naming a source that does
+    ///    not provide the skill is reported in a warning and ignored.
+    /// 2. Otherwise the registry's `ConflictStrategy` decides. Under the
+    ///    default, highest priority wins.
+    /// 3. On equal priority, the higher `SourceKind::rank` wins, then the
+    ///    lexicographically smallest path — independent of add/scan order.
+    /// 4. Returns a list of conflict warnings (sorted by skill name) for
+    ///    skills found in multiple sources.
     pub fn resolve(&mut self) -> Vec<ConflictWarning> {
         let mut warnings = Vec::new();
         self.resolved.clear();
@@ -63,38 +133,62 @@ impl Registry {
                 continue;
             }
 
+            // Try override first
+            let spec = self.overrides.get(name);
+            let overridden = spec.and_then(|spec| {
+                entries
+                    .iter()
+                    .filter(|e| override_matches(spec, e))
+                    .max_by(|a, b| compare_entries(a, b))
+            });
+            // Override source not found — fall through to the strategy
+            let (winner, tie_break, strategy) = match overridden {
+                Some(entry) => (entry, None, None),
+                None => {
+                    let winner = pick_winner(self.strategy, entries);
+                    let tie_break = match self.strategy {
+                        ConflictStrategy::HighestPriority => tie_break_reason(winner, entries),
+                        _ => None,
+                    };
+                    (winner, tie_break, Some(self.strategy))
+                }
+            };
+            let unmatched_override = match (spec, overridden) {
+                (Some(spec), None) => Some(self.unmatched_override_reason(spec, name)),
+                _ => None,
+            };
+
             // Detect conflicts
-            if entries.len() > 1 {
+            if entries.len() > 1 || unmatched_override.is_some() {
                 let sources: Vec<String> = entries.iter().map(|e| e.source.to_string()).collect();
                 warnings.push(ConflictWarning {
                     skill_name: name.clone(),
                     sources,
+                    tie_break,
+                    unmatched_override,
+                    strategy,
                 });
             }
 
-            // Try override first
-            if let Some(override_source) = self.overrides.get(name) {
-                if let Some(entry) = entries
-                    .iter()
-                    .find(|e| e.source.to_string() == *override_source)
-                {
-                    self.resolved.insert(name.clone(), entry.clone());
-                    continue;
-                }
-                // Override source not found — fall through to priority
-            }
-
-            // Highest priority wins; on tie, last-added wins (stable)
-            let winner = entries
-                .iter()
-                .max_by_key(|e| e.priority)
-                .unwrap();
             self.resolved.insert(name.clone(), winner.clone());
         }
 
+        warnings.sort_by(|a, b| a.skill_name.cmp(&b.skill_name));
         warnings
     }
 
+    /// Explain why override `spec` for skill `name` selected nothing.
+    fn unmatched_override_reason(&self, spec: &str, name: &str) -> String {
+        let known = self.sources.iter().any(|src| {
+            src.kind.to_string() == spec || (is_path_spec(spec) && src.path == Path::new(spec))
+        });
+        if known {
+            format!("override '{}' does not provide skill '{}'; using priority order", spec, name)
+        } else {
+            format!("override '{}' matches no registered source; using priority order", spec)
+        }
+    }
+
     /// Get the resolved (winning) entry for a skill name.
     pub fn get(&self, name: &str) -> Option<&SkillEntry> {
         self.resolved.get(name)
@@ -124,13 +218,29 @@ impl Registry {
         &self.sources
     }
 
+    /// Directories the scans skipped (symlink loops, depth limit).
+    pub fn skipped(&self) -> &[SkippedEntry] {
+        &self.skipped
+    }
+
+    /// Skill names defined more than once within a single source. These
+    /// are separate from cross-source conflicts: shadowing between sources
+    /// is expected, a duplicate inside one source is usually a mistake.
+    pub fn duplicates(&self) -> &[DuplicateSkill] {
+        &self.duplicates
+    }
+
     /// Full scan: clear all discovered skills and re-scan all sources.
     pub fn rescan(&mut self) {
         self.skills.clear();
         self.resolved.clear();
+        self.skipped.clear();
+        self.duplicates.clear();
         for source in &self.sources {
-            let entries = source.scan();
-            for entry in entries {
+            let report = source.scan_report();
+            self.skipped.extend(report.skipped);
+            self.duplicates.extend(report.duplicates);
+            for entry in report.entries {
                 self.skills
                     .entry(entry.name.clone())
                     .or_default()
@@ -140,11 +250,100 @@ impl Registry {
     }
 }
 
+/// The winning entry under `strategy`. `entries` is non-empty and in
+/// source registration order.
+fn pick_winner(strategy: ConflictStrategy, entries: &[SkillEntry]) -> &SkillEntry {
+    match strategy {
+        ConflictStrategy::HighestPriority => {
+            entries.iter().max_by(|a, b| compare_entries(a, b)).unwrap()
+        }
+        ConflictStrategy::NewestMtime => entries
+            .iter()
+            .max_by(|a, b| modified(a).cmp(&modified(b)).then_with(|| compare_entries(a, b)))
+            .unwrap(),
+        ConflictStrategy::FirstRegistered => {
+            // A source's own duplicates arrive in directory-read order, so
+            // take the smallest path among the first source's entries.
+            let first = &entries[0].source;
+            entries
+                .iter()
+                .take_while(|e| e.source == *first)
+                .min_by(|a, b| a.path.cmp(&b.path))
+                .unwrap()
+        }
+    }
+}
+
+fn modified(entry: &SkillEntry) -> Option<std::time::SystemTime> {
+    std::fs::metadata(&entry.path).and_then(|m| m.modified()).ok()
+}
+
+/// Total order used to pick a winner: priority, then kind rank, then the
+/// smaller path (reversed so that `max_by` selects it).
+fn compare_entries(a: &SkillEntry, b: &SkillEntry) -> Ordering {
+    a.priority
+        .cmp(&b.priority)
+        .then_with(|| a.source.rank().cmp(&b.source.rank()))
+        .then_with(|| b.path.cmp(&a.path))
+}
+
+/// True if an override spec looks like a folder path rather than a
+/// `kind:name` display string.
+fn is_path_spec(spec: &str) -> bool {
+    spec.contains('/') || spec.contains(std::path::MAIN_SEPARATOR)
+}
+
+/// True if `entry` comes from the source an override spec names.
+///
+/// Display strings must match exactly. Path specs match entries that live
+/// under that folder, so two registered sources sharing a label can still
+/// be told apart.
+fn override_matches(spec: &str, entry: &SkillEntry) -> bool {
+    if is_path_spec(spec) {
+        entry.path.starts_with(Path::new(spec))
+    } else {
+        entry.source.to_string() == spec
+    }
+}
+
+/// Explain why `winner` beat entries that share its priority, if any did.
+fn tie_break_reason(winner: &SkillEntry, entries: &[SkillEntry]) -> Option<String> {
+    let tied: Vec<&SkillEntry> = entries
+        .iter()
+        .filter(|e| e.priority == winner.priority && e.path != winner.path)
+        .collect();
+    if tied.is_empty() {
+        return None;
+    }
+    if tied.iter().any(|e| e.source.rank() == winner.source.rank()) {
+        Some(format!(
+            "equal priority {} and source rank: {} sorts first by path",
+            winner.priority,
+            winner.path.display(),
+        ))
+    } else {
+        let losers: Vec<String> = tied.iter().map(|e| e.source.to_string()).collect();
+        Some(format!(
+            "equal priority {}: {} outranks {}",
+            winner.priority,
+            winner.source,
+            losers.join(", "),
+        ))
+    }
+}
+
 /// Warning about a skill name appearing in multiple sources.
 #[derive(Debug, Clone)]
 pub struct ConflictWarning {
     pub skill_name: String,
     pub sources: Vec<String>,
+    /// Set when the winner was chosen by tie-break rather than priority.
+    pub tie_break: Option<String>,
+    /// Set when an override for this skill named a source that does not
+    /// provide it.
+    pub unmatched_override: Option<String>,
+    /// The strategy that chose the winner; `None` if an override did.
+    pub strategy: Option<ConflictStrategy>,
 }
 
 // ---------------------------------------------------------------------------
@@ -201,6 +400,92 @@ mod tests {
         let _ = fs::remove_dir_all(&high_dir);
     }
 
+    /// Three sources defining `deploy` where registration order, priority,
+    /// and mtime each favor a different one.
+    fn disagreeing_registry(tag: &str) -> (Registry, Vec<PathBuf>) {
+        let now = std::time::SystemTime::now();
+        let hour = std::time::Duration::from_secs(3600);
+        let specs = [("first", 10, now - hour * 3), ("priority", 50, now - hour * 2), ("newest", 20, now)];
+        let mut reg = Registry::new();
+        let mut dirs = Vec::new();
+        for (label, priority, mtime) in specs {
+            let dir = make_temp_dir(&format!("{}_{}", tag, label));
+            let file = dir.join("deploy.md");
+            fs::write(&file, format!("# {}", label)).unwrap();
+            fs::File::options().write(true).open(&file).unwrap().set_modified(mtime).unwrap();
+            reg.add_source(LibrarySource {
+                kind: SourceKind::Registered(label.into()),
+                library_type: LibraryType::SkillsOnly,
+                path: dir.clone(),
+                priority,
+            });
+            dirs.push(dir);
+        }
+        (reg, dirs)
+    }
+
+    #[test]
+    fn conflict_strategies_pick_expected_winner() {
+        let cases = [
+            (ConflictStrategy::HighestPriority, "registered:priority"),
+            (ConflictStrategy::NewestMtime, "registered:newest"),
+            (ConflictStrategy::FirstRegistered, "registered:first"),
+        ];
+        for (strategy, expected) in cases {
+            let (mut reg, dirs) = disagreeing_registry(&strategy.to_string());
+            reg.set_strategy(strategy);
+            let warnings = reg.resolve();
+            assert_eq!(reg.get("deploy").unwrap().source.to_string(), expected, "{}", strategy);
+            assert_eq!(warnings.len(), 1);
+            assert_eq!(warnings[0].strategy, Some(strategy));
+            for dir in dirs {
+                let _ = fs::remove_dir_all(&dir);
+            }
+        }
+    }
+
+    #[test]
+    fn first_registered_picks_smallest_path_within_first_source() {
+        let (mut reg, dirs) = disagreeing_registry("first_dup");
+        let nested = dirs[0].join("b");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(nested.join("deploy.md"), "# nested").unwrap();
+        reg.rescan();
+        reg.set_strategy(ConflictStrategy::FirstRegistered);
+        reg.resolve();
+        assert_eq!(reg.get("deploy").unwrap().path, dirs[0].join("b").join("deploy.md"));
+        for dir in dirs {
+            let _ = fs::remove_dir_all(&dir);
+        }
+    }
+
+    #[test]
+    fn conflict_strategy_parses_display_names() {
+        for strategy in [
+            ConflictStrategy::HighestPriority,
+            ConflictStrategy::NewestMtime,
+            ConflictStrategy::FirstRegistered,
+        ] {
+            assert_eq!(ConflictStrategy::parse(&strategy.to_string()), Ok(strategy));
+        }
+        assert!(ConflictStrategy::parse("random").is_err());
+    }
+
+    #[test]
+    fn override_decides_without_strategy() {
+        let (mut reg, dirs) = disagreeing_registry("ovr_strategy");
+        reg.set_strategy(ConflictStrategy::NewestMtime);
+        let mut overrides = HashMap::new();
+        overrides.insert("deploy".to_string(), "registered:first".to_string());
+        reg.set_overrides(overrides);
+        let warnings = reg.resolve();
+        assert_eq!(reg.get("deploy").unwrap().source.to_string(), "registered:first");
+        assert_eq!(warnings[0].strategy, None);
+        for dir in dirs {
+            let _ = fs::remove_dir_all(&dir);
+        }
+    }
+
     #[test]
     fn override_beats_priority() {
         let low_dir = make_temp_dir("ovr_low");
@@ -236,6 +521,173 @@ mod tests {
         let _ = fs::remove_dir_all(&high_dir);
     }
 
+    #[test]
+    fn path_override_disambiguates_same_label() {
+        let dir_a = make_temp_dir("ovr_path_a");
+        let dir_b = make_temp_dir("ovr_path_b");
+        fs::write(dir_a.join("deploy.md"), "# A").unwrap();
+        fs::write(dir_b.join("deploy.md"), "# B").unwrap();
+
+        let mut reg = Registry::new();
+        for (path, priority) in [(&dir_a, 50), (&dir_b, 10)] {
+            reg.add_source(LibrarySource {
+                kind: SourceKind::Registered("shared".into()),
+                library_type: LibraryType::SkillsOnly,
+                path: path.clone(),
+                priority,
+            });
+        }
+
+        let mut overrides = HashMap::new();
+        overrides.insert("deploy".to_string(), dir_b.to_string_lossy().into_owned());
+        reg.set_overrides(overrides);
+
+        let warnings = reg.resolve();
+        assert_eq!(reg.get("deploy").unwrap().path, dir_b.join("deploy.md"));
+        assert!(warnings[0].unmatched_override.is_none());
+
+        let _ = fs::remove_dir_all(&dir_a);
+        let _ = fs::remove_dir_all(&dir_b);
+    }
+
+    #[test]
+    fn override_to_source_lacking_skill_warns() {
+        let sys_dir = make_temp_dir("ovr_lack_sys");
+        let user_dir = make_temp_dir("ovr_lack_user");
+        fs::write(sys_dir.join("other.md"), "# Other").unwrap();
+        fs::write(user_dir.join("deploy.md"), "# Deploy").unwrap();
+
+        let mut reg = Registry::new();
+        reg.add_source(LibrarySource {
+            kind: SourceKind::System,
+            library_type: LibraryType::SkillsOnly,
+            path: sys_dir.clone(),
+            priority: 0,
+        });
+        reg.add_source(LibrarySource {
+            kind: SourceKind::User,
+            library_type: LibraryType::SkillsOnly,
+            path: user_dir.clone(),
+            priority: 20,
+        });
+
+        let mut overrides = HashMap::new();
+        overrides.insert("deploy".to_string(), "system".to_string());
+        reg.set_overrides(overrides);
+
+        let warnings = reg.resolve();
+        // Falls back to the only source that has it.
+        assert_eq!(reg.get("deploy").unwrap().source, SourceKind::User);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].skill_name, "deploy");
+        let reason = warnings[0].unmatched_override.as_deref().unwrap();
+        assert!(reason.contains("does not provide skill 'deploy'"));
+
+        let _ = fs::remove_dir_all(&sys_dir);
+        let _ = fs::remove_dir_all(&user_dir);
+    }
+
+    #[test]
+    fn override_to_unknown_source_warns() {
+        let dir = make_temp_dir("ovr_unknown");
+        fs::write(dir.join("deploy.md"), "# Deploy").unwrap();
+
+        let mut reg = Registry::new();
+        reg.add_source(LibrarySource {
+            kind: SourceKind::User,
+            library_type: LibraryType::SkillsOnly,
+            path: dir.clone(),
+            priority: 10,
+        });
+        let mut overrides = HashMap::new();
+        overrides.insert("deploy".to_string(), "registered:gone".to_string());
+        reg.set_overrides(overrides);
+
+        let warnings = reg.resolve();
+        assert!(warnings[0].unmatched_override.as_deref().unwrap().contains("matches no registered source"));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn scan_skips_are_collected_and_reset_on_rescan() {
+        let tmp = make_temp_dir("reg_skipped");
+        fs::write(tmp.join("deploy.md"), "# Deploy").unwrap();
+        std::os::unix::fs::symlink(&tmp, tmp.join("loop")).unwrap();
+
+        let mut reg = Registry::new();
+        reg.add_source(LibrarySource {
+            kind: SourceKind::User,
+            library_type: LibraryType::SkillsOnly,
+            path: tmp.clone(),
+            priority: 20,
+        });
+        reg.resolve();
+        assert!(reg.get("deploy").is_some());
+        assert_eq!(reg.skipped().len(), 1);
+
+        reg.rescan();
+        assert_eq!(reg.skipped().len(), 1);
+
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn intra_source_duplicate_is_reported() {
+        let tmp = make_temp_dir("reg_dup");
+        fs::write(tmp.join("deploy.md"), "# Deploy").unwrap();
+        fs::create_dir_all(tmp.join("deploy")).unwrap();
+        fs::write(tmp.join("deploy").join("SKILL.md"), "# Deploy again").unwrap();
+        fs::write(tmp.join("review.md"), "# Review").unwrap();
+
+        let mut reg = Registry::new();
+        reg.add_source(LibrarySource {
+            kind: SourceKind::User,
+            library_type: LibraryType::SkillsOnly,
+            path: tmp.clone(),
+            priority: 20,
+        });
+        reg.resolve();
+
+        assert_eq!(reg.duplicates().len(), 1);
+        let dup = &reg.duplicates()[0];
+        assert_eq!(dup.name, "deploy");
+        assert_eq!(dup.source, SourceKind::User);
+        assert_eq!(dup.paths, vec![tmp.join("deploy").join("SKILL.md"), tmp.join("deploy.md")]);
+        assert!(dup.to_string().contains("within source user"));
+        // Still resolves to exactly one entry.
+        assert!(reg.get("deploy").is_some());
+
+        reg.rescan();
+        assert_eq!(reg.duplicates().len(), 1);
+
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn same_name_across_sources_is_not_a_duplicate() {
+        let a = make_temp_dir("reg_dup_a");
+        let b = make_temp_dir("reg_dup_b");
+        fs::write(a.join("deploy.md"), "# A").unwrap();
+        fs::write(b.join("deploy.md"), "# B").unwrap();
+
+        let mut reg = Registry::new();
+        for (path, kind) in [(&a, SourceKind::User), (&b, SourceKind::System)] {
+            reg.add_source(LibrarySource {
+                kind,
+                library_type: LibraryType::SkillsOnly,
+                path: path.clone(),
+                priority: 20,
+            });
+        }
+        assert_eq!(reg.resolve().len(), 1);
+        assert!(reg.duplicates().is_empty());
+
+        let _ = fs::remove_dir_all(&a);
+        let _ = fs::remove_dir_all(&b);
+    }
+
     #[test]
     fn list_names_sorted() {
         let dir = make_temp_dir("list");
@@ -338,4 +790,101 @@ mod tests {
         let _ = fs::remove_dir_all(&dir1);
         let _ = fs::remove_dir_all(&dir2);
     }
+
+    fn equal_priority_sources(first: &PathBuf, second: &PathBuf) -> Registry {
+        let mut reg = Registry::new();
+        for (kind, path) in [
+            (SourceKind::Registered("a".into()), first),
+            (SourceKind::Registered("b".into()), second),
+        ] {
+            reg.add_source(LibrarySource {
+                kind,
+                library_type: LibraryType::SkillsOnly,
+                path: path.clone(),
+                priority: 30,
+            });
+        }
+        reg
+    }
+
+    #[test]
+    fn equal_priority_winner_independent_of_add_order() {
+        let dir_a = make_temp_dir("tie_a");
+        let dir_b = make_temp_dir("tie_b");
+        fs::write(dir_a.join("deploy.md"), "# A").unwrap();
+        fs::write(dir_b.join("deploy.md"), "# B").unwrap();
+
+        let mut forward = equal_priority_sources(&dir_a, &dir_b);
+        let warnings = forward.resolve();
+        let mut backward = equal_priority_sources(&dir_b, &dir_a);
+        backward.resolve();
+
+        let expected = std::cmp::min(dir_a.join("deploy.md"), dir_b.join("deploy.md"));
+        assert_eq!(forward.get("deploy").unwrap().path, expected);
+        assert_eq!(backward.get("deploy").unwrap().path, expected);
+        assert!(warnings[0].tie_break.as_deref().unwrap().contains("sorts first by path"));
+
+        let _ = fs::remove_dir_all(&dir_a);
+        let _ = fs::remove_dir_all(&dir_b);
+    }
+
+    #[test]
+    fn equal_priority_prefers_higher_kind_rank() {
+        let sys_dir = make_temp_dir("rank_sys");
+        let user_dir = make_temp_dir("rank_user");
+        fs::write(sys_dir.join("deploy.md"), "# Sys").unwrap();
+        fs::write(user_dir.join("deploy.md"), "# User").unwrap();
+
+        let mut reg = Registry::new();
+        reg.add_source(LibrarySource {
+            kind: SourceKind::User,
+            library_type: LibraryType::SkillsOnly,
+            path: user_dir.clone(),
+            priority: 10,
+        });
+        reg.add_source(LibrarySource {
+            kind: SourceKind::System,
+            library_type: LibraryType::SkillsOnly,
+            path: sys_dir.clone(),
+            priority: 10,
+        });
+
+        let warnings = reg.resolve();
+        assert_eq!(reg.get("deploy").unwrap().source, SourceKind::User);
+        assert_eq!(
+            warnings[0].tie_break.as_deref(),
+            Some("equal priority 10: user outranks system")
+        );
+
+        let _ = fs::remove_dir_all(&sys_dir);
+        let _ = fs::remove_dir_all(&user_dir);
+    }
+
+    #[test]
+    fn priority_win_has_no_tie_break() {
+        let low_dir = make_temp_dir("notie_low");
+        let high_dir = make_temp_dir("notie_high");
+        fs::write(low_dir.join("deploy.md"), "# Low").unwrap();
+        fs::write(high_dir.join("deploy.md"), "# High").unwrap();
+
+        let mut reg = Registry::new();
+        reg.add_source(LibrarySource {
+            kind: SourceKind::System,
+            library_type: LibraryType::SkillsOnly,
+            path: low_dir.clone(),
+            priority: 0,
+        });
+        reg.add_source(LibrarySource {
+            kind: SourceKind::User,
+            library_type: LibraryType::SkillsOnly,
+            path: high_dir.clone(),
+            priority: 20,
+        });
+
+        let warnings = reg.resolve();
+        assert!(warnings[0].tie_break.is_none());
+
+        let _ = fs::remove_dir_all(&low_dir);
+        let _ = fs::remove_dir_all(&high_dir);
+    }
 }
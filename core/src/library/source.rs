@@ -1,7 +1,12 @@
+use std::collections::{BTreeMap, HashSet};
 use std::fmt;
 use std::fs;
 use std::path::{Path, PathBuf};
 
+/// Deepest directory level a recursive scan descends to below the folder
+/// it starts from.
+pub const MAX_SCAN_DEPTH: usize = 16;
+
 // ---------------------------------------------------------------------------
 // Source classification
 // ---------------------------------------------------------------------------
@@ -19,6 +24,8 @@ pub enum SourceKind {
     Project(String),
     /// User-registered additional folder.
     Registered(String),
+    /// Local checkout of a git repository, tracking `git_ref`.
+    Git { url: String, git_ref: String },
 }
 
 impl fmt::Display for SourceKind {
@@ -29,6 +36,22 @@ impl fmt::Display for SourceKind {
             SourceKind::User => write!(f, "user"),
             SourceKind::Project(name) => write!(f, "project:{}", name),
             SourceKind::Registered(name) => write!(f, "registered:{}", name),
+            SourceKind::Git { url, git_ref } => write!(f, "git:{}@{}", url, git_ref),
+        }
+    }
+}
+
+impl SourceKind {
+    /// Tie-break rank when two sources share a priority. More specific
+    /// kinds rank higher: system < anthropic-default < user < registered
+    /// (and git) < project.
+    pub fn rank(&self) -> u8 {
+        match self {
+            SourceKind::System => 0,
+            SourceKind::AnthropicDefault => 1,
+            SourceKind::User => 2,
+            SourceKind::Registered(_) | SourceKind::Git { .. } => 3,
+            SourceKind::Project(_) => 4,
         }
     }
 }
@@ -49,6 +72,9 @@ pub enum LibraryType {
     /// Anthropic standard layout: subdirectories each containing a SKILL.md.
     /// Skill name is the directory name.
     AnthropicStandard,
+    /// A git checkout. Scans its skills/ subfolder if present, otherwise
+    /// the whole checkout; the `.git` directory is never scanned.
+    Git,
 }
 
 // ---------------------------------------------------------------------------
@@ -77,6 +103,72 @@ pub struct SkillEntry {
     pub priority: u32,
 }
 
+/// Why a scan skipped a directory instead of descending into it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SkipReason {
+    /// The directory resolves (through a symlink) to one already scanned.
+    SymlinkCycle,
+    /// The directory lies deeper than the scan's depth limit.
+    TooDeep { max_depth: usize },
+}
+
+impl fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SkipReason::SymlinkCycle => write!(f, "symlink loop to an already scanned directory"),
+            SkipReason::TooDeep { max_depth } => {
+                write!(f, "deeper than the scan depth limit ({})", max_depth)
+            }
+        }
+    }
+}
+
+/// A directory the scan skipped, reported as a warning.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkippedEntry {
+    pub path: PathBuf,
+    pub reason: SkipReason,
+}
+
+impl fmt::Display for SkippedEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "skipped {}: {}", self.path.display(), self.reason)
+    }
+}
+
+/// Two or more files in one source that define the same skill name, e.g.
+/// `deploy.md` next to `deploy/SKILL.md`. Usually a mistake: only one of
+/// them can win resolution.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateSkill {
+    pub name: String,
+    pub source: SourceKind,
+    /// Every file naming the skill, sorted.
+    pub paths: Vec<PathBuf>,
+}
+
+impl fmt::Display for DuplicateSkill {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let paths: Vec<String> = self.paths.iter().map(|p| p.display().to_string()).collect();
+        write!(
+            f,
+            "duplicate skill '{}' within source {}: {}",
+            self.name,
+            self.source,
+            paths.join(", ")
+        )
+    }
+}
+
+/// Everything one scan found: skill entries, skipped directories, and
+/// skill names defined more than once.
+#[derive(Debug, Clone, Default)]
+pub struct ScanReport {
+    pub entries: Vec<SkillEntry>,
+    pub skipped: Vec<SkippedEntry>,
+    pub duplicates: Vec<DuplicateSkill>,
+}
+
 // ---------------------------------------------------------------------------
 // Folder scanning
 // ---------------------------------------------------------------------------
@@ -85,33 +177,85 @@ impl LibrarySource {
     /// Scan this source for skill files and return discovered entries.
     /// Returns an empty vec if the path doesn't exist.
     pub fn scan(&self) -> Vec<SkillEntry> {
+        self.scan_report().entries
+    }
+
+    /// Scan this source, also reporting directories that were skipped
+    /// because they loop back through a symlink or exceed `MAX_SCAN_DEPTH`,
+    /// and skill names that more than one file defines.
+    pub fn scan_report(&self) -> ScanReport {
+        self.scan_report_with_depth(MAX_SCAN_DEPTH)
+    }
+
+    /// `scan_report` with an explicit depth limit.
+    pub fn scan_report_with_depth(&self, max_depth: usize) -> ScanReport {
+        let mut report = ScanReport::default();
         if !self.path.exists() {
-            return Vec::new();
+            return report;
         }
 
         match self.library_type {
-            LibraryType::SkillsOnly => self.scan_skills_only(&self.path),
+            LibraryType::SkillsOnly => self.scan_skills_only(&self.path, max_depth, &mut report),
             LibraryType::Full => {
                 let skills_dir = self.path.join("skills");
                 if skills_dir.is_dir() {
-                    self.scan_skills_only(&skills_dir)
-                } else {
-                    Vec::new()
+                    self.scan_skills_only(&skills_dir, max_depth, &mut report);
                 }
             }
-            LibraryType::AnthropicStandard => self.scan_anthropic_standard(),
+            LibraryType::AnthropicStandard => report.entries = self.scan_anthropic_standard(),
+            LibraryType::Git => {
+                let skills_dir = self.path.join("skills");
+                let root = if skills_dir.is_dir() { skills_dir } else { self.path.clone() };
+                self.scan_skills_only(&root, max_depth, &mut report);
+            }
+        }
+        report.duplicates = self.find_duplicates(&report.entries);
+        report
+    }
+
+    /// Group `entries` by name and report every name with several files,
+    /// sorted by name.
+    fn find_duplicates(&self, entries: &[SkillEntry]) -> Vec<DuplicateSkill> {
+        let mut by_name: BTreeMap<&str, Vec<PathBuf>> = BTreeMap::new();
+        for entry in entries {
+            by_name.entry(&entry.name).or_default().push(entry.path.clone());
         }
+        by_name
+            .into_iter()
+            .filter(|(_, paths)| paths.len() > 1)
+            .map(|(name, mut paths)| {
+                paths.sort();
+                DuplicateSkill {
+                    name: name.to_string(),
+                    source: self.kind.clone(),
+                    paths,
+                }
+            })
+            .collect()
     }
 
     /// Recursively scan a directory for *.md files.
-    /// Skill name = filename without extension.
-    fn scan_skills_only(&self, dir: &Path) -> Vec<SkillEntry> {
-        let mut entries = Vec::new();
-        self.scan_dir_recursive(dir, &mut entries);
-        entries
+    /// Skill name = filename without extension, except that a `SKILL.md`
+    /// takes its directory's name, as in the Anthropic layout.
+    fn scan_skills_only(&self, dir: &Path, max_depth: usize, report: &mut ScanReport) {
+        let mut visited = HashSet::new();
+        if let Ok(canonical) = fs::canonicalize(dir) {
+            visited.insert(canonical);
+        }
+        self.scan_dir_recursive(dir, 0, max_depth, &mut visited, report);
     }
 
-    fn scan_dir_recursive(&self, dir: &Path, entries: &mut Vec<SkillEntry>) {
+    /// Scan `dir` (at `depth` below the scan root). Subdirectories are
+    /// canonicalized before descending so a symlink back into the tree is
+    /// visited once rather than followed forever.
+    fn scan_dir_recursive(
+        &self,
+        dir: &Path,
+        depth: usize,
+        max_depth: usize,
+        visited: &mut HashSet<PathBuf>,
+        report: &mut ScanReport,
+    ) {
         let read_dir = match fs::read_dir(dir) {
             Ok(rd) => rd,
             Err(_) => return,
@@ -119,11 +263,29 @@ impl LibrarySource {
         for entry in read_dir.flatten() {
             let path = entry.path();
             if path.is_dir() {
-                self.scan_dir_recursive(&path, entries);
+                if entry.file_name() == ".git" {
+                    continue;
+                }
+                if depth + 1 > max_depth {
+                    report.skipped.push(SkippedEntry {
+                        path,
+                        reason: SkipReason::TooDeep { max_depth },
+                    });
+                    continue;
+                }
+                let canonical = fs::canonicalize(&path).unwrap_or_else(|_| path.clone());
+                if !visited.insert(canonical) {
+                    report.skipped.push(SkippedEntry {
+                        path,
+                        reason: SkipReason::SymlinkCycle,
+                    });
+                    continue;
+                }
+                self.scan_dir_recursive(&path, depth + 1, max_depth, visited, report);
             } else if path.extension().and_then(|e| e.to_str()) == Some("md") {
-                if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
-                    entries.push(SkillEntry {
-                        name: stem.to_string(),
+                if let Some(name) = skill_file_name(&path) {
+                    report.entries.push(SkillEntry {
+                        name,
                         path,
                         source: self.kind.clone(),
                         priority: self.priority,
@@ -161,6 +323,16 @@ impl LibrarySource {
     }
 }
 
+/// Skill name for a markdown file found by a recursive scan.
+fn skill_file_name(path: &Path) -> Option<String> {
+    if path.file_name().and_then(|n| n.to_str()) == Some("SKILL.md") {
+        if let Some(dir) = path.parent().and_then(|d| d.file_name()).and_then(|n| n.to_str()) {
+            return Some(dir.to_string());
+        }
+    }
+    path.file_stem().and_then(|s| s.to_str()).map(|s| s.to_string())
+}
+
 // ---------------------------------------------------------------------------
 // Default source construction helpers
 // ---------------------------------------------------------------------------
@@ -308,6 +480,26 @@ mod tests {
         let _ = fs::remove_dir_all(&tmp);
     }
 
+    #[test]
+    fn skill_md_in_skills_only_takes_directory_name() {
+        let tmp = make_temp_dir("skill_md_name");
+        fs::create_dir_all(tmp.join("deploy")).unwrap();
+        fs::write(tmp.join("deploy").join("SKILL.md"), "# Deploy").unwrap();
+
+        let source = LibrarySource {
+            kind: SourceKind::User,
+            library_type: LibraryType::SkillsOnly,
+            path: tmp.clone(),
+            priority: 20,
+        };
+        let report = source.scan_report();
+        assert_eq!(report.entries.len(), 1);
+        assert_eq!(report.entries[0].name, "deploy");
+        assert!(report.duplicates.is_empty());
+
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
     #[test]
     fn scan_anthropic_standard() {
         let tmp = make_temp_dir("anthropic");
@@ -370,4 +562,63 @@ mod tests {
 
         let _ = fs::remove_dir_all(&tmp);
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlink_loop_is_skipped_and_reported() {
+        let tmp = make_temp_dir("symlink_loop");
+        let sub = tmp.join("sub");
+        fs::create_dir_all(&sub).unwrap();
+        fs::write(tmp.join("top.md"), "# Top").unwrap();
+        fs::write(sub.join("nested.md"), "# Nested").unwrap();
+        // sub/back -> tmp: following it would recurse forever.
+        std::os::unix::fs::symlink(&tmp, sub.join("back")).unwrap();
+
+        let source = LibrarySource {
+            kind: SourceKind::User,
+            library_type: LibraryType::SkillsOnly,
+            path: tmp.clone(),
+            priority: 20,
+        };
+
+        let report = source.scan_report();
+        assert_eq!(report.entries.len(), 2);
+        assert_eq!(report.skipped.len(), 1);
+        assert_eq!(report.skipped[0].path, sub.join("back"));
+        assert_eq!(report.skipped[0].reason, SkipReason::SymlinkCycle);
+
+        let _ = fs::remove_dir_all(&tmp);
+    }
+
+    #[test]
+    fn deep_tree_stops_at_depth_limit() {
+        let tmp = make_temp_dir("deep_tree");
+        let mut dir = tmp.clone();
+        for level in 1..=MAX_SCAN_DEPTH + 3 {
+            dir = dir.join(format!("d{}", level));
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join(format!("skill-{}.md", level)), "# Skill").unwrap();
+        }
+
+        let source = LibrarySource {
+            kind: SourceKind::User,
+            library_type: LibraryType::SkillsOnly,
+            path: tmp.clone(),
+            priority: 20,
+        };
+
+        let report = source.scan_report();
+        assert_eq!(report.entries.len(), MAX_SCAN_DEPTH);
+        assert_eq!(report.skipped.len(), 1);
+        assert_eq!(
+            report.skipped[0].reason,
+            SkipReason::TooDeep { max_depth: MAX_SCAN_DEPTH }
+        );
+        assert!(report.skipped[0].to_string().contains("depth limit"));
+
+        let shallow = source.scan_report_with_depth(2);
+        assert_eq!(shallow.entries.len(), 2);
+
+        let _ = fs::remove_dir_all(&tmp);
+    }
 }
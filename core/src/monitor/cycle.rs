@@ -3,7 +3,8 @@
 //! and trigger evaluation.
 //!
 //! `MonitorCycle` orchestrates one pass of the monitoring loop for all agents:
-//! 1. Capture pane output for each agent via `SessionBackend::capture_pane()`
+//! 1. Capture the visible pane for each agent, adding scrollback via
+//!    `SessionBackend::capture_pane_lines()` only when no heartbeat is visible
 //! 2. Parse heartbeat from each capture via `heartbeat::parse_capture()`
 //! 3. Track output changes (detect stalls by comparing consecutive captures)
 //! 4. Build health signals and assess health per agent
@@ -16,10 +17,13 @@ use std::collections::HashMap;
 use crate::data::messages::MessageStore;
 use crate::infrastructure::SessionBackend;
 use crate::monitor::health;
-use crate::monitor::heartbeat::{self, AgentState as HeartbeatAgentState};
+use crate::monitor::heartbeat::{self, AgentState as HeartbeatAgentState, HeartbeatPattern, HeartbeatPatterns};
+use crate::monitor::render;
+use crate::rules::ReteEngine;
 use skill_docket::trigger::evaluator::{self, AgentContext, TriggerFired};
 use skill_docket::trigger::registry::TriggerRegistry;
 use crate::types::agent::Agent;
+use crate::types::config::resolve_heartbeat_marker;
 use crate::types::health::{HealthAssessment, HealthSignal};
 
 
@@ -40,6 +44,9 @@ pub struct OutputTracker {
     last_change_ms: HashMap<String, u64>,
     /// Number of consecutive identical captures per agent.
     stale_count: HashMap<String, u32>,
+    /// Scrollback lines requested when the visible capture holds no
+    /// heartbeat line. 0 never requests history.
+    pub history_lines: u32,
 }
 
 impl OutputTracker {
@@ -48,6 +55,7 @@ impl OutputTracker {
             last_captures: HashMap::new(),
             last_change_ms: HashMap::new(),
             stale_count: HashMap::new(),
+            history_lines: heartbeat::HEARTBEAT_HISTORY_LINES,
         }
     }
 
@@ -62,8 +70,46 @@ impl OutputTracker {
         prompt_pattern: &str,
         now_ms: u64,
     ) -> Result<OutputCheckResult, String> {
-        let capture = backend.capture_pane(agent)?;
-        let heartbeat = heartbeat::parse_capture(&capture, prompt_pattern);
+        self.check_agent_with_marker(
+            agent,
+            backend,
+            prompt_pattern,
+            heartbeat::DEFAULT_HEARTBEAT_MARKER,
+            now_ms,
+        )
+    }
+
+    /// Like `check_agent`, locating heartbeat lines by `marker`.
+    ///
+    /// Parses the visible capture first. If it holds no heartbeat line,
+    /// `history_lines` of scrollback are captured and parsed instead, since
+    /// the latest heartbeat may have scrolled off screen. Change detection
+    /// always compares visible captures.
+    pub fn check_agent_with_marker(
+        &mut self,
+        agent: &str,
+        backend: &dyn SessionBackend,
+        prompt_pattern: &str,
+        marker: &str,
+        now_ms: u64,
+    ) -> Result<OutputCheckResult, String> {
+        self.check_agent_with_pattern(agent, backend, prompt_pattern, marker, None, now_ms)
+    }
+
+    /// Like `check_agent_with_marker`, parsing with the agent type's
+    /// heartbeat pattern when one is given.
+    pub fn check_agent_with_pattern(
+        &mut self,
+        agent: &str,
+        backend: &dyn SessionBackend,
+        prompt_pattern: &str,
+        marker: &str,
+        pattern: Option<&HeartbeatPattern>,
+        now_ms: u64,
+    ) -> Result<OutputCheckResult, String> {
+        let (capture, heartbeat) = capture_heartbeat(backend, agent, self.history_lines, |output| {
+            heartbeat::parse_capture_with_pattern(output, prompt_pattern, marker, pattern)
+        })?;
 
         let changed = match self.last_captures.get(agent) {
             Some(prev) => prev != &capture,
@@ -102,6 +148,30 @@ impl OutputTracker {
     }
 }
 
+/// Capture `agent`'s visible pane and parse it with `parse`. If the
+/// visible capture holds no heartbeat line, `history_lines` of scrollback
+/// are captured and parsed instead, since the latest heartbeat may have
+/// scrolled off screen; 0 never requests history. Returns the normalized
+/// visible capture with the parse result.
+pub fn capture_heartbeat<F>(
+    backend: &dyn SessionBackend,
+    agent: &str,
+    history_lines: u32,
+    parse: F,
+) -> Result<(String, heartbeat::HeartbeatResult), String>
+where
+    F: Fn(&str) -> heartbeat::HeartbeatResult,
+{
+    let capture = render::normalize_output(&backend.capture_pane(agent)?);
+    let mut result = parse(&capture);
+    if result.heartbeat_line.is_none() && history_lines > 0 {
+        if let Ok(raw) = backend.capture_pane_lines(agent, history_lines) {
+            result = parse(&render::normalize_output(&raw));
+        }
+    }
+    Ok((capture, result))
+}
+
 impl Default for OutputTracker {
     fn default() -> Self {
         Self::new()
@@ -173,32 +243,34 @@ impl DeliveryBridge {
     /// Attempt to deliver pending messages to agents that are ready.
     ///
     /// For each agent with pending messages, captures the pane, parses the
-    /// heartbeat, and delivers the oldest message if the agent is Ready.
+    /// heartbeat, and delivers the oldest message if the agent is Ready and
+    /// the store's pacing interval for it has elapsed.
     /// Returns the list of successful deliveries.
     pub fn deliver_pending(
         &self,
         store: &mut MessageStore,
         backend: &dyn SessionBackend,
         agents: &[String],
-        _now_ms: u64,
+        now_ms: u64,
     ) -> Vec<DeliveryResult> {
         let mut results = Vec::new();
         for agent in agents {
-            let pending = store.pending_for(agent);
-            if pending.is_empty() {
+            if store.next_deliverable(agent, now_ms).is_none() {
                 continue;
             }
 
             // Check if agent is ready (capture pane, parse heartbeat)
-            let capture = match backend.capture_pane(agent) {
-                Ok(output) => output,
+            let history_lines = heartbeat::HEARTBEAT_HISTORY_LINES;
+            let heartbeat = match capture_heartbeat(backend, agent, history_lines, |output| {
+                heartbeat::parse_capture(output, &self.prompt_pattern)
+            }) {
+                Ok((_, heartbeat)) => heartbeat,
                 Err(_) => continue, // can't reach agent, skip
             };
-            let heartbeat = heartbeat::parse_capture(&capture, &self.prompt_pattern);
 
             if heartbeat.state == HeartbeatAgentState::Ready {
                 // Deliver oldest pending message
-                if let Some(msg) = store.deliver(agent) {
+                if let Some(msg) = store.deliver_paced(agent, now_ms) {
                     let formatted = format!("[{}] {}", msg.sender, msg.text);
                     results.push(DeliveryResult {
                         agent: agent.clone(),
@@ -248,21 +320,19 @@ impl DeliveryBridge {
     ) -> Result<(), String> {
         use cmx_utils::response::Action;
 
-        // Send Ctrl-C first
-        backend.execute_action(&Action::SendKeys {
+        // Send Ctrl-C first, then the optional text, as one batch
+        let mut actions = vec![Action::SendKeys {
             target: agent.to_string(),
             keys: "C-c".to_string(),
-        })?;
-
-        // Then optional text
+        }];
         if let Some(t) = text {
             let formatted = format!("{} Enter", t);
-            backend.execute_action(&Action::SendKeys {
+            actions.push(Action::SendKeys {
                 target: agent.to_string(),
                 keys: formatted,
-            })?;
+            });
         }
-        Ok(())
+        backend.execute_batch(&actions).map_err(|e| e.message)
     }
 }
 
@@ -290,6 +360,14 @@ pub struct MonitorCycle {
     pub trigger_registry: TriggerRegistry,
     /// Per-agent timers for heartbeat-type trigger conditions.
     pub heartbeat_timers: HashMap<String, u64>,
+    /// Heartbeat marker for agents without a role override (blank = default).
+    pub heartbeat_marker: String,
+    /// Per-role heartbeat markers.
+    pub heartbeat_marker_overrides: HashMap<String, String>,
+    /// Per-agent-type prompt patterns (empty = built-in detection).
+    pub heartbeat_patterns: HeartbeatPatterns,
+    /// Rules run against each capture to raise health signals (empty = none).
+    pub health_rules: ReteEngine,
 }
 
 impl MonitorCycle {
@@ -305,6 +383,76 @@ impl MonitorCycle {
             heartbeat_timeout_secs,
             trigger_registry: TriggerRegistry::new(),
             heartbeat_timers: HashMap::new(),
+            heartbeat_marker: heartbeat::DEFAULT_HEARTBEAT_MARKER.to_string(),
+            heartbeat_marker_overrides: HashMap::new(),
+            heartbeat_patterns: HeartbeatPatterns::new(),
+            health_rules: ReteEngine::new(),
+        }
+    }
+
+    /// Heartbeat marker for an agent role, falling back to the global marker.
+    pub fn marker_for(&self, role: &str) -> &str {
+        resolve_heartbeat_marker(&self.heartbeat_marker, &self.heartbeat_marker_overrides, role)
+    }
+
+    /// Capture one agent's pane, track its output, and assess its health.
+    ///
+    /// This is phases 1 and 2 of `run_cycle` for a single agent; it is also
+    /// used to force an immediate recheck outside the regular cycle.
+    pub fn assess_agent(
+        &mut self,
+        agent: &Agent,
+        backend: &dyn SessionBackend,
+        now_ms: u64,
+    ) -> HealthAssessment {
+        let marker = self.marker_for(&agent.role).to_string();
+        let (signals, captured) = match self.tracker.check_agent_with_pattern(
+            &agent.name,
+            backend,
+            &self.prompt_pattern,
+            &marker,
+            self.heartbeat_patterns.get(&agent.agent_type),
+            now_ms,
+        ) {
+            Ok(check) => {
+                let mut sigs = vec![HealthSignal::InfrastructureOk];
+                let staleness_secs = self.tracker.staleness_ms(&agent.name, now_ms) / 1000;
+                if staleness_secs > self.heartbeat_timeout_secs {
+                    sigs.push(HealthSignal::HeartbeatStale {
+                        age_secs: staleness_secs,
+                    });
+                } else {
+                    sigs.push(HealthSignal::HeartbeatRecent {
+                        age_secs: staleness_secs,
+                    });
+                }
+                if let HeartbeatAgentState::Error = check.heartbeat.state {
+                    sigs.push(HealthSignal::ErrorPatternDetected {
+                        pattern: check.heartbeat.last_line.clone(),
+                    });
+                }
+                (sigs, true)
+            }
+            Err(_) => {
+                let sigs = vec![HealthSignal::InfrastructureFailed {
+                    reason: "capture failed".into(),
+                }];
+                (sigs, false)
+            }
+        };
+
+        // Rules only see a capture taken this cycle, never a stale one.
+        let capture = if captured { self.tracker.last_captures.get(&agent.name) } else { None };
+        match capture {
+            Some(capture) => health::assess_with_rules(
+                agent,
+                &signals,
+                &self.health_rules,
+                capture,
+                self.heartbeat_timeout_secs,
+                now_ms,
+            ),
+            None => health::assess(agent, &signals, self.heartbeat_timeout_secs, now_ms),
         }
     }
 
@@ -331,45 +479,7 @@ impl MonitorCycle {
 
         // Phase 1 + 2: Capture, parse, and assess health per agent
         for agent in agents {
-            let signals = match self.tracker.check_agent(
-                &agent.name,
-                backend,
-                &self.prompt_pattern,
-                now_ms,
-            ) {
-                Ok(check) => {
-                    let mut sigs = vec![HealthSignal::InfrastructureOk];
-                    let staleness_secs =
-                        self.tracker.staleness_ms(&agent.name, now_ms) / 1000;
-                    if staleness_secs > self.heartbeat_timeout_secs {
-                        sigs.push(HealthSignal::HeartbeatStale {
-                            age_secs: staleness_secs,
-                        });
-                    } else {
-                        sigs.push(HealthSignal::HeartbeatRecent {
-                            age_secs: staleness_secs,
-                        });
-                    }
-                    if let HeartbeatAgentState::Error = check.heartbeat.state {
-                        sigs.push(HealthSignal::ErrorPatternDetected {
-                            pattern: check.heartbeat.last_line.clone(),
-                        });
-                    }
-                    sigs
-                }
-                Err(_) => {
-                    vec![HealthSignal::InfrastructureFailed {
-                        reason: "capture failed".into(),
-                    }]
-                }
-            };
-
-            let assessment = health::assess(
-                agent,
-                &signals,
-                self.heartbeat_timeout_secs,
-                now_ms,
-            );
+            let assessment = self.assess_agent(agent, backend, now_ms);
             health_updates.push(assessment);
         }
 
@@ -464,6 +574,8 @@ mod tests {
             health: HealthState::Healthy,
             last_heartbeat_ms: None,
             session: None,
+            created_at_ms: None,
+            assigned_at_ms: None,
         }
     }
 
@@ -523,6 +635,52 @@ mod tests {
         assert_eq!(result.stale_count, 0);
     }
 
+    #[test]
+    fn visible_heartbeat_skips_history_capture() {
+        let mut tracker = OutputTracker::new();
+        let mut mock = MockBackend::new();
+        mock.set_capture("w1", "HEARTBEAT ok\n$ ");
+
+        let result = tracker.check_agent("w1", &mock, "$ ", 1000).unwrap();
+        assert_eq!(result.heartbeat.heartbeat_line.as_deref(), Some("HEARTBEAT ok"));
+        assert!(mock.history_requests.borrow().is_empty());
+    }
+
+    #[test]
+    fn scrolled_off_heartbeat_found_in_history() {
+        let mut tracker = OutputTracker::new();
+        tracker.history_lines = 50;
+        let mut mock = MockBackend::new();
+        let mut history: Vec<String> = (0..80).map(|i| format!("build step {}", i)).collect();
+        history[60] = "HEARTBEAT compiling".into();
+        mock.set_scrollback("w1", &history.join("\n"));
+        mock.set_capture("w1", "build step 99\n$ ");
+
+        let result = tracker.check_agent("w1", &mock, "$ ", 1000).unwrap();
+        assert_eq!(result.heartbeat.heartbeat_line.as_deref(), Some("HEARTBEAT compiling"));
+        assert_eq!(*mock.history_requests.borrow(), vec![("w1".to_string(), 50)]);
+
+        // Disabled history: the heartbeat stays undiscovered.
+        tracker.history_lines = 0;
+        let result = tracker.check_agent("w1", &mock, "$ ", 2000).unwrap();
+        assert!(result.heartbeat.heartbeat_line.is_none());
+        assert!(!result.output_changed);
+    }
+
+    #[test]
+    fn deliver_pending_reads_visible_pane_before_history() {
+        let bridge = DeliveryBridge::new(60000, "$ ".into());
+        let mut store = MessageStore::new();
+        store.enqueue(make_msg("pm", "w1", "do task X", 1000));
+        let mut mock = MockBackend::new();
+        mock.set_capture("w1", "HEARTBEAT idle\n$ ");
+
+        let agents = vec!["w1".to_string()];
+        let results = bridge.deliver_pending(&mut store, &mock, &agents, 2000);
+        assert_eq!(results.len(), 1);
+        assert!(mock.history_requests.borrow().is_empty());
+    }
+
     #[test]
     fn staleness_ms_tracks_time_since_last_change() {
         let mut tracker = OutputTracker::new();
@@ -580,6 +738,55 @@ mod tests {
         assert_eq!(result.heartbeat.state, HeartbeatAgentState::Error);
     }
 
+    #[test]
+    fn assess_agent_uses_pattern_for_agent_type() {
+        let mut cycle = MonitorCycle::new(60000, 60, "$ ".into());
+        cycle.heartbeat_patterns.set(
+            AgentType::Ssh,
+            HeartbeatPattern::new(r"^\[(?P<state>\w+)\]\$\s*$").unwrap(),
+        );
+        let mut mock = MockBackend::new();
+        mock.set_capture("s1", "job exited\n[error]$ ");
+        mock.set_capture("c1", "job exited\n[error]$ ");
+
+        let mut ssh_agent = make_agent("s1");
+        ssh_agent.agent_type = AgentType::Ssh;
+        let ssh = cycle.assess_agent(&ssh_agent, &mock, 1000);
+        assert!(ssh
+            .signals
+            .iter()
+            .any(|s| matches!(s, HealthSignal::ErrorPatternDetected { .. })));
+
+        // Claude agents have no pattern, so the built-in detection sees a prompt.
+        let claude = cycle.assess_agent(&make_agent("c1"), &mock, 1000);
+        assert!(!claude
+            .signals
+            .iter()
+            .any(|s| matches!(s, HealthSignal::ErrorPatternDetected { .. })));
+    }
+
+    #[test]
+    fn assess_agent_applies_health_rules_to_capture() {
+        let mut cycle = MonitorCycle::new(60000, 60, "$ ".into());
+        let rules = crate::rules::format::parse_arrow_rules(
+            "agent.$a.output contains OOM --> agent.$a.signal = oom",
+        )
+        .unwrap();
+        cycle.health_rules.add_rules(rules);
+        let mut mock = MockBackend::new();
+        mock.set_capture("w1", "step 3\nkilled: OOM\n$ ");
+        mock.set_capture("w2", "all good\n$ ");
+
+        let hit = cycle.assess_agent(&make_agent("w1"), &mock, 1000);
+        assert!(hit
+            .signals
+            .contains(&HealthSignal::TriggerFired { name: "oom".into() }));
+        assert_eq!(hit.overall, HealthState::Degraded);
+
+        let quiet = cycle.assess_agent(&make_agent("w2"), &mock, 1000);
+        assert_eq!(quiet.overall, HealthState::Healthy);
+    }
+
     // ---- DeliveryBridge tests ----
 
     #[test]
@@ -652,6 +859,24 @@ mod tests {
         assert!(r4.is_empty());
     }
 
+    #[test]
+    fn deliver_pending_paces_per_recipient() {
+        let bridge = DeliveryBridge::new(60000, "$ ".into());
+        let mut store = MessageStore::new();
+        store.set_min_interval(2000);
+        store.enqueue(make_msg("pm", "w1", "first", 1000));
+        store.enqueue(make_msg("pm", "w1", "second", 1000));
+
+        let mut mock = MockBackend::new();
+        mock.set_capture("w1", "ready\n$ ");
+        let agents = vec!["w1".to_string()];
+
+        assert_eq!(bridge.deliver_pending(&mut store, &mock, &agents, 4000).len(), 1);
+        assert!(bridge.deliver_pending(&mut store, &mock, &agents, 5000).is_empty());
+        let later = bridge.deliver_pending(&mut store, &mock, &agents, 6000);
+        assert_eq!(later[0].message, "[pm] second");
+    }
+
     #[test]
     fn deliver_pending_skips_unreachable_agent() {
         let bridge = DeliveryBridge::new(60000, "$ ".into());
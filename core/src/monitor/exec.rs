@@ -0,0 +1,287 @@
+//! One-off command execution in an agent pane — settle detection and
+//! output-delta extraction for `agent.exec`.
+//!
+//! `Sys` stays I/O-free: it validates the request and queues the SendKeys
+//! action. The contract for the daemon, which owns the session backend, is:
+//!
+//! 1. Capture the pane *before* the queued keys are sent and start a
+//!    `PendingExec` with that capture.
+//! 2. Send the keys (the backend appends Enter).
+//! 3. On each later tick, capture the pane again and pass it to
+//!    `PendingExec::observe`. The pane is settled once it has changed from
+//!    the before-capture, shows a prompt again, and then stayed identical
+//!    for `EXEC_SETTLE_MS`. A prompt is the before-capture's prompt line
+//!    or, when set with `with_ready_pattern`, a Ready heartbeat reading.
+//!    A command that pauses mid-output is not at a prompt, so it keeps
+//!    waiting; after `EXEC_TIMEOUT_MS` it finishes with whatever appeared.
+//! 4. Reply with `output_delta(before, after, command)`: the lines the
+//!    command printed, without the echoed command line or the new prompt.
+
+use super::heartbeat::{self, AgentState, HeartbeatPattern};
+use super::render::normalize_output;
+
+/// How long the pane must stay unchanged before the output is final.
+pub const EXEC_SETTLE_MS: u64 = 500;
+
+/// Upper bound on waiting for a command to finish.
+pub const EXEC_TIMEOUT_MS: u64 = 10_000;
+
+/// Scrollback lines captured before and after the command.
+pub const EXEC_CAPTURE_LINES: u32 = 200;
+
+/// Outcome of observing one capture of a running exec.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExecPoll {
+    /// Output is still changing (or has not started); capture again later.
+    Waiting,
+    /// The pane settled; carries the output delta.
+    Settled(String),
+    /// The timeout elapsed first; carries the output seen so far.
+    TimedOut(String),
+}
+
+/// An `agent.exec` awaiting its pane to settle.
+#[derive(Debug, Clone)]
+pub struct PendingExec {
+    pub agent: String,
+    pub command: String,
+    before: String,
+    /// The before-capture's last non-blank line, trimmed.
+    prompt: String,
+    /// Prompt substring and agent-type pattern for heartbeat parsing.
+    ready_pattern: Option<(String, Option<HeartbeatPattern>)>,
+    last_capture: String,
+    last_change_ms: u64,
+    started_ms: u64,
+}
+
+impl PendingExec {
+    /// Start tracking an exec whose keys have not been sent yet.
+    pub fn new(agent: &str, command: &str, before: &str, now_ms: u64) -> Self {
+        let before = normalize_output(before);
+        let prompt = trimmed_lines(&before).last().map(|l| l.trim().to_string()).unwrap_or_default();
+        PendingExec {
+            agent: agent.to_string(),
+            command: command.to_string(),
+            prompt,
+            ready_pattern: None,
+            last_capture: before.clone(),
+            before,
+            last_change_ms: now_ms,
+            started_ms: now_ms,
+        }
+    }
+
+    /// Also treat the pane as back at a prompt when heartbeat parsing with
+    /// `prompt_pattern` and the agent type's `pattern` reads it as Ready.
+    pub fn with_ready_pattern(mut self, prompt_pattern: &str, pattern: Option<HeartbeatPattern>) -> Self {
+        self.ready_pattern = Some((prompt_pattern.to_string(), pattern));
+        self
+    }
+
+    /// Feed a fresh pane capture.
+    pub fn observe(&mut self, capture: &str, now_ms: u64) -> ExecPoll {
+        let capture = normalize_output(capture);
+        if capture != self.last_capture {
+            self.last_capture = capture;
+            self.last_change_ms = now_ms;
+        }
+        let changed = self.last_capture != self.before;
+        let quiet = now_ms.saturating_sub(self.last_change_ms) >= EXEC_SETTLE_MS;
+        if changed && quiet && self.at_prompt() {
+            return ExecPoll::Settled(self.delta());
+        }
+        if now_ms.saturating_sub(self.started_ms) >= EXEC_TIMEOUT_MS {
+            return ExecPoll::TimedOut(self.delta());
+        }
+        ExecPoll::Waiting
+    }
+
+    /// Whether the latest capture ends at a prompt.
+    fn at_prompt(&self) -> bool {
+        let last = trimmed_lines(&self.last_capture).last().map(|l| l.trim()).unwrap_or("");
+        if !self.prompt.is_empty() && last == self.prompt {
+            return true;
+        }
+        self.ready_pattern.as_ref().is_some_and(|(prompt_pattern, pattern)| {
+            let reading = heartbeat::parse_capture_with_pattern(
+                &self.last_capture,
+                prompt_pattern,
+                "",
+                pattern.as_ref(),
+            );
+            reading.state == AgentState::Ready
+        })
+    }
+
+    fn delta(&self) -> String {
+        output_delta(&self.before, &self.last_capture, &self.command)
+    }
+}
+
+/// Extract what a command printed, given pane captures from before it was
+/// typed and after it finished.
+///
+/// The before-capture's lines are located in the after-capture — its last
+/// line (the prompt) may have been extended by the echoed command, and the
+/// top may have scrolled out. Everything after that is new output, minus a
+/// trailing copy of the prompt. If the captures do not overlap, output
+/// starts after the last line echoing `command`.
+pub fn output_delta(before: &str, after: &str, command: &str) -> String {
+    let before = trimmed_lines(before);
+    let after = trimmed_lines(after);
+
+    let start = overlap_end(&before, &after)
+        .or_else(|| {
+            after
+                .iter()
+                .rposition(|l| !command.is_empty() && l.contains(command))
+                .map(|i| i + 1)
+        })
+        .unwrap_or(0);
+
+    let mut end = after.len();
+    if let Some(prompt) = before.last().map(|p| p.trim()) {
+        if end > start && !prompt.is_empty() && after[end - 1].trim() == prompt {
+            end -= 1;
+        }
+    }
+    after[start..end].join("\n")
+}
+
+/// Lines of a capture with trailing blank lines removed.
+fn trimmed_lines(text: &str) -> Vec<&str> {
+    let mut lines: Vec<&str> = text.lines().collect();
+    while lines.last().is_some_and(|l| l.trim().is_empty()) {
+        lines.pop();
+    }
+    lines
+}
+
+/// Index in `after` just past the lines carried over from `before`.
+///
+/// Tries the longest suffix of `before` first, at its earliest position:
+/// carried-over lines sit above the new output, at the very top once the
+/// window has scrolled. The last carried-over line only needs to start
+/// with the old prompt line, since the command was typed onto it.
+fn overlap_end(before: &[&str], after: &[&str]) -> Option<usize> {
+    if before.is_empty() {
+        return None;
+    }
+    for k in (1..=before.len().min(after.len())).rev() {
+        let tail = &before[before.len() - k..];
+        for pos in 0..=after.len() - k {
+            let window = &after[pos..pos + k];
+            let body_matches = tail[..k - 1] == window[..k - 1];
+            if body_matches && window[k - 1].starts_with(tail[k - 1]) {
+                return Some(pos + k);
+            }
+        }
+    }
+    None
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delta_strips_echo_and_new_prompt() {
+        let before = "previous output\n$ ";
+        let after = "previous output\n$ ls\nCargo.toml\nsrc\n$ ";
+        assert_eq!(output_delta(before, after, "ls"), "Cargo.toml\nsrc");
+    }
+
+    #[test]
+    fn delta_survives_scrolled_capture() {
+        let before = "line 1\nline 2\nline 3\n$ ";
+        // Visible window scrolled; line 1 is gone.
+        let after = "line 2\nline 3\n$ echo hi\nhi\n$ ";
+        assert_eq!(output_delta(before, after, "echo hi"), "hi");
+    }
+
+    #[test]
+    fn delta_falls_back_to_echo_line() {
+        let before = "old screen";
+        let after = "cleared\n$ make\nbuilding\ndone";
+        assert_eq!(output_delta(before, after, "make"), "building\ndone");
+    }
+
+    #[test]
+    fn delta_ignores_trailing_blank_lines() {
+        let before = "$ \n\n\n";
+        let after = "$ pwd\n/home/w1\n$ \n\n";
+        assert_eq!(output_delta(before, after, "pwd"), "/home/w1");
+    }
+
+    #[test]
+    fn delta_empty_when_command_prints_nothing() {
+        assert_eq!(output_delta("$ ", "$ true\n$ ", "true"), "");
+    }
+
+    #[test]
+    fn exec_waits_until_output_settles() {
+        let mut exec = PendingExec::new("w1", "ls", "$ ", 1000);
+        // Nothing changed yet: keep waiting.
+        assert_eq!(exec.observe("$ ", 1200), ExecPoll::Waiting);
+        assert_eq!(exec.observe("$ ls\na", 1300), ExecPoll::Waiting);
+        assert_eq!(exec.observe("$ ls\na\nb\n$ ", 1400), ExecPoll::Waiting);
+        assert_eq!(exec.observe("$ ls\na\nb\n$ ", 1700), ExecPoll::Waiting);
+        assert_eq!(
+            exec.observe("$ ls\na\nb\n$ ", 1900),
+            ExecPoll::Settled("a\nb".into())
+        );
+    }
+
+    #[test]
+    fn exec_keeps_waiting_when_output_stalls_before_prompt() {
+        let mut exec = PendingExec::new("w1", "make", "$ ", 0);
+        // Output pauses well past the settle time without a new prompt.
+        assert_eq!(exec.observe("$ make
+compiling", 200), ExecPoll::Waiting);
+        assert_eq!(exec.observe("$ make
+compiling", 3000), ExecPoll::Waiting);
+        assert_eq!(exec.observe("$ make
+compiling
+linking
+$ ", 3200), ExecPoll::Waiting);
+        assert_eq!(
+            exec.observe("$ make
+compiling
+linking
+$ ", 3800),
+            ExecPoll::Settled("compiling
+linking".into())
+        );
+    }
+
+    #[test]
+    fn exec_settles_on_ready_heartbeat_when_prompt_changes() {
+        let pattern = HeartbeatPattern::new(r"^\S+ % $").unwrap();
+        let mut exec = PendingExec::new("w1", "cd src", "~ % ", 0)
+            .with_ready_pattern("%", Some(pattern));
+        assert_eq!(exec.observe("~ % cd src\nsrc % ", 100), ExecPoll::Waiting);
+        assert_eq!(exec.observe("~ % cd src\nsrc % ", 700), ExecPoll::Settled("src % ".into()));
+    }
+
+    #[test]
+    fn exec_times_out_with_partial_output() {
+        let mut exec = PendingExec::new("w1", "tail -f log", "$ ", 0);
+        let mut now = 0;
+        let mut screen = String::from("$ tail -f log");
+        let result = loop {
+            now += 400;
+            screen.push_str(&format!("\nline {}", now));
+            match exec.observe(&screen, now) {
+                ExecPoll::Waiting => continue,
+                other => break other,
+            }
+        };
+        match result {
+            ExecPoll::TimedOut(text) => assert!(text.starts_with("line 400")),
+            other => panic!("expected timeout, got {:?}", other),
+        }
+        assert!(now >= EXEC_TIMEOUT_MS);
+    }
+}
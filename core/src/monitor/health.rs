@@ -4,7 +4,19 @@
 //! then produces a `HealthAssessment` that summarizes the agent's health.
 //! Also classifies the failure mode (infrastructure, agent, or strategic)
 //! for use by the PM agent's decision logic.
+//!
+//! Health rules extend the built-in signals: `rule_signals` runs a loaded
+//! `ReteEngine` over an agent's pane capture and turns the actions of fired
+//! rules into `TriggerFired` signals (see `signal_for_action`).
+
+use std::path::Path;
+
+use serde_json::Value;
 
+use crate::diagnosis::SignalType;
+use crate::namespace::store::ParameterStore;
+use crate::rules::format::ActionOp;
+use crate::rules::{ReteEngine, RuleAction};
 use crate::types::agent::{Agent, HealthState};
 use crate::types::health::{HealthAssessment, HealthSignal};
 
@@ -38,7 +50,7 @@ pub enum FailureMode {
 /// - `InfrastructureFailed` or `SshDisconnected` -> Unhealthy
 /// - `HeartbeatStale` with `age_secs > heartbeat_timeout` -> Unhealthy
 /// - `HeartbeatStale` with `age_secs > heartbeat_timeout / 2` -> Degraded
-/// - `ErrorPatternDetected` or `ExplicitError` -> Degraded
+/// - `ErrorPatternDetected`, `ExplicitError` or `TriggerFired` -> Degraded
 /// - All signals positive -> Healthy
 /// - No signals at all -> Unknown
 pub fn assess(
@@ -102,6 +114,12 @@ pub fn assess(
                     reason = format!("explicit error: {}", message);
                 }
             }
+            HealthSignal::TriggerFired { name } => {
+                worst = worst_of(worst.clone(), HealthState::Degraded);
+                if reason.is_empty() {
+                    reason = format!("health rule fired: {}", name);
+                }
+            }
             HealthSignal::InfrastructureOk
             | HealthSignal::HeartbeatRecent { .. }
             | HealthSignal::SshConnected => {
@@ -151,7 +169,9 @@ pub fn classify_failure(assessment: &HealthAssessment) -> FailureMode {
             let has_error = assessment.signals.iter().any(|s| {
                 matches!(
                     s,
-                    HealthSignal::ErrorPatternDetected { .. } | HealthSignal::ExplicitError { .. }
+                    HealthSignal::ErrorPatternDetected { .. }
+                        | HealthSignal::ExplicitError { .. }
+                        | HealthSignal::TriggerFired { .. }
                 )
             });
             if has_error {
@@ -163,6 +183,107 @@ pub fn classify_failure(assessment: &HealthAssessment) -> FailureMode {
     }
 }
 
+// ---------------------------------------------------------------------------
+// Rule-driven signals
+// ---------------------------------------------------------------------------
+
+/// Leaf a rule action must SET to raise a signal, e.g.
+/// `agent.$a.output ~= OOM --> agent.$a.signal = oom`.
+pub const RULE_SIGNAL_FIELD: &str = "signal";
+
+/// The diagnosis signal a rule action raises, if any.
+///
+/// Only `<path>.signal = <name>` actions raise signals; the value names the
+/// trigger. Every other action is ignored by the monitor.
+pub fn signal_for_action(action: &RuleAction) -> Option<SignalType> {
+    if action.operator != ActionOp::Set
+        || action.path.rsplit('.').next() != Some(RULE_SIGNAL_FIELD)
+    {
+        return None;
+    }
+    let name = action.value.trim().trim_matches('"');
+    if name.is_empty() {
+        None
+    } else {
+        Some(SignalType::TriggerFired(name.to_string()))
+    }
+}
+
+/// Run `engine` against one agent's pane capture and return a signal for
+/// each distinct trigger its fired rules raise.
+///
+/// The capture is published at `agent.<name>.output` in a scratch store,
+/// so rules written against the live store's layout apply unchanged.
+pub fn rule_signals(engine: &ReteEngine, agent: &str, capture: &str) -> Vec<HealthSignal> {
+    let mut store = ParameterStore::new();
+    if store
+        .set(&format!("agent.{}.output", agent), Value::String(capture.to_string()))
+        .is_err()
+    {
+        return Vec::new();
+    }
+
+    let mut signals = Vec::new();
+    for fired in engine.evaluate(&store).fired_rules {
+        let rule = match engine.rule(fired.rule_index) {
+            Some(r) => r,
+            None => continue,
+        };
+        for action in &rule.actions {
+            if let Some(SignalType::TriggerFired(name)) = signal_for_action(action) {
+                let signal = HealthSignal::TriggerFired { name };
+                if !signals.contains(&signal) {
+                    signals.push(signal);
+                }
+            }
+        }
+    }
+    signals
+}
+
+/// Like `assess`, adding the signals `engine` raises for `capture`.
+pub fn assess_with_rules(
+    agent: &Agent,
+    signals: &[HealthSignal],
+    engine: &ReteEngine,
+    capture: &str,
+    heartbeat_timeout_secs: u64,
+    now_ms: u64,
+) -> HealthAssessment {
+    let mut all = signals.to_vec();
+    all.extend(rule_signals(engine, &agent.name, capture));
+    assess(agent, &all, heartbeat_timeout_secs, now_ms)
+}
+
+/// File in the config directory holding the monitor's health rules.
+pub const HEALTH_RULES_FILE: &str = "health_rules.md";
+
+/// Load the health rules from `config_dir`, with warnings to report.
+///
+/// A missing file yields an empty engine. A file that fails to parse is
+/// reported and ignored rather than stopping the monitor; rules that load
+/// with warnings (e.g. an invalid `~=` regex) are kept and reported.
+pub fn load_health_rules(config_dir: &Path) -> (ReteEngine, Vec<String>) {
+    let mut engine = ReteEngine::new();
+    let path = config_dir.join(HEALTH_RULES_FILE);
+    let text = match std::fs::read_to_string(&path) {
+        Ok(t) => t,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return (engine, Vec::new()),
+        Err(e) => return (engine, vec![format!("{}: {}", path.display(), e)]),
+    };
+    let rules = match crate::rules::format::parse_rules_auto(&text) {
+        Ok(r) => r,
+        Err(e) => return (engine, vec![format!("{}: {}", path.display(), e)]),
+    };
+    engine.add_rules(rules);
+    let warnings = engine
+        .load_warnings()
+        .iter()
+        .map(|w| format!("{}: {}: {}", path.display(), w.path, w.message))
+        .collect();
+    (engine, warnings)
+}
+
 /// Return the worse of two health states.
 ///
 /// Ordering: Healthy < Degraded < Unhealthy (Unknown treated as Healthy for comparison).
@@ -203,6 +324,8 @@ mod tests {
             health: HealthState::Healthy,
             last_heartbeat_ms: None,
             session: None,
+            created_at_ms: None,
+            assigned_at_ms: None,
         }
     }
 
@@ -372,4 +495,86 @@ mod tests {
         };
         assert_eq!(classify_failure(&assessment), FailureMode::None);
     }
+
+    fn oom_engine() -> ReteEngine {
+        let rules = crate::rules::format::parse_arrow_rules(
+            "agent.$a.output contains OOM --> agent.$a.signal = oom",
+        )
+        .unwrap();
+        let mut engine = ReteEngine::new();
+        engine.add_rules(rules);
+        engine
+    }
+
+    #[test]
+    fn signal_for_action_maps_only_signal_sets() {
+        let fire = RuleAction::parse("agent.$a.signal = oom").unwrap();
+        assert_eq!(signal_for_action(&fire), Some(SignalType::TriggerFired("oom".into())));
+        let other = RuleAction::parse("agent.$a.status = failing").unwrap();
+        assert_eq!(signal_for_action(&other), None);
+        let append = RuleAction::parse("agent.$a.signal += oom").unwrap();
+        assert_eq!(signal_for_action(&append), None);
+    }
+
+    #[test]
+    fn rule_fires_trigger_signal_into_assessment() {
+        let agent = make_agent("w1");
+        let engine = oom_engine();
+        let signals = vec![
+            HealthSignal::InfrastructureOk,
+            HealthSignal::HeartbeatRecent { age_secs: 5 },
+        ];
+
+        let result = assess_with_rules(&agent, &signals, &engine, "step 3\nkilled: OOM\n$ ", 60, 1000);
+        assert!(result
+            .signals
+            .contains(&HealthSignal::TriggerFired { name: "oom".into() }));
+        assert_eq!(result.overall, HealthState::Degraded);
+        assert_eq!(classify_failure(&result), FailureMode::Agent);
+
+        let quiet = assess_with_rules(&agent, &signals, &engine, "all good\n$ ", 60, 1000);
+        assert_eq!(quiet.overall, HealthState::Healthy);
+    }
+
+    #[test]
+    fn load_health_rules_reads_config_file() {
+        let dir = std::env::temp_dir().join(format!("cmx_health_rules_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let (empty, warnings) = load_health_rules(&dir);
+        assert!(empty.rule(0).is_none());
+        assert!(warnings.is_empty());
+
+        std::fs::write(
+            dir.join(HEALTH_RULES_FILE),
+            "agent.$a.output contains OOM --> agent.$a.signal = oom\n",
+        )
+        .unwrap();
+        let (engine, warnings) = load_health_rules(&dir);
+        assert!(warnings.is_empty());
+        let signals = rule_signals(&engine, "w1", "killed: OOM");
+        assert_eq!(signals, vec![HealthSignal::TriggerFired { name: "oom".into() }]);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn load_health_rules_reports_invalid_regex() {
+        let dir = std::env::temp_dir().join(format!("cmx_health_rules_bad_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join(HEALTH_RULES_FILE),
+            "agent.$a.output ~= error: [ --> agent.$a.signal = bad\n",
+        )
+        .unwrap();
+
+        let (engine, warnings) = load_health_rules(&dir);
+        assert!(engine.rule(0).is_some());
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("invalid regex"), "{}", warnings[0]);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
 }
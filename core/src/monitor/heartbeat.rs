@@ -3,6 +3,19 @@
 //! When CMX captures a pane's contents, this module inspects the last few
 //! lines to determine what state the agent is in: waiting at a prompt (Ready),
 //! actively running (Busy), showing an error (Error), or indeterminate (Unknown).
+//! It also locates the most recent heartbeat line, identified by a marker
+//! string the agent prints (configurable per role via settings). Captures
+//! are normalized first (see `render`) so in-place redraws don't confuse it.
+//!
+//! Agent types whose prompt doesn't fit the built-in detection can register
+//! a `HeartbeatPattern`: a regex tested against the last non-empty line,
+//! whose named groups `state` and `context` fill in the result.
+
+use std::collections::HashMap;
+
+use regex::Regex;
+
+use crate::types::agent::AgentType;
 
 /// The state of an agent as inferred from its pane capture.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -26,8 +39,17 @@ pub struct HeartbeatResult {
     pub context_percent: Option<u32>,
     /// The last non-empty line from the capture.
     pub last_line: String,
+    /// The most recent line containing the heartbeat marker, if any.
+    pub heartbeat_line: Option<String>,
 }
 
+/// Lines of scrollback the monitor requests when the visible pane shows no
+/// heartbeat, so the latest one is found after it has scrolled off screen.
+pub const HEARTBEAT_HISTORY_LINES: u32 = 200;
+
+/// Marker used when no heartbeat marker is configured (or it is blank).
+pub const DEFAULT_HEARTBEAT_MARKER: &str = "HEARTBEAT";
+
 /// Common error patterns to look for in pane output.
 const ERROR_PATTERNS: &[&str] = &[
     "Traceback (most recent call last)",
@@ -51,9 +73,23 @@ const ERROR_PATTERNS: &[&str] = &[
 ///   (e.g. `"$ "` or `"❯ "` or a regex-like simple pattern). For simplicity,
 ///   this uses substring matching, not full regex.
 pub fn parse_capture(output: &str, prompt_pattern: &str) -> HeartbeatResult {
+    parse_capture_with_marker(output, prompt_pattern, DEFAULT_HEARTBEAT_MARKER)
+}
+
+/// Like `parse_capture`, locating heartbeat lines by `marker` instead of
+/// the default. A blank marker falls back to `DEFAULT_HEARTBEAT_MARKER`.
+pub fn parse_capture_with_marker(
+    output: &str,
+    prompt_pattern: &str,
+    marker: &str,
+) -> HeartbeatResult {
+    // Collapse `\r`/backspace redraws so spinner frames don't mask the real
+    // last line or heartbeat.
+    let output = super::render::normalize_output(output);
     let lines: Vec<&str> = output.lines().collect();
     let last_line = find_last_nonempty(&lines).unwrap_or("").to_string();
     let context_percent = detect_context_percent(&lines);
+    let heartbeat_line = find_heartbeat_line(&lines, marker).map(|l| l.to_string());
 
     // Check the tail of the output (last 5 lines) for error patterns.
     let tail_start = if lines.len() > 5 { lines.len() - 5 } else { 0 };
@@ -65,6 +101,7 @@ pub fn parse_capture(output: &str, prompt_pattern: &str) -> HeartbeatResult {
             state: AgentState::Error,
             context_percent,
             last_line,
+            heartbeat_line,
         };
     }
 
@@ -74,6 +111,7 @@ pub fn parse_capture(output: &str, prompt_pattern: &str) -> HeartbeatResult {
             state: AgentState::Ready,
             context_percent,
             last_line,
+            heartbeat_line,
         };
     }
 
@@ -83,6 +121,7 @@ pub fn parse_capture(output: &str, prompt_pattern: &str) -> HeartbeatResult {
             state: AgentState::Ready,
             context_percent,
             last_line,
+            heartbeat_line,
         };
     }
 
@@ -92,6 +131,7 @@ pub fn parse_capture(output: &str, prompt_pattern: &str) -> HeartbeatResult {
             state: AgentState::Busy,
             context_percent,
             last_line,
+            heartbeat_line,
         };
     }
 
@@ -99,9 +139,79 @@ pub fn parse_capture(output: &str, prompt_pattern: &str) -> HeartbeatResult {
         state: AgentState::Unknown,
         context_percent,
         last_line,
+        heartbeat_line,
+    }
+}
+
+/// Parse a capture, using `pattern` (if any) in place of the built-in prompt
+/// detection.
+///
+/// When the pattern matches the last non-empty line the agent is Ready, or
+/// the state named by its `state` group (`ready`/`idle`, `busy`, `error`).
+/// A `context` group overrides the detected context percentage. When it does
+/// not match, a Ready reading from the built-in detection becomes Busy;
+/// error detection is unchanged.
+pub fn parse_capture_with_pattern(
+    output: &str,
+    prompt_pattern: &str,
+    marker: &str,
+    pattern: Option<&HeartbeatPattern>,
+) -> HeartbeatResult {
+    let mut result = parse_capture_with_marker(output, prompt_pattern, marker);
+    let pattern = match pattern {
+        Some(p) => p,
+        None => return result,
+    };
+    match pattern.regex.captures(&result.last_line) {
+        Some(caps) => {
+            if let Some(pct) = caps
+                .name("context")
+                .and_then(|m| m.as_str().parse::<u32>().ok())
+                .filter(|pct| *pct <= 100)
+            {
+                result.context_percent = Some(pct);
+            }
+            match caps.name("state").and_then(|m| state_from_name(m.as_str())) {
+                Some(state) => result.state = state,
+                None if result.state != AgentState::Error => result.state = AgentState::Ready,
+                None => {}
+            }
+        }
+        None => {
+            if result.state == AgentState::Ready {
+                result.state = AgentState::Busy;
+            }
+        }
+    }
+    result
+}
+
+/// Map a captured `state` group to an agent state.
+fn state_from_name(name: &str) -> Option<AgentState> {
+    match name.trim().to_lowercase().as_str() {
+        "ready" | "idle" => Some(AgentState::Ready),
+        "busy" => Some(AgentState::Busy),
+        "error" => Some(AgentState::Error),
+        _ => None,
+    }
+}
+
+/// The marker to search for: `marker`, or the default if it is blank.
+/// A blank marker must never be used directly — it would match every line.
+pub fn effective_marker(marker: &str) -> &str {
+    if marker.trim().is_empty() {
+        DEFAULT_HEARTBEAT_MARKER
+    } else {
+        marker
     }
 }
 
+/// Find the most recent line containing the heartbeat marker.
+pub fn find_heartbeat_line<'a>(lines: &[&'a str], marker: &str) -> Option<&'a str> {
+    let marker = effective_marker(marker);
+    lines.iter().rev().find(|l| l.contains(marker)).map(|l| l.trim())
+}
+
 /// Find the last non-empty, non-whitespace line.
 fn find_last_nonempty<'a>(lines: &[&'a str]) -> Option<&'a str> {
     lines.iter().rev().find(|l| !l.trim().is_empty()).copied()
@@ -182,6 +292,74 @@ fn is_claude_prompt(lines: &[&str]) -> bool {
     false
 }
 
+// ---------------------------------------------------------------------------
+// Per-agent-type patterns
+// ---------------------------------------------------------------------------
+
+/// A compiled prompt regex for one agent type.
+#[derive(Debug, Clone)]
+pub struct HeartbeatPattern {
+    regex: Regex,
+}
+
+impl HeartbeatPattern {
+    /// Compile `pattern`. Named groups other than `state` and `context` are
+    /// allowed and ignored.
+    pub fn new(pattern: &str) -> Result<Self, String> {
+        Regex::new(pattern)
+            .map(|regex| HeartbeatPattern { regex })
+            .map_err(|e| format!("invalid heartbeat pattern '{}': {}", pattern, e))
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.regex.as_str()
+    }
+}
+
+/// Heartbeat patterns keyed by agent type. Types without an entry use the
+/// built-in detection, so an empty registry changes nothing.
+#[derive(Debug, Clone, Default)]
+pub struct HeartbeatPatterns {
+    by_type: HashMap<AgentType, HeartbeatPattern>,
+}
+
+impl HeartbeatPatterns {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build the registry from the `heartbeat_patterns` setting. Entries
+    /// with an unknown agent type or an invalid regex are skipped, each
+    /// with a warning.
+    pub fn from_settings(patterns: &HashMap<String, String>) -> (Self, Vec<String>) {
+        let mut registry = Self::new();
+        let mut warnings = Vec::new();
+        let mut keys: Vec<&String> = patterns.keys().collect();
+        keys.sort();
+        for key in keys {
+            let compiled = AgentType::parse(key)
+                .and_then(|t| HeartbeatPattern::new(&patterns[key]).map(|p| (t, p)));
+            match compiled {
+                Ok((agent_type, pattern)) => registry.set(agent_type, pattern),
+                Err(e) => warnings.push(format!("heartbeat_pattern.{}: {}", key, e)),
+            }
+        }
+        (registry, warnings)
+    }
+
+    pub fn set(&mut self, agent_type: AgentType, pattern: HeartbeatPattern) {
+        self.by_type.insert(agent_type, pattern);
+    }
+
+    pub fn get(&self, agent_type: &AgentType) -> Option<&HeartbeatPattern> {
+        self.by_type.get(agent_type)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_type.is_empty()
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Tests
 // ---------------------------------------------------------------------------
@@ -287,4 +465,96 @@ mod tests {
         assert_eq!(extract_context_percent("Context 99%"), Some(99));
         assert_eq!(extract_context_percent("Context: 0%"), Some(0));
     }
+
+    #[test]
+    fn heartbeat_line_default_marker() {
+        let output = "HEARTBEAT step 1\nworking\nHEARTBEAT step 2\nstill working";
+        let result = parse_capture(output, "$ ");
+        assert_eq!(result.heartbeat_line.as_deref(), Some("HEARTBEAT step 2"));
+    }
+
+    #[test]
+    fn heartbeat_line_custom_marker() {
+        let output = "HEARTBEAT ignored\n<<alive 42>>\nbuild ok\n<<alive 43>>\n$ ";
+        let result = parse_capture_with_marker(output, "$ ", "<<alive");
+        assert_eq!(result.heartbeat_line.as_deref(), Some("<<alive 43>>"));
+        assert_eq!(result.state, AgentState::Ready);
+    }
+
+    #[test]
+    fn blank_marker_falls_back_to_default() {
+        let output = "HEARTBEAT t=1\nplain line";
+        let result = parse_capture_with_marker(output, "$ ", "  ");
+        assert_eq!(result.heartbeat_line.as_deref(), Some("HEARTBEAT t=1"));
+        let none = parse_capture_with_marker("plain line\nanother", "$ ", "");
+        assert!(none.heartbeat_line.is_none());
+    }
+
+    #[test]
+    fn spinner_redraws_do_not_hide_prompt() {
+        let output = "compiling\r| working\r/ working\r- working\rdone     \n$ ";
+        let result = parse_capture(output, "$ ");
+        assert_eq!(result.state, AgentState::Ready);
+        let busy = parse_capture("HEARTBEAT 1\n\r| 10%\r/ 50%\r- 90%", "$ ");
+        assert_eq!(busy.last_line, "- 90%");
+        assert_eq!(busy.heartbeat_line.as_deref(), Some("HEARTBEAT 1"));
+    }
+
+    #[test]
+    fn heartbeat_line_found_after_carriage_return_redraw() {
+        let output = "spinner |\rHEARTBEAT step 3\nworking";
+        let result = parse_capture(output, "$ ");
+        assert_eq!(result.heartbeat_line.as_deref(), Some("HEARTBEAT step 3"));
+    }
+
+    #[test]
+    fn pattern_none_matches_builtin_detection() {
+        let output = "Working on it\nContext: 40%\n> ";
+        let builtin = parse_capture_with_marker(output, "$ ", "HEARTBEAT");
+        let with_none = parse_capture_with_pattern(output, "$ ", "HEARTBEAT", None);
+        assert_eq!(with_none.state, builtin.state);
+        assert_eq!(with_none.last_line, builtin.last_line);
+        assert_eq!(with_none.state, AgentState::Ready);
+        assert_eq!(with_none.context_percent, Some(40));
+    }
+
+    #[test]
+    fn ssh_prompt_pattern_extracts_state_and_context() {
+        let pattern =
+            HeartbeatPattern::new(r"^\[(?P<host>[\w.-]+) (?P<state>\w+) ctx=(?P<context>\d+)%\]\$\s*$").unwrap();
+        let busy = parse_capture_with_pattern("make -j8\n[gpu1 busy ctx=12%]$ ", "$ ", "HEARTBEAT", Some(&pattern));
+        assert_eq!(busy.state, AgentState::Busy);
+        assert_eq!(busy.context_percent, Some(12));
+        let idle = parse_capture_with_pattern("[gpu1 idle ctx=7%]$", "$ ", "HEARTBEAT", Some(&pattern));
+        assert_eq!(idle.state, AgentState::Ready);
+        assert_eq!(idle.context_percent, Some(7));
+    }
+
+    #[test]
+    fn pattern_replaces_builtin_prompt_detection() {
+        let pattern = HeartbeatPattern::new(r"^\[(?P<host>[^\]]+)\] \$\s*$").unwrap();
+        // The built-in "$ " check would call this Ready; the pattern does not match it.
+        let plain = parse_capture_with_pattern("output\nuser@box:~$ ", "$ ", "HEARTBEAT", Some(&pattern));
+        assert_eq!(plain.state, AgentState::Busy);
+        let ready = parse_capture_with_pattern("output\n[gpu1] $ ", "$ ", "HEARTBEAT", Some(&pattern));
+        assert_eq!(ready.state, AgentState::Ready);
+        // Errors still win when the pattern has no state group.
+        let error = parse_capture_with_pattern("Error: disk full\n[gpu1] $ ", "$ ", "HEARTBEAT", Some(&pattern));
+        assert_eq!(error.state, AgentState::Error);
+    }
+
+    #[test]
+    fn patterns_from_settings_skip_bad_entries() {
+        let mut settings = HashMap::new();
+        settings.insert("ssh".to_string(), r"\] \$".to_string());
+        settings.insert("console".to_string(), "(unclosed".to_string());
+        settings.insert("robot".to_string(), "x".to_string());
+        let (patterns, warnings) = HeartbeatPatterns::from_settings(&settings);
+        assert_eq!(patterns.get(&AgentType::Ssh).unwrap().as_str(), r"\] \$");
+        assert!(patterns.get(&AgentType::Console).is_none());
+        assert!(patterns.get(&AgentType::Claude).is_none());
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings[0].starts_with("heartbeat_pattern.console:"));
+        assert!(warnings[1].contains("unknown agent type"));
+    }
 }
@@ -1,11 +1,16 @@
 //! Monitoring subsystem — heartbeat parsing, health assessment, and cycle orchestration.
 //!
 //! The `heartbeat` module extracts agent state from raw tmux pane captures.
+//! The `render` module collapses `\r`/backspace redraws in those captures.
 //! The `health` module combines multiple signals into per-agent health
 //! assessments and classifies failure modes.
 //! The `cycle` module orchestrates one monitoring pass: capture → parse →
 //! assess → deliver messages → check timeouts.
+//! The `exec` module decides when an `agent.exec` command's output has
+//! settled and extracts what it printed.
 
 pub mod cycle;
+pub mod exec;
 pub mod health;
 pub mod heartbeat;
+pub mod render;
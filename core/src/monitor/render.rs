@@ -0,0 +1,91 @@
+//! Output normalizer — collapses terminal control sequences in captured
+//! pane output into the text a terminal would finally display.
+//!
+//! Progress bars and spinners redraw a line in place with carriage returns
+//! (`\r`) or backspaces. Captured raw, every intermediate frame survives and
+//! renders as garbled noise; worse, the heartbeat parser sees spinner frames
+//! instead of the real last line. `normalize_output` replays each line the
+//! way a terminal would, so only the final state remains.
+
+/// Collapse carriage-return and backspace rewrites in `raw`.
+///
+/// Each `\n`-separated line is replayed on a virtual cursor: `\r` returns
+/// to column 0 and later characters overwrite earlier ones; a backspace
+/// moves the cursor one column left. Characters past the rewrite point are
+/// kept, as on a terminal. A `\r\n` line ending is treated as a plain `\n`.
+pub fn normalize_output(raw: &str) -> String {
+    if !raw.contains('\r') && !raw.contains('\u{8}') {
+        return raw.to_string();
+    }
+    raw.split('\n')
+        .map(collapse_line)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Replay one line's carriage returns and backspaces.
+fn collapse_line(line: &str) -> String {
+    let mut cells: Vec<char> = Vec::with_capacity(line.len());
+    let mut cursor: usize = 0;
+    for ch in line.chars() {
+        match ch {
+            '\r' => cursor = 0,
+            '\u{8}' => cursor = cursor.saturating_sub(1),
+            c => {
+                if cursor < cells.len() {
+                    cells[cursor] = c;
+                } else {
+                    cells.push(c);
+                }
+                cursor += 1;
+            }
+        }
+    }
+    cells.into_iter().collect()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_text_unchanged() {
+        let text = "line one\nline two\n";
+        assert_eq!(normalize_output(text), text);
+    }
+
+    #[test]
+    fn progress_collapses_to_final_frame() {
+        let raw = "Downloading  10%\rDownloading  55%\rDownloading 100%\ndone";
+        assert_eq!(normalize_output(raw), "Downloading 100%\ndone");
+    }
+
+    #[test]
+    fn shorter_overwrite_keeps_tail() {
+        // A terminal only overwrites as many columns as are written.
+        assert_eq!(normalize_output("abcdef\r12"), "12cdef");
+    }
+
+    #[test]
+    fn spinner_frames_collapse() {
+        let raw = "| Building\r/ Building\r- Building\r\\ Building\r✓ Built   \n$ ";
+        assert_eq!(normalize_output(raw), "✓ Built   \n$ ");
+    }
+
+    #[test]
+    fn backspace_spinner_collapses() {
+        let raw = "Working |\u{8}/\u{8}-\u{8}\\\u{8}|";
+        assert_eq!(normalize_output(raw), "Working |");
+    }
+
+    #[test]
+    fn crlf_line_endings_are_plain_newlines() {
+        assert_eq!(normalize_output("one\r\ntwo\r\n"), "one\ntwo\n");
+    }
+
+    #[test]
+    fn leading_backspace_does_not_underflow() {
+        assert_eq!(normalize_output("\u{8}\u{8}ok"), "ok");
+    }
+}
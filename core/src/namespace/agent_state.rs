@@ -75,14 +75,7 @@ impl AgentStateManager {
         let json = serde_json::to_string_pretty(state)
             .map_err(|e| format!("failed to serialize state: {}", e))?;
 
-        // Write to temp file, then rename for atomicity.
-        let tmp_path = parent.join(".state.json.tmp");
-        fs::write(&tmp_path, &json)
-            .map_err(|e| format!("failed to write {}: {}", tmp_path.display(), e))?;
-        fs::rename(&tmp_path, &path)
-            .map_err(|e| format!("failed to rename {} to {}: {}", tmp_path.display(), path.display(), e))?;
-
-        Ok(())
+        crate::data::write_atomic(&path, &json)
     }
 
     /// Delete agent state (cleanup on agent kill).
@@ -408,8 +401,13 @@ mod tests {
         assert!(read.contains_key("big_field"));
 
         // No temp file should linger.
-        let tmp = dir.join("agents").join("worker").join("w1").join(".state.json.tmp");
-        assert!(!tmp.exists());
+        let agent_dir = dir.join("agents").join("worker").join("w1");
+        let leftovers: Vec<_> = fs::read_dir(&agent_dir)
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_name().to_string_lossy().ends_with(".tmp"))
+            .collect();
+        assert!(leftovers.is_empty());
     }
 
     #[test]
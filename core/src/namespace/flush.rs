@@ -3,6 +3,11 @@
 //! Manages periodic persistence of store state to disk. Tracks dirty files,
 //! detects external modifications via mtime, and resolves conflicts where
 //! a file is both locally dirty and externally modified.
+//!
+//! The manager does not know file formats: `flush` and
+//! `flush_transactional` take a callback that renders each dirty file's
+//! content. `flush` writes what it can; `flush_transactional` writes every
+//! file to a temp sibling first and renames them only once all succeeded.
 
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
@@ -88,6 +93,107 @@ impl FlushManager {
         }
     }
 
+    /// Best-effort flush: render and atomically write each dirty file.
+    ///
+    /// Files that fail stay dirty and are returned with their error; the
+    /// rest are recorded as written.
+    pub fn flush<F>(&mut self, mut render: F) -> Vec<(PathBuf, String)>
+    where
+        F: FnMut(&Path) -> Result<String, String>,
+    {
+        let mut failures = Vec::new();
+        for path in self.sorted_dirty() {
+            let result = render(&path).and_then(|content| crate::data::write_atomic(&path, &content));
+            match result {
+                Ok(()) => self.record_write(&path),
+                Err(e) => failures.push((path, e)),
+            }
+        }
+        failures
+    }
+
+    /// All-or-nothing flush of every dirty file.
+    ///
+    /// Each file is rendered and written to a synced temp sibling. If any
+    /// render or write fails, all temps are deleted, nothing on disk
+    /// changes, and every file stays dirty. Otherwise the temps are renamed
+    /// into place; if a rename fails, files already replaced are restored
+    /// from backups. Files are recorded as written only once every rename
+    /// has succeeded.
+    pub fn flush_transactional<F>(&mut self, mut render: F) -> Result<(), String>
+    where
+        F: FnMut(&Path) -> Result<String, String>,
+    {
+        let paths = self.sorted_dirty();
+        let mut staged: Vec<(PathBuf, PathBuf)> = Vec::new();
+        for path in &paths {
+            let result = render(path).and_then(|content| {
+                let tmp = crate::data::temp_sibling(path)?;
+                if let Err(e) = crate::data::write_synced(&tmp, &content) {
+                    let _ = std::fs::remove_file(&tmp);
+                    return Err(e);
+                }
+                Ok(tmp)
+            });
+            match result {
+                Ok(tmp) => staged.push((tmp, path.clone())),
+                Err(e) => {
+                    for (tmp, _) in &staged {
+                        let _ = std::fs::remove_file(tmp);
+                    }
+                    return Err(format!("flush of {} aborted: {}", path.display(), e));
+                }
+            }
+        }
+
+        // Each committed file keeps a backup of what it replaced (None if
+        // it did not exist) until the whole batch is in place.
+        let mut committed: Vec<(&PathBuf, Option<PathBuf>)> = Vec::new();
+        for (i, (tmp, path)) in staged.iter().enumerate() {
+            let renamed = backup_of(path).and_then(|backup| {
+                std::fs::rename(tmp, path)
+                    .map(|_| backup.clone())
+                    .map_err(|e| {
+                        if let Some(b) = &backup {
+                            let _ = std::fs::remove_file(b);
+                        }
+                        format!("cannot rename {} to {}: {}", tmp.display(), path.display(), e)
+                    })
+            });
+            match renamed {
+                Ok(backup) => committed.push((path, backup)),
+                Err(e) => {
+                    for (rest, _) in &staged[i..] {
+                        let _ = std::fs::remove_file(rest);
+                    }
+                    for (done, backup) in committed.iter().rev() {
+                        let _ = match backup {
+                            Some(b) => std::fs::rename(b, done),
+                            None => std::fs::remove_file(done),
+                        };
+                    }
+                    return Err(e);
+                }
+            }
+        }
+
+        for (path, backup) in committed {
+            if let Some(b) = backup {
+                let _ = std::fs::remove_file(b);
+            }
+            let _ = crate::data::sync_parent_dir(path);
+            self.record_write(path);
+        }
+        Ok(())
+    }
+
+    /// Dirty files in a stable order.
+    fn sorted_dirty(&self) -> Vec<PathBuf> {
+        let mut paths: Vec<PathBuf> = self.dirty_files.iter().cloned().collect();
+        paths.sort();
+        paths
+    }
+
     /// Clear all dirty state.
     pub fn clear(&mut self) {
         self.dirty_files.clear();
@@ -143,6 +249,19 @@ impl Default for FlushManager {
     }
 }
 
+/// Preserve the current contents of `path`, if it exists, in a temp
+/// sibling (a hard link where possible) so a failed batch can restore it.
+fn backup_of(path: &Path) -> Result<Option<PathBuf>, String> {
+    if !path.is_file() {
+        return Ok(None);
+    }
+    let backup = crate::data::temp_sibling(path)?;
+    std::fs::hard_link(path, &backup)
+        .or_else(|_| std::fs::copy(path, &backup).map(|_| ()))
+        .map_err(|e| format!("cannot back up {}: {}", path.display(), e))?;
+    Ok(Some(backup))
+}
+
 
 // ---------------------------------------------------------------------------
 // Tests
@@ -339,4 +458,94 @@ mod tests {
         assert_eq!(fm.dirty_count(), 0);
         assert_eq!(fm.registered_count(), 0);
     }
+
+    fn dir_entries(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn flush_transactional_rolls_back_on_failure() {
+        let dir = test_dir("txn_rollback");
+        let a = dir.join("a.json");
+        let b = dir.join("b.json");
+        let c = dir.join("c.json");
+        fs::write(&a, "old-a").unwrap();
+        let mut fm = FlushManager::new();
+        for p in [&a, &b, &c] {
+            fm.mark_dirty(p);
+        }
+
+        let err = fm
+            .flush_transactional(|p| {
+                if p.ends_with("b.json") {
+                    Err("cannot serialize b".to_string())
+                } else {
+                    Ok(format!("new-{}", p.file_name().unwrap().to_string_lossy()))
+                }
+            })
+            .unwrap_err();
+        assert!(err.contains("cannot serialize b"), "{}", err);
+
+        // No temps and no partial writes; every file is still dirty.
+        assert_eq!(dir_entries(&dir), vec!["a.json"]);
+        assert_eq!(fs::read_to_string(&a).unwrap(), "old-a");
+        assert_eq!(fm.dirty_count(), 3);
+
+        fm.flush_transactional(|p| Ok(format!("new-{}", p.file_name().unwrap().to_string_lossy())))
+            .unwrap();
+        assert_eq!(dir_entries(&dir), vec!["a.json", "b.json", "c.json"]);
+        assert_eq!(fs::read_to_string(&b).unwrap(), "new-b.json");
+        assert_eq!(fm.dirty_count(), 0);
+    }
+
+    #[test]
+    fn flush_transactional_restores_files_when_a_rename_fails() {
+        let dir = test_dir("txn_rename_fail");
+        let a = dir.join("a.json");
+        let b = dir.join("b.json");
+        let c = dir.join("c.json");
+        fs::write(&a, "old-a").unwrap();
+        // A non-empty directory at b's path makes its rename fail after a's
+        // has already gone through.
+        fs::create_dir_all(b.join("inner")).unwrap();
+        let mut fm = FlushManager::new();
+        for p in [&a, &b, &c] {
+            fm.mark_dirty(p);
+        }
+
+        let err = fm.flush_transactional(|_| Ok("new".to_string())).unwrap_err();
+        assert!(err.contains("cannot rename"), "{}", err);
+        assert_eq!(fs::read_to_string(&a).unwrap(), "old-a");
+        assert!(!c.exists());
+        assert_eq!(dir_entries(&dir), vec!["a.json", "b.json"]);
+        assert_eq!(fm.dirty_count(), 3);
+    }
+
+    #[test]
+    fn flush_best_effort_keeps_failures_dirty() {
+        let dir = test_dir("best_effort");
+        let a = dir.join("a.json");
+        let b = dir.join("b.json");
+        let mut fm = FlushManager::new();
+        fm.mark_dirty(&a);
+        fm.mark_dirty(&b);
+
+        let failures = fm.flush(|p| {
+            if p.ends_with("b.json") {
+                Err("bad".to_string())
+            } else {
+                Ok("{}".to_string())
+            }
+        });
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].0, b);
+        assert!(a.exists());
+        assert!(fm.dirty_files().contains(&b));
+        assert!(!fm.dirty_files().contains(&a));
+    }
 }
@@ -0,0 +1,221 @@
+//! Optional schemas for parameter store paths.
+//!
+//! The store accepts any JSON value at any path, so a typo such as
+//! `task.X.staus` goes unnoticed. A schema registered for a path prefix
+//! type-checks every SET and APPEND under that prefix. A closed schema also
+//! names the leaves allowed directly below the prefix and rejects any other.
+//! Paths outside every registered prefix are not checked.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde_json::Value;
+
+use super::path::{NamespacePath, PathSegment};
+
+
+/// The JSON type a schema expects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueKind {
+    /// Any value is accepted.
+    Any,
+    Null,
+    Bool,
+    Number,
+    String,
+    Array,
+    Object,
+}
+
+impl ValueKind {
+    /// The kind of a concrete value (never `Any`).
+    pub fn of(value: &Value) -> ValueKind {
+        match value {
+            Value::Null => ValueKind::Null,
+            Value::Bool(_) => ValueKind::Bool,
+            Value::Number(_) => ValueKind::Number,
+            Value::String(_) => ValueKind::String,
+            Value::Array(_) => ValueKind::Array,
+            Value::Object(_) => ValueKind::Object,
+        }
+    }
+
+    /// True if `value` is of this kind.
+    pub fn accepts(&self, value: &Value) -> bool {
+        *self == ValueKind::Any || *self == ValueKind::of(value)
+    }
+}
+
+impl fmt::Display for ValueKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            ValueKind::Any => "any",
+            ValueKind::Null => "null",
+            ValueKind::Bool => "bool",
+            ValueKind::Number => "number",
+            ValueKind::String => "string",
+            ValueKind::Array => "array",
+            ValueKind::Object => "object",
+        };
+        f.write_str(s)
+    }
+}
+
+
+/// What a registered prefix expects.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Schema {
+    /// The prefix and every path under it hold values of this kind.
+    Value(ValueKind),
+    /// The prefix is a record: only these leaves may appear directly below
+    /// it, each with its kind. Paths deeper than a leaf only need the leaf
+    /// to be known.
+    Closed(BTreeMap<String, ValueKind>),
+}
+
+impl Schema {
+    /// A closed schema from `(leaf, kind)` pairs.
+    pub fn closed(fields: &[(&str, ValueKind)]) -> Schema {
+        Schema::Closed(fields.iter().map(|(k, v)| (k.to_string(), *v)).collect())
+    }
+}
+
+
+/// A schema bound to the prefix it governs.
+#[derive(Debug, Clone)]
+pub(crate) struct PrefixSchema {
+    prefix: NamespacePath,
+    schema: Schema,
+}
+
+impl PrefixSchema {
+    /// Bind `schema` to `prefix`. The prefix may use `*` and `$var`
+    /// segments but not `**`, so it always spans a fixed number of segments.
+    pub(crate) fn new(prefix: &str, schema: Schema) -> Result<Self, String> {
+        let prefix = NamespacePath::parse(prefix)?;
+        if prefix.segments.contains(&PathSegment::DoubleWildcard) {
+            return Err(format!("schema prefix '{}' cannot use '**'", prefix));
+        }
+        Ok(PrefixSchema { prefix, schema })
+    }
+
+    pub(crate) fn prefix(&self) -> &NamespacePath {
+        &self.prefix
+    }
+
+    /// Check a write of `value` to `path`. Paths outside the prefix pass.
+    pub(crate) fn check(&self, path: &NamespacePath, value: &Value) -> Result<(), String> {
+        let depth = self.prefix.segments.len();
+        if path.segments.len() < depth {
+            return Ok(());
+        }
+        let head = NamespacePath {
+            namespace: path.namespace.clone(),
+            segments: path.segments[..depth].to_vec(),
+        };
+        if self.prefix.match_path(&head).is_none() {
+            return Ok(());
+        }
+
+        match &self.schema {
+            Schema::Value(kind) => expect_kind(path, *kind, value),
+            Schema::Closed(fields) => match path.segments.get(depth) {
+                // Writing the whole record: every key must be a known leaf.
+                None => match value {
+                    Value::Object(map) => map.iter().try_for_each(|(key, v)| {
+                        let kind = self.field(fields, path, key)?;
+                        expect_kind(path, kind, v)
+                    }),
+                    other => Err(format!(
+                        "type mismatch at {}: expected object, got {}",
+                        path,
+                        ValueKind::of(other)
+                    )),
+                },
+                Some(leaf) => {
+                    let kind = self.field(fields, path, &leaf.to_string())?;
+                    if path.segments.len() == depth + 1 {
+                        expect_kind(path, kind, value)
+                    } else {
+                        Ok(())
+                    }
+                }
+            },
+        }
+    }
+
+    fn field(
+        &self,
+        fields: &BTreeMap<String, ValueKind>,
+        path: &NamespacePath,
+        leaf: &str,
+    ) -> Result<ValueKind, String> {
+        fields.get(leaf).copied().ok_or_else(|| {
+            let known: Vec<&str> = fields.keys().map(|k| k.as_str()).collect();
+            format!(
+                "unknown field '{}' at {} (schema {} allows: {})",
+                leaf,
+                path,
+                self.prefix,
+                known.join(", ")
+            )
+        })
+    }
+}
+
+/// Check that `value` at `path` is of `kind`.
+pub(crate) fn expect_kind(path: &NamespacePath, kind: ValueKind, value: &Value) -> Result<(), String> {
+    if kind.accepts(value) {
+        Ok(())
+    } else {
+        Err(format!(
+            "type mismatch at {}: expected {}, got {}",
+            path,
+            kind,
+            ValueKind::of(value)
+        ))
+    }
+}
+
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn path(s: &str) -> NamespacePath {
+        NamespacePath::parse(s).unwrap()
+    }
+
+    #[test]
+    fn value_kind_accepts() {
+        assert!(ValueKind::Number.accepts(&json!(3)));
+        assert!(!ValueKind::Number.accepts(&json!("3")));
+        assert!(ValueKind::Any.accepts(&json!(null)));
+        assert_eq!(ValueKind::of(&json!([1])), ValueKind::Array);
+    }
+
+    #[test]
+    fn paths_outside_prefix_pass() {
+        let s = PrefixSchema::new("task.*", Schema::closed(&[("status", ValueKind::String)])).unwrap();
+        assert!(s.check(&path("agent.w1.staus"), &json!(1)).is_ok());
+        assert!(s.check(&path("task"), &json!(1)).is_ok());
+    }
+
+    #[test]
+    fn closed_record_write_checks_keys() {
+        let s = PrefixSchema::new("task.*", Schema::closed(&[("status", ValueKind::String)])).unwrap();
+        assert!(s.check(&path("task.T1"), &json!({"status": "done"})).is_ok());
+        assert!(s.check(&path("task.T1"), &json!({"staus": "done"})).is_err());
+        assert!(s.check(&path("task.T1"), &json!("done")).is_err());
+    }
+
+    #[test]
+    fn double_wildcard_prefix_rejected() {
+        assert!(PrefixSchema::new("task.**", Schema::Value(ValueKind::String)).is_err());
+    }
+}
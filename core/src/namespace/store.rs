@@ -1,12 +1,20 @@
 //! Typed parameter store (M10.2).
 //!
 //! In-memory key-value store using `serde_json::Value` for typed values.
-//! Supports GET (with wildcard patterns), SET, APPEND, and dirty tracking
-//! for batch flush.
+//! Supports GET (with wildcard patterns), SET, APPEND, dirty tracking
+//! for batch flush, change subscriptions by path prefix, and optional
+//! per-prefix schemas that type-check writes.
+//!
+//! Changes can be observed two ways: `subscribe` pushes each write or
+//! removal into a channel, while `watch` buffers matching changes until the
+//! owner polls them with `drain_changes` (suited to a UI redrawing once per
+//! tick).
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::mpsc::{channel, Receiver, Sender};
 use serde_json::Value;
 use super::path::NamespacePath;
+use super::schema::{expect_kind, PrefixSchema, Schema, ValueKind};
 
 /// Alias for stored values — `serde_json::Value` supports all JSON types.
 pub type StoreValue = Value;
@@ -24,13 +32,46 @@ pub enum GetResult {
 }
 
 
+/// A value written or removed under a subscribed prefix.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChangeEvent {
+    /// Full dotted path that was written or removed.
+    pub path: String,
+    /// The value now stored at `path`, or `None` if it was removed.
+    pub value: Option<StoreValue>,
+}
+
+
+/// Most changes buffered per watch; once full, the oldest are dropped.
+pub const WATCH_BUFFER_LIMIT: usize = 1024;
+
+/// Handle for a watch created by `ParameterStore::watch`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SubscriptionId(u64);
+
+/// A polled subscription: its pattern and the changes not yet drained.
+#[derive(Debug)]
+struct Watch {
+    pattern: NamespacePath,
+    changes: VecDeque<(NamespacePath, Option<StoreValue>)>,
+}
+
+
 /// In-memory parameter store keyed by dotted path strings.
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub struct ParameterStore {
     /// All state, keyed by full dotted path string.
     data: HashMap<String, StoreValue>,
     /// Paths that have been modified since last flush.
     dirty: HashSet<String>,
+    /// Change subscribers, each with the prefix it listens under.
+    subscribers: Vec<(NamespacePath, Sender<ChangeEvent>)>,
+    /// Registered schemas; writes under their prefixes are checked.
+    schemas: Vec<PrefixSchema>,
+    /// Polled subscriptions, keyed by handle.
+    watches: HashMap<SubscriptionId, Watch>,
+    /// Next watch handle to allocate.
+    next_watch_id: u64,
 }
 
 impl ParameterStore {
@@ -39,6 +80,66 @@ impl ParameterStore {
         ParameterStore {
             data: HashMap::new(),
             dirty: HashSet::new(),
+            subscribers: Vec::new(),
+            schemas: Vec::new(),
+            watches: HashMap::new(),
+            next_watch_id: 1,
+        }
+    }
+
+    /// Register `schema` for writes at or under `prefix` (e.g. `task.*`).
+    ///
+    /// Later SETs and APPENDs under the prefix fail if the value has the
+    /// wrong type or, for a closed schema, names an unknown leaf. Paths
+    /// under no schema are unchecked. Registering a prefix again replaces
+    /// its schema. Values already stored are not re-validated.
+    pub fn register_schema(&mut self, prefix: &str, schema: Schema) -> Result<(), String> {
+        let entry = PrefixSchema::new(prefix, schema)?;
+        self.schemas.retain(|s| s.prefix() != entry.prefix());
+        self.schemas.push(entry);
+        Ok(())
+    }
+
+    /// Subscribe to writes at or under `prefix`.
+    ///
+    /// Every SET or APPEND whose path starts with the prefix's segments
+    /// (or matches it, for wildcard prefixes) sends a `ChangeEvent`.
+    /// Subscribers whose receiver has been dropped are pruned on the next
+    /// write, so dropping a receiver never makes a write fail.
+    pub fn subscribe(&mut self, prefix: NamespacePath) -> Receiver<ChangeEvent> {
+        let (tx, rx) = channel();
+        self.subscribers.push((prefix, tx));
+        rx
+    }
+
+    /// Number of live subscriptions (as of the last write).
+    pub fn subscriber_count(&self) -> usize {
+        self.subscribers.len()
+    }
+
+    /// Start buffering writes at or under `pattern` (matched as in
+    /// `subscribe`), to be collected with `drain_changes`. At most
+    /// `WATCH_BUFFER_LIMIT` changes are kept per watch.
+    pub fn watch(&mut self, pattern: NamespacePath) -> SubscriptionId {
+        let id = SubscriptionId(self.next_watch_id);
+        self.next_watch_id += 1;
+        self.watches.insert(id, Watch { pattern, changes: VecDeque::new() });
+        id
+    }
+
+    /// Stop a watch, discarding its undrained changes. Returns false if
+    /// `id` is not an active watch.
+    pub fn unwatch(&mut self, id: SubscriptionId) -> bool {
+        self.watches.remove(&id).is_some()
+    }
+
+    /// Take the changes recorded for watch `id` since the last drain, oldest
+    /// first; a removed path carries `None`. An unknown `id` yields no
+    /// changes.
+    pub fn drain_changes(&mut self, id: SubscriptionId) -> Vec<(NamespacePath, Option<StoreValue>)> {
+        match self.watches.get_mut(&id) {
+            Some(w) => w.changes.drain(..).collect(),
+            None => Vec::new(),
         }
     }
 
@@ -76,11 +177,46 @@ impl ParameterStore {
         if parsed.is_pattern() {
             return Err("cannot SET on a wildcard pattern".to_string());
         }
+        self.validate(&parsed, &value)?;
+        self.notify(&parsed, path, Some(&value));
         self.data.insert(path.to_string(), value);
         self.dirty.insert(path.to_string());
         Ok(())
     }
 
+    /// SET a value the caller expects to be of kind `expected`.
+    ///
+    /// Fails if the value is of another kind, then applies any registered
+    /// schema exactly as `set` does.
+    pub fn set_typed(
+        &mut self,
+        path: &NamespacePath,
+        value: StoreValue,
+        expected: ValueKind,
+    ) -> Result<(), String> {
+        expect_kind(path, expected, &value)?;
+        self.set(&path.to_string(), value)
+    }
+
+    /// GET the value at a concrete path, failing if it is not of kind
+    /// `expected`. A missing path is `Ok(None)`.
+    pub fn get_typed(
+        &self,
+        path: &NamespacePath,
+        expected: ValueKind,
+    ) -> Result<Option<StoreValue>, String> {
+        if path.is_pattern() {
+            return Err("cannot GET a typed value from a wildcard pattern".to_string());
+        }
+        match self.data.get(&path.to_string()) {
+            Some(v) => {
+                expect_kind(path, expected, v)?;
+                Ok(Some(v.clone()))
+            }
+            None => Ok(None),
+        }
+    }
+
     /// APPEND a value to a path with array semantics.
     ///
     /// - If the path doesn't exist, creates `[value]`.
@@ -92,15 +228,18 @@ impl ParameterStore {
             return Err("cannot APPEND on a wildcard pattern".to_string());
         }
 
-        let new_val = match self.data.remove(path) {
+        let new_val = match self.data.get(path) {
             None => Value::Array(vec![value]),
-            Some(Value::Array(mut arr)) => {
+            Some(Value::Array(arr)) => {
+                let mut arr = arr.clone();
                 arr.push(value);
                 Value::Array(arr)
             }
-            Some(existing) => Value::Array(vec![existing, value]),
+            Some(existing) => Value::Array(vec![existing.clone(), value]),
         };
+        self.validate(&parsed, &new_val)?;
 
+        self.notify(&parsed, path, Some(&new_val));
         self.data.insert(path.to_string(), new_val);
         self.dirty.insert(path.to_string());
         Ok(())
@@ -124,10 +263,14 @@ impl ParameterStore {
         }
     }
 
-    /// Remove a value at a concrete path.
+    /// Remove a value at a concrete path. Subscribers and watches see the
+    /// removal as a change with no value.
     pub fn remove(&mut self, path: &str) -> Option<StoreValue> {
         let removed = self.data.remove(path);
         if removed.is_some() {
+            if let Ok(parsed) = NamespacePath::parse(path) {
+                self.notify(&parsed, path, None);
+            }
             self.dirty.insert(path.to_string());
         }
         removed
@@ -160,6 +303,35 @@ impl ParameterStore {
     // Internal
     // -------------------------------------------------------------------
 
+    fn validate(&self, parsed: &NamespacePath, value: &StoreValue) -> Result<(), String> {
+        self.schemas.iter().try_for_each(|s| s.check(parsed, value))
+    }
+
+    fn notify(&mut self, parsed: &NamespacePath, path: &str, value: Option<&StoreValue>) {
+        for watch in self.watches.values_mut() {
+            if !is_under(&watch.pattern, parsed) {
+                continue;
+            }
+            if watch.changes.len() >= WATCH_BUFFER_LIMIT {
+                watch.changes.pop_front();
+            }
+            watch.changes.push_back((parsed.clone(), value.cloned()));
+        }
+        if self.subscribers.is_empty() {
+            return;
+        }
+        self.subscribers.retain(|(prefix, tx)| {
+            if !is_under(prefix, parsed) {
+                return true;
+            }
+            tx.send(ChangeEvent {
+                path: path.to_string(),
+                value: value.cloned(),
+            })
+            .is_ok()
+        });
+    }
+
     fn keys_matching_parsed(&self, pattern: &NamespacePath) -> Vec<String> {
         self.data
             .keys()
@@ -175,6 +347,33 @@ impl ParameterStore {
     }
 }
 
+/// Cloning copies the data and schemas but not the subscriptions or
+/// watches; writes to the copy are not reported to the original's
+/// subscribers.
+impl Clone for ParameterStore {
+    fn clone(&self) -> Self {
+        ParameterStore {
+            data: self.data.clone(),
+            dirty: self.dirty.clone(),
+            subscribers: Vec::new(),
+            schemas: self.schemas.clone(),
+            watches: HashMap::new(),
+            next_watch_id: 1,
+        }
+    }
+}
+
+/// True if `path` is at or below `prefix`.
+fn is_under(prefix: &NamespacePath, path: &NamespacePath) -> bool {
+    if prefix.namespace != path.namespace {
+        return false;
+    }
+    if prefix.is_pattern() {
+        return prefix.match_path(path).is_some();
+    }
+    path.segments.starts_with(&prefix.segments)
+}
+
 impl Default for ParameterStore {
     fn default() -> Self {
         Self::new()
@@ -189,6 +388,7 @@ impl Default for ParameterStore {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::namespace::schema::ValueKind;
     use serde_json::json;
 
     #[test]
@@ -486,4 +686,179 @@ mod tests {
         assert!(store.dirty_paths().contains("config.b"));
         assert!(store.dirty_paths().contains("config.c"));
     }
+
+    #[test]
+    fn subscribe_delivers_sets_under_prefix() {
+        let mut store = ParameterStore::new();
+        let rx = store.subscribe(NamespacePath::parse("agent.worker1").unwrap());
+
+        store.set("agent.worker1.health", json!("healthy")).unwrap();
+        store.set("agent.worker2.health", json!("stalled")).unwrap();
+        store.set("task.AUTH1.status", json!("done")).unwrap();
+
+        let event = rx.try_recv().unwrap();
+        assert_eq!(event.path, "agent.worker1.health");
+        assert_eq!(event.value, Some(json!("healthy")));
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn subscribe_reports_appends() {
+        let mut store = ParameterStore::new();
+        let rx = store.subscribe(NamespacePath::parse("config.*").unwrap());
+        store.append("config.tags", json!("a")).unwrap();
+        assert_eq!(rx.try_recv().unwrap().value, Some(json!(["a"])));
+    }
+
+    #[test]
+    fn dropped_receiver_does_not_break_set() {
+        let mut store = ParameterStore::new();
+        let rx = store.subscribe(NamespacePath::parse("config").unwrap());
+        drop(rx);
+        store.set("config.a", json!(1)).unwrap();
+        store.set("config.b", json!(2)).unwrap();
+        assert_eq!(store.subscriber_count(), 0);
+        assert_eq!(store.len(), 2);
+    }
+
+    fn task_schema_store() -> ParameterStore {
+        let mut store = ParameterStore::new();
+        store
+            .register_schema(
+                "task.*",
+                Schema::closed(&[
+                    ("status", ValueKind::String),
+                    ("progress", ValueKind::Number),
+                    ("notes", ValueKind::Any),
+                ]),
+            )
+            .unwrap();
+        store
+    }
+
+    #[test]
+    fn schema_rejects_type_mismatch() {
+        let mut store = task_schema_store();
+        let err = store.set("task.T1.progress", json!("half")).unwrap_err();
+        assert!(err.contains("expected number, got string"), "{}", err);
+        assert!(store.is_empty());
+        assert!(store.dirty_paths().is_empty());
+        store.set("task.T1.progress", json!(50)).unwrap();
+    }
+
+    #[test]
+    fn closed_schema_rejects_unknown_leaf() {
+        let mut store = task_schema_store();
+        let err = store.set("task.X.staus", json!("done")).unwrap_err();
+        assert!(err.contains("unknown field 'staus'"), "{}", err);
+        assert!(err.contains("status"), "{}", err);
+        store.set("task.X.status", json!("done")).unwrap();
+    }
+
+    #[test]
+    fn schema_checks_appended_value() {
+        let mut store = ParameterStore::new();
+        store.register_schema("config.tags", Schema::Value(ValueKind::Array)).unwrap();
+        store.append("config.tags", json!("a")).unwrap();
+        store.set("config.name", json!(1)).unwrap();
+        assert!(store.set("config.tags", json!("a")).is_err());
+        assert!(store.append("config.tags.x", json!(1)).is_ok());
+        assert_eq!(store.len(), 3);
+    }
+
+    #[test]
+    fn unschemad_paths_are_unchecked() {
+        let mut store = task_schema_store();
+        store.set("agent.w1.anything", json!({"free": "form"})).unwrap();
+        store.append("task.T1.notes", json!("first")).unwrap();
+    }
+
+    #[test]
+    fn clone_does_not_share_subscribers() {
+        let mut store = ParameterStore::new();
+        let rx = store.subscribe(NamespacePath::parse("config").unwrap());
+        let mut copy = store.clone();
+        copy.set("config.a", json!(1)).unwrap();
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn set_typed_enforces_registered_schema() {
+        let mut store = ParameterStore::new();
+        store.register_schema("task.*.status", Schema::Value(ValueKind::String)).unwrap();
+        let status = NamespacePath::parse("task.AUTH1.status").unwrap();
+
+        // The caller's expectation matches, but the schema says string.
+        let err = store.set_typed(&status, json!(3), ValueKind::Number).unwrap_err();
+        assert!(err.contains("expected string, got number"), "{}", err);
+        assert!(store.is_empty());
+
+        store.set_typed(&status, json!("done"), ValueKind::String).unwrap();
+        assert_eq!(store.get_typed(&status, ValueKind::String).unwrap(), Some(json!("done")));
+        assert!(store.get_typed(&status, ValueKind::Number).is_err());
+
+        // Unregistered paths only check the caller's expectation.
+        let notes = NamespacePath::parse("agent.w1.notes").unwrap();
+        store.set_typed(&notes, json!(["a"]), ValueKind::Array).unwrap();
+        assert!(store.set_typed(&notes, json!("a"), ValueKind::Array).is_err());
+        assert_eq!(store.get_typed(&NamespacePath::parse("agent.w2.notes").unwrap(), ValueKind::Any).unwrap(), None);
+    }
+
+    #[test]
+    fn watch_drains_only_matching_changes() {
+        let mut store = ParameterStore::new();
+        let id = store.watch(NamespacePath::parse("agent.*.health").unwrap());
+
+        store.set("agent.w1.health", json!("ok")).unwrap();
+        store.set("agent.w2.health", json!("degraded")).unwrap();
+        store.set("agent.w1.status", json!("busy")).unwrap();
+        store.set("task.T1.health", json!("ok")).unwrap();
+
+        let changes = store.drain_changes(id);
+        let paths: Vec<String> = changes.iter().map(|(p, _)| p.to_string()).collect();
+        assert_eq!(paths, vec!["agent.w1.health", "agent.w2.health"]);
+        assert_eq!(changes[1].1, Some(json!("degraded")));
+
+        // Drained changes are not returned again.
+        assert!(store.drain_changes(id).is_empty());
+        store.append("agent.w2.health", json!("ok")).unwrap();
+        assert_eq!(store.drain_changes(id).len(), 1);
+
+        assert!(store.unwatch(id));
+        store.set("agent.w1.health", json!("down")).unwrap();
+        assert!(store.drain_changes(id).is_empty());
+    }
+
+    #[test]
+    fn remove_is_reported_to_subscribers_and_watches() {
+        let mut store = ParameterStore::new();
+        store.set("agent.w1.health", json!("ok")).unwrap();
+        let rx = store.subscribe(NamespacePath::parse("agent").unwrap());
+        let id = store.watch(NamespacePath::parse("agent.*.health").unwrap());
+
+        assert_eq!(store.remove("agent.w1.health"), Some(json!("ok")));
+        // Removing a missing path is not a change.
+        assert_eq!(store.remove("agent.w2.health"), None);
+
+        let event = rx.try_recv().unwrap();
+        assert_eq!(event.path, "agent.w1.health");
+        assert_eq!(event.value, None);
+        assert!(rx.try_recv().is_err());
+        let changes = store.drain_changes(id);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].0.to_string(), "agent.w1.health");
+        assert_eq!(changes[0].1, None);
+    }
+
+    #[test]
+    fn watch_buffer_is_bounded() {
+        let mut store = ParameterStore::new();
+        let id = store.watch(NamespacePath::parse("config").unwrap());
+        for i in 0..(WATCH_BUFFER_LIMIT + 5) {
+            store.set("config.counter", json!(i)).unwrap();
+        }
+        let changes = store.drain_changes(id);
+        assert_eq!(changes.len(), WATCH_BUFFER_LIMIT);
+        assert_eq!(changes[0].1, Some(json!(5)));
+    }
 }
@@ -5,8 +5,16 @@
 //! optional default, providing CRUD, label-based filtering, and YAML round-trip
 //! serialisation (hand-rolled, no external YAML crate required).
 
+use std::path::Path;
+
 use serde::{Deserialize, Serialize};
 
+use crate::rig::connection::multiplex_options;
+
+
+/// File name of the persisted registry inside the config directory.
+pub const RIG_FILE: &str = "rig.yaml";
+
 
 // ---------------------------------------------------------------------------
 // RemoteConfig
@@ -31,6 +39,9 @@ pub struct RemoteConfig {
     pub gpu_count: Option<u32>,
     /// Arbitrary labels for filtering (e.g. "a100", "high-mem").
     pub labels: Vec<String>,
+    /// Reuse one persistent SSH master connection for all commands.
+    #[serde(default)]
+    pub multiplex: bool,
 }
 
 impl RemoteConfig {
@@ -41,6 +52,12 @@ impl RemoteConfig {
 
     /// Build base SSH arguments (port, key, user@host) without a command.
     pub fn ssh_base_args(&self) -> Vec<String> {
+        self.ssh_args(None)
+    }
+
+    /// Build SSH arguments like `ssh_base_args`, adding connection
+    /// multiplexing options when enabled and `config_dir` is known.
+    pub fn ssh_args(&self, config_dir: Option<&Path>) -> Vec<String> {
         let mut args = vec![
             "-p".to_string(),
             self.port.to_string(),
@@ -53,6 +70,7 @@ impl RemoteConfig {
             args.push("-i".to_string());
             args.push(key.clone());
         }
+        args.extend(multiplex_options(self, config_dir));
         args.push(self.user_at_host());
         args
     }
@@ -179,6 +197,9 @@ impl RigRegistry {
                     out.push_str(&format!("      - {}\n", label));
                 }
             }
+            if r.multiplex {
+                out.push_str("    multiplex: true\n");
+            }
         }
         out
     }
@@ -270,6 +291,11 @@ impl RigRegistry {
                     );
                 } else if trimmed.starts_with("labels:") {
                     in_labels = true;
+                } else if let Some(rest) = trimmed.strip_prefix("multiplex:") {
+                    partial.multiplex = rest
+                        .trim()
+                        .parse::<bool>()
+                        .map_err(|e| format!("line {}: bad multiplex: {}", line_no + 1, e))?;
                 }
             }
         }
@@ -292,6 +318,45 @@ impl RigRegistry {
 
         Ok(registry)
     }
+
+    /// Load a registry from a YAML file. A missing file yields an empty registry.
+    pub fn load(path: &Path) -> Result<RigRegistry, String> {
+        if !path.exists() {
+            return Ok(RigRegistry::new());
+        }
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("cannot read {}: {}", path.display(), e))?;
+        RigRegistry::parse_yaml(&content)
+    }
+
+    /// Like `load`, but an unreadable or corrupt file is moved aside to
+    /// `<file>.bad` so a later `save` can't overwrite it. Returns an empty
+    /// registry and a warning in that case. Fails if the file can't be
+    /// moved.
+    pub fn load_or_back_up(path: &Path) -> Result<(RigRegistry, Option<String>), String> {
+        match RigRegistry::load(path) {
+            Ok(registry) => Ok((registry, None)),
+            Err(e) => {
+                let mut backup = path.as_os_str().to_owned();
+                backup.push(".bad");
+                let backup = std::path::PathBuf::from(backup);
+                std::fs::rename(path, &backup).map_err(|re| {
+                    format!("{}; cannot move it to {}: {}", e, backup.display(), re)
+                })?;
+                let warning = format!(
+                    "{}; moved it to {} and started with no remotes",
+                    e,
+                    backup.display()
+                );
+                Ok((RigRegistry::new(), Some(warning)))
+            }
+        }
+    }
+
+    /// Write the registry to a YAML file, replacing it atomically.
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        crate::data::write_atomic(path, &self.serialize_yaml())
+    }
 }
 
 impl Default for RigRegistry {
@@ -315,6 +380,7 @@ struct PartialRemote {
     workspace_dir: Option<String>,
     gpu_count: Option<u32>,
     labels: Vec<String>,
+    multiplex: bool,
 }
 
 impl PartialRemote {
@@ -328,6 +394,7 @@ impl PartialRemote {
             workspace_dir: None,
             gpu_count: None,
             labels: Vec::new(),
+            multiplex: false,
         }
     }
 
@@ -347,6 +414,7 @@ impl PartialRemote {
                 .ok_or_else(|| format!("line ~{}: missing 'workspace_dir'", line_hint))?,
             gpu_count: self.gpu_count,
             labels: self.labels,
+            multiplex: self.multiplex,
         })
     }
 }
@@ -370,6 +438,7 @@ mod tests {
             workspace_dir: "/home/ubuntu/work".to_string(),
             gpu_count: None,
             labels: Vec::new(),
+            multiplex: false,
         }
     }
 
@@ -407,6 +476,25 @@ mod tests {
         assert!(args.contains(&"/home/me/.ssh/gpu_key".to_string()));
     }
 
+    #[test]
+    fn ssh_args_multiplex_before_destination() {
+        let mut cfg = make_config("r1");
+        cfg.multiplex = true;
+        let args = cfg.ssh_args(Some(Path::new("/cfg")));
+        assert!(args.contains(&"ControlMaster=auto".to_string()));
+        assert!(args.contains(&"ControlPath=/cfg/ssh/r1.sock".to_string()));
+        assert_eq!(args.last().unwrap(), "ubuntu@10.0.0.1");
+    }
+
+    #[test]
+    fn ssh_args_without_multiplex_match_base() {
+        let cfg = make_config("r1");
+        let args = cfg.ssh_args(Some(Path::new("/cfg")));
+        assert_eq!(args, cfg.ssh_base_args());
+        assert!(!args.iter().any(|a| a.starts_with("ControlMaster")));
+        assert!(!args.iter().any(|a| a.starts_with("ControlPath")));
+    }
+
     // -- RigRegistry CRUD --
 
     #[test]
@@ -560,6 +648,7 @@ mod tests {
             workspace_dir: "/data/workspace".to_string(),
             gpu_count: Some(4),
             labels: vec!["a100".to_string(), "high-mem".to_string()],
+            multiplex: true,
         };
         reg.add(cfg.clone()).unwrap();
 
@@ -568,6 +657,7 @@ mod tests {
         cfg.ssh_key = None;
         cfg.gpu_count = None;
         cfg.labels = Vec::new();
+        cfg.multiplex = false;
         reg.add(cfg).unwrap();
 
         reg.set_default("gpu-1").unwrap();
@@ -581,7 +671,9 @@ mod tests {
         assert_eq!(parsed.list()[0].port, 2222);
         assert_eq!(parsed.list()[0].gpu_count, Some(4));
         assert_eq!(parsed.list()[0].labels.len(), 2);
+        assert!(parsed.list()[0].multiplex);
         assert_eq!(parsed.list()[1].name, "cpu-1");
+        assert!(!parsed.list()[1].multiplex);
         assert!(parsed.list()[1].ssh_key.is_none());
         assert!(parsed.list()[1].gpu_count.is_none());
     }
@@ -631,4 +723,53 @@ remotes:
         assert!(reg.list().is_empty());
         assert!(reg.default_name().is_none());
     }
+
+    // -- Persistence --
+
+    #[test]
+    fn load_missing_file_is_empty() {
+        let path = std::env::temp_dir().join("cmx_rig_config_missing").join(RIG_FILE);
+        let reg = RigRegistry::load(&path).unwrap();
+        assert!(reg.list().is_empty());
+        assert!(reg.default_name().is_none());
+    }
+
+    #[test]
+    fn save_and_load_round_trip() {
+        let dir = std::env::temp_dir().join("cmx_rig_config_round_trip");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(RIG_FILE);
+
+        let mut reg = RigRegistry::new();
+        reg.add(make_labeled("gpu1", &["a100"])).unwrap();
+        reg.add(make_config("cpu1")).unwrap();
+        reg.set_default("gpu1").unwrap();
+        reg.save(&path).unwrap();
+
+        let loaded = RigRegistry::load(&path).unwrap();
+        assert_eq!(loaded.list(), reg.list());
+        assert_eq!(loaded.default_name(), Some("gpu1"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn load_or_back_up_moves_corrupt_file_aside() {
+        let dir = std::env::temp_dir().join("cmx_rig_config_corrupt");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(RIG_FILE);
+        std::fs::write(&path, "remotes:\n  - name: gpu1\n    port: not-a-port\n").unwrap();
+
+        let (reg, warning) = RigRegistry::load_or_back_up(&path).unwrap();
+        assert!(reg.list().is_empty());
+        assert!(warning.unwrap().contains("rig.yaml.bad"));
+        assert!(!path.exists());
+        let backup = dir.join(format!("{}.bad", RIG_FILE));
+        assert!(std::fs::read_to_string(&backup).unwrap().contains("not-a-port"));
+
+        let (_, warning) = RigRegistry::load_or_back_up(&path).unwrap();
+        assert!(warning.is_none());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
 }
@@ -1,13 +1,56 @@
-//! SSH connection state tracking.
+//! SSH connection state tracking and multiplexing options.
 //!
 //! `ConnectionTracker` maintains per-remote connection state, tracks attempt
 //! counts and timing, and implements exponential backoff for retry decisions.
 //! No actual SSH connections are opened here — this is pure state management.
+//!
+//! Remotes with `multiplex` enabled share one persistent SSH master
+//! connection: `multiplex_options` adds the `ControlMaster`/`ControlPath`
+//! flags so that every command after the first reuses the control socket
+//! instead of paying for a fresh handshake.
 
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
 use serde::{Deserialize, Serialize};
 
+use crate::rig::config::RemoteConfig;
+
+
+// ---------------------------------------------------------------------------
+// Multiplexing
+// ---------------------------------------------------------------------------
+
+/// Directory under the config dir holding SSH control sockets.
+pub const CONTROL_DIR: &str = "ssh";
+
+/// Seconds an idle master connection stays open after its last client exits.
+pub const CONTROL_PERSIST_SECS: u32 = 600;
+
+/// Control socket path for `remote` under `config_dir`.
+pub fn control_path(config_dir: &Path, remote: &str) -> PathBuf {
+    config_dir.join(CONTROL_DIR).join(format!("{}.sock", remote))
+}
+
+/// SSH `-o` options enabling connection reuse for `config`.
+///
+/// Empty unless the remote opts in with `multiplex` and a config dir is
+/// known to hold the control socket.
+pub fn multiplex_options(config: &RemoteConfig, config_dir: Option<&Path>) -> Vec<String> {
+    let dir = match config_dir {
+        Some(dir) if config.multiplex => dir,
+        _ => return Vec::new(),
+    };
+    vec![
+        "-o".to_string(),
+        "ControlMaster=auto".to_string(),
+        "-o".to_string(),
+        format!("ControlPath={}", control_path(dir, &config.name).display()),
+        "-o".to_string(),
+        format!("ControlPersist={}", CONTROL_PERSIST_SECS),
+    ]
+}
+
 
 // ---------------------------------------------------------------------------
 // ConnState
@@ -315,6 +358,51 @@ impl ConnectionTracker {
 mod tests {
     use super::*;
 
+    fn make_config(multiplex: bool) -> RemoteConfig {
+        RemoteConfig {
+            name: "gpu1".to_string(),
+            host: "10.0.0.1".to_string(),
+            port: 22,
+            user: "ubuntu".to_string(),
+            ssh_key: None,
+            workspace_dir: "/home/ubuntu/work".to_string(),
+            gpu_count: None,
+            labels: Vec::new(),
+            multiplex,
+        }
+    }
+
+    // -- Multiplexing --
+
+    #[test]
+    fn control_path_uses_remote_name() {
+        let path = control_path(Path::new("/cfg"), "gpu1");
+        assert_eq!(path, PathBuf::from("/cfg/ssh/gpu1.sock"));
+    }
+
+    #[test]
+    fn multiplex_options_when_enabled() {
+        let opts = multiplex_options(&make_config(true), Some(Path::new("/cfg")));
+        assert_eq!(
+            opts,
+            vec![
+                "-o",
+                "ControlMaster=auto",
+                "-o",
+                "ControlPath=/cfg/ssh/gpu1.sock",
+                "-o",
+                "ControlPersist=600",
+            ]
+        );
+    }
+
+    #[test]
+    fn multiplex_options_absent_when_disabled() {
+        assert!(multiplex_options(&make_config(false), Some(Path::new("/cfg"))).is_empty());
+        // Without a config dir there is nowhere to put the socket.
+        assert!(multiplex_options(&make_config(true), None).is_empty());
+    }
+
     #[test]
     fn register_creates_disconnected() {
         let mut tracker = ConnectionTracker::new(3, 1000);
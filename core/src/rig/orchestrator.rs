@@ -6,13 +6,14 @@
 //! side effects (through the injected runner).
 
 use crate::infrastructure::runner::CommandRunner;
-use crate::rig::config::RigRegistry;
-use crate::rig::connection::ConnectionTracker;
-use crate::rig::remote::RemoteExecutor;
-use crate::rig::sync::SyncManager;
+use crate::rig::config::{RemoteConfig, RigRegistry};
+use crate::rig::connection::{ConnectionTracker, CONTROL_DIR};
+use crate::rig::remote::{parse_nvidia_smi, RemoteExecutor, GPU_PROBE_COMMAND};
+use crate::rig::sync::{RsyncPlan, SyncDirection, SyncManager, SyncOptions};
 use crate::rig::worker::WorkerPool;
 
 use std::fmt;
+use std::path::{Path, PathBuf};
 
 
 /// Orchestrates the rig lifecycle: connect, sync, execute, collect, decommission.
@@ -26,6 +27,9 @@ pub struct RigOrchestrator {
     pub executor: RemoteExecutor,
     pub workers: WorkerPool,
     runner: Box<dyn CommandRunner>,
+    config_dir: Option<PathBuf>,
+    /// Whether `health_check` also asks the remote for its GPU list.
+    probe_gpus: bool,
 }
 
 impl fmt::Debug for RigOrchestrator {
@@ -46,9 +50,40 @@ impl RigOrchestrator {
             executor: RemoteExecutor::new(300_000),
             workers: WorkerPool::new(4),
             runner,
+            config_dir: None,
+            probe_gpus: true,
         }
     }
 
+    /// Enable or disable the GPU probe in `health_check`.
+    pub fn with_gpu_probe(mut self, probe_gpus: bool) -> Self {
+        self.probe_gpus = probe_gpus;
+        self
+    }
+
+    /// Keep SSH control sockets for multiplexed remotes under `config_dir`.
+    /// Without a config dir, remotes never multiplex.
+    pub fn with_config_dir(mut self, config_dir: &Path) -> Self {
+        self.config_dir = Some(config_dir.to_path_buf());
+        self
+    }
+
+    /// Config dir to pass to the command builders for `config`. When the
+    /// remote multiplexes, the control socket directory is created first
+    /// because ssh will not create it.
+    fn control_root(&self, config: &RemoteConfig) -> Option<&Path> {
+        let dir = self.config_dir.as_deref()?;
+        if config.multiplex {
+            let _ = std::fs::create_dir_all(dir.join(CONTROL_DIR));
+        }
+        Some(dir)
+    }
+
+    /// SSH arguments (without a command) for `config`.
+    fn ssh_args(&self, config: &RemoteConfig) -> Vec<String> {
+        config.ssh_args(self.control_root(config))
+    }
+
     /// Initialize a remote: verify SSH connectivity, register in tracker.
     pub fn init_remote(&mut self, name: &str) -> Result<String, String> {
         let config = self
@@ -62,7 +97,7 @@ impl RigOrchestrator {
         self.connections.start_connecting(name, now)?;
 
         // Test SSH connectivity
-        let health_cmd = format!("ssh {} echo ok", config.ssh_base_args().join(" "));
+        let health_cmd = format!("ssh {} echo ok", self.ssh_args(&config).join(" "));
         match self.runner.run(&health_cmd) {
             Ok(output) if output.trim() == "ok" => {
                 let done = now_ms();
@@ -92,69 +127,159 @@ impl RigOrchestrator {
     }
 
     /// Push code to a remote via rsync.
-    pub fn push(&mut self, name: &str, local_path: &str) -> Result<String, String> {
+    pub fn push(&mut self, name: &str, local_path: &str, options: &SyncOptions) -> Result<String, String> {
+        let plan = self.plan(name, local_path, SyncDirection::Push, options)?;
+        let output = self.runner.run(&plan.command_line());
+        self.finish_plan(&plan, output)
+    }
+
+    /// Pull results from a remote via rsync.
+    pub fn pull(&mut self, name: &str, local_path: &str, options: &SyncOptions) -> Result<String, String> {
+        let plan = self.plan(name, local_path, SyncDirection::Pull, options)?;
+        let output = self.runner.run(&plan.command_line());
+        self.finish_plan(&plan, output)
+    }
+
+    /// Queue and start one sync job between `local_path` and the remote's
+    /// `workspace_dir`, returning the plan that carries it out. Report the
+    /// outcome with `finish_plan`.
+    pub fn plan(
+        &mut self,
+        name: &str,
+        local_path: &str,
+        direction: SyncDirection,
+        options: &SyncOptions,
+    ) -> Result<RsyncPlan, String> {
         let config = self
             .registry
             .get(name)
             .ok_or_else(|| format!("Remote '{}' not found", name))?
             .clone();
 
-        let job_id = self
-            .sync_manager
-            .queue_push(name, local_path, &config.workspace_dir);
-        let now = now_ms();
-        let job = self
-            .sync_manager
-            .start_next(now)
-            .ok_or_else(|| "Failed to start sync job".to_string())?
-            .clone();
+        let job_id = match direction {
+            SyncDirection::Push => self.sync_manager.queue_push(name, local_path, &config.workspace_dir),
+            SyncDirection::Pull => self.sync_manager.queue_pull(name, &config.workspace_dir, local_path),
+        };
+        let job = self.sync_manager.start(&job_id, now_ms())?.clone();
+        let args = self.sync_manager.build_rsync_args_with(&job, &config, self.control_root(&config), options);
+        Ok(RsyncPlan { job_id, remote: name.to_string(), direction, args })
+    }
 
-        let args = self.sync_manager.build_rsync_args(&job, &config);
-        let cmd = format!("rsync {}", args.join(" "));
+    /// Queue and start a push of `local_path` to every registered remote,
+    /// returning one plan per remote in registry order. Each remote's
+    /// `workspace_dir` is the destination. The plans are independent, so
+    /// they can run concurrently; report outcomes with `finish_plan`.
+    pub fn push_all(&mut self, local_path: &str, options: &SyncOptions) -> Vec<RsyncPlan> {
+        let names: Vec<String> = self.registry.list().iter().map(|r| r.name.clone()).collect();
+        // Planning only fails for an unregistered remote.
+        names
+            .iter()
+            .filter_map(|name| self.plan(name, local_path, SyncDirection::Push, options).ok())
+            .collect()
+    }
 
-        match self.runner.run(&cmd) {
+    /// Record the outcome of running `plan`, returning the push or pull
+    /// message for its remote.
+    pub fn finish_plan(
+        &mut self,
+        plan: &RsyncPlan,
+        result: Result<String, String>,
+    ) -> Result<String, String> {
+        let what = match plan.direction {
+            SyncDirection::Push => format!("Push to '{}'", plan.remote),
+            SyncDirection::Pull => format!("Pull from '{}'", plan.remote),
+        };
+        match result {
             Ok(output) => {
-                self.sync_manager.complete(&job_id, 0, now_ms())?;
-                Ok(format!("Push to '{}' complete\n{}", name, output))
+                self.sync_manager.complete(&plan.job_id, 0, now_ms())?;
+                Ok(format!("{} complete\n{}", what, output))
             }
             Err(e) => {
-                self.sync_manager.fail(&job_id, &e, now_ms())?;
-                Err(format!("Push to '{}' failed: {}", name, e))
+                self.sync_manager.fail(&plan.job_id, &e, now_ms())?;
+                Err(format!("{} failed: {}", what, e))
             }
         }
     }
 
-    /// Pull results from a remote via rsync.
-    pub fn pull(&mut self, name: &str, local_path: &str) -> Result<String, String> {
-        let config = self
-            .registry
-            .get(name)
-            .ok_or_else(|| format!("Remote '{}' not found", name))?
-            .clone();
+    /// Push code to every registered remote, running the plans from
+    /// `push_all` one after another through the runner. Callers wanting
+    /// concurrency run the plans themselves.
+    pub fn push_all_remotes(&mut self, local_path: &str, options: &SyncOptions) -> Result<String, String> {
+        let plans = self.push_all(local_path, options);
+        if plans.is_empty() {
+            return Err("No remotes registered".into());
+        }
+        self.fan_out(plans, "remotes", |rig, plan| {
+            let output = rig.runner.run(&plan.command_line());
+            rig.finish_plan(&plan, output)
+        })
+    }
 
-        let job_id = self
-            .sync_manager
-            .queue_pull(name, &config.workspace_dir, local_path);
-        let now = now_ms();
-        let job = self
-            .sync_manager
-            .start_next(now)
-            .ok_or_else(|| "Failed to start sync job".to_string())?
-            .clone();
+    /// Push code to every remote carrying `label`.
+    pub fn push_label(
+        &mut self,
+        label: &str,
+        local_path: &str,
+        options: &SyncOptions,
+    ) -> Result<String, String> {
+        self.for_label(label, |rig, name| rig.push(name, local_path, options))
+    }
 
-        let args = self.sync_manager.build_rsync_args(&job, &config);
-        let cmd = format!("rsync {}", args.join(" "));
+    /// Pull results from every remote carrying `label`.
+    pub fn pull_label(
+        &mut self,
+        label: &str,
+        local_path: &str,
+        options: &SyncOptions,
+    ) -> Result<String, String> {
+        self.for_label(label, |rig, name| rig.pull(name, local_path, options))
+    }
 
-        match self.runner.run(&cmd) {
-            Ok(output) => {
-                self.sync_manager.complete(&job_id, 0, now_ms())?;
-                Ok(format!("Pull from '{}' complete\n{}", name, output))
-            }
-            Err(e) => {
-                self.sync_manager.fail(&job_id, &e, now_ms())?;
-                Err(format!("Pull from '{}' failed: {}", name, e))
+    /// Run `op` against each remote carrying `label`, in registry order.
+    /// The result is `Err` if any of them failed or if no remote carries
+    /// the label.
+    fn for_label<F>(&mut self, label: &str, mut op: F) -> Result<String, String>
+    where
+        F: FnMut(&mut Self, &str) -> Result<String, String>,
+    {
+        let names: Vec<String> = self
+            .registry
+            .by_label(label)
+            .iter()
+            .map(|r| r.name.clone())
+            .collect();
+        if names.is_empty() {
+            return Err(format!("No remotes carry label '{}'", label));
+        }
+        let scope = format!("remotes labeled '{}'", label);
+        self.fan_out(names, &scope, |rig, name| op(rig, &name))
+    }
+
+    /// Run `op` against each of `targets`, one remote apiece. Every target
+    /// is attempted; the result is `Err` if any failed, counting failures
+    /// among the `scope` ("remotes", "remotes labeled 'gpu'").
+    fn fan_out<T, F>(&mut self, targets: Vec<T>, scope: &str, mut op: F) -> Result<String, String>
+    where
+        F: FnMut(&mut Self, T) -> Result<String, String>,
+    {
+        let total = targets.len();
+        let mut lines = Vec::new();
+        let mut failures = 0;
+        for target in targets {
+            match op(self, target) {
+                Ok(msg) => lines.push(msg),
+                Err(e) => {
+                    failures += 1;
+                    lines.push(e);
+                }
             }
         }
+
+        if failures == 0 {
+            Ok(lines.join("\n"))
+        } else {
+            Err(format!("{} of {} {} failed\n{}", failures, total, scope, lines.join("\n")))
+        }
     }
 
     /// Execute a command on a remote host via SSH.
@@ -179,7 +304,7 @@ impl RigOrchestrator {
             .get(&exec_id)
             .ok_or_else(|| "Execution not found after start".to_string())?
             .clone();
-        let args = self.executor.build_ssh_command(&exec, &config);
+        let args = self.executor.build_ssh_command(&exec, &config, self.control_root(&config));
         let cmd = format!("ssh {}", args.join(" "));
 
         match self.runner.run(&cmd) {
@@ -196,6 +321,10 @@ impl RigOrchestrator {
     }
 
     /// Check SSH health for a remote.
+    ///
+    /// With the GPU probe enabled, the same SSH call runs `nvidia-smi -L`;
+    /// a parsed count is stored as the remote's `gpu_count` and shown in
+    /// the report. A host without nvidia-smi is still healthy.
     pub fn health_check(&mut self, name: &str) -> Result<String, String> {
         let config = self
             .registry
@@ -207,15 +336,29 @@ impl RigOrchestrator {
         self.connections.register(name);
 
         let now = now_ms();
-        let health_cmd = format!("ssh {} echo ok", config.ssh_base_args().join(" "));
+        let remote_cmd = if self.probe_gpus {
+            format!("'echo ok; {} 2>/dev/null || true'", GPU_PROBE_COMMAND)
+        } else {
+            "echo ok".to_string()
+        };
+        let health_cmd = format!("ssh {} {}", self.ssh_args(&config).join(" "), remote_cmd);
         match self.runner.run(&health_cmd) {
-            Ok(_) => {
+            Ok(output) => {
                 let done = now_ms();
                 let latency = done.saturating_sub(now);
                 // Transition through Connecting before Connected
                 let _ = self.connections.start_connecting(name, now);
                 let _ = self.connections.mark_connected(name, done, latency);
-                Ok(format!("Remote '{}': healthy", name))
+                let gpus = if self.probe_gpus { parse_nvidia_smi(&output) } else { None };
+                match gpus {
+                    Some(count) => {
+                        if let Some(entry) = self.registry.get_mut(name) {
+                            entry.gpu_count = Some(count);
+                        }
+                        Ok(format!("Remote '{}': healthy, {} GPU(s)", name, count))
+                    }
+                    None => Ok(format!("Remote '{}': healthy", name)),
+                }
             }
             Err(e) => {
                 let _ = self.connections.start_connecting(name, now);
@@ -268,7 +411,7 @@ impl RigOrchestrator {
 
         let kill_cmd = format!(
             "ssh {} tmux kill-session -t cmx 2>/dev/null; echo done",
-            config.ssh_base_args().join(" ")
+            self.ssh_args(&config).join(" ")
         );
 
         self.runner
@@ -331,7 +474,18 @@ mod tests {
             workspace_dir: "/home/ubuntu/work".to_string(),
             gpu_count: None,
             labels: Vec::new(),
+            multiplex: false,
+        }
+    }
+
+    fn make_labeled_registry() -> RigRegistry {
+        let mut reg = RigRegistry::new();
+        for (name, labels) in [("r1", vec!["gpu"]), ("r2", vec!["cpu"]), ("r3", vec!["gpu", "a100"])] {
+            let mut config = make_config(name);
+            config.labels = labels.into_iter().map(String::from).collect();
+            reg.add(config).unwrap();
         }
+        reg
     }
 
     fn make_registry(name: &str) -> RigRegistry {
@@ -353,6 +507,26 @@ mod tests {
         assert!(rig.connections.is_connected("r1"));
     }
 
+    #[test]
+    fn multiplexed_remote_gets_control_socket() {
+        let dir = std::env::temp_dir().join(format!("cmx_rig_mux_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let mut config = make_config("r1");
+        let rig = RigOrchestrator::new(RigRegistry::new(), Box::new(MockRunner::new()))
+            .with_config_dir(&dir);
+
+        let args = rig.ssh_args(&config);
+        assert!(!args.iter().any(|a| a.starts_with("ControlPath")));
+        assert!(!dir.join(CONTROL_DIR).exists());
+
+        config.multiplex = true;
+        let args = rig.ssh_args(&config);
+        let expected = format!("ControlPath={}", dir.join("ssh/r1.sock").display());
+        assert!(args.contains(&expected));
+        assert!(dir.join(CONTROL_DIR).is_dir());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn init_remote_ssh_failure() {
         let registry = make_registry("r1");
@@ -390,7 +564,7 @@ mod tests {
         let runner =
             MockRunner::with_responses(vec![Ok("sending incremental file list\n".into())]);
         let mut rig = RigOrchestrator::new(registry, Box::new(runner));
-        let result = rig.push("r1", "/local/project");
+        let result = rig.push("r1", "/local/project", &SyncOptions::default());
         assert!(result.is_ok());
         assert!(result.unwrap().contains("Push to 'r1' complete"));
     }
@@ -400,17 +574,44 @@ mod tests {
         let registry = make_registry("r1");
         let runner = MockRunner::with_responses(vec![Err("rsync: connection unexpectedly closed".into())]);
         let mut rig = RigOrchestrator::new(registry, Box::new(runner));
-        let result = rig.push("r1", "/local/project");
+        let result = rig.push("r1", "/local/project", &SyncOptions::default());
         assert!(result.is_err());
         assert!(result.unwrap_err().contains("failed"));
     }
 
+    #[test]
+    fn push_label_fans_out_to_matching_remotes() {
+        let runner = MockRunner::with_responses(vec![Ok("ok\n".into()), Ok("ok\n".into())]);
+        let mut rig = RigOrchestrator::new(make_labeled_registry(), Box::new(runner));
+        let msg = rig.push_label("gpu", "/local/project", &SyncOptions::default()).unwrap();
+        assert!(msg.contains("Push to 'r1' complete"));
+        assert!(msg.contains("Push to 'r3' complete"));
+        assert!(!msg.contains("'r2'"));
+    }
+
+    #[test]
+    fn push_label_reports_partial_failure() {
+        let runner = MockRunner::with_responses(vec![Ok("ok\n".into()), Err("refused".into())]);
+        let mut rig = RigOrchestrator::new(make_labeled_registry(), Box::new(runner));
+        let err = rig.push_label("gpu", "/local/project", &SyncOptions::default()).unwrap_err();
+        assert!(err.contains("1 of 2 remotes labeled 'gpu' failed"));
+        assert!(err.contains("Push to 'r1' complete"));
+        assert!(err.contains("Push to 'r3' failed"));
+    }
+
+    #[test]
+    fn pull_label_matching_nothing_fails() {
+        let mut rig = RigOrchestrator::new(make_labeled_registry(), Box::new(MockRunner::new()));
+        let err = rig.pull_label("tpu", "/local/results", &SyncOptions::default()).unwrap_err();
+        assert!(err.contains("No remotes carry label 'tpu'"));
+    }
+
     #[test]
     fn pull_success() {
         let registry = make_registry("r1");
         let runner = MockRunner::with_responses(vec![Ok("receiving file list\n".into())]);
         let mut rig = RigOrchestrator::new(registry, Box::new(runner));
-        let result = rig.pull("r1", "/local/results");
+        let result = rig.pull("r1", "/local/results", &SyncOptions::default());
         assert!(result.is_ok());
         assert!(result.unwrap().contains("Pull from 'r1' complete"));
     }
@@ -445,6 +646,29 @@ mod tests {
         assert!(rig.connections.is_connected("r1"));
     }
 
+    #[test]
+    fn health_check_records_gpu_count() {
+        let registry = make_registry("r1");
+        let output = "ok\nGPU 0: NVIDIA A100 (UUID: GPU-a)\nGPU 1: NVIDIA A100 (UUID: GPU-b)\n";
+        let runner = MockRunner::with_responses(vec![Ok(output.into())]);
+        let mut rig = RigOrchestrator::new(registry, Box::new(runner));
+        assert_eq!(rig.registry.get("r1").unwrap().gpu_count, None);
+
+        let msg = rig.health_check("r1").unwrap();
+        assert!(msg.contains("2 GPU(s)"));
+        assert_eq!(rig.registry.get("r1").unwrap().gpu_count, Some(2));
+    }
+
+    #[test]
+    fn health_check_without_probe_leaves_gpu_count() {
+        let registry = make_registry("r1");
+        let runner = MockRunner::with_responses(vec![Ok("ok\nGPU 0: X (UUID: GPU-a)\n".into())]);
+        let mut rig = RigOrchestrator::new(registry, Box::new(runner)).with_gpu_probe(false);
+        let msg = rig.health_check("r1").unwrap();
+        assert_eq!(msg, "Remote 'r1': healthy");
+        assert_eq!(rig.registry.get("r1").unwrap().gpu_count, None);
+    }
+
     #[test]
     fn health_check_failure() {
         let registry = make_registry("r1");
@@ -511,10 +735,65 @@ mod tests {
         let mut rig = RigOrchestrator::new(registry, Box::new(runner));
 
         assert!(rig.init_remote("ghost").is_err());
-        assert!(rig.push("ghost", "/path").is_err());
-        assert!(rig.pull("ghost", "/path").is_err());
+        assert!(rig.push("ghost", "/path", &SyncOptions::default()).is_err());
+        assert!(rig.pull("ghost", "/path", &SyncOptions::default()).is_err());
         assert!(rig.execute_remote("ghost", "cmd", None).is_err());
         assert!(rig.status("ghost").is_err());
         assert!(rig.stop("ghost").is_err());
     }
+
+    fn make_fleet_registry() -> RigRegistry {
+        let mut reg = RigRegistry::new();
+        for (name, host) in [("g1", "10.0.0.1"), ("g2", "10.0.0.2"), ("g3", "10.0.0.3")] {
+            let mut config = make_config(name);
+            config.host = host.to_string();
+            if name == "g3" {
+                config.workspace_dir = "/scratch/exp".to_string();
+                config.port = 2222;
+            }
+            reg.add(config).unwrap();
+        }
+        reg
+    }
+
+    #[test]
+    fn push_all_builds_argv_per_remote() {
+        let mut rig = RigOrchestrator::new(make_fleet_registry(), Box::new(MockRunner::new()));
+        let plans = rig.push_all("/src/proj", &SyncOptions::default());
+
+        assert_eq!(plans.len(), 3);
+        let remotes: Vec<&str> = plans.iter().map(|p| p.remote.as_str()).collect();
+        assert_eq!(remotes, vec!["g1", "g2", "g3"]);
+        assert_eq!(plans[0].args.last().unwrap(), "ubuntu@10.0.0.1:/home/ubuntu/work");
+        assert_eq!(plans[1].args.last().unwrap(), "ubuntu@10.0.0.2:/home/ubuntu/work");
+        assert_eq!(plans[2].args.last().unwrap(), "ubuntu@10.0.0.3:/scratch/exp");
+        assert!(plans[2].args.iter().any(|a| a.starts_with("ssh -p 2222")));
+        for plan in &plans {
+            assert_eq!(plan.args[plan.args.len() - 2], "/src/proj/");
+            assert!(plan.command_line().starts_with("rsync -avz"));
+        }
+
+        // All jobs run at once despite the manager's concurrency limit.
+        assert_eq!(rig.sync_manager.active_count(), 3);
+        assert_eq!(rig.sync_manager.pending_count(), 0);
+    }
+
+    #[test]
+    fn push_all_remotes_reports_each_remote() {
+        let runner = MockRunner::with_responses(vec![
+            Ok("sent".into()),
+            Err("connection refused".into()),
+            Ok("sent".into()),
+        ]);
+        let mut rig = RigOrchestrator::new(make_fleet_registry(), Box::new(runner));
+        let err = rig.push_all_remotes("/src/proj", &SyncOptions::default()).unwrap_err();
+
+        let lines: Vec<&str> = err.lines().collect();
+        assert_eq!(lines[0], "1 of 3 remotes failed");
+        assert!(lines.iter().any(|l| l.starts_with("Push to 'g1' complete")));
+        assert!(lines.contains(&"Push to 'g2' failed: connection refused"));
+        assert!(lines.iter().any(|l| l.starts_with("Push to 'g3' complete")));
+        assert_eq!(rig.sync_manager.active_count(), 0);
+        assert_eq!(rig.sync_manager.history().len(), 3);
+    }
 }
@@ -6,6 +6,7 @@
 //! executes the commands and reports results back.
 
 use std::collections::HashMap;
+use std::path::Path;
 
 use serde::{Deserialize, Serialize};
 
@@ -225,13 +226,15 @@ impl RemoteExecutor {
     /// Build the SSH command argument vector for an execution.
     ///
     /// The resulting `Vec<String>` can be passed to `std::process::Command`
-    /// with `"ssh"` as the program.
+    /// with `"ssh"` as the program. With `config_dir` set, multiplexed
+    /// remotes reuse their control socket.
     pub fn build_ssh_command(
         &self,
         exec: &RemoteExecution,
         config: &RemoteConfig,
+        config_dir: Option<&Path>,
     ) -> Vec<String> {
-        let mut args = config.ssh_base_args();
+        let mut args = config.ssh_args(config_dir);
         // Wrap the remote command in a single string argument so that the
         // remote shell handles pipes, redirects, etc.
         args.push(exec.command.clone());
@@ -255,6 +258,50 @@ impl RemoteExecutor {
 }
 
 
+// ---------------------------------------------------------------------------
+// GPU probing
+// ---------------------------------------------------------------------------
+
+/// Command that lists a host's GPUs, one `GPU <n>: ...` line each.
+pub const GPU_PROBE_COMMAND: &str = "nvidia-smi -L";
+
+/// Count the GPUs listed in `nvidia-smi -L` output.
+///
+/// Returns `Some(0)` for the "No devices found" message and `None` when the
+/// output holds neither GPU rows nor that message (e.g. nvidia-smi missing).
+pub fn parse_nvidia_smi(output: &str) -> Option<u32> {
+    let mut count = 0;
+    let mut no_devices = false;
+    for line in output.lines() {
+        let line = line.trim();
+        if is_gpu_row(line) {
+            count += 1;
+        } else if line.starts_with("No devices were found") || line.starts_with("No devices found") {
+            no_devices = true;
+        }
+    }
+    if count > 0 {
+        Some(count)
+    } else if no_devices {
+        Some(0)
+    } else {
+        None
+    }
+}
+
+/// True for lines like `GPU 0: NVIDIA A100-SXM4-80GB (UUID: GPU-...)`.
+fn is_gpu_row(line: &str) -> bool {
+    let rest = match line.strip_prefix("GPU ") {
+        Some(r) => r,
+        None => return false,
+    };
+    match rest.split_once(':') {
+        Some((index, _)) => !index.is_empty() && index.chars().all(|c| c.is_ascii_digit()),
+        None => false,
+    }
+}
+
+
 // ---------------------------------------------------------------------------
 // Tests
 // ---------------------------------------------------------------------------
@@ -273,6 +320,7 @@ mod tests {
             workspace_dir: "/home/ubuntu/work".to_string(),
             gpu_count: None,
             labels: Vec::new(),
+            multiplex: false,
         }
     }
 
@@ -286,6 +334,7 @@ mod tests {
             workspace_dir: "/data/work".to_string(),
             gpu_count: Some(8),
             labels: Vec::new(),
+            multiplex: false,
         }
     }
 
@@ -536,7 +585,7 @@ mod tests {
             stderr: None,
         };
         let config = test_config();
-        let args = executor.build_ssh_command(&exec, &config);
+        let args = executor.build_ssh_command(&exec, &config, None);
 
         assert!(args.contains(&"-p".to_string()));
         assert!(args.contains(&"22".to_string()));
@@ -560,7 +609,7 @@ mod tests {
             stderr: None,
         };
         let config = test_config_with_key();
-        let args = executor.build_ssh_command(&exec, &config);
+        let args = executor.build_ssh_command(&exec, &config, None);
 
         assert!(args.contains(&"-i".to_string()));
         assert!(args.contains(&"/keys/id_rsa".to_string()));
@@ -570,6 +619,35 @@ mod tests {
         assert_eq!(args.last().unwrap(), "ls -la /data");
     }
 
+    #[test]
+    fn build_ssh_command_multiplexed() {
+        let executor = RemoteExecutor::new(60_000);
+        let exec = RemoteExecution {
+            id: "exec-1".to_string(),
+            remote: "r1".to_string(),
+            command: "uptime".to_string(),
+            timeout_ms: None,
+            status: ExecStatus::Running,
+            started_ms: Some(1000),
+            completed_ms: None,
+            exit_code: None,
+            stdout: None,
+            stderr: None,
+        };
+        let mut config = test_config();
+        let dir = Path::new("/cfg");
+
+        let args = executor.build_ssh_command(&exec, &config, Some(dir));
+        assert!(!args.iter().any(|a| a.starts_with("ControlMaster")));
+        assert!(!args.iter().any(|a| a.starts_with("ControlPath")));
+
+        config.multiplex = true;
+        let args = executor.build_ssh_command(&exec, &config, Some(dir));
+        assert!(args.contains(&"ControlMaster=auto".to_string()));
+        assert!(args.contains(&"ControlPath=/cfg/ssh/r1.sock".to_string()));
+        assert_eq!(args.last().unwrap(), "uptime");
+    }
+
     #[test]
     fn build_ssh_command_complex_remote_command() {
         let executor = RemoteExecutor::new(60_000);
@@ -587,7 +665,7 @@ mod tests {
             stderr: None,
         };
         let config = test_config();
-        let args = executor.build_ssh_command(&exec, &config);
+        let args = executor.build_ssh_command(&exec, &config, None);
 
         // The complex command should be the last argument, passed as a single string.
         assert_eq!(
@@ -628,4 +706,29 @@ mod tests {
         assert_eq!(executor.get(&id2).unwrap().status, ExecStatus::TimedOut);
         assert_eq!(executor.get(&id3).unwrap().status, ExecStatus::Failed);
     }
+
+    // -- GPU probing --
+
+    #[test]
+    fn parse_nvidia_smi_counts_rows() {
+        assert_eq!(parse_nvidia_smi("No devices were found\n"), Some(0));
+        assert_eq!(
+            parse_nvidia_smi("GPU 0: NVIDIA GeForce RTX 4090 (UUID: GPU-1b2c)\n"),
+            Some(1)
+        );
+        let eight: String = (0..8)
+            .map(|i| format!("GPU {}: NVIDIA A100-SXM4-80GB (UUID: GPU-{:04x})\n", i, i))
+            .collect();
+        assert_eq!(parse_nvidia_smi(&eight), Some(8));
+        // Rows may follow other output, such as the health check's "ok".
+        assert_eq!(parse_nvidia_smi(&format!("ok\n{}", eight)), Some(8));
+    }
+
+    #[test]
+    fn parse_nvidia_smi_garbage_is_none() {
+        assert_eq!(parse_nvidia_smi(""), None);
+        assert_eq!(parse_nvidia_smi("ok\n"), None);
+        assert_eq!(parse_nvidia_smi("bash: nvidia-smi: command not found"), None);
+        assert_eq!(parse_nvidia_smi("GPU x: broken\nGPU 1 no colon"), None);
+    }
 }
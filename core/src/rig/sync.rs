@@ -5,10 +5,13 @@
 //! never spawns processes — the caller is responsible for execution.
 
 use std::collections::HashMap;
+use std::path::Path;
 
 use serde::{Deserialize, Serialize};
 
 use super::config::RemoteConfig;
+use super::connection::multiplex_options;
+use crate::infrastructure::shell::join_argv;
 
 
 // ---------------------------------------------------------------------------
@@ -70,6 +73,42 @@ pub struct SyncJob {
 }
 
 
+/// Per-call rsync settings. The default adds nothing to the argv.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SyncOptions {
+    /// Cap the transfer rate, in KiB/s (`--bwlimit=<n>`).
+    pub bwlimit_kbps: Option<u32>,
+    /// Patterns excluded in addition to the job's defaults
+    /// (`--exclude=<pat>` each).
+    pub excludes: Vec<String>,
+}
+
+
+/// A started sync job paired with the rsync arguments that carry it out.
+///
+/// Plans for different remotes are independent, so a caller may run a batch
+/// of them concurrently and report each outcome back by `job_id`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RsyncPlan {
+    /// ID of the running job this plan executes.
+    pub job_id: String,
+    /// Name of the remote host involved.
+    pub remote: String,
+    /// Whether the job pushes or pulls.
+    pub direction: SyncDirection,
+    /// Arguments for `rsync` (the program name is not included).
+    pub args: Vec<String>,
+}
+
+impl RsyncPlan {
+    /// The plan as one shell command line, with each argument escaped so
+    /// patterns and paths can't inject shell syntax.
+    pub fn command_line(&self) -> String {
+        format!("rsync {}", join_argv(&self.args))
+    }
+}
+
+
 // ---------------------------------------------------------------------------
 // SyncManager
 // ---------------------------------------------------------------------------
@@ -175,6 +214,22 @@ impl SyncManager {
         self.active.get(&id)
     }
 
+    /// Start a specific queued job regardless of the concurrency limit, for
+    /// callers that schedule the transfers themselves.
+    pub fn start(&mut self, job_id: &str, now_ms: u64) -> Result<&SyncJob, String> {
+        let pos = self
+            .queue
+            .iter()
+            .position(|j| j.id == job_id)
+            .ok_or_else(|| format!("no queued job '{}'", job_id))?;
+        let mut job = self.queue.remove(pos);
+        job.status = SyncStatus::Running;
+        job.started_ms = Some(now_ms);
+        let id = job.id.clone();
+        self.active.insert(id.clone(), job);
+        Ok(&self.active[&id])
+    }
+
     /// Mark a running job as completed.
     pub fn complete(&mut self, job_id: &str, bytes: u64, now_ms: u64) -> Result<(), String> {
         let mut job = self
@@ -228,13 +283,38 @@ impl SyncManager {
     ///
     /// The resulting `Vec<String>` can be passed to `std::process::Command`
     /// with `"rsync"` as the program.
-    pub fn build_rsync_args(&self, job: &SyncJob, config: &RemoteConfig) -> Vec<String> {
+    ///
+    /// With `config_dir` set, the `-e` transport reuses the remote's SSH
+    /// control socket when multiplexing is enabled.
+    pub fn build_rsync_args(
+        &self,
+        job: &SyncJob,
+        config: &RemoteConfig,
+        config_dir: Option<&Path>,
+    ) -> Vec<String> {
+        self.build_rsync_args_with(job, config, config_dir, &SyncOptions::default())
+    }
+
+    /// Like `build_rsync_args`, adding `--bwlimit=<n>` after the standard
+    /// flags and the extra exclude patterns after the job's own. The args
+    /// are unescaped argv; join them with `join_argv` to run through a
+    /// shell.
+    pub fn build_rsync_args_with(
+        &self,
+        job: &SyncJob,
+        config: &RemoteConfig,
+        config_dir: Option<&Path>,
+        options: &SyncOptions,
+    ) -> Vec<String> {
         let mut args: Vec<String> = Vec::new();
 
         // Standard flags: archive, compress, verbose, partial for resume.
         args.push("-avz".to_string());
         args.push("--partial".to_string());
         args.push("--progress".to_string());
+        if let Some(kbps) = options.bwlimit_kbps {
+            args.push(format!("--bwlimit={}", kbps));
+        }
 
         // SSH transport with port and optional key.
         let mut ssh_cmd = format!("ssh -p {}", config.port);
@@ -242,13 +322,16 @@ impl SyncManager {
         if let Some(ref key) = config.ssh_key {
             ssh_cmd.push_str(&format!(" -i {}", key));
         }
+        for opt in multiplex_options(config, config_dir) {
+            ssh_cmd.push(' ');
+            ssh_cmd.push_str(&opt);
+        }
         args.push("-e".to_string());
         args.push(ssh_cmd);
 
         // Exclude patterns.
-        for pattern in &job.exclude_patterns {
-            args.push("--exclude".to_string());
-            args.push(pattern.clone());
+        for pattern in job.exclude_patterns.iter().chain(&options.excludes) {
+            args.push(format!("--exclude={}", pattern));
         }
 
         // Source and destination depend on direction.
@@ -309,6 +392,7 @@ mod tests {
             workspace_dir: "/home/ubuntu/work".to_string(),
             gpu_count: None,
             labels: Vec::new(),
+            multiplex: false,
         }
     }
 
@@ -322,6 +406,7 @@ mod tests {
             workspace_dir: "/data/work".to_string(),
             gpu_count: Some(4),
             labels: vec!["a100".to_string()],
+            multiplex: false,
         }
     }
 
@@ -496,13 +581,12 @@ mod tests {
             error: None,
         };
         let config = test_config();
-        let args = mgr.build_rsync_args(&job, &config);
+        let args = mgr.build_rsync_args(&job, &config, None);
 
         assert!(args.contains(&"-avz".to_string()));
         assert!(args.contains(&"--partial".to_string()));
         assert!(args.contains(&"--progress".to_string()));
-        assert!(args.contains(&"--exclude".to_string()));
-        assert!(args.contains(&".git".to_string()));
+        assert!(args.contains(&"--exclude=.git".to_string()));
 
         // -e with ssh command
         let e_idx = args.iter().position(|a| a == "-e").unwrap();
@@ -517,6 +601,53 @@ mod tests {
         assert!(second_last.contains("/local/project"));
     }
 
+    #[test]
+    fn rsync_args_with_options_in_order() {
+        let mut mgr = SyncManager::new(2);
+        let id = mgr.queue_push("r1", "/local/project", "/remote/project");
+        let job = mgr.start(&id, 1000).unwrap().clone();
+        let config = test_config();
+
+        let plain = mgr.build_rsync_args(&job, &config, None);
+        assert!(!plain.iter().any(|a| a.starts_with("--bwlimit") || a == "--exclude=data/"));
+
+        let options = SyncOptions {
+            bwlimit_kbps: Some(500),
+            excludes: vec!["data/".to_string(), "*.ckpt".to_string()],
+        };
+        let args = mgr.build_rsync_args_with(&job, &config, None, &options);
+        assert_eq!(&args[..4], &["-avz", "--partial", "--progress", "--bwlimit=500"]);
+        let first = args.iter().position(|a| a == "--exclude=data/").unwrap();
+        assert_eq!(args[first + 1], "--exclude=*.ckpt");
+        // Extra excludes follow the defaults and precede source/destination.
+        let last_default = args.iter().position(|a| a == "--exclude=node_modules/").unwrap();
+        assert!(last_default < first);
+        assert_eq!(first + 2, args.len() - 2);
+        assert_eq!(args.len(), plain.len() + 3);
+    }
+
+    #[test]
+    fn rsync_command_line_escapes_patterns() {
+        let mut mgr = SyncManager::new(2);
+        let id = mgr.queue_push("r1", "/local/my project", "/remote/project");
+        let job = mgr.start(&id, 1000).unwrap().clone();
+        let options = SyncOptions {
+            bwlimit_kbps: None,
+            excludes: vec!["x; rm -rf ~".to_string(), "*.ckpt".to_string()],
+        };
+        let plan = RsyncPlan {
+            job_id: id,
+            remote: "r1".to_string(),
+            direction: SyncDirection::Push,
+            args: mgr.build_rsync_args_with(&job, &test_config(), None, &options),
+        };
+        let line = plan.command_line();
+        assert!(line.contains("'--exclude=x; rm -rf ~'"));
+        assert!(line.contains("'--exclude=*.ckpt'"));
+        assert!(line.contains("'/local/my project/'"));
+        assert!(line.contains("-e 'ssh -p 22 "));
+    }
+
     #[test]
     fn rsync_args_pull() {
         let mgr = SyncManager::new(2);
@@ -534,7 +665,7 @@ mod tests {
             error: None,
         };
         let config = test_config();
-        let args = mgr.build_rsync_args(&job, &config);
+        let args = mgr.build_rsync_args(&job, &config, None);
 
         // Source should be remote spec, dest is local with trailing slash.
         let last = args.last().unwrap();
@@ -561,7 +692,7 @@ mod tests {
             error: None,
         };
         let config = test_config_with_key();
-        let args = mgr.build_rsync_args(&job, &config);
+        let args = mgr.build_rsync_args(&job, &config, None);
 
         let e_idx = args.iter().position(|a| a == "-e").unwrap();
         let ssh_cmd = &args[e_idx + 1];
@@ -569,6 +700,36 @@ mod tests {
         assert!(ssh_cmd.contains("-i /keys/gpu.pem"));
     }
 
+    #[test]
+    fn rsync_args_multiplex_transport() {
+        let mgr = SyncManager::new(2);
+        let job = SyncJob {
+            id: "sync-5".to_string(),
+            remote: "r1".to_string(),
+            direction: SyncDirection::Push,
+            local_path: "/local/a".to_string(),
+            remote_path: "/remote/a".to_string(),
+            exclude_patterns: Vec::new(),
+            status: SyncStatus::Running,
+            started_ms: Some(1000),
+            completed_ms: None,
+            bytes_transferred: None,
+            error: None,
+        };
+        let mut config = test_config();
+        let dir = Path::new("/cfg");
+
+        let args = mgr.build_rsync_args(&job, &config, Some(dir));
+        let e_idx = args.iter().position(|a| a == "-e").unwrap();
+        assert!(!args[e_idx + 1].contains("ControlMaster"));
+
+        config.multiplex = true;
+        let args = mgr.build_rsync_args(&job, &config, Some(dir));
+        let ssh_cmd = &args[e_idx + 1];
+        assert!(ssh_cmd.contains("-o ControlMaster=auto"));
+        assert!(ssh_cmd.contains("-o ControlPath=/cfg/ssh/r1.sock"));
+    }
+
     #[test]
     fn rsync_args_multiple_excludes() {
         let mgr = SyncManager::new(2);
@@ -590,10 +751,10 @@ mod tests {
             error: None,
         };
         let config = test_config();
-        let args = mgr.build_rsync_args(&job, &config);
+        let args = mgr.build_rsync_args(&job, &config, None);
 
         // Count --exclude flags.
-        let exclude_count = args.iter().filter(|a| *a == "--exclude").count();
+        let exclude_count = args.iter().filter(|a| a.starts_with("--exclude=")).count();
         assert_eq!(exclude_count, 3);
     }
 
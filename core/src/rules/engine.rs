@@ -4,8 +4,13 @@
 //! `namespace::ParameterStore`. Uses a simplified three-layer RETE
 //! network: alpha nodes (single-condition filters), beta nodes
 //! (variable joins), and conflict resolution (priority ordering).
+//!
+//! `~=` patterns are compiled once when a rule is added. A pattern that
+//! fails to compile is reported in `ReteEngine::load_warnings()` and its
+//! condition never matches.
 
 use std::collections::HashMap;
+use regex::Regex;
 use serde_json::Value;
 
 use crate::namespace::store::{GetResult, ParameterStore};
@@ -24,13 +29,29 @@ pub struct RuleMatch {
     pub bindings: HashMap<String, String>,
 }
 
-/// A warning emitted during evaluation (e.g., SET on an append-field).
+/// A warning emitted while loading rules (e.g., an invalid regex) or during
+/// evaluation (e.g., SET on an append-field).
 #[derive(Debug, Clone)]
 pub struct EngineWarning {
     pub path: String,
     pub message: String,
 }
 
+/// How one condition fared against one store key during a traced
+/// evaluation. A condition that matched no key is recorded once, with the
+/// queried pattern as `key` and no value.
+#[derive(Debug, Clone)]
+pub struct ConditionTrace {
+    pub rule_index: usize,
+    pub condition: Condition,
+    pub key: String,
+    /// The store value the condition was tested against.
+    pub value: Option<Value>,
+    /// Variables bound by matching `key` against the condition's path.
+    pub bindings: HashMap<String, String>,
+    pub passed: bool,
+}
+
 /// Result of an evaluation pass.
 #[derive(Debug, Clone)]
 pub struct EvalResult {
@@ -65,27 +86,48 @@ impl EvalResult {
 #[derive(Debug, Clone)]
 struct AlphaNode {
     condition: Condition,
+    /// Compiled pattern for a `Matches` condition.
+    regex: Option<Regex>,
 }
 
 impl AlphaNode {
+    #[cfg(test)]
     fn new(condition: Condition) -> Self {
-        AlphaNode { condition }
+        AlphaNode { condition, regex: None }
+    }
+
+    fn with_regex(condition: Condition, regex: Option<Regex>) -> Self {
+        AlphaNode { condition, regex }
     }
 
     /// Evaluate this condition against the store, returning all
     /// `(matched_key, variable_bindings)` pairs.
+    #[cfg(test)]
     fn evaluate(&self, store: &ParameterStore) -> Vec<(String, HashMap<String, String>)> {
+        self.evaluate_traced(store, None)
+    }
+
+    /// Like `evaluate`, recording each tested key in `trace` when given.
+    /// Traces carry `rule_index` 0; the engine fills in the real index.
+    fn evaluate_traced(
+        &self,
+        store: &ParameterStore,
+        mut trace: Option<&mut Vec<ConditionTrace>>,
+    ) -> Vec<(String, HashMap<String, String>)> {
         let pattern_str = path_pattern_to_query(&self.condition.path);
         let matching_keys = store.keys_matching(&pattern_str);
 
         // Handle the case where the pattern is concrete (no wildcards)
         // and the key does not exist (for IsEmpty checks).
         if matching_keys.is_empty() {
-            if self.condition.operator == Operator::IsEmpty {
-                if !self.condition.path.has_variables() {
-                    // Concrete path, not found — IsEmpty is true.
-                    return vec![(pattern_str, HashMap::new())];
-                }
+            let passed = self.condition.operator == Operator::IsEmpty
+                && !self.condition.path.has_variables();
+            if let Some(t) = trace {
+                t.push(self.trace_entry(&pattern_str, None, HashMap::new(), passed));
+            }
+            if passed {
+                // Concrete path, not found — IsEmpty is true.
+                return vec![(pattern_str, HashMap::new())];
             }
             return Vec::new();
         }
@@ -106,8 +148,16 @@ impl AlphaNode {
             };
 
             // Evaluate the operator.
-            let matches = eval_operator(&self.condition.operator, &value, &self.condition.value);
-
+            let matches = eval_operator(
+                &self.condition.operator,
+                &value,
+                &self.condition.value,
+                self.regex.as_ref(),
+            );
+
+            if let Some(t) = trace.as_deref_mut() {
+                t.push(self.trace_entry(key, value, bindings.clone(), matches));
+            }
             if matches {
                 results.push((key.clone(), bindings));
             }
@@ -115,6 +165,23 @@ impl AlphaNode {
 
         results
     }
+
+    fn trace_entry(
+        &self,
+        key: &str,
+        value: Option<Value>,
+        bindings: HashMap<String, String>,
+        passed: bool,
+    ) -> ConditionTrace {
+        ConditionTrace {
+            rule_index: 0,
+            condition: self.condition.clone(),
+            key: key.to_string(),
+            value,
+            bindings,
+            passed,
+        }
+    }
 }
 
 
@@ -194,16 +261,48 @@ fn merge_bindings(
 #[derive(Debug, Clone)]
 struct CompiledRule {
     rule: Rule,
+    /// Compiled `~=` patterns, keyed by pattern text.
+    regexes: HashMap<String, Regex>,
 }
 
 impl CompiledRule {
-    fn compile(rule: Rule) -> Self {
-        CompiledRule { rule }
+    /// Compile `rule`, returning a warning for each `~=` pattern that is
+    /// not a valid regex.
+    fn compile(rule: Rule) -> (Self, Vec<EngineWarning>) {
+        let mut regexes = HashMap::new();
+        let mut warnings = Vec::new();
+        for cond in rule.conditions.conditions() {
+            if cond.operator != Operator::Matches {
+                continue;
+            }
+            let pattern = match &cond.value {
+                Some(p) => p,
+                None => continue,
+            };
+            if regexes.contains_key(pattern) {
+                continue;
+            }
+            match Regex::new(pattern) {
+                Ok(re) => {
+                    regexes.insert(pattern.clone(), re);
+                }
+                Err(e) => warnings.push(EngineWarning {
+                    path: cond.path.to_string(),
+                    message: format!("invalid regex '{}': {}", pattern, e),
+                }),
+            }
+        }
+        (CompiledRule { rule, regexes }, warnings)
     }
 
     /// Evaluate this rule against the store, returning all valid binding sets.
-    fn evaluate(&self, store: &ParameterStore) -> Vec<HashMap<String, String>> {
-        self.evaluate_expression(&self.rule.conditions, store)
+    /// When `trace` is given, every condition tested is recorded in it.
+    fn evaluate(
+        &self,
+        store: &ParameterStore,
+        mut trace: Option<&mut Vec<ConditionTrace>>,
+    ) -> Vec<HashMap<String, String>> {
+        self.evaluate_expression(&self.rule.conditions, store, &mut trace)
     }
 
     /// Evaluate an expression tree recursively using alpha nodes for
@@ -212,18 +311,20 @@ impl CompiledRule {
         &self,
         expr: &Expression,
         store: &ParameterStore,
+        trace: &mut Option<&mut Vec<ConditionTrace>>,
     ) -> Vec<HashMap<String, String>> {
         match expr {
             Expression::Condition(cond) => {
-                let alpha = AlphaNode::new(cond.clone());
-                let results = alpha.evaluate(store);
+                let regex = cond.value.as_ref().and_then(|p| self.regexes.get(p)).cloned();
+                let alpha = AlphaNode::with_regex(cond.clone(), regex);
+                let results = alpha.evaluate_traced(store, trace.as_deref_mut());
                 results.into_iter().map(|(_, bindings)| bindings).collect()
             }
             Expression::And(exprs) => {
                 let alpha_outputs: Vec<Vec<(String, HashMap<String, String>)>> = exprs
                     .iter()
                     .map(|e| {
-                        let binding_sets = self.evaluate_expression(e, store);
+                        let binding_sets = self.evaluate_expression(e, store, trace);
                         // Convert back to alpha-output format for beta_join.
                         binding_sets
                             .into_iter()
@@ -236,7 +337,7 @@ impl CompiledRule {
             Expression::Or(exprs) => {
                 let mut all = Vec::new();
                 for e in exprs {
-                    all.extend(self.evaluate_expression(e, store));
+                    all.extend(self.evaluate_expression(e, store, trace));
                 }
                 // Deduplicate.
                 let mut seen = Vec::new();
@@ -250,7 +351,7 @@ impl CompiledRule {
                 unique
             }
             Expression::Not(inner) => {
-                let inner_results = self.evaluate_expression(inner, store);
+                let inner_results = self.evaluate_expression(inner, store, trace);
                 if inner_results.is_empty() {
                     // NOT of nothing-matches = true (with empty bindings).
                     vec![HashMap::new()]
@@ -269,9 +370,11 @@ impl CompiledRule {
 // ---------------------------------------------------------------------------
 
 /// The RETE evaluation engine.
+#[derive(Debug, Clone)]
 pub struct ReteEngine {
     compiled_rules: Vec<CompiledRule>,
     append_fields: Vec<String>,
+    load_warnings: Vec<EngineWarning>,
 }
 
 impl ReteEngine {
@@ -284,12 +387,20 @@ impl ReteEngine {
                 "log".to_string(),
                 "event".to_string(),
             ],
+            load_warnings: Vec::new(),
         }
     }
 
     /// Compile and add a single rule to the engine.
     pub fn add_rule(&mut self, rule: Rule) {
-        self.compiled_rules.push(CompiledRule::compile(rule));
+        let (compiled, warnings) = CompiledRule::compile(rule);
+        self.compiled_rules.push(compiled);
+        self.load_warnings.extend(warnings);
+    }
+
+    /// The rule loaded at `index`, as referenced by `RuleMatch::rule_index`.
+    pub fn rule(&self, index: usize) -> Option<&Rule> {
+        self.compiled_rules.get(index).map(|c| &c.rule)
     }
 
     /// Compile and add multiple rules to the engine.
@@ -299,6 +410,12 @@ impl ReteEngine {
         }
     }
 
+    /// Warnings from compiling the loaded rules, such as invalid `~=`
+    /// patterns.
+    pub fn load_warnings(&self) -> &[EngineWarning] {
+        &self.load_warnings
+    }
+
     /// Configure which field names trigger overwrite warnings when SET
     /// is used instead of APPEND.
     pub fn set_append_fields(&mut self, fields: Vec<String>) {
@@ -308,10 +425,33 @@ impl ReteEngine {
     /// Full evaluation of all rules against the current store state.
     /// Does NOT execute actions — just reports which rules would fire.
     pub fn evaluate(&self, store: &ParameterStore) -> EvalResult {
+        self.evaluate_with(store, None)
+    }
+
+    /// Like `evaluate`, also returning a trace of every condition tested:
+    /// the key it was checked against, the value found, the bindings, and
+    /// whether it passed.
+    pub fn evaluate_traced(&self, store: &ParameterStore) -> (EvalResult, Vec<ConditionTrace>) {
+        let mut trace = Vec::new();
+        let result = self.evaluate_with(store, Some(&mut trace));
+        (result, trace)
+    }
+
+    fn evaluate_with(
+        &self,
+        store: &ParameterStore,
+        mut trace: Option<&mut Vec<ConditionTrace>>,
+    ) -> EvalResult {
         let mut matches_with_priority: Vec<(i32, usize, HashMap<String, String>)> = Vec::new();
 
         for (idx, compiled) in self.compiled_rules.iter().enumerate() {
-            let binding_sets = compiled.evaluate(store);
+            let start = trace.as_ref().map_or(0, |t| t.len());
+            let binding_sets = compiled.evaluate(store, trace.as_deref_mut());
+            if let Some(t) = trace.as_deref_mut() {
+                for entry in &mut t[start..] {
+                    entry.rule_index = idx;
+                }
+            }
             let priority = compiled.rule.priority.unwrap_or(0);
 
             for bindings in binding_sets {
@@ -477,7 +617,13 @@ fn extract_bindings(pattern: &PathPattern, key: &str) -> Option<HashMap<String,
 }
 
 /// Evaluate a comparison operator against a value from the store.
-fn eval_operator(op: &Operator, value: &Option<Value>, expected: &Option<String>) -> bool {
+/// `regex` is the compiled pattern for `Matches`; without one it never matches.
+fn eval_operator(
+    op: &Operator,
+    value: &Option<Value>,
+    expected: &Option<String>,
+    regex: Option<&Regex>,
+) -> bool {
     match op {
         Operator::IsEmpty => match value {
             None => true,
@@ -566,6 +712,7 @@ fn eval_operator(op: &Operator, value: &Option<Value>, expected: &Option<String>
                         _ => false,
                     }
                 }
+                Operator::Matches => regex.is_some_and(|re| re.is_match(&actual_str)),
                 // IsEmpty and IsNotEmpty already handled above.
                 _ => false,
             }
@@ -1113,4 +1260,97 @@ mod tests {
         let result = engine.step(&mut store);
         assert!(result.warnings.is_empty());
     }
+
+    #[test]
+    fn regex_rule_fires_only_on_match() {
+        let mut store = ParameterStore::new();
+        store.set("agent.w1.output", json!("build failed\nerror: 42 in main.rs")).unwrap();
+        store.set("agent.w2.output", json!("error: none")).unwrap();
+        store.set("agent.w3.output", json!("all good")).unwrap();
+
+        let rule = arrow_rule(
+            "agent.$a.output ~= error: \\d+ --> agent.$a.status = failing",
+        );
+
+        let mut engine = ReteEngine::new();
+        engine.add_rule(rule);
+        assert!(engine.load_warnings().is_empty());
+
+        let result = engine.step(&mut store);
+        assert_eq!(result.fired_rules.len(), 1);
+        assert_eq!(result.fired_rules[0].bindings.get("a").unwrap(), "w1");
+        match store.get("agent.w1.status").unwrap() {
+            GetResult::Single(v) => assert_eq!(v, json!("failing")),
+            other => panic!("expected Single, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn contains_rule_matches_substring() {
+        let mut store = ParameterStore::new();
+        store.set("agent.w1.output", json!("Traceback: panic in worker")).unwrap();
+        store.set("agent.w2.output", json!("ok")).unwrap();
+
+        let rules = crate::rules::format::parse_block_rules(
+            "when:\n  agent.$a.output contains panic\nthen:\n  agent.$a.status = crashed",
+        )
+        .unwrap();
+
+        let mut engine = ReteEngine::new();
+        engine.add_rules(rules);
+
+        let result = engine.evaluate(&store);
+        assert_eq!(result.fired_rules.len(), 1);
+        assert_eq!(result.fired_rules[0].bindings.get("a").unwrap(), "w1");
+    }
+
+    #[test]
+    fn invalid_regex_warns_at_load_and_never_fires() {
+        let mut store = ParameterStore::new();
+        store.set("agent.w1.output", json!("error: (")).unwrap();
+
+        let mut engine = ReteEngine::new();
+        engine.add_rule(arrow_rule("agent.$a.output ~= error: [ --> agent.$a.status = x"));
+
+        let warnings = engine.load_warnings();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].path, "agent.$a.output");
+        assert!(warnings[0].message.contains("invalid regex"));
+        assert!(engine.evaluate(&store).fired_rules.is_empty());
+    }
+
+    #[test]
+    fn trace_reports_failing_condition() {
+        let mut store = ParameterStore::new();
+        store.set("task.T1.status", json!("ready")).unwrap();
+        store.set("agent.w1.status", json!("busy")).unwrap();
+
+        let mut engine = ReteEngine::new();
+        engine.add_rule(arrow_rule("task.T0.status == done --> task.T0.log += x"));
+        engine.add_rule(arrow_rule(
+            "task.$t.status == ready AND agent.w1.status == idle --> task.$t.agent = w1",
+        ));
+
+        let (result, trace) = engine.evaluate_traced(&store);
+        assert!(result.fired_rules.is_empty());
+
+        let rule1: Vec<&ConditionTrace> = trace.iter().filter(|t| t.rule_index == 1).collect();
+        assert_eq!(rule1.len(), 2);
+        assert_eq!(rule1[0].key, "task.T1.status");
+        assert!(rule1[0].passed);
+        assert_eq!(rule1[0].bindings.get("t").unwrap(), "T1");
+        assert_eq!(rule1[1].key, "agent.w1.status");
+        assert_eq!(rule1[1].value, Some(json!("busy")));
+        assert!(!rule1[1].passed);
+
+        // A condition with no matching key is still traced, as a failure.
+        let rule0: Vec<&ConditionTrace> = trace.iter().filter(|t| t.rule_index == 0).collect();
+        assert_eq!(rule0.len(), 1);
+        assert_eq!(rule0[0].key, "task.T0.status");
+        assert!(rule0[0].value.is_none());
+        assert!(!rule0[0].passed);
+
+        // The untraced API reports the same result.
+        assert_eq!(engine.evaluate(&store).fired_rules.len(), result.fired_rules.len());
+    }
 }
@@ -2,6 +2,8 @@
 //! patterns, variable binding, wildcards, and comparison/logical operators.
 //!
 //! Expressions look like `task.$t.status == complete AND agent.$a.status == idle`.
+//! Text conditions use `contains` (substring) or `~=` (regex), as in
+//! `agent.$a.output ~= error: \d+`.
 //! The parser produces an AST of `Expression` nodes that can later be
 //! evaluated against a namespace store.
 
@@ -112,6 +114,8 @@ pub enum Operator {
     LtEq,
     /// `contains`
     Contains,
+    /// `~=` — the value is a regular expression.
+    Matches,
     /// `is empty`
     IsEmpty,
     /// `is not empty`
@@ -183,8 +187,10 @@ impl Condition {
             });
         }
 
-        // Try symbolic operators: >=, <=, !=, ==, >, <
+        // Try symbolic operators: ~=, >=, <=, !=, ==, >, <
+        // `~=` goes first so a regex value may itself contain `=` or `>`.
         let ops: &[(&str, Operator)] = &[
+            ("~=", Operator::Matches),
             (">=", Operator::GtEq),
             ("<=", Operator::LtEq),
             ("!=", Operator::NotEq),
@@ -589,6 +595,14 @@ mod tests {
         assert_eq!(c.value.as_deref(), Some("gpu"));
     }
 
+    #[test]
+    fn parse_condition_matches() {
+        let c = Condition::parse("agent.$a.output ~= error: \\d+ (>=1)").unwrap();
+        assert_eq!(c.path.to_string(), "agent.$a.output");
+        assert_eq!(c.operator, Operator::Matches);
+        assert_eq!(c.value.as_deref(), Some("error: \\d+ (>=1)"));
+    }
+
     #[test]
     fn parse_condition_is_empty() {
         let c = Condition::parse("task.$t.result is empty").unwrap();
@@ -628,6 +628,41 @@ agent.$a.health == unhealthy --> agent.$a.status = error";
         assert!(parse_table_rules(input).is_err());
     }
 
+    // --- Text operators ---
+
+    #[test]
+    fn all_formats_parse_text_operators() {
+        use crate::rules::expr::Operator;
+
+        let arrow = parse_arrow_rules(
+            "agent.$a.output ~= error: \\d+ --> agent.$a.status = failing",
+        )
+        .unwrap();
+        let table = parse_table_rules(
+            "\
+| When | Then |
+|------|------|
+| agent.$a.output ~= error: \\d+ | agent.$a.status = failing |",
+        )
+        .unwrap();
+        let block = parse_block_rules(
+            "\
+when:
+    agent.$a.output ~= error: \\d+
+    agent.$a.output contains main.rs
+then:
+    agent.$a.status = failing",
+        )
+        .unwrap();
+
+        for rules in [&arrow, &table, &block] {
+            let c = rules[0].conditions.conditions()[0];
+            assert_eq!(c.operator, Operator::Matches);
+            assert_eq!(c.value.as_deref(), Some("error: \\d+"));
+        }
+        assert_eq!(block[0].conditions.conditions()[1].operator, Operator::Contains);
+    }
+
     // --- Block format ---
 
     #[test]
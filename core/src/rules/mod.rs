@@ -12,7 +12,7 @@ pub use format::{
     parse_arrow_rules, parse_block_rules, parse_rules_auto, parse_table_rules,
     Rule, RuleAction,
 };
-pub use engine::{ReteEngine, RuleMatch, EvalResult, EngineWarning};
+pub use engine::{ReteEngine, RuleMatch, EvalResult, EngineWarning, ConditionTrace};
 pub use bridge::{
     DecoratorRegistry, DecoratorHandler, ExtractedPython, MarkdownExtraction,
     extract_python_from_markdown, generate_python_source, parse_inline_rules,
@@ -1,9 +1,9 @@
-use std::io::{Read, Write};
 use std::os::unix::net::{UnixListener, UnixStream};
 use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
 
 use crate::command::Command;
+use crate::frame::{read_payload, write_payload, RequestFrame, ResponseFrame};
 use crate::sys::Sys;
 use cmx_utils::response::Response;
 use cmx_utils::watch::WatchRegistry;
@@ -14,11 +14,35 @@ use cmx_utils::watch::WatchRegistry;
 /// writes back a length-prefixed JSON response.
 ///
 /// Watch commands are intercepted at this layer and routed to a
-/// `WatchRegistry` instead of being dispatched through Sys.
+/// `WatchRegistry` instead of being dispatched through Sys. Recheck
+/// commands are parked with their stream until the daemon, which owns the
+/// session backend, answers them via `take_rechecks`. Exec commands are
+/// dispatched through Sys (which queues their keystrokes) and, if
+/// accepted, parked the same way for `take_execs`.
+///
+/// Replies echo the request's correlation id, if it sent one (see
+/// `frame`); parked connections keep the id in their `PendingReply`.
 pub struct ServiceSocket {
     listener: UnixListener,
     path: PathBuf,
     shutdown_requested: std::cell::Cell<bool>,
+    pending_rechecks: std::cell::RefCell<Vec<(PendingReply, String)>>,
+    pending_execs: std::cell::RefCell<Vec<ParkedExec>>,
+}
+
+
+/// A connection awaiting its reply, with the request's correlation id.
+pub struct PendingReply {
+    stream: UnixStream,
+    request_id: Option<u64>,
+}
+
+
+/// An accepted agent.exec whose stream awaits the command's output.
+pub struct ParkedExec {
+    pub stream: PendingReply,
+    pub agent: String,
+    pub cmd: String,
 }
 
 
@@ -30,6 +54,10 @@ enum HandleResult {
     Registered,
     /// A DaemonStop command was received — the response was sent, daemon should shut down.
     Shutdown,
+    /// An agent.recheck was received — the stream awaits a backend-backed reply.
+    Recheck { stream: PendingReply, name: String },
+    /// An agent.exec was accepted — the stream awaits the output delta.
+    Exec(ParkedExec),
 }
 
 
@@ -48,6 +76,8 @@ impl ServiceSocket {
             listener,
             path: path.to_path_buf(),
             shutdown_requested: std::cell::Cell::new(false),
+            pending_rechecks: std::cell::RefCell::new(Vec::new()),
+            pending_execs: std::cell::RefCell::new(Vec::new()),
         })
     }
 
@@ -85,6 +115,12 @@ impl ServiceSocket {
                 self.shutdown_requested.set(true);
                 return Ok(true);
             }
+            HandleResult::Recheck { stream, name } => {
+                self.pending_rechecks.borrow_mut().push((stream, name));
+            }
+            HandleResult::Exec(parked) => {
+                self.pending_execs.borrow_mut().push(parked);
+            }
         }
         Ok(false)
     }
@@ -123,6 +159,12 @@ impl ServiceSocket {
                             registry.notify_all(&summary, now_ms);
                         }
                         HandleResult::Registered => {}
+                        HandleResult::Recheck { stream, name } => {
+                            self.pending_rechecks.borrow_mut().push((stream, name));
+                        }
+                        HandleResult::Exec(parked) => {
+                            self.pending_execs.borrow_mut().push(parked);
+                        }
                         HandleResult::Shutdown => {
                             let _ = self.listener.set_nonblocking(false);
                             self.shutdown_requested.set(true);
@@ -171,6 +213,24 @@ impl ServiceSocket {
         self.shutdown_requested.get()
     }
 
+    /// Take the agent.recheck requests accepted since the last call. The
+    /// caller answers each one with `respond`.
+    pub fn take_rechecks(&self) -> Vec<(PendingReply, String)> {
+        std::mem::take(&mut *self.pending_rechecks.borrow_mut())
+    }
+
+    /// Take the agent.exec requests accepted since the last call. Their
+    /// keystrokes are already queued in Sys; the caller must capture each
+    /// pane before those actions run (see `monitor::exec`).
+    pub fn take_execs(&self) -> Vec<ParkedExec> {
+        std::mem::take(&mut *self.pending_execs.borrow_mut())
+    }
+
+    /// Write a response to a parked connection.
+    pub fn respond(mut reply: PendingReply, response: &Response) -> Result<(), String> {
+        write_frame(&mut reply.stream, reply.request_id, response)
+    }
+
     /// Remove the socket file from disk (static helper).
     pub fn cleanup(path: &Path) {
         let _ = std::fs::remove_file(path);
@@ -188,7 +248,7 @@ fn handle_connection(
     sys: &mut Sys,
     registry: &mut WatchRegistry,
 ) -> Result<HandleResult, String> {
-    let cmd = read_frame(&mut stream)?;
+    let RequestFrame { request_id, command: cmd } = read_frame(&mut stream)?;
 
     match cmd {
         Command::Watch { since, timeout } => {
@@ -201,9 +261,32 @@ fn handle_connection(
         }
         Command::DaemonStop => {
             let response = sys.execute(cmd);
-            write_frame(&mut stream, &response)?;
+            write_frame(&mut stream, request_id, &response)?;
             Ok(HandleResult::Shutdown)
         }
+        Command::AgentRecheck { name } => Ok(HandleResult::Recheck {
+            stream: PendingReply { stream, request_id },
+            name,
+        }),
+        Command::AgentExec { agent, cmd: line } => {
+            let response = sys.execute(Command::AgentExec {
+                agent: agent.clone(),
+                cmd: line.clone(),
+            });
+            match response {
+                Response::Ok { .. } => Ok(HandleResult::Exec(ParkedExec {
+                    stream: PendingReply { stream, request_id },
+                    agent,
+                    cmd: line,
+                })),
+                Response::Error { .. } => {
+                    write_frame(&mut stream, request_id, &response)?;
+                    Ok(HandleResult::Dispatched {
+                        summary: format!("agent.exec {} rejected", agent),
+                    })
+                }
+            }
+        }
         _ => {
             let summary = format!("{:?}", cmd);
             // Truncate the debug summary to a reasonable length.
@@ -213,55 +296,37 @@ fn handle_connection(
                 summary
             };
             let response = sys.execute(cmd);
-            write_frame(&mut stream, &response)?;
+            write_frame(&mut stream, request_id, &response)?;
             Ok(HandleResult::Dispatched { summary })
         }
     }
 }
 
 
-/// Read a length-prefixed JSON frame from a stream.
+/// Read a request frame from a stream.
 ///
 /// Wire format: 4 bytes big-endian length, then that many bytes of JSON.
-fn read_frame(stream: &mut UnixStream) -> Result<Command, String> {
-    // Read 4-byte length prefix
-    let mut len_buf = [0u8; 4];
-    stream
-        .read_exact(&mut len_buf)
-        .map_err(|e| format!("Failed to read frame length: {}", e))?;
-    let len = u32::from_be_bytes(len_buf) as usize;
-
-    if len == 0 {
-        return Err("Empty frame".into());
-    }
-    if len > 16 * 1024 * 1024 {
-        return Err(format!("Frame too large: {} bytes", len));
-    }
-
-    // Read payload
-    let mut payload = vec![0u8; len];
-    stream
-        .read_exact(&mut payload)
-        .map_err(|e| format!("Failed to read frame payload: {}", e))?;
-
-    // Parse JSON
+fn read_frame(stream: &mut UnixStream) -> Result<RequestFrame, String> {
+    let payload = read_payload(stream)?.ok_or("Timed out reading frame")?;
     serde_json::from_slice(&payload)
         .map_err(|e| format!("Failed to parse command JSON: {}", e))
 }
 
 
-/// Write a length-prefixed JSON frame to a stream.
-fn write_frame(stream: &mut UnixStream, response: &Response) -> Result<(), String> {
-    let json = serde_json::to_vec(response)
+/// Write a response frame to a stream, tagged with the request's
+/// correlation id when it had one.
+fn write_frame(
+    stream: &mut UnixStream,
+    request_id: Option<u64>,
+    response: &Response,
+) -> Result<(), String> {
+    let frame = ResponseFrame {
+        request_id,
+        response: response.clone(),
+    };
+    let json = serde_json::to_vec(&frame)
         .map_err(|e| format!("Failed to serialize response: {}", e))?;
-    let len = json.len() as u32;
-    stream
-        .write_all(&len.to_be_bytes())
-        .map_err(|e| format!("Failed to write frame length: {}", e))?;
-    stream
-        .write_all(&json)
-        .map_err(|e| format!("Failed to write frame payload: {}", e))?;
-    Ok(())
+    write_payload(stream, &json)
 }
 
 
@@ -273,6 +338,7 @@ fn write_frame(stream: &mut UnixStream, response: &Response) -> Result<(), Strin
 mod tests {
     use super::*;
     use crate::data::Data;
+    use std::io::{Read, Write};
     use std::os::unix::net::UnixStream;
 
     /// Create a paired (client, server) UnixStream for testing without
@@ -312,7 +378,8 @@ mod tests {
 
         // Read from server side
         let received = read_frame(&mut server).unwrap();
-        assert_eq!(received, Command::Status { format: None });
+        assert_eq!(received.request_id, None);
+        assert_eq!(received.command, Command::Status { format: None });
     }
 
     #[test]
@@ -321,7 +388,7 @@ mod tests {
         let response = Response::Ok {
             output: "hello".into(),
         };
-        write_frame(&mut writer, &response).unwrap();
+        write_frame(&mut writer, None, &response).unwrap();
 
         let received = read_response_from_stream(&mut reader);
         assert_eq!(
@@ -354,6 +421,113 @@ mod tests {
         }
     }
 
+    fn write_request_to_stream(stream: &mut UnixStream, request_id: u64, cmd: &Command) {
+        let frame = RequestFrame {
+            request_id: Some(request_id),
+            command: cmd.clone(),
+        };
+        let json = serde_json::to_vec(&frame).unwrap();
+        stream.write_all(&(json.len() as u32).to_be_bytes()).unwrap();
+        stream.write_all(&json).unwrap();
+    }
+
+    fn read_response_frame(stream: &mut UnixStream) -> ResponseFrame {
+        let mut len_buf = [0u8; 4];
+        stream.read_exact(&mut len_buf).unwrap();
+        let mut payload = vec![0u8; u32::from_be_bytes(len_buf) as usize];
+        stream.read_exact(&mut payload).unwrap();
+        serde_json::from_slice(&payload).unwrap()
+    }
+
+    #[test]
+    fn reply_echoes_request_id() {
+        let (mut client, server) = paired_streams();
+        write_request_to_stream(&mut client, 41, &Command::Status { format: None });
+        let mut sys = test_sys();
+        let mut registry = WatchRegistry::new();
+        handle_connection(server, &mut sys, &mut registry).unwrap();
+        let frame = read_response_frame(&mut client);
+        assert_eq!(frame.request_id, Some(41));
+        assert!(matches!(frame.response, Response::Ok { .. }));
+    }
+
+    #[test]
+    fn parked_reply_keeps_request_id() {
+        let (mut client, server) = paired_streams();
+        write_request_to_stream(&mut client, 9, &Command::AgentRecheck { name: "w1".into() });
+        let mut sys = test_sys();
+        let mut registry = WatchRegistry::new();
+        match handle_connection(server, &mut sys, &mut registry).unwrap() {
+            HandleResult::Recheck { stream, .. } => {
+                ServiceSocket::respond(stream, &Response::Ok { output: "{}".into() }).unwrap();
+            }
+            _ => panic!("expected Recheck"),
+        }
+        assert_eq!(read_response_frame(&mut client).request_id, Some(9));
+    }
+
+    #[test]
+    fn recheck_is_parked_for_daemon() {
+        let (mut client, server) = paired_streams();
+        write_cmd_to_stream(&mut client, &Command::AgentRecheck { name: "w1".into() });
+
+        let mut sys = test_sys();
+        let mut registry = WatchRegistry::new();
+        let result = handle_connection(server, &mut sys, &mut registry).unwrap();
+        match result {
+            HandleResult::Recheck { stream, name } => {
+                assert_eq!(name, "w1");
+                ServiceSocket::respond(stream, &Response::Ok { output: "{}".into() }).unwrap();
+            }
+            _ => panic!("expected Recheck"),
+        }
+        let resp = read_response_from_stream(&mut client);
+        assert_eq!(resp, Response::Ok { output: "{}".into() });
+    }
+
+    #[test]
+    fn exec_queues_keys_and_parks_stream() {
+        let mut sys = test_sys();
+        sys.execute(Command::AgentNew {
+            role: "worker".into(),
+            name: Some("w1".into()),
+            path: None,
+            agent_type: None,
+        });
+        let (mut client, server) = paired_streams();
+        write_cmd_to_stream(&mut client, &Command::AgentExec {
+            agent: "w1".into(),
+            cmd: "ls".into(),
+        });
+
+        let mut registry = WatchRegistry::new();
+        let result = handle_connection(server, &mut sys, &mut registry).unwrap();
+        match result {
+            HandleResult::Exec(parked) => {
+                assert_eq!(parked.agent, "w1");
+                assert_eq!(parked.cmd, "ls");
+            }
+            _ => panic!("expected Exec"),
+        }
+        assert!(!sys.pending_actions().is_empty());
+    }
+
+    #[test]
+    fn exec_for_unknown_agent_replies_at_once() {
+        let (mut client, server) = paired_streams();
+        write_cmd_to_stream(&mut client, &Command::AgentExec {
+            agent: "ghost".into(),
+            cmd: "ls".into(),
+        });
+
+        let mut sys = test_sys();
+        let mut registry = WatchRegistry::new();
+        let result = handle_connection(server, &mut sys, &mut registry).unwrap();
+        assert!(matches!(result, HandleResult::Dispatched { .. }));
+        let resp = read_response_from_stream(&mut client);
+        assert!(matches!(resp, Response::Error { .. }));
+    }
+
     #[test]
     fn dispatch_unknown_command_rejected_at_parse() {
         let (mut client, mut server) = paired_streams();
@@ -1016,6 +1016,10 @@ description: Continuously monitor active projects, detect and respond to problem
             Some("full-feature-delivery")
         );
         assert_eq!(doc.kind(), SkillKind::Orchestration);
+        assert_eq!(
+            doc.referenced_skills(),
+            vec!["pm", "worker", "code-review-pipeline", "deploy-with-approval"]
+        );
 
         let nodes = doc.nodes.unwrap();
         // Check subflow nodes
@@ -31,6 +31,24 @@ impl SkillDocument {
             SkillKind::Simple
         }
     }
+
+    /// Skill names this orchestration's nodes run, in first-use order.
+    /// A node's role names the skill; a `flow:` role names a sub-flow,
+    /// itself a skill. Blank and dash roles reference nothing.
+    pub fn referenced_skills(&self) -> Vec<String> {
+        let mut names: Vec<String> = Vec::new();
+        for node in self.nodes.iter().flat_map(|t| t.nodes.iter()) {
+            let role = node.role.trim();
+            let name = role.strip_prefix("flow:").unwrap_or(role).trim();
+            if name.is_empty() || name == "-" || name == "\u{2014}" {
+                continue;
+            }
+            if !names.iter().any(|n| n == name) {
+                names.push(name.to_string());
+            }
+        }
+        names
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -449,6 +449,7 @@ mod tests {
             result: None,
             children_ids: Vec::new(),
             spec_path: None,
+            notes: Vec::new(),
         }
     }
 
@@ -291,6 +291,7 @@ mod tests {
                 result: None,
                 children_ids: Vec::new(),
                 spec_path: None,
+                notes: Vec::new(),
             }])
     }
 
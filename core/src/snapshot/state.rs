@@ -41,6 +41,9 @@ pub struct TaskSnapshot {
     pub result: Option<String>,
     pub children_ids: Vec<String>,
     pub spec_path: Option<String>,
+    /// Operator notes as `(timestamp_ms, text)`, oldest first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notes: Vec<(u64, String)>,
 }
 
 // ---------------------------------------------------------------------------
@@ -56,6 +59,20 @@ pub struct SessionSnapshot {
     pub agents_placed: Vec<String>,
 }
 
+// ---------------------------------------------------------------------------
+// PendingMessageSnapshot
+// ---------------------------------------------------------------------------
+
+/// Pending (undelivered) messages for one recipient.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PendingMessageSnapshot {
+    pub recipient: String,
+    pub count: usize,
+    /// First line of each pending message, oldest first.
+    #[serde(default)]
+    pub first_lines: Vec<String>,
+}
+
 // ---------------------------------------------------------------------------
 // SnapshotMetadata
 // ---------------------------------------------------------------------------
@@ -82,19 +99,29 @@ pub struct SnapshotMetadata {
 /// the snapshot trivially serializable and suitable for persistence.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct SystemSnapshot {
+    /// Structured-output schema version (`crate::types::SCHEMA_VERSION`).
+    /// Snapshots written before versioning deserialize as 0.
+    #[serde(default)]
+    pub schema_version: u32,
     pub version: String,
     pub timestamp_ms: u64,
     pub agents: Vec<AgentSnapshot>,
     pub tasks: Vec<TaskSnapshot>,
     pub sessions: Vec<SessionSnapshot>,
     pub settings_hash: String,
+    /// Total pending messages across all recipients.
     pub message_count: usize,
+    /// Per-recipient pending messages, sorted by recipient. Recipients with
+    /// nothing pending are omitted.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pending_messages: Vec<PendingMessageSnapshot>,
 }
 
 impl SystemSnapshot {
     /// Create a new empty snapshot with the given version and timestamp.
     pub fn new(version: &str, timestamp_ms: u64) -> Self {
         SystemSnapshot {
+            schema_version: crate::types::SCHEMA_VERSION,
             version: version.to_string(),
             timestamp_ms,
             agents: Vec::new(),
@@ -102,6 +129,7 @@ impl SystemSnapshot {
             sessions: Vec::new(),
             settings_hash: String::new(),
             message_count: 0,
+            pending_messages: Vec::new(),
         }
     }
 
@@ -139,6 +167,14 @@ impl SystemSnapshot {
         self
     }
 
+    /// Set the per-recipient pending messages. Entries are sorted by
+    /// recipient so snapshots of the same state checksum identically.
+    pub fn with_pending_messages(mut self, mut pending: Vec<PendingMessageSnapshot>) -> Self {
+        pending.sort_by(|a, b| a.recipient.cmp(&b.recipient));
+        self.pending_messages = pending;
+        self
+    }
+
     // -------------------------------------------------------------------
     // Metadata and checksums
     // -------------------------------------------------------------------
@@ -301,6 +337,15 @@ impl SystemSnapshot {
     pub fn find_session(&self, name: &str) -> Option<&SessionSnapshot> {
         self.sessions.iter().find(|s| s.name == name)
     }
+
+    /// Number of pending messages recorded for a recipient.
+    pub fn pending_count_for(&self, recipient: &str) -> usize {
+        self.pending_messages
+            .iter()
+            .find(|p| p.recipient == recipient)
+            .map(|p| p.count)
+            .unwrap_or(0)
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -364,6 +409,7 @@ mod tests {
             result: None,
             children_ids: Vec::new(),
             spec_path: None,
+            notes: Vec::new(),
         }
     }
 
@@ -393,9 +439,18 @@ mod tests {
 
     // --- Construction ---
 
+    #[test]
+    fn snapshot_without_schema_version_deserializes_as_zero() {
+        let mut value = serde_json::to_value(make_snapshot()).unwrap();
+        value.as_object_mut().unwrap().remove("schema_version");
+        let snap: SystemSnapshot = serde_json::from_value(value).unwrap();
+        assert_eq!(snap.schema_version, 0);
+    }
+
     #[test]
     fn new_creates_empty_snapshot() {
         let snap = SystemSnapshot::new("0.1.0", 1000);
+        assert_eq!(snap.schema_version, crate::types::SCHEMA_VERSION);
         assert_eq!(snap.version, "0.1.0");
         assert_eq!(snap.timestamp_ms, 1000);
         assert!(snap.agents.is_empty());
@@ -405,6 +460,35 @@ mod tests {
         assert_eq!(snap.message_count, 0);
     }
 
+    #[test]
+    fn pending_messages_sorted_and_round_trip() {
+        let snap = make_snapshot().with_pending_messages(vec![
+            PendingMessageSnapshot {
+                recipient: "worker-1".into(),
+                count: 1,
+                first_lines: vec!["run tests".into()],
+            },
+            PendingMessageSnapshot {
+                recipient: "pilot".into(),
+                count: 2,
+                first_lines: vec!["status?".into(), "review CMX2".into()],
+            },
+        ]);
+        assert_eq!(snap.pending_messages[0].recipient, "pilot");
+        assert_eq!(snap.pending_count_for("pilot"), 2);
+        assert_eq!(snap.pending_count_for("nobody"), 0);
+        let back = SystemSnapshot::from_json(&snap.to_json()).unwrap();
+        assert_eq!(back, snap);
+    }
+
+    #[test]
+    fn snapshot_without_pending_messages_deserializes_empty() {
+        let json = make_snapshot().to_json();
+        assert!(!json.contains("pending_messages"));
+        let back = SystemSnapshot::from_json(&json).unwrap();
+        assert!(back.pending_messages.is_empty());
+    }
+
     #[test]
     fn builder_methods_set_fields() {
         let snap = make_snapshot();
@@ -1,20 +1,28 @@
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
-use crate::agent::pool::{PoolConfig, PoolManager};
+use crate::agent::pool::{PoolAction, PoolConfig, PoolManager};
 use crate::command::Command;
 use crate::data::Data;
+use crate::data::task_tree::TaskDisplayState;
 use crate::infrastructure::runner::ShellRunner;
 use crate::library::{Library, LibrarySource, LibraryType, SourceKind};
-use crate::library::LibraryConfig;
-use crate::rig::config::{RemoteConfig, RigRegistry};
+use crate::library::{ConflictStrategy, LibraryConfig};
+use crate::rig::config::{RemoteConfig, RigRegistry, RIG_FILE};
 use crate::rig::orchestrator::RigOrchestrator;
+use crate::rig::sync::SyncOptions;
 use crate::types::agent::{Agent, AgentStatus, AgentType, HealthState};
-use crate::types::config::{FolderEntry, Settings};
+use crate::types::config::{FolderEntry, SessionBackendKind, Settings};
 use crate::types::message::Message;
 use cmx_utils::response::{Action, Response};
 use crate::types::task::{TaskNode, TaskSource, TaskStatus};
-use crate::diagnosis::{DiagnosisEngine, SignalType};
+use crate::diagnosis::{
+    events_to_csv, DiagnosisEngine, InterventionAction, InterventionOutcome, ReportWindow,
+    SignalType,
+};
 use crate::history::{HistoryManager, HistoryEntry};
+use crate::history::browse::DEFAULT_CONTEXT_LINES;
+use crate::namespace::{AgentStateManager, GetResult, ParameterStore};
 
 
 /// Central runtime for the CMX daemon. Owns all state and dispatches commands.
@@ -25,17 +33,57 @@ use crate::history::{HistoryManager, HistoryEntry};
 pub struct Sys {
     data: Data,
     settings: Settings,
+    /// Settings as last loaded from or saved to disk; `settings` differs
+    /// from this after an unsaved `config add`/`config unset`.
+    saved_settings: Settings,
     actions: Vec<Action>,
     rig: Option<RigOrchestrator>,
     pool: PoolManager,
     library: Library,
+    /// Per-agent scratch values, keyed `agent.<name>.<key>`.
+    agent_state: ParameterStore,
+    /// Latest health problem per agent, taken from monitor assessments.
+    health_signals: HashMap<String, SignalType>,
+    /// Interventions recorded in the diagnosis log that await an outcome.
+    open_interventions: HashMap<String, OpenIntervention>,
+    /// Consecutive Unhealthy/Healthy assessment counts per agent, used for
+    /// status hysteresis.
+    health_streaks: HashMap<String, HealthStreak>,
+    /// Agents in a graceful shutdown, mapped to when their grace period
+    /// ends. They stay registered until `finish_pending_kills` kills them.
+    pending_kills: HashMap<String, u64>,
 }
 
 
+/// Run lengths of identical health readings for one agent.
+#[derive(Debug, Clone, Copy, Default)]
+struct HealthStreak {
+    unhealthy: u32,
+    healthy: u32,
+}
+
+
+/// A Pending diagnosis event opened when an intervention was emitted for
+/// an agent with a known health problem.
+#[derive(Debug, Clone)]
+struct OpenIntervention {
+    event_id: u64,
+    action: InterventionAction,
+    signal: SignalType,
+}
+
+/// Minimum spacing between intervention records for one (signal, agent)
+/// pair in the diagnosis log.
+const INTERVENTION_RECORD_INTERVAL_MS: u64 = 10_000;
+
+
 /// Build a LibraryConfig from the current folder registry, adding project
 /// skill sources for any registered project that has a `skills/` subfolder.
-fn build_library_config(data: &Data) -> LibraryConfig {
-    let mut config = LibraryConfig::default();
+fn build_library_config(data: &Data, settings: &Settings) -> LibraryConfig {
+    let mut config = LibraryConfig {
+        conflict_strategy: settings.skill_conflict_strategy,
+        ..LibraryConfig::default()
+    };
     for folder in data.folders().list() {
         let skills_dir = PathBuf::from(&folder.path).join("skills");
         if skills_dir.is_dir() {
@@ -44,6 +92,7 @@ fn build_library_config(data: &Data) -> LibraryConfig {
                 library_type: LibraryType::SkillsOnly,
                 priority: 25,
                 name: format!("project:{}", folder.name),
+                git: None,
             });
         }
     }
@@ -58,6 +107,7 @@ fn build_pool_manager(settings: &Settings) -> PoolManager {
             target_size: cfg.size,
             auto_expand: settings.pool_auto_expand,
             max_size: cfg.max_size.unwrap_or(cfg.size * 2),
+            min_idle: cfg.min_idle.unwrap_or(0),
             path: cfg.path.clone(),
         });
     }
@@ -70,20 +120,29 @@ impl Sys {
     pub fn new(config_dir: &Path) -> Result<Sys, String> {
         let data = Data::new(config_dir)?;
         let settings = data.settings().clone();
-        let rig = Some(RigOrchestrator::new(
-            RigRegistry::new(),
-            Box::new(ShellRunner),
-        ));
+        let (registry, rig_warning) = RigRegistry::load_or_back_up(&config_dir.join(RIG_FILE))?;
+        if let Some(warning) = rig_warning {
+            eprintln!("cmx: {}", warning);
+        }
+        let rig = Some(
+            RigOrchestrator::new(registry, Box::new(ShellRunner)).with_config_dir(config_dir),
+        );
         let pool = build_pool_manager(&settings);
-        let lib_config = build_library_config(&data);
+        let lib_config = build_library_config(&data, &settings);
         let library = Library::new(&lib_config).unwrap_or_else(|_| Library::empty());
         Ok(Sys {
             data,
+            saved_settings: settings.clone(),
             settings,
             actions: Vec::new(),
             rig,
             pool,
             library,
+            agent_state: ParameterStore::new(),
+            health_signals: HashMap::new(),
+            open_interventions: HashMap::new(),
+            health_streaks: HashMap::new(),
+            pending_kills: HashMap::new(),
         })
     }
 
@@ -91,15 +150,21 @@ impl Sys {
     pub fn from_data(data: Data) -> Sys {
         let settings = data.settings().clone();
         let pool = build_pool_manager(&settings);
-        let lib_config = build_library_config(&data);
+        let lib_config = build_library_config(&data, &settings);
         let library = Library::new(&lib_config).unwrap_or_else(|_| Library::empty());
         Sys {
             data,
+            saved_settings: settings.clone(),
             settings,
             actions: Vec::new(),
             rig: None,
             pool,
             library,
+            agent_state: ParameterStore::new(),
+            health_signals: HashMap::new(),
+            open_interventions: HashMap::new(),
+            health_streaks: HashMap::new(),
+            pending_kills: HashMap::new(),
         }
     }
 
@@ -107,43 +172,67 @@ impl Sys {
     pub fn from_data_with_rig(data: Data, rig: RigOrchestrator) -> Sys {
         let settings = data.settings().clone();
         let pool = build_pool_manager(&settings);
-        let lib_config = build_library_config(&data);
+        let lib_config = build_library_config(&data, &settings);
         let library = Library::new(&lib_config).unwrap_or_else(|_| Library::empty());
         Sys {
             data,
+            saved_settings: settings.clone(),
             settings,
             actions: Vec::new(),
             rig: Some(rig),
             pool,
             library,
+            agent_state: ParameterStore::new(),
+            health_signals: HashMap::new(),
+            open_interventions: HashMap::new(),
+            health_streaks: HashMap::new(),
+            pending_kills: HashMap::new(),
         }
     }
 
     /// The single dispatch method. Every command enters here.
     pub fn execute(&mut self, cmd: Command) -> Response {
         self.actions.clear();
-        match cmd {
+        let response = match cmd {
             Command::Status { format } => self.cmd_status(format),
             Command::View { name } => self.cmd_view(name),
             Command::AgentNew { role, name, path, agent_type } => {
                 self.cmd_agent_new(role, name, path, agent_type)
             }
-            Command::AgentKill { name } => self.cmd_agent_kill(name),
+            Command::AgentKill { name, graceful } => self.cmd_agent_kill(name, graceful),
+            Command::AgentKillWhere { role, status, label } => {
+                self.cmd_agent_kill_where(role, status, label)
+            }
             Command::AgentRestart { name } => self.cmd_agent_restart(name),
             Command::AgentAssign { name, task } => self.cmd_agent_assign(name, task),
             Command::AgentUnassign { name } => self.cmd_agent_unassign(name),
             Command::AgentStatus { name, notes } => self.cmd_agent_status(name, notes),
-            Command::AgentList { format } => self.cmd_agent_list(format),
-            Command::TaskList { format, project } => self.cmd_task_list(format, project),
+            Command::AgentList { format, sort_by, columns } => {
+                self.cmd_agent_list(format, sort_by, columns)
+            }
+            Command::AgentStateSet { agent, key, value } => {
+                self.cmd_agent_state_set(agent, key, value)
+            }
+            Command::AgentStateGet { agent, key } => self.cmd_agent_state_get(agent, key),
+            Command::TaskList {
+                format,
+                project,
+                max_depth,
+                status,
+                agent,
+            } => self.cmd_task_list(format, project, max_depth, status, agent),
             Command::TaskGet { id } => self.cmd_task_get(id),
-            Command::TaskSet { id, status, title, result, agent } => {
-                self.cmd_task_set(id, status, title, result, agent)
+            Command::TaskSet { id, status, title, result, agent, due, cascade } => {
+                self.cmd_task_set(id, status, title, result, agent, due, cascade)
             }
-            Command::TaskCheck { id } => self.cmd_task_check(id),
+            Command::TaskCheck { id, cascade } => self.cmd_task_check(id, cascade),
             Command::TaskUncheck { id } => self.cmd_task_uncheck(id),
+            Command::TaskNote { id, text } => self.cmd_task_note(id, text),
+            Command::TaskMove { id, parent } => self.cmd_task_move(id, parent),
             Command::ConfigLoad { path } => self.cmd_config_load(path),
             Command::ConfigSave { path } => self.cmd_config_save(path),
             Command::ConfigAdd { key, value } => self.cmd_config_add(key, value),
+            Command::ConfigUnset { key } => self.cmd_config_unset(key),
             Command::ConfigList => self.cmd_config_list(),
             Command::ProjectAdd { name, path } => self.cmd_project_add(name, path),
             Command::ProjectRemove { name } => self.cmd_project_remove(name),
@@ -152,9 +241,11 @@ impl Sys {
             Command::RoadmapLoad { path } => self.cmd_roadmap_load(path),
             Command::PoolList => self.cmd_pool_list(),
             Command::PoolStatus { role } => self.cmd_pool_status(role),
-            Command::PoolSet { role, size, path } => self.cmd_pool_set(role, size, path),
+            Command::PoolSet { role, size, path, min_idle } => {
+                self.cmd_pool_set(role, size, path, min_idle)
+            }
             Command::PoolRemove { role } => self.cmd_pool_remove(role),
-            Command::Tell { agent, text } => self.cmd_tell(agent, text),
+            Command::Tell { agent, text, dedup } => self.cmd_tell(agent, text, dedup),
             Command::Interrupt { agent, text } => self.cmd_interrupt(agent, text),
             // Layout and Client commands are handled by MuxUX, not the docket app.
             Command::LayoutRow { .. }
@@ -168,33 +259,50 @@ impl Sys {
                 message: "Layout/Client commands are handled by MuxUX".into(),
             },
             Command::RigInit { host, name } => self.cmd_rig_init(host, name),
-            Command::RigPush { folder, remote } => self.cmd_rig_push(folder, remote),
-            Command::RigPull { folder, remote } => self.cmd_rig_pull(folder, remote),
+            Command::RigPush { folder, remote, label, all, bwlimit, exclude } => {
+                let options = SyncOptions { bwlimit_kbps: bwlimit, excludes: exclude };
+                self.cmd_rig_push(folder, remote, label, all, options)
+            }
+            Command::RigPull { folder, remote, label, bwlimit, exclude } => {
+                let options = SyncOptions { bwlimit_kbps: bwlimit, excludes: exclude };
+                self.cmd_rig_pull(folder, remote, label, options)
+            }
             Command::RigStatus { remote } => self.cmd_rig_status(remote),
             Command::RigHealth { remote } => self.cmd_rig_health(remote),
             Command::RigStop { remote } => self.cmd_rig_stop(remote),
             Command::RigList => self.cmd_rig_list(),
             Command::RigDefault { name } => self.cmd_rig_default(name),
-            Command::DiagnosisReport => self.cmd_diagnosis_report(),
-            Command::DiagnosisReliability { signal, format } => {
-                self.cmd_diagnosis_reliability(signal, format)
+            Command::DiagnosisReport { since, until, dest } => {
+                self.cmd_diagnosis_report(since, until, dest)
+            }
+            Command::DiagnosisReliability { signal, agent, since, format } => {
+                self.cmd_diagnosis_reliability(signal, agent, since, format)
             }
-            Command::DiagnosisEffectiveness { signal, format } => {
-                self.cmd_diagnosis_effectiveness(signal, format)
+            Command::DiagnosisEffectiveness { signal, min_attempts, format } => {
+                self.cmd_diagnosis_effectiveness(signal, min_attempts, format)
             }
             Command::DiagnosisThresholds { format } => self.cmd_diagnosis_thresholds(format),
             Command::DiagnosisEvents { limit, format } => {
                 self.cmd_diagnosis_events(limit, format)
             }
+            Command::DiagnosisCompare { a, b, format } => self.cmd_diagnosis_compare(a, b, format),
+            Command::DiagnosisAnnotate { id, note } => self.cmd_diagnosis_annotate(id, note),
             Command::HistoryList { limit, format } => self.cmd_history_list(limit, format),
             Command::HistoryShow { id } => self.cmd_history_show(id),
-            Command::HistoryDiff { from, to } => self.cmd_history_diff(from, to),
-            Command::HistoryRestore { id } => self.cmd_history_restore(id),
-            Command::HistorySnapshot => self.cmd_history_snapshot(),
-            Command::HistoryPrune => self.cmd_history_prune(),
+            Command::HistoryDiff { from, to, unified } => self.cmd_history_diff(from, to, unified),
+            Command::HistoryRestore { id, dry_run } => self.cmd_history_restore(id, dry_run),
+            Command::HistorySnapshot { label } => self.cmd_history_snapshot(label),
+            Command::HistoryPrune { dry_run } => self.cmd_history_prune(dry_run),
+            Command::HistoryPin { id } => self.cmd_history_set_pinned(id, true),
+            Command::HistoryUnpin { id } => self.cmd_history_set_pinned(id, false),
+            Command::HistorySearch { query, ignore_case } => self.cmd_history_search(query, ignore_case),
             Command::Watch { .. } => Response::Error {
                 message: "Watch commands are handled at the service layer, not via Sys::execute()".into(),
             },
+            Command::AgentRecheck { .. } => Response::Error {
+                message: "agent.recheck needs a session backend and is handled by the daemon".into(),
+            },
+            Command::AgentExec { agent, cmd } => self.cmd_agent_exec(agent, cmd),
             Command::DaemonRun => Response::Error {
                 message: "DaemonRun must be handled by the binary, not dispatched to Sys".into(),
             },
@@ -209,8 +317,11 @@ impl Sys {
                 self.cmd_learnings_add(project, title, body)
             }
             Command::LearningsSearch { query } => self.cmd_learnings_search(query),
-            Command::Help { topic } => self.cmd_help(topic),
-        }
+            Command::SkillValidate => self.cmd_skill_validate(),
+            Command::Help { topic, search } => self.cmd_help(topic, search),
+        };
+        self.record_interventions(now_ms());
+        response
     }
 
     /// Actions emitted during the last execute() call.
@@ -251,6 +362,12 @@ impl Sys {
         &self.settings
     }
 
+    /// Whether the runtime settings have changes not yet written to
+    /// settings.yaml by `config save`.
+    pub fn settings_dirty(&self) -> bool {
+        self.settings != self.saved_settings
+    }
+
     /// Borrow the skill library.
     pub fn library(&self) -> &Library {
         &self.library
@@ -261,18 +378,107 @@ impl Sys {
         self.data.messages_mut()
     }
 
+    /// Force an immediate health reassessment of one agent.
+    ///
+    /// Captures the agent's pane through `backend`, assesses it with
+    /// `monitor` (so output staleness is measured against the regular
+    /// cycle's history), and applies the result. An explicit recheck is
+    /// treated as conclusive: it bypasses the stall/clear hysteresis.
+    /// Returns the fresh `HealthAssessment` as JSON.
+    pub fn recheck_agent(
+        &mut self,
+        name: &str,
+        monitor: &mut crate::monitor::cycle::MonitorCycle,
+        backend: &dyn crate::infrastructure::SessionBackend,
+        now_ms: u64,
+    ) -> Response {
+        let agent = match self.data.agents().get(name) {
+            Some(a) => a.clone(),
+            None => {
+                return Response::Error {
+                    message: format!("Agent '{}' not found", name),
+                }
+            }
+        };
+        if agent.session.is_none() {
+            return Response::Error {
+                message: format!("Agent '{}' has no session to capture", name),
+            };
+        }
+        let assessment = monitor.assess_agent(&agent, backend, now_ms);
+        // Prime the streak so this single reading crosses either threshold.
+        self.health_streaks.insert(
+            name.to_string(),
+            HealthStreak {
+                unhealthy: self.settings.stall_after_unhealthy.saturating_sub(1),
+                healthy: self.settings.clear_after_healthy.saturating_sub(1),
+            },
+        );
+        self.apply_health_update(&assessment);
+        match serde_json::to_string_pretty(&assessment) {
+            Ok(json) => Response::Ok { output: json },
+            Err(e) => Response::Error {
+                message: format!("JSON serialization failed: {}", e),
+            },
+        }
+    }
+
     /// Apply a health assessment from the monitor cycle to an agent's state.
+    ///
+    /// Also feeds the diagnosis log: the assessment's problem signal is
+    /// remembered for the agent, and an open intervention for the agent is
+    /// closed with an outcome derived from this assessment.
     pub fn apply_health_update(&mut self, assessment: &crate::types::health::HealthAssessment) {
+        self.pool.observe_idle(self.data.agents(), assessment.timestamp_ms);
+        let problem = problem_signal(assessment);
+        self.record_intervention_outcome(assessment, problem.as_ref());
+        match problem {
+            Some(signal) => {
+                self.health_signals.insert(assessment.agent.clone(), signal);
+            }
+            None if assessment.overall == HealthState::Healthy => {
+                self.health_signals.remove(&assessment.agent);
+            }
+            None => {}
+        }
+        let streak = self.health_streaks.entry(assessment.agent.clone()).or_default();
+        match assessment.overall {
+            HealthState::Unhealthy => {
+                streak.unhealthy += 1;
+                streak.healthy = 0;
+            }
+            HealthState::Healthy => {
+                streak.healthy += 1;
+                streak.unhealthy = 0;
+            }
+            _ => *streak = HealthStreak::default(),
+        }
+        let streak = *streak;
+        let stall_after = self.settings.stall_after_unhealthy.max(1);
+        let clear_after = self.settings.clear_after_healthy.max(1);
+
         if let Some(agent) = self.data.agents_mut().get_mut(&assessment.agent) {
             agent.health = assessment.overall.clone();
             agent.last_heartbeat_ms = Some(assessment.timestamp_ms);
-            // Update status based on health
+            // Update status based on health. Stalled is entered and left only
+            // after a run of consistent readings, so a borderline signal
+            // doesn't make the status oscillate.
             match &assessment.overall {
-                HealthState::Unhealthy => {
-                    if agent.status != AgentStatus::Dead {
-                        agent.status = AgentStatus::Stalled;
-                        agent.status_notes = assessment.reason.clone();
-                    }
+                HealthState::Unhealthy
+                    if agent.status != AgentStatus::Dead && streak.unhealthy >= stall_after =>
+                {
+                    agent.status = AgentStatus::Stalled;
+                    agent.status_notes = assessment.reason.clone();
+                }
+                HealthState::Healthy
+                    if agent.status == AgentStatus::Stalled && streak.healthy >= clear_after =>
+                {
+                    agent.status = if agent.task.is_some() {
+                        AgentStatus::Busy
+                    } else {
+                        AgentStatus::Idle
+                    };
+                    agent.status_notes = String::new();
                 }
                 HealthState::Degraded => {
                     if agent.status == AgentStatus::Idle || agent.status == AgentStatus::Busy {
@@ -284,9 +490,73 @@ impl Sys {
         }
     }
 
+    /// Open a Pending diagnosis event for each intervention in the emitted
+    /// actions that targets an agent with a known health problem.
+    fn record_interventions(&mut self, now: u64) {
+        for (agent, action) in interventions_in(&self.actions) {
+            if self.open_interventions.contains_key(&agent) {
+                continue;
+            }
+            let signal = match self.health_signals.get(&agent) {
+                Some(s) => s.clone(),
+                None => continue,
+            };
+            let mut engine = match DiagnosisEngine::new(self.data.config_dir().to_path_buf()) {
+                Ok(e) => e.with_rate_limit(INTERVENTION_RECORD_INTERVAL_MS),
+                Err(_) => return,
+            };
+            let detail = format!("{} after {}", action, signal);
+            if let Ok(event_id) = engine.record_signal(&agent, signal.clone(), &detail, now) {
+                self.open_interventions.insert(
+                    agent,
+                    OpenIntervention { event_id, action, signal },
+                );
+            }
+        }
+    }
+
+    /// Close the agent's open intervention, if any, based on a new assessment.
+    /// Assessments that are neither healthy nor show a problem (e.g. the
+    /// Unknown state right after a restart) leave it open.
+    fn record_intervention_outcome(
+        &mut self,
+        assessment: &crate::types::health::HealthAssessment,
+        problem: Option<&SignalType>,
+    ) {
+        let open = match self.open_interventions.get(&assessment.agent) {
+            Some(o) => o.clone(),
+            None => return,
+        };
+        let outcome = match problem {
+            Some(signal) if *signal == open.signal => InterventionOutcome::StillBroken,
+            Some(_) => InterventionOutcome::DifferentError,
+            None if assessment.overall == HealthState::Healthy => InterventionOutcome::Resolved,
+            None => return,
+        };
+        let mut engine = match DiagnosisEngine::new(self.data.config_dir().to_path_buf()) {
+            Ok(e) => e,
+            Err(_) => return,
+        };
+        let detail = if assessment.reason.is_empty() {
+            format!("{:?}", assessment.overall).to_lowercase()
+        } else {
+            assessment.reason.clone()
+        };
+        let _ = engine.record_outcome(
+            open.event_id,
+            open.action,
+            outcome,
+            &detail,
+            assessment.timestamp_ms,
+        );
+        self.open_interventions.remove(&assessment.agent);
+    }
+
     /// Build a `SystemSnapshot` capturing the current system state.
     pub fn build_snapshot(&self) -> crate::snapshot::state::SystemSnapshot {
-        use crate::snapshot::state::{AgentSnapshot, SystemSnapshot, TaskSnapshot};
+        use crate::snapshot::state::{
+            AgentSnapshot, PendingMessageSnapshot, SystemSnapshot, TaskSnapshot,
+        };
 
         let agents: Vec<AgentSnapshot> = self
             .data
@@ -319,14 +589,33 @@ impl Sys {
                 result: t.result.clone(),
                 children_ids: t.children.iter().map(|c| c.id.clone()).collect(),
                 spec_path: t.spec_path.clone(),
+                notes: t.notes.clone(),
             })
             .collect();
 
+        let all_pending = self.data.messages().all_pending();
+        let mut pending: Vec<PendingMessageSnapshot> = Vec::new();
+        for msg in &all_pending {
+            let first_line = msg.text.lines().next().unwrap_or("").to_string();
+            match pending.iter_mut().find(|p| p.recipient == msg.recipient) {
+                Some(entry) => {
+                    entry.count += 1;
+                    entry.first_lines.push(first_line);
+                }
+                None => pending.push(PendingMessageSnapshot {
+                    recipient: msg.recipient.clone(),
+                    count: 1,
+                    first_lines: vec![first_line],
+                }),
+            }
+        }
+
         let now = now_ms();
         SystemSnapshot::new("0.1.0", now)
             .with_agents(agents)
             .with_tasks(tasks)
-            .with_message_count(self.data.messages().all_pending().len())
+            .with_message_count(all_pending.len())
+            .with_pending_messages(pending)
     }
 
     /// Persist the current system state to `current_state.json` in the config directory.
@@ -336,6 +625,27 @@ impl Sys {
         crate::snapshot::checkpoint::save_snapshot(&snapshot, &path)
     }
 
+    /// Clear task `agent` backrefs that point at agents no longer in the
+    /// registry (e.g. killed while assigned). Returns the ids of the tasks
+    /// that were reconciled, in tree order.
+    pub fn reconcile_assignments(&mut self) -> Vec<String> {
+        let orphaned: Vec<String> = self
+            .data
+            .tasks()
+            .flat_list()
+            .into_iter()
+            .filter(|(t, _)| match &t.agent {
+                Some(agent) => self.data.agents().get(agent).is_none(),
+                None => false,
+            })
+            .map(|(t, _)| t.id.clone())
+            .collect();
+        for id in &orphaned {
+            let _ = self.data.tasks_mut().unassign(id);
+        }
+        orphaned
+    }
+
     // -----------------------------------------------------------------------
     // Command handlers
     // -----------------------------------------------------------------------
@@ -348,29 +658,42 @@ impl Sys {
         if format.as_deref() == Some("json") {
             let agents: Vec<&str> = self.data.agents().list().iter().map(|a| a.name.as_str()).collect();
             let obj = serde_json::json!({
+                "schema_version": crate::types::SCHEMA_VERSION,
                 "agents": agents,
                 "agent_count": agent_count,
                 "task_count": task_count,
                 "project_count": project_count,
                 "pending_messages": pending_msgs,
+                "settings_dirty": self.settings_dirty(),
             });
             Response::Ok {
                 output: serde_json::to_string_pretty(&obj).unwrap_or_else(|_| "{}".into()),
             }
         } else {
-            Response::Ok {
-                output: format!(
-                    "agents: {}, tasks: {}, projects: {}, pending messages: {}",
-                    agent_count, task_count, project_count, pending_msgs
-                ),
+            let mut output = format!(
+                "agents: {}, tasks: {}, projects: {}, pending messages: {}",
+                agent_count, task_count, project_count, pending_msgs
+            );
+            if self.settings_dirty() {
+                output.push_str("\nsettings modified, unsaved");
             }
+            Response::Ok { output }
         }
     }
 
     fn cmd_view(&self, name: String) -> Response {
         // Try agent first
         if let Some(agent) = self.data.agents().get(&name) {
-            let json = serde_json::to_string_pretty(agent).unwrap_or_else(|_| "{}".into());
+            let now = now_ms();
+            let mut value = serde_json::to_value(agent).unwrap_or_default();
+            if let Some(obj) = value.as_object_mut() {
+                obj.insert("age".into(), format_elapsed(agent.created_at_ms, now).into());
+                obj.insert(
+                    "time_on_task".into(),
+                    format_elapsed(agent.assigned_at_ms, now).into(),
+                );
+            }
+            let json = serde_json::to_string_pretty(&value).unwrap_or_else(|_| "{}".into());
             return Response::Ok { output: json };
         }
         // Try task
@@ -383,8 +706,51 @@ impl Sys {
             let json = serde_json::to_string_pretty(folder).unwrap_or_else(|_| "{}".into());
             return Response::Ok { output: json };
         }
-        Response::Error {
-            message: format!("Nothing found named '{}'", name),
+        // Try skill
+        if let Some(skill) = self.library.get(&name) {
+            let value = serde_json::json!({
+                "skill": skill.name,
+                "source": skill.source.to_string(),
+                "priority": skill.priority,
+                "path": skill.path.to_string_lossy(),
+            });
+            let json = serde_json::to_string_pretty(&value).unwrap_or_else(|_| "{}".into());
+            return Response::Ok { output: json };
+        }
+        let message = match self.library.did_you_mean(&name) {
+            Some(guess) => format!("Nothing found named '{}' (did you mean '{}'?)", name, guess),
+            None => format!("Nothing found named '{}'", name),
+        };
+        Response::Error { message }
+    }
+
+    fn cmd_skill_validate(&self) -> Response {
+        let total = self.library.list().len();
+        let errors = self.library.validate();
+        let mut lines = Vec::new();
+        if errors.is_empty() {
+            lines.push(format!("All {} skills valid", total));
+        } else {
+            lines.push(format!("{:<24} {:<20} {}", "Skill", "Source", "Error"));
+            lines.push("-".repeat(70));
+            for (name, err) in &errors {
+                let source = self.library.get(name)
+                    .map(|e| e.source.to_string())
+                    .unwrap_or_default();
+                lines.push(format!("{:<24} {:<20} {}", name, source, err));
+            }
+            lines.push(format!("{} of {} skills invalid", errors.len(), total));
+        }
+        for skipped in self.library.skipped_entries() {
+            lines.push(format!("warning: {}", skipped));
+        }
+        for duplicate in self.library.duplicates() {
+            lines.push(format!("warning: {}", duplicate));
+        }
+        if errors.is_empty() {
+            Response::Ok { output: lines.join("\n") }
+        } else {
+            Response::Error { message: lines.join("\n") }
         }
     }
 
@@ -413,6 +779,8 @@ impl Sys {
             health: HealthState::Unknown,
             last_heartbeat_ms: None,
             session: None,
+            created_at_ms: Some(now_ms()),
+            assigned_at_ms: None,
         };
         if let Err(e) = self.data.agents_mut().add(agent) {
             return Response::Error { message: e };
@@ -427,13 +795,151 @@ impl Sys {
         }
     }
 
-    fn cmd_agent_kill(&mut self, name: String) -> Response {
-        if let Err(e) = self.data.agents_mut().remove(&name) {
-            return Response::Error { message: e };
+    /// Hard kill (the default) removes the agent and emits KillAgent. A
+    /// graceful kill only emits the `shutdown_keys` SendKeys so the agent
+    /// can save and exit; the agent stays registered until
+    /// `finish_pending_kills` kills it after `shutdown_grace_ms`.
+    fn cmd_agent_kill(&mut self, name: String, graceful: bool) -> Response {
+        let keys = self.settings.shutdown_keys.clone();
+        if graceful && !keys.trim().is_empty() {
+            if self.data.agents().get(&name).is_none() {
+                return Response::Error {
+                    message: format!("Agent '{}' not found", name),
+                };
+            }
+            if self.pending_kills.contains_key(&name) {
+                return Response::Ok {
+                    output: format!("Agent '{}' is already shutting down", name),
+                };
+            }
+            self.actions.push(Action::SendKeys { target: name.clone(), keys });
+            let grace = self.settings.shutdown_grace_ms;
+            self.pending_kills.insert(name.clone(), now_ms().saturating_add(grace));
+            return Response::Ok {
+                output: format!("Agent '{}' sent shutdown keys; killing in {}ms", name, grace),
+            };
         }
-        self.actions.push(Action::KillAgent { name: name.clone() });
-        Response::Ok {
-            output: format!("Agent '{}' killed", name),
+        let reconciled = match self.kill_agent_now(&name) {
+            Ok(r) => r,
+            Err(e) => return Response::Error { message: e },
+        };
+        let output = if reconciled.is_empty() {
+            format!("Agent '{}' killed", name)
+        } else {
+            format!("Agent '{}' killed; unassigned tasks: {}", name, reconciled.join(", "))
+        };
+        Response::Ok { output }
+    }
+
+    /// Kill agents whose graceful-shutdown grace period has ended by
+    /// `now_ms`. Called from the daemon tick; returns the killed names.
+    pub fn finish_pending_kills(&mut self, now_ms: u64) -> Vec<String> {
+        let mut due: Vec<String> = self
+            .pending_kills
+            .iter()
+            .filter(|(_, at)| **at <= now_ms)
+            .map(|(name, _)| name.clone())
+            .collect();
+        due.sort();
+        let mut killed = Vec::new();
+        for name in due {
+            self.pending_kills.remove(&name);
+            // The agent may have been hard-killed during its grace period.
+            if self.kill_agent_now(&name).is_ok() {
+                killed.push(name);
+            }
+        }
+        killed
+    }
+
+    /// Remove `name` from the registry and emit its KillAgent. Returns the
+    /// tasks unassigned as a result.
+    fn kill_agent_now(&mut self, name: &str) -> Result<Vec<String>, String> {
+        self.data.agents_mut().remove(name)?;
+        self.pending_kills.remove(name);
+        self.actions.push(Action::KillAgent { name: name.to_string() });
+        Ok(self.reconcile_assignments())
+    }
+
+    fn cmd_agent_kill_where(
+        &mut self,
+        role: Option<String>,
+        status: Option<String>,
+        label: Option<String>,
+    ) -> Response {
+        if role.is_none() && status.is_none() && label.is_none() {
+            return Response::Error {
+                message: "agent.kill.where needs at least one of role, status, or label".into(),
+            };
+        }
+        let status = match status.as_deref().map(parse_agent_status).transpose() {
+            Ok(s) => s,
+            Err(e) => return Response::Error { message: e },
+        };
+        let role = role.map(|r| r.to_lowercase());
+
+        let names: Vec<String> = self
+            .data
+            .agents()
+            .list()
+            .iter()
+            .filter(|a| role.as_ref().is_none_or(|r| a.role.to_lowercase() == *r))
+            .filter(|a| status.as_ref().is_none_or(|s| a.status == *s))
+            .map(|a| (a.name.clone(), a.role.clone()))
+            .filter(|(name, agent_role)| {
+                label.as_ref().is_none_or(|l| self.agent_labels(agent_role, name).contains(l))
+            })
+            .map(|(name, _)| name)
+            .collect();
+
+        let mut killed = Vec::new();
+        let mut failed = Vec::new();
+        let mut reconciled = Vec::new();
+        for name in names {
+            match self.kill_agent_now(&name) {
+                Ok(tasks) => {
+                    killed.push(name);
+                    reconciled.extend(tasks);
+                }
+                Err(e) => failed.push(format!("{} ({})", name, e)),
+            }
+        }
+
+        let mut output = format!(
+            "Killed {} agent{}",
+            killed.len(),
+            if killed.len() == 1 { "" } else { "s" }
+        );
+        if !killed.is_empty() {
+            output.push_str(&format!(": {}", killed.join(", ")));
+        }
+        if !reconciled.is_empty() {
+            output.push_str(&format!("; unassigned tasks: {}", reconciled.join(", ")));
+        }
+        if !failed.is_empty() {
+            output.push_str(&format!("; failed: {}", failed.join(", ")));
+        }
+        Response::Ok { output }
+    }
+
+    /// Labels stored in an agent's `labels` state value — a string or an
+    /// array of strings — falling back to the persisted state.
+    fn agent_labels(&self, role: &str, agent: &str) -> Vec<String> {
+        let path = format!("agent.{}.labels", agent);
+        let value = match self.agent_state.get(&path) {
+            Ok(GetResult::Single(v)) => Some(v),
+            _ => AgentStateManager::new(self.data.config_dir())
+                .and_then(|mgr| mgr.read_state(role, agent))
+                .ok()
+                .and_then(|mut state| state.remove("labels")),
+        };
+        match value {
+            Some(serde_json::Value::String(s)) => vec![s],
+            Some(serde_json::Value::Array(items)) => items
+                .into_iter()
+                .filter_map(|v| v.as_str().map(str::to_string))
+                .collect(),
+            _ => Vec::new(),
         }
     }
 
@@ -468,6 +974,9 @@ impl Sys {
         if let Err(e) = self.data.agents_mut().assign(&name, &task) {
             return Response::Error { message: e };
         }
+        if let Some(a) = self.data.agents_mut().get_mut(&name) {
+            a.assigned_at_ms = Some(now_ms());
+        }
         // Also mark the task as assigned in the task tree
         let _ = self.data.tasks_mut().assign(&task, &name);
         self.actions.push(Action::UpdateAssignment {
@@ -542,10 +1051,31 @@ impl Sys {
         }
     }
 
-    fn cmd_agent_list(&self, format: Option<String>) -> Response {
-        let agents = self.data.agents().list();
+    fn cmd_agent_list(
+        &self,
+        format: Option<String>,
+        sort_by: Option<String>,
+        columns: Option<String>,
+    ) -> Response {
+        let mut agents: Vec<&Agent> = self.data.agents().list().iter().collect();
+        if let Some(key) = sort_by.as_deref() {
+            if let Err(message) = sort_agents(&mut agents, key) {
+                return Response::Error { message };
+            }
+        }
+        let columns = match columns.as_deref() {
+            Some(spec) => match parse_agent_columns(spec) {
+                Ok(c) => c,
+                Err(message) => return Response::Error { message },
+            },
+            None => AGENT_COLUMNS.iter().map(|(name, _)| *name).collect(),
+        };
         if format.as_deref() == Some("json") {
-            let json = serde_json::to_string_pretty(agents).unwrap_or_else(|_| "[]".into());
+            let obj = serde_json::json!({
+                "schema_version": crate::types::SCHEMA_VERSION,
+                "agents": agents,
+            });
+            let json = serde_json::to_string_pretty(&obj).unwrap_or_else(|_| "{}".into());
             return Response::Ok { output: json };
         }
         if agents.is_empty() {
@@ -553,26 +1083,123 @@ impl Sys {
                 output: "No agents".into(),
             };
         }
+        let now = now_ms();
         let mut lines = Vec::new();
         for a in agents {
-            let task_str = a.task.as_deref().unwrap_or("-");
-            lines.push(format!(
-                "{:<16} {:<10} {:<10} {:<12} {}",
-                a.name,
-                a.role,
-                format!("{:?}", a.status).to_lowercase(),
-                format!("{:?}", a.health).to_lowercase(),
-                task_str
-            ));
+            let cells: Vec<String> = columns
+                .iter()
+                .enumerate()
+                .map(|(i, col)| {
+                    let value = agent_column_value(a, col, now);
+                    let width = agent_column_width(col);
+                    if i + 1 == columns.len() {
+                        value
+                    } else {
+                        format!("{:<width$}", value, width = width)
+                    }
+                })
+                .collect();
+            lines.push(cells.join(" "));
         }
         Response::Ok {
             output: lines.join("\n"),
         }
     }
 
-    fn cmd_task_list(&self, format: Option<String>, project: Option<String>) -> Response {
-        let all_tasks = self.data.tasks().flat_list();
-        let tasks: Vec<&(&TaskNode, usize)> = if let Some(ref proj) = project {
+    fn cmd_agent_state_set(&mut self, agent: String, key: String, value: String) -> Response {
+        let role = match self.data.agents().get(&agent) {
+            Some(a) => a.role.clone(),
+            None => {
+                return Response::Error {
+                    message: format!("Agent '{}' not found", agent),
+                }
+            }
+        };
+        let value = serde_json::from_str::<serde_json::Value>(&value)
+            .unwrap_or(serde_json::Value::String(value));
+        let path = format!("agent.{}.{}", agent, key);
+        if let Err(e) = self.agent_state.set(&path, value.clone()) {
+            return Response::Error {
+                message: format!("Invalid state key '{}': {}", key, e),
+            };
+        }
+
+        // Persist the agent's full scratch map so it survives restarts. Start
+        // from what is already on disk so keys set by a previous run are kept;
+        // keys set this session take precedence.
+        let prefix = format!("agent.{}.", agent);
+        let session: Vec<(String, serde_json::Value)> = self
+            .agent_state
+            .export()
+            .iter()
+            .filter_map(|(k, v)| k.strip_prefix(&prefix).map(|rest| (rest.to_string(), v.clone())))
+            .collect();
+        let persisted = AgentStateManager::new(self.data.config_dir()).and_then(|mgr| {
+            let mut state = mgr.read_state(&role, &agent)?;
+            state.extend(session);
+            mgr.write_state(&role, &agent, &state)
+        });
+        if let Err(e) = persisted {
+            return Response::Error {
+                message: format!("Failed to persist state for '{}': {}", agent, e),
+            };
+        }
+
+        Response::Ok {
+            output: value.to_string(),
+        }
+    }
+
+    fn cmd_agent_state_get(&self, agent: String, key: String) -> Response {
+        let role = match self.data.agents().get(&agent) {
+            Some(a) => a.role.clone(),
+            None => {
+                return Response::Error {
+                    message: format!("Agent '{}' not found", agent),
+                }
+            }
+        };
+        let path = format!("agent.{}.{}", agent, key);
+        let value = match self.agent_state.get(&path) {
+            Ok(GetResult::Single(v)) => Some(v),
+            Ok(_) => None,
+            Err(e) => {
+                return Response::Error {
+                    message: format!("Invalid state key '{}': {}", key, e),
+                }
+            }
+        };
+        // Fall back to values persisted by a previous run.
+        let value = value.or_else(|| {
+            AgentStateManager::new(self.data.config_dir())
+                .and_then(|mgr| mgr.read_state(&role, &agent))
+                .ok()
+                .and_then(|mut state| state.remove(&key))
+        });
+        match value {
+            Some(v) => Response::Ok {
+                output: v.to_string(),
+            },
+            None => Response::Error {
+                message: format!("State key '{}' not found for agent '{}'", key, agent),
+            },
+        }
+    }
+
+    fn cmd_task_list(
+        &self,
+        format: Option<String>,
+        project: Option<String>,
+        max_depth: Option<usize>,
+        status: Option<String>,
+        agent: Option<String>,
+    ) -> Response {
+        let status = match status.as_deref().map(parse_task_status).transpose() {
+            Ok(s) => s,
+            Err(e) => return Response::Error { message: e },
+        };
+        let all_tasks = self.data.tasks().flat_list_filtered(max_depth, status.clone());
+        let mut tasks: Vec<&(&TaskNode, usize)> = if let Some(ref proj) = project {
             all_tasks
                 .iter()
                 .filter(|(t, _depth)| t.id.starts_with(proj.as_str()))
@@ -580,6 +1207,14 @@ impl Sys {
         } else {
             all_tasks.iter().collect()
         };
+        // An agent filter selects individual tasks, so status context rows
+        // that are not themselves a match are dropped too.
+        if let Some(ref agent) = agent {
+            tasks.retain(|(t, _)| {
+                t.agent.as_deref() == Some(agent.as_str())
+                    && status.as_ref().is_none_or(|s| t.status == *s)
+            });
+        }
         if format.as_deref() == Some("json") {
             let nodes: Vec<&TaskNode> = tasks.iter().map(|(t, _)| *t).collect();
             let json = serde_json::to_string_pretty(&nodes).unwrap_or_else(|_| "[]".into());
@@ -590,18 +1225,32 @@ impl Sys {
                 output: "No tasks".into(),
             };
         }
+        let tree = self.data.tasks();
+        let now = now_ms();
         let mut lines = Vec::new();
         for (t, depth) in &tasks {
             let indent = "  ".repeat(*depth);
             let agent_str = t.agent.as_deref().unwrap_or("-");
-            lines.push(format!(
+            let state = tree.display_state(t);
+            let state_str = state.to_string();
+            let mut line = format!(
                 "{}{:<12} {:<30} {:<12} {}",
-                indent,
-                t.id,
-                t.title,
-                format!("{:?}", t.status).to_lowercase(),
-                agent_str
-            ));
+                indent, t.id, t.title, state_str, agent_str
+            );
+            if let TaskDisplayState::Blocked(ref deps) = state {
+                line.push_str(&format!("  (blocked by {})", deps.join(", ")));
+            }
+            if t.is_overdue(now) {
+                line.push_str("  (overdue)");
+            }
+            // Ancestors kept only to show where a match sits.
+            if status.as_ref().is_some_and(|s| t.status != *s) {
+                line.push_str("  (context)");
+            }
+            lines.push(line);
+        }
+        for cycle in tree.cycles() {
+            lines.push(format!("warning: task cycle {} (not expanded)", cycle));
         }
         Response::Ok {
             output: lines.join("\n"),
@@ -620,6 +1269,7 @@ impl Sys {
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn cmd_task_set(
         &mut self,
         id: String,
@@ -627,7 +1277,18 @@ impl Sys {
         title: Option<String>,
         result: Option<String>,
         agent: Option<String>,
+        due: Option<String>,
+        cascade: bool,
     ) -> Response {
+        if cascade && status.is_none() {
+            return Response::Error {
+                message: "--cascade requires a status=<value> field".into(),
+            };
+        }
+        let due_at_ms = match due.as_deref().map(|d| parse_due(d, now_ms())).transpose() {
+            Ok(d) => d,
+            Err(e) => return Response::Error { message: e },
+        };
         let task = match self.data.tasks_mut().get_mut(&id) {
             Some(t) => t,
             None => {
@@ -643,7 +1304,11 @@ impl Sys {
                 Ok(s) => s,
                 Err(e) => return Response::Error { message: e },
             };
-            task.status = parsed.clone();
+            // A cascade applies the status itself, so the root is counted
+            // the same way `task check --cascade` counts it.
+            if !cascade {
+                task.status = parsed.clone();
+            }
             new_status = Some(parsed);
         }
         if let Some(title) = title {
@@ -659,41 +1324,114 @@ impl Sys {
                 Some(agent)
             };
         }
-        if let Some(ref s) = new_status {
-            self.roadmap_write_back(&id, s);
+        if let Some(due_at_ms) = due_at_ms {
+            task.due_at_ms = due_at_ms;
         }
-        Response::Ok {
-            output: format!("Task '{}' updated", id),
+        match new_status {
+            Some(s) if cascade => {
+                let count = self.cascade_task_status(&id, s);
+                Response::Ok {
+                    output: format!("Task '{}' updated ({} tasks changed status)", id, count),
+                }
+            }
+            Some(s) => {
+                self.roadmap_write_back(&[&id], &s);
+                Response::Ok {
+                    output: format!("Task '{}' updated", id),
+                }
+            }
+            None => Response::Ok {
+                output: format!("Task '{}' updated", id),
+            },
         }
     }
 
-    fn cmd_task_check(&mut self, id: String) -> Response {
+    fn cmd_task_check(&mut self, id: String, cascade: bool) -> Response {
+        if cascade {
+            if self.data.tasks().get(&id).is_none() {
+                return Response::Error {
+                    message: format!("task not found: {}", id),
+                };
+            }
+            let count = self.cascade_task_status(&id, TaskStatus::Completed);
+            return Response::Ok {
+                output: format!("Task '{}' marked completed ({} tasks changed)", id, count),
+            };
+        }
         if let Err(e) = self.data.tasks_mut().set_status(&id, TaskStatus::Completed) {
             return Response::Error { message: e };
         }
-        self.roadmap_write_back(&id, &TaskStatus::Completed);
+        self.roadmap_write_back(&[&id], &TaskStatus::Completed);
         Response::Ok {
             output: format!("Task '{}' marked completed", id),
         }
     }
 
+    /// Apply `status` to task `id` and its subtree, writing each changed
+    /// task (and `id` itself) back to the roadmap. The task must exist.
+    /// Returns how many tasks changed.
+    fn cascade_task_status(&mut self, id: &str, status: TaskStatus) -> usize {
+        let changed = self
+            .data
+            .tasks_mut()
+            .cascade_status(id, status.clone())
+            .unwrap_or_default();
+        let mut ids = vec![id];
+        ids.extend(changed.iter().map(String::as_str).filter(|t| *t != id));
+        self.roadmap_write_back(&ids, &status);
+        changed.len()
+    }
+
     fn cmd_task_uncheck(&mut self, id: String) -> Response {
         if let Err(e) = self.data.tasks_mut().set_status(&id, TaskStatus::Pending) {
             return Response::Error { message: e };
         }
-        self.roadmap_write_back(&id, &TaskStatus::Pending);
+        self.roadmap_write_back(&[&id], &TaskStatus::Pending);
         Response::Ok {
             output: format!("Task '{}' marked pending", id),
         }
     }
 
-    fn cmd_config_load(&mut self, path: Option<String>) -> Response {
-        let path = path
+    fn cmd_task_note(&mut self, id: String, text: String) -> Response {
+        let task = match self.data.tasks_mut().get_mut(&id) {
+            Some(t) => t,
+            None => {
+                return Response::Error {
+                    message: format!("Task '{}' not found", id),
+                }
+            }
+        };
+        task.notes.push((now_ms(), text));
+        Response::Ok {
+            output: format!("Note added to task '{}' ({} total)", id, task.notes.len()),
+        }
+    }
+
+    fn cmd_task_move(&mut self, id: String, parent: String) -> Response {
+        match self.data.tasks_mut().reparent(&id, &parent) {
+            Ok(()) => Response::Ok {
+                output: format!("Task '{}' moved under '{}'", id, parent),
+            },
+            Err(e) => Response::Error { message: e },
+        }
+    }
+
+    fn cmd_config_load(&mut self, path: Option<String>) -> Response {
+        let path = path
             .map(std::path::PathBuf::from)
             .unwrap_or_else(|| self.data.config_dir().join("settings.yaml"));
         match crate::data::settings::load(&path) {
             Ok(loaded) => {
+                let warnings = std::fs::read_to_string(&path)
+                    .map(|content| {
+                        crate::data::settings::validate(&crate::data::settings::raw_document(&content))
+                    })
+                    .unwrap_or_default();
+                self.data.set_settings_warnings(warnings);
+                self.data.messages_mut().set_min_interval(loaded.message_interval);
+                self.saved_settings = loaded.clone();
                 self.settings = loaded;
+                self.snapshot_settings_if_enabled(now_ms());
                 Response::Ok {
                     output: format!("Settings loaded from {}", path.display()),
                 }
@@ -702,18 +1440,39 @@ impl Sys {
         }
     }
 
-    fn cmd_config_save(&self, path: Option<String>) -> Response {
-        let path = path
-            .map(std::path::PathBuf::from)
-            .unwrap_or_else(|| self.data.config_dir().join("settings.yaml"));
+    fn cmd_config_save(&mut self, path: Option<String>) -> Response {
+        let canonical = self.data.config_dir().join("settings.yaml");
+        let path = path.map(std::path::PathBuf::from).unwrap_or_else(|| canonical.clone());
         match crate::data::settings::save(&path, &self.settings) {
-            Ok(()) => Response::Ok {
-                output: format!("Settings saved to {}", path.display()),
-            },
+            Ok(()) => {
+                // Only settings.yaml is loaded at startup; a copy elsewhere
+                // leaves the runtime changes unsaved.
+                if path == canonical {
+                    self.saved_settings = self.settings.clone();
+                }
+                self.snapshot_settings_if_enabled(now_ms());
+                Response::Ok {
+                    output: format!("Settings saved to {}", path.display()),
+                }
+            }
             Err(e) => Response::Error { message: e },
         }
     }
 
+    /// When `auto_snapshot_on_change` is set, record the current settings in
+    /// settings history. Unchanged settings are deduplicated by content, so
+    /// a no-op change adds no entry. Returns whether an entry was created.
+    fn snapshot_settings_if_enabled(&self, now_ms: u64) -> bool {
+        if !self.settings.auto_snapshot_on_change {
+            return false;
+        }
+        let content = crate::data::settings::serialize(&self.settings);
+        match HistoryManager::for_settings(self.data.config_dir().to_path_buf()) {
+            Ok(mgr) => matches!(mgr.snapshot_content(&content, now_ms), Ok(Some(_))),
+            Err(_) => false,
+        }
+    }
+
     fn cmd_config_add(&mut self, key: String, value: String) -> Response {
         match key.as_str() {
             "project_root" => self.settings.project_root = value.clone(),
@@ -725,35 +1484,83 @@ impl Sys {
                     }
                 }
             },
-            "health_check_interval" => match value.parse::<u64>() {
+            "health_check_interval" => match crate::data::settings::parse_duration_ms(&key, &value) {
                 Ok(n) => self.settings.health_check_interval = n,
-                Err(_) => {
-                    return Response::Error {
-                        message: format!("Invalid u64: {}", value),
-                    }
-                }
+                Err(e) => return Response::Error { message: e },
             },
-            "heartbeat_timeout" => match value.parse::<u64>() {
+            "heartbeat_timeout" => match crate::data::settings::parse_duration_ms(&key, &value) {
                 Ok(n) => self.settings.heartbeat_timeout = n,
-                Err(_) => {
-                    return Response::Error {
-                        message: format!("Invalid u64: {}", value),
-                    }
-                }
+                Err(e) => return Response::Error { message: e },
             },
-            "message_timeout" => match value.parse::<u64>() {
+            "message_timeout" => match crate::data::settings::parse_duration_ms(&key, &value) {
                 Ok(n) => self.settings.message_timeout = n,
-                Err(_) => {
-                    return Response::Error {
-                        message: format!("Invalid u64: {}", value),
-                    }
+                Err(e) => return Response::Error { message: e },
+            },
+            "message_interval" => match crate::data::settings::parse_duration_ms(&key, &value) {
+                Ok(n) => {
+                    self.settings.message_interval = n;
+                    self.data.messages_mut().set_min_interval(n);
                 }
+                Err(e) => return Response::Error { message: e },
             },
-            "escalation_timeout" => match value.parse::<u64>() {
+            "escalation_timeout" => match crate::data::settings::parse_duration_ms(&key, &value) {
                 Ok(n) => self.settings.escalation_timeout = n,
-                Err(_) => {
+                Err(e) => return Response::Error { message: e },
+            },
+            "auto_snapshot_on_change" => match crate::data::settings::parse_bool(&key, &value) {
+                Ok(b) => self.settings.auto_snapshot_on_change = b,
+                Err(e) => return Response::Error { message: e },
+            },
+            "heartbeat_marker" => self.settings.heartbeat_marker = value.clone(),
+            "shutdown_keys" => self.settings.shutdown_keys = value.clone(),
+            "shutdown_grace_ms" => match crate::data::settings::parse_duration_ms(&key, &value) {
+                Ok(n) => self.settings.shutdown_grace_ms = n,
+                Err(e) => return Response::Error { message: e },
+            },
+            "session_backend" => match SessionBackendKind::parse(&value) {
+                Ok(kind) => self.settings.session_backend = kind,
+                Err(e) => return Response::Error { message: e },
+            },
+            "skill_conflict_strategy" => match ConflictStrategy::parse(&value) {
+                Ok(strategy) => {
+                    self.settings.skill_conflict_strategy = strategy;
+                    self.rebuild_library();
+                }
+                Err(e) => return Response::Error { message: e },
+            },
+            k if k.starts_with("heartbeat_marker.") => {
+                let role = &k["heartbeat_marker.".len()..];
+                if role.is_empty() {
+                    return Response::Error {
+                        message: "heartbeat_marker.<role> needs a role name".into(),
+                    };
+                }
+                self.settings
+                    .heartbeat_marker_overrides
+                    .insert(role.to_string(), value.clone());
+            }
+            k if k.starts_with("heartbeat_pattern.") => {
+                let agent_type = match AgentType::parse(&k["heartbeat_pattern.".len()..])
+                    .and_then(|t| crate::monitor::heartbeat::HeartbeatPattern::new(&value).map(|_| t))
+                {
+                    Ok(t) => t,
+                    Err(e) => return Response::Error { message: e },
+                };
+                self.settings
+                    .heartbeat_patterns
+                    .insert(agent_type.as_str().to_string(), value.clone());
+            }
+            "stall_after_unhealthy" | "clear_after_healthy" => match value.parse::<u32>() {
+                Ok(n) if n >= 1 => {
+                    if key == "stall_after_unhealthy" {
+                        self.settings.stall_after_unhealthy = n;
+                    } else {
+                        self.settings.clear_after_healthy = n;
+                    }
+                }
+                _ => {
                     return Response::Error {
-                        message: format!("Invalid u64: {}", value),
+                        message: format!("Invalid count for {} (need >= 1): {}", key, value),
                     }
                 }
             },
@@ -763,13 +1570,74 @@ impl Sys {
                 }
             }
         }
+        self.snapshot_settings_if_enabled(now_ms());
         Response::Ok {
             output: format!("Config '{}' set to '{}'", key, value),
         }
     }
 
+    fn cmd_config_unset(&mut self, key: String) -> Response {
+        let defaults = crate::data::settings::default_settings();
+        match key.as_str() {
+            "project_root" => self.settings.project_root = defaults.project_root,
+            "max_retries" => self.settings.max_retries = defaults.max_retries,
+            "health_check_interval" => {
+                self.settings.health_check_interval = defaults.health_check_interval
+            }
+            "heartbeat_timeout" => self.settings.heartbeat_timeout = defaults.heartbeat_timeout,
+            "message_timeout" => self.settings.message_timeout = defaults.message_timeout,
+            "message_interval" => {
+                self.settings.message_interval = defaults.message_interval;
+                self.data.messages_mut().set_min_interval(defaults.message_interval);
+            }
+            "escalation_timeout" => self.settings.escalation_timeout = defaults.escalation_timeout,
+            "auto_snapshot_on_change" => {
+                self.settings.auto_snapshot_on_change = defaults.auto_snapshot_on_change
+            }
+            "heartbeat_marker" => self.settings.heartbeat_marker = defaults.heartbeat_marker,
+            "shutdown_keys" => self.settings.shutdown_keys = defaults.shutdown_keys,
+            "shutdown_grace_ms" => self.settings.shutdown_grace_ms = defaults.shutdown_grace_ms,
+            "session_backend" => self.settings.session_backend = defaults.session_backend,
+            "skill_conflict_strategy" => {
+                self.settings.skill_conflict_strategy = defaults.skill_conflict_strategy;
+                self.rebuild_library();
+            }
+            "stall_after_unhealthy" => {
+                self.settings.stall_after_unhealthy = defaults.stall_after_unhealthy
+            }
+            "clear_after_healthy" => self.settings.clear_after_healthy = defaults.clear_after_healthy,
+            k if k.starts_with("heartbeat_marker.") => {
+                let role = &k["heartbeat_marker.".len()..];
+                if role.is_empty() {
+                    return Response::Error {
+                        message: "heartbeat_marker.<role> needs a role name".into(),
+                    };
+                }
+                self.settings.heartbeat_marker_overrides.remove(role);
+            }
+            k if k.starts_with("heartbeat_pattern.") => {
+                match AgentType::parse(&k["heartbeat_pattern.".len()..]) {
+                    Ok(t) => self.settings.heartbeat_patterns.remove(t.as_str()),
+                    Err(e) => return Response::Error { message: e },
+                };
+            }
+            _ => {
+                return Response::Error {
+                    message: format!("Unknown config key: {}", key),
+                }
+            }
+        }
+        self.snapshot_settings_if_enabled(now_ms());
+        Response::Ok {
+            output: format!("Config '{}' reset to default", key),
+        }
+    }
+
     fn cmd_config_list(&self) -> Response {
-        let text = crate::data::settings::serialize(&self.settings);
+        let mut text = crate::data::settings::serialize(&self.settings);
+        for warning in self.data.settings_warnings() {
+            text.push_str(&format!("# warning: {}\n", warning));
+        }
         Response::Ok { output: text }
     }
 
@@ -791,6 +1659,9 @@ impl Sys {
             agent: None,
             children: vec![],
             spec_path: Some(path.clone()),
+            depends_on: Vec::new(),
+            notes: Vec::new(),
+            due_at_ms: None,
         };
         let _ = self.data.tasks_mut().add_root(task);
 
@@ -879,13 +1750,18 @@ impl Sys {
         }
     }
 
+    /// Rebuild the skill library from the project folders and settings.
+    fn rebuild_library(&mut self) {
+        let lib_config = build_library_config(&self.data, &self.settings);
+        self.library = Library::new(&lib_config).unwrap_or_else(|_| Library::empty());
+    }
+
     fn cmd_project_remove(&mut self, name: String) -> Response {
         if let Err(e) = self.data.folders_mut().remove(&name) {
             return Response::Error { message: e };
         }
         // Rebuild library without the removed project's skills
-        let lib_config = build_library_config(&self.data);
-        self.library = Library::new(&lib_config).unwrap_or_else(|_| Library::empty());
+        self.rebuild_library();
         Response::Ok {
             output: format!("Project '{}' removed", name),
         }
@@ -960,18 +1836,23 @@ impl Sys {
         }
     }
 
-    /// Write back a task's status change to all loaded roadmap files.
-    fn roadmap_write_back(&self, task_id: &str, status: &TaskStatus) {
+    /// Write back a status change for `task_ids` to all loaded roadmap
+    /// files. Each file is read once and written at most once.
+    fn roadmap_write_back(&self, task_ids: &[&str], status: &TaskStatus) {
         for roadmap_path in self.data.roadmap_paths() {
             let content = match std::fs::read_to_string(roadmap_path) {
                 Ok(c) => c,
                 Err(_) => continue,
             };
-            match crate::data::roadmap::update_status_in_place(&content, task_id, status) {
-                Ok(updated) => {
-                    let _ = std::fs::write(roadmap_path, updated);
+            let mut updated = content.clone();
+            for task_id in task_ids {
+                // Tasks not in this roadmap file are left alone.
+                if let Ok(next) = crate::data::roadmap::update_status_in_place(&updated, task_id, status) {
+                    updated = next;
                 }
-                Err(_) => continue, // Task not in this roadmap file
+            }
+            if updated != content {
+                let _ = crate::data::write_atomic(roadmap_path, &updated);
             }
         }
     }
@@ -1006,25 +1887,63 @@ impl Sys {
 
     fn cmd_pool_status(&self, role: String) -> Response {
         match self.pool.pool_state(&role, self.data.agents()) {
-            Some(state) => Response::Ok {
-                output: format!(
+            Some(state) => {
+                let mut output = format!(
                     "Pool '{}': {} idle, {} busy, {} spawning, {} total (target: {})",
                     role, state.idle_count, state.busy_count,
                     state.spawning_count, state.total, state.config.target_size
-                ),
-            },
+                );
+                // Only this pool's project work counts toward its load.
+                let pending = self
+                    .data
+                    .tasks()
+                    .ready_tasks_under(Path::new(&state.config.path))
+                    .iter()
+                    .filter(|t| t.agent.is_none())
+                    .count();
+                let actions = self.pool.autoscale(&role, pending, self.data.agents());
+                if !actions.is_empty() {
+                    let spawns = actions
+                        .iter()
+                        .filter(|a| matches!(a, PoolAction::Spawn { .. }))
+                        .count();
+                    let retires: Vec<&str> = actions
+                        .iter()
+                        .filter_map(|a| match a {
+                            PoolAction::Retire { agent } => Some(agent.as_str()),
+                            PoolAction::Spawn { .. } => None,
+                        })
+                        .collect();
+                    if spawns > 0 {
+                        output.push_str(&format!("; autoscale: spawn {}", spawns));
+                    } else {
+                        output.push_str(&format!("; autoscale: retire {}", retires.join(", ")));
+                    }
+                }
+                Response::Ok { output }
+            }
             None => Response::Error {
                 message: format!("No pool configured for role '{}'", role),
             },
         }
     }
 
-    fn cmd_pool_set(&mut self, role: String, size: u32, path: Option<String>) -> Response {
+    fn cmd_pool_set(
+        &mut self,
+        role: String,
+        size: u32,
+        path: Option<String>,
+        min_idle: Option<u32>,
+    ) -> Response {
         let path = path.unwrap_or_else(|| self.settings.project_root.clone());
+        let min_idle = min_idle
+            .or_else(|| self.pool.get_config(&role).map(|c| c.min_idle))
+            .unwrap_or(0);
         self.pool.set_pool(&role, PoolConfig {
             target_size: size,
             auto_expand: self.settings.pool_auto_expand,
             max_size: size * 2,
+            min_idle,
             path: path.clone(),
         });
         // Compute deficit and create agents one at a time so next_name() sees
@@ -1044,6 +1963,8 @@ impl Sys {
                 health: HealthState::Unknown,
                 last_heartbeat_ms: None,
                 session: None,
+                created_at_ms: Some(now_ms()),
+                assigned_at_ms: None,
             };
             if self.data.agents_mut().add(agent).is_ok() {
                 spawned += 1;
@@ -1070,7 +1991,7 @@ impl Sys {
     // Messaging command handlers
     // -----------------------------------------------------------------------
 
-    fn cmd_tell(&mut self, agent: String, text: String) -> Response {
+    fn cmd_tell(&mut self, agent: String, text: String, dedup: bool) -> Response {
         // Verify agent exists
         if self.data.agents().get(&agent).is_none() {
             return Response::Error {
@@ -1084,7 +2005,15 @@ impl Sys {
             queued_at_ms: now_ms(),
             delivered_at_ms: None,
         };
-        self.data.messages_mut().enqueue(msg);
+        if dedup {
+            if !self.data.messages_mut().enqueue_dedup(msg) {
+                return Response::Ok {
+                    output: format!("Duplicate message for '{}' already pending; not queued", agent),
+                };
+            }
+        } else {
+            self.data.messages_mut().enqueue(msg);
+        }
         self.actions.push(Action::SendKeys {
             target: agent.clone(),
             keys: text,
@@ -1094,6 +2023,29 @@ impl Sys {
         }
     }
 
+    /// Queue the keystrokes for `agent.exec`. Capturing the output is the
+    /// daemon's job (see `monitor::exec`); this only validates and emits
+    /// the SendKeys, so a direct call just types the command.
+    fn cmd_agent_exec(&mut self, agent: String, cmd: String) -> Response {
+        if self.data.agents().get(&agent).is_none() {
+            return Response::Error {
+                message: format!("Agent '{}' not found", agent),
+            };
+        }
+        if cmd.trim().is_empty() {
+            return Response::Error {
+                message: "agent.exec needs a command to run".into(),
+            };
+        }
+        self.actions.push(Action::SendKeys {
+            target: agent.clone(),
+            keys: cmd.clone(),
+        });
+        Response::Ok {
+            output: format!("Sent to '{}': {}", agent, cmd),
+        }
+    }
+
     fn cmd_interrupt(&mut self, agent: String, text: Option<String>) -> Response {
         if self.data.agents().get(&agent).is_none() {
             return Response::Error {
@@ -1123,13 +2075,70 @@ impl Sys {
     // Diagnosis command handlers
     // -----------------------------------------------------------------------
 
-    fn cmd_diagnosis_report(&self) -> Response {
-        match DiagnosisEngine::new(self.data.config_dir().to_path_buf()) {
-            Ok(engine) => Response::Ok {
-                output: engine.generate_report(),
+    fn cmd_diagnosis_report(
+        &self,
+        since: Option<String>,
+        until: Option<String>,
+        dest: Option<String>,
+    ) -> Response {
+        let now = now_ms();
+        let bound = |key: &str, val: Option<String>| {
+            val.map(|v| parse_time_bound(key, &v, now)).transpose()
+        };
+        let window = match (bound("since", since), bound("until", until)) {
+            (Ok(s), Ok(u)) => ReportWindow::new(s, u),
+            (Err(e), _) | (_, Err(e)) => return Response::Error { message: e },
+        };
+        if let (Some(s), Some(u)) = (window.since_ms, window.until_ms) {
+            if s >= u {
+                return Response::Error {
+                    message: format!("Empty report window: since {} is not before until {}", s, u),
+                };
+            }
+        }
+        let engine = match DiagnosisEngine::new(self.data.config_dir().to_path_buf()) {
+            Ok(e) => e,
+            Err(e) => {
+                return Response::Error {
+                    message: format!("Failed to load diagnosis data: {}", e),
+                }
+            }
+        };
+        let dest = match dest {
+            Some(d) => d,
+            None => {
+                let report = if window.is_unbounded() {
+                    engine.generate_report()
+                } else {
+                    engine.generate_window_report(&window)
+                };
+                return Response::Ok { output: report };
+            }
+        };
+
+        let path = Path::new(&dest);
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            if let Err(e) = std::fs::create_dir_all(parent) {
+                return Response::Error {
+                    message: format!("Failed to create {}: {}", parent.display(), e),
+                };
+            }
+        }
+        let written = std::fs::File::create(path).and_then(|file| {
+            let mut out = std::io::BufWriter::new(file);
+            if window.is_unbounded() {
+                engine.write_report(&mut out)?;
+            } else {
+                engine.write_window_report(&mut out, &window)?;
+            }
+            std::io::Write::flush(&mut out)
+        });
+        match written {
+            Ok(()) => Response::Ok {
+                output: format!("Wrote diagnosis report to {}", dest),
             },
             Err(e) => Response::Error {
-                message: format!("Failed to load diagnosis data: {}", e),
+                message: format!("Failed to write {}: {}", dest, e),
             },
         }
     }
@@ -1137,8 +2146,14 @@ impl Sys {
     fn cmd_diagnosis_reliability(
         &self,
         signal: Option<String>,
+        agent: Option<String>,
+        since: Option<String>,
         format: Option<String>,
     ) -> Response {
+        let since_ms = match since.map(|v| parse_time_bound("since", &v, now_ms())).transpose() {
+            Ok(s) => s,
+            Err(e) => return Response::Error { message: e },
+        };
         let engine = match DiagnosisEngine::new(self.data.config_dir().to_path_buf()) {
             Ok(e) => e,
             Err(e) => {
@@ -1148,12 +2163,31 @@ impl Sys {
             }
         };
 
+        // Scoped stats are computed on demand; the engine caches only the
+        // all-time, all-agent aggregate.
+        let scoped = if agent.is_some() || since_ms.is_some() {
+            Some(engine.scoped_reliability(agent.as_deref(), since_ms))
+        } else {
+            None
+        };
+        let mut scope = String::new();
+        if let Some(a) = &agent {
+            scope.push_str(&format!(" for agent '{}'", a));
+        }
+        if let Some(s) = since_ms {
+            scope.push_str(&format!(" since {} ms", s));
+        }
+
         if let Some(signal_str) = signal {
             let signal_type = match parse_signal_type(&signal_str) {
                 Ok(s) => s,
                 Err(e) => return Response::Error { message: e },
             };
-            match engine.signal_reliability(&signal_type) {
+            let rel = match &scoped {
+                Some(map) => map.get(&signal_type),
+                None => engine.signal_reliability(&signal_type),
+            };
+            match rel {
                 Some(rel) => {
                     if format.as_deref() == Some("json") {
                         let json = serde_json::to_string_pretty(rel)
@@ -1166,14 +2200,26 @@ impl Sys {
                     }
                 }
                 None => Response::Ok {
-                    output: format!("No reliability data for signal '{}'", signal_str),
+                    output: format!("No reliability data for signal '{}'{}", signal_str, scope),
                 },
             }
         } else {
-            let all = engine.all_reliability();
+            let all = match &scoped {
+                Some(map) => {
+                    let mut entries: Vec<&crate::diagnosis::SignalReliability> =
+                        map.values().collect();
+                    entries.sort_by(|a, b| {
+                        a.reliability_score
+                            .partial_cmp(&b.reliability_score)
+                            .unwrap_or(std::cmp::Ordering::Equal)
+                    });
+                    entries
+                }
+                None => engine.all_reliability(),
+            };
             if all.is_empty() {
                 return Response::Ok {
-                    output: "No reliability data recorded yet.".into(),
+                    output: format!("No reliability data recorded yet{}.", scope),
                 };
             }
             if format.as_deref() == Some("json") {
@@ -1191,8 +2237,20 @@ impl Sys {
     fn cmd_diagnosis_effectiveness(
         &self,
         signal: Option<String>,
+        min_attempts: Option<String>,
         format: Option<String>,
     ) -> Response {
+        let min_attempts = match min_attempts {
+            Some(s) => match s.parse::<u64>() {
+                Ok(n) => n,
+                Err(_) => {
+                    return Response::Error {
+                        message: format!("Invalid --min-attempts value: '{}'", s),
+                    }
+                }
+            },
+            None => crate::diagnosis::report::MIN_SAMPLES,
+        };
         let engine = match DiagnosisEngine::new(self.data.config_dir().to_path_buf()) {
             Ok(e) => e,
             Err(e) => {
@@ -1248,7 +2306,7 @@ impl Sys {
             Response::Ok { output: json }
         } else {
             Response::Ok {
-                output: format_effectiveness_table(&entries),
+                output: format_effectiveness_table(&entries, min_attempts),
             }
         }
     }
@@ -1329,6 +2387,10 @@ impl Sys {
             let json = serde_json::to_string_pretty(events)
                 .unwrap_or_else(|_| "[]".into());
             Response::Ok { output: json }
+        } else if format.as_deref() == Some("csv") {
+            Response::Ok {
+                output: events_to_csv(events),
+            }
         } else {
             let mut lines = Vec::new();
             lines.push(format!(
@@ -1347,6 +2409,9 @@ impl Sys {
                     format!("{:?}", e.outcome).to_lowercase(),
                     format!("{}ms", e.duration_ms)
                 ));
+                for (ts, note) in &e.annotations {
+                    lines.push(format!("       note {}: {}", ts, note));
+                }
             }
             Response::Ok {
                 output: lines.join("\n"),
@@ -1354,6 +2419,75 @@ impl Sys {
         }
     }
 
+    fn cmd_diagnosis_annotate(&self, id: String, note: String) -> Response {
+        let event_id = match id.parse::<u64>() {
+            Ok(n) => n,
+            Err(_) => {
+                return Response::Error {
+                    message: format!("Invalid event id: {}", id),
+                }
+            }
+        };
+        if note.trim().is_empty() {
+            return Response::Error {
+                message: "diagnosis.annotate needs a note".into(),
+            };
+        }
+        let mut engine = match DiagnosisEngine::new(self.data.config_dir().to_path_buf()) {
+            Ok(e) => e,
+            Err(e) => {
+                return Response::Error {
+                    message: format!("Failed to load diagnosis data: {}", e),
+                }
+            }
+        };
+        match engine.annotate(event_id, &note, now_ms()) {
+            Ok(()) => Response::Ok {
+                output: format!("Annotated event {}", event_id),
+            },
+            Err(e) => Response::Error {
+                message: format!("Failed to annotate event {}: {}", event_id, e),
+            },
+        }
+    }
+
+    fn cmd_diagnosis_compare(&self, a: String, b: String, format: Option<String>) -> Response {
+        let engine = match DiagnosisEngine::new(self.data.config_dir().to_path_buf()) {
+            Ok(e) => e,
+            Err(e) => {
+                return Response::Error {
+                    message: format!("Failed to load diagnosis data: {}", e),
+                }
+            }
+        };
+
+        let rows = engine.compare_agents(&a, &b);
+        if rows.is_empty() {
+            return Response::Ok {
+                output: format!("No reliability data for '{}' or '{}'.", a, b),
+            };
+        }
+
+        if format.as_deref() == Some("json") {
+            let entries: Vec<serde_json::Value> = rows
+                .iter()
+                .map(|(signal, ra, rb)| {
+                    serde_json::json!({
+                        "signal": signal.to_string(),
+                        "a": ra,
+                        "b": rb,
+                    })
+                })
+                .collect();
+            let json = serde_json::to_string_pretty(&entries).unwrap_or_else(|_| "[]".into());
+            Response::Ok { output: json }
+        } else {
+            Response::Ok {
+                output: format_compare_table(&a, &b, &rows),
+            }
+        }
+    }
+
     // -----------------------------------------------------------------------
     // Learnings command handlers
     // -----------------------------------------------------------------------
@@ -1465,8 +2599,11 @@ impl Sys {
         }
     }
 
-    fn cmd_help(&self, topic: Option<String>) -> Response {
-        let text = crate::help::help_text(topic.as_deref());
+    fn cmd_help(&self, topic: Option<String>, search: Option<String>) -> Response {
+        let text = match search {
+            Some(query) => crate::help::search(&query),
+            None => crate::help::help_text(topic.as_deref()),
+        };
         Response::Ok { output: text }
     }
 
@@ -1477,8 +2614,16 @@ impl Sys {
     fn cmd_rig_init(&mut self, host: String, name: Option<String>) -> Response {
         let remote_name = name.unwrap_or_else(|| "default".into());
         let config = parse_host_string(&host, &remote_name);
+        let rig_path = self.data.config_dir().join(RIG_FILE);
         if let Some(rig) = &mut self.rig {
-            let _ = rig.registry.add(config);
+            if let Err(e) = rig.registry.add(config) {
+                return Response::Error { message: e };
+            }
+            if let Err(e) = rig.registry.save(&rig_path) {
+                return Response::Error {
+                    message: format!("Failed to persist rig registry: {}", e),
+                };
+            }
             match rig.init_remote(&remote_name) {
                 Ok(msg) => Response::Ok { output: msg },
                 Err(e) => Response::Error { message: e },
@@ -1488,8 +2633,33 @@ impl Sys {
         }
     }
 
-    fn cmd_rig_push(&mut self, folder: String, remote: Option<String>) -> Response {
+    fn cmd_rig_push(
+        &mut self,
+        folder: String,
+        remote: Option<String>,
+        label: Option<String>,
+        all: bool,
+        options: SyncOptions,
+    ) -> Response {
         if let Some(rig) = &mut self.rig {
+            if all {
+                if remote.is_some() || label.is_some() {
+                    return Response::Error { message: "--all cannot be combined with a remote or a label".into() };
+                }
+                return match rig.push_all_remotes(&folder, &options) {
+                    Ok(msg) => Response::Ok { output: msg },
+                    Err(e) => Response::Error { message: e },
+                };
+            }
+            if let Some(label) = label {
+                if remote.is_some() {
+                    return Response::Error { message: "Specify either a remote or a label, not both".into() };
+                }
+                return match rig.push_label(&label, &folder, &options) {
+                    Ok(msg) => Response::Ok { output: msg },
+                    Err(e) => Response::Error { message: e },
+                };
+            }
             let name = match remote {
                 Some(n) => n,
                 None => match rig.registry.default_name() {
@@ -1497,7 +2667,7 @@ impl Sys {
                     None => return Response::Error { message: "No remote specified and no default set".into() },
                 },
             };
-            match rig.push(&name, &folder) {
+            match rig.push(&name, &folder, &options) {
                 Ok(msg) => Response::Ok { output: msg },
                 Err(e) => Response::Error { message: e },
             }
@@ -1506,8 +2676,23 @@ impl Sys {
         }
     }
 
-    fn cmd_rig_pull(&mut self, folder: String, remote: Option<String>) -> Response {
+    fn cmd_rig_pull(
+        &mut self,
+        folder: String,
+        remote: Option<String>,
+        label: Option<String>,
+        options: SyncOptions,
+    ) -> Response {
         if let Some(rig) = &mut self.rig {
+            if let Some(label) = label {
+                if remote.is_some() {
+                    return Response::Error { message: "Specify either a remote or a label, not both".into() };
+                }
+                return match rig.pull_label(&label, &folder, &options) {
+                    Ok(msg) => Response::Ok { output: msg },
+                    Err(e) => Response::Error { message: e },
+                };
+            }
             let name = match remote {
                 Some(n) => n,
                 None => match rig.registry.default_name() {
@@ -1515,7 +2700,7 @@ impl Sys {
                     None => return Response::Error { message: "No remote specified and no default set".into() },
                 },
             };
-            match rig.pull(&name, &folder) {
+            match rig.pull(&name, &folder, &options) {
                 Ok(msg) => Response::Ok { output: msg },
                 Err(e) => Response::Error { message: e },
             }
@@ -1551,8 +2736,20 @@ impl Sys {
                     None => return Response::Error { message: "No remote specified and no default set".into() },
                 },
             };
+            let gpus_before = rig.registry.get(&name).and_then(|r| r.gpu_count);
             match rig.health_check(&name) {
-                Ok(msg) => Response::Ok { output: msg },
+                Ok(msg) => {
+                    let gpus_after = rig.registry.get(&name).and_then(|r| r.gpu_count);
+                    if gpus_after != gpus_before {
+                        let rig_path = self.data.config_dir().join(RIG_FILE);
+                        if let Err(e) = rig.registry.save(&rig_path) {
+                            return Response::Error {
+                                message: format!("Failed to persist rig registry: {}", e),
+                            };
+                        }
+                    }
+                    Response::Ok { output: msg }
+                }
                 Err(e) => Response::Error { message: e },
             }
         } else {
@@ -1597,10 +2794,16 @@ impl Sys {
     }
 
     fn cmd_rig_default(&mut self, name: Option<String>) -> Response {
+        let rig_path = self.data.config_dir().join(RIG_FILE);
         if let Some(rig) = &mut self.rig {
             match name {
                 Some(n) => match rig.registry.set_default(&n) {
-                    Ok(()) => Response::Ok { output: format!("Default remote set to '{}'", n) },
+                    Ok(()) => match rig.registry.save(&rig_path) {
+                        Ok(()) => Response::Ok { output: format!("Default remote set to '{}'", n) },
+                        Err(e) => Response::Error {
+                            message: format!("Failed to persist rig registry: {}", e),
+                        },
+                    },
                     Err(e) => Response::Error { message: e },
                 },
                 None => match rig.registry.default_name() {
@@ -1644,9 +2847,11 @@ impl Sys {
             lines.push(format!("{:<6} {:<28} {:>12} {:>10}", "Index", "Filename", "Timestamp", "Size"));
             lines.push("-".repeat(60));
             for (i, e) in entries.iter().enumerate() {
+                let pin = if e.pinned { "  pinned" } else { "" };
+                let label = e.label.as_ref().map(|l| format!("  [{}]", l)).unwrap_or_default();
                 lines.push(format!(
-                    "{:<6} {:<28} {:>12} {:>8}B",
-                    i, e.filename, e.timestamp_ms, e.size_bytes
+                    "{:<6} {:<28} {:>12} {:>8}B{}{}",
+                    i, e.filename, e.timestamp_ms, e.size_bytes, pin, label
                 ));
             }
             Response::Ok { output: lines.join("\n") }
@@ -1672,7 +2877,7 @@ impl Sys {
         }
     }
 
-    fn cmd_history_diff(&self, from: String, to: Option<String>) -> Response {
+    fn cmd_history_diff(&self, from: String, to: Option<String>, unified: bool) -> Response {
         let mgr = match HistoryManager::with_defaults(self.data.config_dir().to_path_buf()) {
             Ok(m) => m,
             Err(e) => return Response::Error { message: format!("Failed to init history: {}", e) },
@@ -1707,32 +2912,20 @@ impl Sys {
             }
         };
 
+        if unified {
+            return match mgr.unified_diff(&from_entry, &to_entry, DEFAULT_CONTEXT_LINES) {
+                Ok(text) => Response::Ok { output: text },
+                Err(e) => Response::Error { message: format!("Failed to compute diff: {}", e) },
+            };
+        }
+
         match mgr.diff(&from_entry, &to_entry) {
-            Ok(diff) => {
-                let mut lines = Vec::new();
-                lines.push(format!("From: {} -> To: {}", diff.from.filename, diff.to.filename));
-                lines.push(format!("Summary: {}", diff.summary));
-                if !diff.added_lines.is_empty() {
-                    lines.push(String::new());
-                    lines.push("Added:".into());
-                    for line in &diff.added_lines {
-                        lines.push(format!("+ {}", line));
-                    }
-                }
-                if !diff.removed_lines.is_empty() {
-                    lines.push(String::new());
-                    lines.push("Removed:".into());
-                    for line in &diff.removed_lines {
-                        lines.push(format!("- {}", line));
-                    }
-                }
-                Response::Ok { output: lines.join("\n") }
-            }
+            Ok(diff) => Response::Ok { output: format_history_diff(&diff) },
             Err(e) => Response::Error { message: format!("Failed to compute diff: {}", e) },
         }
     }
 
-    fn cmd_history_restore(&mut self, id: String) -> Response {
+    fn cmd_history_restore(&mut self, id: String, dry_run: bool) -> Response {
         let mgr = match HistoryManager::with_defaults(self.data.config_dir().to_path_buf()) {
             Ok(m) => m,
             Err(e) => return Response::Error { message: format!("Failed to init history: {}", e) },
@@ -1745,6 +2938,14 @@ impl Sys {
             Ok(e) => e,
             Err(msg) => return Response::Error { message: msg },
         };
+        if dry_run {
+            return match mgr.restore_preview(&entry) {
+                Ok(diff) => Response::Ok {
+                    output: format!("Dry run: no changes made.\n{}", format_history_diff(&diff)),
+                },
+                Err(e) => Response::Error { message: format!("Restore preview failed: {}", e) },
+            };
+        }
         let now = now_ms();
         match mgr.restore(&entry, now) {
             Ok(()) => Response::Ok {
@@ -1754,15 +2955,23 @@ impl Sys {
         }
     }
 
-    fn cmd_history_snapshot(&self) -> Response {
+    fn cmd_history_snapshot(&self, label: Option<String>) -> Response {
         let mgr = match HistoryManager::with_defaults(self.data.config_dir().to_path_buf()) {
             Ok(m) => m,
             Err(e) => return Response::Error { message: format!("Failed to init history: {}", e) },
         };
         let now = now_ms();
-        match mgr.maybe_snapshot(now) {
-            Ok(Some(entry)) => Response::Ok {
-                output: format!("Snapshot created: {}", entry.filename),
+        let result = match &label {
+            Some(l) => mgr.maybe_snapshot_labeled(now, l),
+            None => mgr.maybe_snapshot(now).map(|e| e.map(|entry| (entry, true))),
+        };
+        match result {
+            Ok(Some((entry, created))) => Response::Ok {
+                output: match (&entry.label, created) {
+                    (Some(l), true) => format!("Snapshot created: {} ({})", entry.filename, l),
+                    (Some(l), false) => format!("Labeled existing snapshot {} ({})", entry.filename, l),
+                    (None, _) => format!("Snapshot created: {}", entry.filename),
+                },
             },
             Ok(None) => Response::Ok {
                 output: "No changes to snapshot (configuration unchanged)".into(),
@@ -1771,17 +2980,85 @@ impl Sys {
         }
     }
 
-    fn cmd_history_prune(&self) -> Response {
+    fn cmd_history_search(&self, query: String, ignore_case: bool) -> Response {
+        let mgr = match HistoryManager::with_defaults(self.data.config_dir().to_path_buf()) {
+            Ok(m) => m,
+            Err(e) => return Response::Error { message: format!("Failed to init history: {}", e) },
+        };
+        let found = match mgr.search(&query, ignore_case) {
+            Ok(f) => f,
+            Err(e) => return Response::Error { message: format!("History search failed: {}", e) },
+        };
+        let mut lines = Vec::new();
+        if found.hits.is_empty() {
+            lines.push(format!("No snapshots contain '{}'", query));
+        }
+        for (entry, matches) in &found.hits {
+            for (line_no, text) in matches {
+                lines.push(format!("{}:{}: {}", entry.filename, line_no, text));
+            }
+        }
+        for (entry, reason) in &found.skipped {
+            lines.push(format!("warning: skipped {}: {}", entry.filename, reason));
+        }
+        Response::Ok { output: lines.join("\n") }
+    }
+
+    fn cmd_history_set_pinned(&self, id: String, pinned: bool) -> Response {
+        let mgr = match HistoryManager::with_defaults(self.data.config_dir().to_path_buf()) {
+            Ok(m) => m,
+            Err(e) => return Response::Error { message: format!("Failed to init history: {}", e) },
+        };
+        let entries = match mgr.list() {
+            Ok(e) => e,
+            Err(e) => return Response::Error { message: format!("Failed to list history: {}", e) },
+        };
+        let entry = match resolve_history_entry(&entries, &id) {
+            Ok(e) => e,
+            Err(msg) => return Response::Error { message: msg },
+        };
+        let verb = if pinned { "Pinned" } else { "Unpinned" };
+        match mgr.set_pinned(&entry, pinned) {
+            Ok(true) => Response::Ok { output: format!("{} {}", verb, entry.filename) },
+            Ok(false) => Response::Ok {
+                output: format!("{} was already {}", entry.filename, verb.to_lowercase()),
+            },
+            Err(e) => Response::Error { message: format!("Pin update failed: {}", e) },
+        }
+    }
+
+    fn cmd_history_prune(&self, dry_run: bool) -> Response {
         let mgr = match HistoryManager::with_defaults(self.data.config_dir().to_path_buf()) {
             Ok(m) => m,
             Err(e) => return Response::Error { message: format!("Failed to init history: {}", e) },
         };
         let now = now_ms();
-        match mgr.prune(now) {
-            Ok(count) => Response::Ok {
-                output: format!("Pruned {} history entries", count),
+        if dry_run {
+            return match mgr.plan_prune(now) {
+                Ok(plan) => {
+                    let mut out = format!("Would prune {} history entries", plan.len());
+                    for entry in &plan {
+                        out.push_str(&format!("\n  {}", entry.filename));
+                    }
+                    Response::Ok { output: out }
+                }
+                Err(e) => Response::Error { message: format!("Prune failed: {}", e) },
+            };
+        }
+        let pruned = match mgr.prune(now) {
+            Ok(count) => count,
+            Err(e) => return Response::Error { message: format!("Prune failed: {}", e) },
+        };
+        match mgr.compact(now) {
+            Ok(0) => Response::Ok {
+                output: format!("Pruned {} history entries", pruned),
+            },
+            Ok(compacted) => Response::Ok {
+                output: format!("Pruned {} history entries, compressed {}", pruned, compacted),
+            },
+            Err(e) => Response::Error {
+                message: format!("Pruned {} history entries; compression failed: {}", pruned, e),
             },
-            Err(e) => Response::Error { message: format!("Prune failed: {}", e) },
         }
     }
 }
@@ -1791,6 +3068,46 @@ impl Sys {
 // Helpers
 // ---------------------------------------------------------------------------
 
+/// Resolve a report time bound: epoch ms, or a duration suffixed with
+/// ms/s/m/h meaning that long before `now`.
+fn parse_time_bound(key: &str, val: &str, now: u64) -> Result<u64, String> {
+    let val = val.trim();
+    if let Ok(ms) = val.parse::<u64>() {
+        return Ok(ms);
+    }
+    crate::data::settings::parse_duration_ms(key, val).map(|ago| now.saturating_sub(ago))
+}
+
+/// Resolve a task due date: "-" or empty clears it (`None`); otherwise
+/// epoch ms, or a duration suffixed with ms/s/m/h meaning that long after
+/// `now`.
+fn parse_due(val: &str, now: u64) -> Result<Option<u64>, String> {
+    let val = val.trim();
+    if val.is_empty() || val == "-" {
+        return Ok(None);
+    }
+    if let Ok(ms) = val.parse::<u64>() {
+        return Ok(Some(ms));
+    }
+    let ahead = crate::data::settings::parse_duration_ms("due", val)?;
+    match now.checked_add(ahead) {
+        Some(at) => Ok(Some(at)),
+        None => Err(format!("due {} is too far in the future", val)),
+    }
+}
+
+/// Parse a string into an AgentStatus.
+fn parse_agent_status(s: &str) -> Result<AgentStatus, String> {
+    match s.to_lowercase().as_str() {
+        "idle" => Ok(AgentStatus::Idle),
+        "busy" => Ok(AgentStatus::Busy),
+        "stalled" => Ok(AgentStatus::Stalled),
+        "error" => Ok(AgentStatus::Error),
+        "dead" => Ok(AgentStatus::Dead),
+        _ => Err(format!("Unknown agent status: '{}'", s)),
+    }
+}
+
 /// Parse a string into a TaskStatus.
 fn parse_task_status(s: &str) -> Result<TaskStatus, String> {
     match s.to_lowercase().as_str() {
@@ -1828,6 +3145,7 @@ fn parse_host_string(host_str: &str, name: &str) -> RemoteConfig {
         workspace_dir: "/home/ubuntu/work".to_string(),
         gpu_count: None,
         labels: Vec::new(),
+        multiplex: false,
     }
 }
 
@@ -1839,6 +3157,174 @@ fn now_ms() -> u64 {
         .as_millis() as u64
 }
 
+/// Render the time elapsed since `since_ms` compactly ("42s", "5m", "3h", "2d"),
+/// or "-" when the timestamp is unknown.
+fn format_elapsed(since_ms: Option<u64>, now_ms: u64) -> String {
+    let since = match since_ms {
+        Some(ms) => ms,
+        None => return "-".into(),
+    };
+    let secs = now_ms.saturating_sub(since) / 1000;
+    if secs < 60 {
+        format!("{}s", secs)
+    } else if secs < 3600 {
+        format!("{}m", secs / 60)
+    } else if secs < 86_400 {
+        format!("{}h", secs / 3600)
+    } else {
+        format!("{}d", secs / 86_400)
+    }
+}
+
+/// Tabular `agent list` columns in default order, with their padded widths.
+const AGENT_COLUMNS: &[(&str, usize)] = &[
+    ("name", 16),
+    ("role", 10),
+    ("status", 10),
+    ("health", 12),
+    ("age", 6),
+    ("on_task", 6),
+    ("task", 0),
+];
+
+/// Valid `agent list` sort keys.
+const AGENT_SORT_KEYS: &[&str] = &["name", "role", "status", "health", "age"];
+
+/// Sort agents in place by `key`. The sort is stable, so ties keep
+/// registry order. Health sorts worst first; age sorts oldest first.
+fn sort_agents(agents: &mut [&Agent], key: &str) -> Result<(), String> {
+    match key {
+        "name" => agents.sort_by(|a, b| a.name.cmp(&b.name)),
+        "role" => agents.sort_by(|a, b| a.role.cmp(&b.role)),
+        "status" => agents.sort_by_key(|a| format!("{:?}", a.status).to_lowercase()),
+        "health" => agents.sort_by_key(|a| health_rank(&a.health)),
+        "age" => agents.sort_by_key(|a| a.created_at_ms.unwrap_or(u64::MAX)),
+        other => {
+            return Err(format!(
+                "Unknown sort key: '{}'. Valid keys: {}",
+                other,
+                AGENT_SORT_KEYS.join(", ")
+            ))
+        }
+    }
+    Ok(())
+}
+
+/// Severity rank for sorting; lower ranks are less healthy.
+fn health_rank(health: &HealthState) -> u8 {
+    match health {
+        HealthState::Unhealthy => 0,
+        HealthState::Degraded => 1,
+        HealthState::Unknown => 2,
+        HealthState::Healthy => 3,
+    }
+}
+
+/// Parse a comma-separated column list, rejecting unknown names.
+fn parse_agent_columns(spec: &str) -> Result<Vec<&'static str>, String> {
+    let mut columns = Vec::new();
+    for raw in spec.split(',').map(str::trim).filter(|c| !c.is_empty()) {
+        let name = raw.replace('-', "_");
+        match AGENT_COLUMNS.iter().find(|(c, _)| *c == name) {
+            Some((c, _)) => columns.push(*c),
+            None => {
+                let valid: Vec<&str> = AGENT_COLUMNS.iter().map(|(c, _)| *c).collect();
+                return Err(format!(
+                    "Unknown column: '{}'. Valid columns: {}",
+                    raw,
+                    valid.join(", ")
+                ));
+            }
+        }
+    }
+    if columns.is_empty() {
+        return Err("No columns selected".into());
+    }
+    Ok(columns)
+}
+
+fn agent_column_width(column: &str) -> usize {
+    AGENT_COLUMNS
+        .iter()
+        .find(|(c, _)| *c == column)
+        .map(|(_, w)| *w)
+        .unwrap_or(0)
+}
+
+fn agent_column_value(agent: &Agent, column: &str, now: u64) -> String {
+    match column {
+        "name" => agent.name.clone(),
+        "role" => agent.role.clone(),
+        "status" => format!("{:?}", agent.status).to_lowercase(),
+        "health" => format!("{:?}", agent.health).to_lowercase(),
+        "age" => format_elapsed(agent.created_at_ms, now),
+        "on_task" => format_elapsed(agent.assigned_at_ms, now),
+        _ => agent.task.as_deref().unwrap_or("-").to_string(),
+    }
+}
+
+/// The diagnosis signal behind an unhealthy or degraded assessment, if any.
+fn problem_signal(assessment: &crate::types::health::HealthAssessment) -> Option<SignalType> {
+    use crate::types::health::HealthSignal;
+
+    if !matches!(assessment.overall, HealthState::Unhealthy | HealthState::Degraded) {
+        return None;
+    }
+    assessment.signals.iter().find_map(|s| match s {
+        HealthSignal::HeartbeatStale { .. } => Some(SignalType::HeartbeatStale),
+        HealthSignal::ErrorPatternDetected { .. } => Some(SignalType::ErrorPattern),
+        HealthSignal::ExplicitError { .. } => Some(SignalType::ExplicitError),
+        HealthSignal::SshDisconnected => Some(SignalType::SshDisconnected),
+        HealthSignal::TriggerFired { name } => Some(SignalType::TriggerFired(name.clone())),
+        _ => None,
+    })
+}
+
+/// Interventions expressed by a batch of actions: a kill followed by a
+/// create for the same agent is a restart, and a Ctrl-C is a retry.
+fn interventions_in(actions: &[Action]) -> Vec<(String, InterventionAction)> {
+    let mut found = Vec::new();
+    for (i, action) in actions.iter().enumerate() {
+        match action {
+            Action::CreateAgent { name, .. } => {
+                let killed = actions[..i]
+                    .iter()
+                    .any(|a| matches!(a, Action::KillAgent { name: n, .. } if n == name));
+                if killed {
+                    found.push((name.clone(), InterventionAction::Restart));
+                }
+            }
+            Action::SendKeys { target, keys } if keys == "C-c" => {
+                found.push((target.clone(), InterventionAction::Retry));
+            }
+            _ => {}
+        }
+    }
+    found
+}
+
+/// Render a history diff as the lines shown by `history diff`.
+fn format_history_diff(diff: &crate::history::HistoryDiff) -> String {
+    let mut lines = Vec::new();
+    lines.push(format!("From: {} -> To: {}", diff.from.filename, diff.to.filename));
+    lines.push(format!("Summary: {}", diff.summary));
+    if !diff.added_lines.is_empty() {
+        lines.push(String::new());
+        lines.push("Added:".into());
+        for line in &diff.added_lines {
+            lines.push(format!("+ {}", line));
+        }
+    }
+    if !diff.removed_lines.is_empty() {
+        lines.push(String::new());
+        lines.push("Removed:".into());
+        for line in &diff.removed_lines {
+            lines.push(format!("- {}", line));
+        }
+    }
+    lines.join("\n")
+}
+
 /// Resolve a history ID (index or filename) to a HistoryEntry.
 fn resolve_history_entry(entries: &[HistoryEntry], id: &str) -> Result<HistoryEntry, String> {
     if let Ok(idx) = id.parse::<usize>() {
@@ -1846,7 +3332,10 @@ fn resolve_history_entry(entries: &[HistoryEntry], id: &str) -> Result<HistoryEn
             format!("History index {} out of range (have {} entries)", idx, entries.len())
         })
     } else {
-        entries.iter().find(|e| e.filename == id).cloned()
+        // Entries are newest first, so a reused label finds its latest use.
+        entries.iter().find(|e| e.filename == id)
+            .or_else(|| entries.iter().find(|e| e.label.as_deref() == Some(id)))
+            .cloned()
             .ok_or_else(|| format!("History entry '{}' not found", id))
     }
 }
@@ -1859,47 +3348,89 @@ fn resolve_history_entry(entries: &[HistoryEntry], id: &str) -> Result<HistoryEn
 fn format_reliability_table(entries: &[&crate::diagnosis::SignalReliability]) -> String {
     let mut lines = Vec::new();
     lines.push(format!(
-        "{:<24} {:>6} {:>6} {:>6} {:>8} {:>8} {:>14}",
-        "Signal", "Fires", "TP", "FP", "Unknown", "Score", "Avg Resolution"
+        "{:<24} {:>6} {:>6} {:>6} {:>8} {:>8} {:>14} {:>14}",
+        "Signal", "Fires", "TP", "FP", "Unknown", "Score", "95% CI", "Avg Resolution"
     ));
-    lines.push("-".repeat(80));
+    lines.push("-".repeat(95));
     for r in entries {
         lines.push(format!(
-            "{:<24} {:>6} {:>6} {:>6} {:>8} {:>8.2} {:>12}ms",
+            "{:<24} {:>6} {:>6} {:>6} {:>8} {:>8.2} {:>14} {:>12}ms",
             r.signal.to_string(),
             r.total_fires,
             r.true_positives,
             r.false_positives,
             r.unknown,
             r.reliability_score,
+            format!("[{:.2}, {:.2}]", r.confidence_low, r.confidence_high),
             r.avg_resolution_ms
         ));
     }
     lines.join("\n")
 }
 
-fn format_effectiveness_table(entries: &[crate::diagnosis::ActionEffectiveness]) -> String {
+/// Pairs with fewer than `min_attempts` attempts get a "low confidence"
+/// note, mirroring the gate `best_action` applies before trusting a rate.
+fn format_effectiveness_table(
+    entries: &[crate::diagnosis::ActionEffectiveness],
+    min_attempts: u64,
+) -> String {
     let mut lines = Vec::new();
     lines.push(format!(
-        "{:<24} {:<10} {:>10} {:>10} {:>10} {:>8}",
-        "Signal", "Action", "Attempts", "Successes", "Failures", "Rate"
+        "{:<24} {:<10} {:>10} {:>10} {:>10} {:>8}  {}",
+        "Signal", "Action", "Attempts", "Successes", "Failures", "Rate", "Note"
     ));
-    lines.push("-".repeat(78));
+    lines.push("-".repeat(94));
     for e in entries {
+        let note = if e.attempts < min_attempts { "low confidence" } else { "" };
+        lines.push(
+            format!(
+                "{:<24} {:<10} {:>10} {:>10} {:>10} {:>7.1}%  {}",
+                e.signal.to_string(),
+                e.action.to_string(),
+                e.attempts,
+                e.successes,
+                e.failures,
+                e.success_rate * 100.0,
+                note
+            )
+            .trim_end()
+            .to_string(),
+        );
+    }
+    lines.join("\n")
+}
+
+fn format_compare_table(
+    a: &str,
+    b: &str,
+    rows: &[(
+        SignalType,
+        crate::diagnosis::SignalReliability,
+        crate::diagnosis::SignalReliability,
+    )],
+) -> String {
+    let mut lines = Vec::new();
+    lines.push(format!(
+        "{:<24} {:>18} {:>18}",
+        "Signal",
+        format!("{} (fires/score)", a),
+        format!("{} (fires/score)", b)
+    ));
+    lines.push("-".repeat(62));
+    for (signal, ra, rb) in rows {
         lines.push(format!(
-            "{:<24} {:<10} {:>10} {:>10} {:>10} {:>7.1}%",
-            e.signal.to_string(),
-            e.action.to_string(),
-            e.attempts,
-            e.successes,
-            e.failures,
-            e.success_rate * 100.0
+            "{:<24} {:>18} {:>18}",
+            signal.to_string(),
+            format!("{} / {:.2}", ra.total_fires, ra.reliability_score),
+            format!("{} / {:.2}", rb.total_fires, rb.reliability_score)
         ));
     }
     lines.join("\n")
 }
 
-/// Parse a signal type string into a `SignalType` enum variant.
+/// Parse a signal type string into a `SignalType` enum variant. Accepts
+/// the `Display` form, so `trigger_fired(<name>)` round-trips with the
+/// trigger name's case preserved.
 fn parse_signal_type(s: &str) -> Result<SignalType, String> {
     match s.to_lowercase().as_str() {
         "heartbeat_stale" => Ok(SignalType::HeartbeatStale),
@@ -1908,10 +3439,10 @@ fn parse_signal_type(s: &str) -> Result<SignalType, String> {
         "ssh_disconnected" => Ok(SignalType::SshDisconnected),
         "explicit_error" => Ok(SignalType::ExplicitError),
         "manual_escalation" => Ok(SignalType::ManualEscalation),
-        other => {
-            if other.starts_with("trigger_fired") {
-                let name = other
-                    .strip_prefix("trigger_fired")
+        lower => {
+            if lower.starts_with("trigger_fired") {
+                let name = s
+                    .get("trigger_fired".len()..)
                     .unwrap_or("")
                     .trim_start_matches(|c: char| c == '(' || c == ' ')
                     .trim_end_matches(|c: char| c == ')' || c == ' ');
@@ -1981,6 +3512,81 @@ mod tests {
         assert!(output(&r).contains("agents: 1"));
     }
 
+    #[test]
+    fn config_add_marks_settings_dirty_until_saved() {
+        let dir = std::env::temp_dir().join(format!("cmx_dirty_settings_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut sys = Sys::from_data(Data::new(&dir).unwrap());
+        assert!(!sys.settings_dirty());
+
+        sys.execute(Command::ConfigAdd {
+            key: "max_retries".into(),
+            value: "9".into(),
+        });
+        assert!(sys.settings_dirty());
+        let r = sys.execute(Command::Status { format: None });
+        assert!(output(&r).contains("settings modified, unsaved"));
+        let r = sys.execute(Command::Status { format: Some("json".into()) });
+        let v: serde_json::Value = serde_json::from_str(output(&r)).unwrap();
+        assert_eq!(v["settings_dirty"], true);
+
+        let r = sys.execute(Command::ConfigSave { path: None });
+        assert!(is_ok(&r));
+        assert!(!sys.settings_dirty());
+        let r = sys.execute(Command::Status { format: None });
+        assert!(!output(&r).contains("unsaved"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn config_save_elsewhere_keeps_settings_dirty() {
+        let dir = std::env::temp_dir().join(format!("cmx_dirty_save_as_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut sys = Sys::from_data(Data::new(&dir).unwrap());
+        sys.execute(Command::ConfigAdd {
+            key: "max_retries".into(),
+            value: "9".into(),
+        });
+
+        let copy = dir.join("copy.yaml");
+        let r = sys.execute(Command::ConfigSave {
+            path: Some(copy.to_string_lossy().into_owned()),
+        });
+        assert!(is_ok(&r));
+        assert!(copy.exists());
+        assert!(sys.settings_dirty());
+
+        let r = sys.execute(Command::ConfigSave {
+            path: Some(dir.join("settings.yaml").to_string_lossy().into_owned()),
+        });
+        assert!(is_ok(&r));
+        assert!(!sys.settings_dirty());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn config_unset_back_to_saved_value_is_clean() {
+        let mut sys = test_sys();
+        sys.execute(Command::ConfigAdd {
+            key: "message_timeout".into(),
+            value: "5s".into(),
+        });
+        assert!(sys.settings_dirty());
+        sys.execute(Command::ConfigUnset { key: "message_timeout".into() });
+        assert!(!sys.settings_dirty());
+    }
+
+    #[test]
+    fn status_json_has_schema_version() {
+        let mut sys = test_sys();
+        let r = sys.execute(Command::Status { format: Some("json".into()) });
+        let v: serde_json::Value = serde_json::from_str(output(&r)).unwrap();
+        assert_eq!(v["schema_version"], crate::types::SCHEMA_VERSION);
+        assert_eq!(v["agent_count"], 0);
+    }
+
     // --- agent lifecycle ---
 
     #[test]
@@ -2072,7 +3678,7 @@ mod tests {
             path: None,
             agent_type: None,
         });
-        let r = sys.execute(Command::AgentKill { name: "w1".into() });
+        let r = sys.execute(Command::AgentKill { name: "w1".into(), graceful: false });
         assert!(is_ok(&r));
         assert!(sys.data.agents().list().is_empty());
     }
@@ -2080,8 +3686,194 @@ mod tests {
     #[test]
     fn agent_kill_nonexistent() {
         let mut sys = test_sys();
-        let r = sys.execute(Command::AgentKill { name: "ghost".into() });
+        let r = sys.execute(Command::AgentKill { name: "ghost".into(), graceful: false });
+        assert!(is_err(&r));
+        let r = sys.execute(Command::AgentKill { name: "ghost".into(), graceful: true });
+        assert!(is_err(&r));
+        assert!(sys.pending_actions().is_empty());
+    }
+
+    #[test]
+    fn agent_kill_hard_emits_only_kill() {
+        let mut sys = test_sys();
+        add_agent(&mut sys, "worker", "w1");
+        let r = sys.execute(Command::AgentKill { name: "w1".into(), graceful: false });
+        assert!(is_ok(&r));
+        assert_eq!(sys.pending_actions(), &[Action::KillAgent { name: "w1".into() }]);
+    }
+
+    #[test]
+    fn agent_kill_graceful_sends_shutdown_keys_first() {
+        let mut sys = test_sys();
+        add_agent(&mut sys, "worker", "w1");
+        sys.execute(Command::ConfigAdd {
+            key: "shutdown_keys".into(),
+            value: "/save-and-exit".into(),
+        });
+        let r = sys.execute(Command::AgentKill { name: "w1".into(), graceful: true });
+        assert!(is_ok(&r));
+        assert_eq!(
+            sys.pending_actions(),
+            &[Action::SendKeys { target: "w1".into(), keys: "/save-and-exit".into() }]
+        );
+        assert!(sys.data.agents().get("w1").is_some());
+    }
+
+    #[test]
+    fn agent_kill_graceful_waits_for_grace_period() {
+        let mut sys = test_sys();
+        add_agent(&mut sys, "worker", "w1");
+        sys.settings.shutdown_grace_ms = 60_000;
+        let start = now_ms();
+        sys.execute(Command::AgentKill { name: "w1".into(), graceful: true });
+        sys.drain_actions();
+
+        // Still inside the grace period: nothing is killed.
+        assert!(sys.finish_pending_kills(start).is_empty());
+        assert!(sys.pending_actions().is_empty());
+        assert!(sys.data.agents().get("w1").is_some());
+
+        // Once it has passed, the agent is killed and removed.
+        let killed = sys.finish_pending_kills(start + 60_000 + 1000);
+        assert_eq!(killed, vec!["w1".to_string()]);
+        assert_eq!(sys.pending_actions(), &[Action::KillAgent { name: "w1".into() }]);
+        assert!(sys.data.agents().get("w1").is_none());
+        assert!(sys.finish_pending_kills(u64::MAX).is_empty());
+    }
+
+    #[test]
+    fn agent_kill_hard_during_grace_period_cancels_pending_kill() {
+        let mut sys = test_sys();
+        add_agent(&mut sys, "worker", "w1");
+        sys.execute(Command::AgentKill { name: "w1".into(), graceful: true });
+        let r = sys.execute(Command::AgentKill { name: "w1".into(), graceful: false });
+        assert!(is_ok(&r));
+        sys.drain_actions();
+        assert!(sys.finish_pending_kills(u64::MAX).is_empty());
+        assert!(sys.pending_actions().is_empty());
+    }
+
+    #[test]
+    fn agent_kill_graceful_with_blank_keys_just_kills() {
+        let mut sys = test_sys();
+        add_agent(&mut sys, "worker", "w1");
+        sys.settings.shutdown_keys = String::new();
+        sys.execute(Command::AgentKill { name: "w1".into(), graceful: true });
+        assert_eq!(sys.pending_actions(), &[Action::KillAgent { name: "w1".into() }]);
+    }
+
+    fn add_agent(sys: &mut Sys, role: &str, name: &str) {
+        sys.execute(Command::AgentNew {
+            role: role.into(),
+            name: Some(name.into()),
+            path: None,
+            agent_type: None,
+        });
+    }
+
+    fn kill_actions(sys: &Sys) -> Vec<String> {
+        sys.pending_actions()
+            .iter()
+            .filter_map(|a| match a {
+                Action::KillAgent { name } => Some(name.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn agent_kill_where_by_role() {
+        let mut sys = test_sys();
+        add_agent(&mut sys, "worker", "w1");
+        add_agent(&mut sys, "pilot", "p1");
+        add_agent(&mut sys, "worker", "w2");
+        let r = sys.execute(Command::AgentKillWhere {
+            role: Some("Worker".into()),
+            status: None,
+            label: None,
+        });
+        assert!(is_ok(&r));
+        assert_eq!(output(&r), "Killed 2 agents: w1, w2");
+        assert_eq!(kill_actions(&sys), vec!["w1", "w2"]);
+        let left: Vec<&str> = sys.data.agents().list().iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(left, vec!["p1"]);
+    }
+
+    #[test]
+    fn agent_kill_where_by_status_and_label() {
+        let mut sys = test_sys();
+        add_agent(&mut sys, "worker", "w1");
+        add_agent(&mut sys, "worker", "w2");
+        add_agent(&mut sys, "worker", "w3");
+        for name in ["w1", "w3"] {
+            sys.data.agents_mut().get_mut(name).unwrap().status = AgentStatus::Dead;
+        }
+        let r = sys.execute(Command::AgentKillWhere {
+            role: None,
+            status: Some("dead".into()),
+            label: None,
+        });
+        assert_eq!(output(&r), "Killed 2 agents: w1, w3");
+        assert_eq!(kill_actions(&sys), vec!["w1", "w3"]);
+
+        add_agent(&mut sys, "worker", "w4");
+        sys.execute(Command::AgentStateSet {
+            agent: "w4".into(),
+            key: "labels".into(),
+            value: r#"["ci","gpu"]"#.into(),
+        });
+        let r = sys.execute(Command::AgentKillWhere {
+            role: None,
+            status: None,
+            label: Some("ci".into()),
+        });
+        assert_eq!(output(&r), "Killed 1 agent: w4");
+        assert!(sys.data.agents().get("w2").is_some());
+    }
+
+    #[test]
+    fn agent_kill_where_cancels_pending_graceful_kills() {
+        let mut sys = test_sys();
+        add_agent(&mut sys, "worker", "w1");
+        sys.execute(Command::AgentKill { name: "w1".into(), graceful: true });
+        assert!(sys.pending_kills.contains_key("w1"));
+        let r = sys.execute(Command::AgentKillWhere {
+            role: Some("worker".into()),
+            status: None,
+            label: None,
+        });
+        assert_eq!(output(&r), "Killed 1 agent: w1");
+        assert!(sys.pending_kills.is_empty());
+    }
+
+    #[test]
+    fn agent_kill_where_no_match_is_zero() {
+        let mut sys = test_sys();
+        add_agent(&mut sys, "worker", "w1");
+        let r = sys.execute(Command::AgentKillWhere {
+            role: None,
+            status: Some("stalled".into()),
+            label: None,
+        });
+        assert!(is_ok(&r));
+        assert_eq!(output(&r), "Killed 0 agents");
+        assert!(kill_actions(&sys).is_empty());
+        assert_eq!(sys.data.agents().list().len(), 1);
+    }
+
+    #[test]
+    fn agent_kill_where_requires_predicate() {
+        let mut sys = test_sys();
+        add_agent(&mut sys, "worker", "w1");
+        let r = sys.execute(Command::AgentKillWhere { role: None, status: None, label: None });
+        assert!(is_err(&r));
+        let r = sys.execute(Command::AgentKillWhere {
+            role: None,
+            status: Some("asleep".into()),
+            label: None,
+        });
         assert!(is_err(&r));
+        assert_eq!(sys.data.agents().list().len(), 1);
     }
 
     #[test]
@@ -2145,7 +3937,7 @@ mod tests {
     #[test]
     fn agent_list_empty() {
         let mut sys = test_sys();
-        let r = sys.execute(Command::AgentList { format: None });
+        let r = sys.execute(Command::AgentList { format: None, sort_by: None, columns: None });
         assert!(is_ok(&r));
         assert!(output(&r).contains("No agents"));
     }
@@ -2161,45 +3953,417 @@ mod tests {
         });
         let r = sys.execute(Command::AgentList {
             format: Some("json".into()),
+            sort_by: None,
+            columns: None,
         });
         assert!(is_ok(&r));
         assert!(output(&r).contains("\"name\": \"w1\""));
+        let v: serde_json::Value = serde_json::from_str(output(&r)).unwrap();
+        assert_eq!(v["schema_version"], crate::types::SCHEMA_VERSION);
+        assert_eq!(v["agents"][0]["name"], "w1");
     }
 
-    // --- task lifecycle ---
+    fn agent_list_sys() -> Sys {
+        let mut sys = test_sys();
+        for name in ["w1", "w2", "w3"] {
+            sys.execute(Command::AgentNew {
+                role: "worker".into(),
+                name: Some(name.into()),
+                path: None,
+                agent_type: None,
+            });
+        }
+        let agents = sys.data.agents_mut();
+        agents.update_health("w1", HealthState::Healthy).unwrap();
+        agents.update_health("w2", HealthState::Degraded).unwrap();
+        agents.update_health("w3", HealthState::Unhealthy).unwrap();
+        sys
+    }
 
     #[test]
-    fn task_list_empty() {
-        let mut sys = test_sys();
-        let r = sys.execute(Command::TaskList {
+    fn agent_list_sort_by_health_surfaces_unhealthy_first() {
+        let mut sys = agent_list_sys();
+        let r = sys.execute(Command::AgentList {
             format: None,
-            project: None,
+            sort_by: Some("health".into()),
+            columns: Some("name,health".into()),
         });
         assert!(is_ok(&r));
-        assert!(output(&r).contains("No tasks"));
+        let names: Vec<&str> = output(&r)
+            .lines()
+            .map(|l| l.split_whitespace().next().unwrap())
+            .collect();
+        assert_eq!(names, vec!["w3", "w2", "w1"]);
+        assert!(output(&r).lines().next().unwrap().ends_with("unhealthy"));
     }
 
     #[test]
-    fn task_get_not_found() {
-        let mut sys = test_sys();
-        let r = sys.execute(Command::TaskGet { id: "NOPE".into() });
-        assert!(is_err(&r));
+    fn agent_list_default_keeps_registry_order() {
+        let mut sys = agent_list_sys();
+        let r = sys.execute(Command::AgentList { format: None, sort_by: None, columns: None });
+        let names: Vec<&str> = output(&r)
+            .lines()
+            .map(|l| l.split_whitespace().next().unwrap())
+            .collect();
+        assert_eq!(names, vec!["w1", "w2", "w3"]);
+        // All seven default columns, task last.
+        assert_eq!(output(&r).lines().next().unwrap().split_whitespace().count(), 7);
     }
 
     #[test]
-    fn task_check_and_uncheck() {
-        let mut sys = test_sys();
-        // Add a project which creates a root task
-        sys.execute(Command::ProjectAdd {
-            name: "PRJ".into(),
-            path: "/tmp/prj".into(),
+    fn agent_list_invalid_sort_key_lists_valid_keys() {
+        let mut sys = agent_list_sys();
+        let r = sys.execute(Command::AgentList {
+            format: None,
+            sort_by: Some("mood".into()),
+            columns: None,
         });
-        let r = sys.execute(Command::TaskCheck { id: "PRJ".into() });
-        assert!(is_ok(&r));
-        assert_eq!(
-            sys.data.tasks().get("PRJ").unwrap().status,
-            TaskStatus::Completed
-        );
+        assert!(is_err(&r));
+        assert!(output(&r).contains("mood"));
+        assert!(output(&r).contains("name, role, status, health, age"));
+    }
+
+    #[test]
+    fn agent_list_invalid_column_errors() {
+        let mut sys = agent_list_sys();
+        let r = sys.execute(Command::AgentList {
+            format: None,
+            sort_by: None,
+            columns: Some("name,shoe_size".into()),
+        });
+        assert!(is_err(&r));
+        assert!(output(&r).contains("shoe_size"));
+    }
+
+    // --- agent state ---
+
+    fn agent_state_sys(name: &str) -> Sys {
+        let dir = std::env::temp_dir().join(name);
+        let _ = std::fs::remove_dir_all(&dir);
+        let _ = std::fs::create_dir_all(&dir);
+        let mut sys = Sys::from_data(Data::new(&dir).unwrap());
+        sys.execute(Command::AgentNew {
+            role: "worker".into(),
+            name: Some("w1".into()),
+            path: None,
+            agent_type: None,
+        });
+        sys
+    }
+
+    #[test]
+    fn agent_state_set_then_get() {
+        let mut sys = agent_state_sys("cmx_sys_agent_state_set_get");
+        let r = sys.execute(Command::AgentStateSet {
+            agent: "w1".into(),
+            key: "progress".into(),
+            value: r#"{"step": 3}"#.into(),
+        });
+        assert!(is_ok(&r));
+        assert_eq!(output(&r), r#"{"step":3}"#);
+
+        let r = sys.execute(Command::AgentStateGet {
+            agent: "w1".into(),
+            key: "progress".into(),
+        });
+        assert!(is_ok(&r));
+        assert_eq!(output(&r), r#"{"step":3}"#);
+    }
+
+    #[test]
+    fn agent_state_non_json_stored_as_string() {
+        let mut sys = agent_state_sys("cmx_sys_agent_state_string");
+        sys.execute(Command::AgentStateSet {
+            agent: "w1".into(),
+            key: "note".into(),
+            value: "hello world".into(),
+        });
+        let r = sys.execute(Command::AgentStateGet {
+            agent: "w1".into(),
+            key: "note".into(),
+        });
+        assert_eq!(output(&r), "\"hello world\"");
+    }
+
+    #[test]
+    fn agent_state_get_missing_key() {
+        let mut sys = agent_state_sys("cmx_sys_agent_state_missing");
+        let r = sys.execute(Command::AgentStateGet {
+            agent: "w1".into(),
+            key: "nope".into(),
+        });
+        assert!(is_err(&r));
+        assert!(output(&r).contains("not found"));
+    }
+
+    #[test]
+    fn agent_state_unknown_agent() {
+        let mut sys = agent_state_sys("cmx_sys_agent_state_unknown");
+        let r = sys.execute(Command::AgentStateSet {
+            agent: "ghost".into(),
+            key: "k".into(),
+            value: "1".into(),
+        });
+        assert!(is_err(&r));
+    }
+
+    #[test]
+    fn agent_state_persists_across_sys() {
+        let mut sys = agent_state_sys("cmx_sys_agent_state_persist");
+        sys.execute(Command::AgentStateSet {
+            agent: "w1".into(),
+            key: "attempts".into(),
+            value: "2".into(),
+        });
+        let config_dir = sys.data().config_dir().to_path_buf();
+        let mgr = AgentStateManager::new(&config_dir).unwrap();
+        let state = mgr.read_state("worker", "w1").unwrap();
+        assert_eq!(state.get("attempts"), Some(&serde_json::json!(2)));
+    }
+
+    #[test]
+    fn agent_state_set_keeps_keys_already_on_disk() {
+        let mut sys = agent_state_sys("cmx_sys_agent_state_merge");
+        let config_dir = sys.data().config_dir().to_path_buf();
+        let mgr = AgentStateManager::new(&config_dir).unwrap();
+        let mut existing = std::collections::HashMap::new();
+        existing.insert("a".to_string(), serde_json::json!("from disk"));
+        mgr.write_state("worker", "w1", &existing).unwrap();
+
+        let r = sys.execute(Command::AgentStateSet {
+            agent: "w1".into(),
+            key: "b".into(),
+            value: "3".into(),
+        });
+        assert!(is_ok(&r));
+        let state = mgr.read_state("worker", "w1").unwrap();
+        assert_eq!(state.get("a"), Some(&serde_json::json!("from disk")));
+        assert_eq!(state.get("b"), Some(&serde_json::json!(3)));
+    }
+
+    // --- task lifecycle ---
+
+    #[test]
+    fn task_list_empty() {
+        let mut sys = test_sys();
+        let r = sys.execute(Command::TaskList {
+            format: None,
+            project: None,
+            max_depth: None,
+            status: None,
+            agent: None,
+        });
+        assert!(is_ok(&r));
+        assert!(output(&r).contains("No tasks"));
+    }
+
+    #[test]
+    fn task_list_renders_blocked_then_ready() {
+        let mut sys = test_sys();
+        let mk = |id: &str, deps: Vec<String>| TaskNode {
+            id: id.into(),
+            title: format!("Task {}", id),
+            source: TaskSource::Roadmap,
+            status: TaskStatus::Pending,
+            result: None,
+            agent: None,
+            children: Vec::new(),
+            spec_path: None,
+            depends_on: deps,
+            notes: Vec::new(),
+            due_at_ms: None,
+        };
+        sys.data.tasks_mut().add_root(mk("T1", Vec::new()));
+        sys.data.tasks_mut().add_root(mk("T2", vec!["T1".into()]));
+
+        let r = sys.execute(Command::TaskList {
+            format: None,
+            project: None,
+            max_depth: None,
+            status: None,
+            agent: None,
+        });
+        let out = output(&r);
+        let t2 = out.lines().find(|l| l.starts_with("T2")).unwrap();
+        assert!(t2.contains("blocked"));
+        assert!(t2.contains("(blocked by T1)"));
+
+        sys.execute(Command::TaskCheck { id: "T1".into(), cascade: false });
+        let r = sys.execute(Command::TaskList {
+            format: None,
+            project: None,
+            max_depth: None,
+            status: None,
+            agent: None,
+        });
+        let out = output(&r);
+        let t2 = out.lines().find(|l| l.starts_with("T2")).unwrap();
+        assert!(t2.contains("ready"));
+        assert!(!t2.contains("blocked"));
+    }
+
+    #[test]
+    fn task_list_flags_cycle() {
+        let mut sys = test_sys();
+        let mk = |id: &str| TaskNode {
+            id: id.into(),
+            title: format!("Task {}", id),
+            source: TaskSource::Roadmap,
+            status: TaskStatus::Pending,
+            result: None,
+            agent: None,
+            children: Vec::new(),
+            spec_path: None,
+            depends_on: Vec::new(),
+            notes: Vec::new(),
+            due_at_ms: None,
+        };
+        let mut child = mk("M1.1");
+        child.children.push(mk("M1"));
+        let mut root = mk("M1");
+        root.children.push(child);
+        sys.data.tasks_mut().add_root(root);
+
+        let r = sys.execute(Command::TaskList {
+            format: None,
+            project: None,
+            max_depth: None,
+            status: None,
+            agent: None,
+        });
+        assert!(is_ok(&r));
+        let out = output(&r);
+        assert_eq!(out.lines().filter(|l| l.trim_start().starts_with("M1")).count(), 2);
+        assert!(out.ends_with("warning: task cycle M1 > M1.1 > M1 (not expanded)"));
+    }
+
+    #[test]
+    fn task_list_depth_and_status_filters() {
+        let mut sys = test_sys();
+        let mk = |id: &str, status: TaskStatus| TaskNode {
+            id: id.into(),
+            title: format!("Task {}", id),
+            source: TaskSource::Roadmap,
+            status,
+            result: None,
+            agent: None,
+            children: Vec::new(),
+            spec_path: None,
+            depends_on: Vec::new(),
+            notes: Vec::new(),
+            due_at_ms: None,
+        };
+        let mut root = mk("M1", TaskStatus::InProgress);
+        root.children.push(mk("M1.1", TaskStatus::Failed));
+        root.children.push(mk("M1.2", TaskStatus::Completed));
+        sys.data.tasks_mut().add_root(root);
+
+        let r = sys.execute(Command::TaskList {
+            format: None,
+            project: None,
+            max_depth: Some(0),
+            status: None,
+            agent: None,
+        });
+        assert_eq!(output(&r).lines().count(), 1);
+
+        let r = sys.execute(Command::TaskList {
+            format: None,
+            project: None,
+            max_depth: None,
+            status: Some("failed".into()),
+            agent: None,
+        });
+        let out = output(&r);
+        assert_eq!(out.lines().count(), 2);
+        assert!(out.lines().next().unwrap().contains("(context)"));
+        assert!(!out.contains("M1.2"));
+
+        let r = sys.execute(Command::TaskList {
+            format: None,
+            project: None,
+            max_depth: None,
+            status: Some("bogus".into()),
+            agent: None,
+        });
+        assert!(is_err(&r));
+    }
+
+    #[test]
+    fn task_list_status_and_agent_filters() {
+        let mut sys = test_sys();
+        let mk = |id: &str, status: TaskStatus, agent: Option<&str>| TaskNode {
+            id: id.into(),
+            title: format!("Task {}", id),
+            source: TaskSource::Roadmap,
+            status,
+            result: None,
+            agent: agent.map(String::from),
+            children: Vec::new(),
+            spec_path: None,
+            depends_on: Vec::new(),
+            notes: Vec::new(),
+            due_at_ms: None,
+        };
+        let mut root = mk("M1", TaskStatus::InProgress, Some("w2"));
+        root.children.push(mk("M1.1", TaskStatus::InProgress, Some("w1")));
+        root.children.push(mk("M1.2", TaskStatus::Completed, Some("w1")));
+        root.children.push(mk("M1.3", TaskStatus::InProgress, None));
+        sys.data.tasks_mut().add_root(root);
+        sys.data.tasks_mut().add_root(mk("X1", TaskStatus::InProgress, Some("w1")));
+
+        let mut list = |project: Option<&str>, status: Option<&str>, agent: Option<&str>| {
+            let r = sys.execute(Command::TaskList {
+                format: None,
+                project: project.map(String::from),
+                max_depth: None,
+                status: status.map(String::from),
+                agent: agent.map(String::from),
+            });
+            assert!(is_ok(&r), "{}", output(&r));
+            output(&r)
+                .lines()
+                .map(|l| l.split_whitespace().next().unwrap().to_string())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(list(None, None, Some("w1")), vec!["M1.1", "M1.2", "X1"]);
+        assert_eq!(list(None, Some("in_progress"), None), vec!["M1", "M1.1", "M1.3", "X1"]);
+        assert_eq!(list(None, Some("in_progress"), Some("w1")), vec!["M1.1", "X1"]);
+        assert_eq!(list(Some("M1"), Some("in_progress"), Some("w1")), vec!["M1.1"]);
+        assert_eq!(list(None, Some("completed"), Some("w2")), vec!["No"]);
+
+        let r = sys.execute(Command::TaskList {
+            format: None,
+            project: None,
+            max_depth: None,
+            status: Some("bogus".into()),
+            agent: Some("w1".into()),
+        });
+        assert!(is_err(&r));
+    }
+
+    #[test]
+    fn task_get_not_found() {
+        let mut sys = test_sys();
+        let r = sys.execute(Command::TaskGet { id: "NOPE".into() });
+        assert!(is_err(&r));
+    }
+
+    #[test]
+    fn task_check_and_uncheck() {
+        let mut sys = test_sys();
+        // Add a project which creates a root task
+        sys.execute(Command::ProjectAdd {
+            name: "PRJ".into(),
+            path: "/tmp/prj".into(),
+        });
+        let r = sys.execute(Command::TaskCheck { id: "PRJ".into(), cascade: false });
+        assert!(is_ok(&r));
+        assert_eq!(
+            sys.data.tasks().get("PRJ").unwrap().status,
+            TaskStatus::Completed
+        );
 
         let r = sys.execute(Command::TaskUncheck { id: "PRJ".into() });
         assert!(is_ok(&r));
@@ -2209,6 +4373,78 @@ mod tests {
         );
     }
 
+    #[test]
+    fn task_note_appends_and_shows_in_get() {
+        let mut sys = test_sys();
+        sys.execute(Command::ProjectAdd {
+            name: "PRJ".into(),
+            path: "/tmp/prj".into(),
+        });
+        let r = sys.execute(Command::TaskNote {
+            id: "PRJ".into(),
+            text: "blocked on credentials".into(),
+        });
+        assert!(is_ok(&r));
+        let r = sys.execute(Command::TaskNote {
+            id: "PRJ".into(),
+            text: "credentials arrived".into(),
+        });
+        assert!(output(&r).contains("2 total"));
+
+        let task = sys.data.tasks().get("PRJ").unwrap();
+        assert_eq!(task.notes.len(), 2);
+        assert_eq!(task.notes[0].1, "blocked on credentials");
+        assert_eq!(task.notes[1].1, "credentials arrived");
+        assert!(task.notes[0].0 <= task.notes[1].0);
+        assert_eq!(task.status, TaskStatus::Pending);
+        assert!(task.result.is_none());
+
+        let r = sys.execute(Command::TaskGet { id: "PRJ".into() });
+        assert!(output(&r).contains("credentials arrived"));
+        assert_eq!(sys.build_snapshot().find_task("PRJ").unwrap().notes.len(), 2);
+    }
+
+    #[test]
+    fn task_move_reparents_project_task() {
+        let mut sys = test_sys();
+        for name in ["PRJ", "OTHER"] {
+            sys.execute(Command::ProjectAdd {
+                name: name.into(),
+                path: format!("/tmp/{}", name.to_lowercase()),
+            });
+        }
+        let r = sys.execute(Command::TaskMove {
+            id: "OTHER".into(),
+            parent: "PRJ".into(),
+        });
+        assert!(is_ok(&r), "{}", output(&r));
+        let prj = sys.data.tasks().get("PRJ").unwrap();
+        assert!(prj.children.iter().any(|c| c.id == "OTHER"));
+        assert!(sys.data.tasks().roots().iter().all(|t| t.id != "OTHER"));
+
+        let r = sys.execute(Command::TaskMove {
+            id: "PRJ".into(),
+            parent: "OTHER".into(),
+        });
+        assert!(is_err(&r));
+        let r = sys.execute(Command::TaskMove {
+            id: "PRJ".into(),
+            parent: "NOPE".into(),
+        });
+        assert!(output(&r).contains("not found"));
+    }
+
+    #[test]
+    fn task_note_missing_task_errors() {
+        let mut sys = test_sys();
+        let r = sys.execute(Command::TaskNote {
+            id: "NOPE".into(),
+            text: "hello".into(),
+        });
+        assert!(is_err(&r));
+        assert!(output(&r).contains("not found"));
+    }
+
     #[test]
     fn task_set_updates_fields() {
         let mut sys = test_sys();
@@ -2222,6 +4458,8 @@ mod tests {
             title: Some("New Title".into()),
             result: None,
             agent: None,
+            due: None,
+            cascade: false,
         });
         assert!(is_ok(&r));
         let t = sys.data.tasks().get("T1").unwrap();
@@ -2229,6 +4467,55 @@ mod tests {
         assert_eq!(t.title, "New Title");
     }
 
+    #[test]
+    fn parse_due_rejects_overflow() {
+        assert_eq!(parse_due("2h", 1_000).unwrap(), Some(1_000 + 7_200_000));
+        assert_eq!(parse_due("-", 1_000).unwrap(), None);
+        let max_ms = format!("{}ms", u64::MAX);
+        assert!(parse_due(&max_ms, 1).unwrap_err().contains("too far"));
+    }
+
+    #[test]
+    fn task_set_due_marks_overdue_in_list() {
+        let mut sys = test_sys();
+        sys.execute(Command::ProjectAdd {
+            name: "T1".into(),
+            path: "/tmp".into(),
+        });
+        let set_due = |sys: &mut Sys, due: &str| {
+            sys.execute(Command::TaskSet {
+                id: "T1".into(),
+                status: None,
+                title: None,
+                result: None,
+                agent: None,
+                due: Some(due.into()),
+                cascade: false,
+            })
+        };
+        let list = |sys: &mut Sys| {
+            output(&sys.execute(Command::TaskList {
+                format: None,
+                project: None,
+                max_depth: None,
+                status: None,
+                agent: None,
+            }))
+            .to_string()
+        };
+
+        assert!(is_ok(&set_due(&mut sys, "1000")));
+        assert_eq!(sys.data.tasks().get("T1").unwrap().due_at_ms, Some(1000));
+        assert!(list(&mut sys).contains("(overdue)"));
+
+        assert!(is_ok(&set_due(&mut sys, "2h")));
+        assert!(!list(&mut sys).contains("(overdue)"));
+
+        assert!(is_ok(&set_due(&mut sys, "-")));
+        assert!(sys.data.tasks().get("T1").unwrap().due_at_ms.is_none());
+        assert!(is_err(&set_due(&mut sys, "soon")));
+    }
+
     #[test]
     fn task_set_invalid_status() {
         let mut sys = test_sys();
@@ -2242,10 +4529,139 @@ mod tests {
             title: None,
             result: None,
             agent: None,
+            due: None,
+            cascade: false,
         });
         assert!(is_err(&r));
     }
 
+    // --- assignment reconciliation ---
+
+    fn sys_with_task(name: &str, task_id: &str) -> Sys {
+        let mut sys = test_sys();
+        sys.data.tasks_mut().add_root(TaskNode {
+            id: task_id.into(),
+            title: "Task".into(),
+            source: TaskSource::Roadmap,
+            status: TaskStatus::Pending,
+            result: None,
+            agent: None,
+            children: Vec::new(),
+            spec_path: None,
+            depends_on: Vec::new(),
+            notes: Vec::new(),
+            due_at_ms: None,
+        });
+        sys.execute(Command::AgentNew {
+            role: "worker".into(),
+            name: Some(name.into()),
+            path: None,
+            agent_type: None,
+        });
+        sys
+    }
+
+    #[test]
+    fn kill_assigned_agent_unassigns_task() {
+        let mut sys = sys_with_task("w1", "T1");
+        sys.execute(Command::AgentAssign { name: "w1".into(), task: "T1".into() });
+        assert_eq!(sys.data.tasks().get("T1").unwrap().agent.as_deref(), Some("w1"));
+
+        let r = sys.execute(Command::AgentKill { name: "w1".into(), graceful: false });
+        assert!(is_ok(&r));
+        assert!(output(&r).contains("unassigned tasks: T1"));
+        assert_eq!(sys.data.tasks().get("T1").unwrap().agent, None);
+    }
+
+    #[test]
+    fn reconcile_clears_only_orphaned_backrefs() {
+        let mut sys = sys_with_task("w1", "T1");
+        sys.data.tasks_mut().add_root(TaskNode {
+            id: "T2".into(),
+            title: "Other".into(),
+            source: TaskSource::Roadmap,
+            status: TaskStatus::Pending,
+            result: None,
+            agent: None,
+            children: Vec::new(),
+            spec_path: None,
+            depends_on: Vec::new(),
+            notes: Vec::new(),
+            due_at_ms: None,
+        });
+        sys.data.tasks_mut().assign("T1", "w1").unwrap();
+        sys.data.tasks_mut().assign("T2", "ghost").unwrap();
+
+        assert_eq!(sys.reconcile_assignments(), vec!["T2".to_string()]);
+        assert_eq!(sys.data.tasks().get("T1").unwrap().agent.as_deref(), Some("w1"));
+        assert_eq!(sys.data.tasks().get("T2").unwrap().agent, None);
+        assert!(sys.reconcile_assignments().is_empty());
+    }
+
+    // --- agent timestamps ---
+
+    #[test]
+    fn agent_new_sets_created_at() {
+        let mut sys = test_sys();
+        let before = now_ms();
+        sys.execute(Command::AgentNew {
+            role: "worker".into(),
+            name: Some("w1".into()),
+            path: None,
+            agent_type: None,
+        });
+        let agent = sys.data.agents().get("w1").unwrap();
+        assert!(agent.created_at_ms.unwrap() >= before);
+        assert_eq!(agent.assigned_at_ms, None);
+    }
+
+    #[test]
+    fn agent_assign_sets_and_unassign_clears_assigned_at() {
+        let mut sys = test_sys();
+        sys.execute(Command::AgentNew {
+            role: "worker".into(),
+            name: Some("w1".into()),
+            path: None,
+            agent_type: None,
+        });
+        let before = now_ms();
+        let r = sys.execute(Command::AgentAssign {
+            name: "w1".into(),
+            task: "T1".into(),
+        });
+        assert!(is_ok(&r));
+        let assigned = sys.data.agents().get("w1").unwrap().assigned_at_ms;
+        assert!(assigned.unwrap() >= before);
+
+        sys.execute(Command::AgentUnassign { name: "w1".into() });
+        assert_eq!(sys.data.agents().get("w1").unwrap().assigned_at_ms, None);
+    }
+
+    #[test]
+    fn agent_view_and_list_show_age() {
+        let mut sys = test_sys();
+        sys.execute(Command::AgentNew {
+            role: "worker".into(),
+            name: Some("w1".into()),
+            path: None,
+            agent_type: None,
+        });
+        let r = sys.execute(Command::View { name: "w1".into() });
+        assert!(output(&r).contains("\"age\": \"0s\""));
+        assert!(output(&r).contains("\"time_on_task\": \"-\""));
+        let r = sys.execute(Command::AgentList { format: None, sort_by: None, columns: None });
+        assert!(output(&r).contains("0s"));
+    }
+
+    #[test]
+    fn format_elapsed_units() {
+        assert_eq!(format_elapsed(None, 1000), "-");
+        assert_eq!(format_elapsed(Some(0), 42_000), "42s");
+        assert_eq!(format_elapsed(Some(0), 300_000), "5m");
+        assert_eq!(format_elapsed(Some(0), 3 * 3_600_000), "3h");
+        assert_eq!(format_elapsed(Some(0), 2 * 86_400_000), "2d");
+    }
+
     // --- view ---
 
     #[test]
@@ -2295,18 +4711,69 @@ mod tests {
         let r = sys.execute(Command::Tell {
             agent: "w1".into(),
             text: "start task".into(),
+            dedup: false,
         });
         assert!(is_ok(&r));
         assert_eq!(sys.data.messages().pending_for("w1").len(), 1);
         assert_eq!(sys.pending_actions().len(), 1);
     }
 
+    #[test]
+    fn tell_dedup_queues_identical_message_once() {
+        let mut sys = test_sys();
+        sys.execute(Command::AgentNew {
+            role: "worker".into(),
+            name: Some("w1".into()),
+            path: None,
+            agent_type: None,
+        });
+        for _ in 0..2 {
+            sys.execute(Command::Tell {
+                agent: "w1".into(),
+                text: "start task".into(),
+                dedup: true,
+            });
+        }
+        assert_eq!(sys.data.messages().pending_for("w1").len(), 1);
+        // The second tell was dropped, so it emitted no keystrokes.
+        assert!(sys.pending_actions().is_empty());
+
+        let r = sys.execute(Command::Tell {
+            agent: "w1".into(),
+            text: "start task".into(),
+            dedup: true,
+        });
+        assert!(is_ok(&r));
+        assert!(output(&r).contains("Duplicate"));
+    }
+
+    #[test]
+    fn tell_without_dedup_queues_both() {
+        let mut sys = test_sys();
+        sys.execute(Command::AgentNew {
+            role: "worker".into(),
+            name: Some("w1".into()),
+            path: None,
+            agent_type: None,
+        });
+        for _ in 0..2 {
+            sys.execute(Command::Tell {
+                agent: "w1".into(),
+                text: "start task".into(),
+                dedup: false,
+            });
+        }
+        assert_eq!(sys.data.messages().pending_for("w1").len(), 2);
+        assert_eq!(sys.pending_actions().len(), 1);
+    }
+
     #[test]
     fn tell_nonexistent_agent() {
         let mut sys = test_sys();
         let r = sys.execute(Command::Tell {
             agent: "ghost".into(),
             text: "hello".into(),
+            dedup: false,
         });
         assert!(is_err(&r));
     }
@@ -2349,6 +4816,52 @@ mod tests {
         assert_eq!(sys.pending_actions().len(), 2);
     }
 
+    #[test]
+    fn agent_exec_emits_send_keys() {
+        let mut sys = test_sys();
+        sys.execute(Command::AgentNew {
+            role: "worker".into(),
+            name: Some("w1".into()),
+            path: None,
+            agent_type: None,
+        });
+        let r = sys.execute(Command::AgentExec {
+            agent: "w1".into(),
+            cmd: "git status".into(),
+        });
+        assert!(is_ok(&r));
+        assert_eq!(sys.pending_actions().len(), 1);
+        assert_eq!(
+            sys.pending_actions().last(),
+            Some(&Action::SendKeys {
+                target: "w1".into(),
+                keys: "git status".into(),
+            })
+        );
+    }
+
+    #[test]
+    fn agent_exec_rejects_unknown_agent_and_blank_command() {
+        let mut sys = test_sys();
+        let r = sys.execute(Command::AgentExec {
+            agent: "ghost".into(),
+            cmd: "ls".into(),
+        });
+        assert!(output(&r).contains("not found"));
+        sys.execute(Command::AgentNew {
+            role: "worker".into(),
+            name: Some("w1".into()),
+            path: None,
+            agent_type: None,
+        });
+        let r = sys.execute(Command::AgentExec {
+            agent: "w1".into(),
+            cmd: "  ".into(),
+        });
+        assert!(is_err(&r));
+        assert!(sys.pending_actions().iter().all(|a| !matches!(a, Action::SendKeys { .. })));
+    }
+
     // --- project lifecycle ---
 
     #[test]
@@ -2419,21 +4932,242 @@ mod tests {
     }
 
     #[test]
-    fn config_add_unknown_key() {
+    fn config_list_appends_settings_warnings() {
+        let dir = std::env::temp_dir().join(format!("cmx_sys_settings_warn_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("settings.yaml"), "health_check_intervall: 9999\n").unwrap();
+        let mut sys = Sys::from_data(Data::new(&dir).unwrap());
+        let r = sys.execute(Command::ConfigList);
+        let out = output(&r);
+        assert!(out.contains("# warning: settings: unknown key 'health_check_intervall'"), "{}", out);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn config_load_refreshes_settings_warnings() {
+        let dir = std::env::temp_dir().join(format!("cmx_sys_settings_reload_warn_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("settings.yaml"), "health_check_intervall: 9999\n").unwrap();
+        let mut sys = Sys::from_data(Data::new(&dir).unwrap());
+        let other = dir.join("other.yaml");
+        std::fs::write(&other, "message_timout: 5s\n").unwrap();
+        let r = sys.execute(Command::ConfigLoad {
+            path: Some(other.to_string_lossy().into_owned()),
+        });
+        assert!(!is_err(&r));
+        let r = sys.execute(Command::ConfigList);
+        let out = output(&r);
+        assert!(out.contains("unknown key 'message_timout'"), "{}", out);
+        assert!(!out.contains("health_check_intervall"), "{}", out);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn config_add_unknown_key() {
+        let mut sys = test_sys();
+        let r = sys.execute(Command::ConfigAdd {
+            key: "bogus_key".into(),
+            value: "x".into(),
+        });
+        assert!(is_err(&r));
+    }
+
+    #[test]
+    fn config_add_session_backend() {
+        let mut sys = test_sys();
+        let r = sys.execute(Command::ConfigAdd {
+            key: "session_backend".into(),
+            value: "zellij".into(),
+        });
+        assert!(is_ok(&r));
+        assert_eq!(sys.settings.session_backend, SessionBackendKind::Zellij);
+        assert!(output(&sys.execute(Command::ConfigList)).contains("session_backend: zellij"));
+        let r = sys.execute(Command::ConfigAdd {
+            key: "session_backend".into(),
+            value: "screen".into(),
+        });
+        assert!(is_err(&r));
+        sys.execute(Command::ConfigUnset { key: "session_backend".into() });
+        assert_eq!(sys.settings.session_backend, SessionBackendKind::Tmux);
+    }
+
+    #[test]
+    fn config_add_skill_conflict_strategy_reaches_library() {
+        let mut sys = test_sys();
+        let r = sys.execute(Command::ConfigAdd {
+            key: "skill_conflict_strategy".into(),
+            value: "first-registered".into(),
+        });
+        assert!(is_ok(&r));
+        assert_eq!(sys.library.strategy(), ConflictStrategy::FirstRegistered);
+        let r = sys.execute(Command::ConfigAdd {
+            key: "skill_conflict_strategy".into(),
+            value: "newest".into(),
+        });
+        assert!(is_err(&r));
+        sys.execute(Command::ConfigUnset { key: "skill_conflict_strategy".into() });
+        assert_eq!(sys.library.strategy(), ConflictStrategy::HighestPriority);
+    }
+
+    #[test]
+    fn config_unset_restores_default() {
+        let mut sys = test_sys();
+        let default = crate::data::settings::default_settings().max_retries;
+        sys.execute(Command::ConfigAdd {
+            key: "max_retries".into(),
+            value: "10".into(),
+        });
+        assert!(output(&sys.execute(Command::ConfigList)).contains("max_retries: 10"));
+
+        let r = sys.execute(Command::ConfigUnset { key: "max_retries".into() });
+        assert!(is_ok(&r));
+        assert_eq!(sys.settings.max_retries, default);
+        let listed = sys.execute(Command::ConfigList);
+        assert!(output(&listed).contains(&format!("max_retries: {}", default)));
+    }
+
+    #[test]
+    fn config_unset_role_marker_override() {
+        let mut sys = test_sys();
+        sys.execute(Command::ConfigAdd {
+            key: "heartbeat_marker.worker".into(),
+            value: "PING".into(),
+        });
+        assert_eq!(sys.settings.heartbeat_marker_for("worker"), "PING");
+        let r = sys.execute(Command::ConfigUnset { key: "heartbeat_marker.worker".into() });
+        assert!(is_ok(&r));
+        assert!(sys.settings.heartbeat_marker_overrides.is_empty());
+    }
+
+    #[test]
+    fn config_heartbeat_pattern_add_and_unset() {
+        let mut sys = test_sys();
+        let r = sys.execute(Command::ConfigAdd {
+            key: "heartbeat_pattern.ssh".into(),
+            value: r"^\[(?P<host>[^\]]+)\] \$".into(),
+        });
+        assert!(is_ok(&r));
+        assert!(sys.settings.heartbeat_patterns.contains_key("ssh"));
+        let bad_regex = sys.execute(Command::ConfigAdd {
+            key: "heartbeat_pattern.console".into(),
+            value: "(unclosed".into(),
+        });
+        assert!(!is_ok(&bad_regex));
+        let bad_type = sys.execute(Command::ConfigAdd {
+            key: "heartbeat_pattern.robot".into(),
+            value: "x".into(),
+        });
+        assert!(!is_ok(&bad_type));
+        let r = sys.execute(Command::ConfigUnset { key: "heartbeat_pattern.ssh".into() });
+        assert!(is_ok(&r));
+        assert!(sys.settings.heartbeat_patterns.is_empty());
+
+        // Type names are case-insensitive and stored canonically.
+        sys.execute(Command::ConfigAdd { key: "heartbeat_pattern.SSH".into(), value: "x".into() });
+        assert!(sys.settings.heartbeat_patterns.contains_key("ssh"));
+        sys.execute(Command::ConfigUnset { key: "heartbeat_pattern.Ssh".into() });
+        assert!(sys.settings.heartbeat_patterns.is_empty());
+    }
+
+    #[test]
+    fn config_add_hysteresis_counts() {
+        let mut sys = test_sys();
+        let r = sys.execute(Command::ConfigAdd {
+            key: "stall_after_unhealthy".into(),
+            value: "5".into(),
+        });
+        assert!(is_ok(&r));
+        assert_eq!(sys.settings.stall_after_unhealthy, 5);
+        let r = sys.execute(Command::ConfigAdd {
+            key: "clear_after_healthy".into(),
+            value: "0".into(),
+        });
+        assert!(is_err(&r));
+    }
+
+    #[test]
+    fn config_add_message_interval_paces_store() {
+        let mut sys = test_sys();
+        let r = sys.execute(Command::ConfigAdd {
+            key: "message_interval".into(),
+            value: "2s".into(),
+        });
+        assert!(is_ok(&r));
+        assert_eq!(sys.settings.message_interval, 2000);
+        assert_eq!(sys.data.messages().interval_for("w1"), 2000);
+
+        let r = sys.execute(Command::ConfigUnset { key: "message_interval".into() });
+        assert!(is_ok(&r));
+        assert_eq!(sys.data.messages().interval_for("w1"), 0);
+    }
+
+    #[test]
+    fn config_unset_unknown_key() {
+        let mut sys = test_sys();
+        let r = sys.execute(Command::ConfigUnset { key: "bogus_key".into() });
+        assert!(is_err(&r));
+        assert!(output(&r).contains("Unknown config key"));
+    }
+
+    #[test]
+    fn config_add_invalid_number() {
+        let mut sys = test_sys();
+        let r = sys.execute(Command::ConfigAdd {
+            key: "max_retries".into(),
+            value: "notanumber".into(),
+        });
+        assert!(is_err(&r));
+    }
+
+    #[test]
+    fn config_change_snapshots_settings_when_enabled() {
+        let dir = std::env::temp_dir().join("cmx_sys_config_auto_snapshot");
+        let _ = std::fs::remove_dir_all(&dir);
+        let _ = std::fs::create_dir_all(&dir);
+        let mut sys = Sys::from_data(Data::new(&dir).unwrap());
+        let settings_history = || {
+            HistoryManager::for_settings(dir.clone()).unwrap().list().unwrap().len()
+        };
+
+        // Disabled by default: nothing recorded.
+        sys.execute(Command::ConfigAdd { key: "max_retries".into(), value: "4".into() });
+        assert_eq!(settings_history(), 0);
+
+        sys.execute(Command::ConfigAdd {
+            key: "auto_snapshot_on_change".into(),
+            value: "true".into(),
+        });
+        assert_eq!(settings_history(), 1);
+
+        // Redundant set: content unchanged, no new entry.
+        sys.execute(Command::ConfigAdd { key: "max_retries".into(), value: "4".into() });
+        assert_eq!(settings_history(), 1);
+
+        // Back-to-back real changes each get their own entry.
+        sys.execute(Command::ConfigAdd { key: "max_retries".into(), value: "9".into() });
+        assert_eq!(settings_history(), 2);
+        sys.execute(Command::ConfigAdd { key: "max_retries".into(), value: "10".into() });
+        assert_eq!(settings_history(), 3);
+        assert!(!sys.snapshot_settings_if_enabled(now_ms()));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn config_add_human_duration() {
         let mut sys = test_sys();
         let r = sys.execute(Command::ConfigAdd {
-            key: "bogus_key".into(),
-            value: "x".into(),
+            key: "heartbeat_timeout".into(),
+            value: "45s".into(),
         });
-        assert!(is_err(&r));
-    }
+        assert!(is_ok(&r));
+        assert_eq!(sys.settings.heartbeat_timeout, 45_000);
 
-    #[test]
-    fn config_add_invalid_number() {
-        let mut sys = test_sys();
         let r = sys.execute(Command::ConfigAdd {
-            key: "max_retries".into(),
-            value: "notanumber".into(),
+            key: "message_timeout".into(),
+            value: "later".into(),
         });
         assert!(is_err(&r));
     }
@@ -2540,6 +5274,127 @@ mod tests {
         assert!(output(&r).contains("not initialized"));
     }
 
+    #[test]
+    fn rig_push_by_label() {
+        use crate::infrastructure::runner::MockRunner;
+
+        let dir = std::env::temp_dir().join("cmx_sys_rig_label");
+        let _ = std::fs::remove_dir_all(&dir);
+        let _ = std::fs::create_dir_all(&dir);
+        let mut registry = RigRegistry::new();
+        for (name, label) in [("g1", "gpu"), ("c1", "cpu"), ("g2", "gpu")] {
+            let mut config = parse_host_string(&format!("root@{}", name), name);
+            config.labels = vec![label.into()];
+            registry.add(config).unwrap();
+        }
+        let runner = MockRunner::with_responses(vec![Ok("ok".into()), Ok("ok".into())]);
+        let rig = RigOrchestrator::new(registry, Box::new(runner));
+        let mut sys = Sys::from_data_with_rig(Data::new(&dir).unwrap(), rig);
+
+        let r = sys.execute(Command::RigPush {
+            folder: "/src".into(),
+            remote: None,
+            label: Some("gpu".into()),
+            all: false,
+            bwlimit: None,
+            exclude: Vec::new(),
+        });
+        assert!(is_ok(&r));
+        assert!(output(&r).contains("'g1'"));
+        assert!(output(&r).contains("'g2'"));
+        assert!(!output(&r).contains("'c1'"));
+
+        let r = sys.execute(Command::RigPull {
+            folder: "/src".into(),
+            remote: None,
+            label: Some("tpu".into()),
+            bwlimit: None,
+            exclude: Vec::new(),
+        });
+        assert!(is_err(&r));
+
+        let r = sys.execute(Command::RigPush {
+            folder: "/src".into(),
+            remote: Some("g1".into()),
+            label: Some("gpu".into()),
+            all: false,
+            bwlimit: None,
+            exclude: Vec::new(),
+        });
+        assert!(is_err(&r));
+
+        let r = sys.execute(Command::RigPush {
+            folder: "/src".into(),
+            remote: None,
+            label: None,
+            all: true,
+            bwlimit: None,
+            exclude: Vec::new(),
+        });
+        assert!(is_ok(&r));
+        for name in ["'g1'", "'c1'", "'g2'"] {
+            assert!(output(&r).contains(name));
+        }
+
+        let r = sys.execute(Command::RigPush {
+            folder: "/src".into(),
+            remote: None,
+            label: Some("gpu".into()),
+            all: true,
+            bwlimit: None,
+            exclude: Vec::new(),
+        });
+        assert!(is_err(&r));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn rig_registry_survives_sys_rebuild() {
+        use crate::infrastructure::runner::MockRunner;
+
+        let dir = std::env::temp_dir().join("cmx_sys_rig_persist");
+        let _ = std::fs::remove_dir_all(&dir);
+        let _ = std::fs::create_dir_all(&dir);
+        let runner = MockRunner::with_responses(vec![Ok("ok".into())]);
+        let rig = RigOrchestrator::new(RigRegistry::new(), Box::new(runner));
+        let mut sys = Sys::from_data_with_rig(Data::new(&dir).unwrap(), rig);
+        sys.execute(Command::RigInit {
+            host: "ubuntu@10.0.0.7:2222".into(),
+            name: Some("gpu1".into()),
+        });
+        let r = sys.execute(Command::RigDefault { name: Some("gpu1".into()) });
+        assert!(is_ok(&r));
+        assert!(dir.join(RIG_FILE).exists());
+        let r = sys.execute(Command::RigInit {
+            host: "ubuntu@10.0.0.8".into(),
+            name: Some("gpu1".into()),
+        });
+        assert!(is_err(&r));
+        assert!(format!("{:?}", r).contains("remote 'gpu1' already exists"));
+
+        let mut rebuilt = Sys::new(&dir).unwrap();
+        let r = rebuilt.execute(Command::RigList);
+        assert!(is_ok(&r));
+        assert!(output(&r).contains("gpu1"));
+        assert!(output(&r).contains("10.0.0.7:2222"));
+        let r = rebuilt.execute(Command::RigDefault { name: None });
+        assert!(output(&r).contains("gpu1"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn sys_new_without_rig_file_has_empty_registry() {
+        let dir = std::env::temp_dir().join("cmx_sys_rig_missing");
+        let _ = std::fs::remove_dir_all(&dir);
+        let _ = std::fs::create_dir_all(&dir);
+        let mut sys = Sys::new(&dir).unwrap();
+        let r = sys.execute(Command::RigList);
+        assert!(output(&r).contains("No remotes configured"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
     // Client tests removed — handled by MuxUX.
 
     #[test]
@@ -2557,6 +5412,35 @@ mod tests {
         assert_eq!(snap.agents[0].role, "worker");
     }
 
+    #[test]
+    fn build_snapshot_records_pending_messages_per_agent() {
+        let mut sys = test_sys();
+        for name in ["w1", "w2"] {
+            sys.execute(Command::AgentNew {
+                role: "worker".into(),
+                name: Some(name.into()),
+                path: None,
+                agent_type: None,
+            });
+        }
+        sys.execute(Command::Tell {
+            agent: "w1".into(),
+            text: "start task\nwith details".into(),
+            dedup: false,
+        });
+        sys.execute(Command::Tell {
+            agent: "w1".into(),
+            text: "run tests".into(),
+            dedup: false,
+        });
+        let snap = sys.build_snapshot();
+        assert_eq!(snap.message_count, 2);
+        assert_eq!(snap.pending_count_for("w1"), 2);
+        assert_eq!(snap.pending_count_for("w2"), 0);
+        assert_eq!(snap.pending_messages.len(), 1);
+        assert_eq!(snap.pending_messages[0].first_lines, vec!["start task", "run tests"]);
+    }
+
     #[test]
     fn save_current_state_creates_file() {
         let dir = std::env::temp_dir().join("cmx_sys_save_state_test");
@@ -2596,6 +5480,7 @@ mod tests {
             role: "worker".into(),
             size: 3,
             path: Some("/tmp/work".into()),
+            min_idle: None,
         });
         assert!(is_ok(&r));
         assert!(output(&r).contains("Pool 'worker' set to 3"));
@@ -2603,6 +5488,39 @@ mod tests {
         assert_eq!(sys.data.agents().list().len(), 3);
     }
 
+    #[test]
+    fn pool_set_min_idle_spawns_reserve() {
+        let mut sys = test_sys();
+        sys.execute(Command::PoolSet {
+            role: "worker".into(),
+            size: 2,
+            path: Some("/tmp".into()),
+            min_idle: None,
+        });
+        for (i, agent) in ["worker1", "worker2"].iter().enumerate() {
+            let a = sys.data.agents_mut().get_mut(agent).unwrap();
+            a.task = Some(format!("T{}", i));
+            a.status = AgentStatus::Busy;
+        }
+        let r = sys.execute(Command::PoolSet {
+            role: "worker".into(),
+            size: 2,
+            path: Some("/tmp".into()),
+            min_idle: Some(1),
+        });
+        assert!(output(&r).contains("spawned 1"), "{}", output(&r));
+        assert_eq!(sys.data.agents().list().len(), 3);
+
+        // Re-setting without min_idle keeps the reserve.
+        sys.execute(Command::PoolSet {
+            role: "worker".into(),
+            size: 2,
+            path: Some("/tmp".into()),
+            min_idle: None,
+        });
+        assert_eq!(sys.pool.get_config("worker").unwrap().min_idle, 1);
+    }
+
     #[test]
     fn pool_status_shows_counts() {
         let mut sys = test_sys();
@@ -2610,6 +5528,7 @@ mod tests {
             role: "worker".into(),
             size: 2,
             path: Some("/tmp".into()),
+            min_idle: None,
         });
         let r = sys.execute(Command::PoolStatus { role: "worker".into() });
         assert!(is_ok(&r));
@@ -2617,6 +5536,51 @@ mod tests {
         assert!(output(&r).contains("target: 2"));
     }
 
+    #[test]
+    fn pool_status_counts_only_its_own_project_tasks() {
+        let mut sys = test_sys();
+        sys.settings.pool_auto_expand = true;
+        for role in ["alpha", "beta"] {
+            sys.execute(Command::PoolSet {
+                role: role.into(),
+                size: 1,
+                path: Some(format!("/work/{}", role)),
+                min_idle: None,
+            });
+        }
+        let names: Vec<String> = sys.data.agents().list().iter().map(|a| a.name.clone()).collect();
+        for name in names {
+            sys.data.agents_mut().get_mut(&name).unwrap().status = AgentStatus::Busy;
+        }
+        let mut project = TaskNode {
+            id: "alpha".into(),
+            title: "alpha".into(),
+            source: TaskSource::Filesystem,
+            status: TaskStatus::InProgress,
+            result: None,
+            agent: None,
+            children: vec![],
+            spec_path: Some("/work/alpha".into()),
+            depends_on: Vec::new(),
+            notes: Vec::new(),
+            due_at_ms: None,
+        };
+        for id in ["A1", "A2"] {
+            let mut step = project.clone();
+            step.id = id.into();
+            step.status = TaskStatus::Pending;
+            step.spec_path = None;
+            project.children.push(step);
+        }
+        sys.data.tasks_mut().add_root(project);
+
+        let alpha = output(&sys.execute(Command::PoolStatus { role: "alpha".into() })).to_string();
+        assert!(alpha.contains("0 idle"), "got: {}", alpha);
+        assert!(alpha.ends_with("; autoscale: spawn 1"), "got: {}", alpha);
+        let beta = output(&sys.execute(Command::PoolStatus { role: "beta".into() })).to_string();
+        assert!(!beta.contains("autoscale"), "got: {}", beta);
+    }
+
     #[test]
     fn pool_list_after_set() {
         let mut sys = test_sys();
@@ -2624,6 +5588,7 @@ mod tests {
             role: "worker".into(),
             size: 2,
             path: Some("/tmp".into()),
+            min_idle: None,
         });
         let r = sys.execute(Command::PoolList);
         assert!(is_ok(&r));
@@ -2638,6 +5603,7 @@ mod tests {
             role: "worker".into(),
             size: 2,
             path: Some("/tmp".into()),
+            min_idle: None,
         });
         let r = sys.execute(Command::PoolRemove { role: "worker".into() });
         assert!(is_ok(&r));
@@ -2664,7 +5630,7 @@ mod tests {
         let _ = std::fs::create_dir_all(&dir);
         let data = Data::new(&dir).unwrap();
         let mut sys = Sys::from_data(data);
-        let r = sys.execute(Command::DiagnosisReport);
+        let r = sys.execute(Command::DiagnosisReport { since: None, until: None, dest: None });
         assert!(is_ok(&r));
         assert!(output(&r).contains("No intervention events recorded"));
         let _ = std::fs::remove_dir_all(&dir);
@@ -2695,19 +5661,234 @@ mod tests {
                     outcome_detail: "ok".into(),
                     duration_ms: 500,
                     failure_mode: "none".into(),
+                    annotations: Vec::new(),
                 })
                 .unwrap();
         }
 
         let data = Data::new(&dir).unwrap();
         let mut sys = Sys::from_data(data);
-        let r = sys.execute(Command::DiagnosisReport);
+        let r = sys.execute(Command::DiagnosisReport { since: None, until: None, dest: None });
         assert!(is_ok(&r));
         assert!(output(&r).contains("# Diagnosis Report"));
         assert!(output(&r).contains("Signal Reliability"));
         let _ = std::fs::remove_dir_all(&dir);
     }
 
+    fn seed_diagnosis(dir: &Path, outcomes: &[(u64, InterventionOutcome)]) {
+        use crate::diagnosis::InterventionEvent;
+        let mut engine = DiagnosisEngine::new(dir.to_path_buf()).unwrap();
+        for (i, (ts, outcome)) in outcomes.iter().enumerate() {
+            engine
+                .record(InterventionEvent {
+                    id: i as u64,
+                    timestamp_ms: *ts,
+                    agent: "w1".into(),
+                    signal: SignalType::HeartbeatStale,
+                    signal_detail: "stale".into(),
+                    action: InterventionAction::Retry,
+                    outcome: outcome.clone(),
+                    outcome_detail: "".into(),
+                    duration_ms: 500,
+                    failure_mode: "none".into(),
+                    annotations: Vec::new(),
+                })
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn diagnosis_annotate_shows_in_events() {
+        let dir = std::env::temp_dir().join("cmx_sys_diag_annotate");
+        let _ = std::fs::remove_dir_all(&dir);
+        let _ = std::fs::create_dir_all(&dir);
+        seed_diagnosis(&dir, &[(1_000, InterventionOutcome::Resolved)]);
+        let id = DiagnosisEngine::new(dir.clone()).unwrap().events()[0].id;
+
+        let mut sys = Sys::from_data(Data::new(&dir).unwrap());
+        let r = sys.execute(Command::DiagnosisAnnotate {
+            id: id.to_string(),
+            note: "retry fixed a flaky mount".into(),
+        });
+        assert!(is_ok(&r), "{}", output(&r));
+
+        let r = sys.execute(Command::DiagnosisEvents { limit: None, format: None });
+        assert!(output(&r).contains("retry fixed a flaky mount"));
+
+        let r = sys.execute(Command::DiagnosisAnnotate {
+            id: "999".into(),
+            note: "x".into(),
+        });
+        assert!(is_err(&r));
+        assert!(output(&r).contains("event not found"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn diagnosis_report_window_differs_from_all_time() {
+        let dir = std::env::temp_dir().join("cmx_sys_diag_report_window");
+        let _ = std::fs::remove_dir_all(&dir);
+        let _ = std::fs::create_dir_all(&dir);
+        seed_diagnosis(&dir, &[
+            (1_000, InterventionOutcome::SelfResolved),
+            (2_000, InterventionOutcome::SelfResolved),
+            (3_000, InterventionOutcome::Resolved),
+            (4_000, InterventionOutcome::Resolved),
+        ]);
+        let mut sys = Sys::from_data(Data::new(&dir).unwrap());
+
+        let all = sys.execute(Command::DiagnosisReport { since: None, until: None, dest: None });
+        assert!(output(&all).contains("- **Total events:** 4"));
+        assert!(output(&all).contains("- **Overall success rate:** 50.0%"));
+
+        let r = sys.execute(Command::DiagnosisReport {
+            since: Some("3000".into()),
+            until: None,
+            dest: None,
+        });
+        let out = output(&r);
+        assert!(out.contains("- **Window:** 3000 ms to open"));
+        assert!(out.contains("- **Total events:** 2"));
+        assert!(out.contains("- **Overall success rate:** 100.0%"));
+
+        // Relative bounds: nothing happened in the last hour.
+        let r = sys.execute(Command::DiagnosisReport {
+            since: Some("1h".into()),
+            until: None,
+            dest: None,
+        });
+        assert!(is_ok(&r));
+        assert!(output(&r).contains("No events in window."));
+
+        let r = sys.execute(Command::DiagnosisReport {
+            since: Some("4000".into()),
+            until: Some("2000".into()),
+            dest: None,
+        });
+        assert!(is_err(&r));
+        let r = sys.execute(Command::DiagnosisReport {
+            since: Some("yesterday".into()),
+            until: None,
+            dest: None,
+        });
+        assert!(is_err(&r));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn diagnosis_report_writes_file() {
+        let dir = std::env::temp_dir().join("cmx_sys_diag_report_dest");
+        let _ = std::fs::remove_dir_all(&dir);
+        let _ = std::fs::create_dir_all(&dir);
+        seed_diagnosis(&dir, &[(1_000, InterventionOutcome::Resolved)]);
+        let mut sys = Sys::from_data(Data::new(&dir).unwrap());
+
+        let dest = dir.join("reports").join("diag.md");
+        let r = sys.execute(Command::DiagnosisReport {
+            since: None,
+            until: Some("5000".into()),
+            dest: Some(dest.to_string_lossy().into_owned()),
+        });
+        assert!(is_ok(&r));
+        assert!(output(&r).starts_with("Wrote diagnosis report to"));
+        let written = std::fs::read_to_string(&dest).unwrap();
+        assert!(written.starts_with("# Diagnosis Report"));
+        assert!(written.contains("- **Window:** open to 5000 ms"));
+        assert!(written.contains("- **Total events:** 1"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn diagnosis_report_file_matches_printed_report() {
+        let dir = std::env::temp_dir().join("cmx_sys_diag_report_stream");
+        let _ = std::fs::remove_dir_all(&dir);
+        let _ = std::fs::create_dir_all(&dir);
+        seed_diagnosis(&dir, &[
+            (1_000, InterventionOutcome::Resolved),
+            (2_000, InterventionOutcome::StillBroken),
+        ]);
+        let mut sys = Sys::from_data(Data::new(&dir).unwrap());
+
+        let printed = sys.execute(Command::DiagnosisReport { since: None, until: None, dest: None });
+        let dest = dir.join("diag.md");
+        let r = sys.execute(Command::DiagnosisReport {
+            since: None,
+            until: None,
+            dest: Some(dest.to_string_lossy().into_owned()),
+        });
+        assert!(is_ok(&r));
+        assert_eq!(std::fs::read_to_string(&dest).unwrap(), output(&printed));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn diagnosis_compare_agents_table() {
+        use crate::diagnosis::{
+            DiagnosisEngine, InterventionAction, InterventionEvent,
+            InterventionOutcome, SignalType,
+        };
+        let dir = std::env::temp_dir().join("cmx_sys_diag_compare");
+        let _ = std::fs::remove_dir_all(&dir);
+        let _ = std::fs::create_dir_all(&dir);
+
+        {
+            let mut engine = DiagnosisEngine::new(dir.clone()).unwrap();
+            for (agent, signal) in [
+                ("w1", SignalType::HeartbeatStale),
+                ("w2", SignalType::ErrorPattern),
+            ] {
+                engine
+                    .record(InterventionEvent {
+                        id: 0,
+                        timestamp_ms: 1000,
+                        agent: agent.into(),
+                        signal,
+                        signal_detail: "x".into(),
+                        action: InterventionAction::Retry,
+                        outcome: InterventionOutcome::Resolved,
+                        outcome_detail: "ok".into(),
+                        duration_ms: 500,
+                        failure_mode: "none".into(),
+                        annotations: Vec::new(),
+                    })
+                    .unwrap();
+            }
+        }
+
+        let data = Data::new(&dir).unwrap();
+        let mut sys = Sys::from_data(data);
+
+        let r = sys.execute(Command::DiagnosisCompare {
+            a: "w1".into(),
+            b: "w2".into(),
+            format: None,
+        });
+        assert!(is_ok(&r));
+        let out = output(&r);
+        assert!(out.contains("w1 (fires/score)"));
+        assert!(out.contains("heartbeat_stale"));
+        assert!(out.contains("error_pattern"));
+        assert!(out.contains("0 / 0.00"));
+
+        let r = sys.execute(Command::DiagnosisCompare {
+            a: "w1".into(),
+            b: "w2".into(),
+            format: Some("json".into()),
+        });
+        assert!(is_ok(&r));
+        assert!(output(&r).contains("\"total_fires\""));
+
+        let r = sys.execute(Command::DiagnosisCompare {
+            a: "x".into(),
+            b: "y".into(),
+            format: None,
+        });
+        assert!(output(&r).contains("No reliability data"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn diagnosis_reliability_all() {
         use crate::diagnosis::{
@@ -2733,6 +5914,7 @@ mod tests {
                         outcome_detail: "ok".into(),
                         duration_ms: 500,
                         failure_mode: "none".into(),
+                        annotations: Vec::new(),
                     })
                     .unwrap();
             }
@@ -2744,6 +5926,8 @@ mod tests {
         // Tabular format
         let r = sys.execute(Command::DiagnosisReliability {
             signal: None,
+            agent: None,
+            since: None,
             format: None,
         });
         assert!(is_ok(&r));
@@ -2753,6 +5937,8 @@ mod tests {
         // JSON format
         let r = sys.execute(Command::DiagnosisReliability {
             signal: None,
+            agent: None,
+            since: None,
             format: Some("json".into()),
         });
         assert!(is_ok(&r));
@@ -2761,6 +5947,49 @@ mod tests {
         let _ = std::fs::remove_dir_all(&dir);
     }
 
+    #[test]
+    fn diagnosis_reliability_per_agent() {
+        let dir = std::env::temp_dir().join("cmx_sys_diag_rel_agent");
+        let _ = std::fs::remove_dir_all(&dir);
+        let _ = std::fs::create_dir_all(&dir);
+        seed_diagnosis(&dir, &[(1_000, InterventionOutcome::Resolved)]);
+
+        let mut sys = Sys::from_data(Data::new(&dir).unwrap());
+        let r = sys.execute(Command::DiagnosisReliability {
+            signal: None,
+            agent: Some("ghost".into()),
+            since: None,
+            format: None,
+        });
+        assert!(is_ok(&r));
+        assert!(output(&r).contains("for agent 'ghost'"));
+
+        // Window after the only event: no recent data.
+        let r = sys.execute(Command::DiagnosisReliability {
+            signal: Some("heartbeat_stale".into()),
+            agent: None,
+            since: Some("1001".into()),
+            format: None,
+        });
+        assert!(is_ok(&r));
+        assert!(output(&r).contains("No reliability data for signal 'heartbeat_stale' since 1001 ms"));
+
+        let agent = DiagnosisEngine::new(dir.clone()).unwrap().events()[0].agent.clone();
+        let r = sys.execute(Command::DiagnosisReliability {
+            signal: None,
+            agent: Some(agent),
+            since: None,
+            format: None,
+        });
+        assert!(is_ok(&r));
+        assert!(output(&r).contains("Fires"));
+        // One fire: the interval is wide.
+        assert!(output(&r).contains("95% CI"));
+        assert!(output(&r).contains("[0.21, 1.00]"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn diagnosis_reliability_single_signal() {
         use crate::diagnosis::{
@@ -2785,6 +6014,7 @@ mod tests {
                     outcome_detail: "ok".into(),
                     duration_ms: 1000,
                     failure_mode: "none".into(),
+                    annotations: Vec::new(),
                 })
                 .unwrap();
         }
@@ -2793,6 +6023,8 @@ mod tests {
         let mut sys = Sys::from_data(data);
         let r = sys.execute(Command::DiagnosisReliability {
             signal: Some("error_pattern".into()),
+            agent: None,
+            since: None,
             format: None,
         });
         assert!(is_ok(&r));
@@ -2801,6 +6033,8 @@ mod tests {
         // Unknown signal
         let r = sys.execute(Command::DiagnosisReliability {
             signal: Some("bogus_signal".into()),
+            agent: None,
+            since: None,
             format: None,
         });
         assert!(is_err(&r));
@@ -2833,6 +6067,7 @@ mod tests {
                     outcome_detail: "ok".into(),
                     duration_ms: 500,
                     failure_mode: "none".into(),
+                    annotations: Vec::new(),
                 })
                 .unwrap();
             engine
@@ -2847,21 +6082,65 @@ mod tests {
                     outcome_detail: "nope".into(),
                     duration_ms: 1000,
                     failure_mode: "agent".into(),
+                    annotations: Vec::new(),
                 })
                 .unwrap();
         }
 
-        let data = Data::new(&dir).unwrap();
-        let mut sys = Sys::from_data(data);
+        let data = Data::new(&dir).unwrap();
+        let mut sys = Sys::from_data(data);
+        let r = sys.execute(Command::DiagnosisEffectiveness {
+            signal: None,
+            min_attempts: None,
+            format: None,
+        });
+        assert!(is_ok(&r));
+        assert!(output(&r).contains("retry"));
+        assert!(output(&r).contains("Attempts"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn effectiveness_table_flags_low_sample_pairs() {
+        use crate::diagnosis::{ActionEffectiveness, InterventionAction};
+        let entries = vec![
+            ActionEffectiveness {
+                signal: SignalType::ErrorPattern,
+                action: InterventionAction::Retry,
+                attempts: 1,
+                successes: 1,
+                failures: 0,
+                success_rate: 1.0,
+            },
+            ActionEffectiveness {
+                signal: SignalType::ErrorPattern,
+                action: InterventionAction::Restart,
+                attempts: 5,
+                successes: 4,
+                failures: 1,
+                success_rate: 0.8,
+            },
+        ];
+        let table = format_effectiveness_table(&entries, 5);
+        let retry = table.lines().find(|l| l.contains("retry")).unwrap();
+        let restart = table.lines().find(|l| l.contains("restart")).unwrap();
+        assert!(retry.contains("low confidence"));
+        assert!(!restart.contains("low confidence"));
+
+        let lenient = format_effectiveness_table(&entries, 1);
+        assert!(!lenient.contains("low confidence"));
+    }
+
+    #[test]
+    fn diagnosis_effectiveness_rejects_bad_min_attempts() {
+        let mut sys = test_sys();
         let r = sys.execute(Command::DiagnosisEffectiveness {
             signal: None,
+            min_attempts: Some("lots".into()),
             format: None,
         });
-        assert!(is_ok(&r));
-        assert!(output(&r).contains("retry"));
-        assert!(output(&r).contains("Attempts"));
-
-        let _ = std::fs::remove_dir_all(&dir);
+        assert!(is_err(&r));
     }
 
     #[test]
@@ -2918,6 +6197,7 @@ mod tests {
                         outcome_detail: "ok".into(),
                         duration_ms: 500,
                         failure_mode: "none".into(),
+                        annotations: Vec::new(),
                     })
                     .unwrap();
             }
@@ -2980,6 +6260,35 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_signal_type_round_trips_display() {
+        let signal = crate::diagnosis::SignalType::TriggerFired("BuildFailed".into());
+        assert_eq!(signal.to_string(), "trigger_fired(BuildFailed)");
+        assert_eq!(parse_signal_type(&signal.to_string()).unwrap(), signal);
+    }
+
+    #[test]
+    fn diagnosis_events_csv_format() {
+        let dir = std::env::temp_dir().join("cmx_sys_diag_csv");
+        let _ = std::fs::remove_dir_all(&dir);
+        let _ = std::fs::create_dir_all(&dir);
+        seed_diagnosis(&dir, &[
+            (1_000, InterventionOutcome::Resolved),
+            (2_000, InterventionOutcome::StillBroken),
+        ]);
+        let mut sys = Sys::from_data(Data::new(&dir).unwrap());
+        let r = sys.execute(Command::DiagnosisEvents {
+            limit: None,
+            format: Some("csv".into()),
+        });
+        assert!(is_ok(&r));
+        let lines: Vec<&str> = output(&r).lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], crate::diagnosis::CSV_HEADER);
+        assert!(lines[2].contains(",still_broken,"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn parse_signal_type_invalid() {
         assert!(parse_signal_type("bogus").is_err());
@@ -3009,7 +6318,7 @@ mod tests {
         std::fs::write(dir.join("Current Configuration.md"), "# Config v1\n").unwrap();
         let data = Data::new(&dir).unwrap();
         let mut sys = Sys::from_data(data);
-        let r = sys.execute(Command::HistorySnapshot);
+        let r = sys.execute(Command::HistorySnapshot { label: None });
         assert!(is_ok(&r));
         assert!(output(&r).contains("Snapshot created"));
         let _ = std::fs::remove_dir_all(&dir);
@@ -3024,9 +6333,9 @@ mod tests {
         let data = Data::new(&dir).unwrap();
         let mut sys = Sys::from_data(data);
         // First snapshot.
-        sys.execute(Command::HistorySnapshot);
+        sys.execute(Command::HistorySnapshot { label: None });
         // Second snapshot should detect no change.
-        let r = sys.execute(Command::HistorySnapshot);
+        let r = sys.execute(Command::HistorySnapshot { label: None });
         assert!(is_ok(&r));
         assert!(output(&r).contains("No changes to snapshot"));
         let _ = std::fs::remove_dir_all(&dir);
@@ -3040,7 +6349,7 @@ mod tests {
         std::fs::write(dir.join("Current Configuration.md"), "# Config v1\n").unwrap();
         let data = Data::new(&dir).unwrap();
         let mut sys = Sys::from_data(data);
-        sys.execute(Command::HistorySnapshot);
+        sys.execute(Command::HistorySnapshot { label: None });
         let r = sys.execute(Command::HistoryList { limit: None, format: None });
         assert!(is_ok(&r));
         assert!(output(&r).contains("Filename"));
@@ -3056,7 +6365,7 @@ mod tests {
         std::fs::write(dir.join("Current Configuration.md"), "# Config v1\n").unwrap();
         let data = Data::new(&dir).unwrap();
         let mut sys = Sys::from_data(data);
-        sys.execute(Command::HistorySnapshot);
+        sys.execute(Command::HistorySnapshot { label: None });
         let r = sys.execute(Command::HistoryList { limit: None, format: Some("json".into()) });
         assert!(is_ok(&r));
         assert!(output(&r).contains("timestamp_ms"));
@@ -3094,7 +6403,7 @@ mod tests {
         std::fs::write(dir.join("Current Configuration.md"), "# My Config\n").unwrap();
         let data = Data::new(&dir).unwrap();
         let mut sys = Sys::from_data(data);
-        sys.execute(Command::HistorySnapshot);
+        sys.execute(Command::HistorySnapshot { label: None });
         let r = sys.execute(Command::HistoryShow { id: "0".into() });
         assert!(is_ok(&r));
         assert!(output(&r).contains("# My Config"));
@@ -3132,7 +6441,7 @@ mod tests {
         let data = Data::new(&dir).unwrap();
         let mut sys = Sys::from_data(data);
         // Restore oldest (index 1 since newest is 0).
-        let r = sys.execute(Command::HistoryRestore { id: "1".into() });
+        let r = sys.execute(Command::HistoryRestore { id: "1".into(), dry_run: false });
         assert!(is_ok(&r));
         assert!(output(&r).contains("Restored"));
         let restored = std::fs::read_to_string(&config).unwrap();
@@ -3140,6 +6449,28 @@ mod tests {
         let _ = std::fs::remove_dir_all(&dir);
     }
 
+    #[test]
+    fn history_restore_dry_run_changes_nothing() {
+        use crate::history::snapshot::{create_snapshot, compose_timestamp};
+        let dir = std::env::temp_dir().join("cmx_sys_hist_restore_dry");
+        let _ = std::fs::remove_dir_all(&dir);
+        let _ = std::fs::create_dir_all(&dir);
+        let config = dir.join("Current Configuration.md");
+        let history_dir = dir.join("history");
+        let ts = compose_timestamp(2026, 2, 22, 10, 0, 0) * 1000;
+        create_snapshot(&history_dir, "original\n", ts).unwrap();
+        std::fs::write(&config, "current\n").unwrap();
+        let mut sys = Sys::from_data(Data::new(&dir).unwrap());
+        let r = sys.execute(Command::HistoryRestore { id: "0".into(), dry_run: true });
+        let text = output(&r);
+        assert!(text.contains("Dry run"), "got: {}", text);
+        assert!(text.contains("+ original"));
+        assert!(text.contains("- current"));
+        assert_eq!(std::fs::read_to_string(&config).unwrap(), "current\n");
+        assert_eq!(std::fs::read_dir(&history_dir).unwrap().count(), 1);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn history_prune_empty() {
         let dir = std::env::temp_dir().join("cmx_sys_hist_prune_empty");
@@ -3147,12 +6478,94 @@ mod tests {
         let _ = std::fs::create_dir_all(&dir);
         let data = Data::new(&dir).unwrap();
         let mut sys = Sys::from_data(data);
-        let r = sys.execute(Command::HistoryPrune);
+        let r = sys.execute(Command::HistoryPrune { dry_run: false });
         assert!(is_ok(&r));
         assert!(output(&r).contains("Pruned 0"));
         let _ = std::fs::remove_dir_all(&dir);
     }
 
+    #[test]
+    fn history_search_lists_matching_lines() {
+        use crate::history::snapshot::{create_snapshot, compose_timestamp};
+        let dir = std::env::temp_dir().join("cmx_sys_hist_search");
+        let _ = std::fs::remove_dir_all(&dir);
+        let _ = std::fs::create_dir_all(&dir);
+        let history_dir = dir.join("history");
+        let ts1 = compose_timestamp(2026, 2, 22, 10, 0, 0) * 1000;
+        let ts2 = compose_timestamp(2026, 2, 22, 11, 0, 0) * 1000;
+        create_snapshot(&history_dir, "agent: pilot\n", ts1).unwrap();
+        create_snapshot(&history_dir, "agent: pilot\nagent: w3\n", ts2).unwrap();
+        let mut sys = Sys::from_data(Data::new(&dir).unwrap());
+        let r = sys.execute(Command::HistorySearch { query: "w3".into(), ignore_case: false });
+        assert_eq!(output(&r), "2026-02-22T11-00-00.md:2: agent: w3");
+        let r = sys.execute(Command::HistorySearch { query: "w9".into(), ignore_case: false });
+        assert!(output(&r).contains("No snapshots"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn history_pin_survives_prune() {
+        use crate::history::snapshot::{create_snapshot, compose_timestamp};
+        let dir = std::env::temp_dir().join("cmx_sys_hist_pin");
+        let _ = std::fs::remove_dir_all(&dir);
+        let _ = std::fs::create_dir_all(&dir);
+        let history_dir = dir.join("history");
+        // Three snapshots in one old week; weekly retention keeps only the newest.
+        let oldest = compose_timestamp(2020, 1, 6, 10, 0, 0) * 1000;
+        create_snapshot(&history_dir, "v1\n", oldest).unwrap();
+        create_snapshot(&history_dir, "v2\n", oldest + 3_600_000).unwrap();
+        create_snapshot(&history_dir, "v3\n", oldest + 7_200_000).unwrap();
+        let data = Data::new(&dir).unwrap();
+        let mut sys = Sys::from_data(data);
+
+        let r = sys.execute(Command::HistoryPin { id: "2".into() });
+        assert!(is_ok(&r));
+        assert!(output(&r).contains("Pinned"));
+
+        let r = sys.execute(Command::HistoryPrune { dry_run: false });
+        assert!(output(&r).contains("Pruned 1"));
+
+        let r = sys.execute(Command::HistoryList { limit: None, format: None });
+        let out = output(&r);
+        assert!(out.contains("2020-01-06T10-00-00.md"));
+        assert!(out.contains("pinned"));
+        assert!(out.contains("2020-01-06T12-00-00.md"));
+        assert!(!out.contains("2020-01-06T11-00-00.md"));
+
+        let r = sys.execute(Command::HistoryUnpin { id: "1".into() });
+        assert!(output(&r).contains("Unpinned 2020-01-06T10-00-00.md"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn history_prune_dry_run_keeps_files() {
+        use crate::history::snapshot::{create_snapshot, compose_timestamp};
+        let dir = std::env::temp_dir().join("cmx_sys_hist_prune_dry");
+        let _ = std::fs::remove_dir_all(&dir);
+        let _ = std::fs::create_dir_all(&dir);
+        let history_dir = dir.join("history");
+        let oldest = compose_timestamp(2020, 1, 6, 10, 0, 0) * 1000;
+        let entries: Vec<_> = (0..3)
+            .map(|i| create_snapshot(&history_dir, &format!("v{}\n", i), oldest + i * 3_600_000).unwrap())
+            .collect();
+        let data = Data::new(&dir).unwrap();
+        let mut sys = Sys::from_data(data);
+
+        let r = sys.execute(Command::HistoryPrune { dry_run: true });
+        assert!(is_ok(&r));
+        let out = output(&r);
+        assert!(out.contains("Would prune 2"));
+        assert!(out.contains("2020-01-06T10-00-00.md"));
+        assert!(out.contains("2020-01-06T11-00-00.md"));
+        assert!(!out.contains("2020-01-06T12-00-00.md"));
+        assert!(entries.iter().all(|e| e.path.exists()));
+
+        let r = sys.execute(Command::HistoryPrune { dry_run: false });
+        assert!(output(&r).contains("Pruned 2 history entries, compressed 1"), "got: {}", output(&r));
+        assert!(history_dir.join("2020-01-06T12-00-00.md.gz").exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn history_diff_between_entries() {
         use crate::history::snapshot::{create_snapshot, compose_timestamp};
@@ -3168,10 +6581,15 @@ mod tests {
         let data = Data::new(&dir).unwrap();
         let mut sys = Sys::from_data(data);
         // Diff oldest (index 1) vs newest (index 0).
-        let r = sys.execute(Command::HistoryDiff { from: "1".into(), to: Some("0".into()) });
+        let r = sys.execute(Command::HistoryDiff { from: "1".into(), to: Some("0".into()), unified: false });
         assert!(is_ok(&r));
         assert!(output(&r).contains("gamma"));
         assert!(output(&r).contains("beta"));
+
+        let r = sys.execute(Command::HistoryDiff { from: "1".into(), to: Some("0".into()), unified: true });
+        let text = output(&r);
+        assert!(text.starts_with("--- a/2026-02-22T10-00-00.md\n+++ b/2026-02-22T11-00-00.md\n"));
+        assert!(text.contains("@@ -1,2 +1,2 @@\n alpha\n-beta\n+gamma\n"));
         let _ = std::fs::remove_dir_all(&dir);
     }
 
@@ -3185,12 +6603,16 @@ mod tests {
                 filename: "2026-02-22T10-00-00.md".into(),
                 path: PathBuf::from("/tmp/test"),
                 size_bytes: 100,
+                pinned: false,
+                label: None,
             },
             HistoryEntry {
                 timestamp_ms: 1000,
                 filename: "2026-02-22T09-00-00.md".into(),
                 path: PathBuf::from("/tmp/test2"),
                 size_bytes: 50,
+                pinned: false,
+                label: None,
             },
         ];
         // By index.
@@ -3207,6 +6629,32 @@ mod tests {
         assert!(resolve_history_entry(&entries, "nonexistent.md").is_err());
     }
 
+    #[test]
+    fn history_snapshot_label_resolves_after_reload() {
+        let dir = std::env::temp_dir().join("cmx_sys_hist_snap_label");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("Current Configuration.md"), "# Config\n").unwrap();
+
+        let mut sys = Sys::from_data(Data::new(&dir).unwrap());
+        let r = sys.execute(Command::HistorySnapshot { label: Some("baseline".into()) });
+        assert!(output(&r).starts_with("Snapshot created:"), "got: {:?}", r);
+        assert!(output(&r).contains("(baseline)"), "got: {:?}", r);
+        let r = sys.execute(Command::HistorySnapshot { label: Some("stable".into()) });
+        assert!(output(&r).starts_with("Labeled existing snapshot"), "got: {:?}", r);
+        let r = sys.execute(Command::HistorySnapshot { label: Some("baseline".into()) });
+        assert!(output(&r).starts_with("Labeled existing snapshot"), "got: {:?}", r);
+
+        // A fresh Sys reads the label back from disk.
+        let mut sys = Sys::from_data(Data::new(&dir).unwrap());
+        let r = sys.execute(Command::HistoryList { limit: None, format: None });
+        assert!(output(&r).contains("[baseline]"));
+        let r = sys.execute(Command::HistoryShow { id: "baseline".into() });
+        assert!(output(&r).contains("# Config"), "got: {:?}", r);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
     // --- hollow world (Milestone M) ---
     //
     // Synthetic E2E tests exercising the full orchestration pipeline:
@@ -3247,6 +6695,7 @@ mod tests {
         let r = sys.execute(Command::Tell {
             agent: "hwpm".into(),
             text: "build widget-alpha".into(),
+            dedup: false,
         });
         assert!(is_ok(&r));
         assert_eq!(sys.data.messages().pending_for("hwpm").len(), 1);
@@ -3295,6 +6744,7 @@ mod tests {
         let r = sys.execute(Command::Tell {
             agent: "hwp".into(),
             text: "widget-alpha complete".into(),
+            dedup: false,
         });
         assert!(is_ok(&r));
         assert_eq!(sys.data.messages().pending_for("hwp").len(), 1);
@@ -3328,7 +6778,7 @@ mod tests {
         assert_eq!(sys.data.agents().get("hwb2").unwrap().task, None);
 
         // Verify both builders listed
-        let r = sys.execute(Command::AgentList { format: None });
+        let r = sys.execute(Command::AgentList { format: None, sort_by: None, columns: None });
         assert!(output(&r).contains("hwb1"));
         assert!(output(&r).contains("hwb2"));
     }
@@ -3373,7 +6823,7 @@ mod tests {
         let mut sys = hw_sys();
 
         // Kill hwb2
-        let r = sys.execute(Command::AgentKill { name: "hwb2".into() });
+        let r = sys.execute(Command::AgentKill { name: "hwb2".into(), graceful: false });
         assert!(is_ok(&r));
         assert!(sys.data.agents().get("hwb2").is_none());
 
@@ -3404,18 +6854,21 @@ mod tests {
         sys.execute(Command::Tell {
             agent: "hwc1".into(),
             text: "build artifact ready at /out/widget".into(),
+            dedup: false,
         });
 
         // Checker tells PM
         sys.execute(Command::Tell {
             agent: "hwpm".into(),
             text: "check passed for /out/widget".into(),
+            dedup: false,
         });
 
         // PM tells pilot
         sys.execute(Command::Tell {
             agent: "hwp".into(),
             text: "widget verified".into(),
+            dedup: false,
         });
 
         // Verify message queues
@@ -3442,9 +6895,9 @@ mod tests {
         let mut sys = hw_sys();
 
         // Send 3 normal messages
-        sys.execute(Command::Tell { agent: "hwb1".into(), text: "normal msg 1".into() });
-        sys.execute(Command::Tell { agent: "hwb1".into(), text: "normal msg 2".into() });
-        sys.execute(Command::Tell { agent: "hwb1".into(), text: "normal msg 3".into() });
+        sys.execute(Command::Tell { agent: "hwb1".into(), text: "normal msg 1".into(), dedup: false });
+        sys.execute(Command::Tell { agent: "hwb1".into(), text: "normal msg 2".into(), dedup: false });
+        sys.execute(Command::Tell { agent: "hwb1".into(), text: "normal msg 3".into(), dedup: false });
 
         // Verify 3 pending
         assert_eq!(sys.data.messages().pending_for("hwb1").len(), 3);
@@ -3543,6 +6996,205 @@ mod tests {
         assert!(result.unwrap_err().contains("not found"));
     }
 
+    // --- automatic intervention recording ---
+
+    fn assessment(
+        agent: &str,
+        overall: HealthState,
+        signals: Vec<crate::types::health::HealthSignal>,
+        ts: u64,
+    ) -> crate::types::health::HealthAssessment {
+        crate::types::health::HealthAssessment {
+            agent: agent.into(),
+            overall,
+            signals,
+            reason: String::new(),
+            timestamp_ms: ts,
+        }
+    }
+
+    #[test]
+    fn single_unhealthy_assessment_does_not_stall() {
+        use crate::types::health::HealthSignal;
+
+        let mut sys = test_sys();
+        sys.settings.stall_after_unhealthy = 3;
+        sys.execute(Command::AgentNew {
+            role: "worker".into(),
+            name: Some("w1".into()),
+            path: None,
+            agent_type: None,
+        });
+        let stale = || vec![HealthSignal::HeartbeatStale { age_secs: 120 }];
+        sys.apply_health_update(&assessment("w1", HealthState::Unhealthy, stale(), 1));
+        assert_ne!(sys.data().agents().get("w1").unwrap().status, AgentStatus::Stalled);
+
+        // A healthy reading in between resets the run.
+        sys.apply_health_update(&assessment("w1", HealthState::Healthy, vec![], 2));
+        sys.apply_health_update(&assessment("w1", HealthState::Unhealthy, stale(), 3));
+        sys.apply_health_update(&assessment("w1", HealthState::Unhealthy, stale(), 4));
+        assert_ne!(sys.data().agents().get("w1").unwrap().status, AgentStatus::Stalled);
+
+        sys.apply_health_update(&assessment("w1", HealthState::Unhealthy, stale(), 5));
+        assert_eq!(sys.data().agents().get("w1").unwrap().status, AgentStatus::Stalled);
+    }
+
+    #[test]
+    fn stalled_clears_after_consecutive_healthy() {
+        let mut sys = test_sys();
+        sys.settings.stall_after_unhealthy = 1;
+        sys.settings.clear_after_healthy = 2;
+        sys.execute(Command::AgentNew {
+            role: "worker".into(),
+            name: Some("w1".into()),
+            path: None,
+            agent_type: None,
+        });
+        sys.apply_health_update(&assessment("w1", HealthState::Unhealthy, vec![], 1));
+        assert_eq!(sys.data().agents().get("w1").unwrap().status, AgentStatus::Stalled);
+
+        sys.apply_health_update(&assessment("w1", HealthState::Healthy, vec![], 2));
+        assert_eq!(sys.data().agents().get("w1").unwrap().status, AgentStatus::Stalled);
+        sys.apply_health_update(&assessment("w1", HealthState::Healthy, vec![], 3));
+        assert_eq!(sys.data().agents().get("w1").unwrap().status, AgentStatus::Idle);
+    }
+
+    #[test]
+    fn recheck_with_healthy_output_clears_stall() {
+        use crate::infrastructure::mock::MockBackend;
+        use crate::monitor::cycle::MonitorCycle;
+
+        let mut sys = test_sys();
+        sys.settings.stall_after_unhealthy = 1;
+        sys.settings.clear_after_healthy = 3;
+        sys.execute(Command::AgentNew {
+            role: "worker".into(),
+            name: Some("w1".into()),
+            path: None,
+            agent_type: None,
+        });
+        sys.notify_session_created("w1", "cmx-w1").unwrap();
+        sys.apply_health_update(&assessment("w1", HealthState::Unhealthy, vec![], 1));
+        assert_eq!(sys.data().agents().get("w1").unwrap().status, AgentStatus::Stalled);
+
+        let mut backend = MockBackend::new();
+        backend.set_capture("w1", "all tests passed\n$ ");
+        let mut monitor = MonitorCycle::new(30_000, 300, r"\$\s*$".into());
+        let r = sys.recheck_agent("w1", &mut monitor, &backend, 2_000);
+        assert!(is_ok(&r));
+        let fresh: crate::types::health::HealthAssessment =
+            serde_json::from_str(output(&r)).unwrap();
+        assert_eq!(fresh.overall, HealthState::Healthy);
+
+        let agent = sys.data().agents().get("w1").unwrap();
+        assert_eq!(agent.status, AgentStatus::Idle);
+        assert_eq!(agent.health, HealthState::Healthy);
+    }
+
+    #[test]
+    fn recheck_unknown_or_sessionless_agent_errors() {
+        use crate::infrastructure::mock::MockBackend;
+        use crate::monitor::cycle::MonitorCycle;
+
+        let mut sys = test_sys();
+        let backend = MockBackend::new();
+        let mut monitor = MonitorCycle::new(30_000, 300, r"\$\s*$".into());
+        assert!(is_err(&sys.recheck_agent("ghost", &mut monitor, &backend, 1)));
+
+        sys.execute(Command::AgentNew {
+            role: "worker".into(),
+            name: Some("w1".into()),
+            path: None,
+            agent_type: None,
+        });
+        assert!(is_err(&sys.recheck_agent("w1", &mut monitor, &backend, 1)));
+        assert!(is_err(&sys.execute(Command::AgentRecheck { name: "w1".into() })));
+    }
+
+    #[test]
+    fn stale_heartbeat_restart_resolved_is_recorded() {
+        use crate::types::health::HealthSignal;
+
+        let dir = std::env::temp_dir().join("cmx_sys_auto_intervention");
+        let _ = std::fs::remove_dir_all(&dir);
+        let _ = std::fs::create_dir_all(&dir);
+        let mut sys = Sys::from_data(Data::new(&dir).unwrap());
+        sys.execute(Command::AgentNew {
+            role: "worker".into(),
+            name: Some("w1".into()),
+            path: None,
+            agent_type: None,
+        });
+
+        // Monitor reports a stale heartbeat.
+        sys.apply_health_update(&assessment(
+            "w1",
+            HealthState::Unhealthy,
+            vec![HealthSignal::HeartbeatStale { age_secs: 600 }],
+            1_000,
+        ));
+        // Restart is emitted in response; a Pending event is opened.
+        sys.execute(Command::AgentRestart { name: "w1".into() });
+        let engine = DiagnosisEngine::new(dir.clone()).unwrap();
+        assert_eq!(engine.event_count(), 1);
+        assert_eq!(engine.events()[0].outcome, InterventionOutcome::Pending);
+        assert_eq!(engine.events()[0].signal, SignalType::HeartbeatStale);
+
+        // Unknown right after restart keeps the event open.
+        sys.apply_health_update(&assessment("w1", HealthState::Unknown, vec![], 2_000));
+        let engine = DiagnosisEngine::new(dir.clone()).unwrap();
+        assert_eq!(engine.events()[0].outcome, InterventionOutcome::Pending);
+
+        // Next healthy assessment resolves it.
+        sys.apply_health_update(&assessment(
+            "w1",
+            HealthState::Healthy,
+            vec![HealthSignal::HeartbeatRecent { age_secs: 5 }],
+            5_000,
+        ));
+        let engine = DiagnosisEngine::new(dir.clone()).unwrap();
+        assert_eq!(engine.event_count(), 1);
+        let event = &engine.events()[0];
+        assert_eq!(event.agent, "w1");
+        assert_eq!(event.action, InterventionAction::Restart);
+        assert_eq!(event.outcome, InterventionOutcome::Resolved);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn restart_of_healthy_agent_records_nothing() {
+        let dir = std::env::temp_dir().join("cmx_sys_auto_intervention_healthy");
+        let _ = std::fs::remove_dir_all(&dir);
+        let _ = std::fs::create_dir_all(&dir);
+        let mut sys = Sys::from_data(Data::new(&dir).unwrap());
+        sys.execute(Command::AgentNew {
+            role: "worker".into(),
+            name: Some("w1".into()),
+            path: None,
+            agent_type: None,
+        });
+        sys.execute(Command::AgentRestart { name: "w1".into() });
+        assert_eq!(DiagnosisEngine::new(dir.clone()).unwrap().event_count(), 0);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn interventions_in_detects_restart_and_retry() {
+        let actions = vec![
+            Action::KillAgent { name: "w1".into() },
+            Action::CreateAgent { name: "w1".into(), role: "worker".into(), path: ".".into() },
+            Action::SendKeys { target: "w2".into(), keys: "C-c".into() },
+            Action::SendKeys { target: "w2".into(), keys: "carry on".into() },
+            Action::CreateAgent { name: "w3".into(), role: "worker".into(), path: ".".into() },
+        ];
+        let found = interventions_in(&actions);
+        assert_eq!(found, vec![
+            ("w1".to_string(), InterventionAction::Restart),
+            ("w2".to_string(), InterventionAction::Retry),
+        ]);
+    }
+
     // --- notify_agent_ready ---
 
     #[test]
@@ -3600,6 +7252,109 @@ mod tests {
         assert!(skills.contains(&"hw-pm"), "expected hw-pm in {:?}", skills);
     }
 
+    #[test]
+    fn skill_validate_lists_invalid_skills() {
+        let project = std::env::temp_dir().join("cmx_sys_skill_validate");
+        let _ = std::fs::remove_dir_all(&project);
+        std::fs::create_dir_all(project.join("skills")).unwrap();
+        std::fs::write(
+            project.join("skills/deploy.md"),
+            "---\nname: deploy\ndescription: Ship it\n---\n\nDeploy.\n",
+        ).unwrap();
+        std::fs::write(
+            project.join("skills/rollback.md"),
+            "---\nname: undo\ndescription: Go back\n---\n\nRoll back.\n",
+        ).unwrap();
+        let mut sys = test_sys();
+        sys.execute(Command::ProjectAdd {
+            name: "validate".into(),
+            path: project.to_string_lossy().into(),
+        });
+
+        let r = sys.execute(Command::SkillValidate);
+        let text = match r {
+            Response::Error { message } => message,
+            other => panic!("expected error, got {:?}", other),
+        };
+        assert!(text.contains("rollback"), "got: {}", text);
+        assert!(text.contains("frontmatter name 'undo'"));
+        assert!(!text.lines().any(|l| l.starts_with("deploy ")));
+        let _ = std::fs::remove_dir_all(&project);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn skill_validate_reports_skipped_scan_dirs() {
+        let project = std::env::temp_dir().join("cmx_sys_skill_validate_skipped");
+        let _ = std::fs::remove_dir_all(&project);
+        std::fs::create_dir_all(project.join("skills/nested")).unwrap();
+        std::fs::write(
+            project.join("skills/deploy.md"),
+            "---\nname: deploy\ndescription: Ship it\n---\n\nDeploy.\n",
+        ).unwrap();
+        std::os::unix::fs::symlink(project.join("skills"), project.join("skills/nested/back"))
+            .unwrap();
+        let mut sys = test_sys();
+        sys.execute(Command::ProjectAdd {
+            name: "skipped".into(),
+            path: project.to_string_lossy().into(),
+        });
+
+        let r = sys.execute(Command::SkillValidate);
+        let text = output(&r);
+        assert!(text.contains("All 1 skills valid"), "got: {}", text);
+        assert!(text.lines().any(|l| l.starts_with("warning: skipped") && l.contains("symlink loop")),
+            "got: {}", text);
+        let _ = std::fs::remove_dir_all(&project);
+    }
+
+    #[test]
+    fn skill_validate_reports_duplicate_names_in_one_source() {
+        let project = std::env::temp_dir().join("cmx_sys_skill_validate_dup");
+        let _ = std::fs::remove_dir_all(&project);
+        std::fs::create_dir_all(project.join("skills/deploy")).unwrap();
+        let body = "---\nname: deploy\ndescription: Ship it\n---\n\nDeploy.\n";
+        std::fs::write(project.join("skills/deploy.md"), body).unwrap();
+        std::fs::write(project.join("skills/deploy/SKILL.md"), body).unwrap();
+        let mut sys = test_sys();
+        sys.execute(Command::ProjectAdd {
+            name: "dup".into(),
+            path: project.to_string_lossy().into(),
+        });
+
+        let r = sys.execute(Command::SkillValidate);
+        let text = output(&r);
+        assert!(text.lines().any(|l| l.starts_with("warning: duplicate skill 'deploy'")),
+            "got: {}", text);
+        let _ = std::fs::remove_dir_all(&project);
+    }
+
+    #[test]
+    fn view_suggests_close_skill_name() {
+        let project = std::env::temp_dir().join("cmx_sys_view_fuzzy");
+        let _ = std::fs::remove_dir_all(&project);
+        std::fs::create_dir_all(project.join("skills")).unwrap();
+        std::fs::write(
+            project.join("skills/deploy.md"),
+            "---\nname: deploy\ndescription: Ship it\n---\n\nDeploy.\n",
+        ).unwrap();
+        let mut sys = test_sys();
+        sys.execute(Command::ProjectAdd {
+            name: "fuzzy".into(),
+            path: project.to_string_lossy().into(),
+        });
+
+        let r = sys.execute(Command::View { name: "deploy".into() });
+        assert!(output(&r).contains("deploy.md"), "got: {:?}", r);
+
+        let r = sys.execute(Command::View { name: "deplyo".into() });
+        match r {
+            Response::Error { message } => assert!(message.contains("did you mean 'deploy'"), "{}", message),
+            other => panic!("expected error, got {:?}", other),
+        }
+        let _ = std::fs::remove_dir_all(&project);
+    }
+
     #[test]
     fn project_add_without_skills_no_error() {
         let mut sys = test_sys();
@@ -3662,6 +7417,8 @@ mod tests {
             status: None,
             result: None,
             agent: None,
+            due: None,
+            cascade: false,
         });
 
         sys.drain_actions(); // clear prior actions
@@ -3696,6 +7453,8 @@ mod tests {
             status: None,
             result: None,
             agent: None,
+            due: None,
+            cascade: false,
         });
 
         let r = sys.execute(Command::AgentAssign {
@@ -3728,6 +7487,8 @@ mod tests {
             status: None,
             result: None,
             agent: None,
+            due: None,
+            cascade: false,
         });
 
         sys.drain_actions();
@@ -3801,7 +7562,7 @@ mod tests {
             path: roadmap.to_str().unwrap().into(),
         });
 
-        sys.execute(Command::TaskCheck { id: "M1.1".into() });
+        sys.execute(Command::TaskCheck { id: "M1.1".into(), cascade: false });
 
         // Verify in-memory
         let task = sys.data().tasks().get("M1.1").unwrap();
@@ -3815,6 +7576,83 @@ mod tests {
         let _ = std::fs::remove_dir_all(&dir);
     }
 
+    #[test]
+    fn task_check_cascade_writes_back_every_changed_task() {
+        let dir = roadmap_test_dir("check_cascade");
+        let roadmap = dir.join("Roadmap.md");
+        std::fs::write(&roadmap, "\
+# \u{25EF} M1 \u{2014} Core
+## \u{25EF} M1.1 \u{2014} Socket
+## \u{25EF} M1.2 \u{2014} Health
+# \u{25EF} M2 \u{2014} Later
+").unwrap();
+
+        let mut sys = test_sys();
+        sys.execute(Command::RoadmapLoad {
+            path: roadmap.to_str().unwrap().into(),
+        });
+        sys.data.tasks_mut().get_mut("M1.2").unwrap().status = TaskStatus::Cancelled;
+
+        let r = sys.execute(Command::TaskCheck { id: "M1".into(), cascade: true });
+        assert!(is_ok(&r), "{}", output(&r));
+        assert!(output(&r).contains("2 tasks changed"));
+        let tasks = sys.data().tasks();
+        assert_eq!(tasks.get("M1.1").unwrap().status, TaskStatus::Completed);
+        assert_eq!(tasks.get("M1.2").unwrap().status, TaskStatus::Cancelled);
+        assert_eq!(tasks.get("M2").unwrap().status, TaskStatus::Pending);
+
+        let content = std::fs::read_to_string(&roadmap).unwrap();
+        assert!(content.contains("\u{2B24} M1 \u{2014} Core"), "got:\n{}", content);
+        assert!(content.contains("\u{2B24} M1.1"));
+        assert!(content.contains("\u{25EF} M1.2"));
+        assert!(content.contains("\u{25EF} M2"));
+
+        let r = sys.execute(Command::TaskSet {
+            id: "M2".into(),
+            status: None,
+            title: Some("Renamed".into()),
+            result: None,
+            agent: None,
+            due: None,
+            cascade: true,
+        });
+        assert!(is_err(&r));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn task_set_cascade_counts_like_task_check() {
+        let dir = roadmap_test_dir("set_cascade");
+        let roadmap = dir.join("Roadmap.md");
+        std::fs::write(&roadmap, "\
+# \u{25EF} M1 \u{2014} Core
+## \u{25EF} M1.1 \u{2014} Socket
+## \u{25EF} M1.2 \u{2014} Health
+").unwrap();
+
+        let mut sys = test_sys();
+        sys.execute(Command::RoadmapLoad {
+            path: roadmap.to_str().unwrap().into(),
+        });
+        sys.data.tasks_mut().get_mut("M1.2").unwrap().status = TaskStatus::Cancelled;
+
+        let r = sys.execute(Command::TaskSet {
+            id: "M1".into(),
+            status: Some("completed".into()),
+            title: None,
+            result: None,
+            agent: None,
+            due: None,
+            cascade: true,
+        });
+        assert_eq!(output(&r), "Task 'M1' updated (2 tasks changed status)");
+        let tasks = sys.data().tasks();
+        assert_eq!(tasks.get("M1").unwrap().status, TaskStatus::Completed);
+        assert_eq!(tasks.get("M1.1").unwrap().status, TaskStatus::Completed);
+        assert_eq!(tasks.get("M1.2").unwrap().status, TaskStatus::Cancelled);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn task_uncheck_writes_back_to_roadmap_file() {
         let dir = roadmap_test_dir("uncheck_writeback");
@@ -3854,6 +7692,8 @@ mod tests {
             title: None,
             result: None,
             agent: None,
+            due: None,
+            cascade: false,
         });
 
         let content = std::fs::read_to_string(&roadmap).unwrap();
@@ -3878,6 +7718,8 @@ mod tests {
             title: Some("Updated Core".into()),
             result: None,
             agent: None,
+            due: None,
+            cascade: false,
         });
 
         let content = std::fs::read_to_string(&roadmap).unwrap();
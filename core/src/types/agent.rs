@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 #[serde(rename_all = "snake_case")]
 pub enum AgentType {
     Claude,
@@ -8,6 +8,29 @@ pub enum AgentType {
     Ssh,
 }
 
+impl AgentType {
+    /// Parse an agent type name (`claude`, `console` or `ssh`, case-insensitive).
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.trim().to_lowercase().as_str() {
+            "claude" => Ok(AgentType::Claude),
+            "console" => Ok(AgentType::Console),
+            "ssh" => Ok(AgentType::Ssh),
+            _ => Err(format!(
+                "unknown agent type: {} (expected claude, console or ssh)",
+                value
+            )),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AgentType::Claude => "claude",
+            AgentType::Console => "console",
+            AgentType::Ssh => "ssh",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum AgentStatus {
@@ -39,6 +62,13 @@ pub struct Agent {
     pub health: HealthState,
     pub last_heartbeat_ms: Option<u64>,
     pub session: Option<String>,
+    /// When the agent was created (ms since epoch). `None` for agents
+    /// persisted before this field existed.
+    #[serde(default)]
+    pub created_at_ms: Option<u64>,
+    /// When the agent was assigned its current task (ms since epoch).
+    #[serde(default)]
+    pub assigned_at_ms: Option<u64>,
 }
 
 #[cfg(test)]
@@ -58,6 +88,8 @@ mod tests {
             health: HealthState::Healthy,
             last_heartbeat_ms: Some(1700000000000),
             session: Some("cmx-main".into()),
+            created_at_ms: None,
+            assigned_at_ms: None,
         };
         let json = serde_json::to_string(&agent).unwrap();
         let back: Agent = serde_json::from_str(&json).unwrap();
@@ -67,9 +99,26 @@ mod tests {
         assert_eq!(back.health, HealthState::Healthy);
     }
 
+    #[test]
+    fn agent_without_timestamps_deserializes() {
+        let json = r#"{"name":"w1","role":"worker","agent_type":"claude","task":null,
+            "path":"/tmp","status":"idle","status_notes":"","health":"unknown",
+            "last_heartbeat_ms":null,"session":null}"#;
+        let agent: Agent = serde_json::from_str(json).unwrap();
+        assert_eq!(agent.created_at_ms, None);
+        assert_eq!(agent.assigned_at_ms, None);
+    }
+
     #[test]
     fn health_state_serde() {
         let json = serde_json::to_string(&HealthState::Degraded).unwrap();
         assert_eq!(json, "\"degraded\"");
     }
+
+    #[test]
+    fn agent_type_parse_and_as_str() {
+        assert_eq!(AgentType::parse("SSH").unwrap(), AgentType::Ssh);
+        assert_eq!(AgentType::parse("console").unwrap().as_str(), "console");
+        assert!(AgentType::parse("robot").is_err());
+    }
 }
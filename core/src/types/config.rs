@@ -2,6 +2,8 @@ use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
 
+use crate::library::ConflictStrategy;
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum BackoffStrategy {
@@ -10,12 +12,41 @@ pub enum BackoffStrategy {
     Fixed,
 }
 
+/// Terminal multiplexer that hosts agent sessions.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionBackendKind {
+    #[default]
+    Tmux,
+    Zellij,
+}
+
+impl SessionBackendKind {
+    /// Parse a setting value (`tmux` or `zellij`, case-insensitive).
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.trim().to_lowercase().as_str() {
+            "tmux" => Ok(SessionBackendKind::Tmux),
+            "zellij" => Ok(SessionBackendKind::Zellij),
+            _ => Err(format!("unknown session_backend: {} (expected tmux or zellij)", value)),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SessionBackendKind::Tmux => "tmux",
+            SessionBackendKind::Zellij => "zellij",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct PoolConfigYaml {
     pub size: u32,
     pub path: String,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub max_size: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_idle: Option<u32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -38,12 +69,88 @@ pub struct Settings {
     pub pool_auto_expand: bool,
     #[serde(default = "default_launch_command")]
     pub agent_launch_command: String,
+    /// Snapshot settings into `history/settings/` after config-mutating commands.
+    #[serde(default)]
+    pub auto_snapshot_on_change: bool,
+    /// Substring identifying heartbeat lines in pane output. Blank means
+    /// the built-in default marker.
+    #[serde(default)]
+    pub heartbeat_marker: String,
+    /// Per-role heartbeat markers, overriding `heartbeat_marker`.
+    #[serde(default)]
+    pub heartbeat_marker_overrides: HashMap<String, String>,
+    /// Per-agent-type prompt regexes (`claude`, `console`, `ssh`) for
+    /// heartbeat parsing. Types without one use the built-in detection.
+    #[serde(default)]
+    pub heartbeat_patterns: HashMap<String, String>,
+    /// Consecutive Unhealthy assessments before an agent is marked Stalled.
+    #[serde(default = "default_stall_after_unhealthy")]
+    pub stall_after_unhealthy: u32,
+    /// Consecutive Healthy assessments before a Stalled agent is cleared.
+    #[serde(default = "default_clear_after_healthy")]
+    pub clear_after_healthy: u32,
+    /// Minimum delay between message deliveries to one agent (0 = unpaced).
+    #[serde(default)]
+    pub message_interval: u64,
+    /// Keys sent to an agent by a graceful `agent.kill` before it is
+    /// killed. Blank skips the keys, making graceful kills hard kills.
+    #[serde(default = "default_shutdown_keys")]
+    pub shutdown_keys: String,
+    /// How long a graceful `agent.kill` waits after the shutdown keys
+    /// before killing the agent.
+    #[serde(default = "default_shutdown_grace_ms")]
+    pub shutdown_grace_ms: u64,
+    /// Multiplexer used for agent sessions.
+    #[serde(default)]
+    pub session_backend: SessionBackendKind,
+    /// How the skill library picks among sources defining the same skill
+    /// when no override applies.
+    #[serde(default)]
+    pub skill_conflict_strategy: ConflictStrategy,
+}
+
+impl Settings {
+    /// Heartbeat marker for agents with `role`: the role override if set,
+    /// else the global marker. May be blank; the heartbeat parser treats a
+    /// blank marker as the default.
+    pub fn heartbeat_marker_for(&self, role: &str) -> &str {
+        resolve_heartbeat_marker(&self.heartbeat_marker, &self.heartbeat_marker_overrides, role)
+    }
+}
+
+/// The marker for `role`: its non-blank override, else `global`. The one
+/// resolution rule shared by `Settings` and the monitor.
+pub fn resolve_heartbeat_marker<'a>(
+    global: &'a str,
+    overrides: &'a HashMap<String, String>,
+    role: &str,
+) -> &'a str {
+    match overrides.get(role) {
+        Some(m) if !m.trim().is_empty() => m,
+        _ => global,
+    }
 }
 
 fn default_launch_command() -> String {
     "claude".to_string()
 }
 
+pub(crate) fn default_shutdown_keys() -> String {
+    "/exit".to_string()
+}
+
+pub(crate) fn default_shutdown_grace_ms() -> u64 {
+    5000
+}
+
+pub(crate) fn default_stall_after_unhealthy() -> u32 {
+    3
+}
+
+pub(crate) fn default_clear_after_healthy() -> u32 {
+    2
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct FolderEntry {
     pub name: String,
@@ -73,12 +180,34 @@ mod tests {
             pool_configs: HashMap::new(),
             pool_auto_expand: false,
             agent_launch_command: "claude".into(),
+            auto_snapshot_on_change: false,
+            heartbeat_marker: "HEARTBEAT".into(),
+            heartbeat_marker_overrides: HashMap::new(),
+            heartbeat_patterns: HashMap::new(),
+            stall_after_unhealthy: 3,
+            clear_after_healthy: 2,
+            message_interval: 500,
+            shutdown_keys: "/exit".into(),
+            shutdown_grace_ms: 5000,
+            session_backend: SessionBackendKind::Zellij,
+            skill_conflict_strategy: ConflictStrategy::NewestMtime,
         };
         let json = serde_json::to_string(&settings).unwrap();
         let back: Settings = serde_json::from_str(&json).unwrap();
         assert_eq!(back, settings);
     }
 
+    #[test]
+    fn settings_without_hysteresis_fields_use_defaults() {
+        let mut value = serde_json::to_value(crate::data::settings::default_settings()).unwrap();
+        let obj = value.as_object_mut().unwrap();
+        obj.remove("stall_after_unhealthy");
+        obj.remove("clear_after_healthy");
+        let back: Settings = serde_json::from_value(value).unwrap();
+        assert_eq!(back.stall_after_unhealthy, 3);
+        assert_eq!(back.clear_after_healthy, 2);
+    }
+
     #[test]
     fn backoff_strategy_serde() {
         let json = serde_json::to_string(&BackoffStrategy::Exponential).unwrap();
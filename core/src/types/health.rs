@@ -13,6 +13,8 @@ pub enum HealthSignal {
     ExplicitError { message: String },
     SshConnected,
     SshDisconnected,
+    /// A health rule fired and named this signal.
+    TriggerFired { name: String },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
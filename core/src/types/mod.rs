@@ -5,3 +5,11 @@ pub mod message;
 pub mod tiles;
 pub mod session;
 pub mod task;
+
+/// Version of the structured (JSON) output schema shared by `status --json`,
+/// `agent list --json`, and `SystemSnapshot`.
+///
+/// Compatibility contract: adding fields is non-breaking and does not bump
+/// the version, so clients must ignore unknown fields. Removing or renaming
+/// a field, or changing its type or meaning, is breaking and bumps this.
+pub const SCHEMA_VERSION: u32 = 1;
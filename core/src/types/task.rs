@@ -19,7 +19,7 @@ pub enum TaskSource {
     Both,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct TaskNode {
     pub id: String,
     pub title: String,
@@ -29,6 +29,25 @@ pub struct TaskNode {
     pub agent: Option<String>,
     pub children: Vec<TaskNode>,
     pub spec_path: Option<String>,
+    /// Ids of tasks that must complete before this one can start.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<String>,
+    /// Operator notes as `(timestamp_ms, text)`, oldest first. Kept with
+    /// task state only; never written to the roadmap file.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notes: Vec<(u64, String)>,
+    /// Deadline as epoch ms. Incomplete tasks past it are overdue.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub due_at_ms: Option<u64>,
+}
+
+impl TaskNode {
+    /// True when the task has a deadline before `now_ms` and is neither
+    /// completed nor cancelled.
+    pub fn is_overdue(&self, now_ms: u64) -> bool {
+        let done = matches!(self.status, TaskStatus::Completed | TaskStatus::Cancelled);
+        !done && self.due_at_ms.is_some_and(|due| due < now_ms)
+    }
 }
 
 #[cfg(test)]
@@ -53,8 +72,14 @@ mod tests {
                 agent: None,
                 children: vec![],
                 spec_path: Some("/tasks/CMX1A/CMX1A.md".into()),
+                depends_on: Vec::new(),
+                notes: Vec::new(),
+                due_at_ms: None,
             }],
             spec_path: Some("/tasks/CMX1/CMX1.md".into()),
+            depends_on: Vec::new(),
+            notes: Vec::new(),
+            due_at_ms: None,
         };
         let json = serde_json::to_string(&task).unwrap();
         let back: TaskNode = serde_json::from_str(&json).unwrap();
@@ -163,6 +163,8 @@ impl MuxClient {
     pub fn agent_list_json(&mut self) -> Result<String, String> {
         let cmd = Command::AgentList {
             format: Some("json".to_string()),
+            sort_by: None,
+            columns: None,
         };
         let resp = self.send(&cmd)?;
         match resp {
@@ -176,6 +178,9 @@ impl MuxClient {
         let cmd = Command::TaskList {
             format: Some("json".to_string()),
             project: None,
+            max_depth: None,
+            status: None,
+            agent: None,
         };
         let resp = self.send(&cmd)?;
         match resp {
@@ -200,6 +205,7 @@ impl MuxClient {
     pub fn help(&mut self, topic: Option<&str>) -> Result<String, String> {
         let cmd = Command::Help {
             topic: topic.map(|s| s.to_string()),
+            search: None,
         };
         let resp = self.send(&cmd)?;
         match resp {
@@ -414,7 +420,7 @@ mod tests {
     fn batch_add_commands() {
         let mut batch = CommandBatch::new();
         batch.add(Command::Status { format: None });
-        batch.add(Command::AgentList { format: None });
+        batch.add(Command::AgentList { format: None, sort_by: None, columns: None });
         assert_eq!(batch.len(), 2);
         assert!(!batch.is_empty());
     }
@@ -424,7 +430,7 @@ mod tests {
         let mut client = MuxClient::new(PathBuf::from("/tmp/no.sock"));
         let mut batch = CommandBatch::new();
         batch.add(Command::Status { format: None });
-        batch.add(Command::AgentList { format: None });
+        batch.add(Command::AgentList { format: None, sort_by: None, columns: None });
 
         let results = batch.execute(&mut client);
         assert_eq!(results.len(), 2);
@@ -487,6 +493,8 @@ mod tests {
         // Verify the commands we build serialize correctly
         let cmd = Command::AgentList {
             format: Some("json".into()),
+            sort_by: None,
+            columns: None,
         };
         let json = serde_json::to_string(&cmd).unwrap();
         assert!(json.contains("\"command\":\"agent.list\""));
@@ -504,6 +512,7 @@ mod tests {
     fn command_help_serialization() {
         let cmd = Command::Help {
             topic: Some("agent".into()),
+            search: None,
         };
         let json = serde_json::to_string(&cmd).unwrap();
         assert!(json.contains("\"command\":\"help\""));
@@ -512,7 +521,7 @@ mod tests {
 
     #[test]
     fn command_help_no_topic_serialization() {
-        let cmd = Command::Help { topic: None };
+        let cmd = Command::Help { topic: None, search: None };
         let json = serde_json::to_string(&cmd).unwrap();
         assert!(json.contains("\"command\":\"help\""));
         assert!(!json.contains("topic"));
@@ -4,6 +4,12 @@
 //! suggest completions for partial input. Supports both static completions
 //! (command names, fixed argument values) and dynamic completion types
 //! that can be resolved at runtime.
+//!
+//! Candidates are ranked by a [`UsageFreq`] counter of previously executed
+//! commands, most-used first, with alphabetical order as the tiebreak.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
 /// Specifies how arguments for a command can be completed.
 #[derive(Debug, Clone)]
@@ -50,9 +56,106 @@ pub struct CompletionResult {
 }
 
 
+/// File name of the usage counts, relative to the config directory.
+pub const COMPLETION_FREQ_FILE: &str = "completion_freq";
+
+
+/// Decayed per-command usage counts.
+///
+/// Each recorded use first multiplies every count by [`UsageFreq::DECAY`],
+/// then adds one to the used command, so habits that stop fade away.
+/// Counts are capped at [`UsageFreq::MAX_COUNT`] and forgotten once they
+/// decay below [`UsageFreq::MIN_COUNT`]. Keys are a top-level word
+/// (`agent`) or a dotted subcommand (`agent.list`).
+#[derive(Debug, Clone, Default)]
+pub struct UsageFreq {
+    counts: HashMap<String, f64>,
+    path: Option<PathBuf>,
+}
+
+impl UsageFreq {
+    /// Multiplier applied to all counts on each recorded use.
+    pub const DECAY: f64 = 0.95;
+    /// Upper bound for a single count.
+    pub const MAX_COUNT: f64 = 50.0;
+    /// Counts below this are dropped.
+    pub const MIN_COUNT: f64 = 0.05;
+
+    /// Empty counter that is never persisted.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load counts from `path`, one `<key> <count>` per line. A missing or
+    /// unreadable file yields an empty counter; malformed lines are skipped.
+    /// Later `save` calls write back to the same path.
+    pub fn load(path: &Path) -> Self {
+        let mut counts = HashMap::new();
+        if let Ok(text) = std::fs::read_to_string(path) {
+            for line in text.lines() {
+                let mut fields = line.split_whitespace();
+                if let (Some(key), Some(count)) = (fields.next(), fields.next()) {
+                    if let Ok(n) = count.parse::<f64>() {
+                        counts.insert(key.to_string(), n.min(Self::MAX_COUNT));
+                    }
+                }
+            }
+        }
+        UsageFreq {
+            counts,
+            path: Some(path.to_path_buf()),
+        }
+    }
+
+    /// Write counts back to the path given to `load`. No-op otherwise.
+    pub fn save(&self) -> std::io::Result<()> {
+        let path = match &self.path {
+            Some(p) => p,
+            None => return Ok(()),
+        };
+        let mut keys: Vec<&String> = self.counts.keys().collect();
+        keys.sort();
+        let mut out = String::new();
+        for key in keys {
+            out.push_str(&format!("{} {:.3}\n", key, self.counts[key]));
+        }
+        std::fs::write(path, out)
+    }
+
+    /// Record one use of each key.
+    pub fn record(&mut self, keys: &[String]) {
+        for count in self.counts.values_mut() {
+            *count *= Self::DECAY;
+        }
+        self.counts.retain(|_, c| *c >= Self::MIN_COUNT);
+        for key in keys {
+            let count = self.counts.entry(key.clone()).or_insert(0.0);
+            *count = (*count + 1.0).min(Self::MAX_COUNT);
+        }
+    }
+
+    /// Current score for a key (0 if never used).
+    pub fn score(&self, key: &str) -> f64 {
+        self.counts.get(key).copied().unwrap_or(0.0)
+    }
+
+    /// Sort candidates by descending score, then alphabetically. `key`
+    /// maps a candidate to its counter key.
+    fn rank<F: Fn(&str) -> String>(&self, candidates: &mut [String], key: F) {
+        candidates.sort_by(|a, b| {
+            self.score(&key(b))
+                .partial_cmp(&self.score(&key(a)))
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.cmp(b))
+        });
+    }
+}
+
+
 /// Tab completer for CMX commands.
 pub struct Completer {
     commands: Vec<CompletionEntry>,
+    freq: UsageFreq,
 }
 
 
@@ -61,6 +164,7 @@ impl Completer {
     pub fn new() -> Self {
         Completer {
             commands: Vec::new(),
+            freq: UsageFreq::new(),
         }
     }
 
@@ -78,6 +182,41 @@ impl Completer {
         self.commands.push(entry);
     }
 
+    /// Replace the usage counter used to rank candidates.
+    pub fn set_frequency(&mut self, freq: UsageFreq) {
+        self.freq = freq;
+    }
+
+    /// The usage counter used to rank candidates.
+    pub fn frequency(&self) -> &UsageFreq {
+        &self.freq
+    }
+
+    /// Count an executed command line toward completion ranking and
+    /// persist the counter. Accepts both `agent list` and `agent.list`.
+    pub fn record_use(&mut self, text: &str) -> std::io::Result<()> {
+        let mut words: Vec<&str> = Vec::new();
+        for part in text.split_whitespace().take(2) {
+            words.extend(part.split('.').filter(|w| !w.is_empty()));
+        }
+        let first = match words.first() {
+            Some(w) if self.commands.iter().any(|e| e.prefix[0] == *w) => *w,
+            _ => return Ok(()),
+        };
+        let mut keys = vec![first.to_string()];
+        if let Some(second) = words.get(1) {
+            if self
+                .commands
+                .iter()
+                .any(|e| e.prefix.len() >= 2 && e.prefix[0] == first && e.prefix[1] == *second)
+            {
+                keys.push(format!("{}.{}", first, second));
+            }
+        }
+        self.freq.record(&keys);
+        self.freq.save()
+    }
+
     /// Attempt completion at the given cursor position in the input string.
     ///
     /// Returns a [`CompletionResult`] with matching candidates and their
@@ -213,6 +352,7 @@ impl Completer {
 
         candidates.sort();
         candidates.dedup();
+        self.freq.rank(&mut candidates, |c| c.to_string());
 
         let common = longest_common_prefix(&candidates);
         let complete = candidates.len() == 1 && common == candidates[0];
@@ -243,6 +383,7 @@ impl Completer {
 
         candidates.sort();
         candidates.dedup();
+        self.freq.rank(&mut candidates, |c| format!("{}.{}", first, c));
 
         let common = longest_common_prefix(&candidates);
         let complete = candidates.len() == 1 && common == candidates[0];
@@ -271,6 +412,7 @@ impl Completer {
             .collect();
         words.sort();
         words.dedup();
+        self.freq.rank(&mut words, |w| w.to_string());
         words
     }
 }
@@ -1041,4 +1183,72 @@ mod tests {
             );
         }
     }
+
+    // --- usage frequency ranking ---
+
+    #[test]
+    fn frequent_command_ranks_above_alphabetical() {
+        let mut c = make_completer();
+        for _ in 0..3 {
+            c.record_use("tell w1 hello").unwrap();
+        }
+        c.record_use("task list").unwrap();
+        let result = c.complete("t", 1);
+        assert_eq!(result.candidates[0], "tell");
+        assert_eq!(result.candidates[1], "task");
+        assert_eq!(result.common_prefix, "t");
+    }
+
+    #[test]
+    fn frequent_subcommand_ranks_first() {
+        let mut c = make_completer();
+        c.record_use("agent.list").unwrap();
+        c.record_use("agent list --json").unwrap();
+        let result = c.complete("agent ", 6);
+        assert_eq!(result.candidates[0], "list");
+        // Unused subcommands keep alphabetical order.
+        let rest: Vec<String> = result.candidates[1..].to_vec();
+        let mut sorted = rest.clone();
+        sorted.sort();
+        assert_eq!(rest, sorted);
+    }
+
+    #[test]
+    fn unused_counts_decay_and_fade() {
+        let mut freq = UsageFreq::new();
+        freq.record(&["old".to_string()]);
+        for _ in 0..10 {
+            freq.record(&["new".to_string()]);
+        }
+        assert!(freq.score("old") < 1.0);
+        assert!(freq.score("new") > freq.score("old"));
+        for _ in 0..100 {
+            freq.record(&["new".to_string()]);
+        }
+        assert_eq!(freq.score("old"), 0.0);
+        assert!(freq.score("new") <= UsageFreq::MAX_COUNT);
+    }
+
+    #[test]
+    fn unknown_commands_are_not_counted() {
+        let mut c = make_completer();
+        c.record_use("bogus thing").unwrap();
+        assert_eq!(c.frequency().score("bogus"), 0.0);
+    }
+
+    #[test]
+    fn frequency_persists_round_trip() {
+        let dir = std::env::temp_dir().join(format!("cmx_completion_freq_{}", std::process::id()));
+        let _ = std::fs::create_dir_all(&dir);
+        let path = dir.join(COMPLETION_FREQ_FILE);
+        let _ = std::fs::remove_file(&path);
+
+        let mut c = make_completer();
+        c.set_frequency(UsageFreq::load(&path));
+        c.record_use("status").unwrap();
+
+        let loaded = UsageFreq::load(&path);
+        assert!((loaded.score("status") - 1.0).abs() < 0.01);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
 }
@@ -166,6 +166,8 @@ mod tests {
             health,
             last_heartbeat_ms: Some(1000),
             session: Some("main".into()),
+            created_at_ms: None,
+            assigned_at_ms: None,
         }
     }
 
@@ -4,6 +4,10 @@
 //! This module provides the building blocks that [`crate::status`] uses to
 //! compose full status displays.
 
+use skill_docket_core::history::browse::{ordered_diff, DiffOp};
+
+use crate::theme::{Color, Theme};
+
 // ---------------------------------------------------------------------------
 // ANSI escape constants
 // ---------------------------------------------------------------------------
@@ -502,26 +506,39 @@ impl Panel {
 // Sparkline
 // ---------------------------------------------------------------------------
 
-/// Render a sparkline from a series of values.
+/// Block characters from lowest to highest.
+const SPARK_CHARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Render `values` as one block character each, scaled so the smallest
+/// value maps to `▁` and the largest to `█`.
+///
+/// An empty slice renders as an empty string. A single value or a series
+/// of equal values has no range to scale, so it renders as a flat line of
+/// mid-height blocks.
+pub fn spark_blocks(values: &[f64]) -> String {
+    let min = values.iter().cloned().fold(f64::INFINITY, f64::min);
+    let max = values.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    let range = max - min;
+    let top = (SPARK_CHARS.len() - 1) as f64;
+    values
+        .iter()
+        .map(|&v| {
+            let normalized = if range > 0.0 { (v - min) / range } else { 0.5 };
+            let idx = (normalized * top).round() as usize;
+            SPARK_CHARS[idx.min(SPARK_CHARS.len() - 1)]
+        })
+        .collect()
+}
+
+/// Render a sparkline from a series of values, fitted to `width` columns.
 ///
 /// The sparkline uses Unicode block characters to show a compact inline chart.
-/// Values are scaled to the range of the data.
+/// Values are scaled to the range of the data; see [`spark_blocks`].
 pub fn sparkline(values: &[f64], width: usize) -> String {
     if values.is_empty() || width == 0 {
         return String::new();
     }
 
-    let spark_chars = [
-        '\u{2581}', // ▁
-        '\u{2582}', // ▂
-        '\u{2583}', // ▃
-        '\u{2584}', // ▄
-        '\u{2585}', // ▅
-        '\u{2586}', // ▆
-        '\u{2587}', // ▇
-        '\u{2588}', // █
-    ];
-
     // Sample or repeat values to fill width
     let data: Vec<f64> = if values.len() >= width {
         // Take the last `width` values
@@ -533,22 +550,65 @@ pub fn sparkline(values: &[f64], width: usize) -> String {
         d
     };
 
-    let min = data.iter().cloned().fold(f64::INFINITY, f64::min);
-    let max = data.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
-    let range = max - min;
+    spark_blocks(&data)
+}
 
-    let mut out = String::with_capacity(width);
-    for &v in &data {
-        let normalized = if range == 0.0 {
-            0.5
-        } else {
-            (v - min) / range
-        };
-        let idx = (normalized * 7.0).round() as usize;
-        let idx = idx.min(7);
-        out.push(spark_chars[idx]);
+
+// ---------------------------------------------------------------------------
+// Side-by-side diff
+// ---------------------------------------------------------------------------
+
+/// Render a two-column diff: old text on the left, new on the right.
+///
+/// Unchanged lines appear on both sides. A run of removals followed by a
+/// run of additions is paired row by row as modifications; any surplus
+/// shows against a blank cell, so an insertion leaves the left side empty
+/// and a deletion leaves the right side empty. Each cell starts with a
+/// `-`/`+` gutter colored from the theme and is truncated to fit its half
+/// of `width`.
+pub fn side_by_side_diff(old_lines: &[&str], new_lines: &[&str], width: usize, theme: &Theme) -> String {
+    let col = width.saturating_sub(3) / 2;
+    let text_width = col.saturating_sub(2);
+    let cell = |gutter: char, text: &str, color: Option<&Color>| -> String {
+        let body = format!("{} {}", gutter, pad_right(text, text_width));
+        match color {
+            Some(c) => format!("{}{}{}", c.ansi_fg(), body, RESET),
+            None => body,
+        }
+    };
+    let blank = || " ".repeat(col);
+    let sep = format!(" {} ", BOX_V);
+
+    let mut rows = Vec::new();
+    let ops = ordered_diff(old_lines, new_lines);
+    let mut i = 0;
+    while i < ops.len() {
+        if let DiffOp::Same(line) = &ops[i] {
+            rows.push(format!("{}{}{}", cell(' ', line, None), sep, cell(' ', line, None)));
+            i += 1;
+            continue;
+        }
+        let mut removed = Vec::new();
+        while let Some(DiffOp::Removed(line)) = ops.get(i) {
+            removed.push(line.as_str());
+            i += 1;
+        }
+        let mut added = Vec::new();
+        while let Some(DiffOp::Added(line)) = ops.get(i) {
+            added.push(line.as_str());
+            i += 1;
+        }
+        for k in 0..removed.len().max(added.len()) {
+            let left = removed
+                .get(k)
+                .map_or_else(blank, |l| cell('-', l, Some(&theme.error)));
+            let right = added
+                .get(k)
+                .map_or_else(blank, |l| cell('+', l, Some(&theme.task_done)));
+            rows.push(format!("{}{}{}", left, sep, right));
+        }
     }
-    out
+    rows.join("\n")
 }
 
 
@@ -914,6 +974,25 @@ mod tests {
 
     // --- sparkline ---
 
+    #[test]
+    fn spark_blocks_scale_to_data_range() {
+        assert_eq!(spark_blocks(&[0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0]), "▁▂▃▄▅▆▇█");
+        // Only the range matters, not the absolute values.
+        assert_eq!(spark_blocks(&[10.0, 20.0, 15.0]), "▁█▅");
+        assert_eq!(spark_blocks(&[0.9, 0.1]), "█▁");
+    }
+
+    #[test]
+    fn spark_blocks_empty_is_empty() {
+        assert_eq!(spark_blocks(&[]), "");
+    }
+
+    #[test]
+    fn spark_blocks_all_equal_is_flat() {
+        assert_eq!(spark_blocks(&[3.0, 3.0, 3.0]), "▅▅▅");
+        assert_eq!(spark_blocks(&[42.0]), "▅");
+    }
+
     #[test]
     fn sparkline_constant_values() {
         let sl = sparkline(&[5.0, 5.0, 5.0, 5.0], 4);
@@ -1030,4 +1109,52 @@ mod tests {
         assert_eq!(BOX_R, '\u{2524}');
         assert_eq!(BOX_X, '\u{253C}');
     }
+
+    // --- side_by_side_diff ---
+
+    fn plain_rows(old: &[&str], new: &[&str]) -> Vec<String> {
+        side_by_side_diff(old, new, 23, &Theme::default_dark())
+            .lines()
+            .map(strip_ansi)
+            .collect()
+    }
+
+    #[test]
+    fn side_by_side_insertion_leaves_left_blank() {
+        let rows = plain_rows(&["a", "c"], &["a", "b", "c"]);
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0], "  a        \u{2502}   a       ");
+        assert_eq!(rows[1], "           \u{2502} + b       ");
+        assert_eq!(rows[2], "  c        \u{2502}   c       ");
+    }
+
+    #[test]
+    fn side_by_side_deletion_leaves_right_blank() {
+        let rows = plain_rows(&["a", "b", "c"], &["a", "c"]);
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[1], "- b        \u{2502}           ");
+    }
+
+    #[test]
+    fn side_by_side_modification_pairs_on_one_row() {
+        let rows = plain_rows(&["a", "old", "c"], &["a", "new", "c"]);
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[1], "- old      \u{2502} + new     ");
+        assert!(rows.iter().all(|r| r.chars().count() == 23));
+    }
+
+    #[test]
+    fn side_by_side_truncates_long_lines() {
+        let rows = plain_rows(&["a very long old line"], &["short"]);
+        assert_eq!(rows[0].chars().count(), 23);
+        assert!(rows[0].starts_with("- a very \u{2026}"));
+    }
+
+    #[test]
+    fn side_by_side_colors_gutters() {
+        let theme = Theme::default_dark();
+        let out = side_by_side_diff(&["x"], &["y"], 23, &theme);
+        assert!(out.contains(&theme.error.ansi_fg()));
+        assert!(out.contains(&theme.task_done.ansi_fg()));
+    }
 }
@@ -440,6 +440,8 @@ mod tests {
                 health: HealthState::Healthy,
                 last_heartbeat_ms: Some(1700000000000),
                 session: Some("cmx-main".into()),
+                created_at_ms: None,
+                assigned_at_ms: None,
             },
             Agent {
                 name: "worker-1".into(),
@@ -452,6 +454,8 @@ mod tests {
                 health: HealthState::Healthy,
                 last_heartbeat_ms: Some(1700000000000),
                 session: Some("cmx-main".into()),
+                created_at_ms: None,
+                assigned_at_ms: None,
             },
             Agent {
                 name: "worker-2".into(),
@@ -464,6 +468,8 @@ mod tests {
                 health: HealthState::Unknown,
                 last_heartbeat_ms: None,
                 session: None,
+                created_at_ms: None,
+                assigned_at_ms: None,
             },
             Agent {
                 name: "worker-3".into(),
@@ -476,6 +482,8 @@ mod tests {
                 health: HealthState::Unhealthy,
                 last_heartbeat_ms: Some(1699999990000),
                 session: Some("cmx-main".into()),
+                created_at_ms: None,
+                assigned_at_ms: None,
             },
         ]
     }
@@ -499,6 +507,9 @@ mod tests {
                         agent: Some("worker-1".into()),
                         children: vec![],
                         spec_path: Some("/tasks/CMX1A/CMX1A.md".into()),
+                        depends_on: Vec::new(),
+                        notes: Vec::new(),
+                        due_at_ms: None,
                     },
                     TaskNode {
                         id: "CMX1B".into(),
@@ -509,9 +520,15 @@ mod tests {
                         agent: None,
                         children: vec![],
                         spec_path: Some("/tasks/CMX1B/CMX1B.md".into()),
+                        depends_on: Vec::new(),
+                        notes: Vec::new(),
+                        due_at_ms: None,
                     },
                 ],
                 spec_path: Some("/tasks/CMX1/CMX1.md".into()),
+                depends_on: Vec::new(),
+                notes: Vec::new(),
+                due_at_ms: None,
             },
             TaskNode {
                 id: "CMX2".into(),
@@ -522,6 +539,9 @@ mod tests {
                 agent: Some("worker-3".into()),
                 children: vec![],
                 spec_path: None,
+                depends_on: Vec::new(),
+                notes: Vec::new(),
+                due_at_ms: None,
             },
         ]
     }
@@ -6,7 +6,7 @@
 //! periodically refresh data from the daemon.
 
 use std::io;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
 
 use crossterm::event::{self, Event, KeyCode, KeyModifiers};
@@ -19,6 +19,7 @@ use ratatui::Terminal;
 use crate::agent_view;
 use crate::app::{App, AppAction, AppState, Key};
 use crate::client::MuxClient;
+use crate::completion::{UsageFreq, COMPLETION_FREQ_FILE};
 use crate::dashboard;
 use crate::notification::{NotificationCenter, NotificationType};
 
@@ -71,6 +72,13 @@ impl Tui {
         let backend = CrosstermBackend::new(stdout);
         let terminal = Terminal::new(backend)?;
 
+        // Completion usage counts live next to the daemon socket.
+        let mut app = App::new();
+        if let Some(dir) = socket_path.as_deref().and_then(|p| Path::new(p).parent()) {
+            app.completer
+                .set_frequency(UsageFreq::load(&dir.join(COMPLETION_FREQ_FILE)));
+        }
+
         let client = socket_path.and_then(|path| {
             let mut c = MuxClient::new(PathBuf::from(&path));
             c.connect().ok().map(|_| c)
@@ -78,7 +86,7 @@ impl Tui {
 
         Ok(Self {
             terminal,
-            app: App::new(),
+            app,
             client,
             tick_rate: Duration::from_millis(250),
             last_refresh: Instant::now(),
@@ -155,6 +163,7 @@ impl Tui {
                     .unwrap_or_default()
                     .as_millis() as u64;
 
+                let _ = self.app.completer.record_use(&cmd_text);
                 let parsed = parse_command_text(&cmd_text);
                 if let Some(client) = &mut self.client {
                     match client.send(&parsed) {
@@ -252,7 +261,7 @@ impl Tui {
             // in reconnect() — we always want to try when the daemon is up.
             let _ = client.connect();
             if let Ok(json) = client.agent_list_json() {
-                if let Ok(agents) = serde_json::from_str::<Vec<Agent>>(&json) {
+                if let Some(agents) = parse_agent_list(&json) {
                     self.agents = agents;
                 }
             }
@@ -445,6 +454,28 @@ fn render_notifications(
 }
 
 
+// ---------------------------------------------------------------------------
+// Agent list parsing
+// ---------------------------------------------------------------------------
+
+/// Parse `agent.list --json` output. Accepts the versioned wrapper
+/// (`{"schema_version": N, "agents": [...]}`) and the bare array older
+/// daemons send. Returns `None` for a newer, incompatible schema.
+fn parse_agent_list(json: &str) -> Option<Vec<Agent>> {
+    use skill_docket_core::types::SCHEMA_VERSION;
+
+    let value: serde_json::Value = serde_json::from_str(json).ok()?;
+    if value.is_array() {
+        return serde_json::from_value(value).ok();
+    }
+    let version = value.get("schema_version")?.as_u64()?;
+    if version > SCHEMA_VERSION as u64 {
+        return None;
+    }
+    serde_json::from_value(value.get("agents")?.clone()).ok()
+}
+
+
 // ---------------------------------------------------------------------------
 // Command text parsing
 // ---------------------------------------------------------------------------
@@ -463,9 +494,12 @@ fn parse_command_text(text: &str) -> skill_docket_core::command::Command {
         "status" => Command::Status { format: None },
         "help" => Command::Help {
             topic: parts.get(1).map(|s| s.to_string()),
+            search: None,
         },
         "agent.list" => Command::AgentList {
             format: parts.get(1).map(|s| s.to_string()),
+            sort_by: None,
+            columns: None,
         },
         "agent.new" => Command::AgentNew {
             role: parts.get(1).unwrap_or(&"worker").to_string(),
@@ -477,6 +511,7 @@ fn parse_command_text(text: &str) -> skill_docket_core::command::Command {
             if let Some(name) = parts.get(1) {
                 Command::AgentKill {
                     name: name.to_string(),
+                    graceful: false,
                 }
             } else {
                 Command::Status { format: None }
@@ -485,6 +520,9 @@ fn parse_command_text(text: &str) -> skill_docket_core::command::Command {
         "task.list" => Command::TaskList {
             format: parts.get(1).map(|s| s.to_string()),
             project: None,
+            max_depth: None,
+            status: None,
+            agent: None,
         },
         "project.list" => Command::ProjectList {
             format: parts.get(1).map(|s| s.to_string()),
@@ -549,6 +587,19 @@ pub fn crossterm_to_key(code: KeyCode, modifiers: KeyModifiers) -> Key {
 mod tests {
     use super::*;
 
+    #[test]
+    fn parse_agent_list_versioned_and_legacy() {
+        let agent = r#"{"name":"w1","role":"worker","agent_type":"claude","task":null,
+            "path":"/tmp","status":"idle","status_notes":"","health":"unknown",
+            "last_heartbeat_ms":null,"session":null}"#;
+        let versioned = format!(r#"{{"schema_version":1,"agents":[{}]}}"#, agent);
+        assert_eq!(super::parse_agent_list(&versioned).unwrap()[0].name, "w1");
+        let legacy = format!("[{}]", agent);
+        assert_eq!(super::parse_agent_list(&legacy).unwrap().len(), 1);
+        let future = format!(r#"{{"schema_version":999,"agents":[{}]}}"#, agent);
+        assert!(super::parse_agent_list(&future).is_none());
+    }
+
     #[test]
     fn crossterm_char_to_key() {
         let key = crossterm_to_key(KeyCode::Char('a'), KeyModifiers::NONE);
@@ -676,6 +727,7 @@ mod tests {
             cmd,
             skill_docket_core::command::Command::Help {
                 topic: Some("agent".into()),
+                search: None,
             }
         );
     }
@@ -685,7 +737,7 @@ mod tests {
         let cmd = parse_command_text("help");
         assert_eq!(
             cmd,
-            skill_docket_core::command::Command::Help { topic: None }
+            skill_docket_core::command::Command::Help { topic: None, search: None }
         );
     }
 
@@ -696,6 +748,8 @@ mod tests {
             cmd,
             skill_docket_core::command::Command::AgentList {
                 format: Some("json".into()),
+                sort_by: None,
+                columns: None,
             }
         );
     }
@@ -493,6 +493,8 @@ mod tests {
             health: HealthState::Healthy,
             last_heartbeat_ms: Some(1000),
             session: Some("main".into()),
+            created_at_ms: None,
+            assigned_at_ms: None,
         }
     }
 
@@ -506,6 +508,9 @@ mod tests {
             agent: None,
             children: vec![],
             spec_path: None,
+            depends_on: Vec::new(),
+            notes: Vec::new(),
+            due_at_ms: None,
         }
     }
 